@@ -0,0 +1,310 @@
+use anyhow::{Context, Result, bail};
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::book_name_parser;
+use crate::db::{QUEUE_TYPE_NEW, QUEUE_TYPE_SUSPENDED};
+use crate::models::{BibleStats, BookStats, PassageState};
+use crate::stats_backend::StatsBackend;
+use crate::verse_parser;
+
+/// Default address AnkiConnect listens on
+pub const DEFAULT_URL: &str = "http://127.0.0.1:8765";
+
+/// AnkiConnect API version this client speaks
+const API_VERSION: u32 = 6;
+
+/// Deck containing the Bible verse cards, in Anki's "::" notation
+const DECK_NAME: &str = "Bible::Verses";
+
+/// Search matching Bible Verse notes in the verses deck (excluding subdecks)
+const CARD_QUERY: &str = r#""deck:Bible::Verses" -"deck:Bible::Verses::*" "note:Bible Verse""#;
+
+/// Envelope returned by every AnkiConnect action
+#[derive(Deserialize)]
+struct ApiResponse<T> {
+    result: Option<T>,
+    error: Option<String>,
+}
+
+/// The subset of AnkiConnect's `cardsInfo` output used for stats
+#[derive(Debug, Deserialize)]
+struct CardInfo {
+    note: i64,
+    interval: i64,
+    queue: i64,
+    fields: HashMap<String, FieldInfo>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FieldInfo {
+    value: String,
+    order: i64,
+}
+
+impl CardInfo {
+    /// The note's first field, which holds the Bible reference
+    fn reference(&self) -> Option<&str> {
+        self.fields
+            .values()
+            .find(|field| field.order == 0)
+            .map(|field| field.value.trim())
+    }
+}
+
+/// Classifies a passage from all of its cards, matching the rules used by the SQLite queries
+fn classify_passage(cards: &[&CardInfo]) -> PassageState {
+    let min_interval = cards.iter().map(|card| card.interval).min().unwrap_or(0);
+
+    if cards.iter().any(|card| card.queue == QUEUE_TYPE_SUSPENDED) {
+        PassageState::Suspended
+    } else if cards.iter().all(|card| card.queue == QUEUE_TYPE_NEW) {
+        PassageState::Unseen
+    } else if min_interval >= 21 {
+        PassageState::Mature
+    } else if min_interval >= 7 {
+        PassageState::Young
+    } else {
+        PassageState::Learning
+    }
+}
+
+/// Aggregates cards into per-book stats, classifying each note by all of its cards
+fn aggregate_books(cards: &[CardInfo]) -> HashMap<String, BookStats> {
+    let mut notes: HashMap<i64, Vec<&CardInfo>> = HashMap::new();
+    for card in cards {
+        notes.entry(card.note).or_default().push(card);
+    }
+
+    let mut books_map: HashMap<String, BookStats> = HashMap::new();
+
+    for note_cards in notes.into_values() {
+        let Some(reference) = note_cards[0].reference() else {
+            continue;
+        };
+        let Some(book) = book_name_parser::parse_book_name(reference) else {
+            continue;
+        };
+        let verses = verse_parser::count_verses_in_reference(reference);
+
+        let stats = books_map
+            .entry(book.clone())
+            .or_insert_with(|| BookStats::empty(&book));
+        let (passages, verse_count) = match classify_passage(&note_cards) {
+            PassageState::Suspended => (&mut stats.suspended_passages, &mut stats.suspended_verses),
+            PassageState::Unseen => (&mut stats.unseen_passages, &mut stats.unseen_verses),
+            PassageState::Mature => (&mut stats.mature_passages, &mut stats.mature_verses),
+            PassageState::Young => (&mut stats.young_passages, &mut stats.young_verses),
+            PassageState::Learning => (&mut stats.learning_passages, &mut stats.learning_verses),
+        };
+        *passages += 1;
+        *verse_count += verses;
+    }
+
+    for stats in books_map.values_mut() {
+        stats.update_coverage();
+    }
+
+    books_map
+}
+
+/// Reads stats from a running Anki instance through the AnkiConnect add-on
+pub struct AnkiConnectBackend {
+    url: String,
+    agent: ureq::Agent,
+}
+
+impl AnkiConnectBackend {
+    pub fn new(url: &str) -> Self {
+        let agent = ureq::Agent::config_builder()
+            .timeout_global(Some(Duration::from_secs(30)))
+            .build()
+            .into();
+
+        Self {
+            url: url.to_string(),
+            agent,
+        }
+    }
+
+    /// Calls an AnkiConnect action and returns its result
+    fn invoke<T: DeserializeOwned>(&self, action: &str, params: Value) -> Result<T> {
+        let request = json!({
+            "action": action,
+            "version": API_VERSION,
+            "params": params,
+        });
+
+        let response: ApiResponse<T> = self
+            .agent
+            .post(&self.url)
+            .send_json(&request)
+            .context(format!("Failed to reach AnkiConnect at {}", self.url))?
+            .body_mut()
+            .read_json()
+            .context(format!("Invalid AnkiConnect response for {}", action))?;
+
+        if let Some(error) = response.error {
+            bail!("AnkiConnect {} failed: {}", action, error);
+        }
+        response
+            .result
+            .context(format!("AnkiConnect {} returned no result", action))
+    }
+
+    /// Fetches every card in the verses deck, optionally only from notes with `tag`
+    fn get_cards(&self, tag: Option<&str>) -> Result<Vec<CardInfo>> {
+        let query = match tag {
+            Some(tag) => format!(r#"{} "tag:{}""#, CARD_QUERY, tag),
+            None => CARD_QUERY.to_string(),
+        };
+        let card_ids: Vec<i64> = self.invoke("findCards", json!({ "query": query }))?;
+        if card_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        self.invoke("cardsInfo", json!({ "cards": card_ids }))
+    }
+}
+
+impl StatsBackend for AnkiConnectBackend {
+    fn get_bible_stats(&self, tag: Option<&str>) -> Result<BibleStats> {
+        let cards = self.get_cards(tag)?;
+        Ok(BibleStats::from_books(&aggregate_books(&cards)))
+    }
+
+    fn get_today_study_time(&self) -> Result<f64> {
+        let start_ms = statsutils::get_today_start_ms()?;
+
+        // Each review is [reviewTime, cardID, usn, buttonPressed, newInterval,
+        // previousInterval, newFactor, reviewDuration, reviewType]
+        let reviews: Vec<Vec<i64>> = self.invoke(
+            "cardReviews",
+            json!({ "deck": DECK_NAME, "startID": start_ms }),
+        )?;
+
+        let total_ms: i64 = reviews
+            .iter()
+            .filter(|review| review.first().is_some_and(|&id| id >= start_ms))
+            .filter_map(|review| review.get(7))
+            .sum();
+
+        Ok(total_ms as f64 / 60_000.0)
+    }
+
+    fn get_bible_references(&self) -> Result<Vec<String>> {
+        let mut references: Vec<String> = self
+            .get_cards(None)?
+            .iter()
+            .filter_map(|card| card.reference().map(str::to_string))
+            .collect();
+        references.sort();
+        references.dedup();
+        Ok(references)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn card(note: i64, reference: &str, queue: i64, interval: i64) -> CardInfo {
+        CardInfo {
+            note,
+            interval,
+            queue,
+            fields: HashMap::from([(
+                "Reference".to_string(),
+                FieldInfo {
+                    value: reference.to_string(),
+                    order: 0,
+                },
+            )]),
+        }
+    }
+
+    #[test]
+    fn test_parse_cards_info_response() {
+        let body = r#"{
+            "result": [{
+                "cardId": 1, "note": 10, "ord": 0, "interval": 30, "queue": 2, "type": 2,
+                "deckName": "Bible::Verses", "modelName": "Bible Verse",
+                "fields": {
+                    "Reference": {"value": "John 3:16", "order": 0},
+                    "Text": {"value": "For God so loved the world", "order": 1}
+                }
+            }],
+            "error": null
+        }"#;
+
+        let response: ApiResponse<Vec<CardInfo>> = serde_json::from_str(body).unwrap();
+        let cards = response.result.unwrap();
+        assert_eq!(cards.len(), 1);
+        assert_eq!(cards[0].reference(), Some("John 3:16"));
+        assert_eq!(cards[0].interval, 30);
+    }
+
+    #[test]
+    fn test_classify_passage() {
+        let mature = card(1, "John 3:16", 2, 30);
+        let young = card(1, "John 3:16", 2, 10);
+        let suspended = card(1, "John 3:16", QUEUE_TYPE_SUSPENDED, 30);
+        let new = card(1, "John 3:16", QUEUE_TYPE_NEW, 0);
+
+        assert_eq!(classify_passage(&[&mature, &mature]), PassageState::Mature);
+        assert_eq!(classify_passage(&[&mature, &young]), PassageState::Young);
+        assert_eq!(
+            classify_passage(&[&mature, &suspended]),
+            PassageState::Suspended
+        );
+        assert_eq!(classify_passage(&[&new, &new]), PassageState::Unseen);
+        assert_eq!(classify_passage(&[&new, &young]), PassageState::Learning);
+        assert_eq!(classify_passage(&[&mature]), PassageState::Mature);
+        assert_eq!(
+            classify_passage(&[&mature, &mature, &young]),
+            PassageState::Young
+        );
+    }
+
+    #[test]
+    fn test_passage_state_names() {
+        for state in PassageState::ALL {
+            assert_eq!(
+                serde_json::to_string(&state).unwrap(),
+                format!("\"{}\"", state)
+            );
+            assert_eq!(state.as_str().parse::<PassageState>().unwrap(), state);
+        }
+        assert!("matured".parse::<PassageState>().is_err());
+    }
+
+    #[test]
+    fn test_aggregate_books() {
+        let cards = vec![
+            card(1, "John 3:16-17", 2, 30),
+            card(1, "John 3:16-17", 2, 25),
+            card(2, "John 1:1", 2, 8),
+            card(2, "John 1:1", 2, 9),
+            // A single-template note
+            card(3, "Romans 8:28", 2, 30),
+            // A three-template note held back by its weakest card
+            card(4, "John 14:6", 2, 30),
+            card(4, "John 14:6", 2, 30),
+            card(4, "John 14:6", 1, 2),
+        ];
+
+        let books = aggregate_books(&cards);
+        assert_eq!(books.len(), 2);
+        assert_eq!(books["Romans"].mature_passages, 1);
+
+        let john = &books["John"];
+        assert_eq!(john.mature_passages, 1);
+        assert_eq!(john.mature_verses, 2);
+        assert_eq!(john.young_passages, 1);
+        assert_eq!(john.young_verses, 1);
+        assert_eq!(john.learning_passages, 1);
+        assert_eq!(john.canonical_verses, 879);
+    }
+}
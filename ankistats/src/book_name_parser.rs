@@ -1,26 +1,225 @@
-/// Normalizes a book name to use the standard display name
+use std::collections::HashMap;
+use std::sync::{OnceLock, RwLock};
+
+use anyhow::{Context, Result};
+
+use crate::bible;
+
+/// Common abbreviations and alternate spellings for each book
+///
+/// Entries are matched after `lookup_key` folds case, drops periods and spaces, and turns
+/// leading ordinals ("I", "Second", "3rd") into digits, so "1 Cor." and "I Cor" both match
+/// "1cor". Canonical names from `bible.rs` always match and are not repeated here.
+const BOOK_ALIASES: &[(&str, &[&str])] = &[
+    ("Genesis", &["gen", "ge", "gn"]),
+    ("Exodus", &["exod", "exo", "ex"]),
+    ("Leviticus", &["lev", "le", "lv"]),
+    ("Numbers", &["num", "nu", "nm", "nb"]),
+    ("Deuteronomy", &["deut", "de", "dt"]),
+    ("Joshua", &["josh", "jos", "jsh"]),
+    ("Judges", &["judg", "jdg", "jg", "jdgs"]),
+    ("Ruth", &["rth", "ru"]),
+    ("1 Samuel", &["1sam", "1sa", "1sm"]),
+    ("2 Samuel", &["2sam", "2sa", "2sm"]),
+    ("1 Kings", &["1kgs", "1ki", "1kg", "1kin"]),
+    ("2 Kings", &["2kgs", "2ki", "2kg", "2kin"]),
+    ("1 Chronicles", &["1chron", "1chr", "1ch"]),
+    ("2 Chronicles", &["2chron", "2chr", "2ch"]),
+    ("Ezra", &["ezr"]),
+    ("Nehemiah", &["neh", "ne"]),
+    ("Esther", &["esth", "est", "es"]),
+    ("Job", &["jb"]),
+    ("Psalms", &["psalm", "ps", "psa", "pss", "psm", "pslm"]),
+    ("Proverbs", &["prov", "pro", "prv", "pr"]),
+    (
+        "Ecclesiastes",
+        &["eccles", "eccl", "ecc", "ec", "qoh", "qoheleth"],
+    ),
+    (
+        "Song of Solomon",
+        &[
+            "song",
+            "songofsongs",
+            "sos",
+            "so",
+            "canticles",
+            "cant",
+            "songofsol",
+        ],
+    ),
+    ("Isaiah", &["isa", "is"]),
+    ("Jeremiah", &["jer", "je", "jr"]),
+    ("Lamentations", &["lam", "la"]),
+    ("Ezekiel", &["ezek", "eze", "ezk"]),
+    ("Daniel", &["dan", "da", "dn"]),
+    ("Hosea", &["hos", "ho"]),
+    ("Joel", &["jl"]),
+    ("Amos", &["am"]),
+    ("Obadiah", &["obad", "ob"]),
+    ("Jonah", &["jon", "jnh"]),
+    ("Micah", &["mic", "mc"]),
+    ("Nahum", &["nah", "na"]),
+    ("Habakkuk", &["hab", "hb"]),
+    ("Zephaniah", &["zeph", "zep", "zp"]),
+    ("Haggai", &["hag", "hg"]),
+    ("Zechariah", &["zech", "zec", "zc"]),
+    ("Malachi", &["mal", "ml"]),
+    ("Matthew", &["matt", "mat", "mt"]),
+    ("Mark", &["mrk", "mar", "mk", "mr"]),
+    ("Luke", &["luk", "lk"]),
+    ("John", &["joh", "jhn", "jn"]),
+    ("Acts", &["act", "ac", "actsoftheapostles"]),
+    ("Romans", &["rom", "ro", "rm"]),
+    ("1 Corinthians", &["1cor", "1co"]),
+    ("2 Corinthians", &["2cor", "2co"]),
+    ("Galatians", &["gal", "ga"]),
+    ("Ephesians", &["eph", "ephes"]),
+    ("Philippians", &["phil", "php", "pp"]),
+    ("Colossians", &["col", "co"]),
+    ("1 Thessalonians", &["1thess", "1thes", "1th"]),
+    ("2 Thessalonians", &["2thess", "2thes", "2th"]),
+    ("1 Timothy", &["1tim", "1ti"]),
+    ("2 Timothy", &["2tim", "2ti"]),
+    ("Titus", &["tit", "ti"]),
+    ("Philemon", &["philem", "phm", "pm"]),
+    ("Hebrews", &["heb"]),
+    ("James", &["jas", "jm"]),
+    ("1 Peter", &["1pet", "1pe", "1pt", "1p"]),
+    ("2 Peter", &["2pet", "2pe", "2pt", "2p"]),
+    ("1 John", &["1jn", "1jhn", "1jo", "1joh"]),
+    ("2 John", &["2jn", "2jhn", "2jo", "2joh"]),
+    ("3 John", &["3jn", "3jhn", "3jo", "3joh"]),
+    ("Jude", &["jud", "jd"]),
+    (
+        "Revelation",
+        &["rev", "re", "revelations", "apocalypse", "revelationofjohn"],
+    ),
+    ("Tobit", &["tob", "tb"]),
+    ("Judith", &["jdt", "jdth", "jth"]),
+    ("1 Maccabees", &["1macc", "1mac", "1ma", "1mc"]),
+    ("2 Maccabees", &["2macc", "2mac", "2ma", "2mc"]),
+    ("Wisdom", &["wis", "ws", "wisdomofsolomon", "wisofsol"]),
+    ("Sirach", &["sir", "ecclus", "ecclesiasticus", "ben sira"]),
+    ("Baruch", &["bar"]),
+];
+
+/// User-supplied aliases, keyed by `lookup_key`, consulted before the built-in table
+static CUSTOM_ALIASES: RwLock<Option<HashMap<String, String>>> = RwLock::new(None);
+
+/// Folds a book name into the form used for alias lookups
 ///
-/// Currently handles:
-/// - "Psalm" (from references) → "Psalms" (display name)
-fn normalize_book_name(book_name: &str) -> String {
-    if book_name.eq_ignore_ascii_case("Psalm") {
-        "Psalms".to_string()
-    } else {
-        book_name.to_string()
+/// "1 Cor." → "1cor", "II Timothy" → "2timothy", "First John" → "1john"
+fn lookup_key(book_name: &str) -> String {
+    let mut words: Vec<String> = book_name
+        .split_whitespace()
+        .map(|word| word.trim_end_matches('.').to_lowercase())
+        .filter(|word| !word.is_empty())
+        .collect();
+
+    if words.len() > 1 {
+        let ordinal = match words[0].as_str() {
+            "i" | "first" | "1st" => Some("1"),
+            "ii" | "second" | "2nd" => Some("2"),
+            "iii" | "third" | "3rd" => Some("3"),
+            _ => None,
+        };
+        if let Some(digit) = ordinal {
+            words[0] = digit.to_string();
+        }
     }
+
+    words.concat().replace('.', "")
 }
 
-/// Parses a Bible reference and extracts the book name
+/// Built-in alias table, including each canonical name, keyed by `lookup_key`
+fn builtin_aliases() -> &'static HashMap<String, &'static str> {
+    static ALIASES: OnceLock<HashMap<String, &'static str>> = OnceLock::new();
+    ALIASES.get_or_init(|| {
+        let mut aliases: HashMap<String, &'static str> = bible::known_books()
+            .map(|book| (lookup_key(book), book))
+            .collect();
+        for (book, book_aliases) in BOOK_ALIASES {
+            for alias in *book_aliases {
+                aliases.insert(lookup_key(alias), book);
+            }
+        }
+        aliases
+    })
+}
+
+/// Replaces the user-supplied alias map used alongside the built-in abbreviations
 ///
-/// Supports:
-/// - Multi-chapter books: "Genesis 1:1" → "Genesis"
-/// - Numbered books: "2 Timothy 3:16" → "2 Timothy"
-/// - Single-chapter books: "Jude 24" → "Jude"
+/// Keys are aliases as they appear in references (e.g. "Rö" or "Sal"), values are the
+/// canonical names from `bible.rs`. Custom aliases take priority over built-in ones.
+pub fn set_custom_aliases(aliases: HashMap<String, String>) {
+    let aliases = aliases
+        .into_iter()
+        .map(|(alias, book)| (lookup_key(&alias), book))
+        .collect();
+    *CUSTOM_ALIASES.write().unwrap_or_else(|e| e.into_inner()) = Some(aliases);
+}
+
+/// Parses an alias file with one `alias = Book` mapping per line
 ///
-/// Returns an error if the reference cannot be parsed.
-pub fn try_parse_book_name(reference: &str) -> Result<String, String> {
-    // Strip any Unicode formatting characters (like zero-width spaces and directional marks)
-    let reference = reference
+/// Blank lines and lines starting with `#` are ignored. The target must be a canonical
+/// book name from `bible.rs`, so typos are caught when the file is loaded.
+pub fn parse_alias_file(content: &str) -> Result<HashMap<String, String>> {
+    let mut aliases = HashMap::new();
+
+    for (line_number, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let (alias, book) = line
+            .split_once('=')
+            .context(format!("Line {}: expected 'alias = Book'", line_number + 1))?;
+        let (alias, book) = (alias.trim(), book.trim());
+
+        let canonical = bible::known_books()
+            .find(|name| name.eq_ignore_ascii_case(book))
+            .context(format!(
+                "Line {}: '{}' is not a book of the Bible",
+                line_number + 1,
+                book
+            ))?;
+        aliases.insert(alias.to_string(), canonical.to_string());
+    }
+
+    Ok(aliases)
+}
+
+/// Loads an alias file (see `parse_alias_file`) and installs it with `set_custom_aliases`
+pub fn load_alias_file(path: &str) -> Result<()> {
+    let content =
+        std::fs::read_to_string(path).context(format!("Failed to read alias file '{}'", path))?;
+    set_custom_aliases(parse_alias_file(&content)?);
+    Ok(())
+}
+
+/// Normalizes a book name to use the standard display name
+///
+/// Handles abbreviations ("Gen", "1 Cor", "Php"), alternate spellings ("Psalm",
+/// "Song of Songs", "II Timothy"), and any custom aliases. Names that aren't recognized
+/// are returned unchanged.
+pub(crate) fn normalize_book_name(book_name: &str) -> String {
+    let key = lookup_key(book_name);
+
+    let custom = CUSTOM_ALIASES.read().unwrap_or_else(|e| e.into_inner());
+    if let Some(book) = custom.as_ref().and_then(|aliases| aliases.get(&key)) {
+        return book.clone();
+    }
+
+    match builtin_aliases().get(&key) {
+        Some(book) => book.to_string(),
+        None => book_name.to_string(),
+    }
+}
+
+/// Strips Unicode formatting characters (like zero-width spaces and directional marks)
+pub(crate) fn strip_formatting_chars(reference: &str) -> String {
+    reference
         .chars()
         .filter(|c| {
             !c.is_control()
@@ -32,25 +231,49 @@ pub fn try_parse_book_name(reference: &str) -> Result<String, String> {
                 && *c != '\u{202D}' // Left-to-Right Override
                 && *c != '\u{202E}' // Right-to-Left Override
         })
-        .collect::<String>();
-
-    // Find the last space to extract the book name
-    match reference.rfind(' ') {
-        Some(pos) => {
-            let book_name = reference[..pos].trim();
-            if book_name.is_empty() {
-                Err(format!("No book name found in reference '{}'", reference))
-            } else {
-                Ok(normalize_book_name(book_name))
-            }
-        }
+        .collect()
+}
+
+/// Splits a reference into its book name and its chapter/verse part
+///
+/// The book name runs up to the first word starting with a digit, so comma lists like
+/// "Romans 3:23, 6:23" keep the whole verse part. A leading number belongs to the book
+/// itself: "1 John 3:16" → ("1 John", "3:16").
+pub(crate) fn split_reference(reference: &str) -> Result<(String, String), String> {
+    let words: Vec<&str> = reference.split_whitespace().collect();
+
+    let verse_start = words
+        .iter()
+        .skip(1)
+        .position(|word| word.starts_with(|c: char| c.is_ascii_digit()))
+        .map(|pos| pos + 1);
+
+    match verse_start {
+        Some(pos) => Ok((words[..pos].join(" "), words[pos..].join(" "))),
         None => Err(format!(
-            "No space found in reference '{}' (cannot extract book name)",
+            "No chapter or verse found in reference '{}' (cannot extract book name)",
             reference
         )),
     }
 }
 
+/// Parses a Bible reference and extracts the book name
+///
+/// Supports:
+/// - Multi-chapter books: "Genesis 1:1" → "Genesis"
+/// - Numbered books: "2 Timothy 3:16" → "2 Timothy"
+/// - Single-chapter books: "Jude 24" → "Jude"
+/// - Verse lists: "Romans 3:23, 6:23" → "Romans"
+/// - Abbreviations and alternate spellings: "1 Cor 13:4" → "1 Corinthians",
+///   "Song 2:4" → "Song of Solomon"
+///
+/// Returns an error if the reference cannot be parsed.
+pub fn try_parse_book_name(reference: &str) -> Result<String, String> {
+    let reference = strip_formatting_chars(reference);
+    let (book_name, _) = split_reference(&reference)?;
+    Ok(normalize_book_name(&book_name))
+}
+
 /// Parses a Bible reference and extracts the book name
 ///
 /// Supports:
@@ -175,6 +398,99 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_book_name_verse_lists() {
+        assert_eq!(
+            try_parse_book_name("Romans 3:23, 6:23"),
+            Ok("Romans".to_string())
+        );
+        assert_eq!(
+            try_parse_book_name("1 John 1:9; 2:1-2"),
+            Ok("1 John".to_string())
+        );
+        assert_eq!(try_parse_book_name("Psalm 1–2"), Ok("Psalms".to_string()));
+    }
+
+    #[test]
+    fn test_parse_book_name_abbreviations() {
+        assert_eq!(try_parse_book_name("Gen 1:1"), Ok("Genesis".to_string()));
+        assert_eq!(
+            try_parse_book_name("1 Cor 13:4-7"),
+            Ok("1 Corinthians".to_string())
+        );
+        assert_eq!(
+            try_parse_book_name("1Cor. 13:4"),
+            Ok("1 Corinthians".to_string())
+        );
+        assert_eq!(
+            try_parse_book_name("Php 4:13"),
+            Ok("Philippians".to_string())
+        );
+        assert_eq!(
+            try_parse_book_name("Song 2:4"),
+            Ok("Song of Solomon".to_string())
+        );
+        assert_eq!(try_parse_book_name("Jud 24"), Ok("Jude".to_string()));
+        assert_eq!(try_parse_book_name("Ps 23:1"), Ok("Psalms".to_string()));
+    }
+
+    #[test]
+    fn test_parse_book_name_alternate_spellings() {
+        assert_eq!(
+            try_parse_book_name("Song of Songs 8:6"),
+            Ok("Song of Solomon".to_string())
+        );
+        assert_eq!(
+            try_parse_book_name("II Timothy 3:16"),
+            Ok("2 Timothy".to_string())
+        );
+        assert_eq!(
+            try_parse_book_name("First John 1:9"),
+            Ok("1 John".to_string())
+        );
+        assert_eq!(
+            try_parse_book_name("revelations 21:4"),
+            Ok("Revelation".to_string())
+        );
+        // Unknown names pass through unchanged
+        assert_eq!(try_parse_book_name("Enoch 1:9"), Ok("Enoch".to_string()));
+    }
+
+    #[test]
+    fn test_parse_book_name_deuterocanon() {
+        assert_eq!(try_parse_book_name("Tob 4:15"), Ok("Tobit".to_string()));
+        assert_eq!(
+            try_parse_book_name("I Macc 2:51"),
+            Ok("1 Maccabees".to_string())
+        );
+        assert_eq!(
+            try_parse_book_name("Wisdom of Solomon 3:1"),
+            Ok("Wisdom".to_string())
+        );
+        assert_eq!(
+            try_parse_book_name("Ecclesiasticus 2:1"),
+            Ok("Sirach".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_alias_file() {
+        let aliases = parse_alias_file("# Spanish\nSal = Psalms\n\nRo = romans\n").unwrap();
+        assert_eq!(aliases.get("Sal"), Some(&"Psalms".to_string()));
+        assert_eq!(aliases.get("Ro"), Some(&"Romans".to_string()));
+
+        assert!(parse_alias_file("Sal Psalms").is_err());
+        assert!(parse_alias_file("Sal = Salmos").is_err());
+    }
+
+    #[test]
+    fn test_custom_aliases() {
+        set_custom_aliases(HashMap::from([("Sal".to_string(), "Psalms".to_string())]));
+        assert_eq!(try_parse_book_name("Sal 23:1"), Ok("Psalms".to_string()));
+        // Built-in aliases still apply
+        assert_eq!(try_parse_book_name("Gen 1:1"), Ok("Genesis".to_string()));
+    }
+
     #[test]
     fn test_parse_book_name_invalid() {
         // References without spaces should fail
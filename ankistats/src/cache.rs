@@ -0,0 +1,293 @@
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::Result;
+use statsutils::Granularity;
+use statsutils::cache::FileMemo;
+
+use crate::config::{self, AnkiStatsConfig};
+use crate::models::{
+    BibleStats, CumulativePoint, DayStats, DueForecast, Milestone, MonthStats, PassageDetail,
+    PeriodStats, StudyStreaks, WeekStats,
+};
+
+pub use statsutils::cache::DEFAULT_TTL;
+
+/// Database path, call description, the configuration the result was computed with, and
+/// the result's type
+type CacheKey = (String, String, AnkiStatsConfig, TypeId);
+
+/// A result of any type, reused until the collection changes
+type CacheEntry = Arc<FileMemo<Arc<dyn Any + Send + Sync>>>;
+
+/// Memoizes the results of the public stats functions for server use
+///
+/// Entries are keyed by database path, function, arguments, and the current
+/// `AnkiStatsConfig`, and are recomputed when the collection (or its write-ahead log) is
+/// modified or the TTL expires. Outdated entries are dropped whenever a new one is added,
+/// so arbitrary arguments (like the dates of a range) don't accumulate. Cloning is cheap;
+/// clones share the same entries.
+#[derive(Clone)]
+pub struct AnkiStatsCache {
+    entries: Arc<Mutex<HashMap<CacheKey, CacheEntry>>>,
+    ttl: Duration,
+}
+
+impl Default for AnkiStatsCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AnkiStatsCache {
+    pub fn new() -> Self {
+        Self::with_ttl(DEFAULT_TTL)
+    }
+
+    pub fn with_ttl(ttl: Duration) -> Self {
+        Self {
+            entries: Arc::new(Mutex::new(HashMap::new())),
+            ttl,
+        }
+    }
+
+    /// Drops every cached result
+    pub fn clear(&self) {
+        self.entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clear();
+    }
+
+    /// Returns the cached result for `call` on `db_path`, or computes and stores it
+    ///
+    /// `call` must identify the function and its arguments. Errors are not cached. The
+    /// lock isn't held while computing, so concurrent misses may both query the database.
+    pub fn get_or_compute<T, F>(&self, db_path: &str, call: &str, compute: F) -> Result<T>
+    where
+        T: Clone + Send + Sync + 'static,
+        F: FnOnce() -> Result<T>,
+    {
+        let key = (
+            db_path.to_string(),
+            call.to_string(),
+            config::config(),
+            TypeId::of::<T>(),
+        );
+        let memo = self.entry(key, db_path);
+        let value = memo.get_or_compute(|| {
+            compute().map(|value| Arc::new(value) as Arc<dyn Any + Send + Sync>)
+        })?;
+        Ok(value
+            .downcast_ref::<T>()
+            .expect("entries are keyed by result type")
+            .clone())
+    }
+
+    /// The entry for `key`, adding one (and dropping outdated ones) if it's missing
+    fn entry(&self, key: CacheKey, db_path: &str) -> CacheEntry {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(memo) = entries.get(&key) {
+            return Arc::clone(memo);
+        }
+
+        // Keep entries another call is still computing
+        entries.retain(|_, memo| memo.is_fresh() || Arc::strong_count(memo) > 1);
+        // Anki writes to the write-ahead log first and only checkpoints into the main
+        // file occasionally
+        let memo = Arc::new(
+            FileMemo::new([db_path.to_string(), format!("{}-wal", db_path)]).with_ttl(self.ttl),
+        );
+        entries.insert(key, Arc::clone(&memo));
+        memo
+    }
+
+    /// Cached `get_bible_stats`
+    pub fn bible_stats(&self, db_path: &str) -> Result<BibleStats> {
+        self.get_or_compute(db_path, "bible_stats", || crate::get_bible_stats(db_path))
+    }
+
+    /// Cached `get_today_study_time`
+    pub fn today_study_time(&self, db_path: &str) -> Result<f64> {
+        self.get_or_compute(db_path, "today_study_time", || {
+            crate::get_today_study_time(db_path)
+        })
+    }
+
+    /// Cached `get_last_30_days_stats`
+    pub fn last_30_days_stats(&self, db_path: &str) -> Result<Vec<DayStats>> {
+        self.get_or_compute(db_path, "last_30_days_stats", || {
+            crate::get_last_30_days_stats(db_path)
+        })
+    }
+
+    /// Cached `get_last_12_weeks_stats`
+    pub fn last_12_weeks_stats(&self, db_path: &str) -> Result<Vec<WeekStats>> {
+        self.get_or_compute(db_path, "last_12_weeks_stats", || {
+            crate::get_last_12_weeks_stats(db_path)
+        })
+    }
+
+    /// Cached `get_last_12_months_stats`
+    pub fn last_12_months_stats(&self, db_path: &str) -> Result<Vec<MonthStats>> {
+        self.get_or_compute(db_path, "last_12_months_stats", || {
+            crate::get_last_12_months_stats(db_path)
+        })
+    }
+
+    /// Cached `get_yearly_stats`
+    pub fn yearly_stats(&self, db_path: &str, year: i32) -> Result<Vec<MonthStats>> {
+        self.get_or_compute(db_path, &format!("yearly_stats({})", year), || {
+            crate::get_yearly_stats(db_path, year)
+        })
+    }
+
+    /// Cached `get_stats_for_range`
+    pub fn stats_for_range(
+        &self,
+        db_path: &str,
+        start_date: &str,
+        end_date: &str,
+        granularity: Granularity,
+    ) -> Result<Vec<PeriodStats>> {
+        let call = format!(
+            "stats_for_range({}, {}, {:?})",
+            start_date, end_date, granularity
+        );
+        self.get_or_compute(db_path, &call, || {
+            crate::get_stats_for_range(db_path, start_date, end_date, granularity)
+        })
+    }
+
+    /// Cached `get_due_forecast`
+    pub fn due_forecast(&self, db_path: &str, days: u32) -> Result<DueForecast> {
+        self.get_or_compute(db_path, &format!("due_forecast({})", days), || {
+            crate::get_due_forecast(db_path, days)
+        })
+    }
+
+    /// Cached `get_streaks`
+    pub fn streaks(&self, db_path: &str) -> Result<StudyStreaks> {
+        self.get_or_compute(db_path, "streaks", || crate::get_streaks(db_path))
+    }
+
+    /// Cached `get_passage_details`
+    pub fn passage_details(&self, db_path: &str) -> Result<Vec<PassageDetail>> {
+        self.get_or_compute(db_path, "passage_details", || {
+            crate::get_passage_details(db_path)
+        })
+    }
+
+    /// Cached `get_milestones`
+    pub fn milestones(&self, db_path: &str) -> Result<Vec<Milestone>> {
+        self.get_or_compute(db_path, "milestones", || crate::get_milestones(db_path))
+    }
+
+    /// Cached `get_cumulative_history`
+    pub fn cumulative_history(&self, db_path: &str) -> Result<Vec<CumulativePoint>> {
+        self.get_or_compute(db_path, "cumulative_history", || {
+            crate::get_cumulative_history(db_path)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use std::time::SystemTime;
+
+    #[test]
+    fn test_reuses_result_until_file_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("collection.anki2");
+        std::fs::write(&path, "one").unwrap();
+        let db_path = path.to_str().unwrap();
+
+        let cache = AnkiStatsCache::new();
+        let calls = Cell::new(0);
+        let compute = || {
+            calls.set(calls.get() + 1);
+            Ok(calls.get())
+        };
+
+        assert_eq!(cache.get_or_compute(db_path, "f", compute).unwrap(), 1);
+        assert_eq!(cache.get_or_compute(db_path, "f", compute).unwrap(), 1);
+        // Different arguments are cached separately
+        assert_eq!(cache.get_or_compute(db_path, "f(2)", compute).unwrap(), 2);
+
+        // Touch the file with a later modification time
+        let file = std::fs::File::options().write(true).open(&path).unwrap();
+        file.set_modified(SystemTime::now() + Duration::from_secs(10))
+            .unwrap();
+        assert_eq!(cache.get_or_compute(db_path, "f", compute).unwrap(), 3);
+    }
+
+    #[test]
+    fn test_survives_poisoned_lock() {
+        let cache = AnkiStatsCache::new();
+        let entries = Arc::clone(&cache.entries);
+        let _ = std::thread::spawn(move || {
+            let _guard = entries.lock().unwrap();
+            panic!("poison the lock");
+        })
+        .join();
+
+        assert_eq!(cache.get_or_compute("missing", "f", || Ok(1)).unwrap(), 1);
+        assert_eq!(cache.get_or_compute("missing", "f", || Ok(2)).unwrap(), 1);
+        cache.clear();
+    }
+
+    #[test]
+    fn test_expires_after_ttl_and_skips_errors() {
+        let cache = AnkiStatsCache::with_ttl(Duration::ZERO);
+        let calls = Cell::new(0);
+        let compute = || {
+            calls.set(calls.get() + 1);
+            Ok(calls.get())
+        };
+
+        assert_eq!(cache.get_or_compute("missing", "f", compute).unwrap(), 1);
+        assert_eq!(cache.get_or_compute("missing", "f", compute).unwrap(), 2);
+
+        let cache = AnkiStatsCache::new();
+        assert!(
+            cache
+                .get_or_compute::<i32, _>("missing", "g", || anyhow::bail!("failed"))
+                .is_err()
+        );
+        assert_eq!(cache.get_or_compute("missing", "g", || Ok(5)).unwrap(), 5);
+    }
+
+    #[test]
+    fn test_drops_outdated_entries() {
+        let cache = AnkiStatsCache::with_ttl(Duration::ZERO);
+        for day in 1..=10 {
+            let call = format!("stats_for_range(2025-06-{:02})", day);
+            cache.get_or_compute("missing", &call, || Ok(day)).unwrap();
+        }
+        // Failed calls leave nothing behind either
+        let _ = cache.get_or_compute::<i32, _>("missing", "g", || anyhow::bail!("failed"));
+        cache.get_or_compute("missing", "h", || Ok(0)).unwrap();
+        assert_eq!(cache.entries.lock().unwrap().len(), 1);
+
+        // Fresh entries are kept
+        let cache = AnkiStatsCache::new();
+        cache.get_or_compute("missing", "f", || Ok(1)).unwrap();
+        cache.get_or_compute("missing", "g", || Ok(2)).unwrap();
+        assert_eq!(cache.entries.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_clones_share_entries() {
+        let cache = AnkiStatsCache::new();
+        let clone = cache.clone();
+        cache.get_or_compute("missing", "f", || Ok(1)).unwrap();
+        assert_eq!(clone.get_or_compute("missing", "f", || Ok(2)).unwrap(), 1);
+
+        clone.clear();
+        assert_eq!(cache.get_or_compute("missing", "f", || Ok(3)).unwrap(), 3);
+    }
+}
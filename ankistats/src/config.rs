@@ -0,0 +1,74 @@
+use std::sync::RwLock;
+
+/// How a review longer than `max_answer_seconds` counts toward study time
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum OutlierHandling {
+    /// Count the review as `max_answer_seconds` long
+    #[default]
+    Cap,
+    /// Leave the review's time out entirely (it still counts as a review)
+    Exclude,
+}
+
+/// Library-wide settings for how stats are computed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct AnkiStatsConfig {
+    /// Longest time a single review may add to study time, like Anki's "Maximum answer
+    /// seconds" deck option. `None` counts every review's recorded time in full.
+    pub max_answer_seconds: Option<i64>,
+    pub outlier_handling: OutlierHandling,
+}
+
+impl AnkiStatsConfig {
+    /// Milliseconds a review recorded as taking `time_ms` adds to study time
+    pub fn review_ms(&self, time_ms: i64) -> i64 {
+        match self.max_answer_seconds {
+            Some(max_seconds) if time_ms > max_seconds * 1000 => match self.outlier_handling {
+                OutlierHandling::Cap => max_seconds * 1000,
+                OutlierHandling::Exclude => 0,
+            },
+            _ => time_ms,
+        }
+    }
+}
+
+static CONFIG: RwLock<AnkiStatsConfig> = RwLock::new(AnkiStatsConfig {
+    max_answer_seconds: None,
+    outlier_handling: OutlierHandling::Cap,
+});
+
+/// Sets the configuration used by databases opened afterwards
+pub fn set_config(config: AnkiStatsConfig) {
+    *CONFIG.write().unwrap_or_else(|e| e.into_inner()) = config;
+}
+
+/// Returns the configuration currently in use
+pub fn config() -> AnkiStatsConfig {
+    *CONFIG.read().unwrap_or_else(|e| e.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_review_ms() {
+        let uncapped = AnkiStatsConfig::default();
+        assert_eq!(uncapped.review_ms(1_200_000), 1_200_000);
+
+        let capped = AnkiStatsConfig {
+            max_answer_seconds: Some(60),
+            outlier_handling: OutlierHandling::Cap,
+        };
+        assert_eq!(capped.review_ms(12_000), 12_000);
+        assert_eq!(capped.review_ms(60_000), 60_000);
+        assert_eq!(capped.review_ms(1_200_000), 60_000);
+
+        let excluded = AnkiStatsConfig {
+            outlier_handling: OutlierHandling::Exclude,
+            ..capped
+        };
+        assert_eq!(excluded.review_ms(12_000), 12_000);
+        assert_eq!(excluded.review_ms(1_200_000), 0);
+    }
+}
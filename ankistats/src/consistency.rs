@@ -0,0 +1,94 @@
+pub use statsutils::math::median;
+
+/// Length of the longest run of consecutive periods without study
+///
+/// Runs at the start and end of the window count too: a gap that is still going on is
+/// as much a break in the habit as one that has ended.
+pub fn longest_gap(studied: impl IntoIterator<Item = bool>) -> usize {
+    let mut longest = 0;
+    let mut run = 0;
+    for studied in studied {
+        run = if studied { 0 } else { run + 1 };
+        longest = longest.max(run);
+    }
+    longest
+}
+
+/// Number of study days in each 7-day window, counting back from the most recent day
+///
+/// The oldest window is dropped when it's incomplete, unless it's the only one.
+pub fn days_per_week(studied: &[bool]) -> Vec<f64> {
+    let weeks: Vec<f64> = studied
+        .rchunks(7)
+        .filter(|week| week.len() == 7)
+        .map(|week| week.iter().filter(|&&s| s).count() as f64)
+        .collect();
+    if weeks.is_empty() && !studied.is_empty() {
+        vec![studied.iter().filter(|&&s| s).count() as f64]
+    } else {
+        weeks
+    }
+}
+
+/// Scores how regular a study habit is, from 0 to 100
+///
+/// The share of days studied is scaled down by the share of the window lost to the
+/// longest gap, so studying every other day scores about 50 while studying the same
+/// number of days in one block scores about 25.
+pub fn consistency_score(days_studied: usize, total_days: usize, longest_gap_days: usize) -> f64 {
+    if total_days == 0 {
+        return 0.0;
+    }
+    let coverage = days_studied as f64 / total_days as f64;
+    let gap_penalty = 1.0 - longest_gap_days.min(total_days) as f64 / total_days as f64;
+    coverage * gap_penalty * 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_longest_gap() {
+        assert_eq!(longest_gap([]), 0);
+        assert_eq!(longest_gap([true, true]), 0);
+        assert_eq!(longest_gap([true, false, false, true, false]), 2);
+        // An ongoing gap counts
+        assert_eq!(longest_gap([true, false, true, false, false, false]), 3);
+    }
+
+    #[test]
+    fn test_median() {
+        assert_eq!(median(&[]), 0.0);
+        assert_eq!(median(&[5.0, 1.0, 3.0]), 3.0);
+        assert_eq!(median(&[4.0, 1.0, 3.0, 2.0]), 2.5);
+    }
+
+    #[test]
+    fn test_days_per_week_drops_partial_oldest_week() {
+        let mut studied = vec![true, true];
+        studied.extend([true, false, true, false, true, false, true]);
+        studied.extend([false; 7]);
+        assert_eq!(days_per_week(&studied), [0.0, 4.0]);
+
+        assert_eq!(days_per_week(&[true, false, true]), [2.0]);
+        assert!(days_per_week(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_consistency_score_prefers_spread_out_study() {
+        let alternating: Vec<bool> = (0..30).map(|d| d % 2 == 0).collect();
+        let block: Vec<bool> = (0..30).map(|d| d < 15).collect();
+
+        let score = |studied: &[bool]| {
+            let days = studied.iter().filter(|&&s| s).count();
+            consistency_score(days, studied.len(), longest_gap(studied.iter().copied()))
+        };
+
+        assert_eq!(score(&[true; 30]), 100.0);
+        assert_eq!(score(&[false; 30]), 0.0);
+        assert!((score(&alternating) - 48.33).abs() < 0.01);
+        assert_eq!(score(&block), 25.0);
+        assert_eq!(consistency_score(0, 0, 0), 0.0);
+    }
+}
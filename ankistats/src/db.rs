@@ -1,10 +1,22 @@
 use anyhow::{Context, Result};
-use rusqlite::{Connection, OpenFlags};
-use statsutils::{DatePeriod, get_today_start_ms, register_date_functions};
+use chrono::{FixedOffset, NaiveDate};
+use rusqlite::{Connection, OptionalExtension};
+use statsutils::{
+    DatePeriod, Granularity, PeriodConfig, StatsTimezone, get_today_start_ms_with_config,
+    register_date_functions_with_config,
+    series::densify,
+    sqlite::{OpenOptions, load_into_memory, open_readonly_with_retry},
+};
 use std::collections::HashMap;
 
 use crate::book_name_parser;
-use crate::models::{BookStats, DayStats, WeekStats};
+use crate::config;
+use crate::milestones::CardReview;
+use crate::models::{
+    BookStats, DayStats, DueDay, DueForecast, MonthStats, PassageDetail, PassageState, PeriodStats,
+    PeriodTotals, RetentionStats, ReviewEvent, WeekStats,
+};
+use crate::package;
 use crate::verse_parser;
 
 // Anki queue type constants
@@ -13,30 +25,82 @@ use crate::verse_parser;
 const QUEUE_TYPE_MANUALLY_BURIED: i64 = -3;
 #[allow(dead_code)]
 const QUEUE_TYPE_SIBLING_BURIED: i64 = -2;
-const QUEUE_TYPE_SUSPENDED: i64 = -1;
-const QUEUE_TYPE_NEW: i64 = 0;
-#[allow(dead_code)]
+pub(crate) const QUEUE_TYPE_SUSPENDED: i64 = -1;
+pub(crate) const QUEUE_TYPE_NEW: i64 = 0;
 const QUEUE_TYPE_LRN: i64 = 1;
-#[allow(dead_code)]
 const QUEUE_TYPE_REV: i64 = 2;
-#[allow(dead_code)]
 const QUEUE_TYPE_DAY_LEARN_RELEARN: i64 = 3;
 #[allow(dead_code)]
 const QUEUE_TYPE_PREVIEW: i64 = 4;
 
+// Anki revlog entry type constants
+// See https://github.com/ankitects/anki/blob/76d3237139b3e73b98f5a5b4dfeeeea2f0554644/pylib/anki/consts.py#L47-L52
+const REVLOG_TYPE_LRN: i64 = 0;
+const REVLOG_TYPE_REV: i64 = 1;
+const REVLOG_TYPE_RELRN: i64 = 2;
+const REVLOG_TYPE_CRAM: i64 = 3;
+const REVLOG_TYPE_MANUAL: i64 = 4;
+const REVLOG_TYPE_RESCHED: i64 = 5;
+
+/// Name of a revlog entry type, as used in exported review events
+fn review_type_name(review_type: i64) -> &'static str {
+    match review_type {
+        REVLOG_TYPE_LRN => "learning",
+        REVLOG_TYPE_REV => "review",
+        REVLOG_TYPE_RELRN => "relearning",
+        REVLOG_TYPE_CRAM => "filtered",
+        REVLOG_TYPE_MANUAL => "manual",
+        REVLOG_TYPE_RESCHED => "rescheduled",
+        _ => "unknown",
+    }
+}
+
 /// Unicode unit separator character (used in Anki deck names)
 const UNIT_SEPARATOR: char = '\x1F';
 
 /// Opens a connection to an Anki database in read-only mode
+///
+/// `.colpkg`/`.apkg` backups are also accepted: the collection is extracted and loaded
+/// into an in-memory database, so the package itself is never modified.
+///
+/// If Anki holds a lock on the collection (e.g. while syncing), opening is retried with
+/// backoff. When the database stays locked, a snapshot of the file and its write-ahead
+/// log is copied to a temporary directory and read instead.
 pub fn open_database(path: &str) -> Result<Connection> {
-    let conn = Connection::open_with_flags(
-        path,
-        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
-    )
-    .context("Failed to open Anki database in read-only mode")?;
+    let conn = open_collection(path)?;
+
+    // Register date functions from statsutils, using Anki's own day boundaries
+    let period_config = get_period_config(&conn)?;
+    register_functions(&conn, &period_config)?;
+    Ok(conn)
+}
 
-    // Register date functions from statsutils
-    register_date_functions(&conn)?;
+/// Like `open_database`, splitting days at `period_config`'s boundaries instead of the
+/// collection's own, so the results line up with other sources read with the same config
+pub fn open_database_with_config(path: &str, period_config: &PeriodConfig) -> Result<Connection> {
+    let conn = open_collection(path)?;
+    register_functions(&conn, period_config)?;
+    Ok(conn)
+}
+
+/// Opens the collection (or a package's collection) without any custom functions
+fn open_collection(path: &str) -> Result<Connection> {
+    if package::is_package(path) {
+        let collection = package::extract_collection(path)?;
+        load_into_memory(collection.path()).context("Failed to load collection from Anki package")
+    } else {
+        // Date functions are registered by the caller, once the day boundaries are known
+        let options = OpenOptions {
+            date_functions: None,
+            ..OpenOptions::new("Anki database")
+        };
+        open_readonly_with_retry(path, &options)
+    }
+}
+
+/// Registers the date functions for `period_config` and Anki's custom SQLite functions
+fn register_functions(conn: &Connection, period_config: &PeriodConfig) -> Result<()> {
+    register_date_functions_with_config(conn, period_config)?;
 
     // Register custom SQLite function for counting verses in a reference
     conn.create_scalar_function(
@@ -64,7 +128,106 @@ pub fn open_database(path: &str) -> Result<Connection> {
     )
     .context("Failed to register parse_book_name SQLite function")?;
 
-    Ok(conn)
+    // Register custom SQLite function for filtering notes by tag
+    conn.create_scalar_function(
+        "has_tag",
+        2, // number of arguments
+        rusqlite::functions::FunctionFlags::SQLITE_UTF8
+            | rusqlite::functions::FunctionFlags::SQLITE_DETERMINISTIC,
+        |ctx| {
+            let tags = ctx.get::<String>(0)?;
+            let tag = ctx.get::<String>(1)?;
+            Ok(note_has_tag(&tags, &tag))
+        },
+    )
+    .context("Failed to register has_tag SQLite function")?;
+
+    // Register custom SQLite function applying the configured per-review time cap
+    let stats_config = config::config();
+    conn.create_scalar_function(
+        "review_ms",
+        1, // number of arguments
+        rusqlite::functions::FunctionFlags::SQLITE_UTF8
+            | rusqlite::functions::FunctionFlags::SQLITE_DETERMINISTIC,
+        move |ctx| Ok(stats_config.review_ms(ctx.get::<i64>(0)?)),
+    )
+    .context("Failed to register review_ms SQLite function")?;
+
+    Ok(())
+}
+
+/// Reads a value from the collection config
+///
+/// Anki 2.1.28+ stores each config key as a JSON blob in the `config` table; older
+/// collections keep a single JSON object in `col.conf`.
+fn get_config_value(conn: &Connection, key: &str) -> Result<Option<serde_json::Value>> {
+    let has_config_table: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'config')",
+        [],
+        |row| row.get(0),
+    )?;
+
+    if has_config_table {
+        let value: Option<Vec<u8>> = conn
+            .query_row("SELECT val FROM config WHERE KEY = ?1", [key], |row| {
+                row.get(0)
+            })
+            .optional()?;
+        return value
+            .map(|bytes| serde_json::from_slice(&bytes))
+            .transpose()
+            .context(format!("Invalid value for config key '{}'", key));
+    }
+
+    let conf: Option<String> = conn
+        .query_row("SELECT conf FROM col", [], |row| row.get(0))
+        .optional()
+        .unwrap_or(None);
+    let Some(conf) = conf.filter(|conf| !conf.is_empty()) else {
+        return Ok(None);
+    };
+
+    let mut conf: serde_json::Value =
+        serde_json::from_str(&conf).context("Failed to parse collection config")?;
+    Ok(conf.get_mut(key).map(serde_json::Value::take))
+}
+
+/// Reads the day boundaries Anki uses from the collection config
+///
+/// Anki records the rollover hour and the machine's UTC offset (`localOffset`, in minutes
+/// west of UTC). Missing values fall back to the statsutils defaults.
+pub fn get_period_config(conn: &Connection) -> Result<PeriodConfig> {
+    let mut config = PeriodConfig::default();
+
+    if let Some(rollover) = get_config_value(conn, "rollover")?.and_then(|v| v.as_i64()) {
+        config.rollover_hour = rollover;
+    }
+
+    let offset_minutes = match get_config_value(conn, "localOffset")? {
+        Some(value) => value.as_i64(),
+        None => get_config_value(conn, "creationOffset")?.and_then(|v| v.as_i64()),
+    };
+    if let Some(minutes) = offset_minutes {
+        let offset = FixedOffset::west_opt((minutes * 60) as i32).context(format!(
+            "Invalid UTC offset in collection config: {}",
+            minutes
+        ))?;
+        config.timezone = StatsTimezone::Fixed(offset);
+    }
+
+    Ok(config)
+}
+
+/// Checks whether a note's space-separated tag list contains `tag` or one of its children
+///
+/// Matching is case-insensitive like Anki's own search, and hierarchical: filtering on
+/// "memorize" also matches "memorize::current".
+fn note_has_tag(tags: &str, tag: &str) -> bool {
+    let tag = tag.to_lowercase();
+    let child_prefix = format!("{}::", tag);
+    tags.split_whitespace()
+        .map(str::to_lowercase)
+        .any(|note_tag| note_tag == tag || note_tag.starts_with(&child_prefix))
 }
 
 /// Looks up the deck ID for "Bible<unit-separator>Verses"
@@ -97,72 +260,100 @@ pub fn get_model_id(conn: &Connection) -> Result<i64> {
     Ok(model_id)
 }
 
+/// SQL CASE expression classifying a passage from all of its cards (aliased `c`), for
+/// use in a query grouped by note
+///
+/// A passage is only as strong as its weakest card: every card must reach an interval
+/// of 21 days to count as mature, or 7 days to count as young. This holds for note types
+/// with any number of card templates, not just the usual two.
+fn passage_state_sql() -> String {
+    format!(
+        r#"CASE
+                    WHEN MAX(c.queue = {QUEUE_TYPE_SUSPENDED})
+                        THEN '{suspended}'
+                    WHEN MIN(c.queue = {QUEUE_TYPE_NEW})
+                        THEN '{unseen}'
+                    WHEN MIN(c.ivl) >= 21
+                        THEN '{mature}'
+                    WHEN MIN(c.ivl) >= 7
+                        THEN '{young}'
+                    ELSE '{learning}'
+                    END"#,
+        suspended = PassageState::Suspended,
+        unseen = PassageState::Unseen,
+        mature = PassageState::Mature,
+        young = PassageState::Young,
+        learning = PassageState::Learning,
+    )
+}
+
 /// Gets statistics for all Bible books in a single query using GROUP BY
 /// Returns a HashMap with book names as keys and BookStats as values
+///
+/// When `tag` is given, only notes with that tag (or a child tag) are counted.
 pub fn get_all_books_stats(
     conn: &Connection,
     deck_id: i64,
     model_id: i64,
+    tag: Option<&str>,
 ) -> Result<HashMap<String, BookStats>> {
+    let passage_state = passage_state_sql();
     let query = format!(
         r#"
         SELECT
             book,
-            SUM(CASE WHEN type='mature' THEN 1 ELSE 0 END) AS mature_passages,
-            SUM(CASE WHEN type='young' THEN 1 ELSE 0 END) AS young_passages,
-            SUM(CASE WHEN type='learning' THEN 1 ELSE 0 END) AS learning_passages,
-            SUM(CASE WHEN type='unseen' THEN 1 ELSE 0 END) AS unseen_passages,
-            SUM(CASE WHEN type='suspended' THEN 1 ELSE 0 END) AS suspended_passages,
-            SUM(CASE WHEN type='mature' THEN verses_count ELSE 0 END) AS mature_verses,
-            SUM(CASE WHEN type='young' THEN verses_count ELSE 0 END) AS young_verses,
-            SUM(CASE WHEN type='learning' THEN verses_count ELSE 0 END) AS learning_verses,
-            SUM(CASE WHEN type='unseen' THEN verses_count ELSE 0 END) AS unseen_verses,
-            SUM(CASE WHEN type='suspended' THEN verses_count ELSE 0 END) AS suspended_verses
+            SUM(CASE WHEN type='{mature}' THEN 1 ELSE 0 END) AS mature_passages,
+            SUM(CASE WHEN type='{young}' THEN 1 ELSE 0 END) AS young_passages,
+            SUM(CASE WHEN type='{learning}' THEN 1 ELSE 0 END) AS learning_passages,
+            SUM(CASE WHEN type='{unseen}' THEN 1 ELSE 0 END) AS unseen_passages,
+            SUM(CASE WHEN type='{suspended}' THEN 1 ELSE 0 END) AS suspended_passages,
+            SUM(CASE WHEN type='{mature}' THEN verses_count ELSE 0 END) AS mature_verses,
+            SUM(CASE WHEN type='{young}' THEN verses_count ELSE 0 END) AS young_verses,
+            SUM(CASE WHEN type='{learning}' THEN verses_count ELSE 0 END) AS learning_verses,
+            SUM(CASE WHEN type='{unseen}' THEN verses_count ELSE 0 END) AS unseen_verses,
+            SUM(CASE WHEN type='{suspended}' THEN verses_count ELSE 0 END) AS suspended_verses
         FROM (
             SELECT
                 parse_book_name(sfld) AS book,
                 count_verses(sfld) AS verses_count,
-                CASE
-                    WHEN c0.queue={QUEUE_TYPE_SUSPENDED} OR c1.queue={QUEUE_TYPE_SUSPENDED}
-                        THEN 'suspended'
-                    WHEN c0.queue={QUEUE_TYPE_NEW} AND c1.queue={QUEUE_TYPE_NEW}
-                        THEN 'unseen'
-                    WHEN c0.ivl >= 21 AND c1.ivl >= 21
-                        THEN 'mature'
-                    WHEN c0.ivl >= 7 AND c1.ivl >= 7
-                        THEN 'young'
-                    ELSE 'learning'
-                    END as type
+                {passage_state} as type
             FROM notes
-            JOIN cards AS c0 ON c0.nid = notes.id AND c0.ord = 0 AND c0.did = ?2
-            JOIN cards AS c1 ON c1.nid = notes.id AND c1.ord = 1 AND c1.did = ?2
-            WHERE notes.mid = ?1
+            JOIN cards AS c ON c.nid = notes.id AND c.did = ?2
+            WHERE notes.mid = ?1 AND (?3 IS NULL OR has_tag(notes.tags, ?3))
+            GROUP BY notes.id
         )
         WHERE book IS NOT NULL
         GROUP BY book
-        "#
+        "#,
+        suspended = PassageState::Suspended,
+        unseen = PassageState::Unseen,
+        mature = PassageState::Mature,
+        young = PassageState::Young,
+        learning = PassageState::Learning,
     );
 
     let mut stmt = conn.prepare(&query)?;
 
-    let books_iter = stmt.query_map(rusqlite::params![model_id, deck_id], |row| {
+    let books_iter = stmt.query_map(rusqlite::params![model_id, deck_id, tag], |row| {
         let book_name: String = row.get(0)?;
-        Ok((
-            book_name.clone(),
-            BookStats {
-                book: book_name,
-                mature_passages: row.get(1).unwrap_or(0),
-                young_passages: row.get(2).unwrap_or(0),
-                learning_passages: row.get(3).unwrap_or(0),
-                unseen_passages: row.get(4).unwrap_or(0),
-                suspended_passages: row.get(5).unwrap_or(0),
-                mature_verses: row.get(6).unwrap_or(0),
-                young_verses: row.get(7).unwrap_or(0),
-                learning_verses: row.get(8).unwrap_or(0),
-                unseen_verses: row.get(9).unwrap_or(0),
-                suspended_verses: row.get(10).unwrap_or(0),
-            },
-        ))
+        let mut stats = BookStats {
+            book: book_name.clone(),
+            mature_passages: row.get(1).unwrap_or(0),
+            young_passages: row.get(2).unwrap_or(0),
+            learning_passages: row.get(3).unwrap_or(0),
+            unseen_passages: row.get(4).unwrap_or(0),
+            suspended_passages: row.get(5).unwrap_or(0),
+            mature_verses: row.get(6).unwrap_or(0),
+            young_verses: row.get(7).unwrap_or(0),
+            learning_verses: row.get(8).unwrap_or(0),
+            unseen_verses: row.get(9).unwrap_or(0),
+            suspended_verses: row.get(10).unwrap_or(0),
+            canonical_verses: 0,
+            mature_percent: 0.0,
+            learned_percent: 0.0,
+        };
+        stats.update_coverage();
+        Ok((book_name, stats))
     })?;
 
     let mut books_map = HashMap::new();
@@ -174,14 +365,14 @@ pub fn get_all_books_stats(
     Ok(books_map)
 }
 
-/// Gets the total study time for today in minutes
-pub fn get_today_study_minutes(conn: &Connection) -> Result<f64> {
-    let today_start_ms = get_today_start_ms()?;
+/// Gets the total study time for today in minutes, with today as `period_config` sees it
+pub fn get_today_study_minutes(conn: &Connection, period_config: &PeriodConfig) -> Result<f64> {
+    let today_start_ms = get_today_start_ms_with_config(period_config)?;
 
     let deck_id = get_deck_id(conn)?;
 
     let query = r#"
-        SELECT COALESCE(SUM(r.time), 0) as total_ms
+        SELECT COALESCE(SUM(review_ms(r.time)), 0) as total_ms
         FROM revlog r
         JOIN cards c ON c.id = r.cid
         WHERE c.did = ?1 AND r.id >= ?2
@@ -193,35 +384,80 @@ pub fn get_today_study_minutes(conn: &Connection) -> Result<f64> {
     Ok(total_ms as f64 / 60000.0)
 }
 
-/// Gets study time and learning progress for each of the last 30 days
-pub fn get_last_30_days_stats(conn: &Connection) -> Result<Vec<DayStats>> {
+/// Study time and progress for a single bucket (day, week, or month) of a period
+struct BucketProgress {
+    key: String,
+    minutes: f64,
+    reviews: i64,
+    due_completed: i64,
+    /// Distinct days with any review in the bucket
+    days_studied: i64,
+    new_passages: i64,
+    matured_passages: i64,
+    lost_passages: i64,
+    cumulative_passages: i64,
+}
+
+/// Queries study time and learning progress for every bucket of a period
+///
+/// Revlog entries are grouped with the SQLite bucket function for `granularity`,
+/// which must produce keys matching the period's dates. When `tag` is given, only
+/// reviews of notes with that tag are included; when `book` is given, only reviews of
+/// passages from that book.
+fn get_period_progress(
+    conn: &Connection,
+    period: DatePeriod,
+    granularity: Granularity,
+    tag: Option<&str>,
+    book: Option<&str>,
+) -> Result<Vec<BucketProgress>> {
+    let bucket_fn = granularity.ms_bucket_function();
     let deck_id = get_deck_id(conn)?;
     let model_id = get_model_id(conn)?;
 
-    // Get the period data for the last 30 days
-    let period = DatePeriod::last_30_days()?;
-
-    // Query 1: Study time grouped by date
-    let time_query = r#"
-        SELECT date_str_from_ms(r.id) as date, SUM(r.time) as total_ms
+    // Query 1: Study time and review volume grouped by bucket. Manual reschedules are
+    // logged with ease 0 and aren't answers, so they don't count as reviews.
+    let time_query = format!(
+        r#"
+        SELECT
+            {bucket_fn}(r.id) as bucket,
+            SUM(review_ms(r.time)) as total_ms,
+            COUNT(CASE WHEN r.ease > 0 THEN 1 END) as reviews,
+            COUNT(DISTINCT CASE WHEN r.type = {REVLOG_TYPE_REV} THEN r.cid END) as due_completed,
+            COUNT(DISTINCT date_str_from_ms(r.id)) as days_studied
         FROM revlog r
         JOIN cards c ON c.id = r.cid
+        JOIN notes n ON n.id = c.nid
         WHERE c.did = ?1 AND r.id >= ?2 AND r.id < ?3
-        GROUP BY date_str_from_ms(r.id)
-    "#;
+            AND (?4 IS NULL OR has_tag(n.tags, ?4))
+            AND (?5 IS NULL OR parse_book_name(n.sfld) = ?5)
+        GROUP BY {bucket_fn}(r.id)
+        "#
+    );
 
-    let mut time_stmt = conn.prepare(time_query)?;
+    let mut time_stmt = conn.prepare(&time_query)?;
     let time_results = time_stmt
-        .query_map([deck_id, period.start_ms, period.end_ms], |row| {
-            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
-        })?
-        .collect::<Result<HashMap<String, i64>, _>>()?;
+        .query_map(
+            rusqlite::params![deck_id, period.start_ms, period.end_ms, tag, book],
+            |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    (
+                        row.get::<_, i64>(1)?,
+                        row.get::<_, i64>(2)?,
+                        row.get::<_, i64>(3)?,
+                        row.get::<_, i64>(4)?,
+                    ),
+                ))
+            },
+        )?
+        .collect::<Result<HashMap<String, (i64, i64, i64, i64)>, _>>()?;
 
-    // Query 2: Progress (maturation and loss) grouped by date
+    // Query 2: Progress (maturation and loss) grouped by bucket
     let progress_query = format!(
         r#"
         SELECT
-            date_str_from_ms(r.id) as date,
+            {bucket_fn}(r.id) as bucket,
             COUNT(CASE WHEN r.lastIvl < 21 AND r.ivl >= 21 THEN 1 END) as matured,
             COUNT(CASE WHEN r.lastIvl >= 21 AND r.ivl < 21 THEN 1 END) as lost
         FROM revlog r
@@ -230,111 +466,736 @@ pub fn get_last_30_days_stats(conn: &Connection) -> Result<Vec<DayStats>> {
         WHERE c.did = ?1 AND n.mid = ?2 AND c.ord = 0
             AND c.queue != {QUEUE_TYPE_SUSPENDED}
             AND r.id >= ?3 AND r.id < ?4
-        GROUP BY date_str_from_ms(r.id)
+            AND (?5 IS NULL OR has_tag(n.tags, ?5))
+            AND (?6 IS NULL OR parse_book_name(n.sfld) = ?6)
+        GROUP BY {bucket_fn}(r.id)
         "#
     );
 
     let mut progress_stmt = conn.prepare(&progress_query)?;
     let progress_results = progress_stmt
+        .query_map(
+            rusqlite::params![deck_id, model_id, period.start_ms, period.end_ms, tag, book],
+            |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    (row.get::<_, i64>(1)?, row.get::<_, i64>(2)?),
+                ))
+            },
+        )?
+        .collect::<Result<HashMap<String, (i64, i64)>, _>>()?;
+
+    // Query 3: Passages introduced, bucketed by the first answer to either of their cards
+    let new_query = format!(
+        r#"
+        SELECT {bucket_fn}(first_review) as bucket, COUNT(*) as new_passages
+        FROM (
+            SELECT MIN(r.id) as first_review
+            FROM revlog r
+            JOIN cards c ON c.id = r.cid
+            JOIN notes n ON n.id = c.nid
+            WHERE c.did = ?1 AND n.mid = ?2 AND r.ease > 0
+                AND (?5 IS NULL OR has_tag(n.tags, ?5))
+                AND (?6 IS NULL OR parse_book_name(n.sfld) = ?6)
+            GROUP BY n.id
+        )
+        WHERE first_review >= ?3 AND first_review < ?4
+        GROUP BY {bucket_fn}(first_review)
+        "#
+    );
+
+    let mut new_stmt = conn.prepare(&new_query)?;
+    let new_results = new_stmt
+        .query_map(
+            rusqlite::params![deck_id, model_id, period.start_ms, period.end_ms, tag, book],
+            |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?)),
+        )?
+        .collect::<Result<HashMap<String, i64>, _>>()?;
+
+    let mut cumulative_passages = 0i64;
+
+    let results = densify(time_results, &period)?
+        .zip(densify(progress_results, &period)?)?
+        .zip(densify(new_results, &period)?)?
+        .map(
+            |key,
+             (
+                (
+                    (total_ms, reviews, due_completed, days_studied),
+                    (matured_passages, lost_passages),
+                ),
+                new_passages,
+            )| {
+                cumulative_passages += matured_passages - lost_passages;
+
+                BucketProgress {
+                    key,
+                    minutes: total_ms as f64 / 60_000.0,
+                    reviews,
+                    due_completed,
+                    days_studied,
+                    new_passages,
+                    matured_passages,
+                    lost_passages,
+                    cumulative_passages,
+                }
+            },
+        );
+
+    Ok(results)
+}
+
+/// Gets study time and learning progress for each of the last 30 days as `period_config`
+/// splits them, optionally limited to notes with `tag`
+pub fn get_last_30_days_stats(
+    conn: &Connection,
+    period_config: &PeriodConfig,
+    tag: Option<&str>,
+) -> Result<Vec<DayStats>> {
+    let period = DatePeriod::last_30_days_with_config(period_config)?;
+    let results = get_period_progress(conn, period, Granularity::Day, tag, None)?;
+
+    Ok(results
+        .into_iter()
+        .map(|bucket| DayStats {
+            date: bucket.key,
+            minutes: bucket.minutes,
+            reviews: bucket.reviews,
+            due_completed: bucket.due_completed,
+            new_passages: bucket.new_passages,
+            matured_passages: bucket.matured_passages,
+            lost_passages: bucket.lost_passages,
+            cumulative_passages: bucket.cumulative_passages,
+        })
+        .collect())
+}
+
+/// Gets study time and learning progress for each of the last 12 weeks as `period_config`
+/// splits them, optionally limited to notes with `tag`
+pub fn get_last_12_weeks_stats(
+    conn: &Connection,
+    period_config: &PeriodConfig,
+    tag: Option<&str>,
+) -> Result<Vec<WeekStats>> {
+    let period = DatePeriod::last_12_weeks_with_config(period_config)?;
+    get_weekly_stats(conn, period, tag, None)
+}
+
+/// Gets study time and learning progress for each of the last `weeks` weeks, counting
+/// only passages from `book`
+pub fn get_book_weekly_stats(conn: &Connection, book: &str, weeks: i64) -> Result<Vec<WeekStats>> {
+    let period_config = get_period_config(conn)?;
+    let today = period_config.today()?;
+    let period = DatePeriod::for_range_with_config(
+        today - chrono::Duration::weeks(weeks.max(1) - 1),
+        today,
+        Granularity::Week,
+        &period_config,
+    )?;
+    get_weekly_stats(conn, period, None, Some(book))
+}
+
+/// Gets study time and learning progress for each week of a period
+fn get_weekly_stats(
+    conn: &Connection,
+    period: DatePeriod,
+    tag: Option<&str>,
+    book: Option<&str>,
+) -> Result<Vec<WeekStats>> {
+    let results = get_period_progress(conn, period, Granularity::Week, tag, book)?;
+
+    Ok(results
+        .into_iter()
+        .map(|bucket| WeekStats {
+            week_start: bucket.key,
+            minutes: bucket.minutes,
+            reviews: bucket.reviews,
+            due_completed: bucket.due_completed,
+            days_studied: bucket.days_studied,
+            new_passages: bucket.new_passages,
+            matured_passages: bucket.matured_passages,
+            lost_passages: bucket.lost_passages,
+            cumulative_passages: bucket.cumulative_passages,
+        })
+        .collect())
+}
+
+/// Gets study time and learning progress for each month of a period
+fn get_monthly_stats(conn: &Connection, period: DatePeriod) -> Result<Vec<MonthStats>> {
+    let results = get_period_progress(conn, period, Granularity::Month, None, None)?;
+
+    Ok(results
+        .into_iter()
+        .map(|bucket| MonthStats {
+            month: bucket.key,
+            minutes: bucket.minutes,
+            reviews: bucket.reviews,
+            due_completed: bucket.due_completed,
+            new_passages: bucket.new_passages,
+            matured_passages: bucket.matured_passages,
+            lost_passages: bucket.lost_passages,
+            cumulative_passages: bucket.cumulative_passages,
+        })
+        .collect())
+}
+
+/// Gets study time and learning progress for each of the last 12 months
+pub fn get_last_12_months_stats(conn: &Connection) -> Result<Vec<MonthStats>> {
+    get_monthly_stats(
+        conn,
+        DatePeriod::last_12_months_with_config(&get_period_config(conn)?)?,
+    )
+}
+
+/// Gets study time and learning progress for each month of a calendar year
+pub fn get_yearly_stats(conn: &Connection, year: i32) -> Result<Vec<MonthStats>> {
+    get_monthly_stats(
+        conn,
+        DatePeriod::months_of_year_with_config(year, &get_period_config(conn)?)?,
+    )
+}
+
+/// Gets study time and learning progress for each bucket of an arbitrary period
+pub fn get_stats_for_period(
+    conn: &Connection,
+    period: DatePeriod,
+    granularity: Granularity,
+) -> Result<Vec<PeriodStats>> {
+    let results = get_period_progress(conn, period, granularity, None, None)?;
+
+    Ok(results
+        .into_iter()
+        .map(|bucket| PeriodStats {
+            period_start: bucket.key,
+            minutes: bucket.minutes,
+            reviews: bucket.reviews,
+            due_completed: bucket.due_completed,
+            new_passages: bucket.new_passages,
+            matured_passages: bucket.matured_passages,
+            lost_passages: bucket.lost_passages,
+            cumulative_passages: bucket.cumulative_passages,
+        })
+        .collect())
+}
+
+/// Gets study time, review counts, maturation, and retention totals for a whole period
+pub fn get_period_totals(conn: &Connection, period: &DatePeriod) -> Result<PeriodTotals> {
+    let deck_id = get_deck_id(conn)?;
+    let model_id = get_model_id(conn)?;
+
+    let query = format!(
+        r#"
+        SELECT
+            COALESCE(SUM(review_ms(r.time)), 0),
+            COUNT(CASE WHEN r.ease > 0 THEN 1 END),
+            COUNT(CASE WHEN c.ord = 0 AND c.queue != {QUEUE_TYPE_SUSPENDED}
+                AND r.lastIvl < 21 AND r.ivl >= 21 THEN 1 END),
+            COUNT(CASE WHEN c.ord = 0 AND c.queue != {QUEUE_TYPE_SUSPENDED}
+                AND r.lastIvl >= 21 AND r.ivl < 21 THEN 1 END)
+        FROM revlog r
+        JOIN cards c ON c.id = r.cid
+        JOIN notes n ON n.id = c.nid
+        WHERE c.did = ?1 AND n.mid = ?2 AND r.id >= ?3 AND r.id < ?4
+        "#
+    );
+
+    let (total_ms, reviews, matured_passages, lost_passages): (i64, i64, i64, i64) = conn
+        .query_row(
+            &query,
+            [deck_id, model_id, period.start_ms, period.end_ms],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )
+        .context("Failed to query period totals")?;
+
+    let retention = get_retention_stats(conn, period)?;
+
+    Ok(PeriodTotals {
+        start_date: period.dates.first().cloned().unwrap_or_default(),
+        end_date: period.dates.last().cloned().unwrap_or_default(),
+        minutes: total_ms as f64 / 60_000.0,
+        reviews,
+        matured_passages,
+        lost_passages,
+        retention_percent: retention.overall.retention_percent,
+    })
+}
+
+/// Counts the verses in passages whose first answer falls within `[start_ms, end_ms)`
+pub fn get_new_verses(
+    conn: &Connection,
+    deck_id: i64,
+    model_id: i64,
+    start_ms: i64,
+    end_ms: i64,
+) -> Result<i64> {
+    let query = r#"
+        SELECT COALESCE(SUM(count_verses(reference)), 0)
+        FROM (
+            SELECT n.sfld as reference, MIN(r.id) as first_review
+            FROM revlog r
+            JOIN cards c ON c.id = r.cid
+            JOIN notes n ON n.id = c.nid
+            WHERE c.did = ?1 AND n.mid = ?2 AND r.ease > 0
+            GROUP BY n.id
+        )
+        WHERE first_review >= ?3 AND first_review < ?4
+    "#;
+
+    conn.query_row(query, [deck_id, model_id, start_ms, end_ms], |row| {
+        row.get(0)
+    })
+    .context("Failed to count new verses")
+}
+
+/// Gets the collection creation time in seconds (the epoch for review card due days)
+pub fn get_collection_creation_secs(conn: &Connection) -> Result<i64> {
+    conn.query_row("SELECT crt FROM col", [], |row| row.get(0))
+        .context("Failed to read collection creation time")
+}
+
+/// Gets the collection's schema version (11 for older collections, 18 for current Anki)
+pub fn get_schema_version(conn: &Connection) -> Result<i64> {
+    conn.query_row("SELECT ver FROM col", [], |row| row.get(0))
+        .context("Failed to read collection schema version")
+}
+
+/// Gets the next due day (relative to collection creation) for each scheduled passage
+///
+/// Returns (reference, due_day) pairs. A passage's due day is the earliest due day of any
+/// of its cards; new, suspended, and buried cards are not scheduled and are excluded.
+pub fn get_passage_due_days(
+    conn: &Connection,
+    deck_id: i64,
+    model_id: i64,
+) -> Result<Vec<(String, i64)>> {
+    let crt = get_collection_creation_secs(conn)?;
+
+    // Learning cards store their due time as epoch seconds; review and day-learning
+    // cards store it as a day number relative to collection creation
+    let query = format!(
+        r#"
+        SELECT
+            n.sfld,
+            MIN(CASE WHEN c.queue = {QUEUE_TYPE_LRN} THEN (c.due - ?3) / 86400 ELSE c.due END)
+        FROM notes n
+        JOIN cards c ON c.nid = n.id
+        WHERE c.did = ?1 AND n.mid = ?2
+            AND c.queue IN ({QUEUE_TYPE_LRN}, {QUEUE_TYPE_REV}, {QUEUE_TYPE_DAY_LEARN_RELEARN})
+        GROUP BY n.id
+        "#
+    );
+
+    let mut stmt = conn.prepare(&query)?;
+    let due_days = stmt
+        .query_map([deck_id, model_id, crt], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+        })?
+        .collect::<Result<Vec<(String, i64)>, _>>()?;
+
+    Ok(due_days)
+}
+
+/// Gets the number of passages and verses coming due on each of the next `days` days
+///
+/// Overdue passages are counted as due today.
+pub fn get_due_forecast(conn: &Connection, days: u32) -> Result<DueForecast> {
+    let deck_id = get_deck_id(conn)?;
+    let model_id = get_model_id(conn)?;
+    let crt = get_collection_creation_secs(conn)?;
+
+    let period_config = get_period_config(conn)?;
+    let today = period_config.today()?;
+    let last_day = today + chrono::Duration::days(days.max(1) as i64 - 1);
+    let period =
+        DatePeriod::for_range_with_config(today, last_day, Granularity::Day, &period_config)?;
+
+    // Anki's day number for today, relative to collection creation
+    let today_day =
+        (get_today_start_ms_with_config(&period_config)? / 1000 - crt).div_euclid(86400);
+
+    let mut due_counts: HashMap<String, (i64, i64)> = HashMap::new();
+    let mut due_today = Vec::new();
+
+    for (reference, due_day) in get_passage_due_days(conn, deck_id, model_id)? {
+        let offset = (due_day - today_day).max(0);
+        if offset >= days as i64 {
+            continue;
+        }
+
+        if offset == 0 {
+            due_today.push(reference.clone());
+        }
+
+        let date = (today + chrono::Duration::days(offset))
+            .format("%Y-%m-%d")
+            .to_string();
+        let entry = due_counts.entry(date).or_insert((0, 0));
+        entry.0 += 1;
+        entry.1 += verse_parser::count_verses_in_reference(&reference);
+    }
+
+    due_today.sort();
+
+    let days = densify(due_counts, &period)?.map(|date, (passages, verses)| DueDay {
+        date,
+        passages,
+        verses,
+    });
+
+    Ok(DueForecast { days, due_today })
+}
+
+/// Gets review pass rates by card maturity and the answer button distribution for a period
+///
+/// Learning covers learning and relearning steps, while young and mature are review
+/// answers split on the interval before the review (mature = 21+ days). A review passes
+/// when any button other than "Again" is pressed.
+pub fn get_retention_stats(conn: &Connection, period: &DatePeriod) -> Result<RetentionStats> {
+    let deck_id = get_deck_id(conn)?;
+    let model_id = get_model_id(conn)?;
+
+    let query = format!(
+        r#"
+        SELECT
+            CASE
+                WHEN r.type IN ({REVLOG_TYPE_LRN}, {REVLOG_TYPE_RELRN}) THEN 'learning'
+                WHEN r.lastIvl >= 21 THEN 'mature'
+                ELSE 'young'
+            END AS maturity,
+            r.ease,
+            COUNT(*)
+        FROM revlog r
+        JOIN cards c ON c.id = r.cid
+        JOIN notes n ON n.id = c.nid
+        WHERE c.did = ?1 AND n.mid = ?2
+            AND r.type IN ({REVLOG_TYPE_LRN}, {REVLOG_TYPE_REV}, {REVLOG_TYPE_RELRN})
+            AND r.ease BETWEEN 1 AND 4
+            AND r.id >= ?3 AND r.id < ?4
+        GROUP BY maturity, r.ease
+        "#
+    );
+
+    let mut stmt = conn.prepare(&query)?;
+    let rows = stmt
         .query_map([deck_id, model_id, period.start_ms, period.end_ms], |row| {
             Ok((
                 row.get::<_, String>(0)?,
-                (row.get::<_, i64>(1)?, row.get::<_, i64>(2)?),
+                row.get::<_, i64>(1)?,
+                row.get::<_, i64>(2)?,
             ))
         })?
-        .collect::<Result<HashMap<String, (i64, i64)>, _>>()?;
+        .collect::<Result<Vec<(String, i64, i64)>, _>>()?;
 
-    let mut cumulative_passages = 0i64;
+    let mut stats = RetentionStats::default();
 
-    let results = period.build_results_2(
-        time_results,
-        progress_results,
-        |date, total_ms, (matured_passages, lost_passages)| {
-            cumulative_passages += matured_passages - lost_passages;
-
-            DayStats {
-                date,
-                minutes: total_ms as f64 / 60_000.0,
-                matured_passages,
-                lost_passages,
-                cumulative_passages,
-            }
-        },
-    );
+    for (maturity, ease, count) in rows {
+        stats.add(&maturity, ease, count);
+    }
 
-    Ok(results)
+    Ok(stats)
 }
 
-/// Gets study time and learning progress for each of the last 12 weeks
-pub fn get_last_12_weeks_stats(conn: &Connection) -> Result<Vec<WeekStats>> {
+/// Gets every distinct day (with rollover applied) on which the Bible deck was studied
+pub fn get_study_dates(conn: &Connection) -> Result<Vec<NaiveDate>> {
     let deck_id = get_deck_id(conn)?;
-    let model_id = get_model_id(conn)?;
 
-    // Get the period data for the last 12 weeks
-    let period = DatePeriod::last_12_weeks()?;
+    let query = r#"
+        SELECT DISTINCT date_str_from_ms(r.id) as date
+        FROM revlog r
+        JOIN cards c ON c.id = r.cid
+        WHERE c.did = ?1
+        ORDER BY date
+    "#;
+
+    let mut stmt = conn.prepare(query)?;
+    let dates = stmt
+        .query_map([deck_id], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<String>, _>>()?;
+
+    dates
+        .iter()
+        .map(|date| {
+            NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                .context(format!("Invalid study date '{}'", date))
+        })
+        .collect()
+}
+
+/// Gets scheduling details for every passage, sorted by reference
+pub fn get_passage_details(
+    conn: &Connection,
+    deck_id: i64,
+    model_id: i64,
+) -> Result<Vec<PassageDetail>> {
+    let crt = get_collection_creation_secs(conn)?;
+    let period_config = get_period_config(conn)?;
+    let today = period_config.today()?;
+    let today_day =
+        (get_today_start_ms_with_config(&period_config)? / 1000 - crt).div_euclid(86400);
+
+    let passage_state = passage_state_sql();
+    let query = format!(
+        r#"
+        SELECT
+            reference,
+            count_verses(reference),
+            state,
+            interval_days,
+            ease_permille,
+            lapses,
+            due_day,
+            CASE WHEN last_review_ms IS NOT NULL THEN date_str_from_ms(last_review_ms) END
+        FROM (
+            SELECT
+                notes.sfld AS reference,
+                {passage_state} AS state,
+                MIN(c.ivl) AS interval_days,
+                AVG(c.factor) AS ease_permille,
+                SUM(c.lapses) AS lapses,
+                MIN(CASE
+                    WHEN c.queue = {QUEUE_TYPE_LRN} THEN (c.due - ?3) / 86400
+                    WHEN c.queue IN ({QUEUE_TYPE_REV}, {QUEUE_TYPE_DAY_LEARN_RELEARN}) THEN c.due
+                    END) AS due_day,
+                (
+                    SELECT MAX(r.id)
+                    FROM revlog r
+                    JOIN cards rc ON rc.id = r.cid
+                    WHERE rc.nid = notes.id AND rc.did = ?2
+                ) AS last_review_ms
+            FROM notes
+            JOIN cards AS c ON c.nid = notes.id AND c.did = ?2
+            WHERE notes.mid = ?1
+            GROUP BY notes.id
+        )
+        ORDER BY reference
+        "#
+    );
+
+    let mut stmt = conn.prepare(&query)?;
+    let details = stmt
+        .query_map([model_id, deck_id, crt], |row| {
+            let due_day: Option<i64> = row.get(6)?;
+            Ok(PassageDetail {
+                reference: row.get(0)?,
+                verses: row.get(1)?,
+                state: row.get(2)?,
+                interval_days: row.get(3)?,
+                ease_percent: row.get::<_, f64>(4)? / 10.0,
+                lapses: row.get(5)?,
+                // Overdue passages are reported with their original due date
+                due_date: due_day.map(|day| {
+                    (today + chrono::Duration::days(day - today_day))
+                        .format("%Y-%m-%d")
+                        .to_string()
+                }),
+                last_review: row.get(7)?,
+            })
+        })?
+        .collect::<Result<Vec<PassageDetail>, _>>()?;
+
+    Ok(details)
+}
 
-    // Query 1: Study time grouped by week
-    let time_query = r#"
-        SELECT week_str_from_ms(r.id) as week, SUM(r.time) as total_ms
+/// Counts how many times each passage's cards went from mature to not mature since `since_ms`
+pub fn get_maturity_losses(
+    conn: &Connection,
+    deck_id: i64,
+    model_id: i64,
+    since_ms: i64,
+) -> Result<HashMap<String, i64>> {
+    let query = r#"
+        SELECT n.sfld, COUNT(*)
         FROM revlog r
         JOIN cards c ON c.id = r.cid
-        WHERE c.did = ?1 AND r.id >= ?2 AND r.id < ?3
-        GROUP BY week_str_from_ms(r.id)
+        JOIN notes n ON n.id = c.nid
+        WHERE c.did = ?1 AND n.mid = ?2
+            AND r.lastIvl >= 21 AND r.ivl < 21
+            AND r.id >= ?3
+        GROUP BY n.id
     "#;
 
-    let mut time_stmt = conn.prepare(time_query)?;
-    let time_results = time_stmt
-        .query_map([deck_id, period.start_ms, period.end_ms], |row| {
+    let mut stmt = conn.prepare(query)?;
+    let losses = stmt
+        .query_map([deck_id, model_id, since_ms], |row| {
             Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
         })?
         .collect::<Result<HashMap<String, i64>, _>>()?;
 
-    // Query 2: Progress (maturation and loss) grouped by week
-    let progress_query = format!(
+    Ok(losses)
+}
+
+/// Gets the current interval (days) and ease (percent) of every reviewed card
+///
+/// New, learning, and suspended cards have no meaningful interval and are left out.
+pub fn get_card_intervals(
+    conn: &Connection,
+    deck_id: i64,
+    model_id: i64,
+) -> Result<Vec<(i64, i64)>> {
+    let query = format!(
+        r#"
+        SELECT c.ivl, c.factor / 10
+        FROM cards c
+        JOIN notes n ON n.id = c.nid
+        WHERE c.did = ?1 AND n.mid = ?2
+            AND c.queue != {QUEUE_TYPE_SUSPENDED}
+            AND c.ivl > 0
+        "#
+    );
+
+    let mut stmt = conn.prepare(&query)?;
+    let cards = stmt
+        .query_map([deck_id, model_id], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<Vec<(i64, i64)>, _>>()?;
+
+    Ok(cards)
+}
+
+/// Counts passages in a book that matured and that lost maturity since `since_ms`
+///
+/// Like the period progress query, only the first card of each passage is considered.
+pub fn get_book_maturation(
+    conn: &Connection,
+    deck_id: i64,
+    model_id: i64,
+    book: &str,
+    since_ms: i64,
+) -> Result<(i64, i64)> {
+    let query = format!(
         r#"
         SELECT
-            week_str_from_ms(r.id) as week,
-            COUNT(CASE WHEN r.lastIvl < 21 AND r.ivl >= 21 THEN 1 END) as matured,
-            COUNT(CASE WHEN r.lastIvl >= 21 AND r.ivl < 21 THEN 1 END) as lost
+            COUNT(CASE WHEN r.lastIvl < 21 AND r.ivl >= 21 THEN 1 END),
+            COUNT(CASE WHEN r.lastIvl >= 21 AND r.ivl < 21 THEN 1 END)
         FROM revlog r
         JOIN cards c ON c.id = r.cid
         JOIN notes n ON n.id = c.nid
         WHERE c.did = ?1 AND n.mid = ?2 AND c.ord = 0
             AND c.queue != {QUEUE_TYPE_SUSPENDED}
-            AND r.id >= ?3 AND r.id < ?4
-        GROUP BY week_str_from_ms(r.id)
+            AND r.id >= ?3
+            AND parse_book_name(n.sfld) = ?4
         "#
     );
 
-    let mut progress_stmt = conn.prepare(&progress_query)?;
-    let progress_results = progress_stmt
+    conn.query_row(
+        &query,
+        rusqlite::params![deck_id, model_id, since_ms, book],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )
+    .context(format!("Failed to count maturation for {}", book))
+}
+
+/// Gets the reference of every Bible Verse note in the deck, keyed by note ID
+pub fn get_note_references(
+    conn: &Connection,
+    deck_id: i64,
+    model_id: i64,
+) -> Result<HashMap<i64, String>> {
+    let query = r#"
+        SELECT DISTINCT n.id, n.sfld
+        FROM notes n
+        JOIN cards c ON c.nid = n.id
+        WHERE c.did = ?1 AND n.mid = ?2
+    "#;
+
+    let mut stmt = conn.prepare(query)?;
+    let references = stmt
+        .query_map([deck_id, model_id], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<HashMap<i64, String>, _>>()?;
+
+    Ok(references)
+}
+
+/// Gets every answer to the first two cards of each passage, oldest first
+///
+/// Manual reschedules (ease 0) are included because they change the interval.
+pub fn get_card_reviews(conn: &Connection, deck_id: i64, model_id: i64) -> Result<Vec<CardReview>> {
+    let query = r#"
+        SELECT r.id, c.nid, c.ord, r.ivl
+        FROM revlog r
+        JOIN cards c ON c.id = r.cid
+        JOIN notes n ON n.id = c.nid
+        WHERE c.did = ?1 AND n.mid = ?2 AND c.ord IN (0, 1)
+        ORDER BY r.id
+    "#;
+
+    let mut stmt = conn.prepare(query)?;
+    let reviews = stmt
+        .query_map([deck_id, model_id], |row| {
+            Ok(CardReview {
+                timestamp_ms: row.get(0)?,
+                note_id: row.get(1)?,
+                ord: row.get(2)?,
+                interval: row.get(3)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(reviews)
+}
+
+/// Gets every answer and reschedule of a Bible Verse card within a period, oldest first
+pub fn get_review_log(
+    conn: &Connection,
+    deck_id: i64,
+    model_id: i64,
+    period: &DatePeriod,
+) -> Result<Vec<ReviewEvent>> {
+    let period_config = get_period_config(conn)?;
+
+    let query = r#"
+        SELECT r.id, c.nid, n.sfld, c.ord, r.type, r.ease, r.lastIvl, r.ivl, r.factor, r.time
+        FROM revlog r
+        JOIN cards c ON c.id = r.cid
+        JOIN notes n ON n.id = c.nid
+        WHERE c.did = ?1 AND n.mid = ?2 AND r.id >= ?3 AND r.id < ?4
+        ORDER BY r.id
+    "#;
+
+    let mut stmt = conn.prepare(query)?;
+    let rows = stmt
         .query_map([deck_id, model_id, period.start_ms, period.end_ms], |row| {
             Ok((
-                row.get::<_, String>(0)?,
-                (row.get::<_, i64>(1)?, row.get::<_, i64>(2)?),
+                row.get::<_, i64>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, i64>(3)?,
+                row.get::<_, i64>(4)?,
+                row.get::<_, i64>(5)?,
+                row.get::<_, i64>(6)?,
+                row.get::<_, i64>(7)?,
+                row.get::<_, i64>(8)?,
+                row.get::<_, i64>(9)?,
             ))
         })?
-        .collect::<Result<HashMap<String, (i64, i64)>, _>>()?;
+        .collect::<Result<Vec<_>, _>>()?;
 
-    let mut cumulative_passages = 0;
-
-    let results = period.build_results_2(
-        time_results,
-        progress_results,
-        |date, total_ms, (matured_passages, lost_passages)| {
-            cumulative_passages += matured_passages - lost_passages;
-
-            WeekStats {
-                week_start: date,
-                minutes: total_ms as f64 / 60_000.0,
-                matured_passages,
-                lost_passages,
-                cumulative_passages,
-            }
-        },
-    );
-
-    Ok(results)
+    rows.into_iter()
+        .map(
+            |(timestamp_ms, note_id, reference, ord, kind, ease, last_ivl, ivl, factor, time)| {
+                Ok(ReviewEvent {
+                    timestamp_ms,
+                    time: period_config
+                        .timezone
+                        .local_datetime(timestamp_ms)?
+                        .format("%Y-%m-%d %H:%M:%S")
+                        .to_string(),
+                    date: period_config
+                        .logical_date(timestamp_ms)?
+                        .format("%Y-%m-%d")
+                        .to_string(),
+                    note_id,
+                    reference,
+                    card_ord: ord,
+                    review_type: review_type_name(kind).to_string(),
+                    ease,
+                    last_interval: last_ivl,
+                    interval: ivl,
+                    ease_factor_percent: factor as f64 / 10.0,
+                    time_seconds: time as f64 / 1000.0,
+                })
+            },
+        )
+        .collect()
 }
 
 /// Gets all distinct Bible references from the database, sorted alphabetically
@@ -354,3 +1215,158 @@ pub fn get_all_references(conn: &Connection, deck_id: i64, model_id: i64) -> Res
 
     Ok(references)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn create_collection(path: &Path) -> Connection {
+        let conn = Connection::open(path).unwrap();
+        conn.execute_batch("CREATE TABLE col (crt INTEGER); INSERT INTO col VALUES (1700000000);")
+            .unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_note_has_tag() {
+        assert!(note_has_tag(
+            " memorize::current john ",
+            "memorize::current"
+        ));
+        assert!(note_has_tag(" Memorize::Current ", "memorize::current"));
+        assert!(note_has_tag(
+            " memorize::current::week1 ",
+            "memorize::current"
+        ));
+        assert!(note_has_tag(" memorize::current ", "memorize"));
+        assert!(!note_has_tag(" memorize::currently ", "memorize::current"));
+        assert!(!note_has_tag(" memorize::review ", "memorize::current"));
+        assert!(!note_has_tag("", "memorize"));
+    }
+
+    #[test]
+    fn test_period_config_from_config_table() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE config (KEY text primary key, usn integer, mtime_secs integer, val blob);
+             INSERT INTO config VALUES ('rollover', 0, 0, CAST('2' AS BLOB));
+             INSERT INTO config VALUES ('localOffset', 0, 0, CAST('-60' AS BLOB));",
+        )
+        .unwrap();
+
+        let config = get_period_config(&conn).unwrap();
+        assert_eq!(config.rollover_hour, 2);
+        assert_eq!(
+            config.timezone,
+            StatsTimezone::Fixed(FixedOffset::east_opt(3600).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_period_config_from_legacy_conf() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            r#"CREATE TABLE col (crt integer, conf text);
+               INSERT INTO col VALUES (1700000000, '{"rollover": 5, "creationOffset": 300}');"#,
+        )
+        .unwrap();
+
+        let config = get_period_config(&conn).unwrap();
+        assert_eq!(config.rollover_hour, 5);
+        assert_eq!(
+            config.timezone,
+            StatsTimezone::Fixed(FixedOffset::west_opt(300 * 60).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_period_config_defaults() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("CREATE TABLE col (crt integer, conf text);")
+            .unwrap();
+        assert_eq!(get_period_config(&conn).unwrap(), PeriodConfig::default());
+    }
+
+    #[test]
+    fn test_open_database_read_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("collection.anki2");
+        drop(create_collection(&path));
+
+        let conn = open_database(path.to_str().unwrap()).unwrap();
+        assert_eq!(get_collection_creation_secs(&conn).unwrap(), 1700000000);
+        assert!(conn.execute("DELETE FROM col", []).is_err());
+    }
+
+    #[test]
+    fn test_open_locked_database_uses_snapshot() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("collection.anki2");
+
+        // Hold an exclusive lock, as Anki does while syncing
+        let writer = create_collection(&path);
+        writer
+            .execute_batch(
+                "PRAGMA locking_mode = EXCLUSIVE; BEGIN EXCLUSIVE; UPDATE col SET crt = crt;",
+            )
+            .unwrap();
+
+        let conn = open_database(path.to_str().unwrap()).unwrap();
+        assert_eq!(get_collection_creation_secs(&conn).unwrap(), 1700000000);
+    }
+
+    #[test]
+    fn test_snapshot_includes_wal() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("collection.anki2");
+
+        let writer = create_collection(&path);
+        writer
+            .execute_batch(
+                "PRAGMA journal_mode = WAL; PRAGMA wal_autocheckpoint = 0; UPDATE col SET crt = 1800000000;",
+            )
+            .unwrap();
+
+        let conn = statsutils::sqlite::open_snapshot(&path).unwrap();
+        assert_eq!(get_collection_creation_secs(&conn).unwrap(), 1800000000);
+    }
+
+    #[test]
+    fn test_books_stats_with_any_number_of_templates() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("collection.anki2");
+        let writer = create_collection(&path);
+        writer
+            .execute_batch(
+                r#"
+                CREATE TABLE notes (id INTEGER, mid INTEGER, sfld TEXT, tags TEXT);
+                CREATE TABLE cards (id INTEGER, nid INTEGER, did INTEGER, ord INTEGER,
+                    queue INTEGER, ivl INTEGER);
+                INSERT INTO notes VALUES
+                    (1, 10, 'John 3:16', ''),
+                    (2, 10, 'John 1:1-2', ''),
+                    (3, 10, 'Romans 8:28', '');
+                -- One template, mature
+                INSERT INTO cards VALUES (1, 1, 20, 0, 2, 30);
+                -- Three templates, held back by the weakest card
+                INSERT INTO cards VALUES (2, 2, 20, 0, 2, 30), (3, 2, 20, 1, 2, 25),
+                    (4, 2, 20, 2, 2, 10);
+                -- Three templates, one suspended
+                INSERT INTO cards VALUES (5, 3, 20, 0, 2, 30), (6, 3, 20, 1, 2, 30),
+                    (7, 3, 20, 2, -1, 30);
+                "#,
+            )
+            .unwrap();
+        drop(writer);
+
+        let conn = open_database(path.to_str().unwrap()).unwrap();
+        let books = get_all_books_stats(&conn, 20, 10, None).unwrap();
+
+        let john = &books["John"];
+        assert_eq!(john.mature_passages, 1);
+        assert_eq!(john.young_passages, 1);
+        assert_eq!(john.young_verses, 2);
+        assert_eq!(books["Romans"].suspended_passages, 1);
+    }
+}
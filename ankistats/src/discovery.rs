@@ -0,0 +1,114 @@
+use anyhow::{Result, bail};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// File name of the collection inside each Anki profile directory
+const COLLECTION_FILE: &str = "collection.anki2";
+
+/// Standard locations of Anki's data directory (which holds one folder per profile)
+///
+/// Every platform's locations are checked, since only the existing ones are used.
+fn anki_data_dirs() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+
+    if let Some(data_home) = env::var_os("XDG_DATA_HOME") {
+        dirs.push(PathBuf::from(data_home).join("Anki2"));
+    }
+    if let Some(home) = env::var_os("HOME") {
+        let home = PathBuf::from(home);
+        // Linux
+        dirs.push(home.join(".local/share/Anki2"));
+        // Linux Flatpak
+        dirs.push(home.join(".var/app/net.ankiweb.Anki/data/Anki2"));
+        // macOS
+        dirs.push(home.join("Library/Application Support/Anki2"));
+    }
+    // Windows
+    if let Some(app_data) = env::var_os("APPDATA") {
+        dirs.push(PathBuf::from(app_data).join("Anki2"));
+    }
+
+    dirs
+}
+
+/// When a collection was last written, including its write-ahead log
+fn last_modified(collection: &Path) -> Option<SystemTime> {
+    let mut wal = collection.as_os_str().to_owned();
+    wal.push("-wal");
+
+    [collection.to_path_buf(), PathBuf::from(wal)]
+        .iter()
+        .filter_map(|path| fs::metadata(path).and_then(|m| m.modified()).ok())
+        .max()
+}
+
+/// Finds the most recently modified profile collection in any of `data_dirs`
+pub fn find_collection_in(data_dirs: &[PathBuf]) -> Option<PathBuf> {
+    data_dirs
+        .iter()
+        .filter_map(|dir| fs::read_dir(dir).ok())
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path().join(COLLECTION_FILE))
+        .filter_map(|collection| Some((last_modified(&collection)?, collection)))
+        .max_by_key(|(modified, _)| *modified)
+        .map(|(_, collection)| collection)
+}
+
+/// Locates the collection of the most recently used Anki profile
+///
+/// Looks in the standard Anki data directories for Linux (including Flatpak), macOS, and
+/// Windows, and picks the profile whose collection was modified most recently.
+pub fn find_default_collection() -> Result<PathBuf> {
+    let data_dirs = anki_data_dirs();
+    match find_collection_in(&data_dirs) {
+        Some(collection) => Ok(collection),
+        None => bail!(
+            "No Anki collection found in {}",
+            data_dirs
+                .iter()
+                .map(|dir| dir.display().to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn create_profile(data_dir: &Path, profile: &str, modified: SystemTime) -> PathBuf {
+        let profile_dir = data_dir.join(profile);
+        fs::create_dir_all(&profile_dir).unwrap();
+        let collection = profile_dir.join(COLLECTION_FILE);
+        let file = fs::File::create(&collection).unwrap();
+        file.set_modified(modified).unwrap();
+        collection
+    }
+
+    #[test]
+    fn test_picks_most_recent_profile() {
+        let dir = tempfile::tempdir().unwrap();
+        let now = SystemTime::now();
+        create_profile(dir.path(), "User 1", now - Duration::from_secs(3600));
+        let recent = create_profile(dir.path(), "Work", now);
+        fs::create_dir_all(dir.path().join("addons21")).unwrap();
+
+        let missing = dir.path().join("missing");
+        assert_eq!(
+            find_collection_in(&[missing, dir.path().to_path_buf()]),
+            Some(recent)
+        );
+    }
+
+    #[test]
+    fn test_no_collection() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("addons21")).unwrap();
+        assert_eq!(find_collection_in(&[dir.path().to_path_buf()]), None);
+    }
+}
@@ -0,0 +1,104 @@
+use statsutils::math;
+
+use crate::models::{HistogramBucket, IntervalDistribution};
+
+/// Lower bounds (in days) of the interval histogram buckets; the last bucket is open-ended
+pub const INTERVAL_BUCKETS: &[i64] = &[1, 2, 4, 8, 15, 21, 31, 61, 91, 181, 366];
+
+/// Lower bounds (in percent) of the ease histogram buckets; the last bucket is open-ended
+///
+/// Anki never lets ease drop below 130%, so the first bucket starts there.
+pub const EASE_BUCKETS: &[i64] = &[130, 150, 170, 190, 210, 230, 250, 270, 290, 310];
+
+/// Counts values into buckets starting at each of `lower_bounds`
+///
+/// Values below the first bound are counted in the first bucket.
+fn build_histogram(values: &[i64], lower_bounds: &[i64], unit: &str) -> Vec<HistogramBucket> {
+    let mut buckets: Vec<HistogramBucket> = lower_bounds
+        .iter()
+        .enumerate()
+        .map(|(i, &min)| {
+            let max = lower_bounds.get(i + 1).map(|next| next - 1);
+            let label = match max {
+                Some(max) if max == min => format!("{}{}", min, unit),
+                Some(max) => format!("{}-{}{}", min, max, unit),
+                None => format!("{}{}+", min, unit),
+            };
+            HistogramBucket {
+                label,
+                min,
+                max,
+                cards: 0,
+            }
+        })
+        .collect();
+
+    let counts = math::bucket_counts(&as_f64(values), &as_f64(lower_bounds));
+    for (bucket, count) in buckets.iter_mut().zip(counts) {
+        bucket.cards = count as i64;
+    }
+
+    buckets
+}
+
+fn as_f64(values: &[i64]) -> Vec<f64> {
+    values.iter().map(|&v| v as f64).collect()
+}
+
+/// Builds interval and ease histograms from `(interval_days, ease_percent)` pairs
+pub fn build_distribution(cards: &[(i64, i64)]) -> IntervalDistribution {
+    let intervals: Vec<i64> = cards.iter().map(|&(interval, _)| interval).collect();
+    // Cards scheduled by FSRS store no ease factor
+    let eases: Vec<i64> = cards
+        .iter()
+        .map(|&(_, ease)| ease)
+        .filter(|&ease| ease > 0)
+        .collect();
+
+    IntervalDistribution {
+        total_cards: cards.len() as i64,
+        average_interval_days: math::mean(&as_f64(&intervals)),
+        average_ease_percent: math::mean(&as_f64(&eases)),
+        intervals: build_histogram(&intervals, INTERVAL_BUCKETS, "d"),
+        ease: build_histogram(&eases, EASE_BUCKETS, "%"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_histogram_labels() {
+        let buckets = build_histogram(&[], &[1, 2, 4], "d");
+        let labels: Vec<&str> = buckets.iter().map(|b| b.label.as_str()).collect();
+        assert_eq!(labels, ["1d", "2-3d", "4d+"]);
+        assert_eq!(buckets[1].max, Some(3));
+        assert_eq!(buckets[2].max, None);
+    }
+
+    #[test]
+    fn test_build_distribution() {
+        let cards = [(1, 250), (3, 250), (25, 130), (400, 0), (30, 290)];
+        let distribution = build_distribution(&cards);
+
+        assert_eq!(distribution.total_cards, 5);
+        assert_eq!(distribution.average_interval_days, 91.8);
+        // The FSRS card (ease 0) is left out of the ease histogram
+        assert_eq!(distribution.average_ease_percent, 230.0);
+
+        let interval_counts: Vec<i64> = distribution.intervals.iter().map(|b| b.cards).collect();
+        assert_eq!(interval_counts, [1, 1, 0, 0, 0, 2, 0, 0, 0, 0, 1]);
+
+        let ease_counts: Vec<i64> = distribution.ease.iter().map(|b| b.cards).collect();
+        assert_eq!(ease_counts, [1, 0, 0, 0, 0, 0, 2, 0, 1, 0]);
+    }
+
+    #[test]
+    fn test_build_distribution_empty() {
+        let distribution = build_distribution(&[]);
+        assert_eq!(distribution.total_cards, 0);
+        assert_eq!(distribution.average_interval_days, 0.0);
+        assert!(distribution.intervals.iter().all(|b| b.cards == 0));
+    }
+}
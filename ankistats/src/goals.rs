@@ -0,0 +1,231 @@
+use anyhow::{Context, Result};
+use chrono::{Datelike, NaiveDate};
+use serde::Deserialize;
+use statsutils::parse_local_date;
+
+use crate::models::{Goal, GoalProgress};
+
+/// Layout of a goals TOML file: a list of `[[goals]]` tables
+#[derive(Deserialize)]
+struct GoalsFile {
+    goals: Vec<Goal>,
+}
+
+/// Parses goals from TOML text
+pub fn parse_goals(content: &str) -> Result<Vec<Goal>> {
+    let file: GoalsFile = toml::from_str(content).context("Invalid goals file")?;
+    Ok(file.goals)
+}
+
+/// Reads goals from a TOML file
+pub fn load_goals(path: &str) -> Result<Vec<Goal>> {
+    let content =
+        std::fs::read_to_string(path).context(format!("Failed to read goals file '{}'", path))?;
+    parse_goals(&content)
+}
+
+/// Current numbers that goals are measured against
+#[derive(Debug, Clone, Default)]
+pub struct GoalInputs {
+    /// Verses in passages first studied this calendar month
+    pub verses_this_month: i64,
+    pub minutes_today: f64,
+    /// Average daily minutes over the last 7 days, including today
+    pub average_minutes_7_days: f64,
+    pub mature_passages: i64,
+    /// Net passages matured per week recently (matured minus lost)
+    pub matured_per_week: f64,
+}
+
+fn progress(
+    goal: &Goal,
+    description: String,
+    current: f64,
+    target: f64,
+    on_pace: bool,
+    days_left: i64,
+) -> GoalProgress {
+    let shared = statsutils::goals::GoalProgress::measure(current, target, on_pace, days_left);
+    GoalProgress {
+        goal: goal.clone(),
+        description,
+        current: shared.current,
+        target: shared.target,
+        remaining: shared.remaining,
+        percent: shared.percent,
+        achieved: shared.achieved,
+        on_track: shared.on_track,
+        pace_needed: shared.pace_needed,
+    }
+}
+
+fn days_in_month(date: NaiveDate) -> u32 {
+    let (year, month) = if date.month() == 12 {
+        (date.year() + 1, 1)
+    } else {
+        (date.year(), date.month() + 1)
+    };
+    NaiveDate::from_ymd_opt(year, month, 1)
+        .and_then(|next| next.pred_opt())
+        .map_or(31, |last| last.day())
+}
+
+/// Measures a goal against the current numbers
+///
+/// - Verses per month is on track when this month's count keeps pace with the share of
+///   the month that has passed.
+/// - Minutes per day counts today's minutes as progress and is on track when the 7-day
+///   average meets the target.
+/// - Passages by date is on track when the recent net maturation rate would reach the
+///   target by the deadline.
+pub fn evaluate_goal(goal: &Goal, inputs: &GoalInputs, today: NaiveDate) -> Result<GoalProgress> {
+    Ok(match goal {
+        Goal::VersesPerMonth { target } => {
+            let target = *target as f64;
+            let current = inputs.verses_this_month as f64;
+            let month_elapsed = today.day() as f64 / days_in_month(today) as f64;
+            progress(
+                goal,
+                format!("{} new verses per month", target),
+                current,
+                target,
+                current >= target * month_elapsed,
+                (days_in_month(today) - today.day() + 1) as i64,
+            )
+        }
+        Goal::MinutesPerDay { target } => progress(
+            goal,
+            format!("{} minutes per day", target),
+            inputs.minutes_today,
+            *target,
+            inputs.average_minutes_7_days >= *target,
+            1,
+        ),
+        Goal::PassagesByDate { target, date } => {
+            let deadline = parse_local_date(date).context("Invalid goal date")?;
+            let remaining = (*target - inputs.mature_passages).max(0) as f64;
+            let weeks_left = (deadline - today).num_days().max(0) as f64 / 7.0;
+            progress(
+                goal,
+                format!("{} mature passages by {}", target, date),
+                inputs.mature_passages as f64,
+                *target as f64,
+                inputs.matured_per_week * weeks_left >= remaining,
+                (deadline - today).num_days() + 1,
+            )
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_parse_goals() {
+        let goals = parse_goals(
+            r#"
+            [[goals]]
+            type = "verses_per_month"
+            target = 30
+
+            [[goals]]
+            type = "minutes_per_day"
+            target = 15.0
+
+            [[goals]]
+            type = "passages_by_date"
+            target = 150
+            date = "2026-12-31"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            goals,
+            [
+                Goal::VersesPerMonth { target: 30 },
+                Goal::MinutesPerDay { target: 15.0 },
+                Goal::PassagesByDate {
+                    target: 150,
+                    date: "2026-12-31".to_string()
+                },
+            ]
+        );
+        assert!(parse_goals("[[goals]]\ntype = \"pages\"").is_err());
+    }
+
+    #[test]
+    fn test_verses_per_month_pace() {
+        let goal = Goal::VersesPerMonth { target: 30 };
+        let inputs = GoalInputs {
+            verses_this_month: 12,
+            ..Default::default()
+        };
+
+        // 12 of 30 verses is ahead of pace on June 10, behind on June 20
+        let early = evaluate_goal(&goal, &inputs, date(2025, 6, 10)).unwrap();
+        assert!(early.on_track);
+        assert!(!early.achieved);
+        assert_eq!(early.remaining, 18.0);
+        assert_eq!(early.percent, 40.0);
+
+        let late = evaluate_goal(&goal, &inputs, date(2025, 6, 20)).unwrap();
+        assert!(!late.on_track);
+        // 18 verses over the 11 days left, including today
+        assert!((late.pace_needed - 18.0 / 11.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_minutes_per_day() {
+        let goal = Goal::MinutesPerDay { target: 15.0 };
+        let inputs = GoalInputs {
+            minutes_today: 20.0,
+            average_minutes_7_days: 10.0,
+            ..Default::default()
+        };
+
+        let result = evaluate_goal(&goal, &inputs, date(2025, 6, 10)).unwrap();
+        assert!(result.achieved);
+        assert!(result.on_track);
+        assert_eq!(result.remaining, 0.0);
+        assert_eq!(result.percent, 100.0);
+    }
+
+    #[test]
+    fn test_passages_by_date() {
+        let goal = Goal::PassagesByDate {
+            target: 100,
+            date: "2025-07-01".to_string(),
+        };
+        let inputs = GoalInputs {
+            mature_passages: 90,
+            matured_per_week: 2.0,
+            ..Default::default()
+        };
+
+        // 10 remaining, 3 weeks left at 2/week: not enough
+        let result = evaluate_goal(&goal, &inputs, date(2025, 6, 10)).unwrap();
+        assert!(!result.on_track);
+        // 5 weeks left: enough
+        let result = evaluate_goal(&goal, &inputs, date(2025, 5, 27)).unwrap();
+        assert!(result.on_track);
+
+        let bad_date = Goal::PassagesByDate {
+            target: 100,
+            date: "soon".to_string(),
+        };
+        assert!(evaluate_goal(&bad_date, &inputs, date(2025, 6, 10)).is_err());
+    }
+
+    #[test]
+    fn test_days_in_month() {
+        assert_eq!(days_in_month(date(2024, 2, 10)), 29);
+        assert_eq!(days_in_month(date(2025, 12, 31)), 31);
+        assert_eq!(days_in_month(date(2025, 6, 1)), 30);
+    }
+}
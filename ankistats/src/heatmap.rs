@@ -0,0 +1,140 @@
+use anyhow::Result;
+use chrono::NaiveDate;
+use statsutils::PeriodConfig;
+use statsutils::calendar::{self, CalendarCell};
+use std::collections::HashMap;
+
+use crate::models::{HeatmapDay, HeatmapWeek, StudyHeatmap};
+
+/// Number of intensity levels (excluding "no study") used for rendering, like GitHub's grid
+pub use statsutils::calendar::INTENSITY_LEVELS;
+
+/// Lays out daily study minutes as a calendar matrix of weeks starting on
+/// `config.week_start`
+///
+/// `days` must be consecutive dates in order. Slots before the first day and after the last
+/// one are `None`, so every week has exactly seven entries.
+pub fn build_heatmap(days: &[(NaiveDate, f64)], config: &PeriodConfig) -> Result<StudyHeatmap> {
+    let weekdays = std::iter::successors(Some(config.week_start), |day| Some(day.succ()))
+        .take(7)
+        .map(|day| day.to_string())
+        .collect();
+    let (Some(&(start, _)), Some(&(end, _))) = (days.first(), days.last()) else {
+        return Ok(StudyHeatmap {
+            start_date: None,
+            end_date: None,
+            total_minutes: 0.0,
+            max_minutes: 0.0,
+            days_studied: 0,
+            weekdays,
+            days: Vec::new(),
+            weeks: Vec::new(),
+        });
+    };
+
+    let minutes: HashMap<NaiveDate, f64> = days.iter().copied().collect();
+    let matrix = calendar::build_matrix_for_range(&minutes, start, end, config)?;
+
+    let heatmap_day = |cell: &CalendarCell| HeatmapDay {
+        date: cell.date.to_string(),
+        minutes: cell.value,
+        level: cell.level,
+    };
+
+    Ok(StudyHeatmap {
+        start_date: Some(start.to_string()),
+        end_date: Some(end.to_string()),
+        total_minutes: matrix.total,
+        max_minutes: matrix.max,
+        days_studied: matrix.active_days,
+        weekdays,
+        days: matrix.cells().map(heatmap_day).collect(),
+        weeks: matrix
+            .weeks
+            .iter()
+            .map(|week| HeatmapWeek {
+                week_start: week.week_start.to_string(),
+                days: week
+                    .days
+                    .iter()
+                    .map(|day| day.as_ref().map(heatmap_day))
+                    .collect(),
+            })
+            .collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Weekday;
+    use statsutils::StatsTimezone;
+    use statsutils::calendar::intensity_level;
+
+    fn sunday_weeks() -> PeriodConfig {
+        PeriodConfig::new(
+            StatsTimezone::Fixed(chrono::FixedOffset::east_opt(0).unwrap()),
+            4,
+        )
+        .with_week_start(Weekday::Sun)
+    }
+
+    fn date(day: u32) -> NaiveDate {
+        // 2025-06-01 is a Sunday
+        NaiveDate::from_ymd_opt(2025, 6, day).unwrap()
+    }
+
+    #[test]
+    fn test_heatmap_weeks() {
+        // Wednesday June 4 through Monday June 9
+        let days: Vec<(NaiveDate, f64)> = (4..=9).map(|d| (date(d), d as f64)).collect();
+        let heatmap = build_heatmap(&days, &sunday_weeks()).unwrap();
+
+        assert_eq!(heatmap.weeks.len(), 2);
+        assert_eq!(heatmap.weeks[0].week_start, "2025-06-01");
+        assert!(heatmap.weeks[0].days[..3].iter().all(Option::is_none));
+        assert_eq!(
+            heatmap.weeks[0].days[3].as_ref().map(|d| d.date.as_str()),
+            Some("2025-06-04")
+        );
+        assert_eq!(heatmap.weeks[1].week_start, "2025-06-08");
+        assert!(heatmap.weeks[1].days[2..].iter().all(Option::is_none));
+        assert_eq!(heatmap.max_minutes, 9.0);
+        assert_eq!(heatmap.days_studied, 6);
+        assert_eq!(
+            heatmap.weekdays,
+            ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"]
+        );
+    }
+
+    #[test]
+    fn test_heatmap_weekdays_follow_week_start() {
+        let config = sunday_weeks().with_week_start(Weekday::Mon);
+        let days: Vec<(NaiveDate, f64)> = (2..=8).map(|d| (date(d), 1.0)).collect();
+        let heatmap = build_heatmap(&days, &config).unwrap();
+
+        assert_eq!(heatmap.weekdays[0], "Mon");
+        assert_eq!(heatmap.weekdays[6], "Sun");
+        // June 2 is a Monday, so it fills the first slot
+        assert_eq!(
+            heatmap.weeks[0].days[0].as_ref().map(|d| d.date.as_str()),
+            Some("2025-06-02")
+        );
+    }
+
+    #[test]
+    fn test_intensity_levels() {
+        assert_eq!(intensity_level(0.0, 60.0), 0);
+        assert_eq!(intensity_level(1.0, 60.0), 1);
+        assert_eq!(intensity_level(30.0, 60.0), 2);
+        assert_eq!(intensity_level(60.0, 60.0), 4);
+        assert_eq!(intensity_level(5.0, 0.0), 0);
+    }
+
+    #[test]
+    fn test_empty_heatmap() {
+        let heatmap = build_heatmap(&[], &sunday_weeks()).unwrap();
+        assert!(heatmap.weeks.is_empty());
+        assert_eq!(heatmap.start_date, None);
+    }
+}
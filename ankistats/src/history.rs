@@ -0,0 +1,110 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use chrono::{Duration, NaiveDate};
+use statsutils::PeriodConfig;
+
+use crate::milestones::CardReview;
+use crate::models::CumulativePoint;
+
+/// Replays the whole review history into a daily count of mature passages
+///
+/// A passage is mature while both of its cards have an interval of at least 21 days, the same
+/// rule the book stats use, so the final point matches the current mature passage count
+/// (ignoring suspensions). There is one point per day from the first review through `today`,
+/// including days without reviews, so the result can be charted directly.
+pub fn cumulative_history(
+    reviews: &[CardReview],
+    config: &PeriodConfig,
+    today: NaiveDate,
+) -> Result<Vec<CumulativePoint>> {
+    let Some(first) = reviews.first() else {
+        return Ok(Vec::new());
+    };
+
+    // Net change in mature passages per day: (matured, lost)
+    let mut changes: HashMap<NaiveDate, (i64, i64)> = HashMap::new();
+    let mut intervals: HashMap<i64, [i64; 2]> = HashMap::new();
+
+    for review in reviews {
+        let Ok(ord @ 0..=1) = usize::try_from(review.ord) else {
+            continue;
+        };
+        let card_intervals = intervals.entry(review.note_id).or_insert([0, 0]);
+        let was_mature = card_intervals.iter().all(|&ivl| ivl >= 21);
+        card_intervals[ord] = review.interval;
+        let is_mature = card_intervals.iter().all(|&ivl| ivl >= 21);
+
+        if was_mature != is_mature {
+            let date = config.logical_date(review.timestamp_ms)?;
+            let (matured, lost) = changes.entry(date).or_default();
+            if is_mature {
+                *matured += 1;
+            } else {
+                *lost += 1;
+            }
+        }
+    }
+
+    let mut points = Vec::new();
+    let mut mature_passages = 0;
+    let mut date = config.logical_date(first.timestamp_ms)?;
+
+    while date <= today {
+        let (matured, lost) = changes.get(&date).copied().unwrap_or_default();
+        mature_passages += matured - lost;
+        points.push(CumulativePoint {
+            date: date.to_string(),
+            mature_passages,
+            matured_passages: matured,
+            lost_passages: lost,
+        });
+        date += Duration::days(1);
+    }
+
+    Ok(points)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY_MS: i64 = 86_400_000;
+
+    fn review(day: i64, note_id: i64, ord: i64, interval: i64) -> CardReview {
+        CardReview {
+            timestamp_ms: 1_700_000_000_000 + day * DAY_MS,
+            note_id,
+            ord,
+            interval,
+        }
+    }
+
+    #[test]
+    fn test_cumulative_history() {
+        let config = PeriodConfig::default();
+        let reviews = [
+            review(0, 1, 0, 25),
+            review(0, 1, 1, 25),
+            review(1, 2, 0, 30),
+            review(2, 2, 1, 30),
+            // Note 1 lapses on day 3
+            review(3, 1, 1, -600),
+        ];
+        let first_day = config.logical_date(reviews[0].timestamp_ms).unwrap();
+        let today = first_day + Duration::days(4);
+
+        let points = cumulative_history(&reviews, &config, today).unwrap();
+        let mature: Vec<i64> = points.iter().map(|p| p.mature_passages).collect();
+        assert_eq!(mature, [1, 1, 2, 1, 1]);
+        assert_eq!(points[3].lost_passages, 1);
+        assert_eq!(points[0].date, first_day.to_string());
+    }
+
+    #[test]
+    fn test_cumulative_history_empty() {
+        let today = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        let points = cumulative_history(&[], &PeriodConfig::default(), today).unwrap();
+        assert!(points.is_empty());
+    }
+}
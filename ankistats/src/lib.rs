@@ -1,86 +1,522 @@
+pub mod anki_connect;
 pub mod bible;
 pub mod book_name_parser;
+pub mod cache;
+pub mod config;
+pub mod consistency;
 pub mod db;
+pub mod discovery;
+pub mod distribution;
+pub mod goals;
+pub mod heatmap;
+pub mod history;
+pub mod lint;
+pub mod milestones;
 pub mod models;
+#[cfg(feature = "tokio")]
+pub mod nonblocking;
+pub mod package;
+pub mod problems;
+pub mod projection;
+pub mod sections;
+pub mod stats_backend;
+pub mod streaks;
+pub mod suggestions;
 pub mod verse_parser;
 
-use anyhow::Result;
+use anyhow::{Context, Result, bail};
+use chrono::{Datelike, NaiveDate};
+use statsutils::{DatePeriod, Granularity, PeriodConfig, parse_local_date};
 
-use crate::bible::{NEW_TESTAMENT, OLD_TESTAMENT};
-use crate::models::{BibleStats, DayStats, WeekStats};
+use crate::models::{
+    BibleStats, BookStats, CompletionProjection, CumulativePoint, DayStats, DueForecast, Goal,
+    GoalProgress, IntervalDistribution, Milestone, MonthStats, PassageDetail, PassageOverlap,
+    PeriodComparison, PeriodStats, ProblemPassage, ReferenceIssue, RetentionStats, ReviewEvent,
+    ReviewSuggestions, SectionProgress, StudyHeatmap, StudyStreaks, WeekStats,
+};
 
 /// Retrieves statistics for all Bible books from an Anki database
 pub fn get_bible_stats(db_path: &str) -> Result<BibleStats> {
+    get_bible_stats_with_tag(db_path, None)
+}
+
+/// Retrieves statistics for all Bible books, counting only notes tagged with `tag`
+/// (e.g. "memorize::current") when one is given
+///
+/// Child tags match too, so "memorize" includes "memorize::current".
+pub fn get_bible_stats_with_tag(db_path: &str, tag: Option<&str>) -> Result<BibleStats> {
     let conn = db::open_database(db_path)?;
     let deck_id = db::get_deck_id(&conn)?;
     let model_id = db::get_model_id(&conn)?;
 
     // Get all book stats in a single query
-    let books_map = db::get_all_books_stats(&conn, deck_id, model_id)?;
-
-    let mut stats = BibleStats::new();
-
-    // Get Old Testament stats - lookup from HashMap or create zero-filled stats
-    for &book in OLD_TESTAMENT {
-        let book_stats = books_map
-            .get(book)
-            .cloned()
-            .unwrap_or_else(|| models::BookStats {
-                book: book.to_string(),
-                mature_passages: 0,
-                young_passages: 0,
-                learning_passages: 0,
-                unseen_passages: 0,
-                suspended_passages: 0,
-                mature_verses: 0,
-                young_verses: 0,
-                learning_verses: 0,
-                unseen_verses: 0,
-                suspended_verses: 0,
-            });
-        stats.old_testament.add_book(book_stats);
-    }
+    let books_map = db::get_all_books_stats(&conn, deck_id, model_id, tag)?;
 
-    // Get New Testament stats - lookup from HashMap or create zero-filled stats
-    for &book in NEW_TESTAMENT {
-        let book_stats = books_map
-            .get(book)
-            .cloned()
-            .unwrap_or_else(|| models::BookStats {
-                book: book.to_string(),
-                mature_passages: 0,
-                young_passages: 0,
-                learning_passages: 0,
-                unseen_passages: 0,
-                suspended_passages: 0,
-                mature_verses: 0,
-                young_verses: 0,
-                learning_verses: 0,
-                unseen_verses: 0,
-                suspended_verses: 0,
-            });
-        stats.new_testament.add_book(book_stats);
-    }
+    Ok(BibleStats::from_books(&books_map))
+}
 
-    Ok(stats)
+/// Gets the timezone and rollover hour Anki uses to split days for this collection
+///
+/// Use this to build a `DatePeriod` whose boundaries match the ones Anki shows.
+pub fn get_period_config(db_path: &str) -> Result<PeriodConfig> {
+    let conn = db::open_database(db_path)?;
+    db::get_period_config(&conn)
 }
 
 /// Gets the total study time for today in minutes
 pub fn get_today_study_time(db_path: &str) -> Result<f64> {
     let conn = db::open_database(db_path)?;
-    db::get_today_study_minutes(&conn)
+    db::get_today_study_minutes(&conn, &db::get_period_config(&conn)?)
+}
+
+/// Like `get_today_study_time`, with today's boundaries from `period_config` rather than
+/// the collection's own timezone and rollover hour
+pub fn get_today_study_time_with_config(
+    db_path: &str,
+    period_config: &PeriodConfig,
+) -> Result<f64> {
+    let conn = db::open_database_with_config(db_path, period_config)?;
+    db::get_today_study_minutes(&conn, period_config)
 }
 
 /// Gets study time and learning progress for each of the last 30 days
 pub fn get_last_30_days_stats(db_path: &str) -> Result<Vec<DayStats>> {
+    get_last_30_days_stats_with_tag(db_path, None)
+}
+
+/// Gets daily stats for the last 30 days, counting only reviews of notes tagged with `tag`
+/// when one is given
+pub fn get_last_30_days_stats_with_tag(db_path: &str, tag: Option<&str>) -> Result<Vec<DayStats>> {
     let conn = db::open_database(db_path)?;
-    db::get_last_30_days_stats(&conn)
+    db::get_last_30_days_stats(&conn, &db::get_period_config(&conn)?, tag)
+}
+
+/// Like `get_last_30_days_stats`, splitting days at `period_config`'s boundaries rather than
+/// the collection's own, so the dates line up with other sources read with the same config
+pub fn get_last_30_days_stats_with_config(
+    db_path: &str,
+    period_config: &PeriodConfig,
+) -> Result<Vec<DayStats>> {
+    let conn = db::open_database_with_config(db_path, period_config)?;
+    db::get_last_30_days_stats(&conn, period_config, None)
 }
 
 /// Gets study time and learning progress for each of the last 12 weeks
 pub fn get_last_12_weeks_stats(db_path: &str) -> Result<Vec<WeekStats>> {
+    get_last_12_weeks_stats_with_tag(db_path, None)
+}
+
+/// Gets weekly stats for the last 12 weeks, counting only reviews of notes tagged with `tag`
+/// when one is given
+pub fn get_last_12_weeks_stats_with_tag(
+    db_path: &str,
+    tag: Option<&str>,
+) -> Result<Vec<WeekStats>> {
+    let conn = db::open_database(db_path)?;
+    db::get_last_12_weeks_stats(&conn, &db::get_period_config(&conn)?, tag)
+}
+
+/// Like `get_last_12_weeks_stats`, splitting weeks at `period_config`'s boundaries rather
+/// than the collection's own
+pub fn get_last_12_weeks_stats_with_config(
+    db_path: &str,
+    period_config: &PeriodConfig,
+) -> Result<Vec<WeekStats>> {
+    let conn = db::open_database_with_config(db_path, period_config)?;
+    db::get_last_12_weeks_stats(&conn, period_config, None)
+}
+
+/// Gets study time and learning progress for each of the last 12 months
+pub fn get_last_12_months_stats(db_path: &str) -> Result<Vec<MonthStats>> {
+    let conn = db::open_database(db_path)?;
+    db::get_last_12_months_stats(&conn)
+}
+
+/// Gets study time and learning progress for each month of the given calendar year
+pub fn get_yearly_stats(db_path: &str, year: i32) -> Result<Vec<MonthStats>> {
+    let conn = db::open_database(db_path)?;
+    db::get_yearly_stats(&conn, year)
+}
+
+/// Gets study time and learning progress for an arbitrary date range
+///
+/// `start_date` and `end_date` are inclusive YYYY-MM-DD dates. The results contain one
+/// entry per day, week, or month depending on `granularity`, with zeros for empty buckets.
+pub fn get_stats_for_range(
+    db_path: &str,
+    start_date: &str,
+    end_date: &str,
+    granularity: Granularity,
+) -> Result<Vec<PeriodStats>> {
+    let start = parse_local_date(start_date).context("Invalid start date")?;
+    let end = parse_local_date(end_date).context("Invalid end date")?;
+
+    let conn = db::open_database(db_path)?;
+    let period_config = db::get_period_config(&conn)?;
+    let period = DatePeriod::for_range_with_config(start, end, granularity, &period_config)?;
+    db::get_stats_for_period(&conn, period, granularity)
+}
+
+/// Gets study time and learning progress for each bucket of a period, at the period's
+/// granularity
+///
+/// The period's buckets are kept, but their boundaries follow the collection's own timezone
+/// and rollover hour, as in `get_stats_for_range`.
+pub fn get_stats_for_period(db_path: &str, period: &DatePeriod) -> Result<Vec<PeriodStats>> {
+    let buckets = period.buckets()?;
+    let (Some(first), Some(last)) = (buckets.first(), buckets.last()) else {
+        return Ok(Vec::new());
+    };
+
+    let conn = db::open_database(db_path)?;
+    let period_config = db::get_period_config(&conn)?;
+    let anki_period = DatePeriod::for_range_with_config(
+        first.start,
+        last.end,
+        period.granularity,
+        &period_config,
+    )?;
+    db::get_stats_for_period(&conn, anki_period, period.granularity)
+}
+
+/// Like `get_stats_for_period`, keeping the period's own boundaries, which `period_config`
+/// built, instead of moving them to the collection's timezone and rollover hour
+pub fn get_stats_for_period_with_config(
+    db_path: &str,
+    period: &DatePeriod,
+    period_config: &PeriodConfig,
+) -> Result<Vec<PeriodStats>> {
+    let conn = db::open_database_with_config(db_path, period_config)?;
+    db::get_stats_for_period(&conn, period.clone(), period.granularity)
+}
+
+/// Compares study minutes, reviews, matured passages, and retention between two periods
+///
+/// Deltas are `period_a` minus `period_b`, so pass the recent window first
+/// (e.g. this month, last month) to see whether things are improving.
+pub fn compare_periods(
+    db_path: &str,
+    period_a: &DatePeriod,
+    period_b: &DatePeriod,
+) -> Result<PeriodComparison> {
+    let conn = db::open_database(db_path)?;
+    let totals_a = db::get_period_totals(&conn, period_a)?;
+    let totals_b = db::get_period_totals(&conn, period_b)?;
+    Ok(PeriodComparison::new(totals_a, totals_b))
+}
+
+/// Gets study minutes for each of the last `days` days (ending today) as a calendar
+/// heatmap, with weeks starting on the workspace's week start
+pub fn get_study_heatmap(db_path: &str, days: u32) -> Result<StudyHeatmap> {
+    let conn = db::open_database(db_path)?;
+    let period_config = db::get_period_config(&conn)?;
+    let today = period_config.today()?;
+    let start = today - chrono::Duration::days(days.max(1) as i64 - 1);
+
+    let period = DatePeriod::for_range_with_config(start, today, Granularity::Day, &period_config)?;
+    let daily = db::get_stats_for_period(&conn, period, Granularity::Day)?;
+
+    let minutes: Vec<(NaiveDate, f64)> = daily
+        .into_iter()
+        .map(|day| {
+            NaiveDate::parse_from_str(&day.period_start, "%Y-%m-%d")
+                .map(|date| (date, day.minutes))
+                .context(format!("Invalid date '{}'", day.period_start))
+        })
+        .collect::<Result<_>>()?;
+
+    heatmap::build_heatmap(&minutes, &period_config)
+}
+
+/// Gets how many passages and verses come due on each of the next `days` days,
+/// along with the references due today
+pub fn get_due_forecast(db_path: &str, days: u32) -> Result<DueForecast> {
+    let conn = db::open_database(db_path)?;
+    db::get_due_forecast(&conn, days)
+}
+
+/// Gets review pass rates by card maturity and the answer button distribution for a period
+pub fn get_retention_stats(db_path: &str, period: &DatePeriod) -> Result<RetentionStats> {
+    let conn = db::open_database(db_path)?;
+    db::get_retention_stats(&conn, period)
+}
+
+/// Gets the current and longest study streaks and the total number of days ever studied
+pub fn get_streaks(db_path: &str) -> Result<StudyStreaks> {
+    let conn = db::open_database(db_path)?;
+    let study_dates = db::get_study_dates(&conn)?;
+    Ok(streaks::compute_streaks(
+        &study_dates,
+        db::get_period_config(&conn)?.today()?,
+    ))
+}
+
+/// Gets every day the Bible deck was studied, oldest first
+pub fn get_study_dates(db_path: &str) -> Result<Vec<NaiveDate>> {
+    let conn = db::open_database(db_path)?;
+    db::get_study_dates(&conn)
+}
+
+/// Like `get_study_dates`, dating each review by `period_config`'s day boundaries
+pub fn get_study_dates_with_config(
+    db_path: &str,
+    period_config: &PeriodConfig,
+) -> Result<Vec<NaiveDate>> {
+    let conn = db::open_database_with_config(db_path, period_config)?;
+    db::get_study_dates(&conn)
+}
+
+/// Gets the collection's schema version, as recorded by Anki
+pub fn get_schema_version(db_path: &str) -> Result<i64> {
+    let conn = db::open_database(db_path)?;
+    db::get_schema_version(&conn)
+}
+
+/// Gets scheduling details (state, interval, ease, lapses, due date, last review)
+/// for every passage, sorted by reference
+pub fn get_passage_details(db_path: &str) -> Result<Vec<PassageDetail>> {
+    let conn = db::open_database(db_path)?;
+    let deck_id = db::get_deck_id(&conn)?;
+    let model_id = db::get_model_id(&conn)?;
+    db::get_passage_details(&conn, deck_id, model_id)
+}
+
+/// Gets the passages due soonest and the unmatured passages closest to maturing
+pub fn get_review_suggestions(db_path: &str) -> Result<ReviewSuggestions> {
+    get_review_suggestions_with_limit(db_path, suggestions::DEFAULT_SUGGESTION_LIMIT)
+}
+
+/// Gets up to `limit` passages due soonest and up to `limit` closest to maturing
+pub fn get_review_suggestions_with_limit(db_path: &str, limit: usize) -> Result<ReviewSuggestions> {
+    let conn = db::open_database(db_path)?;
+    let deck_id = db::get_deck_id(&conn)?;
+    let model_id = db::get_model_id(&conn)?;
+
+    let details = db::get_passage_details(&conn, deck_id, model_id)?;
+    let today = db::get_period_config(&conn)?.today()?;
+    Ok(suggestions::suggest_reviews(&details, today, limit))
+}
+
+/// Gets passages with many lapses, low ease, or repeated recent maturity loss,
+/// most troubled first
+pub fn get_problem_passages(db_path: &str, lapse_threshold: i64) -> Result<Vec<ProblemPassage>> {
+    let conn = db::open_database(db_path)?;
+    let deck_id = db::get_deck_id(&conn)?;
+    let model_id = db::get_model_id(&conn)?;
+
+    let details = db::get_passage_details(&conn, deck_id, model_id)?;
+    let today_start_ms =
+        statsutils::get_today_start_ms_with_config(&db::get_period_config(&conn)?)?;
+    let since_ms = today_start_ms - problems::LOOKBACK_WEEKS * 7 * 86_400_000;
+    let losses = db::get_maturity_losses(&conn, deck_id, model_id, since_ms)?;
+
+    Ok(problems::find_problem_passages(
+        &details,
+        &losses,
+        lapse_threshold,
+    ))
+}
+
+/// Gets histograms of current card intervals and ease factors for the Bible deck
+pub fn get_interval_distribution(db_path: &str) -> Result<IntervalDistribution> {
     let conn = db::open_database(db_path)?;
-    db::get_last_12_weeks_stats(&conn)
+    let deck_id = db::get_deck_id(&conn)?;
+    let model_id = db::get_model_id(&conn)?;
+    let cards = db::get_card_intervals(&conn, deck_id, model_id)?;
+    Ok(distribution::build_distribution(&cards))
+}
+
+/// Resolves a book name or abbreviation to its canonical name
+fn resolve_book(book: &str) -> Result<String> {
+    let book = book_name_parser::normalize_book_name(book);
+    if !bible::all_books().any(|name| name == book) {
+        bail!("'{}' is not a book of the Bible", book);
+    }
+    Ok(book)
+}
+
+/// Gets study time and learning progress for each of the last `weeks` weeks, counting
+/// only passages from `book` (given by name or abbreviation)
+pub fn get_book_weekly_trends(db_path: &str, book: &str, weeks: i64) -> Result<Vec<WeekStats>> {
+    let book = resolve_book(book)?;
+    let conn = db::open_database(db_path)?;
+    db::get_book_weekly_stats(&conn, &book, weeks)
+}
+
+/// Gets progress through each `section_size`-verse section of a chapter, so progress
+/// inside very long chapters like Psalm 119 is visible
+pub fn get_section_progress(
+    db_path: &str,
+    book: &str,
+    chapter: i64,
+    section_size: i64,
+) -> Result<Vec<SectionProgress>> {
+    let book = resolve_book(book)?;
+    let Some(chapter_length) = bible::chapter_verse_count(&book, chapter) else {
+        bail!("{} has no chapter {}", book, chapter);
+    };
+    if section_size < 1 {
+        bail!("Section size must be at least 1 verse");
+    }
+
+    let conn = db::open_database(db_path)?;
+    let deck_id = db::get_deck_id(&conn)?;
+    let model_id = db::get_model_id(&conn)?;
+    let details = db::get_passage_details(&conn, deck_id, model_id)?;
+
+    Ok(sections::section_progress(
+        &book,
+        chapter,
+        chapter_length,
+        section_size,
+        &details,
+    ))
+}
+
+/// Projects when every remaining passage in `book` will be mature, using the net
+/// maturation rate of the last 12 weeks
+pub fn get_completion_projection(db_path: &str, book: &str) -> Result<CompletionProjection> {
+    get_completion_projection_with_weeks(db_path, book, projection::DEFAULT_RATE_WEEKS)
+}
+
+/// Projects when every remaining passage in `book` will be mature, measuring the
+/// maturation rate over the last `weeks` weeks
+///
+/// The book may be given by name or abbreviation ("James", "Jas").
+pub fn get_completion_projection_with_weeks(
+    db_path: &str,
+    book: &str,
+    weeks: i64,
+) -> Result<CompletionProjection> {
+    let book = resolve_book(book)?;
+
+    let conn = db::open_database(db_path)?;
+    let deck_id = db::get_deck_id(&conn)?;
+    let model_id = db::get_model_id(&conn)?;
+
+    let books_map = db::get_all_books_stats(&conn, deck_id, model_id, None)?;
+    let stats = books_map
+        .get(&book)
+        .cloned()
+        .unwrap_or_else(|| BookStats::empty(&book));
+
+    let period_config = db::get_period_config(&conn)?;
+    let today_start_ms = statsutils::get_today_start_ms_with_config(&period_config)?;
+    let since_ms = today_start_ms - weeks * 7 * 86_400_000;
+    let (matured, lost) = db::get_book_maturation(&conn, deck_id, model_id, &book, since_ms)?;
+
+    Ok(projection::project_completion(
+        &stats,
+        matured,
+        lost,
+        weeks,
+        period_config.today()?,
+    ))
+}
+
+/// Measures each goal against the collection: new verses this month, study minutes,
+/// and mature passages with the net maturation rate of the last 12 weeks
+pub fn evaluate_goals(db_path: &str, goals: &[Goal]) -> Result<Vec<GoalProgress>> {
+    let conn = db::open_database(db_path)?;
+    let deck_id = db::get_deck_id(&conn)?;
+    let model_id = db::get_model_id(&conn)?;
+    let period_config = db::get_period_config(&conn)?;
+    let today = period_config.today()?;
+
+    let month_start = today.with_day(1).context("Invalid month start")?;
+    let month =
+        DatePeriod::for_range_with_config(month_start, today, Granularity::Day, &period_config)?;
+    let verses_this_month =
+        db::get_new_verses(&conn, deck_id, model_id, month.start_ms, month.end_ms)?;
+
+    let week = DatePeriod::for_range_with_config(
+        today - chrono::Duration::days(6),
+        today,
+        Granularity::Day,
+        &period_config,
+    )?;
+    let week_minutes = db::get_period_totals(&conn, &week)?.minutes;
+
+    let rate_weeks = projection::DEFAULT_RATE_WEEKS;
+    let recent = DatePeriod::for_range_with_config(
+        today - chrono::Duration::days(rate_weeks * 7 - 1),
+        today,
+        Granularity::Day,
+        &period_config,
+    )?;
+    let recent_totals = db::get_period_totals(&conn, &recent)?;
+
+    let books_map = db::get_all_books_stats(&conn, deck_id, model_id, None)?;
+    let mature_passages = BibleStats::from_books(&books_map).total_mature_passages();
+
+    let inputs = goals::GoalInputs {
+        verses_this_month,
+        minutes_today: db::get_today_study_minutes(&conn, &period_config)?,
+        average_minutes_7_days: week_minutes / 7.0,
+        mature_passages,
+        matured_per_week: (recent_totals.matured_passages - recent_totals.lost_passages) as f64
+            / rate_weeks as f64,
+    };
+
+    goals
+        .iter()
+        .map(|goal| goals::evaluate_goal(goal, &inputs, today))
+        .collect()
+}
+
+/// Gets the number of mature passages at the end of every day since the first review
+///
+/// Unlike `cumulative_passages` in the daily/weekly stats, which starts at zero at the
+/// beginning of the queried window, this replays the whole review history.
+pub fn get_cumulative_history(db_path: &str) -> Result<Vec<CumulativePoint>> {
+    let conn = db::open_database(db_path)?;
+    let deck_id = db::get_deck_id(&conn)?;
+    let model_id = db::get_model_id(&conn)?;
+
+    let reviews = db::get_card_reviews(&conn, deck_id, model_id)?;
+    let period_config = db::get_period_config(&conn)?;
+    history::cumulative_history(&reviews, &period_config, period_config.today()?)
+}
+
+/// Gets milestones reached so far (books started, mature passage counts, chapters and
+/// books fully mature), oldest first, each dated from the review history
+pub fn get_milestones(db_path: &str) -> Result<Vec<Milestone>> {
+    let conn = db::open_database(db_path)?;
+    let deck_id = db::get_deck_id(&conn)?;
+    let model_id = db::get_model_id(&conn)?;
+
+    let references = db::get_note_references(&conn, deck_id, model_id)?;
+    let reviews = db::get_card_reviews(&conn, deck_id, model_id)?;
+    milestones::detect_milestones(&references, &reviews, &db::get_period_config(&conn)?)
+}
+
+/// Finds notes whose reference can't be parsed, names an unknown book, or points at a
+/// chapter or verse that doesn't exist, sorted by note ID
+pub fn validate_references(db_path: &str) -> Result<Vec<ReferenceIssue>> {
+    let conn = db::open_database(db_path)?;
+    let deck_id = db::get_deck_id(&conn)?;
+    let model_id = db::get_model_id(&conn)?;
+    let references = db::get_note_references(&conn, deck_id, model_id)?;
+    Ok(lint::validate_references(&references))
+}
+
+/// Finds pairs of notes whose verses overlap or are identical, e.g. "Romans 8:1-4" and
+/// "Romans 8:3-5"
+pub fn find_overlapping_passages(db_path: &str) -> Result<Vec<PassageOverlap>> {
+    let conn = db::open_database(db_path)?;
+    let deck_id = db::get_deck_id(&conn)?;
+    let model_id = db::get_model_id(&conn)?;
+    let references = db::get_note_references(&conn, deck_id, model_id)?;
+    Ok(lint::find_overlaps(&references))
+}
+
+/// Gets every review of a Bible Verse card within a period, oldest first, for analysis
+/// outside ankistats
+pub fn export_review_log(db_path: &str, period: &DatePeriod) -> Result<Vec<ReviewEvent>> {
+    let conn = db::open_database(db_path)?;
+    let deck_id = db::get_deck_id(&conn)?;
+    let model_id = db::get_model_id(&conn)?;
+    db::get_review_log(&conn, deck_id, model_id, period)
 }
 
 /// Gets all Bible references from the database, sorted alphabetically
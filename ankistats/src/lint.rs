@@ -0,0 +1,191 @@
+use std::collections::{BTreeSet, HashMap};
+
+use crate::bible;
+use crate::book_name_parser::try_parse_book_name;
+use crate::models::{PassageOverlap, ReferenceIssue, ReferenceIssueKind};
+use crate::verse_parser::parse_reference;
+
+/// Checks a single reference, returning what is wrong with it, if anything
+pub fn check_reference(reference: &str) -> Option<(ReferenceIssueKind, String)> {
+    let book = match try_parse_book_name(reference) {
+        Ok(book) => book,
+        Err(err) => return Some((ReferenceIssueKind::Unparsable, err)),
+    };
+    if !bible::all_books().any(|name| name == book) {
+        return Some((
+            ReferenceIssueKind::UnknownBook,
+            format!("Unknown book '{}'", book),
+        ));
+    }
+
+    let parsed = match parse_reference(reference) {
+        Ok(parsed) => parsed,
+        Err(err) => return Some((ReferenceIssueKind::Unparsable, err)),
+    };
+
+    // Same-chapter ranges are counted without looking up the chapter, so check every
+    // endpoint against the chapter lengths
+    for range in &parsed.ranges {
+        for (chapter, verse) in [
+            (range.start_chapter, range.start_verse),
+            (range.end_chapter, range.end_verse),
+        ] {
+            let Some(chapter_length) = bible::chapter_verse_count(&parsed.book, chapter) else {
+                return Some((
+                    ReferenceIssueKind::InvalidRange,
+                    format!("{} has no chapter {}", parsed.book, chapter),
+                ));
+            };
+            if verse < 1 || verse > chapter_length {
+                return Some((
+                    ReferenceIssueKind::InvalidRange,
+                    format!(
+                        "{} {} has {} verses, not {}",
+                        parsed.book, chapter, chapter_length, verse
+                    ),
+                ));
+            }
+        }
+    }
+
+    None
+}
+
+/// Checks every note's reference, returning the problems sorted by note ID
+pub fn validate_references(references: &HashMap<i64, String>) -> Vec<ReferenceIssue> {
+    let mut issues: Vec<ReferenceIssue> = references
+        .iter()
+        .filter_map(|(&note_id, reference)| {
+            check_reference(reference).map(|(kind, message)| ReferenceIssue {
+                note_id,
+                reference: reference.clone(),
+                kind,
+                message,
+            })
+        })
+        .collect();
+    issues.sort_by_key(|issue| issue.note_id);
+    issues
+}
+
+/// A valid note and the verses it covers
+struct CoveredNote<'a> {
+    note_id: i64,
+    reference: &'a str,
+    verses: BTreeSet<(i64, i64)>,
+}
+
+/// Finds pairs of notes in the same book that share at least one verse, ordered by book
+/// and note IDs
+///
+/// References that fail `check_reference` are skipped; the lint reports those.
+pub fn find_overlaps(references: &HashMap<i64, String>) -> Vec<PassageOverlap> {
+    let mut by_book: HashMap<String, Vec<CoveredNote>> = HashMap::new();
+    for (&note_id, reference) in references {
+        if check_reference(reference).is_some() {
+            continue;
+        }
+        let Ok(parsed) = parse_reference(reference) else {
+            continue;
+        };
+        if let Some(verses) = parsed.verses() {
+            by_book.entry(parsed.book).or_default().push(CoveredNote {
+                note_id,
+                reference,
+                verses,
+            });
+        }
+    }
+
+    let mut overlaps = Vec::new();
+    for (book, mut notes) in by_book {
+        notes.sort_by_key(|note| note.note_id);
+        for (i, a) in notes.iter().enumerate() {
+            for b in &notes[i + 1..] {
+                let shared_verses = a.verses.intersection(&b.verses).count() as i64;
+                if shared_verses > 0 {
+                    overlaps.push(PassageOverlap {
+                        book: book.clone(),
+                        note_id_a: a.note_id,
+                        reference_a: a.reference.to_string(),
+                        note_id_b: b.note_id,
+                        reference_b: b.reference.to_string(),
+                        shared_verses,
+                        duplicate: a.verses == b.verses,
+                    });
+                }
+            }
+        }
+    }
+
+    overlaps.sort_by(|a, b| {
+        (&a.book, a.note_id_a, a.note_id_b).cmp(&(&b.book, b.note_id_a, b.note_id_b))
+    });
+    overlaps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kind(reference: &str) -> Option<ReferenceIssueKind> {
+        check_reference(reference).map(|(kind, _)| kind)
+    }
+
+    #[test]
+    fn test_valid_references() {
+        assert_eq!(kind("John 3:16"), None);
+        assert_eq!(kind("Psalm 23"), None);
+        assert_eq!(kind("John 7:53–8:11"), None);
+        assert_eq!(kind("Jude 24-25"), None);
+        assert_eq!(kind("Romans 3:23, 6:23"), None);
+    }
+
+    #[test]
+    fn test_invalid_references() {
+        assert_eq!(kind("Memory verse"), Some(ReferenceIssueKind::Unparsable));
+        assert_eq!(kind("John 3:abc"), Some(ReferenceIssueKind::Unparsable));
+        assert_eq!(kind("Hezekiah 1:1"), Some(ReferenceIssueKind::UnknownBook));
+        assert_eq!(kind("John 3:99"), Some(ReferenceIssueKind::InvalidRange));
+        assert_eq!(kind("John 3:36-40"), Some(ReferenceIssueKind::InvalidRange));
+        assert_eq!(kind("Jude 30"), Some(ReferenceIssueKind::InvalidRange));
+        assert_eq!(kind("John 22:1"), Some(ReferenceIssueKind::InvalidRange));
+    }
+
+    #[test]
+    fn test_validate_references_sorted_by_note() {
+        let references = HashMap::from([
+            (3, "John 3:99".to_string()),
+            (1, "Hezekiah 1:1".to_string()),
+            (2, "John 3:16".to_string()),
+        ]);
+
+        let issues = validate_references(&references);
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0].note_id, 1);
+        assert_eq!(issues[1].note_id, 3);
+        assert_eq!(issues[1].message, "John 3 has 36 verses, not 99");
+    }
+
+    #[test]
+    fn test_find_overlaps() {
+        let references = HashMap::from([
+            (1, "Romans 8:1-4".to_string()),
+            (2, "Rom 8:3-5".to_string()),
+            (3, "Romans 8:28".to_string()),
+            (4, "John 3:16".to_string()),
+            (5, "Jn 3:16".to_string()),
+            (6, "John 3:35-4:2".to_string()),
+            (7, "John 4:1".to_string()),
+            (8, "John 3:99".to_string()),
+        ]);
+
+        let overlaps = find_overlaps(&references);
+        let pairs: Vec<(i64, i64, i64, bool)> = overlaps
+            .iter()
+            .map(|o| (o.note_id_a, o.note_id_b, o.shared_verses, o.duplicate))
+            .collect();
+        assert_eq!(pairs, [(4, 5, 1, true), (6, 7, 1, false), (1, 2, 2, false)]);
+        assert_eq!(overlaps[0].book, "John");
+    }
+}
@@ -1,9 +1,30 @@
-use ankistats::models::{BookStats, BookStatsDisplay};
+use ankistats::bible::{self, Canon};
+use ankistats::book_name_parser;
+use ankistats::config::{self, AnkiStatsConfig, OutlierHandling};
+use ankistats::discovery;
+use ankistats::goals;
+use ankistats::models::{
+    BookStats, BookStatsDisplay, DailyStats, DailySummary, HistogramBucket, MonthStats,
+    MonthlyStats, MonthlySummary, PassageDetailDisplay, RetentionBucket, ReviewEvent, StudyHeatmap,
+    TodayStats, WeeklyStats, WeeklySummary,
+};
+use ankistats::stats_backend::backend_for;
 use ankistats::{
-    get_bible_references, get_bible_stats, get_last_12_weeks_stats, get_last_30_days_stats,
-    get_today_study_time,
+    compare_periods, evaluate_goals, export_review_log, find_overlapping_passages,
+    get_book_weekly_trends, get_completion_projection_with_weeks, get_cumulative_history,
+    get_due_forecast, get_interval_distribution, get_last_12_months_stats,
+    get_last_12_weeks_stats_with_tag, get_last_30_days_stats_with_tag, get_milestones,
+    get_passage_details, get_period_config, get_problem_passages, get_retention_stats,
+    get_review_suggestions_with_limit, get_section_progress, get_stats_for_range, get_streaks,
+    get_study_heatmap, get_yearly_stats, validate_references,
 };
-use clap::{Parser, Subcommand};
+use anyhow::Context;
+use clap::{Parser, Subcommand, ValueEnum};
+use serde::Serialize;
+use statsutils::fmt::duration_minutes;
+use statsutils::{DatePeriod, Granularity};
+use std::fs::File;
+use std::io::{self, Write};
 use std::process;
 use tabled::{Table, settings::Style};
 
@@ -14,67 +35,456 @@ use tabled::{Table, settings::Style};
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+
+    /// Output format: human-readable text or JSON for scripts and dashboards
+    #[arg(long, global = true, value_enum, default_value = "text")]
+    format: OutputFormat,
+
+    /// File of extra book name aliases, one `alias = Book` per line (e.g. `Sal = Psalms`)
+    #[arg(long, global = true, value_name = "FILE")]
+    aliases: Option<String>,
+
+    /// Canon for book lists and Old/New Testament grouping: protestant, catholic (adds
+    /// the deuterocanonical books), or hebrew (Hebrew Bible order)
+    #[arg(long, global = true, default_value = "protestant")]
+    canon: Canon,
+
+    /// Count at most this many seconds of study time per review, like Anki's "Maximum
+    /// answer seconds", so a card left open doesn't inflate totals
+    #[arg(long, global = true, value_name = "SECONDS")]
+    max_answer_seconds: Option<i64>,
+
+    /// Leave reviews longer than --max-answer-seconds out of study time instead of
+    /// capping them
+    #[arg(long, global = true, requires = "max_answer_seconds")]
+    exclude_outliers: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    Text,
+    Json,
 }
 
 #[derive(Subcommand)]
 enum Commands {
     /// Show statistics for each Bible book
     Books {
-        /// Path to the Anki database file
+        /// Path to the Anki database file, or an AnkiConnect URL (e.g. http://127.0.0.1:8765);
+        /// defaults to the most recently used Anki profile
         #[arg(value_name = "DATABASE_PATH")]
-        db_path: String,
+        db_path: Option<String>,
+        /// Only count notes with this tag or its child tags (e.g. memorize::current)
+        #[arg(long)]
+        tag: Option<String>,
     },
     /// Show study time for today
     Today {
-        /// Path to the Anki database file
+        /// Path to the Anki database file, or an AnkiConnect URL (e.g. http://127.0.0.1:8765);
+        /// defaults to the most recently used Anki profile
         #[arg(value_name = "DATABASE_PATH")]
-        db_path: String,
+        db_path: Option<String>,
     },
     /// Show study time for each of the last 30 days
     Daily {
-        /// Path to the Anki database file
+        /// Path to the Anki database file (defaults to the most recently used Anki profile)
         #[arg(value_name = "DATABASE_PATH")]
-        db_path: String,
+        db_path: Option<String>,
+        /// Only count notes with this tag or its child tags (e.g. memorize::current)
+        #[arg(long)]
+        tag: Option<String>,
     },
     /// Show study time for each of the last 12 weeks
     Weekly {
-        /// Path to the Anki database file
+        /// Path to the Anki database file (defaults to the most recently used Anki profile)
+        #[arg(value_name = "DATABASE_PATH")]
+        db_path: Option<String>,
+        /// Only count notes with this tag or its child tags (e.g. memorize::current)
+        #[arg(long, conflicts_with = "book")]
+        tag: Option<String>,
+        /// Only count passages from this book (e.g. Philippians or Phil)
+        #[arg(long)]
+        book: Option<String>,
+        /// Number of weeks to show when filtering by book
+        #[arg(long, default_value_t = 12, requires = "book")]
+        weeks: i64,
+    },
+    /// Show study time for each of the last 12 months
+    Monthly {
+        /// Path to the Anki database file (defaults to the most recently used Anki profile)
+        #[arg(value_name = "DATABASE_PATH")]
+        db_path: Option<String>,
+    },
+    /// Show study time for each month of a calendar year
+    Yearly {
+        /// Calendar year to show (e.g. 2025)
+        year: i32,
+        /// Path to the Anki database file (defaults to the most recently used Anki profile)
+        #[arg(value_name = "DATABASE_PATH")]
+        db_path: Option<String>,
+    },
+    /// Show study time for a custom date range
+    Range {
+        /// First day of the range (YYYY-MM-DD)
+        start_date: String,
+        /// Last day of the range, inclusive (YYYY-MM-DD)
+        end_date: String,
+        /// Path to the Anki database file (defaults to the most recently used Anki profile)
+        #[arg(value_name = "DATABASE_PATH")]
+        db_path: Option<String>,
+        /// Bucket size: day, week, or month
+        #[arg(long, default_value = "day")]
+        granularity: Granularity,
+    },
+    /// Show how many passages come due over the next few days
+    Forecast {
+        /// Path to the Anki database file (defaults to the most recently used Anki profile)
+        #[arg(value_name = "DATABASE_PATH")]
+        db_path: Option<String>,
+        /// Number of days to forecast, starting with today
+        #[arg(long, default_value_t = 14)]
+        days: u32,
+    },
+    /// Show review retention and answer button statistics
+    Retention {
+        /// Path to the Anki database file (defaults to the most recently used Anki profile)
+        #[arg(value_name = "DATABASE_PATH")]
+        db_path: Option<String>,
+        /// Number of days to include, ending today
+        #[arg(long, default_value_t = 30)]
+        days: u32,
+    },
+    /// Export the review log as CSV (or JSON with --format json)
+    ExportReviews {
+        /// Path to the Anki database file (defaults to the most recently used Anki profile)
+        #[arg(value_name = "DATABASE_PATH")]
+        db_path: Option<String>,
+        /// Number of days to export, ending today
+        #[arg(long, default_value_t = 30)]
+        days: u32,
+        /// Write to this file instead of standard output
+        #[arg(long, short)]
+        output: Option<String>,
+    },
+    /// Show histograms of card intervals and ease factors
+    Intervals {
+        /// Path to the Anki database file (defaults to the most recently used Anki profile)
+        #[arg(value_name = "DATABASE_PATH")]
+        db_path: Option<String>,
+    },
+    /// Compare the last N days with the N days before
+    Compare {
+        /// Path to the Anki database file (defaults to the most recently used Anki profile)
+        #[arg(value_name = "DATABASE_PATH")]
+        db_path: Option<String>,
+        /// Length of each window in days, the recent one ending today
+        #[arg(long, default_value_t = 30)]
+        days: u32,
+    },
+    /// Show a GitHub-style grid of daily study time
+    Heatmap {
+        /// Path to the Anki database file (defaults to the most recently used Anki profile)
+        #[arg(value_name = "DATABASE_PATH")]
+        db_path: Option<String>,
+        /// Number of days to show, ending today
+        #[arg(long, default_value_t = 365)]
+        days: u32,
+    },
+    /// Show progress toward memorization goals
+    Goals {
+        /// TOML file listing goals as [[goals]] tables
+        #[arg(value_name = "GOALS_FILE")]
+        goals_path: String,
+        /// Path to the Anki database file (defaults to the most recently used Anki profile)
         #[arg(value_name = "DATABASE_PATH")]
-        db_path: String,
+        db_path: Option<String>,
+    },
+    /// Suggest which passages to review next
+    Suggest {
+        /// Path to the Anki database file (defaults to the most recently used Anki profile)
+        #[arg(value_name = "DATABASE_PATH")]
+        db_path: Option<String>,
+        /// Number of passages in each list
+        #[arg(long, default_value_t = 5)]
+        limit: usize,
+    },
+    /// Show current and longest study streaks
+    Streaks {
+        /// Path to the Anki database file (defaults to the most recently used Anki profile)
+        #[arg(value_name = "DATABASE_PATH")]
+        db_path: Option<String>,
+    },
+    /// Show scheduling details for every passage
+    Passages {
+        /// Path to the Anki database file (defaults to the most recently used Anki profile)
+        #[arg(value_name = "DATABASE_PATH")]
+        db_path: Option<String>,
+        /// Column to sort by
+        #[arg(long, value_enum, default_value_t = PassageSort::Reference)]
+        sort: PassageSort,
+        /// Reverse the sort order
+        #[arg(long)]
+        reverse: bool,
+    },
+    /// Show passages that are struggling (leeches, low ease, lost maturity)
+    Problems {
+        /// Path to the Anki database file (defaults to the most recently used Anki profile)
+        #[arg(value_name = "DATABASE_PATH")]
+        db_path: Option<String>,
+        /// Minimum number of lapses to flag a passage
+        #[arg(long, default_value_t = 8)]
+        lapses: i64,
+    },
+    /// Estimate when every passage in a book will be mature
+    Project {
+        /// Book name or abbreviation (e.g. James, 1 Cor)
+        book: String,
+        /// Path to the Anki database file (defaults to the most recently used Anki profile)
+        #[arg(value_name = "DATABASE_PATH")]
+        db_path: Option<String>,
+        /// Number of recent weeks used to measure the maturation rate
+        #[arg(long, default_value_t = 12)]
+        weeks: i64,
+    },
+    /// Show progress through each section of a long chapter (e.g. the stanzas of Psalm 119)
+    Sections {
+        /// Book name or abbreviation (e.g. Psalms, Ps)
+        book: String,
+        /// Chapter number
+        chapter: i64,
+        /// Path to the Anki database file (defaults to the most recently used Anki profile)
+        #[arg(value_name = "DATABASE_PATH")]
+        db_path: Option<String>,
+        /// Number of verses per section
+        #[arg(long, default_value_t = 8)]
+        size: i64,
+    },
+    /// Show the all-time number of mature passages, one line per day it changed
+    History {
+        /// Path to the Anki database file (defaults to the most recently used Anki profile)
+        #[arg(value_name = "DATABASE_PATH")]
+        db_path: Option<String>,
+    },
+    /// Show milestones like books started and chapters fully mature
+    Milestones {
+        /// Path to the Anki database file (defaults to the most recently used Anki profile)
+        #[arg(value_name = "DATABASE_PATH")]
+        db_path: Option<String>,
+    },
+    /// List notes with invalid references
+    Lint {
+        /// Path to the Anki database file (defaults to the most recently used Anki profile)
+        #[arg(value_name = "DATABASE_PATH")]
+        db_path: Option<String>,
+    },
+    /// List pairs of notes that cover some of the same verses
+    Overlaps {
+        /// Path to the Anki database file (defaults to the most recently used Anki profile)
+        #[arg(value_name = "DATABASE_PATH")]
+        db_path: Option<String>,
     },
     /// List all Bible references in the database
     Refs {
-        /// Path to the Anki database file
+        /// Path to the Anki database file, or an AnkiConnect URL (e.g. http://127.0.0.1:8765);
+        /// defaults to the most recently used Anki profile
         #[arg(value_name = "DATABASE_PATH")]
-        db_path: String,
+        db_path: Option<String>,
     },
 }
 
+/// Sort orders for the passages table
+#[derive(Clone, Copy, ValueEnum)]
+enum PassageSort {
+    Reference,
+    Verses,
+    Interval,
+    Ease,
+    Lapses,
+    Due,
+    LastReview,
+}
+
 fn main() {
     let cli = Cli::parse();
+    let format = cli.format;
+
+    // Timezone, rollover hour, and week start shared with the rest of the workspace
+    if let Err(e) = statsutils::configure_from_env() {
+        eprintln!("Error: {:#}", e);
+        process::exit(1);
+    }
+
+    bible::set_canon(cli.canon);
+    config::set_config(AnkiStatsConfig {
+        max_answer_seconds: cli.max_answer_seconds,
+        outlier_handling: if cli.exclude_outliers {
+            OutlierHandling::Exclude
+        } else {
+            OutlierHandling::Cap
+        },
+    });
+
+    if let Some(path) = &cli.aliases
+        && let Err(e) = book_name_parser::load_alias_file(path)
+    {
+        eprintln!("Error: {:#}", e);
+        process::exit(1);
+    }
 
     match cli.command {
-        Commands::Books { db_path } => {
-            run_books_command(&db_path);
+        Commands::Books { db_path, tag } => {
+            run_books_command(&resolve_db_path(db_path), tag.as_deref(), format);
         }
         Commands::Today { db_path } => {
-            run_today_command(&db_path);
+            run_today_command(&resolve_db_path(db_path), format);
         }
-        Commands::Daily { db_path } => {
-            run_daily_command(&db_path);
+        Commands::Daily { db_path, tag } => {
+            run_daily_command(&resolve_db_path(db_path), tag.as_deref(), format);
         }
-        Commands::Weekly { db_path } => {
-            run_weekly_command(&db_path);
+        Commands::Weekly {
+            db_path,
+            tag,
+            book,
+            weeks,
+        } => {
+            run_weekly_command(
+                &resolve_db_path(db_path),
+                tag.as_deref(),
+                book.as_deref(),
+                weeks,
+                format,
+            );
+        }
+        Commands::Monthly { db_path } => {
+            run_monthly_command(&resolve_db_path(db_path), format);
+        }
+        Commands::Yearly { year, db_path } => {
+            run_yearly_command(year, &resolve_db_path(db_path), format);
+        }
+        Commands::Range {
+            start_date,
+            end_date,
+            db_path,
+            granularity,
+        } => {
+            run_range_command(
+                &start_date,
+                &end_date,
+                &resolve_db_path(db_path),
+                granularity,
+                format,
+            );
+        }
+        Commands::Forecast { db_path, days } => {
+            run_forecast_command(&resolve_db_path(db_path), days, format);
+        }
+        Commands::Retention { db_path, days } => {
+            run_retention_command(&resolve_db_path(db_path), days, format);
+        }
+        Commands::ExportReviews {
+            db_path,
+            days,
+            output,
+        } => {
+            run_export_reviews_command(&resolve_db_path(db_path), days, output, format);
+        }
+        Commands::Intervals { db_path } => {
+            run_intervals_command(&resolve_db_path(db_path), format);
+        }
+        Commands::Compare { db_path, days } => {
+            run_compare_command(&resolve_db_path(db_path), days, format);
+        }
+        Commands::Heatmap { db_path, days } => {
+            run_heatmap_command(&resolve_db_path(db_path), days, format);
+        }
+        Commands::Goals {
+            goals_path,
+            db_path,
+        } => {
+            run_goals_command(&goals_path, &resolve_db_path(db_path), format);
+        }
+        Commands::Suggest { db_path, limit } => {
+            run_suggest_command(&resolve_db_path(db_path), limit, format);
+        }
+        Commands::Streaks { db_path } => {
+            run_streaks_command(&resolve_db_path(db_path), format);
+        }
+        Commands::Passages {
+            db_path,
+            sort,
+            reverse,
+        } => {
+            run_passages_command(&resolve_db_path(db_path), sort, reverse, format);
+        }
+        Commands::Problems { db_path, lapses } => {
+            run_problems_command(&resolve_db_path(db_path), lapses, format);
+        }
+        Commands::Project {
+            book,
+            db_path,
+            weeks,
+        } => {
+            run_project_command(&book, &resolve_db_path(db_path), weeks, format);
+        }
+        Commands::Sections {
+            book,
+            chapter,
+            db_path,
+            size,
+        } => {
+            run_sections_command(&book, chapter, &resolve_db_path(db_path), size, format);
+        }
+        Commands::History { db_path } => {
+            run_history_command(&resolve_db_path(db_path), format);
+        }
+        Commands::Milestones { db_path } => {
+            run_milestones_command(&resolve_db_path(db_path), format);
+        }
+        Commands::Lint { db_path } => {
+            run_lint_command(&resolve_db_path(db_path), format);
+        }
+        Commands::Overlaps { db_path } => {
+            run_overlaps_command(&resolve_db_path(db_path), format);
         }
         Commands::Refs { db_path } => {
-            run_refs_command(&db_path);
+            run_refs_command(&resolve_db_path(db_path), format);
+        }
+    }
+}
+
+/// Prints a value as pretty JSON
+fn print_json<T: Serialize>(value: &T) {
+    match serde_json::to_string_pretty(value) {
+        Ok(json) => println!("{}", json),
+        Err(e) => {
+            eprintln!("Error: {:#}", e);
+            process::exit(1);
         }
     }
 }
 
-fn run_books_command(db_path: &str) {
-    match get_bible_stats(db_path) {
+/// Uses the given database path, or finds the most recently used Anki profile
+fn resolve_db_path(db_path: Option<String>) -> String {
+    match db_path {
+        Some(db_path) => db_path,
+        None => match discovery::find_default_collection() {
+            Ok(collection) => collection.to_string_lossy().into_owned(),
+            Err(e) => {
+                eprintln!("Error: {:#}", e);
+                process::exit(1);
+            }
+        },
+    }
+}
+
+fn run_books_command(db_path: &str, tag: Option<&str>, format: OutputFormat) {
+    match backend_for(db_path).get_bible_stats(tag) {
         Ok(stats) => {
+            if format == OutputFormat::Json {
+                print_json(&stats);
+                return;
+            }
+
             println!("\n=== OLD TESTAMENT ===\n");
             print_book_stats(&stats.old_testament.book_stats);
             println!(
@@ -95,6 +505,12 @@ fn run_books_command(db_path: &str) {
                 stats.old_testament.suspended_verses,
                 stats.old_testament.total_verses()
             );
+            println!(
+                "OT Coverage: {:.1}% mature, {:.1}% learned (of {} verses)",
+                stats.old_testament.mature_percent,
+                stats.old_testament.learned_percent,
+                stats.old_testament.canonical_verses
+            );
 
             println!("\n\n=== NEW TESTAMENT ===\n");
             print_book_stats(&stats.new_testament.book_stats);
@@ -116,6 +532,12 @@ fn run_books_command(db_path: &str) {
                 stats.new_testament.suspended_verses,
                 stats.new_testament.total_verses()
             );
+            println!(
+                "NT Coverage: {:.1}% mature, {:.1}% learned (of {} verses)",
+                stats.new_testament.mature_percent,
+                stats.new_testament.learned_percent,
+                stats.new_testament.canonical_verses
+            );
 
             println!("\n\n=== GRAND TOTAL ===");
             println!(
@@ -136,6 +558,12 @@ fn run_books_command(db_path: &str) {
                 stats.total_suspended_verses(),
                 stats.total_verses()
             );
+            println!(
+                "Coverage: {:.1}% mature, {:.1}% learned (of {} verses)",
+                stats.mature_percent,
+                stats.learned_percent,
+                stats.total_canonical_verses()
+            );
         }
         Err(e) => {
             eprintln!("Error: {:#}", e);
@@ -151,14 +579,19 @@ fn print_book_stats(book_stats: &[BookStats]) {
     println!("\n(Format: Passages / Verses)");
 }
 
-fn run_today_command(db_path: &str) {
-    match get_today_study_time(db_path) {
+fn run_today_command(db_path: &str, format: OutputFormat) {
+    match backend_for(db_path).get_today_study_time() {
         Ok(minutes) => {
+            if format == OutputFormat::Json {
+                print_json(&TodayStats::new(minutes));
+                return;
+            }
+
             println!("\n=== TODAY'S STUDY TIME ===\n");
             println!(
-                "Total: {:.2} minutes ({:.1} hours)",
-                minutes,
-                minutes / 60.0
+                "Total: {} ({:.2} minutes)",
+                duration_minutes(minutes),
+                minutes
             );
         }
         Err(e) => {
@@ -168,19 +601,27 @@ fn run_today_command(db_path: &str) {
     }
 }
 
-fn run_daily_command(db_path: &str) {
-    match get_last_30_days_stats(db_path) {
+fn run_daily_command(db_path: &str, tag: Option<&str>, format: OutputFormat) {
+    match get_last_30_days_stats_with_tag(db_path, tag) {
         Ok(daily_stats) => {
+            if format == OutputFormat::Json {
+                print_json(&DailyStats::new(daily_stats));
+                return;
+            }
+
             println!("\n=== DAILY STATS - LAST 30 DAYS ===\n");
 
             let total_minutes: f64 = daily_stats.iter().map(|d| d.minutes).sum();
             let avg_minutes = total_minutes / daily_stats.len() as f64;
             let total_matured: i64 = daily_stats.iter().map(|d| d.matured_passages).sum();
             let total_lost: i64 = daily_stats.iter().map(|d| d.lost_passages).sum();
+            let total_reviews: i64 = daily_stats.iter().map(|d| d.reviews).sum();
+            let total_due_completed: i64 = daily_stats.iter().map(|d| d.due_completed).sum();
+            let total_new: i64 = daily_stats.iter().map(|d| d.new_passages).sum();
 
             // Print each day
             for day in &daily_stats {
-                let hours = day.minutes / 60.0;
+                let volume_str = format_volume(day.reviews, day.new_passages);
                 let progress_str = if day.matured_passages > 0 || day.lost_passages > 0 {
                     format!(
                         " | Matured: {}, Lost: {}, Cumulative: {}",
@@ -194,8 +635,12 @@ fn run_daily_command(db_path: &str) {
 
                 if day.minutes > 0.0 || day.matured_passages > 0 || day.lost_passages > 0 {
                     println!(
-                        "{}: {:.2} min ({:.1} hrs){}",
-                        day.date, day.minutes, hours, progress_str
+                        "{}: {:.2} min ({}){}{}",
+                        day.date,
+                        day.minutes,
+                        duration_minutes(day.minutes),
+                        volume_str,
+                        progress_str
                     );
                 } else {
                     println!("{}: --- (no activity)", day.date);
@@ -204,18 +649,24 @@ fn run_daily_command(db_path: &str) {
 
             println!("\n--- SUMMARY ---");
             println!(
-                "Study Time: {:.2} minutes ({:.1} hours)",
-                total_minutes,
-                total_minutes / 60.0
+                "Study Time: {} ({:.2} minutes)",
+                duration_minutes(total_minutes),
+                total_minutes
             );
             println!(
-                "Average per day: {:.2} minutes ({:.1} hours)",
-                avg_minutes,
-                avg_minutes / 60.0
+                "Average per day: {} ({:.2} minutes)",
+                duration_minutes(avg_minutes),
+                avg_minutes
             );
 
             let days_studied = daily_stats.iter().filter(|d| d.minutes > 0.0).count();
             println!("Days studied: {} out of 30", days_studied);
+            let summary = DailySummary::from_daily_stats(&daily_stats);
+            println!(
+                "Consistency: {:.0}/100 (longest gap {} days, median {} days per week)",
+                summary.consistency_score, summary.longest_gap_days, summary.median_days_per_week
+            );
+            print_volume_summary(total_reviews, total_due_completed, total_new);
 
             println!("\nProgress:");
             println!("  Matured: {} passages", total_matured);
@@ -229,19 +680,45 @@ fn run_daily_command(db_path: &str) {
     }
 }
 
-fn run_weekly_command(db_path: &str) {
-    match get_last_12_weeks_stats(db_path) {
+fn run_weekly_command(
+    db_path: &str,
+    tag: Option<&str>,
+    book: Option<&str>,
+    weeks: i64,
+    format: OutputFormat,
+) {
+    let result = match book {
+        Some(book) => get_book_weekly_trends(db_path, book, weeks),
+        None => get_last_12_weeks_stats_with_tag(db_path, tag),
+    };
+
+    match result {
         Ok(weekly_stats) => {
-            println!("\n=== WEEKLY STATS - LAST 12 WEEKS ===\n");
+            if format == OutputFormat::Json {
+                print_json(&WeeklyStats::new(weekly_stats));
+                return;
+            }
+
+            match book {
+                Some(book) => println!(
+                    "\n=== WEEKLY STATS - LAST {} WEEKS ({}) ===\n",
+                    weekly_stats.len(),
+                    book
+                ),
+                None => println!("\n=== WEEKLY STATS - LAST 12 WEEKS ===\n"),
+            }
 
             let total_minutes: f64 = weekly_stats.iter().map(|w| w.minutes).sum();
             let avg_minutes = total_minutes / weekly_stats.len() as f64;
             let total_matured: i64 = weekly_stats.iter().map(|w| w.matured_passages).sum();
             let total_lost: i64 = weekly_stats.iter().map(|w| w.lost_passages).sum();
+            let total_reviews: i64 = weekly_stats.iter().map(|w| w.reviews).sum();
+            let total_due_completed: i64 = weekly_stats.iter().map(|w| w.due_completed).sum();
+            let total_new: i64 = weekly_stats.iter().map(|w| w.new_passages).sum();
 
             // Print each week
             for week in &weekly_stats {
-                let hours = week.minutes / 60.0;
+                let volume_str = format_volume(week.reviews, week.new_passages);
                 let progress_str = if week.matured_passages > 0 || week.lost_passages > 0 {
                     format!(
                         " | Matured: {}, Lost: {}, Cumulative: {}",
@@ -255,8 +732,12 @@ fn run_weekly_command(db_path: &str) {
 
                 if week.minutes > 0.0 || week.matured_passages > 0 || week.lost_passages > 0 {
                     println!(
-                        "Week of {}: {:.2} min ({:.1} hrs){}",
-                        week.week_start, week.minutes, hours, progress_str
+                        "Week of {}: {:.2} min ({}){}{}",
+                        week.week_start,
+                        week.minutes,
+                        duration_minutes(week.minutes),
+                        volume_str,
+                        progress_str
                     );
                 } else {
                     println!("Week of {}: --- (no activity)", week.week_start);
@@ -265,18 +746,200 @@ fn run_weekly_command(db_path: &str) {
 
             println!("\n--- SUMMARY ---");
             println!(
-                "Study Time: {:.2} minutes ({:.1} hours)",
-                total_minutes,
-                total_minutes / 60.0
+                "Study Time: {} ({:.2} minutes)",
+                duration_minutes(total_minutes),
+                total_minutes
             );
             println!(
-                "Average per week: {:.2} minutes ({:.1} hours)",
-                avg_minutes,
-                avg_minutes / 60.0
+                "Average per week: {} ({:.2} minutes)",
+                duration_minutes(avg_minutes),
+                avg_minutes
             );
 
             let weeks_studied = weekly_stats.iter().filter(|w| w.minutes > 0.0).count();
-            println!("Weeks studied: {} out of 12", weeks_studied);
+            println!(
+                "Weeks studied: {} out of {}",
+                weeks_studied,
+                weekly_stats.len()
+            );
+            let summary = WeeklySummary::from_weekly_stats(&weekly_stats);
+            println!(
+                "Consistency: {:.0}/100 (longest gap {} weeks, median {} days per week)",
+                summary.consistency_score, summary.longest_gap_weeks, summary.median_days_per_week
+            );
+            print_volume_summary(total_reviews, total_due_completed, total_new);
+
+            println!("\nProgress:");
+            println!("  Matured: {} passages", total_matured);
+            println!("  Lost: {} passages", total_lost);
+            println!("  Net: {} passages", total_matured - total_lost);
+        }
+        Err(e) => {
+            eprintln!("Error: {:#}", e);
+            process::exit(1);
+        }
+    }
+}
+
+/// Formats review volume for a bucket line, e.g. " | 42 reviews, 3 new"
+fn format_volume(reviews: i64, new_passages: i64) -> String {
+    match (reviews, new_passages) {
+        (0, 0) => String::new(),
+        (reviews, 0) => format!(" | {} reviews", reviews),
+        (reviews, new) => format!(" | {} reviews, {} new", reviews, new),
+    }
+}
+
+fn print_volume_summary(reviews: i64, due_completed: i64, new_passages: i64) {
+    println!("\nVolume:");
+    println!("  Reviews: {}", reviews);
+    println!("  Due cards completed: {}", due_completed);
+    println!("  New passages: {}", new_passages);
+}
+
+fn run_monthly_command(db_path: &str, format: OutputFormat) {
+    match get_last_12_months_stats(db_path) {
+        Ok(monthly_stats) => {
+            if format == OutputFormat::Json {
+                print_json(&MonthlyStats::new(monthly_stats));
+                return;
+            }
+
+            println!("\n=== MONTHLY STATS - LAST 12 MONTHS ===\n");
+            print_monthly_stats(&monthly_stats);
+        }
+        Err(e) => {
+            eprintln!("Error: {:#}", e);
+            process::exit(1);
+        }
+    }
+}
+
+fn run_yearly_command(year: i32, db_path: &str, format: OutputFormat) {
+    match get_yearly_stats(db_path, year) {
+        Ok(monthly_stats) => {
+            if format == OutputFormat::Json {
+                print_json(&MonthlyStats::new(monthly_stats));
+                return;
+            }
+
+            println!("\n=== MONTHLY STATS - {} ===\n", year);
+            print_monthly_stats(&monthly_stats);
+        }
+        Err(e) => {
+            eprintln!("Error: {:#}", e);
+            process::exit(1);
+        }
+    }
+}
+
+fn print_monthly_stats(monthly_stats: &[MonthStats]) {
+    let summary = MonthlySummary::from_monthly_stats(monthly_stats);
+
+    // Print each month
+    for month in monthly_stats {
+        let volume_str = format_volume(month.reviews, month.new_passages);
+        let progress_str = if month.matured_passages > 0 || month.lost_passages > 0 {
+            format!(
+                " | Matured: {}, Lost: {}, Cumulative: {}",
+                month.matured_passages, month.lost_passages, month.cumulative_passages
+            )
+        } else if month.cumulative_passages != 0 {
+            format!(" | Cumulative: {}", month.cumulative_passages)
+        } else {
+            String::new()
+        };
+
+        if month.minutes > 0.0 || month.matured_passages > 0 || month.lost_passages > 0 {
+            println!(
+                "{}: {:.2} min ({}){}{}",
+                month.month,
+                month.minutes,
+                duration_minutes(month.minutes),
+                volume_str,
+                progress_str
+            );
+        } else {
+            println!("{}: --- (no activity)", month.month);
+        }
+    }
+
+    println!("\n--- SUMMARY ---");
+    println!(
+        "Study Time: {} ({:.2} minutes)",
+        duration_minutes(summary.total_minutes),
+        summary.total_minutes
+    );
+    println!(
+        "Average per month: {} ({:.2} minutes)",
+        duration_minutes(summary.average_minutes_per_month),
+        summary.average_minutes_per_month
+    );
+    println!(
+        "Months studied: {} out of {}",
+        summary.months_studied, summary.total_months
+    );
+
+    println!("\nProgress:");
+    println!("  Matured: {} passages", summary.total_matured_passages);
+    println!("  Lost: {} passages", summary.total_lost_passages);
+    println!("  Net: {} passages", summary.net_progress);
+}
+
+fn run_range_command(
+    start_date: &str,
+    end_date: &str,
+    db_path: &str,
+    granularity: Granularity,
+    format: OutputFormat,
+) {
+    match get_stats_for_range(db_path, start_date, end_date, granularity) {
+        Ok(range_stats) => {
+            if format == OutputFormat::Json {
+                print_json(&range_stats);
+                return;
+            }
+
+            println!(
+                "\n=== {} STATS - {} TO {} ===\n",
+                granularity.to_string().to_uppercase(),
+                start_date,
+                end_date
+            );
+
+            let total_minutes: f64 = range_stats.iter().map(|p| p.minutes).sum();
+            let total_matured: i64 = range_stats.iter().map(|p| p.matured_passages).sum();
+            let total_lost: i64 = range_stats.iter().map(|p| p.lost_passages).sum();
+
+            for bucket in &range_stats {
+                if bucket.minutes > 0.0 || bucket.matured_passages > 0 || bucket.lost_passages > 0 {
+                    println!(
+                        "{}: {:.2} min ({}) | Matured: {}, Lost: {}, Cumulative: {}",
+                        bucket.period_start,
+                        bucket.minutes,
+                        duration_minutes(bucket.minutes),
+                        bucket.matured_passages,
+                        bucket.lost_passages,
+                        bucket.cumulative_passages
+                    );
+                } else {
+                    println!("{}: --- (no activity)", bucket.period_start);
+                }
+            }
+
+            println!("\n--- SUMMARY ---");
+            println!(
+                "Study Time: {} ({:.2} minutes)",
+                duration_minutes(total_minutes),
+                total_minutes
+            );
+            let active = range_stats.iter().filter(|p| p.minutes > 0.0).count();
+            println!(
+                "Active {}s: {} out of {}",
+                granularity,
+                active,
+                range_stats.len()
+            );
 
             println!("\nProgress:");
             println!("  Matured: {} passages", total_matured);
@@ -290,9 +953,723 @@ fn run_weekly_command(db_path: &str) {
     }
 }
 
-fn run_refs_command(db_path: &str) {
-    match get_bible_references(db_path) {
+fn run_forecast_command(db_path: &str, days: u32, format: OutputFormat) {
+    match get_due_forecast(db_path, days) {
+        Ok(forecast) => {
+            if format == OutputFormat::Json {
+                print_json(&forecast);
+                return;
+            }
+
+            println!("\n=== REVIEW FORECAST - NEXT {} DAYS ===\n", days);
+
+            for day in &forecast.days {
+                if day.passages > 0 {
+                    println!(
+                        "{}: {} passages ({} verses)",
+                        day.date, day.passages, day.verses
+                    );
+                } else {
+                    println!("{}: --- (nothing due)", day.date);
+                }
+            }
+
+            println!("\n--- DUE TODAY ---");
+            if forecast.due_today.is_empty() {
+                println!("Nothing due today");
+            } else {
+                for reference in &forecast.due_today {
+                    println!("  {}", reference);
+                }
+            }
+        }
+        Err(e) => {
+            eprintln!("Error: {:#}", e);
+            process::exit(1);
+        }
+    }
+}
+
+fn run_export_reviews_command(
+    db_path: &str,
+    days: u32,
+    output: Option<String>,
+    format: OutputFormat,
+) {
+    let result = get_period_config(db_path)
+        .and_then(|config| {
+            let today = config.today()?;
+            let start = today - chrono::Duration::days(days.max(1) as i64 - 1);
+            DatePeriod::for_range_with_config(start, today, Granularity::Day, &config)
+        })
+        .and_then(|period| export_review_log(db_path, &period))
+        .and_then(|events| {
+            let writer: Box<dyn Write> = match &output {
+                Some(path) => {
+                    Box::new(File::create(path).context(format!("Failed to create '{}'", path))?)
+                }
+                None => Box::new(io::stdout().lock()),
+            };
+            write_review_events(writer, &events, format)?;
+            Ok(events.len())
+        });
+
+    match result {
+        Ok(count) => {
+            if let Some(path) = output {
+                eprintln!("Exported {} reviews to {}", count, path);
+            }
+        }
+        Err(e) => {
+            eprintln!("Error: {:#}", e);
+            process::exit(1);
+        }
+    }
+}
+
+fn write_review_events(
+    mut writer: Box<dyn Write>,
+    events: &[ReviewEvent],
+    format: OutputFormat,
+) -> anyhow::Result<()> {
+    if format == OutputFormat::Json {
+        serde_json::to_writer_pretty(&mut writer, events)?;
+        writeln!(writer)?;
+        return Ok(());
+    }
+
+    let mut csv_writer = csv::Writer::from_writer(writer);
+    for event in events {
+        csv_writer.serialize(event)?;
+    }
+    csv_writer.flush()?;
+    Ok(())
+}
+
+fn run_retention_command(db_path: &str, days: u32, format: OutputFormat) {
+    let result = get_period_config(db_path)
+        .and_then(|config| {
+            let today = config.today()?;
+            let start = today - chrono::Duration::days(days.max(1) as i64 - 1);
+            DatePeriod::for_range_with_config(start, today, Granularity::Day, &config)
+        })
+        .and_then(|period| get_retention_stats(db_path, &period));
+
+    match result {
+        Ok(stats) => {
+            if format == OutputFormat::Json {
+                print_json(&stats);
+                return;
+            }
+
+            println!("\n=== RETENTION - LAST {} DAYS ===\n", days);
+
+            print_retention_bucket("Learning", &stats.learning);
+            print_retention_bucket("Young", &stats.young);
+            print_retention_bucket("Mature", &stats.mature);
+            print_retention_bucket("Overall", &stats.overall);
+
+            println!("\n--- ANSWER BUTTONS ---");
+            println!("Again: {}", stats.answer_buttons.again);
+            println!("Hard:  {}", stats.answer_buttons.hard);
+            println!("Good:  {}", stats.answer_buttons.good);
+            println!("Easy:  {}", stats.answer_buttons.easy);
+        }
+        Err(e) => {
+            eprintln!("Error: {:#}", e);
+            process::exit(1);
+        }
+    }
+}
+
+fn print_retention_bucket(label: &str, bucket: &RetentionBucket) {
+    if bucket.reviews > 0 {
+        println!(
+            "{:<9} {:.1}% ({} / {} passed)",
+            format!("{}:", label),
+            bucket.retention_percent,
+            bucket.passed,
+            bucket.reviews
+        );
+    } else {
+        println!("{:<9} --- (no reviews)", format!("{}:", label));
+    }
+}
+
+fn run_intervals_command(db_path: &str, format: OutputFormat) {
+    match get_interval_distribution(db_path) {
+        Ok(distribution) => {
+            if format == OutputFormat::Json {
+                print_json(&distribution);
+                return;
+            }
+
+            println!("\n=== CARD INTERVALS ===\n");
+            print_histogram(&distribution.intervals, distribution.total_cards);
+            println!(
+                "\nAverage interval: {:.1} days",
+                distribution.average_interval_days
+            );
+
+            println!("\n=== EASE ===\n");
+            let ease_cards = distribution.ease.iter().map(|b| b.cards).sum();
+            print_histogram(&distribution.ease, ease_cards);
+            println!("\nAverage ease: {:.0}%", distribution.average_ease_percent);
+            println!("Cards counted: {}", distribution.total_cards);
+        }
+        Err(e) => {
+            eprintln!("Error: {:#}", e);
+            process::exit(1);
+        }
+    }
+}
+
+/// Prints one bar per bucket, scaled so the fullest bucket is 40 characters wide
+fn print_histogram(buckets: &[HistogramBucket], total: i64) {
+    let max_cards = buckets.iter().map(|b| b.cards).max().unwrap_or(0).max(1);
+    for bucket in buckets {
+        let width = (bucket.cards * 40 / max_cards) as usize;
+        let percent = if total > 0 {
+            bucket.cards as f64 / total as f64 * 100.0
+        } else {
+            0.0
+        };
+        println!(
+            "{:>9} | {:<40} {:>5} ({:.1}%)",
+            bucket.label,
+            "█".repeat(width),
+            bucket.cards,
+            percent
+        );
+    }
+}
+
+fn run_compare_command(db_path: &str, days: u32, format: OutputFormat) {
+    let days = days.max(1) as i64;
+    let result = get_period_config(db_path).and_then(|config| {
+        let today = config.today()?;
+        let recent_start = today - chrono::Duration::days(days - 1);
+        let previous_end = recent_start - chrono::Duration::days(1);
+        let previous_start = previous_end - chrono::Duration::days(days - 1);
+
+        let recent =
+            DatePeriod::for_range_with_config(recent_start, today, Granularity::Day, &config)?;
+        let previous = DatePeriod::for_range_with_config(
+            previous_start,
+            previous_end,
+            Granularity::Day,
+            &config,
+        )?;
+        compare_periods(db_path, &recent, &previous)
+    });
+
+    match result {
+        Ok(comparison) => {
+            if format == OutputFormat::Json {
+                print_json(&comparison);
+                return;
+            }
+
+            let (a, b) = (&comparison.period_a, &comparison.period_b);
+            println!(
+                "\n=== LAST {} DAYS ({} to {}) vs PREVIOUS ({} to {}) ===\n",
+                days, a.start_date, a.end_date, b.start_date, b.end_date
+            );
+
+            let change = comparison
+                .minutes_change_percent
+                .map(|percent| format!(" ({:+.0}%)", percent))
+                .unwrap_or_default();
+            println!(
+                "Study time: {:.1} min vs {:.1} min, {:+.1} min{}",
+                a.minutes, b.minutes, comparison.minutes_delta, change
+            );
+            println!(
+                "Reviews:    {} vs {}, {:+}",
+                a.reviews, b.reviews, comparison.reviews_delta
+            );
+            println!(
+                "Matured:    {} vs {}, {:+}",
+                a.matured_passages, b.matured_passages, comparison.matured_passages_delta
+            );
+            println!(
+                "Retention:  {:.1}% vs {:.1}%, {:+.1} points",
+                a.retention_percent, b.retention_percent, comparison.retention_delta
+            );
+        }
+        Err(e) => {
+            eprintln!("Error: {:#}", e);
+            process::exit(1);
+        }
+    }
+}
+
+fn run_heatmap_command(db_path: &str, days: u32, format: OutputFormat) {
+    match get_study_heatmap(db_path, days) {
+        Ok(heatmap) => {
+            if format == OutputFormat::Json {
+                print_json(&heatmap);
+                return;
+            }
+
+            println!("\n=== STUDY HEATMAP - LAST {} DAYS ===\n", days);
+            print_heatmap_grid(&heatmap);
+            println!(
+                "\nStudied {} of {} days, {} total (busiest day: {})",
+                heatmap.days_studied,
+                heatmap.days.len(),
+                duration_minutes(heatmap.total_minutes),
+                duration_minutes(heatmap.max_minutes)
+            );
+        }
+        Err(e) => {
+            eprintln!("Error: {:#}", e);
+            process::exit(1);
+        }
+    }
+}
+
+fn run_suggest_command(db_path: &str, limit: usize, format: OutputFormat) {
+    match get_review_suggestions_with_limit(db_path, limit) {
+        Ok(suggestions) => {
+            if format == OutputFormat::Json {
+                print_json(&suggestions);
+                return;
+            }
+
+            let Some(headline) = &suggestions.headline else {
+                println!("Nothing to suggest yet");
+                return;
+            };
+            println!("\n{}\n", headline);
+
+            println!("Due soonest:");
+            for suggestion in &suggestions.due_soonest {
+                println!("  {}", suggestion.message);
+            }
+            if !suggestions.closest_to_maturing.is_empty() {
+                println!("\nClosest to maturing:");
+                for suggestion in &suggestions.closest_to_maturing {
+                    println!("  {}", suggestion.message);
+                }
+            }
+        }
+        Err(e) => {
+            eprintln!("Error: {:#}", e);
+            process::exit(1);
+        }
+    }
+}
+
+fn run_sections_command(
+    book: &str,
+    chapter: i64,
+    db_path: &str,
+    section_size: i64,
+    format: OutputFormat,
+) {
+    match get_section_progress(db_path, book, chapter, section_size) {
+        Ok(sections) => {
+            if format == OutputFormat::Json {
+                print_json(&sections);
+                return;
+            }
+
+            // Title with the canonical book name rather than whatever abbreviation was typed
+            let chapter_name = sections
+                .first()
+                .and_then(|s| s.reference.rsplit_once(':'))
+                .map_or_else(|| format!("{} {}", book, chapter), |(c, _)| c.to_string());
+            println!("\n=== SECTIONS OF {} ===\n", chapter_name.to_uppercase());
+            for section in &sections {
+                // One character per verse: █ mature, ▓ young, ░ learning, · not started
+                let bar: String = (0..section.verses)
+                    .map(|i| {
+                        if i < section.mature_verses {
+                            '█'
+                        } else if i < section.learned_verses {
+                            '▓'
+                        } else if i < section.started_verses {
+                            '░'
+                        } else {
+                            '·'
+                        }
+                    })
+                    .collect();
+                println!(
+                    "{:<22} {}  {}/{} mature",
+                    section.reference, bar, section.mature_verses, section.verses
+                );
+            }
+
+            let mature: i64 = sections.iter().map(|s| s.mature_verses).sum();
+            let total: i64 = sections.iter().map(|s| s.verses).sum();
+            let complete = sections
+                .iter()
+                .filter(|s| s.mature_verses == s.verses)
+                .count();
+            println!(
+                "\n{} of {} sections fully mature, {} of {} verses mature",
+                complete,
+                sections.len(),
+                mature,
+                total
+            );
+        }
+        Err(e) => {
+            eprintln!("Error: {:#}", e);
+            process::exit(1);
+        }
+    }
+}
+
+fn run_goals_command(goals_path: &str, db_path: &str, format: OutputFormat) {
+    let result = goals::load_goals(goals_path).and_then(|goals| evaluate_goals(db_path, &goals));
+
+    match result {
+        Ok(progress) => {
+            if format == OutputFormat::Json {
+                print_json(&progress);
+                return;
+            }
+
+            println!("\n=== GOALS ===\n");
+            if progress.is_empty() {
+                println!("No goals defined in {}", goals_path);
+                return;
+            }
+
+            for goal in &progress {
+                let status = if goal.achieved {
+                    "achieved"
+                } else if goal.on_track {
+                    "on track"
+                } else {
+                    "behind"
+                };
+                println!(
+                    "{:<34} {:>7.1} / {:<7.1} {:>5.1}%  {:>7.1} to go  [{}]",
+                    goal.description,
+                    goal.current,
+                    goal.target,
+                    goal.percent,
+                    goal.remaining,
+                    status
+                );
+            }
+        }
+        Err(e) => {
+            eprintln!("Error: {:#}", e);
+            process::exit(1);
+        }
+    }
+}
+
+/// Prints one row per weekday and one column per week, with month labels on top
+fn print_heatmap_grid(heatmap: &StudyHeatmap) {
+    const LEVEL_CHARS: [char; 5] = ['·', '░', '▒', '▓', '█'];
+
+    // Label a column with the month when its week contains the 1st, if there's room
+    let mut month_row = String::new();
+    for (column, week) in heatmap.weeks.iter().enumerate() {
+        let month = week
+            .days
+            .iter()
+            .flatten()
+            .find(|day| day.date.ends_with("-01"))
+            .and_then(|day| chrono::NaiveDate::parse_from_str(&day.date, "%Y-%m-%d").ok());
+        if let Some(month) = month
+            && month_row.chars().count() <= column
+        {
+            while month_row.chars().count() < column {
+                month_row.push(' ');
+            }
+            month_row.push_str(&month.format("%b").to_string());
+        }
+    }
+    println!("    {}", month_row.trim_end());
+
+    for (weekday, name) in heatmap.weekdays.iter().enumerate() {
+        // Label every other row, like GitHub's grid
+        let label = if weekday % 2 == 0 { name.as_str() } else { "" };
+        let row: String = heatmap
+            .weeks
+            .iter()
+            .map(|week| match &week.days[weekday] {
+                Some(day) => LEVEL_CHARS[day.level as usize],
+                None => ' ',
+            })
+            .collect();
+        println!("{:<3} {}", label, row);
+    }
+
+    println!("\n    Less {} More", LEVEL_CHARS.iter().collect::<String>());
+}
+
+fn run_streaks_command(db_path: &str, format: OutputFormat) {
+    match get_streaks(db_path) {
+        Ok(streaks) => {
+            if format == OutputFormat::Json {
+                print_json(&streaks);
+                return;
+            }
+
+            println!("\n=== STUDY STREAKS ===\n");
+            println!("Current streak: {} days", streaks.current_streak);
+            println!("Longest streak: {} days", streaks.longest_streak);
+            println!("Total days studied: {}", streaks.total_days_studied);
+            if let Some(last) = &streaks.last_study_date {
+                println!("Last studied: {}", last);
+            }
+        }
+        Err(e) => {
+            eprintln!("Error: {:#}", e);
+            process::exit(1);
+        }
+    }
+}
+
+fn run_passages_command(db_path: &str, sort: PassageSort, reverse: bool, format: OutputFormat) {
+    match get_passage_details(db_path) {
+        Ok(mut passages) => {
+            // Unscheduled and never-reviewed passages sort last
+            match sort {
+                PassageSort::Reference => {}
+                PassageSort::Verses => passages.sort_by_key(|p| p.verses),
+                PassageSort::Interval => passages.sort_by_key(|p| p.interval_days),
+                PassageSort::Ease => {
+                    passages.sort_by(|a, b| a.ease_percent.total_cmp(&b.ease_percent))
+                }
+                PassageSort::Lapses => passages.sort_by_key(|p| p.lapses),
+                PassageSort::Due => {
+                    passages.sort_by_key(|p| (p.due_date.is_none(), p.due_date.clone()))
+                }
+                PassageSort::LastReview => {
+                    passages.sort_by_key(|p| (p.last_review.is_none(), p.last_review.clone()))
+                }
+            }
+            if reverse {
+                passages.reverse();
+            }
+
+            if format == OutputFormat::Json {
+                print_json(&passages);
+                return;
+            }
+
+            println!("\n=== PASSAGES ===\n");
+            let display: Vec<PassageDetailDisplay> = passages.iter().map(|p| p.into()).collect();
+            let table = Table::new(display).with(Style::rounded()).to_string();
+            println!("{}", table);
+            println!("\nTotal: {} passages", passages.len());
+        }
+        Err(e) => {
+            eprintln!("Error: {:#}", e);
+            process::exit(1);
+        }
+    }
+}
+
+fn run_problems_command(db_path: &str, lapse_threshold: i64, format: OutputFormat) {
+    match get_problem_passages(db_path, lapse_threshold) {
+        Ok(problems) => {
+            if format == OutputFormat::Json {
+                print_json(&problems);
+                return;
+            }
+
+            println!("\n=== PROBLEM PASSAGES ===\n");
+
+            if problems.is_empty() {
+                println!("No struggling passages found");
+                return;
+            }
+
+            for problem in &problems {
+                println!(
+                    "{} ({}): {}",
+                    problem.reference,
+                    problem.state,
+                    problem.reasons.join(", ")
+                );
+            }
+            println!("\nTotal: {} passages", problems.len());
+        }
+        Err(e) => {
+            eprintln!("Error: {:#}", e);
+            process::exit(1);
+        }
+    }
+}
+
+fn run_project_command(book: &str, db_path: &str, weeks: i64, format: OutputFormat) {
+    match get_completion_projection_with_weeks(db_path, book, weeks) {
+        Ok(projection) => {
+            if format == OutputFormat::Json {
+                print_json(&projection);
+                return;
+            }
+
+            println!("\n=== COMPLETION PROJECTION: {} ===\n", projection.book);
+            println!("Mature passages: {}", projection.mature_passages);
+            println!("Remaining passages: {}", projection.remaining_passages);
+            if projection.suspended_passages > 0 {
+                println!(
+                    "Suspended passages (not counted): {}",
+                    projection.suspended_passages
+                );
+            }
+            println!(
+                "Net rate: {:.2} passages/week over the last {} weeks",
+                projection.matured_per_week, projection.rate_weeks
+            );
+
+            match (&projection.projected_date, projection.weeks_remaining) {
+                (Some(date), Some(weeks)) if projection.remaining_passages > 0 => {
+                    println!("\nProjected completion: {} (~{:.1} weeks)", date, weeks)
+                }
+                (Some(_), _) => println!("\nEvery passage is already mature"),
+                _ => println!("\nNo projection: no net progress in this period"),
+            }
+        }
+        Err(e) => {
+            eprintln!("Error: {:#}", e);
+            process::exit(1);
+        }
+    }
+}
+
+fn run_history_command(db_path: &str, format: OutputFormat) {
+    match get_cumulative_history(db_path) {
+        Ok(points) => {
+            if format == OutputFormat::Json {
+                print_json(&points);
+                return;
+            }
+
+            println!("\n=== MATURE PASSAGES OVER TIME ===\n");
+            for point in points
+                .iter()
+                .filter(|p| p.matured_passages > 0 || p.lost_passages > 0)
+            {
+                println!(
+                    "{}: {:>4} (+{}, -{})",
+                    point.date, point.mature_passages, point.matured_passages, point.lost_passages
+                );
+            }
+            match (points.first(), points.last()) {
+                (Some(first), Some(last)) => println!(
+                    "\nSince {}: {} passages mature today",
+                    first.date, last.mature_passages
+                ),
+                _ => println!("No reviews yet"),
+            }
+        }
+        Err(e) => {
+            eprintln!("Error: {:#}", e);
+            process::exit(1);
+        }
+    }
+}
+
+fn run_milestones_command(db_path: &str, format: OutputFormat) {
+    match get_milestones(db_path) {
+        Ok(milestones) => {
+            if format == OutputFormat::Json {
+                print_json(&milestones);
+                return;
+            }
+
+            println!("\n=== MILESTONES ===\n");
+            if milestones.is_empty() {
+                println!("No milestones yet");
+            }
+            for milestone in &milestones {
+                println!("{}  {}", milestone.date, milestone.description);
+            }
+        }
+        Err(e) => {
+            eprintln!("Error: {:#}", e);
+            process::exit(1);
+        }
+    }
+}
+
+fn run_lint_command(db_path: &str, format: OutputFormat) {
+    match validate_references(db_path) {
+        Ok(issues) => {
+            if format == OutputFormat::Json {
+                print_json(&issues);
+            } else if issues.is_empty() {
+                println!("All references are valid");
+            } else {
+                println!("\n=== INVALID REFERENCES ===\n");
+                for issue in &issues {
+                    println!(
+                        "{:>14}  {:<24}  {}",
+                        issue.note_id, issue.reference, issue.message
+                    );
+                }
+                println!("\n{} invalid references", issues.len());
+            }
+
+            // Exit non-zero so the command can gate scripts
+            if !issues.is_empty() {
+                process::exit(1);
+            }
+        }
+        Err(e) => {
+            eprintln!("Error: {:#}", e);
+            process::exit(1);
+        }
+    }
+}
+
+fn run_overlaps_command(db_path: &str, format: OutputFormat) {
+    match find_overlapping_passages(db_path) {
+        Ok(overlaps) => {
+            if format == OutputFormat::Json {
+                print_json(&overlaps);
+                return;
+            }
+
+            if overlaps.is_empty() {
+                println!("No overlapping passages");
+                return;
+            }
+
+            println!("\n=== OVERLAPPING PASSAGES ===\n");
+            for overlap in &overlaps {
+                let relation = if overlap.duplicate {
+                    "duplicate".to_string()
+                } else {
+                    format!("{} shared verses", overlap.shared_verses)
+                };
+                println!(
+                    "{} ({})  <->  {} ({})  {}",
+                    overlap.reference_a,
+                    overlap.note_id_a,
+                    overlap.reference_b,
+                    overlap.note_id_b,
+                    relation
+                );
+            }
+        }
+        Err(e) => {
+            eprintln!("Error: {:#}", e);
+            process::exit(1);
+        }
+    }
+}
+
+fn run_refs_command(db_path: &str, format: OutputFormat) {
+    match backend_for(db_path).get_bible_references() {
         Ok(references) => {
+            if format == OutputFormat::Json {
+                print_json(&references);
+                return;
+            }
+
             for reference in references {
                 println!("{}", reference);
             }
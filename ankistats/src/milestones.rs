@@ -0,0 +1,306 @@
+use std::collections::{HashMap, HashSet};
+
+use anyhow::Result;
+use statsutils::PeriodConfig;
+
+use crate::bible;
+use crate::book_name_parser;
+use crate::models::{Milestone, MilestoneKind};
+use crate::verse_parser;
+
+/// Mature passage counts worth celebrating
+pub const PASSAGE_MILESTONES: &[i64] =
+    &[1, 10, 25, 50, 100, 150, 200, 250, 300, 400, 500, 750, 1000];
+
+/// Interval (in days) at which a card counts as mature, matching the stats queries
+const MATURE_INTERVAL_DAYS: i64 = 21;
+
+/// A single answer to one of a passage's two cards
+#[derive(Debug, Clone, Copy)]
+pub struct CardReview {
+    pub timestamp_ms: i64,
+    pub note_id: i64,
+    /// Card template: 0 or 1
+    pub ord: i64,
+    /// Interval after the answer (negative values are learning steps in seconds)
+    pub interval: i64,
+}
+
+/// A passage that the replay tracks
+struct Passage {
+    book: String,
+    /// Every (chapter, verse) the reference covers
+    verses: Vec<(i64, i64)>,
+    intervals: [i64; 2],
+}
+
+impl Passage {
+    fn is_mature(&self) -> bool {
+        self.intervals
+            .iter()
+            .all(|&interval| interval >= MATURE_INTERVAL_DAYS)
+    }
+}
+
+/// Verse coverage of mature passages, counting overlapping passages once per verse
+#[derive(Default)]
+struct Coverage {
+    /// Number of mature passages covering each (book, chapter, verse)
+    verse_refs: HashMap<(String, i64, i64), i64>,
+    /// Distinct mature verses per (book, chapter)
+    chapter_verses: HashMap<(String, i64), i64>,
+    /// Distinct mature verses per book
+    book_verses: HashMap<String, i64>,
+}
+
+impl Coverage {
+    /// Adds (`delta` = 1) or removes (`delta` = -1) a passage's verses
+    fn apply(&mut self, passage: &Passage, delta: i64) {
+        for &(chapter, verse) in &passage.verses {
+            let refs = self
+                .verse_refs
+                .entry((passage.book.clone(), chapter, verse))
+                .or_default();
+            let was_covered = *refs > 0;
+            *refs += delta;
+
+            if was_covered != (*refs > 0) {
+                *self
+                    .chapter_verses
+                    .entry((passage.book.clone(), chapter))
+                    .or_default() += delta;
+                *self.book_verses.entry(passage.book.clone()).or_default() += delta;
+            }
+        }
+    }
+}
+
+/// Expands a reference into the (chapter, verse) pairs it covers
+fn passage_verses(reference: &str) -> Vec<(i64, i64)> {
+    verse_parser::parse_reference(reference)
+        .ok()
+        .and_then(|parsed| parsed.verses())
+        .map(|verses| verses.into_iter().collect())
+        .unwrap_or_default()
+}
+
+/// Replays the review history to find when milestones were reached
+///
+/// `references` maps note IDs to their Bible reference and `reviews` holds every answer in
+/// chronological order. A passage is mature while both of its cards have an interval of at
+/// least 21 days. Each milestone is reported once, on the (logical) date it was first
+/// reached, even if maturity was later lost. Results are sorted oldest first.
+pub fn detect_milestones(
+    references: &HashMap<i64, String>,
+    reviews: &[CardReview],
+    config: &PeriodConfig,
+) -> Result<Vec<Milestone>> {
+    let mut passages: HashMap<i64, Passage> = references
+        .iter()
+        .filter_map(|(&note_id, reference)| {
+            let book = book_name_parser::parse_book_name(reference)?;
+            Some((
+                note_id,
+                Passage {
+                    book,
+                    verses: passage_verses(reference),
+                    intervals: [0, 0],
+                },
+            ))
+        })
+        .collect();
+
+    let mut milestones = Vec::new();
+    let mut coverage = Coverage::default();
+    let mut mature_passages = 0i64;
+    let mut next_passage_milestone = 0;
+    let mut started_books: HashSet<String> = HashSet::new();
+    let mut mastered_chapters: HashSet<(String, i64)> = HashSet::new();
+    let mut mastered_books: HashSet<String> = HashSet::new();
+
+    for review in reviews {
+        let Some(passage) = passages.get_mut(&review.note_id) else {
+            continue;
+        };
+        let Ok(ord @ 0..=1) = usize::try_from(review.ord) else {
+            continue;
+        };
+
+        let date = config.logical_date(review.timestamp_ms)?.to_string();
+        let mut reached = |kind, description: String| {
+            milestones.push(Milestone {
+                date: date.clone(),
+                kind,
+                description,
+            })
+        };
+
+        if started_books.insert(passage.book.clone()) {
+            reached(
+                MilestoneKind::BookStarted,
+                format!("Started memorizing {}", passage.book),
+            );
+        }
+
+        let was_mature = passage.is_mature();
+        passage.intervals[ord] = review.interval;
+        let is_mature = passage.is_mature();
+        if was_mature == is_mature {
+            continue;
+        }
+
+        if is_mature {
+            mature_passages += 1;
+            coverage.apply(passage, 1);
+        } else {
+            mature_passages -= 1;
+            coverage.apply(passage, -1);
+            continue;
+        }
+
+        while PASSAGE_MILESTONES
+            .get(next_passage_milestone)
+            .is_some_and(|&threshold| mature_passages >= threshold)
+        {
+            let threshold = PASSAGE_MILESTONES[next_passage_milestone];
+            let description = if threshold == 1 {
+                "First passage mature".to_string()
+            } else {
+                format!("{} passages mature", threshold)
+            };
+            reached(MilestoneKind::PassagesMatured, description);
+            next_passage_milestone += 1;
+        }
+
+        let book = passage.book.clone();
+        let chapters: HashSet<i64> = passage.verses.iter().map(|&(chapter, _)| chapter).collect();
+        let mut chapters: Vec<i64> = chapters.into_iter().collect();
+        chapters.sort();
+
+        for chapter in chapters {
+            let covered = coverage
+                .chapter_verses
+                .get(&(book.clone(), chapter))
+                .copied()
+                .unwrap_or(0);
+            if bible::chapter_verse_count(&book, chapter) == Some(covered)
+                && mastered_chapters.insert((book.clone(), chapter))
+            {
+                // A single psalm reads as "Psalm 23"
+                let chapter_book = if book == "Psalms" { "Psalm" } else { &book };
+                reached(
+                    MilestoneKind::ChapterMastered,
+                    format!("All of {} {} mature", chapter_book, chapter),
+                );
+            }
+        }
+
+        let covered = coverage.book_verses.get(&book).copied().unwrap_or(0);
+        if bible::verse_count(&book) == Some(covered) && mastered_books.insert(book.clone()) {
+            reached(
+                MilestoneKind::BookMastered,
+                format!("All of {} mature", book),
+            );
+        }
+    }
+
+    Ok(milestones)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY_MS: i64 = 86_400_000;
+
+    fn review(day: i64, note_id: i64, ord: i64, interval: i64) -> CardReview {
+        // Noon UTC keeps every review on its own logical day
+        CardReview {
+            timestamp_ms: 1_700_000_000_000 + day * DAY_MS,
+            note_id,
+            ord,
+            interval,
+        }
+    }
+
+    fn kinds(milestones: &[Milestone]) -> Vec<(MilestoneKind, &str)> {
+        milestones
+            .iter()
+            .map(|m| (m.kind, m.description.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_book_started_and_first_mature() {
+        let references = HashMap::from([(1, "James 1:2-4".to_string())]);
+        let reviews = [
+            review(0, 1, 0, 1),
+            review(0, 1, 1, 1),
+            review(30, 1, 0, 25),
+            review(31, 1, 1, 30),
+        ];
+
+        let milestones =
+            detect_milestones(&references, &reviews, &PeriodConfig::default()).unwrap();
+        assert_eq!(
+            kinds(&milestones),
+            [
+                (MilestoneKind::BookStarted, "Started memorizing James"),
+                (MilestoneKind::PassagesMatured, "First passage mature"),
+            ]
+        );
+        // Maturity needs both cards, so it happens with the second card's review
+        assert_ne!(milestones[0].date, milestones[1].date);
+    }
+
+    #[test]
+    fn test_chapter_and_book_mastered() {
+        // Jude has a single chapter of 25 verses
+        let references =
+            HashMap::from([(1, "Jude 1-12".to_string()), (2, "Jude 10-25".to_string())]);
+        let mut reviews = Vec::new();
+        for note_id in [1, 2] {
+            for ord in [0, 1] {
+                reviews.push(review(note_id, note_id, ord, 21));
+            }
+        }
+
+        let milestones =
+            detect_milestones(&references, &reviews, &PeriodConfig::default()).unwrap();
+        assert_eq!(
+            kinds(&milestones),
+            [
+                (MilestoneKind::BookStarted, "Started memorizing Jude"),
+                (MilestoneKind::PassagesMatured, "First passage mature"),
+                (MilestoneKind::ChapterMastered, "All of Jude 1 mature"),
+                (MilestoneKind::BookMastered, "All of Jude mature"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_milestones_reported_once() {
+        let references = HashMap::from([(1, "John 11:35".to_string())]);
+        let reviews = [
+            review(0, 1, 0, 30),
+            review(0, 1, 1, 30),
+            // Lapse, then mature again
+            review(40, 1, 0, -600),
+            review(45, 1, 0, 30),
+        ];
+
+        let milestones =
+            detect_milestones(&references, &reviews, &PeriodConfig::default()).unwrap();
+        let first_mature = milestones
+            .iter()
+            .filter(|m| m.kind == MilestoneKind::PassagesMatured)
+            .count();
+        assert_eq!(first_mature, 1);
+    }
+
+    #[test]
+    fn test_passage_verses() {
+        assert_eq!(passage_verses("John 7:52-8:1"), [(7, 52), (7, 53), (8, 1)]);
+        assert!(passage_verses("not a reference").is_empty());
+    }
+}
@@ -1,7 +1,19 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use tabled::Tabled;
 use utoipa::ToSchema;
 
+use crate::{bible, consistency};
+
+/// Percentage of `part` in `whole`, or 0 when `whole` is empty
+fn percent_of(part: i64, whole: i64) -> f64 {
+    if whole > 0 {
+        part as f64 / whole as f64 * 100.0
+    } else {
+        0.0
+    }
+}
+
 /// Statistics for a single Bible book
 #[derive(Debug, Clone, Serialize, ToSchema)]
 pub struct BookStats {
@@ -16,6 +28,12 @@ pub struct BookStats {
     pub learning_verses: i64,
     pub unseen_verses: i64,
     pub suspended_verses: i64,
+    /// Number of verses in the book
+    pub canonical_verses: i64,
+    /// Percentage of the book's verses that are mature
+    pub mature_percent: f64,
+    /// Percentage of the book's verses that are mature or young
+    pub learned_percent: f64,
 }
 
 /// Display wrapper for BookStats that formats passages and verses as "P / V"
@@ -38,6 +56,9 @@ pub struct BookStatsDisplay {
 
     #[tabled(rename = "Suspended")]
     pub suspended: String,
+
+    #[tabled(rename = "Mature %")]
+    pub mature_percent: String,
 }
 
 impl From<&BookStats> for BookStatsDisplay {
@@ -49,11 +70,44 @@ impl From<&BookStats> for BookStatsDisplay {
             learning: format!("{} / {}", stats.learning_passages, stats.learning_verses),
             unseen: format!("{} / {}", stats.unseen_passages, stats.unseen_verses),
             suspended: format!("{} / {}", stats.suspended_passages, stats.suspended_verses),
+            mature_percent: format!("{:.1}%", stats.mature_percent),
         }
     }
 }
 
 impl BookStats {
+    /// Creates zero-filled stats for a book with no passages
+    pub fn empty(book: &str) -> Self {
+        let mut stats = Self {
+            book: book.to_string(),
+            mature_passages: 0,
+            young_passages: 0,
+            learning_passages: 0,
+            unseen_passages: 0,
+            suspended_passages: 0,
+            mature_verses: 0,
+            young_verses: 0,
+            learning_verses: 0,
+            unseen_verses: 0,
+            suspended_verses: 0,
+            canonical_verses: 0,
+            mature_percent: 0.0,
+            learned_percent: 0.0,
+        };
+        stats.update_coverage();
+        stats
+    }
+
+    /// Fills in the canonical verse count and coverage percentages from the verse counts
+    pub fn update_coverage(&mut self) {
+        self.canonical_verses = bible::verse_count(&self.book).unwrap_or(0);
+        self.mature_percent = percent_of(self.mature_verses, self.canonical_verses);
+        self.learned_percent = percent_of(
+            self.mature_verses + self.young_verses,
+            self.canonical_verses,
+        );
+    }
+
     pub fn total_passages(&self) -> i64 {
         self.mature_passages
             + self.young_passages
@@ -85,6 +139,9 @@ pub struct AggregateStats {
     pub learning_verses: i64,
     pub unseen_verses: i64,
     pub suspended_verses: i64,
+    pub canonical_verses: i64,
+    pub mature_percent: f64,
+    pub learned_percent: f64,
     pub book_stats: Vec<BookStats>,
 }
 
@@ -102,6 +159,9 @@ impl AggregateStats {
             learning_verses: 0,
             unseen_verses: 0,
             suspended_verses: 0,
+            canonical_verses: 0,
+            mature_percent: 0.0,
+            learned_percent: 0.0,
             book_stats: Vec::new(),
         }
     }
@@ -117,6 +177,12 @@ impl AggregateStats {
         self.learning_verses += stats.learning_verses;
         self.unseen_verses += stats.unseen_verses;
         self.suspended_verses += stats.suspended_verses;
+        self.canonical_verses += stats.canonical_verses;
+        self.mature_percent = percent_of(self.mature_verses, self.canonical_verses);
+        self.learned_percent = percent_of(
+            self.mature_verses + self.young_verses,
+            self.canonical_verses,
+        );
         self.book_stats.push(stats);
     }
 
@@ -138,10 +204,14 @@ impl AggregateStats {
 }
 
 /// Complete Bible statistics report
-#[derive(Debug, Serialize, ToSchema)]
+#[derive(Debug, Clone, Serialize, ToSchema)]
 pub struct BibleStats {
     pub old_testament: AggregateStats,
     pub new_testament: AggregateStats,
+    /// Percentage of all verses in the Bible that are mature
+    pub mature_percent: f64,
+    /// Percentage of all verses in the Bible that are mature or young
+    pub learned_percent: f64,
 }
 
 impl BibleStats {
@@ -149,7 +219,49 @@ impl BibleStats {
         Self {
             old_testament: AggregateStats::new("Old Testament".to_string()),
             new_testament: AggregateStats::new("New Testament".to_string()),
+            mature_percent: 0.0,
+            learned_percent: 0.0,
+        }
+    }
+
+    /// Builds the full report from per-book stats keyed by book name
+    ///
+    /// Books missing from the map get zero-filled stats so every book is listed.
+    pub fn from_books(books_map: &HashMap<String, BookStats>) -> Self {
+        let mut stats = Self::new();
+
+        for &book in bible::old_testament() {
+            let book_stats = books_map
+                .get(book)
+                .cloned()
+                .unwrap_or_else(|| BookStats::empty(book));
+            stats.old_testament.add_book(book_stats);
         }
+
+        for &book in bible::new_testament() {
+            let book_stats = books_map
+                .get(book)
+                .cloned()
+                .unwrap_or_else(|| BookStats::empty(book));
+            stats.new_testament.add_book(book_stats);
+        }
+
+        stats.update_coverage();
+        stats
+    }
+
+    /// Recomputes the whole-Bible coverage percentages from both testaments
+    pub fn update_coverage(&mut self) {
+        let canonical = self.total_canonical_verses();
+        self.mature_percent = percent_of(self.total_mature_verses(), canonical);
+        self.learned_percent = percent_of(
+            self.total_mature_verses() + self.total_young_verses(),
+            canonical,
+        );
+    }
+
+    pub fn total_canonical_verses(&self) -> i64 {
+        self.old_testament.canonical_verses + self.new_testament.canonical_verses
     }
 
     pub fn total_mature_passages(&self) -> i64 {
@@ -212,6 +324,12 @@ impl Default for BibleStats {
 pub struct DayStats {
     pub date: String,
     pub minutes: f64,
+    /// Number of answers given (each card answer counts once)
+    pub reviews: i64,
+    /// Number of distinct cards answered as scheduled reviews
+    pub due_completed: i64,
+    /// Number of passages seen for the first time
+    pub new_passages: i64,
     pub matured_passages: i64,
     pub lost_passages: i64,
     pub cumulative_passages: i64,
@@ -264,9 +382,17 @@ pub struct DailySummary {
     pub average_hours_per_day: f64,
     pub days_studied: usize,
     pub total_days: usize,
+    pub total_reviews: i64,
+    pub total_new_passages: i64,
     pub total_matured_passages: i64,
     pub total_lost_passages: i64,
     pub net_progress: i64,
+    /// Most consecutive days without study, including a gap still ongoing
+    pub longest_gap_days: usize,
+    /// Median study days in each 7-day window, most recent first
+    pub median_days_per_week: f64,
+    /// 0–100 score of how regular the habit is, rather than how much was studied
+    pub consistency_score: f64,
 }
 
 impl DailySummary {
@@ -276,6 +402,10 @@ impl DailySummary {
         let days_studied = daily.iter().filter(|d| d.minutes > 0.0).count();
         let total_matured: i64 = daily.iter().map(|d| d.matured_passages).sum();
         let total_lost: i64 = daily.iter().map(|d| d.lost_passages).sum();
+        let total_reviews: i64 = daily.iter().map(|d| d.reviews).sum();
+        let total_new_passages: i64 = daily.iter().map(|d| d.new_passages).sum();
+        let studied: Vec<bool> = daily.iter().map(|d| d.minutes > 0.0).collect();
+        let longest_gap_days = consistency::longest_gap(studied.iter().copied());
 
         Self {
             total_minutes,
@@ -284,9 +414,18 @@ impl DailySummary {
             average_hours_per_day: avg_minutes / 60.0,
             days_studied,
             total_days: daily.len(),
+            total_reviews,
+            total_new_passages,
             total_matured_passages: total_matured,
             total_lost_passages: total_lost,
             net_progress: total_matured - total_lost,
+            longest_gap_days,
+            median_days_per_week: consistency::median(&consistency::days_per_week(&studied)),
+            consistency_score: consistency::consistency_score(
+                days_studied,
+                daily.len(),
+                longest_gap_days,
+            ),
         }
     }
 }
@@ -310,6 +449,14 @@ impl DailyStats {
 pub struct WeekStats {
     pub week_start: String,
     pub minutes: f64,
+    /// Number of answers given (each card answer counts once)
+    pub reviews: i64,
+    /// Number of distinct cards answered as scheduled reviews
+    pub due_completed: i64,
+    /// Number of days in the week with any study
+    pub days_studied: i64,
+    /// Number of passages seen for the first time
+    pub new_passages: i64,
     pub matured_passages: i64,
     pub lost_passages: i64,
     pub cumulative_passages: i64,
@@ -324,9 +471,16 @@ pub struct WeeklySummary {
     pub average_hours_per_week: f64,
     pub weeks_studied: usize,
     pub total_weeks: usize,
+    pub total_reviews: i64,
+    pub total_new_passages: i64,
     pub total_matured_passages: i64,
     pub total_lost_passages: i64,
     pub net_progress: i64,
+    /// Most consecutive weeks without study, including a gap still ongoing
+    pub longest_gap_weeks: usize,
+    pub median_days_per_week: f64,
+    /// 0–100 score of how regular the habit is, rather than how much was studied
+    pub consistency_score: f64,
 }
 
 impl WeeklySummary {
@@ -336,6 +490,10 @@ impl WeeklySummary {
         let weeks_studied = weekly.iter().filter(|w| w.minutes > 0.0).count();
         let total_matured: i64 = weekly.iter().map(|w| w.matured_passages).sum();
         let total_lost: i64 = weekly.iter().map(|w| w.lost_passages).sum();
+        let total_reviews: i64 = weekly.iter().map(|w| w.reviews).sum();
+        let total_new_passages: i64 = weekly.iter().map(|w| w.new_passages).sum();
+        let days_per_week: Vec<f64> = weekly.iter().map(|w| w.days_studied as f64).collect();
+        let longest_gap_weeks = consistency::longest_gap(weekly.iter().map(|w| w.days_studied > 0));
 
         Self {
             total_minutes,
@@ -344,9 +502,18 @@ impl WeeklySummary {
             average_hours_per_week: avg_minutes / 60.0,
             weeks_studied,
             total_weeks: weekly.len(),
+            total_reviews,
+            total_new_passages,
             total_matured_passages: total_matured,
             total_lost_passages: total_lost,
             net_progress: total_matured - total_lost,
+            longest_gap_weeks,
+            median_days_per_week: consistency::median(&days_per_week),
+            consistency_score: consistency::consistency_score(
+                weekly.iter().map(|w| w.days_studied as usize).sum(),
+                weekly.len() * 7,
+                longest_gap_weeks * 7,
+            ),
         }
     }
 }
@@ -365,6 +532,652 @@ impl WeeklyStats {
     }
 }
 
+/// Study time and progress statistics for a single month
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct MonthStats {
+    /// Month in YYYY-MM format
+    pub month: String,
+    pub minutes: f64,
+    /// Number of answers given (each card answer counts once)
+    pub reviews: i64,
+    /// Number of distinct cards answered as scheduled reviews
+    pub due_completed: i64,
+    /// Number of passages seen for the first time
+    pub new_passages: i64,
+    pub matured_passages: i64,
+    pub lost_passages: i64,
+    pub cumulative_passages: i64,
+}
+
+/// Summary statistics for monthly study time and progress
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct MonthlySummary {
+    pub total_minutes: f64,
+    pub total_hours: f64,
+    pub average_minutes_per_month: f64,
+    pub average_hours_per_month: f64,
+    pub months_studied: usize,
+    pub total_months: usize,
+    pub total_reviews: i64,
+    pub total_new_passages: i64,
+    pub total_matured_passages: i64,
+    pub total_lost_passages: i64,
+    pub net_progress: i64,
+}
+
+impl MonthlySummary {
+    pub fn from_monthly_stats(monthly: &[MonthStats]) -> Self {
+        let total_minutes: f64 = monthly.iter().map(|m| m.minutes).sum();
+        let avg_minutes = total_minutes / monthly.len() as f64;
+        let months_studied = monthly.iter().filter(|m| m.minutes > 0.0).count();
+        let total_matured: i64 = monthly.iter().map(|m| m.matured_passages).sum();
+        let total_lost: i64 = monthly.iter().map(|m| m.lost_passages).sum();
+        let total_reviews: i64 = monthly.iter().map(|m| m.reviews).sum();
+        let total_new_passages: i64 = monthly.iter().map(|m| m.new_passages).sum();
+
+        Self {
+            total_minutes,
+            total_hours: total_minutes / 60.0,
+            average_minutes_per_month: avg_minutes,
+            average_hours_per_month: avg_minutes / 60.0,
+            months_studied,
+            total_months: monthly.len(),
+            total_reviews,
+            total_new_passages,
+            total_matured_passages: total_matured,
+            total_lost_passages: total_lost,
+            net_progress: total_matured - total_lost,
+        }
+    }
+}
+
+/// Monthly study time response with summary
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct MonthlyStats {
+    pub months: Vec<MonthStats>,
+    pub summary: MonthlySummary,
+}
+
+impl MonthlyStats {
+    pub fn new(months: Vec<MonthStats>) -> Self {
+        let summary = MonthlySummary::from_monthly_stats(&months);
+        Self { months, summary }
+    }
+}
+
+/// Study time and progress statistics for a single bucket of a custom date range
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct PeriodStats {
+    /// First day of the bucket: YYYY-MM-DD for days and weeks, YYYY-MM for months
+    pub period_start: String,
+    pub minutes: f64,
+    /// Number of answers given (each card answer counts once)
+    pub reviews: i64,
+    /// Number of distinct cards answered as scheduled reviews
+    pub due_completed: i64,
+    /// Number of passages seen for the first time
+    pub new_passages: i64,
+    pub matured_passages: i64,
+    pub lost_passages: i64,
+    pub cumulative_passages: i64,
+}
+
+/// Number of passages coming due on a single upcoming day
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct DueDay {
+    /// Date in YYYY-MM-DD format
+    pub date: String,
+    pub passages: i64,
+    pub verses: i64,
+}
+
+/// Review forecast for the upcoming days
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct DueForecast {
+    /// One entry per upcoming day, starting with today (overdue passages count as today)
+    pub days: Vec<DueDay>,
+    /// References of the passages due today, sorted alphabetically
+    pub due_today: Vec<String>,
+}
+
+/// Pass rate for a group of review answers
+#[derive(Debug, Clone, Default, Serialize, ToSchema)]
+pub struct RetentionBucket {
+    /// Number of answers in this group
+    pub reviews: i64,
+    /// Number of answers other than "Again"
+    pub passed: i64,
+    /// Percentage of answers that passed (0 when there were no reviews)
+    pub retention_percent: f64,
+}
+
+impl RetentionBucket {
+    fn add(&mut self, ease: i64, count: i64) {
+        self.reviews += count;
+        if ease > 1 {
+            self.passed += count;
+        }
+        self.retention_percent = if self.reviews > 0 {
+            self.passed as f64 / self.reviews as f64 * 100.0
+        } else {
+            0.0
+        };
+    }
+}
+
+/// Number of times each answer button was pressed
+#[derive(Debug, Clone, Default, Serialize, ToSchema)]
+pub struct AnswerButtons {
+    pub again: i64,
+    pub hard: i64,
+    pub good: i64,
+    pub easy: i64,
+}
+
+impl AnswerButtons {
+    fn add(&mut self, ease: i64, count: i64) {
+        match ease {
+            1 => self.again += count,
+            2 => self.hard += count,
+            3 => self.good += count,
+            4 => self.easy += count,
+            _ => {}
+        }
+    }
+}
+
+/// Retention and answer button statistics for a period
+#[derive(Debug, Clone, Default, Serialize, ToSchema)]
+pub struct RetentionStats {
+    /// Answers given during learning and relearning steps
+    pub learning: RetentionBucket,
+    /// Review answers for cards with an interval under 21 days
+    pub young: RetentionBucket,
+    /// Review answers for cards with an interval of 21 days or more
+    pub mature: RetentionBucket,
+    /// All answers combined
+    pub overall: RetentionBucket,
+    pub answer_buttons: AnswerButtons,
+}
+
+impl RetentionStats {
+    pub(crate) fn add(&mut self, maturity: &str, ease: i64, count: i64) {
+        let bucket = match maturity {
+            "learning" => &mut self.learning,
+            "mature" => &mut self.mature,
+            _ => &mut self.young,
+        };
+        bucket.add(ease, count);
+        self.overall.add(ease, count);
+        self.answer_buttons.add(ease, count);
+    }
+}
+
+/// Study streak statistics
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct StudyStreaks {
+    /// Consecutive days studied, ending today (or yesterday if not yet studied today)
+    pub current_streak: i64,
+    /// Longest run of consecutive study days ever
+    pub longest_streak: i64,
+    /// Total number of distinct days with any study
+    pub total_days_studied: i64,
+    /// Most recent study day in YYYY-MM-DD format
+    pub last_study_date: Option<String>,
+}
+
+/// A memorization target, as written in a goals TOML file
+///
+/// ```toml
+/// [[goals]]
+/// type = "passages_by_date"
+/// target = 150
+/// date = "2026-12-31"
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Goal {
+    /// Start memorizing this many new verses each calendar month
+    VersesPerMonth { target: i64 },
+    /// Study for this many minutes every day
+    MinutesPerDay { target: f64 },
+    /// Have this many mature passages by `date` (YYYY-MM-DD)
+    PassagesByDate { target: i64, date: String },
+}
+
+/// How a goal is going
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct GoalProgress {
+    pub goal: Goal,
+    /// Human-readable summary of the goal
+    pub description: String,
+    /// Progress so far, in the goal's unit
+    pub current: f64,
+    pub target: f64,
+    /// Amount still needed to reach the target (0 once achieved)
+    pub remaining: f64,
+    /// Progress as a percentage of the target, capped at 100
+    pub percent: f64,
+    pub achieved: bool,
+    /// Whether the goal is on pace to be met, or already met
+    pub on_track: bool,
+    /// Amount needed per remaining day (including today) to reach the target
+    pub pace_needed: f64,
+}
+
+/// Study time for one day of a heatmap
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct HeatmapDay {
+    /// Date in YYYY-MM-DD format
+    pub date: String,
+    pub minutes: f64,
+    /// Color intensity from 0 (no study) to 4 (close to the busiest day)
+    pub level: u8,
+}
+
+/// One column of the heatmap grid
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct HeatmapWeek {
+    /// First day of the week (Sunday unless configured otherwise), in YYYY-MM-DD format
+    pub week_start: String,
+    /// Seven slots from the first day of the week; None for days outside the requested range
+    pub days: Vec<Option<HeatmapDay>>,
+}
+
+/// Daily study time laid out for a GitHub-style contribution grid
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct StudyHeatmap {
+    pub start_date: Option<String>,
+    pub end_date: Option<String>,
+    pub total_minutes: f64,
+    pub max_minutes: f64,
+    pub days_studied: usize,
+    /// Names of the weekdays in each week's seven slots, starting from the configured
+    /// week start (e.g. "Mon")
+    pub weekdays: Vec<String>,
+    /// Every day in the range, oldest first
+    pub days: Vec<HeatmapDay>,
+    /// The same days grouped into calendar weeks
+    pub weeks: Vec<HeatmapWeek>,
+}
+
+/// Number of mature passages at the end of a day, for all-time progress charts
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct CumulativePoint {
+    /// Date in YYYY-MM-DD format
+    pub date: String,
+    /// Mature passages at the end of the day
+    pub mature_passages: i64,
+    /// Passages that became mature during the day
+    pub matured_passages: i64,
+    /// Passages that lost maturity during the day
+    pub lost_passages: i64,
+}
+
+/// Study totals for one window of a period comparison
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct PeriodTotals {
+    /// First day of the window in YYYY-MM-DD format
+    pub start_date: String,
+    /// Last day of the window in YYYY-MM-DD format
+    pub end_date: String,
+    pub minutes: f64,
+    pub reviews: i64,
+    pub matured_passages: i64,
+    pub lost_passages: i64,
+    /// Percentage of all answers that passed (0 when there were no reviews)
+    pub retention_percent: f64,
+}
+
+/// Change between two windows: each delta is `period_a` minus `period_b`
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct PeriodComparison {
+    pub period_a: PeriodTotals,
+    pub period_b: PeriodTotals,
+    pub minutes_delta: f64,
+    /// Relative change in study time, or None when `period_b` has no study time
+    pub minutes_change_percent: Option<f64>,
+    pub reviews_delta: i64,
+    pub matured_passages_delta: i64,
+    /// Difference in retention, in percentage points
+    pub retention_delta: f64,
+}
+
+impl PeriodComparison {
+    pub fn new(period_a: PeriodTotals, period_b: PeriodTotals) -> Self {
+        let minutes_change_percent = (period_b.minutes > 0.0)
+            .then(|| (period_a.minutes - period_b.minutes) / period_b.minutes * 100.0);
+
+        Self {
+            minutes_delta: period_a.minutes - period_b.minutes,
+            minutes_change_percent,
+            reviews_delta: period_a.reviews - period_b.reviews,
+            matured_passages_delta: period_a.matured_passages - period_b.matured_passages,
+            retention_delta: period_a.retention_percent - period_b.retention_percent,
+            period_a,
+            period_b,
+        }
+    }
+}
+
+/// Estimated completion date for a book, based on the recent maturation rate
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct CompletionProjection {
+    pub book: String,
+    pub mature_passages: i64,
+    /// Young, learning, and unseen passages still to mature
+    pub remaining_passages: i64,
+    /// Suspended passages, which are not counted as remaining
+    pub suspended_passages: i64,
+    /// Number of recent weeks the rate is measured over
+    pub rate_weeks: i64,
+    /// Net passages matured per week (matured minus lost)
+    pub matured_per_week: f64,
+    /// Weeks until every remaining passage is mature, if progress is being made
+    pub weeks_remaining: Option<f64>,
+    /// Projected completion date in YYYY-MM-DD format, if progress is being made
+    pub projected_date: Option<String>,
+}
+
+/// Type of milestone reached
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MilestoneKind {
+    /// First review of a passage from a book not studied before
+    BookStarted,
+    /// The number of mature passages reached a round number
+    PassagesMatured,
+    /// Every verse of a chapter is covered by mature passages
+    ChapterMastered,
+    /// Every verse of a book is covered by mature passages
+    BookMastered,
+}
+
+/// A memorization achievement and the day it happened
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct Milestone {
+    /// Date in YYYY-MM-DD format
+    pub date: String,
+    pub kind: MilestoneKind,
+    /// Human-readable summary, e.g. "All of James mature"
+    pub description: String,
+}
+
+/// What is wrong with a note's reference
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReferenceIssueKind {
+    /// The sort field isn't in "Book chapter:verse" form
+    Unparsable,
+    /// The book name isn't a book of the Bible (or a known alias)
+    UnknownBook,
+    /// A chapter or verse number that doesn't exist, e.g. "John 3:99"
+    InvalidRange,
+}
+
+/// A note whose reference can't be counted correctly
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ReferenceIssue {
+    pub note_id: i64,
+    /// The note's sort field, as stored
+    pub reference: String,
+    pub kind: ReferenceIssueKind,
+    pub message: String,
+}
+
+/// Two notes that cover some of the same verses
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct PassageOverlap {
+    pub book: String,
+    pub note_id_a: i64,
+    pub reference_a: String,
+    pub note_id_b: i64,
+    pub reference_b: String,
+    /// Number of verses both notes cover
+    pub shared_verses: i64,
+    /// Whether both notes cover exactly the same verses
+    pub duplicate: bool,
+}
+
+/// A single answer or reschedule of a card, from Anki's review log
+///
+/// Intervals follow Anki's convention: positive values are days, negative values are
+/// seconds (learning steps).
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ReviewEvent {
+    /// Review time in milliseconds since the Unix epoch (the revlog ID)
+    pub timestamp_ms: i64,
+    /// Local review time in "YYYY-MM-DD HH:MM:SS" format
+    pub time: String,
+    /// Study day in YYYY-MM-DD format, respecting Anki's rollover hour
+    pub date: String,
+    pub note_id: i64,
+    pub reference: String,
+    /// Card template number (0 for the first card of a note)
+    pub card_ord: i64,
+    /// One of "learning", "review", "relearning", "filtered", "manual", or "rescheduled"
+    pub review_type: String,
+    /// Answer button (1 = Again through 4 = Easy), or 0 for a manual reschedule
+    pub ease: i64,
+    pub last_interval: i64,
+    pub interval: i64,
+    /// Ease factor after the review as a percentage (e.g. 250.0)
+    pub ease_factor_percent: f64,
+    /// Time spent answering, in seconds
+    pub time_seconds: f64,
+}
+
+/// A passage worth reviewing next, with a ready-to-display reason
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ReviewSuggestion {
+    pub reference: String,
+    /// Mature, young, or learning
+    pub state: PassageState,
+    pub interval_days: i64,
+    /// Next due date in YYYY-MM-DD format
+    pub due_date: Option<String>,
+    /// e.g. "Review Romans 12:1-2 today to keep it mature"
+    pub message: String,
+}
+
+/// What to review next, for dashboards and notifications
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ReviewSuggestions {
+    /// Date the suggestions are for, in YYYY-MM-DD format
+    pub date: String,
+    /// The single most useful suggestion, if there is anything to suggest
+    pub headline: Option<String>,
+    /// Scheduled passages in due order, overdue first
+    pub due_soonest: Vec<ReviewSuggestion>,
+    /// Young and learning passages with the longest intervals
+    pub closest_to_maturing: Vec<ReviewSuggestion>,
+}
+
+/// Memorization progress through one section of a long chapter (e.g. a stanza of
+/// Psalm 119)
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SectionProgress {
+    /// The section as a reference, e.g. "Psalms 119:9-16"
+    pub reference: String,
+    pub start_verse: i64,
+    pub end_verse: i64,
+    pub verses: i64,
+    /// Verses covered by any passage being studied (learning, young, or mature)
+    pub started_verses: i64,
+    /// Verses covered by young or mature passages
+    pub learned_verses: i64,
+    pub mature_verses: i64,
+    pub mature_percent: f64,
+}
+
+/// How well a passage is known, from the weakest of its cards
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PassageState {
+    /// At least one card is suspended
+    Suspended,
+    /// No card has been studied yet
+    Unseen,
+    /// Every card has an interval of 21 days or more
+    Mature,
+    /// Every card has an interval of 7 days or more
+    Young,
+    Learning,
+}
+
+impl PassageState {
+    pub const ALL: [PassageState; 5] = [
+        PassageState::Suspended,
+        PassageState::Unseen,
+        PassageState::Mature,
+        PassageState::Young,
+        PassageState::Learning,
+    ];
+
+    /// The state's name in JSON and in SQL queries, e.g. "mature"
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PassageState::Suspended => "suspended",
+            PassageState::Unseen => "unseen",
+            PassageState::Mature => "mature",
+            PassageState::Young => "young",
+            PassageState::Learning => "learning",
+        }
+    }
+}
+
+impl std::fmt::Display for PassageState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for PassageState {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        PassageState::ALL
+            .into_iter()
+            .find(|state| state.as_str() == s)
+            .ok_or_else(|| anyhow::anyhow!("Unknown passage state '{}'", s))
+    }
+}
+
+impl rusqlite::types::FromSql for PassageState {
+    fn column_result(value: rusqlite::types::ValueRef<'_>) -> rusqlite::types::FromSqlResult<Self> {
+        value
+            .as_str()?
+            .parse()
+            .map_err(|e: anyhow::Error| rusqlite::types::FromSqlError::Other(e.into()))
+    }
+}
+
+/// Scheduling details for a single passage (note)
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct PassageDetail {
+    pub reference: String,
+    pub verses: i64,
+    pub state: PassageState,
+    /// Shortest current interval of the passage's cards, in days
+    pub interval_days: i64,
+    /// Average ease of the passage's cards as a percentage (e.g. 250.0), or 0 for new cards
+    pub ease_percent: f64,
+    /// Total lapses across the passage's cards
+    pub lapses: i64,
+    /// Next due date in YYYY-MM-DD format, if scheduled
+    pub due_date: Option<String>,
+    /// Most recent review date in YYYY-MM-DD format, if ever reviewed
+    pub last_review: Option<String>,
+}
+
+/// Number of cards whose value falls in a histogram bucket
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct HistogramBucket {
+    /// Human-readable range, e.g. "8-14d" or "250-269%"
+    pub label: String,
+    /// Inclusive lower bound
+    pub min: i64,
+    /// Inclusive upper bound, or None for the last, open-ended bucket
+    pub max: Option<i64>,
+    pub cards: i64,
+}
+
+/// Shape of the current scheduling: how long intervals are and how ease is spread
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct IntervalDistribution {
+    /// Number of reviewed, unsuspended cards counted
+    pub total_cards: i64,
+    pub average_interval_days: f64,
+    /// Average ease of cards that have one (FSRS cards are skipped)
+    pub average_ease_percent: f64,
+    pub intervals: Vec<HistogramBucket>,
+    pub ease: Vec<HistogramBucket>,
+}
+
+/// Display wrapper for PassageDetail
+#[derive(Debug, Clone, Tabled)]
+pub struct PassageDetailDisplay {
+    #[tabled(rename = "Reference")]
+    pub reference: String,
+
+    #[tabled(rename = "Verses")]
+    pub verses: i64,
+
+    #[tabled(rename = "State")]
+    pub state: String,
+
+    #[tabled(rename = "Interval")]
+    pub interval: String,
+
+    #[tabled(rename = "Ease")]
+    pub ease: String,
+
+    #[tabled(rename = "Lapses")]
+    pub lapses: i64,
+
+    #[tabled(rename = "Due")]
+    pub due: String,
+
+    #[tabled(rename = "Last Review")]
+    pub last_review: String,
+}
+
+impl From<&PassageDetail> for PassageDetailDisplay {
+    fn from(detail: &PassageDetail) -> Self {
+        Self {
+            reference: detail.reference.clone(),
+            verses: detail.verses,
+            state: detail.state.to_string(),
+            interval: format!("{}d", detail.interval_days),
+            ease: if detail.ease_percent > 0.0 {
+                format!("{:.0}%", detail.ease_percent)
+            } else {
+                "-".to_string()
+            },
+            lapses: detail.lapses,
+            due: detail.due_date.clone().unwrap_or_else(|| "-".to_string()),
+            last_review: detail
+                .last_review
+                .clone()
+                .unwrap_or_else(|| "-".to_string()),
+        }
+    }
+}
+
+/// A passage that is struggling and needs extra attention
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ProblemPassage {
+    pub reference: String,
+    pub verses: i64,
+    pub state: PassageState,
+    pub lapses: i64,
+    pub ease_percent: f64,
+    /// Number of times a card lost maturity within the lookback window
+    pub recent_losses: i64,
+    /// Human-readable reasons the passage was flagged (e.g. "9 lapses")
+    pub reasons: Vec<String>,
+}
+
 /// Error response
 #[derive(Debug, Clone, Serialize, ToSchema)]
 pub struct ErrorResponse {
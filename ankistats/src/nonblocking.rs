@@ -0,0 +1,66 @@
+//! Async versions of the main entry points, run on tokio's blocking thread pool
+//!
+//! Each function takes the same arguments as its namesake in the crate root.
+
+use anyhow::Result;
+use statsutils::nonblocking::run;
+use statsutils::{DatePeriod, Granularity};
+
+use crate::models::{BibleStats, DayStats, MonthStats, PeriodStats, StudyStreaks, WeekStats};
+
+/// Async `crate::get_bible_stats`
+pub async fn get_bible_stats(db_path: &str) -> Result<BibleStats> {
+    let db_path = db_path.to_string();
+    run(move || crate::get_bible_stats(&db_path)).await
+}
+
+/// Async `crate::get_today_study_time`
+pub async fn get_today_study_time(db_path: &str) -> Result<f64> {
+    let db_path = db_path.to_string();
+    run(move || crate::get_today_study_time(&db_path)).await
+}
+
+/// Async `crate::get_last_30_days_stats`
+pub async fn get_last_30_days_stats(db_path: &str) -> Result<Vec<DayStats>> {
+    let db_path = db_path.to_string();
+    run(move || crate::get_last_30_days_stats(&db_path)).await
+}
+
+/// Async `crate::get_last_12_weeks_stats`
+pub async fn get_last_12_weeks_stats(db_path: &str) -> Result<Vec<WeekStats>> {
+    let db_path = db_path.to_string();
+    run(move || crate::get_last_12_weeks_stats(&db_path)).await
+}
+
+/// Async `crate::get_last_12_months_stats`
+pub async fn get_last_12_months_stats(db_path: &str) -> Result<Vec<MonthStats>> {
+    let db_path = db_path.to_string();
+    run(move || crate::get_last_12_months_stats(&db_path)).await
+}
+
+/// Async `crate::get_stats_for_range`
+pub async fn get_stats_for_range(
+    db_path: &str,
+    start_date: &str,
+    end_date: &str,
+    granularity: Granularity,
+) -> Result<Vec<PeriodStats>> {
+    let (db_path, start_date, end_date) = (
+        db_path.to_string(),
+        start_date.to_string(),
+        end_date.to_string(),
+    );
+    run(move || crate::get_stats_for_range(&db_path, &start_date, &end_date, granularity)).await
+}
+
+/// Async `crate::get_stats_for_period`
+pub async fn get_stats_for_period(db_path: &str, period: &DatePeriod) -> Result<Vec<PeriodStats>> {
+    let (db_path, period) = (db_path.to_string(), period.clone());
+    run(move || crate::get_stats_for_period(&db_path, &period)).await
+}
+
+/// Async `crate::get_streaks`
+pub async fn get_streaks(db_path: &str) -> Result<StudyStreaks> {
+    let db_path = db_path.to_string();
+    run(move || crate::get_streaks(&db_path)).await
+}
@@ -0,0 +1,116 @@
+use anyhow::{Context, Result, bail};
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::Path;
+use tempfile::NamedTempFile;
+use zip::ZipArchive;
+
+/// Collection file names found inside Anki packages, newest format first
+///
+/// `collection.anki21b` is zstd-compressed (Anki 2.1.50+). Newer packages also include a
+/// placeholder `collection.anki2` for old clients, so it is only used as a last resort.
+const COLLECTION_ENTRIES: &[(&str, bool)] = &[
+    ("collection.anki21b", true),
+    ("collection.anki21", false),
+    ("collection.anki2", false),
+];
+
+/// Returns true if the path looks like an Anki backup or shared deck (`.colpkg`/`.apkg`)
+pub fn is_package(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("colpkg") || ext.eq_ignore_ascii_case("apkg"))
+}
+
+/// Extracts the SQLite collection from an Anki package into a temporary file
+///
+/// The temporary file is deleted when the returned handle is dropped.
+pub fn extract_collection(path: &str) -> Result<NamedTempFile> {
+    let file = File::open(path).context(format!("Failed to open Anki package: {}", path))?;
+    let mut archive =
+        ZipArchive::new(file).context("Failed to read Anki package as a zip archive")?;
+
+    for &(name, compressed) in COLLECTION_ENTRIES {
+        let Ok(mut entry) = archive.by_name(name) else {
+            continue;
+        };
+
+        let mut data = Vec::new();
+        entry
+            .read_to_end(&mut data)
+            .context(format!("Failed to read {} from Anki package", name))?;
+
+        if compressed {
+            data = zstd::decode_all(data.as_slice())
+                .context(format!("Failed to decompress {}", name))?;
+        }
+
+        let mut temp = NamedTempFile::new().context("Failed to create temporary file")?;
+        temp.write_all(&data)
+            .context("Failed to write extracted collection")?;
+        return Ok(temp);
+    }
+
+    bail!("No Anki collection found in package: {}", path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zip::ZipWriter;
+    use zip::write::SimpleFileOptions;
+
+    fn write_package(entries: &[(&str, &[u8])]) -> NamedTempFile {
+        let temp = tempfile::Builder::new()
+            .suffix(".colpkg")
+            .tempfile()
+            .unwrap();
+        let mut writer = ZipWriter::new(temp.reopen().unwrap());
+        for (name, data) in entries {
+            writer
+                .start_file(*name, SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(data).unwrap();
+        }
+        writer.finish().unwrap();
+        temp
+    }
+
+    #[test]
+    fn test_is_package() {
+        assert!(is_package("backup-2025-01-01.colpkg"));
+        assert!(is_package("/tmp/Shared Deck.APKG"));
+        assert!(!is_package("collection.anki2"));
+        assert!(!is_package("colpkg"));
+    }
+
+    #[test]
+    fn test_prefers_compressed_collection() {
+        let compressed = zstd::encode_all(&b"new collection"[..], 0).unwrap();
+        let package = write_package(&[
+            ("collection.anki2", b"legacy placeholder"),
+            ("collection.anki21b", &compressed),
+        ]);
+
+        let extracted = extract_collection(package.path().to_str().unwrap()).unwrap();
+        assert_eq!(std::fs::read(extracted.path()).unwrap(), b"new collection");
+    }
+
+    #[test]
+    fn test_legacy_collection() {
+        let package = write_package(&[("collection.anki2", b"legacy collection")]);
+
+        let extracted = extract_collection(package.path().to_str().unwrap()).unwrap();
+        assert_eq!(
+            std::fs::read(extracted.path()).unwrap(),
+            b"legacy collection"
+        );
+    }
+
+    #[test]
+    fn test_missing_collection() {
+        let package = write_package(&[("media", b"{}")]);
+        assert!(extract_collection(package.path().to_str().unwrap()).is_err());
+    }
+}
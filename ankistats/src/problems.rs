@@ -0,0 +1,130 @@
+use std::collections::HashMap;
+
+use crate::models::{PassageDetail, ProblemPassage};
+
+/// Ease below which a reviewed passage is considered hard (Anki's starting ease is 250%)
+pub const LOW_EASE_PERCENT: f64 = 200.0;
+
+/// Number of weeks of review history checked for lost maturity
+pub const LOOKBACK_WEEKS: i64 = 8;
+
+/// Number of maturity losses within the lookback window that flags a passage
+pub const REPEATED_LOSS_THRESHOLD: i64 = 2;
+
+/// Picks out passages that need extra attention
+///
+/// A passage is flagged when its lapse count reaches `lapse_threshold`, its ease has dropped
+/// below [`LOW_EASE_PERCENT`], or it lost maturity at least [`REPEATED_LOSS_THRESHOLD`] times
+/// recently. `recent_losses` maps references to their maturity losses in the lookback window.
+/// Results are sorted with the most troubled passages first.
+pub fn find_problem_passages(
+    details: &[PassageDetail],
+    recent_losses: &HashMap<String, i64>,
+    lapse_threshold: i64,
+) -> Vec<ProblemPassage> {
+    let mut problems: Vec<ProblemPassage> = details
+        .iter()
+        .filter_map(|detail| {
+            let losses = recent_losses.get(&detail.reference).copied().unwrap_or(0);
+            let mut reasons = Vec::new();
+
+            if detail.lapses >= lapse_threshold {
+                reasons.push(format!("{} lapses", detail.lapses));
+            }
+            // New cards have no ease yet
+            if detail.ease_percent > 0.0 && detail.ease_percent < LOW_EASE_PERCENT {
+                reasons.push(format!("{:.0}% ease", detail.ease_percent));
+            }
+            if losses >= REPEATED_LOSS_THRESHOLD {
+                reasons.push(format!(
+                    "lost maturity {} times in {} weeks",
+                    losses, LOOKBACK_WEEKS
+                ));
+            }
+
+            if reasons.is_empty() {
+                return None;
+            }
+
+            Some(ProblemPassage {
+                reference: detail.reference.clone(),
+                verses: detail.verses,
+                state: detail.state,
+                lapses: detail.lapses,
+                ease_percent: detail.ease_percent,
+                recent_losses: losses,
+                reasons,
+            })
+        })
+        .collect();
+
+    problems.sort_by(|a, b| {
+        b.reasons
+            .len()
+            .cmp(&a.reasons.len())
+            .then(b.lapses.cmp(&a.lapses))
+            .then(a.reference.cmp(&b.reference))
+    });
+
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::PassageState;
+
+    fn detail(reference: &str, lapses: i64, ease_percent: f64) -> PassageDetail {
+        PassageDetail {
+            reference: reference.to_string(),
+            verses: 1,
+            state: PassageState::Young,
+            interval_days: 10,
+            ease_percent,
+            lapses,
+            due_date: None,
+            last_review: None,
+        }
+    }
+
+    #[test]
+    fn test_healthy_passages_not_flagged() {
+        let details = vec![detail("John 3:16", 1, 250.0), detail("Jude 24", 0, 0.0)];
+        assert!(find_problem_passages(&details, &HashMap::new(), 8).is_empty());
+    }
+
+    #[test]
+    fn test_flags_each_reason() {
+        let details = vec![
+            detail("Genesis 1:1", 8, 250.0),
+            detail("Psalm 23:1", 0, 170.0),
+            detail("Romans 8:28", 0, 250.0),
+        ];
+        let losses = HashMap::from([("Romans 8:28".to_string(), 2)]);
+
+        let problems = find_problem_passages(&details, &losses, 8);
+        assert_eq!(problems.len(), 3);
+
+        let reasons: HashMap<_, _> = problems
+            .iter()
+            .map(|p| (p.reference.as_str(), p.reasons.clone()))
+            .collect();
+        assert_eq!(reasons["Genesis 1:1"], vec!["8 lapses"]);
+        assert_eq!(reasons["Psalm 23:1"], vec!["170% ease"]);
+        assert_eq!(
+            reasons["Romans 8:28"],
+            vec!["lost maturity 2 times in 8 weeks"]
+        );
+    }
+
+    #[test]
+    fn test_most_troubled_first() {
+        let details = vec![
+            detail("Genesis 1:1", 9, 250.0),
+            detail("Psalm 23:1", 10, 150.0),
+        ];
+        let problems = find_problem_passages(&details, &HashMap::new(), 8);
+        assert_eq!(problems[0].reference, "Psalm 23:1");
+        assert_eq!(problems[0].reasons.len(), 2);
+    }
+}
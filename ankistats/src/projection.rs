@@ -0,0 +1,95 @@
+use chrono::{Duration, NaiveDate};
+
+use crate::models::{BookStats, CompletionProjection};
+
+/// Default number of recent weeks used to measure the maturation rate
+pub const DEFAULT_RATE_WEEKS: i64 = 12;
+
+/// Estimates when every remaining passage in a book will be mature
+///
+/// `matured` and `lost` count passages that crossed into or out of maturity during the last
+/// `weeks` weeks; their difference is the net weekly rate. Suspended passages aren't
+/// expected to mature and are left out of the remaining count. Without positive net
+/// progress there is no projected date.
+pub fn project_completion(
+    stats: &BookStats,
+    matured: i64,
+    lost: i64,
+    weeks: i64,
+    today: NaiveDate,
+) -> CompletionProjection {
+    let remaining_passages = stats.young_passages + stats.learning_passages + stats.unseen_passages;
+    let matured_per_week = if weeks > 0 {
+        (matured - lost) as f64 / weeks as f64
+    } else {
+        0.0
+    };
+
+    let weeks_remaining = if remaining_passages == 0 {
+        Some(0.0)
+    } else if matured_per_week > 0.0 {
+        Some(remaining_passages as f64 / matured_per_week)
+    } else {
+        None
+    };
+
+    let projected_date = weeks_remaining.map(|weeks| {
+        let days = (weeks * 7.0).ceil() as i64;
+        (today + Duration::days(days)).to_string()
+    });
+
+    CompletionProjection {
+        book: stats.book.clone(),
+        mature_passages: stats.mature_passages,
+        remaining_passages,
+        suspended_passages: stats.suspended_passages,
+        rate_weeks: weeks,
+        matured_per_week,
+        weeks_remaining,
+        projected_date,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn james(mature: i64, young: i64, unseen: i64) -> BookStats {
+        BookStats {
+            mature_passages: mature,
+            young_passages: young,
+            unseen_passages: unseen,
+            suspended_passages: 1,
+            ..BookStats::empty("James")
+        }
+    }
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 3, 1).unwrap()
+    }
+
+    #[test]
+    fn test_projection_with_progress() {
+        // 6 matured, 2 lost over 4 weeks: 1 passage per week, 3 remaining
+        let projection = project_completion(&james(5, 1, 2), 6, 2, 4, today());
+        assert_eq!(projection.remaining_passages, 3);
+        assert_eq!(projection.matured_per_week, 1.0);
+        assert_eq!(projection.weeks_remaining, Some(3.0));
+        assert_eq!(projection.projected_date.as_deref(), Some("2025-03-22"));
+    }
+
+    #[test]
+    fn test_projection_without_progress() {
+        let projection = project_completion(&james(5, 1, 2), 1, 3, 4, today());
+        assert_eq!(projection.matured_per_week, -0.5);
+        assert_eq!(projection.weeks_remaining, None);
+        assert_eq!(projection.projected_date, None);
+    }
+
+    #[test]
+    fn test_projection_already_complete() {
+        let projection = project_completion(&james(8, 0, 0), 0, 0, 4, today());
+        assert_eq!(projection.remaining_passages, 0);
+        assert_eq!(projection.projected_date.as_deref(), Some("2025-03-01"));
+    }
+}
@@ -0,0 +1,141 @@
+use std::collections::HashMap;
+
+use crate::models::{PassageDetail, PassageState, SectionProgress};
+use crate::verse_parser::parse_reference;
+
+/// Stanza length of Psalm 119, the usual reason to split a chapter into sections
+pub const DEFAULT_SECTION_SIZE: i64 = 8;
+
+/// How well a single verse is known, from the strongest passage covering it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum VerseState {
+    Unstarted,
+    Learning,
+    Young,
+    Mature,
+}
+
+/// Splits a chapter into consecutive sections of `section_size` verses and reports how
+/// many verses of each are covered by mature and by learned (mature or young) passages
+///
+/// A verse takes the state of the strongest passage covering it; suspended and unseen
+/// passages don't count. The last section is shorter when the chapter length isn't a
+/// multiple of `section_size`.
+pub fn section_progress(
+    book: &str,
+    chapter: i64,
+    chapter_length: i64,
+    section_size: i64,
+    passages: &[PassageDetail],
+) -> Vec<SectionProgress> {
+    let mut verse_states: HashMap<i64, VerseState> = HashMap::new();
+    for passage in passages {
+        let state = match passage.state {
+            PassageState::Mature => VerseState::Mature,
+            PassageState::Young => VerseState::Young,
+            PassageState::Learning => VerseState::Learning,
+            PassageState::Suspended | PassageState::Unseen => continue,
+        };
+        let Some(verses) = parse_reference(&passage.reference)
+            .ok()
+            .filter(|parsed| parsed.book == book)
+            .and_then(|parsed| parsed.verses())
+        else {
+            continue;
+        };
+        for (_, verse) in verses.into_iter().filter(|&(c, _)| c == chapter) {
+            let entry = verse_states.entry(verse).or_insert(VerseState::Unstarted);
+            *entry = (*entry).max(state);
+        }
+    }
+
+    let section_size = section_size.max(1);
+    (1..=chapter_length)
+        .step_by(section_size as usize)
+        .map(|start_verse| {
+            let end_verse = (start_verse + section_size - 1).min(chapter_length);
+            let states: Vec<VerseState> = (start_verse..=end_verse)
+                .map(|verse| {
+                    verse_states
+                        .get(&verse)
+                        .copied()
+                        .unwrap_or(VerseState::Unstarted)
+                })
+                .collect();
+            let verses = states.len() as i64;
+            let mature_verses = states.iter().filter(|&&s| s == VerseState::Mature).count() as i64;
+            let learned_verses = states.iter().filter(|&&s| s >= VerseState::Young).count() as i64;
+            let started_verses = states
+                .iter()
+                .filter(|&&s| s >= VerseState::Learning)
+                .count() as i64;
+
+            SectionProgress {
+                reference: format!("{} {}:{}-{}", book, chapter, start_verse, end_verse),
+                start_verse,
+                end_verse,
+                verses,
+                started_verses,
+                learned_verses,
+                mature_verses,
+                mature_percent: mature_verses as f64 / verses as f64 * 100.0,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn passage(reference: &str, state: &str) -> PassageDetail {
+        PassageDetail {
+            reference: reference.to_string(),
+            verses: 1,
+            state: state.parse().unwrap(),
+            interval_days: 0,
+            ease_percent: 0.0,
+            lapses: 0,
+            due_date: None,
+            last_review: None,
+        }
+    }
+
+    #[test]
+    fn test_psalm_119_stanzas() {
+        let passages = vec![
+            passage("Psalm 119:1-8", "mature"),
+            passage("Psalm 119:9-11", "young"),
+            // Overlapping weaker passage doesn't downgrade verse 11
+            passage("Psalm 119:11-12", "learning"),
+            passage("Psalm 119:105", "suspended"),
+            passage("Psalm 23:1", "mature"),
+            passage("John 3:16", "mature"),
+        ];
+
+        let sections = section_progress("Psalms", 119, 176, 8, &passages);
+        assert_eq!(sections.len(), 22);
+
+        assert_eq!(sections[0].reference, "Psalms 119:1-8");
+        assert_eq!(sections[0].mature_verses, 8);
+        assert_eq!(sections[0].mature_percent, 100.0);
+
+        assert_eq!(sections[1].reference, "Psalms 119:9-16");
+        assert_eq!(sections[1].learned_verses, 3);
+        assert_eq!(sections[1].started_verses, 4);
+        assert_eq!(sections[1].mature_verses, 0);
+
+        assert!(sections[2..].iter().all(|s| s.started_verses == 0));
+    }
+
+    #[test]
+    fn test_short_last_section() {
+        let sections = section_progress("John", 3, 36, 10, &[]);
+        let bounds: Vec<(i64, i64)> = sections
+            .iter()
+            .map(|s| (s.start_verse, s.end_verse))
+            .collect();
+        assert_eq!(bounds, [(1, 10), (11, 20), (21, 30), (31, 36)]);
+        assert_eq!(sections[3].verses, 6);
+    }
+}
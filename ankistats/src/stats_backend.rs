@@ -0,0 +1,58 @@
+use anyhow::Result;
+
+use crate::anki_connect::AnkiConnectBackend;
+use crate::models::BibleStats;
+
+/// A source of Anki study data
+///
+/// The SQLite backend reads the collection file directly, while the AnkiConnect backend
+/// asks a running Anki instance, which avoids "database is locked" errors while Anki is open.
+pub trait StatsBackend {
+    /// Retrieves statistics for all Bible books, optionally only for notes with `tag`
+    fn get_bible_stats(&self, tag: Option<&str>) -> Result<BibleStats>;
+
+    /// Gets the total study time for today in minutes
+    fn get_today_study_time(&self) -> Result<f64>;
+
+    /// Gets all Bible references, sorted alphabetically
+    fn get_bible_references(&self) -> Result<Vec<String>>;
+}
+
+/// Reads stats directly from an Anki collection file (or `.colpkg`/`.apkg` backup)
+pub struct SqliteBackend {
+    db_path: String,
+}
+
+impl SqliteBackend {
+    pub fn new(db_path: &str) -> Self {
+        Self {
+            db_path: db_path.to_string(),
+        }
+    }
+}
+
+impl StatsBackend for SqliteBackend {
+    fn get_bible_stats(&self, tag: Option<&str>) -> Result<BibleStats> {
+        crate::get_bible_stats_with_tag(&self.db_path, tag)
+    }
+
+    fn get_today_study_time(&self) -> Result<f64> {
+        crate::get_today_study_time(&self.db_path)
+    }
+
+    fn get_bible_references(&self) -> Result<Vec<String>> {
+        crate::get_bible_references(&self.db_path)
+    }
+}
+
+/// Picks a backend for a data source
+///
+/// `http://` and `https://` URLs are treated as AnkiConnect endpoints; anything else is a
+/// path to an Anki database.
+pub fn backend_for(source: &str) -> Box<dyn StatsBackend> {
+    if source.starts_with("http://") || source.starts_with("https://") {
+        Box::new(AnkiConnectBackend::new(source))
+    } else {
+        Box::new(SqliteBackend::new(source))
+    }
+}
@@ -0,0 +1,78 @@
+use chrono::NaiveDate;
+
+use crate::models::StudyStreaks;
+
+/// Computes study streaks from the set of days with any study activity
+///
+/// The current streak counts consecutive study days ending today. If nothing has been
+/// studied yet today, the streak ending yesterday is still considered current, since the
+/// day isn't over yet.
+pub fn compute_streaks(study_dates: &[NaiveDate], today: NaiveDate) -> StudyStreaks {
+    let streaks = statsutils::streaks::compute_as_of(study_dates, today);
+
+    StudyStreaks {
+        current_streak: streaks.current_streak,
+        longest_streak: streaks.longest_streak,
+        total_days_studied: streaks.active_days,
+        last_study_date: streaks
+            .last_active_date
+            .map(|d| d.format("%Y-%m-%d").to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_no_study_days() {
+        let streaks = compute_streaks(&[], date("2025-10-20"));
+        assert_eq!(streaks.current_streak, 0);
+        assert_eq!(streaks.longest_streak, 0);
+        assert_eq!(streaks.total_days_studied, 0);
+        assert_eq!(streaks.last_study_date, None);
+    }
+
+    #[test]
+    fn test_current_streak_including_today() {
+        let dates = [date("2025-10-18"), date("2025-10-19"), date("2025-10-20")];
+        let streaks = compute_streaks(&dates, date("2025-10-20"));
+        assert_eq!(streaks.current_streak, 3);
+        assert_eq!(streaks.longest_streak, 3);
+    }
+
+    #[test]
+    fn test_current_streak_ending_yesterday() {
+        let dates = [date("2025-10-18"), date("2025-10-19")];
+        let streaks = compute_streaks(&dates, date("2025-10-20"));
+        assert_eq!(streaks.current_streak, 2);
+    }
+
+    #[test]
+    fn test_broken_streak() {
+        let dates = [
+            date("2025-10-01"),
+            date("2025-10-02"),
+            date("2025-10-03"),
+            date("2025-10-04"),
+            date("2025-10-10"),
+        ];
+        let streaks = compute_streaks(&dates, date("2025-10-20"));
+        assert_eq!(streaks.current_streak, 0);
+        assert_eq!(streaks.longest_streak, 4);
+        assert_eq!(streaks.total_days_studied, 5);
+        assert_eq!(streaks.last_study_date, Some("2025-10-10".to_string()));
+    }
+
+    #[test]
+    fn test_unsorted_and_duplicate_dates() {
+        let dates = [date("2025-10-20"), date("2025-10-19"), date("2025-10-20")];
+        let streaks = compute_streaks(&dates, date("2025-10-20"));
+        assert_eq!(streaks.current_streak, 2);
+        assert_eq!(streaks.total_days_studied, 2);
+    }
+}
@@ -0,0 +1,179 @@
+use chrono::NaiveDate;
+
+use crate::models::{PassageDetail, PassageState, ReviewSuggestion, ReviewSuggestions};
+
+/// Number of passages suggested in each list by default
+pub const DEFAULT_SUGGESTION_LIMIT: usize = 5;
+
+/// Interval (days) at which a passage counts as mature
+const MATURE_INTERVAL_DAYS: i64 = 21;
+
+/// Picks the passages due soonest and the unmatured passages closest to maturing
+///
+/// Due dates are compared as YYYY-MM-DD strings, so overdue passages sort first.
+/// Passages without a due date (new or suspended) are never suggested for review.
+pub fn suggest_reviews(
+    details: &[PassageDetail],
+    today: NaiveDate,
+    limit: usize,
+) -> ReviewSuggestions {
+    let today_str = today.format("%Y-%m-%d").to_string();
+
+    let mut due: Vec<&PassageDetail> = details
+        .iter()
+        .filter(|detail| detail.due_date.is_some())
+        .collect();
+    due.sort_by(|a, b| (&a.due_date, &a.reference).cmp(&(&b.due_date, &b.reference)));
+
+    let due_soonest: Vec<ReviewSuggestion> = due
+        .into_iter()
+        .take(limit)
+        .map(|detail| {
+            let due_date = detail.due_date.clone().unwrap_or_default();
+            let message = if due_date > today_str {
+                format!("{} is due {}", detail.reference, due_date)
+            } else if detail.state == PassageState::Mature {
+                format!("Review {} today to keep it mature", detail.reference)
+            } else {
+                format!("Review {} today", detail.reference)
+            };
+            suggestion(detail, message)
+        })
+        .collect();
+
+    let mut maturing: Vec<&PassageDetail> = details
+        .iter()
+        .filter(|detail| matches!(detail.state, PassageState::Young | PassageState::Learning))
+        .collect();
+    maturing.sort_by(|a, b| {
+        b.interval_days
+            .cmp(&a.interval_days)
+            .then_with(|| a.reference.cmp(&b.reference))
+    });
+
+    let closest_to_maturing = maturing
+        .into_iter()
+        .take(limit)
+        .map(|detail| {
+            let days = (MATURE_INTERVAL_DAYS - detail.interval_days).max(1);
+            let message = format!(
+                "{} is {} day{} from maturing",
+                detail.reference,
+                days,
+                if days == 1 { "" } else { "s" }
+            );
+            suggestion(detail, message)
+        })
+        .collect::<Vec<_>>();
+
+    let headline = due_soonest
+        .first()
+        .or(closest_to_maturing.first())
+        .map(|s| s.message.clone());
+
+    ReviewSuggestions {
+        date: today_str,
+        headline,
+        due_soonest,
+        closest_to_maturing,
+    }
+}
+
+fn suggestion(detail: &PassageDetail, message: String) -> ReviewSuggestion {
+    ReviewSuggestion {
+        reference: detail.reference.clone(),
+        state: detail.state,
+        interval_days: detail.interval_days,
+        due_date: detail.due_date.clone(),
+        message,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detail(
+        reference: &str,
+        state: &str,
+        interval_days: i64,
+        due: Option<&str>,
+    ) -> PassageDetail {
+        PassageDetail {
+            reference: reference.to_string(),
+            verses: 1,
+            state: state.parse().unwrap(),
+            interval_days,
+            ease_percent: 250.0,
+            lapses: 0,
+            due_date: due.map(str::to_string),
+            last_review: None,
+        }
+    }
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 6, 10).unwrap()
+    }
+
+    #[test]
+    fn test_due_soonest_first() {
+        let details = vec![
+            detail("John 3:16", "young", 10, Some("2025-06-12")),
+            detail("Romans 12:1-2", "mature", 40, Some("2025-06-08")),
+            detail("Psalm 23", "learning", 1, Some("2025-06-10")),
+            detail("Jude 24", "suspended", 30, None),
+        ];
+
+        let suggestions = suggest_reviews(&details, today(), 5);
+        let messages: Vec<&str> = suggestions
+            .due_soonest
+            .iter()
+            .map(|s| s.message.as_str())
+            .collect();
+        assert_eq!(
+            messages,
+            [
+                "Review Romans 12:1-2 today to keep it mature",
+                "Review Psalm 23 today",
+                "John 3:16 is due 2025-06-12",
+            ]
+        );
+        assert_eq!(
+            suggestions.headline.as_deref(),
+            Some("Review Romans 12:1-2 today to keep it mature")
+        );
+    }
+
+    #[test]
+    fn test_closest_to_maturing() {
+        let details = vec![
+            detail("John 3:16", "young", 18, Some("2025-07-01")),
+            detail("Psalm 23", "learning", 1, Some("2025-06-11")),
+            detail("James 1:2", "young", 20, Some("2025-07-01")),
+            detail("Romans 8:28", "mature", 40, Some("2025-08-01")),
+            detail("Jude 24", "unseen", 0, None),
+        ];
+
+        let suggestions = suggest_reviews(&details, today(), 2);
+        let maturing: Vec<(&str, &str)> = suggestions
+            .closest_to_maturing
+            .iter()
+            .map(|s| (s.reference.as_str(), s.message.as_str()))
+            .collect();
+        assert_eq!(
+            maturing,
+            [
+                ("James 1:2", "James 1:2 is 1 day from maturing"),
+                ("John 3:16", "John 3:16 is 3 days from maturing"),
+            ]
+        );
+        assert_eq!(suggestions.due_soonest.len(), 2);
+    }
+
+    #[test]
+    fn test_no_passages() {
+        let suggestions = suggest_reviews(&[], today(), 5);
+        assert!(suggestions.headline.is_none());
+        assert_eq!(suggestions.date, "2025-06-10");
+    }
+}
@@ -1,3 +1,9 @@
+use std::collections::BTreeSet;
+use std::fmt;
+
+use crate::bible;
+use crate::book_name_parser::{normalize_book_name, split_reference, strip_formatting_chars};
+
 /// Checks if a book name is a single-chapter book in the Bible
 fn is_single_chapter_book(book_name: &str) -> bool {
     const SINGLE_CHAPTER_BOOKS: &[&str] = &["Obadiah", "Philemon", "2 John", "3 John", "Jude"];
@@ -7,84 +13,221 @@ fn is_single_chapter_book(book_name: &str) -> bool {
         .any(|&book| book_name.eq_ignore_ascii_case(book))
 }
 
-/// Parses a Bible verse reference and counts the number of verses it contains
+/// An inclusive span of verses, which may cross chapter boundaries
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VerseRange {
+    pub start_chapter: i64,
+    pub start_verse: i64,
+    pub end_chapter: i64,
+    pub end_verse: i64,
+}
+
+impl VerseRange {
+    /// Counts the verses in the range, looking up chapter lengths when it spans chapters
+    fn verse_count(&self, book: &str) -> Result<i64, String> {
+        if self.start_chapter == self.end_chapter {
+            return Ok(self.end_verse - self.start_verse + 1);
+        }
+
+        let chapter_length = |chapter| {
+            bible::chapter_verse_count(book, chapter)
+                .ok_or_else(|| format!("Unknown chapter {} {}", book, chapter))
+        };
+
+        let mut count = chapter_length(self.start_chapter)? - self.start_verse + 1;
+        for chapter in self.start_chapter + 1..self.end_chapter {
+            count += chapter_length(chapter)?;
+        }
+        Ok(count + self.end_verse)
+    }
+}
+
+impl fmt::Display for VerseRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.start_chapter, self.start_verse)?;
+        if self.start_chapter != self.end_chapter {
+            write!(f, "-{}:{}", self.end_chapter, self.end_verse)
+        } else if self.start_verse != self.end_verse {
+            write!(f, "-{}", self.end_verse)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// A Bible reference broken down into its book and verse ranges
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsedReference {
+    /// Book name as written, with "Psalm" normalized to "Psalms"
+    pub book: String,
+    pub ranges: Vec<VerseRange>,
+    /// Total number of verses across all ranges
+    pub verse_count: i64,
+}
+
+impl ParsedReference {
+    /// Formats the reference in a canonical form, e.g. "Psalms 1:1-2:12, 4:8"
+    pub fn normalized(&self) -> String {
+        let ranges: Vec<String> = self.ranges.iter().map(|r| r.to_string()).collect();
+        format!("{} {}", self.book, ranges.join(", "))
+    }
+
+    /// Expands the ranges into every (chapter, verse) they cover, or None if a range
+    /// crosses a chapter that doesn't exist
+    pub fn verses(&self) -> Option<BTreeSet<(i64, i64)>> {
+        let mut verses = BTreeSet::new();
+        for range in &self.ranges {
+            for chapter in range.start_chapter..=range.end_chapter {
+                let first = if chapter == range.start_chapter {
+                    range.start_verse
+                } else {
+                    1
+                };
+                let last = if chapter == range.end_chapter {
+                    range.end_verse
+                } else {
+                    bible::chapter_verse_count(&self.book, chapter)?
+                };
+                verses.extend((first..=last).map(|verse| (chapter, verse)));
+            }
+        }
+        Some(verses)
+    }
+}
+
+/// A chapter and/or verse as written in a reference ("3:16", "16", or "16a")
+#[derive(Debug, Clone, Copy)]
+enum Point {
+    ChapterVerse(i64, i64),
+    Number(i64),
+}
+
+fn parse_point(s: &str) -> Option<Point> {
+    match s.split_once(':') {
+        Some((chapter, verse)) => Some(Point::ChapterVerse(
+            chapter.parse().ok()?,
+            parse_verse_number(verse)?,
+        )),
+        None => Some(Point::Number(parse_verse_number(s)?)),
+    }
+}
+
+/// Parses a Bible reference into its book and verse ranges
 ///
 /// Supports:
-/// - Single verses: "Genesis 1:1" → 1
-/// - Simple ranges: "Genesis 1:1-5" → 5
-/// - Verse parts (letters are stripped): "Proverbs 12:4a" → 1, "Colossians 1:9a-12" → 4
-/// - Single-chapter books: "Jude 24-25" → 2 (no colon needed)
+/// - Single verses and ranges: "Genesis 1:1", "Genesis 1:1-5"
+/// - Verse parts (letters are stripped): "Proverbs 12:4a", "Colossians 1:9a-12"
+/// - Cross-chapter ranges: "John 7:53–8:11"
+/// - Whole chapters and chapter ranges: "Psalm 23", "Psalm 1–2"
+/// - Lists separated by commas or semicolons: "Romans 3:23, 6:23", "John 3:16, 18"
+/// - Single-chapter books without a chapter: "Jude 24-25"
 ///
-/// Returns an error if the reference cannot be parsed.
-pub fn try_count_verses_in_reference(reference: &str) -> Result<i64, String> {
-    // Strip any Unicode formatting characters (like zero-width spaces and directional marks)
-    let reference = reference
+/// In a list, a bare number continues the previous item: it is a verse after a verse
+/// ("John 3:16, 18") and a chapter after a whole chapter ("Psalm 1, 3"). En and em dashes
+/// are accepted as range separators.
+pub fn parse_reference(reference: &str) -> Result<ParsedReference, String> {
+    let reference = strip_formatting_chars(reference);
+    let (book, verse_part) = split_reference(&reference)?;
+    let book = normalize_book_name(&book);
+    let single_chapter = is_single_chapter_book(&book);
+
+    // Whitespace is insignificant inside the verse part ("Romans 5: 1 - 8")
+    let verse_part: String = verse_part
         .chars()
-        .filter(|c| {
-            !c.is_control()
-                && *c != '\u{200B}' // Zero Width Space
-                && *c != '\u{FEFF}' // Zero Width No-Break Space (BOM)
-                && *c != '\u{202A}' // Left-to-Right Embedding
-                && *c != '\u{202B}' // Right-to-Left Embedding
-                && *c != '\u{202C}' // Pop Directional Formatting
-                && *c != '\u{202D}' // Left-to-Right Override
-                && *c != '\u{202E}' // Right-to-Left Override
+        .filter(|c| !c.is_whitespace())
+        .map(|c| if c == '–' || c == '—' { '-' } else { c })
+        .collect();
+
+    let chapter_length = |chapter| {
+        bible::chapter_verse_count(&book, chapter).ok_or_else(|| {
+            format!(
+                "Unknown chapter {} {} in reference '{}'",
+                book, chapter, reference
+            )
         })
-        .collect::<String>();
-
-    // Find the last colon to extract the verse portion
-    let verse_part = match reference.rfind(':') {
-        Some(pos) => &reference[pos + 1..],
-        None => {
-            // No colon found - check if this is a single-chapter book
-            if let Some(space_pos) = reference.rfind(' ') {
-                let book_name = &reference[..space_pos];
-                if is_single_chapter_book(book_name) {
-                    // Extract verse numbers after the space
-                    &reference[space_pos + 1..]
-                } else {
-                    return Err(format!(
-                        "No colon found in reference '{}' (not a single-chapter book)",
-                        reference
-                    ));
-                }
-            } else {
-                return Err(format!(
-                    "No colon or space found in reference '{}'",
-                    reference
-                ));
+    };
+
+    let mut ranges = Vec::new();
+    // The chapter of the previous item, and whether that item was a whole chapter
+    let mut context: Option<(i64, bool)> = None;
+
+    for item in verse_part.split([',', ';']).filter(|item| !item.is_empty()) {
+        let (start_str, end_str) = match item.split_once('-') {
+            Some((start, end)) => (start, Some(end)),
+            None => (item, None),
+        };
+
+        let invalid = || format!("Could not parse '{}' in reference '{}'", item, reference);
+        let start = parse_point(start_str).ok_or_else(invalid)?;
+        let end = end_str
+            .map(|end| parse_point(end).ok_or_else(invalid))
+            .transpose()?;
+
+        // Resolve the start to a chapter and verse; whole chapters start at verse 1
+        let (start_chapter, start_verse, whole_chapters) = match start {
+            Point::ChapterVerse(chapter, verse) => (chapter, verse, false),
+            Point::Number(verse) if single_chapter => (1, verse, false),
+            Point::Number(verse) if context.is_some_and(|(_, whole)| !whole) => {
+                (context.map_or(1, |(chapter, _)| chapter), verse, false)
             }
+            Point::Number(chapter) => (chapter, 1, true),
+        };
+
+        let (end_chapter, end_verse) = match end {
+            Some(Point::ChapterVerse(chapter, verse)) => (chapter, verse),
+            Some(Point::Number(chapter)) if whole_chapters => (chapter, chapter_length(chapter)?),
+            Some(Point::Number(verse)) => (start_chapter, verse),
+            None if whole_chapters => (start_chapter, chapter_length(start_chapter)?),
+            None => (start_chapter, start_verse),
+        };
+
+        if (end_chapter, end_verse) < (start_chapter, start_verse) {
+            return Err(format!(
+                "Could not parse range '{}' in reference '{}'",
+                item, reference
+            ));
         }
-    };
 
-    // Strip any whitespace and remaining Unicode formatting characters
-    let verse_part = verse_part.trim();
+        ranges.push(VerseRange {
+            start_chapter,
+            start_verse,
+            end_chapter,
+            end_verse,
+        });
+        context = Some((end_chapter, whole_chapters));
+    }
 
-    // Check if it's a range (contains a hyphen)
-    if let Some(hyphen_pos) = verse_part.find('-') {
-        let start_str = verse_part[..hyphen_pos].trim();
-        let end_str = verse_part[hyphen_pos + 1..].trim();
+    if ranges.is_empty() {
+        return Err(format!("No verses found in reference '{}'", reference));
+    }
 
-        let start = parse_verse_number(start_str);
-        let end = parse_verse_number(end_str);
+    let verse_count = ranges
+        .iter()
+        .map(|range| range.verse_count(&book))
+        .sum::<Result<i64, String>>()
+        .map_err(|err| format!("{} in reference '{}'", err, reference))?;
+
+    Ok(ParsedReference {
+        book,
+        ranges,
+        verse_count,
+    })
+}
 
-        match (start, end) {
-            (Some(s), Some(e)) if e >= s => Ok(e - s + 1),
-            _ => Err(format!(
-                "Could not parse range '{}' in reference '{}'",
-                verse_part, reference
-            )),
-        }
-    } else {
-        // Single verse
-        match parse_verse_number(verse_part) {
-            Some(_) => Ok(1),
-            None => Err(format!(
-                "Could not parse verse '{}' in reference '{}'",
-                verse_part, reference
-            )),
-        }
-    }
+/// Parses a Bible verse reference and counts the number of verses it contains
+///
+/// Supports everything `parse_reference` does, for example:
+/// - Single verses: "Genesis 1:1" → 1
+/// - Simple ranges: "Genesis 1:1-5" → 5
+/// - Verse parts (letters are stripped): "Proverbs 12:4a" → 1, "Colossians 1:9a-12" → 4
+/// - Single-chapter books: "Jude 24-25" → 2 (no colon needed)
+/// - Cross-chapter ranges: "John 7:53–8:11" → 12
+/// - Chapter ranges: "Psalm 1–2" → 18
+/// - Lists: "Romans 3:23, 6:23" → 2
+///
+/// Returns an error if the reference cannot be parsed.
+pub fn try_count_verses_in_reference(reference: &str) -> Result<i64, String> {
+    parse_reference(reference).map(|parsed| parsed.verse_count)
 }
 
 /// Parses a Bible verse reference and counts the number of verses it contains
@@ -199,12 +342,12 @@ mod tests {
     #[test]
     fn test_invalid_references_fallback_to_one() {
         // count_verses_in_reference should return 1 for invalid references
-        assert_eq!(count_verses_in_reference("Genesis 1"), 1);
+        assert_eq!(count_verses_in_reference("Genesis"), 1);
         assert_eq!(count_verses_in_reference("Genesis 1:abc"), 1);
         assert_eq!(count_verses_in_reference("Genesis 1:5-1"), 1);
 
         // try_count_verses_in_reference should return errors
-        assert!(try_count_verses_in_reference("Genesis 1").is_err());
+        assert!(try_count_verses_in_reference("Genesis").is_err());
         assert!(try_count_verses_in_reference("Genesis 1:abc").is_err());
         assert!(try_count_verses_in_reference("Genesis 1:5-1").is_err());
     }
@@ -263,4 +406,53 @@ mod tests {
             1
         );
     }
+
+    #[test]
+    fn test_cross_chapter_range() {
+        // John 7 has 53 verses
+        assert_eq!(try_count_verses_in_reference("John 7:53–8:11"), Ok(12));
+        assert_eq!(try_count_verses_in_reference("John 7:53-8:11"), Ok(12));
+        // Spanning a full chapter in the middle: 2 + 25 (John 2) + 1
+        assert_eq!(try_count_verses_in_reference("John 1:50-3:1"), Ok(28));
+        assert!(try_count_verses_in_reference("John 8:11-7:53").is_err());
+    }
+
+    #[test]
+    fn test_whole_chapters() {
+        assert_eq!(try_count_verses_in_reference("Psalm 23"), Ok(6));
+        assert_eq!(try_count_verses_in_reference("Psalm 1–2"), Ok(18));
+        assert_eq!(try_count_verses_in_reference("Psalm 1, 3"), Ok(14));
+        assert_eq!(try_count_verses_in_reference("Genesis 1"), Ok(31));
+        assert!(try_count_verses_in_reference("Psalm 151").is_err());
+    }
+
+    #[test]
+    fn test_verse_lists() {
+        assert_eq!(try_count_verses_in_reference("Romans 3:23, 6:23"), Ok(2));
+        assert_eq!(try_count_verses_in_reference("John 3:16, 18"), Ok(2));
+        assert_eq!(try_count_verses_in_reference("1 John 1:9; 2:1-2"), Ok(3));
+        assert_eq!(try_count_verses_in_reference("Jude 1, 24-25"), Ok(3));
+        assert!(try_count_verses_in_reference("John 3:16,").is_ok());
+        assert!(try_count_verses_in_reference("John 3:16, x").is_err());
+    }
+
+    #[test]
+    fn test_parse_reference_ranges() {
+        let parsed = parse_reference("Psalm 1–2, 4:8").unwrap();
+        assert_eq!(parsed.book, "Psalms");
+        assert_eq!(parsed.verse_count, 19);
+        assert_eq!(
+            parsed.ranges[0],
+            VerseRange {
+                start_chapter: 1,
+                start_verse: 1,
+                end_chapter: 2,
+                end_verse: 12,
+            }
+        );
+        assert_eq!(parsed.normalized(), "Psalms 1:1-2:12, 4:8");
+
+        let parsed = parse_reference("Jude 24-25").unwrap();
+        assert_eq!(parsed.normalized(), "Jude 1:24-25");
+    }
 }
@@ -0,0 +1,55 @@
+use ankistats::{
+    get_bible_stats, get_last_30_days_stats, get_schema_version, get_today_study_time,
+};
+use chrono::Utc;
+use statsutils::testing::AnkiFixture;
+
+/// Builds a small collection with one mature, one learning, and one unseen passage, and
+/// checks the public stats functions against it end to end
+#[test]
+fn test_stats_from_built_collection() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("collection.anki2");
+    let now_ms = Utc::now().timestamp_millis();
+
+    let mut anki = AnkiFixture::create(&path, now_ms / 1000 - 400 * 86_400).unwrap();
+    let deck = anki.deck("Bible::Verses").unwrap();
+    let other_deck = anki.deck("Default").unwrap();
+    let model = anki.notetype("Bible Verse").unwrap();
+
+    let mature = anki.note(model, "John 3:16", "").unwrap();
+    for ord in 0..2 {
+        let card = anki.review_card(mature, deck, ord, 30, 410).unwrap();
+        anki.review(card, now_ms, 90_000, 3, 30).unwrap();
+    }
+
+    let learning = anki.note(model, "Romans 8:1-4", "").unwrap();
+    let card = anki.review_card(learning, deck, 0, 2, 401).unwrap();
+    anki.review(card, now_ms, 30_000, 1, 2).unwrap();
+    anki.new_card(learning, deck, 1).unwrap();
+
+    let unseen = anki.note(model, "Psalm 23:1", "").unwrap();
+    anki.new_card(unseen, deck, 0).unwrap();
+
+    // Reviews outside the Bible deck don't count
+    let elsewhere = anki.note(model, "Genesis 1:1", "").unwrap();
+    let card = anki.review_card(elsewhere, other_deck, 0, 5, 405).unwrap();
+    anki.review(card, now_ms, 600_000, 3, 5).unwrap();
+
+    let db_path = path.to_str().unwrap();
+    assert_eq!(get_schema_version(db_path).unwrap(), 18);
+    assert_eq!(get_today_study_time(db_path).unwrap(), 3.5);
+
+    let days = get_last_30_days_stats(db_path).unwrap();
+    assert_eq!(days.len(), 30);
+    assert_eq!(days.last().unwrap().minutes, 3.5);
+
+    let stats = get_bible_stats(db_path).unwrap();
+    let nt = &stats.new_testament;
+    assert_eq!(nt.mature_passages, 1);
+    assert_eq!(nt.learning_passages, 1);
+    assert_eq!(nt.learning_verses, 4);
+    let ot = &stats.old_testament;
+    assert_eq!(ot.unseen_passages, 1);
+    assert_eq!(ot.mature_passages, 0);
+}
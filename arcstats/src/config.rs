@@ -0,0 +1,98 @@
+use std::sync::RwLock;
+
+/// Places counted as church by default, whatever their Google place type
+pub const DEFAULT_CHURCH_PLACES: [&str; 1] = ["Martin Luther Church"];
+
+/// Places counted as home by default
+pub const DEFAULT_HOME_PLACES: [&str; 1] = ["Home"];
+
+/// Category for Sunday services, and for churches without a configured category
+pub const SERVICE_CATEGORY: &str = "service";
+
+/// A place whose visits count as church attendance of a particular kind
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChurchPlace {
+    /// Place name as it appears in the Arc Timeline export
+    pub name: String,
+    /// Kind of attendance, e.g. "service", "small_group", or "volunteering"
+    pub category: String,
+}
+
+impl ChurchPlace {
+    /// A place where Sunday services are attended
+    pub fn service(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            category: SERVICE_CATEGORY.to_string(),
+        }
+    }
+}
+
+static CHURCH_PLACES: RwLock<Option<Vec<ChurchPlace>>> = RwLock::new(None);
+static HOME_PLACES: RwLock<Option<Vec<String>>> = RwLock::new(None);
+
+/// Sets which place names always count as church services, for stats computed afterwards
+///
+/// Places whose Google type is "church" or whose name contains "Church" count as well.
+pub fn set_church_places(names: Vec<String>) {
+    set_church_place_categories(
+        names
+            .iter()
+            .map(|name| ChurchPlace::service(name))
+            .collect(),
+    );
+}
+
+/// Sets which places always count as church and the kind of attendance each one is, for
+/// stats computed afterwards
+pub fn set_church_place_categories(places: Vec<ChurchPlace>) {
+    *CHURCH_PLACES.write().unwrap_or_else(|e| e.into_inner()) = Some(places);
+}
+
+/// Returns the places currently counted as church, with their categories
+pub fn church_place_categories() -> Vec<ChurchPlace> {
+    CHURCH_PLACES
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+        .unwrap_or_else(|| {
+            DEFAULT_CHURCH_PLACES
+                .iter()
+                .map(|name| ChurchPlace::service(name))
+                .collect()
+        })
+}
+
+/// Returns the place names currently counted as church
+pub fn church_places() -> Vec<String> {
+    church_place_categories()
+        .into_iter()
+        .map(|place| place.name)
+        .collect()
+}
+
+/// Returns every attendance category, sorted, always including `SERVICE_CATEGORY`
+pub fn church_categories() -> Vec<String> {
+    let mut categories: Vec<String> = church_place_categories()
+        .into_iter()
+        .map(|place| place.category)
+        .collect();
+    categories.push(SERVICE_CATEGORY.to_string());
+    categories.sort();
+    categories.dedup();
+    categories
+}
+
+/// Sets which place names count as home, for stats computed afterwards
+pub fn set_home_places(names: Vec<String>) {
+    *HOME_PLACES.write().unwrap_or_else(|e| e.into_inner()) = Some(names);
+}
+
+/// Returns the place names currently counted as home
+pub fn home_places() -> Vec<String> {
+    HOME_PLACES
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+        .unwrap_or_else(|| DEFAULT_HOME_PLACES.iter().map(|s| s.to_string()).collect())
+}
@@ -23,8 +23,11 @@
 //! }
 //! ```
 
+pub mod config;
 pub mod loader;
 pub mod models;
+#[cfg(feature = "tokio")]
+pub mod nonblocking;
 pub mod stats;
 
 // Re-export commonly used types and functions
@@ -36,4 +39,7 @@ pub use models::{
     BaseItem, ExportStats, Item, ItemWithPlace, Metadata, Place, TripDetails, VisitDetails,
     parse_iso8601_timestamp,
 };
-pub use stats::{WeekStats, get_last_12_weeks_stats};
+pub use stats::{
+    DayStats, PeriodStats, WeekStats, get_church_dates, get_home_stats_for_period,
+    get_last_12_weeks_stats, get_last_30_days_stats, get_stats_for_period, get_today_church_time,
+};
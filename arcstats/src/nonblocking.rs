@@ -0,0 +1,46 @@
+//! Async versions of the church attendance and place entry points, run on tokio's
+//! blocking thread pool
+//!
+//! Each function takes the same arguments as its namesake in `stats`.
+
+use anyhow::Result;
+use statsutils::DatePeriod;
+use statsutils::nonblocking::run;
+
+use crate::stats::{self, DayStats, PeriodStats, PlaceStats, WeekStats};
+
+/// Async `stats::get_today_church_time`
+pub async fn get_today_church_time(export_path: &str) -> Result<f64> {
+    let export_path = export_path.to_string();
+    run(move || stats::get_today_church_time(&export_path)).await
+}
+
+/// Async `stats::get_last_30_days_stats`
+pub async fn get_last_30_days_stats(export_path: &str) -> Result<Vec<DayStats>> {
+    let export_path = export_path.to_string();
+    run(move || stats::get_last_30_days_stats(&export_path)).await
+}
+
+/// Async `stats::get_last_12_weeks_stats`
+pub async fn get_last_12_weeks_stats(export_path: &str) -> Result<Vec<WeekStats>> {
+    let export_path = export_path.to_string();
+    run(move || stats::get_last_12_weeks_stats(&export_path)).await
+}
+
+/// Async `stats::get_stats_for_period`
+pub async fn get_stats_for_period(
+    export_path: &str,
+    period: &DatePeriod,
+) -> Result<Vec<PeriodStats>> {
+    let (export_path, period) = (export_path.to_string(), period.clone());
+    run(move || stats::get_stats_for_period(&export_path, &period)).await
+}
+
+/// Async `stats::get_top_places_last_6_months`
+pub async fn get_top_places_last_6_months(
+    export_path: &str,
+    limit: usize,
+) -> Result<Vec<PlaceStats>> {
+    let export_path = export_path.to_string();
+    run(move || stats::get_top_places_last_6_months(&export_path, limit)).await
+}
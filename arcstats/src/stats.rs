@@ -1,20 +1,17 @@
 use anyhow::Result;
-use chrono::{DateTime, Datelike, Duration, Timelike, Utc};
-use chrono_tz::America::Chicago;
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Timelike, Utc, Weekday};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use utoipa::ToSchema;
 
+use crate::config::{SERVICE_CATEGORY, church_categories, church_place_categories, home_places};
 use crate::loader::load_all_items_with_places;
 use crate::models::Place;
-use statsutils::DatePeriod;
-
-const MARTIN_LUTHER_CHURCH: &str = "Martin Luther Church";
-const ROLLOVER_HOUR: u32 = 4;
+use statsutils::{DatePeriod, PeriodConfig, series::densify, split_minutes_by_day};
 
 /// Checks if a place is a church based on Google place type or place name
-fn is_church(place: &Place) -> bool {
-    if place.name == MARTIN_LUTHER_CHURCH {
+fn is_church(place: &Place, church_places: &[String]) -> bool {
+    if church_places.contains(&place.name) {
         return true;
     }
 
@@ -28,31 +25,55 @@ fn is_church(place: &Place) -> bool {
     place.name.contains("Church")
 }
 
-/// Checks if a visit time falls on a Sunday morning (4 AM–1 PM Chicago time).
-/// Uses the same 4 AM rollover as the rest of the module.
-fn is_sunday_morning(dt: DateTime<Utc>) -> bool {
-    let dt_chicago = dt.with_timezone(&Chicago);
-    let hour = dt_chicago.hour();
+/// Checks if a visit time falls on a Sunday morning (rollover hour to 1 PM local time).
+/// Uses the same rollover as the rest of the workspace.
+fn is_sunday_morning(config: &PeriodConfig, dt: DateTime<Utc>) -> Result<bool> {
+    let local = config.timezone.local_datetime(dt.timestamp_millis())?;
+    let hour = local.hour() as i64;
 
-    // Apply 4 AM rollover: before 4 AM counts as previous day
-    if hour < ROLLOVER_HOUR {
-        return false; // Before rollover, so this is really Saturday night
+    // Before the rollover, so this is really Saturday night
+    if hour < config.rollover_hour {
+        return Ok(false);
     }
 
     // After rollover, check if it's Sunday and morning (before 1 PM)
-    dt_chicago.weekday() == chrono::Weekday::Sun && hour < 13
+    Ok(local.weekday() == Weekday::Sun && hour < 13)
+}
+
+/// Daily statistics for church attendance
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[schema(as = ChurchDayStats)]
+pub struct DayStats {
+    /// Date in YYYY-MM-DD format
+    pub date: String,
+    /// Time spent at church in minutes
+    pub minutes: f64,
+}
+
+/// Church attendance for one bucket (day, week, or month) of an arbitrary period
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PeriodStats {
+    /// First day of the bucket: YYYY-MM-DD for days and weeks, YYYY-MM for months
+    pub period_start: String,
+    /// Time spent at church in minutes
+    pub minutes: f64,
 }
 
 /// Weekly statistics for church attendance
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[schema(as = ChurchWeekStats)]
 pub struct WeekStats {
-    /// Week start date in YYYY-MM-DD format (Sunday)
+    /// Week start date in YYYY-MM-DD format (Sunday unless the workspace's week start is
+    /// configured otherwise)
     pub week_start: String,
     /// Time spent at church in minutes
     pub minutes: f64,
-    /// Daily breakdown: [Sunday, Monday, Tuesday, Wednesday, Thursday, Friday, Saturday]
-    /// Index 0 = Sunday, Index 6 = Saturday
+    /// Daily breakdown, one entry per day from the start of the week
+    /// Index 0 = Sunday, Index 6 = Saturday with the default week start
     pub daily_minutes: Vec<f64>,
+    /// Minutes for each kind of attendance (e.g. "service", "small_group"), with every
+    /// configured category present
+    pub category_minutes: BTreeMap<String, f64>,
 }
 
 /// Statistics for a single place showing time spent
@@ -64,46 +85,96 @@ pub struct PlaceStats {
     pub hours: f64,
 }
 
-/// Converts a UTC datetime to a week start date string (YYYY-MM-DD)
-/// Applies 4 AM rollover and finds the most recent Sunday in Chicago timezone
-fn get_week_start_for_datetime(dt: DateTime<Utc>) -> String {
-    // Convert to Chicago timezone
-    let dt_chicago = dt.with_timezone(&Chicago);
+/// Loads every church visit in the export as (start, end, category), with times in
+/// milliseconds
+///
+/// The configured church places count any time, in their own category; other churches
+/// only on Sunday mornings, as services.
+fn load_church_visits(export_path: &str, config: &PeriodConfig) -> Result<Vec<(i64, i64, String)>> {
+    // Load all items with their associated places
+    let items = load_all_items_with_places(export_path)?;
+    let church_places = church_place_categories();
+    let church_names: Vec<String> = church_places.iter().map(|p| p.name.clone()).collect();
 
-    // Apply 4 AM rollover: if before 4 AM, consider it part of previous day
-    let adjusted_dt = if dt_chicago.hour() < ROLLOVER_HOUR {
-        dt_chicago - Duration::hours(24)
-    } else {
-        dt_chicago
-    };
+    // Filter for visits at any church, keeping each visit's start and end in milliseconds
+    let mut church_visits: Vec<(i64, i64, String)> = Vec::new();
 
-    // Calculate days since last Sunday (0 if today is Sunday)
-    let days_since_sunday = adjusted_dt.weekday().num_days_from_sunday();
+    for item_with_place in items {
+        // Skip if not a visit
+        if !item_with_place.item.base.is_visit {
+            continue;
+        }
 
-    // Go back to the most recent Sunday
-    let week_start = adjusted_dt - Duration::days(days_since_sunday as i64);
+        // Include church visits: the configured church places any time,
+        // other churches only on Sunday mornings
+        if let Some(place) = &item_with_place.place
+            && is_church(place, &church_names)
+        {
+            let start = item_with_place.item.start_datetime();
+            let own_church = church_places.iter().find(|p| p.name == place.name);
+
+            let category = match own_church {
+                Some(own_church) => Some(own_church.category.clone()),
+                None if is_sunday_morning(config, start)? => Some(SERVICE_CATEGORY.to_string()),
+                None => None,
+            };
+            if let Some(category) = category {
+                let end = item_with_place.item.end_datetime();
+                church_visits.push((start.timestamp_millis(), end.timestamp_millis(), category));
+            }
+        }
+    }
 
-    // Format as YYYY-MM-DD
-    week_start.format("%Y-%m-%d").to_string()
+    Ok(church_visits)
 }
 
-/// Gets day of week index (0=Sunday, 6=Saturday) for a datetime with 4 AM rollover
-fn get_day_of_week_index(dt: DateTime<Utc>) -> usize {
-    // Convert to Chicago timezone
-    let dt_chicago = dt.with_timezone(&Chicago);
+/// Minutes at church on each logical day by category, splitting visits that run past the
+/// rollover hour
+fn church_category_minutes_by_day(
+    export_path: &str,
+    config: &PeriodConfig,
+) -> Result<HashMap<NaiveDate, BTreeMap<String, f64>>> {
+    let mut daily_minutes: HashMap<NaiveDate, BTreeMap<String, f64>> = HashMap::new();
+    for (start_ms, end_ms, category) in load_church_visits(export_path, config)? {
+        for (date, minutes) in split_minutes_by_day(start_ms, end_ms, config)? {
+            *daily_minutes
+                .entry(date)
+                .or_default()
+                .entry(category.clone())
+                .or_insert(0.0) += minutes;
+        }
+    }
+    Ok(daily_minutes)
+}
 
-    // Apply 4 AM rollover: if before 4 AM, consider it part of previous day
-    let adjusted_dt = if dt_chicago.hour() < ROLLOVER_HOUR {
-        dt_chicago - Duration::hours(24)
-    } else {
-        dt_chicago
-    };
+/// Minutes at church on each logical day, across every category
+fn church_minutes_by_day(
+    export_path: &str,
+    config: &PeriodConfig,
+) -> Result<HashMap<NaiveDate, f64>> {
+    Ok(church_category_minutes_by_day(export_path, config)?
+        .into_iter()
+        .map(|(date, categories)| (date, categories.values().sum()))
+        .collect())
+}
 
-    // Get day of week (0=Sunday, 6=Saturday)
-    adjusted_dt.weekday().num_days_from_sunday() as usize
+/// Minutes of `visits` in each bucket of `period`, splitting visits that cross a bucket
+/// boundary (such as the rollover hour)
+fn bucket_minutes(
+    visits: impl IntoIterator<Item = (i64, i64)>,
+    period: &DatePeriod,
+    config: &PeriodConfig,
+) -> Result<HashMap<String, f64>> {
+    let mut minutes: HashMap<String, f64> = HashMap::new();
+    for (start_ms, end_ms) in visits {
+        for (key, visit_minutes) in period.split_interval(start_ms, end_ms, config)? {
+            *minutes.entry(key).or_insert(0.0) += visit_minutes;
+        }
+    }
+    Ok(minutes)
 }
 
-/// Gets church attendance statistics for the last 12 weeks
+/// Gets church attendance for each of the last 30 days
 ///
 /// # Arguments
 ///
@@ -111,54 +182,148 @@ fn get_day_of_week_index(dt: DateTime<Utc>) -> usize {
 ///
 /// # Returns
 ///
-/// A vector of 12 WeekStats, one for each week, in chronological order.
-/// Weeks without church visits will have 0 minutes.
-pub fn get_last_12_weeks_stats(export_path: &str) -> Result<Vec<WeekStats>> {
-    // Get the period data for the last 12 weeks
-    let period = DatePeriod::last_12_weeks()?;
+/// A vector of 30 DayStats in chronological order. Days without church visits have 0
+/// minutes.
+pub fn get_last_30_days_stats(export_path: &str) -> Result<Vec<DayStats>> {
+    let config = PeriodConfig::default();
+    let period = DatePeriod::last_30_days_with_config(&config)?;
+
+    let visits = load_church_visits(export_path, &config)?;
+    let daily_minutes = bucket_minutes(
+        visits
+            .into_iter()
+            .map(|(start_ms, end_ms, _)| (start_ms, end_ms)),
+        &period,
+        &config,
+    )?;
+
+    Ok(densify(daily_minutes, &period)?.map(|date, minutes| DayStats { date, minutes }))
+}
 
-    // Load all items with their associated places
-    let items = load_all_items_with_places(export_path)?;
+/// Gets church attendance for each bucket of a period, at the period's granularity
+///
+/// Buckets without church visits have 0 minutes.
+pub fn get_stats_for_period(export_path: &str, period: &DatePeriod) -> Result<Vec<PeriodStats>> {
+    let config = PeriodConfig::default();
+
+    let visits = load_church_visits(export_path, &config)?;
+    let minutes = bucket_minutes(
+        visits
+            .into_iter()
+            .map(|(start_ms, end_ms, _)| (start_ms, end_ms)),
+        period,
+        &config,
+    )?;
+
+    Ok(
+        densify(minutes, period)?.map(|period_start, minutes| PeriodStats {
+            period_start,
+            minutes,
+        }),
+    )
+}
 
-    // Filter for visits at any church and calculate duration in minutes for each visit
-    let mut church_visits: Vec<(DateTime<Utc>, f64)> = Vec::new();
+/// Gets the time spent at church today, in minutes
+pub fn get_today_church_time(export_path: &str) -> Result<f64> {
+    let config = PeriodConfig::default();
+    let today = config.today()?;
+    let daily_minutes = church_minutes_by_day(export_path, &config)?;
+    Ok(daily_minutes.get(&today).copied().unwrap_or(0.0))
+}
 
-    for item_with_place in items {
-        // Skip if not a visit
+/// Gets every logical day with any time at church, oldest first
+pub fn get_church_dates(export_path: &str) -> Result<Vec<NaiveDate>> {
+    let mut dates: Vec<NaiveDate> = church_minutes_by_day(export_path, &PeriodConfig::default())?
+        .into_iter()
+        .filter(|(_, minutes)| *minutes > 0.0)
+        .map(|(date, _)| date)
+        .collect();
+    dates.sort();
+    Ok(dates)
+}
+
+/// Start and end (milliseconds) of every visit to a home place
+fn load_home_visits(export_path: &str) -> Result<Vec<(i64, i64)>> {
+    let home_places = home_places();
+    let mut visits = Vec::new();
+
+    for item_with_place in load_all_items_with_places(export_path)? {
         if !item_with_place.item.base.is_visit {
             continue;
         }
-
-        // Include church visits: Martin Luther Church any time,
-        // other churches only on Sunday mornings
-        if let Some(place) = &item_with_place.place
-            && is_church(place)
-        {
-            let start = item_with_place.item.start_datetime();
-            let is_martin_luther = place.name == MARTIN_LUTHER_CHURCH;
-
-            if is_martin_luther || is_sunday_morning(start) {
-                let duration_minutes = item_with_place.item.duration_seconds() / 60.0;
-                church_visits.push((start, duration_minutes));
-            }
+        let Some(place) = &item_with_place.place else {
+            continue;
+        };
+        if !home_places.contains(&place.name) {
+            continue;
         }
+
+        visits.push((
+            item_with_place.item.start_datetime().timestamp_millis(),
+            item_with_place.item.end_datetime().timestamp_millis(),
+        ));
     }
+    Ok(visits)
+}
 
-    // Track both total and daily breakdown per week
-    // HashMap<week_start, (total_minutes, [daily_minutes; 7])>
-    let mut weekly_data: HashMap<String, (f64, [f64; 7])> = HashMap::new();
+/// Gets the time spent at home (see `config::home_places`) for each bucket of a period, at
+/// the period's granularity
+///
+/// Buckets without time at home have 0 minutes.
+pub fn get_home_stats_for_period(
+    export_path: &str,
+    period: &DatePeriod,
+) -> Result<Vec<PeriodStats>> {
+    let config = PeriodConfig::default();
+    let minutes = bucket_minutes(load_home_visits(export_path)?, period, &config)?;
+
+    Ok(
+        densify(minutes, period)?.map(|period_start, minutes| PeriodStats {
+            period_start,
+            minutes,
+        }),
+    )
+}
 
-    for (visit_time, minutes) in church_visits {
-        let week_start = get_week_start_for_datetime(visit_time);
-        let day_index = get_day_of_week_index(visit_time);
+/// A week's total minutes, daily breakdown, and minutes by category
+type WeekTotals = (f64, [f64; 7], BTreeMap<String, f64>);
 
-        let entry = weekly_data.entry(week_start).or_insert((0.0, [0.0; 7]));
+/// Gets church attendance statistics for the last 12 weeks
+///
+/// # Arguments
+///
+/// * `export_path` - Path to the Arc Timeline export directory containing places/, items/, and metadata.json
+///
+/// # Returns
+///
+/// A vector of 12 WeekStats, one for each week, in chronological order.
+/// Weeks without church visits will have 0 minutes.
+pub fn get_last_12_weeks_stats(export_path: &str) -> Result<Vec<WeekStats>> {
+    // Get the period data for the last 12 weeks
+    let config = PeriodConfig::default();
+    let period = DatePeriod::last_12_weeks_with_config(&config)?;
+
+    // Track the total, daily breakdown, and categories per week
+    let categories: BTreeMap<String, f64> = church_categories()
+        .into_iter()
+        .map(|category| (category, 0.0))
+        .collect();
+    let mut weekly_data: HashMap<String, WeekTotals> = HashMap::new();
+
+    for (date, day_categories) in church_category_minutes_by_day(export_path, &config)? {
+        let week_start = config.week_key(date);
+        let day_index = config.day_of_week_index(date);
+        let minutes: f64 = day_categories.values().sum();
+
+        let entry = weekly_data.entry(week_start).or_default();
         entry.0 += minutes; // Total minutes
         entry.1[day_index] += minutes; // Daily breakdown
+        for (category, minutes) in day_categories {
+            *entry.2.entry(category).or_insert(0.0) += minutes;
+        }
     }
-
     // Build results for all 12 weeks, filling gaps with 0 minutes
-    let results = period.build_results(weekly_data, |date, (total, daily)| {
+    let results = period.build_results(weekly_data, |date, (total, daily, by_category)| {
         // Validation: sum of daily should equal total (within rounding tolerance)
         debug_assert!(
             (daily.iter().sum::<f64>() - total).abs() < 0.1,
@@ -168,10 +333,15 @@ pub fn get_last_12_weeks_stats(export_path: &str) -> Result<Vec<WeekStats>> {
             total
         );
 
+        // Every category gets an entry, even in weeks without any of it
+        let mut category_minutes = categories.clone();
+        category_minutes.extend(by_category);
+
         WeekStats {
             week_start: date,
             minutes: total,
             daily_minutes: daily.to_vec(),
+            category_minutes,
         }
     });
 
@@ -188,7 +358,7 @@ pub fn get_last_12_weeks_stats(export_path: &str) -> Result<Vec<WeekStats>> {
 /// # Returns
 ///
 /// A vector of PlaceStats sorted by hours descending (most time first).
-/// Excludes the place named "Home".
+/// Excludes home places (see `config::home_places`).
 pub fn get_top_places_last_6_months(export_path: &str, limit: usize) -> Result<Vec<PlaceStats>> {
     const DAYS_IN_6_MONTHS: i64 = 182;
 
@@ -198,6 +368,7 @@ pub fn get_top_places_last_6_months(export_path: &str, limit: usize) -> Result<V
 
     // Load all items with their associated places
     let items = load_all_items_with_places(export_path)?;
+    let home_places = home_places();
 
     // Collect visits with place names and durations
     let mut place_durations: HashMap<String, f64> = HashMap::new();
@@ -213,8 +384,8 @@ pub fn get_top_places_last_6_months(export_path: &str, limit: usize) -> Result<V
             continue;
         };
 
-        // Skip if place name is "Home"
-        if place.name == "Home" {
+        // Skip time at home
+        if home_places.contains(&place.name) {
             continue;
         }
 
@@ -259,6 +430,7 @@ mod tests {
             week_start: "2025-10-19".to_string(),
             minutes: 120.5,
             daily_minutes: vec![120.5, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0],
+            category_minutes: BTreeMap::from([("service".to_string(), 120.5)]),
         };
 
         assert_eq!(stats.week_start, "2025-10-19");
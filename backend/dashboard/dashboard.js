@@ -0,0 +1,192 @@
+// Lifestats dashboard: reads the JSON endpoints with the API key saved in this browser
+// and draws the charts as inline SVG, so there is nothing to build or install.
+
+const SOURCES = [
+  { key: "anki_minutes", label: "Anki", color: "var(--anki)" },
+  { key: "reading_minutes", label: "Reading", color: "var(--reading)" },
+  { key: "at_church_minutes", label: "Church", color: "var(--church)" },
+  { key: "prayer_minutes", label: "Prayer", color: "var(--prayer)" },
+];
+
+const API_KEY_STORAGE = "lifestats-api-key";
+const SVG = "http://www.w3.org/2000/svg";
+
+function formatMinutes(minutes) {
+  if (minutes > 0 && minutes < 0.5) return "<1m";
+  const total = Math.round(minutes);
+  const hours = Math.floor(total / 60);
+  const mins = total % 60;
+  if (hours === 0) return `${mins}m`;
+  return mins === 0 ? `${hours}h` : `${hours}h ${mins}m`;
+}
+
+function element(tag, attributes = {}, text) {
+  const node = document.createElement(tag);
+  Object.assign(node, attributes);
+  if (text !== undefined) node.textContent = text;
+  return node;
+}
+
+function svgElement(tag, attributes) {
+  const node = document.createElementNS(SVG, tag);
+  for (const [name, value] of Object.entries(attributes)) {
+    node.setAttribute(name, value);
+  }
+  return node;
+}
+
+async function fetchJson(path) {
+  const response = await fetch(path, {
+    headers: { Authorization: `Bearer ${localStorage.getItem(API_KEY_STORAGE) || ""}` },
+  });
+  if (response.status === 401) throw new Error("Invalid or missing API key");
+  if (!response.ok) {
+    const body = await response.json().catch(() => ({}));
+    throw new Error(body.error || `${path} failed with status ${response.status}`);
+  }
+  return response.json();
+}
+
+function renderToday(today) {
+  const container = document.getElementById("today");
+  container.replaceChildren();
+  const items = SOURCES.map((source) => [source.label, today[source.key]]);
+  items.push(["Total", today.total_minutes]);
+  for (const [label, minutes] of items) {
+    const item = element("div");
+    item.append(element("span", { className: "value" }, formatMinutes(minutes)));
+    item.append(element("span", { className: "label" }, label));
+    container.append(item);
+  }
+}
+
+function renderLegend() {
+  const legend = document.getElementById("legend");
+  legend.replaceChildren();
+  for (const source of SOURCES) {
+    const item = element("span");
+    const swatch = element("i");
+    swatch.style.background = source.color;
+    item.append(swatch, source.label);
+    legend.append(item);
+  }
+}
+
+// Stacked bar chart of each source's minutes per row, labelled every `labelEvery` rows
+function renderStackedChart(containerId, rows, labelKey, labelEvery) {
+  const container = document.getElementById(containerId);
+  const width = 1000;
+  const height = 220;
+  const bottom = 20;
+  const chartHeight = height - bottom;
+  const totals = rows.map((row) => SOURCES.reduce((sum, s) => sum + (row[s.key] || 0), 0));
+  const max = Math.max(1, ...totals);
+  const slot = width / Math.max(rows.length, 1);
+
+  const svg = svgElement("svg", { viewBox: `0 0 ${width} ${height}`, preserveAspectRatio: "none" });
+  rows.forEach((row, i) => {
+    let y = chartHeight;
+    for (const source of SOURCES) {
+      const minutes = row[source.key] || 0;
+      if (minutes <= 0) continue;
+      const barHeight = (minutes / max) * (chartHeight - 10);
+      y -= barHeight;
+      const rect = svgElement("rect", {
+        x: i * slot + slot * 0.15,
+        y,
+        width: slot * 0.7,
+        height: barHeight,
+        style: `fill: ${source.color}`,
+      });
+      const title = svgElement("title", {});
+      title.textContent = `${row[labelKey]} ${source.label}: ${formatMinutes(minutes)}`;
+      rect.append(title);
+      svg.append(rect);
+    }
+    if (i % labelEvery === 0) {
+      const label = svgElement("text", { x: i * slot + slot / 2, y: height - 5, "text-anchor": "middle" });
+      label.textContent = row[labelKey].slice(5);
+      svg.append(label);
+    }
+  });
+  container.replaceChildren(svg);
+}
+
+// Horizontal bars scaled so `max` fills the track (the largest value by default)
+function renderBars(containerId, rows, max = Math.max(1, ...rows.map((row) => row.value))) {
+  const container = document.getElementById(containerId);
+  container.replaceChildren();
+  for (const row of rows) {
+    const bar = element("div", { className: "bar-row" });
+    const track = element("div", { className: "bar-track" });
+    const fill = element("div", { className: "bar-fill" });
+    fill.style.width = `${Math.min(row.value / max, 1) * 100}%`;
+    fill.style.background = row.color;
+    track.append(fill);
+    bar.append(element("span", { className: "name", title: row.name }, row.name), track);
+    bar.append(element("span", { className: "muted" }, row.label));
+    container.append(bar);
+  }
+}
+
+function renderPlaces(places) {
+  renderBars(
+    "places",
+    places.map((place) => ({
+      name: place.place_name,
+      value: place.hours,
+      label: `${place.hours.toFixed(1)} h`,
+      color: "var(--church)",
+    })),
+  );
+}
+
+// Books with anything learned, by share of verses mature, with the whole-Bible total first
+function renderBooks(bible) {
+  const books = [...bible.old_testament.book_stats, ...bible.new_testament.book_stats]
+    .filter((book) => book.learned_percent > 0)
+    .sort((a, b) => b.mature_percent - a.mature_percent);
+  const rows = [{ book: "Whole Bible", mature_percent: bible.mature_percent }, ...books];
+  renderBars(
+    "books",
+    rows.map((book) => ({
+      name: book.book,
+      value: book.mature_percent,
+      label: `${book.mature_percent.toFixed(1)}%`,
+      color: "var(--anki)",
+    })),
+    // A full bar means every verse in the book is mature
+    100,
+  );
+}
+
+// Each panel loads on its own, so a missing source (like an unset Arc export) only
+// blanks its panel
+async function load() {
+  const status = document.getElementById("status");
+  status.textContent = "";
+  const errors = [];
+  const panel = (path, render) =>
+    fetchJson(path)
+      .then(render)
+      .catch((error) => errors.push(`${path}: ${error.message}`));
+
+  renderLegend();
+  await Promise.all([
+    panel("/api/faith/today", renderToday),
+    panel("/api/faith/daily", (stats) => renderStackedChart("daily-chart", stats.days, "date", 5)),
+    panel("/api/faith/weekly", (stats) => renderStackedChart("weekly-chart", stats.weeks, "week_start", 1)),
+    panel("/api/arc/top-places", renderPlaces),
+    panel("/api/anki/books", renderBooks),
+  ]);
+  status.textContent = errors.join(" · ");
+}
+
+document.getElementById("api-key-form").addEventListener("submit", (event) => {
+  event.preventDefault();
+  localStorage.setItem(API_KEY_STORAGE, document.getElementById("api-key").value);
+  load();
+});
+
+document.getElementById("api-key").value = localStorage.getItem(API_KEY_STORAGE) || "";
+if (localStorage.getItem(API_KEY_STORAGE)) load();
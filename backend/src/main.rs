@@ -1,24 +1,38 @@
 use ankistats::{
-    get_bible_stats,
-    models::{AggregateStats, BibleStats, BookStats, ErrorResponse, HealthCheck},
+    cache::AnkiStatsCache,
+    models::{
+        AggregateStats, BibleStats, BookStats, DailyStats, DailySummary, DayStats, ErrorResponse,
+        HealthCheck, PeriodStats, TodayStats, WeekStats, WeeklyStats, WeeklySummary,
+    },
 };
-use arcstats::stats::{PlaceStats, get_top_places_last_6_months};
+use arcstats::stats::PlaceStats;
 use axum::{
     Router,
-    extract::Request,
-    http::StatusCode,
+    extract::{Path, Query, Request},
+    http::{StatusCode, header},
     middleware::{self, Next},
-    response::{IntoResponse, Json, Response},
+    response::{Html, IntoResponse, Json, Redirect, Response},
     routing::get,
 };
 use faithstats::{
-    get_faith_daily_stats, get_faith_today_stats, get_faith_weekly_stats,
+    cache::FaithStatsCache,
+    config::{CONFIG_FILE, FaithConfig},
+    export::{TimeSeries, daily_time_series, weekly_time_series},
+    goals::{FaithGoal, FaithGoalPeriod, FaithGoalProgress, FaithMetric},
     models::{
-        FaithDailyStats, FaithDailySummary, FaithDayStats, FaithTodayStats, FaithWeekStats,
+        ActivityStreaks, FaithDailyStats, FaithDailySummary, FaithDayStats, FaithPeriodStats,
+        FaithRangeStats, FaithRangeSummary, FaithStreaks, FaithTodayStats, FaithWeekStats,
         FaithWeeklyStats, FaithWeeklySummary,
     },
 };
+use prayerstats::CategoryStats;
+use readingstats::config::BibleBookMatcher;
+use readingstats::models::BookReadingStats;
+use serde::Deserialize;
+use statsutils::nonblocking::run;
+use statsutils::{DatePeriod, Granularity, parse_local_date};
 use std::env;
+use std::path::PathBuf;
 use tower_http::cors::CorsLayer;
 use utoipa::OpenApi;
 use utoipa_swagger_ui::SwaggerUi;
@@ -26,10 +40,13 @@ use utoipa_swagger_ui::SwaggerUi;
 /// Application configuration holding database paths
 #[derive(Clone)]
 struct AppConfig {
-    anki_db_path: String,
-    koreader_db_path: String,
-    arcstats_export_path: String,
-    proseuche_db_path: String,
+    /// Source paths from lifestats.toml or the environment
+    faith: FaithConfig,
+    /// Shared memo of Anki results, refreshed when the collection changes
+    anki_cache: AnkiStatsCache,
+    /// Shared memo of the daily, weekly, and today faith stats, refreshed when any source
+    /// changes
+    faith_cache: FaithStatsCache,
 }
 
 /// OpenAPI documentation structure
@@ -38,19 +55,46 @@ struct AppConfig {
     paths(
         health_check,
         get_books_stats,
+        get_anki_today_stats,
+        get_anki_daily_stats,
+        get_anki_weekly_stats,
+        get_anki_range_stats,
+        get_reading_today_stats,
+        get_reading_daily_stats,
+        get_reading_weekly_stats,
+        get_reading_books_stats,
+        get_prayer_today_stats,
+        get_prayer_daily_stats,
+        get_prayer_weekly_stats,
+        get_prayer_categories_stats,
         get_faith_today_stats_endpoint,
         get_faith_daily_stats_endpoint,
         get_faith_weekly_stats_endpoint,
+        get_faith_daily_time_series_endpoint,
+        get_faith_weekly_time_series_endpoint,
+        get_faith_range_stats_endpoint,
         get_top_places_stats_endpoint,
+        get_church_today_stats,
+        get_church_daily_stats,
+        get_church_weekly_stats,
     ),
     components(
-        schemas(HealthCheck, BibleStats, BookStats, AggregateStats, ErrorResponse,
+        schemas(HealthCheck, BibleStats, BookStats, AggregateStats, ErrorResponse, PeriodStats,
+                TodayStats, DailyStats, DailySummary, DayStats, WeeklyStats, WeeklySummary,
+                WeekStats, readingstats::models::DayStats, readingstats::models::WeekStats,
+                BookReadingStats, prayerstats::DayStats, prayerstats::WeekStats, CategoryStats,
+                arcstats::stats::DayStats, arcstats::stats::WeekStats,
                 FaithTodayStats, FaithDailyStats, FaithDailySummary, FaithDayStats,
-                FaithWeeklyStats, FaithWeeklySummary, FaithWeekStats, PlaceStats)
+                FaithWeeklyStats, FaithWeeklySummary, FaithWeekStats, FaithRangeStats,
+                FaithRangeSummary, FaithPeriodStats, FaithGoalProgress, FaithGoal, FaithMetric,
+                FaithGoalPeriod, FaithStreaks, ActivityStreaks, PlaceStats, Granularity, DatePeriod,
+                TimeSeries)
     ),
     tags(
         (name = "health", description = "Health check endpoints"),
         (name = "anki", description = "Anki Bible memorization statistics endpoints"),
+        (name = "reading", description = "KOReader Bible reading statistics endpoints"),
+        (name = "prayer", description = "Proseuche prayer statistics endpoints"),
         (name = "faith", description = "Unified faith statistics endpoints combining multiple sources"),
         (name = "arc", description = "Arc Timeline location tracking statistics endpoints")
     ),
@@ -88,24 +132,15 @@ async fn main() {
     // Load environment variables from .env file if present
     dotenvy::dotenv().ok();
 
-    // Get configuration from environment variables
-    let anki_db_path = env::var("ANKI_DATABASE_PATH").unwrap_or_else(|_| {
-        eprintln!("Error: ANKI_DATABASE_PATH environment variable is required");
-        std::process::exit(1);
-    });
-
-    let koreader_db_path = env::var("KOREADER_DATABASE_PATH").unwrap_or_else(|_| {
-        eprintln!("Error: KOREADER_DATABASE_PATH environment variable is required");
+    // Optionally override the timezone, rollover hour, and week start used for every source
+    if let Err(e) = statsutils::configure_from_env() {
+        eprintln!("Error: {:#}", e);
         std::process::exit(1);
-    });
-
-    let arcstats_export_path = env::var("ARCSTATS_EXPORT_PATH").unwrap_or_else(|_| {
-        eprintln!("Error: ARCSTATS_EXPORT_PATH environment variable is required");
-        std::process::exit(1);
-    });
+    }
 
-    let proseuche_db_path = env::var("PROSEUCHE_DATABASE_PATH").unwrap_or_else(|_| {
-        eprintln!("Error: PROSEUCHE_DATABASE_PATH environment variable is required");
+    // Get the source paths from lifestats.toml, or from environment variables without one
+    let faith = load_config().unwrap_or_else(|e| {
+        eprintln!("Error: {:#}", e);
         std::process::exit(1);
     });
 
@@ -114,49 +149,79 @@ async fn main() {
         std::process::exit(1);
     });
 
-    // Validate that the database paths exist
-    if !std::path::Path::new(&anki_db_path).exists() {
-        eprintln!("Error: Anki database file not found at: {}", anki_db_path);
-        std::process::exit(1);
-    }
-
-    if !std::path::Path::new(&koreader_db_path).exists() {
-        eprintln!(
-            "Error: KOReader database file not found at: {}",
-            koreader_db_path
-        );
-        std::process::exit(1);
+    // Optionally override which KOReader books count as Bible reading
+    let bible_titles = env::var("KOREADER_BIBLE_TITLES").ok();
+    let bible_md5s = env::var("KOREADER_BIBLE_MD5S").ok();
+    if bible_titles.is_some() || bible_md5s.is_some() {
+        readingstats::config::set_bible_books(BibleBookMatcher::from_comma_lists(
+            bible_titles.as_deref(),
+            bible_md5s.as_deref(),
+        ));
     }
 
-    if !std::path::Path::new(&proseuche_db_path).exists() {
-        eprintln!(
-            "Error: Proseuche database file not found at: {}",
-            proseuche_db_path
-        );
-        std::process::exit(1);
+    // Validate that the database paths exist
+    let required = [
+        ("Anki database", &faith.sources.anki),
+        ("KOReader database", &faith.sources.koreader),
+        ("Proseuche database", &faith.sources.proseuche),
+    ];
+    for (name, path) in required {
+        if !std::path::Path::new(path).exists() {
+            eprintln!("Error: {} file not found at: {}", name, path);
+            std::process::exit(1);
+        }
     }
 
     let config = AppConfig {
-        anki_db_path: anki_db_path.clone(),
-        koreader_db_path: koreader_db_path.clone(),
-        arcstats_export_path: arcstats_export_path.clone(),
-        proseuche_db_path: proseuche_db_path.clone(),
+        anki_cache: AnkiStatsCache::new(),
+        faith_cache: FaithStatsCache::new(faith.clone()),
+        faith,
     };
 
     println!("Starting life stats API server...");
-    println!("Anki Database: {}", anki_db_path);
-    println!("KOReader Database: {}", koreader_db_path);
-    println!("Proseuche Database: {}", proseuche_db_path);
+    println!("Anki Database: {}", config.faith.sources.anki);
+    println!("KOReader Database: {}", config.faith.sources.koreader);
+    println!("Proseuche Database: {}", config.faith.sources.proseuche);
+    if let Some(arc) = &config.faith.sources.arc {
+        println!("Arc Timeline Export: {}", arc);
+    }
 
     // Build the router with routes
     let app = Router::new()
         .merge(SwaggerUi::new("/swagger-ui").url("/openapi.json", ApiDoc::openapi()))
+        .route("/", get(|| async { Redirect::temporary("/dashboard") }))
+        .route("/dashboard", get(dashboard_page))
+        .route("/dashboard/{file}", get(dashboard_asset))
         .route("/health", get(health_check))
         .route("/api/anki/books", get(get_books_stats))
+        .route("/api/anki/today", get(get_anki_today_stats))
+        .route("/api/anki/daily", get(get_anki_daily_stats))
+        .route("/api/anki/weekly", get(get_anki_weekly_stats))
+        .route("/api/anki/range", get(get_anki_range_stats))
+        .route("/api/reading/today", get(get_reading_today_stats))
+        .route("/api/reading/daily", get(get_reading_daily_stats))
+        .route("/api/reading/weekly", get(get_reading_weekly_stats))
+        .route("/api/reading/books", get(get_reading_books_stats))
+        .route("/api/prayer/today", get(get_prayer_today_stats))
+        .route("/api/prayer/daily", get(get_prayer_daily_stats))
+        .route("/api/prayer/weekly", get(get_prayer_weekly_stats))
+        .route("/api/prayer/categories", get(get_prayer_categories_stats))
         .route("/api/faith/today", get(get_faith_today_stats_endpoint))
         .route("/api/faith/daily", get(get_faith_daily_stats_endpoint))
         .route("/api/faith/weekly", get(get_faith_weekly_stats_endpoint))
+        .route(
+            "/api/faith/daily/timeseries",
+            get(get_faith_daily_time_series_endpoint),
+        )
+        .route(
+            "/api/faith/weekly/timeseries",
+            get(get_faith_weekly_time_series_endpoint),
+        )
+        .route("/api/faith/range", get(get_faith_range_stats_endpoint))
         .route("/api/arc/top-places", get(get_top_places_stats_endpoint))
+        .route("/api/arc/church/today", get(get_church_today_stats))
+        .route("/api/arc/church/daily", get(get_church_daily_stats))
+        .route("/api/arc/church/weekly", get(get_church_weekly_stats))
         .layer(middleware::from_fn(move |req, next| {
             auth_middleware(req, next, api_key.clone())
         }))
@@ -172,12 +237,33 @@ async fn main() {
     println!("API Documentation:");
     println!("  - Swagger UI: http://localhost:3000/swagger-ui/");
     println!("  - OpenAPI spec: http://localhost:3000/openapi.json");
+    println!("Dashboard: http://localhost:3000/dashboard");
 
     axum::serve(listener, app)
         .await
         .expect("Server failed to start");
 }
 
+/// Reads the config file named by `LIFESTATS_CONFIG`, or lifestats.toml in the working
+/// directory, and applies its day boundaries and place names; without either, the paths
+/// come from environment variables
+fn load_config() -> anyhow::Result<FaithConfig> {
+    let path = env::var("LIFESTATS_CONFIG")
+        .ok()
+        .map(PathBuf::from)
+        .or_else(|| Some(PathBuf::from(CONFIG_FILE)).filter(|p| p.exists()));
+
+    match path {
+        Some(path) => {
+            let config = FaithConfig::from_file(&path)?;
+            config.apply()?;
+            println!("Config: {}", path.display());
+            Ok(config)
+        }
+        None => FaithConfig::from_env(),
+    }
+}
+
 /// Authentication middleware that validates the API key
 async fn auth_middleware(
     req: Request,
@@ -186,8 +272,14 @@ async fn auth_middleware(
 ) -> Result<Response, StatusCode> {
     let path = req.uri().path();
 
-    // Skip auth for public endpoints
-    if path == "/health" || path == "/openapi.json" || path.starts_with("/swagger-ui") {
+    // Skip auth for public endpoints; the dashboard's own requests send the API key
+    if path == "/"
+        || path == "/health"
+        || path == "/openapi.json"
+        || path.starts_with("/swagger-ui")
+        || path == "/dashboard"
+        || path.starts_with("/dashboard/")
+    {
         return Ok(next.run(req).await);
     }
 
@@ -206,6 +298,27 @@ async fn auth_middleware(
     Err(StatusCode::UNAUTHORIZED)
 }
 
+/// The bundled dashboard page, which charts the JSON endpoints in the browser
+async fn dashboard_page() -> Html<&'static str> {
+    Html(include_str!("../dashboard/index.html"))
+}
+
+/// The dashboard's script and stylesheet
+async fn dashboard_asset(Path(file): Path<String>) -> Response {
+    let (content_type, body) = match file.as_str() {
+        "dashboard.js" => (
+            "text/javascript; charset=utf-8",
+            include_str!("../dashboard/dashboard.js"),
+        ),
+        "dashboard.css" => (
+            "text/css; charset=utf-8",
+            include_str!("../dashboard/dashboard.css"),
+        ),
+        _ => return StatusCode::NOT_FOUND.into_response(),
+    };
+    ([(header::CONTENT_TYPE, content_type)], body).into_response()
+}
+
 /// Health check endpoint
 #[utoipa::path(
     get,
@@ -236,7 +349,158 @@ async fn health_check() -> impl IntoResponse {
 async fn get_books_stats(
     axum::extract::State(config): axum::extract::State<AppConfig>,
 ) -> Result<Json<BibleStats>, AppError> {
-    let stats = get_bible_stats(&config.anki_db_path)?;
+    let stats = run(move || config.anki_cache.bible_stats(&config.faith.sources.anki)).await?;
+    Ok(Json(stats))
+}
+
+/// Get today's Anki study time
+#[utoipa::path(
+    get,
+    path = "/api/anki/today",
+    responses(
+        (status = 200, description = "Today's Anki study time retrieved successfully", body = TodayStats),
+        (status = 401, description = "Unauthorized - invalid or missing API key"),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "anki"
+)]
+async fn get_anki_today_stats(
+    axum::extract::State(config): axum::extract::State<AppConfig>,
+) -> Result<Json<TodayStats>, AppError> {
+    let minutes = run(move || {
+        config
+            .anki_cache
+            .today_study_time(&config.faith.sources.anki)
+    })
+    .await?;
+    Ok(Json(TodayStats::new(minutes)))
+}
+
+/// Get Anki study time and progress for last 30 days
+#[utoipa::path(
+    get,
+    path = "/api/anki/daily",
+    responses(
+        (status = 200, description = "Anki study time for last 30 days retrieved successfully", body = DailyStats),
+        (status = 401, description = "Unauthorized - invalid or missing API key"),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "anki"
+)]
+async fn get_anki_daily_stats(
+    axum::extract::State(config): axum::extract::State<AppConfig>,
+) -> Result<Json<DailyStats>, AppError> {
+    let days = run(move || {
+        config
+            .anki_cache
+            .last_30_days_stats(&config.faith.sources.anki)
+    })
+    .await?;
+    Ok(Json(DailyStats::new(days)))
+}
+
+/// Get Anki study time and progress for last 12 weeks
+#[utoipa::path(
+    get,
+    path = "/api/anki/weekly",
+    responses(
+        (status = 200, description = "Anki study time for last 12 weeks retrieved successfully", body = WeeklyStats),
+        (status = 401, description = "Unauthorized - invalid or missing API key"),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "anki"
+)]
+async fn get_anki_weekly_stats(
+    axum::extract::State(config): axum::extract::State<AppConfig>,
+) -> Result<Json<WeeklyStats>, AppError> {
+    let weeks = run(move || {
+        config
+            .anki_cache
+            .last_12_weeks_stats(&config.faith.sources.anki)
+    })
+    .await?;
+    Ok(Json(WeeklyStats::new(weeks)))
+}
+
+/// Query parameters for date range endpoints
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+struct RangeQuery {
+    /// First day of the range (YYYY-MM-DD)
+    start: String,
+    /// Last day of the range, inclusive (YYYY-MM-DD)
+    end: String,
+    /// Bucket size: day, week, or month (defaults to day)
+    granularity: Option<Granularity>,
+}
+
+/// Longest range, in days, that the range endpoints will compute in one request
+const MAX_RANGE_DAYS: i64 = 366;
+
+impl RangeQuery {
+    /// Rejects unparseable dates, ranges that end before they start, and ranges longer
+    /// than `MAX_RANGE_DAYS`, so they are reported as bad requests rather than server errors
+    fn validate(&self) -> Result<(), AppError> {
+        let start = parse_local_date(&self.start)
+            .map_err(|e| AppError::BadRequest(format!("Invalid start date: {:#}", e)))?;
+        let end = parse_local_date(&self.end)
+            .map_err(|e| AppError::BadRequest(format!("Invalid end date: {:#}", e)))?;
+        if end < start {
+            return Err(AppError::BadRequest(format!(
+                "End date {} is before start date {}",
+                end, start
+            )));
+        }
+        let days = (end - start).num_days() + 1;
+        if days > MAX_RANGE_DAYS {
+            return Err(AppError::BadRequest(format!(
+                "Range of {} days is longer than the maximum of {} days",
+                days, MAX_RANGE_DAYS
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Get Anki study statistics for a custom date range
+#[utoipa::path(
+    get,
+    path = "/api/anki/range",
+    params(RangeQuery),
+    responses(
+        (status = 200, description = "Anki study statistics for the date range retrieved successfully", body = Vec<PeriodStats>),
+        (status = 400, description = "Invalid dates or a range longer than 366 days", body = ErrorResponse),
+        (status = 401, description = "Unauthorized - invalid or missing API key"),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "anki"
+)]
+async fn get_anki_range_stats(
+    axum::extract::State(config): axum::extract::State<AppConfig>,
+    Query(query): Query<RangeQuery>,
+) -> Result<Json<Vec<PeriodStats>>, AppError> {
+    query.validate()?;
+    let granularity = query.granularity.unwrap_or(Granularity::Day);
+    let stats = run(move || {
+        config.anki_cache.stats_for_range(
+            &config.faith.sources.anki,
+            &query.start,
+            &query.end,
+            granularity,
+        )
+    })
+    .await?;
     Ok(Json(stats))
 }
 
@@ -257,11 +521,189 @@ async fn get_books_stats(
 async fn get_faith_today_stats_endpoint(
     axum::extract::State(config): axum::extract::State<AppConfig>,
 ) -> Result<Json<FaithTodayStats>, AppError> {
-    let stats = get_faith_today_stats(
-        &config.anki_db_path,
-        &config.koreader_db_path,
-        &config.proseuche_db_path,
-    )?;
+    let stats = run(move || config.faith_cache.today_stats()).await?;
+    Ok(Json(stats))
+}
+
+/// Get today's Bible reading time
+#[utoipa::path(
+    get,
+    path = "/api/reading/today",
+    responses(
+        (status = 200, description = "Today's Bible reading time retrieved successfully", body = TodayStats),
+        (status = 401, description = "Unauthorized - invalid or missing API key"),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "reading"
+)]
+async fn get_reading_today_stats(
+    axum::extract::State(config): axum::extract::State<AppConfig>,
+) -> Result<Json<TodayStats>, AppError> {
+    let minutes =
+        readingstats::nonblocking::get_today_reading_time(&config.faith.sources.koreader).await?;
+    Ok(Json(TodayStats::new(minutes)))
+}
+
+/// Get Bible reading time and pages for last 30 days
+#[utoipa::path(
+    get,
+    path = "/api/reading/daily",
+    responses(
+        (status = 200, description = "Bible reading time for last 30 days retrieved successfully", body = Vec<readingstats::models::DayStats>),
+        (status = 401, description = "Unauthorized - invalid or missing API key"),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "reading"
+)]
+async fn get_reading_daily_stats(
+    axum::extract::State(config): axum::extract::State<AppConfig>,
+) -> Result<Json<Vec<readingstats::models::DayStats>>, AppError> {
+    let stats =
+        readingstats::nonblocking::get_last_30_days_stats(&config.faith.sources.koreader).await?;
+    Ok(Json(stats))
+}
+
+/// Get Bible reading time and pages for last 12 weeks
+#[utoipa::path(
+    get,
+    path = "/api/reading/weekly",
+    responses(
+        (status = 200, description = "Bible reading time for last 12 weeks retrieved successfully", body = Vec<readingstats::models::WeekStats>),
+        (status = 401, description = "Unauthorized - invalid or missing API key"),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "reading"
+)]
+async fn get_reading_weekly_stats(
+    axum::extract::State(config): axum::extract::State<AppConfig>,
+) -> Result<Json<Vec<readingstats::models::WeekStats>>, AppError> {
+    let stats =
+        readingstats::nonblocking::get_last_12_weeks_stats(&config.faith.sources.koreader).await?;
+    Ok(Json(stats))
+}
+
+/// Get reading time per book, Bible or not, over last 30 days
+#[utoipa::path(
+    get,
+    path = "/api/reading/books",
+    responses(
+        (status = 200, description = "Per-book reading time for last 30 days retrieved successfully", body = Vec<BookReadingStats>),
+        (status = 401, description = "Unauthorized - invalid or missing API key"),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "reading"
+)]
+async fn get_reading_books_stats(
+    axum::extract::State(config): axum::extract::State<AppConfig>,
+) -> Result<Json<Vec<BookReadingStats>>, AppError> {
+    let stats = readingstats::nonblocking::get_per_book_stats(
+        &config.faith.sources.koreader,
+        &DatePeriod::last_30_days()?,
+    )
+    .await?;
+    Ok(Json(stats))
+}
+
+/// Get today's prayer time
+#[utoipa::path(
+    get,
+    path = "/api/prayer/today",
+    responses(
+        (status = 200, description = "Today's prayer time retrieved successfully", body = TodayStats),
+        (status = 401, description = "Unauthorized - invalid or missing API key"),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "prayer"
+)]
+async fn get_prayer_today_stats(
+    axum::extract::State(config): axum::extract::State<AppConfig>,
+) -> Result<Json<TodayStats>, AppError> {
+    let minutes =
+        prayerstats::nonblocking::get_today_prayer_time(&config.faith.sources.proseuche).await?;
+    Ok(Json(TodayStats::new(minutes)))
+}
+
+/// Get prayer time for last 30 days
+#[utoipa::path(
+    get,
+    path = "/api/prayer/daily",
+    responses(
+        (status = 200, description = "Prayer time for last 30 days retrieved successfully", body = Vec<prayerstats::DayStats>),
+        (status = 401, description = "Unauthorized - invalid or missing API key"),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "prayer"
+)]
+async fn get_prayer_daily_stats(
+    axum::extract::State(config): axum::extract::State<AppConfig>,
+) -> Result<Json<Vec<prayerstats::DayStats>>, AppError> {
+    let stats =
+        prayerstats::nonblocking::get_last_30_days_stats(&config.faith.sources.proseuche).await?;
+    Ok(Json(stats))
+}
+
+/// Get prayer time for last 12 weeks
+#[utoipa::path(
+    get,
+    path = "/api/prayer/weekly",
+    responses(
+        (status = 200, description = "Prayer time for last 12 weeks retrieved successfully", body = Vec<prayerstats::WeekStats>),
+        (status = 401, description = "Unauthorized - invalid or missing API key"),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "prayer"
+)]
+async fn get_prayer_weekly_stats(
+    axum::extract::State(config): axum::extract::State<AppConfig>,
+) -> Result<Json<Vec<prayerstats::WeekStats>>, AppError> {
+    let stats =
+        prayerstats::nonblocking::get_last_12_weeks_stats(&config.faith.sources.proseuche).await?;
+    Ok(Json(stats))
+}
+
+/// Get prayer time per prayer list over last 30 days
+#[utoipa::path(
+    get,
+    path = "/api/prayer/categories",
+    responses(
+        (status = 200, description = "Prayer time per prayer list for last 30 days retrieved successfully", body = Vec<CategoryStats>),
+        (status = 401, description = "Unauthorized - invalid or missing API key"),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "prayer"
+)]
+async fn get_prayer_categories_stats(
+    axum::extract::State(config): axum::extract::State<AppConfig>,
+) -> Result<Json<Vec<CategoryStats>>, AppError> {
+    let stats = prayerstats::nonblocking::get_stats_by_category(
+        &config.faith.sources.proseuche,
+        &DatePeriod::last_30_days()?,
+    )
+    .await?;
     Ok(Json(stats))
 }
 
@@ -282,11 +724,7 @@ async fn get_faith_today_stats_endpoint(
 async fn get_faith_daily_stats_endpoint(
     axum::extract::State(config): axum::extract::State<AppConfig>,
 ) -> Result<Json<FaithDailyStats>, AppError> {
-    let stats = get_faith_daily_stats(
-        &config.anki_db_path,
-        &config.koreader_db_path,
-        &config.proseuche_db_path,
-    )?;
+    let stats = run(move || config.faith_cache.daily_stats()).await?;
     Ok(Json(stats))
 }
 
@@ -307,12 +745,80 @@ async fn get_faith_daily_stats_endpoint(
 async fn get_faith_weekly_stats_endpoint(
     axum::extract::State(config): axum::extract::State<AppConfig>,
 ) -> Result<Json<FaithWeeklyStats>, AppError> {
-    let stats = get_faith_weekly_stats(
-        &config.anki_db_path,
-        &config.koreader_db_path,
-        &config.arcstats_export_path,
-        &config.proseuche_db_path,
-    )?;
+    let stats = run(move || config.faith_cache.weekly_stats()).await?;
+    Ok(Json(stats))
+}
+
+/// Get the last 30 days' faith minutes as Grafana JSON datasource time series
+#[utoipa::path(
+    get,
+    path = "/api/faith/daily/timeseries",
+    responses(
+        (status = 200, description = "One series per source plus the total, as [value, timestamp] pairs", body = Vec<TimeSeries>),
+        (status = 401, description = "Unauthorized - invalid or missing API key"),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "faith"
+)]
+async fn get_faith_daily_time_series_endpoint(
+    axum::extract::State(config): axum::extract::State<AppConfig>,
+) -> Result<Json<Vec<TimeSeries>>, AppError> {
+    let stats = run(move || config.faith_cache.daily_stats()).await?;
+    Ok(Json(daily_time_series(&stats)?))
+}
+
+/// Get the last 12 weeks' faith minutes as Grafana JSON datasource time series
+#[utoipa::path(
+    get,
+    path = "/api/faith/weekly/timeseries",
+    responses(
+        (status = 200, description = "One series per source plus the total, as [value, timestamp] pairs", body = Vec<TimeSeries>),
+        (status = 401, description = "Unauthorized - invalid or missing API key"),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "faith"
+)]
+async fn get_faith_weekly_time_series_endpoint(
+    axum::extract::State(config): axum::extract::State<AppConfig>,
+) -> Result<Json<Vec<TimeSeries>>, AppError> {
+    let stats = run(move || config.faith_cache.weekly_stats()).await?;
+    Ok(Json(weekly_time_series(&stats)?))
+}
+
+/// Get unified faith statistics for a custom date range
+#[utoipa::path(
+    get,
+    path = "/api/faith/range",
+    params(RangeQuery),
+    responses(
+        (status = 200, description = "Unified faith statistics for the date range retrieved successfully", body = FaithRangeStats),
+        (status = 400, description = "Invalid dates or a range longer than 366 days", body = ErrorResponse),
+        (status = 401, description = "Unauthorized - invalid or missing API key"),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "faith"
+)]
+async fn get_faith_range_stats_endpoint(
+    axum::extract::State(config): axum::extract::State<AppConfig>,
+    Query(query): Query<RangeQuery>,
+) -> Result<Json<FaithRangeStats>, AppError> {
+    query.validate()?;
+    let stats = faithstats::nonblocking::get_faith_stats_for_range(
+        &config.faith,
+        &query.start,
+        &query.end,
+        query.granularity.unwrap_or(Granularity::Day),
+    )
+    .await?;
     Ok(Json(stats))
 }
 
@@ -333,20 +839,89 @@ async fn get_faith_weekly_stats_endpoint(
 async fn get_top_places_stats_endpoint(
     axum::extract::State(config): axum::extract::State<AppConfig>,
 ) -> Result<Json<Vec<PlaceStats>>, AppError> {
-    let stats = get_top_places_last_6_months(&config.arcstats_export_path, 10)?;
+    let stats =
+        arcstats::nonblocking::get_top_places_last_6_months(config.faith.arc_path()?, 10).await?;
+    Ok(Json(stats))
+}
+
+/// Get today's time at church
+#[utoipa::path(
+    get,
+    path = "/api/arc/church/today",
+    responses(
+        (status = 200, description = "Today's time at church retrieved successfully", body = TodayStats),
+        (status = 401, description = "Unauthorized - invalid or missing API key"),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "arc"
+)]
+async fn get_church_today_stats(
+    axum::extract::State(config): axum::extract::State<AppConfig>,
+) -> Result<Json<TodayStats>, AppError> {
+    let minutes = arcstats::nonblocking::get_today_church_time(config.faith.arc_path()?).await?;
+    Ok(Json(TodayStats::new(minutes)))
+}
+
+/// Get time at church for last 30 days
+#[utoipa::path(
+    get,
+    path = "/api/arc/church/daily",
+    responses(
+        (status = 200, description = "Time at church for last 30 days retrieved successfully", body = Vec<arcstats::stats::DayStats>),
+        (status = 401, description = "Unauthorized - invalid or missing API key"),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "arc"
+)]
+async fn get_church_daily_stats(
+    axum::extract::State(config): axum::extract::State<AppConfig>,
+) -> Result<Json<Vec<arcstats::stats::DayStats>>, AppError> {
+    let stats = arcstats::nonblocking::get_last_30_days_stats(config.faith.arc_path()?).await?;
+    Ok(Json(stats))
+}
+
+/// Get time at church, by day and kind of attendance, for last 12 weeks
+#[utoipa::path(
+    get,
+    path = "/api/arc/church/weekly",
+    responses(
+        (status = 200, description = "Time at church for last 12 weeks retrieved successfully", body = Vec<arcstats::stats::WeekStats>),
+        (status = 401, description = "Unauthorized - invalid or missing API key"),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "arc"
+)]
+async fn get_church_weekly_stats(
+    axum::extract::State(config): axum::extract::State<AppConfig>,
+) -> Result<Json<Vec<arcstats::stats::WeekStats>>, AppError> {
+    let stats = arcstats::nonblocking::get_last_12_weeks_stats(config.faith.arc_path()?).await?;
     Ok(Json(stats))
 }
 
 /// Custom error type for API errors
-struct AppError(anyhow::Error);
+enum AppError {
+    /// The request itself was invalid, reported as 400 Bad Request
+    BadRequest(String),
+    /// Anything that failed while computing the response, reported as 500
+    Internal(anyhow::Error),
+}
 
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse::new(format!("{:#}", self.0))),
-        )
-            .into_response()
+        let (status, message) = match self {
+            Self::BadRequest(message) => (StatusCode::BAD_REQUEST, message),
+            Self::Internal(err) => (StatusCode::INTERNAL_SERVER_ERROR, format!("{:#}", err)),
+        };
+        (status, Json(ErrorResponse::new(message))).into_response()
     }
 }
 
@@ -355,6 +930,6 @@ where
     E: Into<anyhow::Error>,
 {
     fn from(err: E) -> Self {
-        Self(err.into())
+        Self::Internal(err.into())
     }
 }
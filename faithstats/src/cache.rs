@@ -0,0 +1,162 @@
+use anyhow::Result;
+use statsutils::cache::FileMemo;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::config::FaithConfig;
+use crate::models::{FaithDailyStats, FaithTodayStats, FaithWeeklyStats};
+
+pub use statsutils::cache::DEFAULT_TTL;
+
+/// Memoizes the daily, weekly, and today faith stats for the server and the dashboard
+///
+/// Results come from the config-based entry points, so they include the config's optional
+/// sources, score weights, and goals. Each result is recomputed when any source database
+/// (or its write-ahead log), the Arc Timeline export's metadata, or an optional source's
+/// files are modified, or when the TTL expires. Cloning is cheap; clones share the same
+/// config and results.
+#[derive(Clone)]
+pub struct FaithStatsCache {
+    config: Arc<FaithConfig>,
+    daily: Arc<FileMemo<FaithDailyStats>>,
+    weekly: Arc<FileMemo<FaithWeeklyStats>>,
+    today: Arc<FileMemo<FaithTodayStats>>,
+}
+
+impl FaithStatsCache {
+    /// Caches stats for the sources in `config`
+    pub fn new(config: FaithConfig) -> Self {
+        Self::with_ttl(config, DEFAULT_TTL)
+    }
+
+    /// Like `new`, reusing results for at most `ttl`
+    pub fn with_ttl(config: FaithConfig, ttl: Duration) -> Self {
+        let paths = watched_paths(&config);
+
+        FaithStatsCache {
+            config: Arc::new(config),
+            daily: Arc::new(FileMemo::new(paths.clone()).with_ttl(ttl)),
+            weekly: Arc::new(FileMemo::new(paths.clone()).with_ttl(ttl)),
+            today: Arc::new(FileMemo::new(paths).with_ttl(ttl)),
+        }
+    }
+
+    /// The config the stats are computed from
+    pub fn config(&self) -> &FaithConfig {
+        &self.config
+    }
+
+    /// Files whose modification invalidates the cached results
+    pub fn paths(&self) -> &[PathBuf] {
+        self.daily.paths()
+    }
+
+    /// Cached `get_faith_daily_stats`
+    pub fn daily_stats(&self) -> Result<FaithDailyStats> {
+        self.daily
+            .get_or_compute(|| crate::get_faith_daily_stats(&self.config))
+    }
+
+    /// Cached `get_faith_weekly_stats`; fails without an Arc Timeline export path
+    pub fn weekly_stats(&self) -> Result<FaithWeeklyStats> {
+        self.weekly
+            .get_or_compute(|| crate::get_faith_weekly_stats(&self.config))
+    }
+
+    /// Cached `get_faith_today_stats`
+    pub fn today_stats(&self) -> Result<FaithTodayStats> {
+        self.today
+            .get_or_compute(|| crate::get_faith_today_stats(&self.config))
+    }
+
+    /// Drops every cached result
+    pub fn clear(&self) {
+        self.daily.invalidate();
+        self.weekly.invalidate();
+        self.today.invalidate();
+    }
+}
+
+/// Each SQLite database and its write-ahead log, since writes land there first, the
+/// metadata file Arc Timeline rewrites on every export, and the files each optional
+/// source reads
+///
+/// Proseuche accepts a comma-separated list of databases to merge, so each is watched. The
+/// journal is watched by its folder, which misses edits to existing notes; those wait for
+/// the TTL.
+fn watched_paths(config: &FaithConfig) -> Vec<PathBuf> {
+    let sources = &config.sources;
+    let mut paths = Vec::new();
+    let databases = [sources.anki.as_str(), sources.koreader.as_str()]
+        .into_iter()
+        .chain(sources.proseuche.split(',').map(str::trim));
+    for db_path in databases {
+        paths.push(PathBuf::from(db_path));
+        paths.push(PathBuf::from(format!("{}-wal", db_path)));
+    }
+    if let Some(export_path) = &sources.arc {
+        paths.push(Path::new(export_path).join("metadata.json"));
+    }
+    for source in config.extra_sources() {
+        paths.extend(source.paths());
+    }
+    paths
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(toml: &str) -> FaithConfig {
+        FaithConfig::parse(toml).unwrap()
+    }
+
+    #[test]
+    fn test_watched_paths() {
+        let cache = FaithStatsCache::new(config(
+            r#"
+            [sources]
+            anki = "/data/collection.anki2"
+            koreader = "/data/statistics.sqlite3"
+            arc = "/data/arc"
+            proseuche = "/data/database.sqlite, /data/phone.sqlite"
+            youversion = "/data/youversion.json"
+            journal = "/data/journal"
+            fasting = "/data/fasts.csv"
+            "#,
+        ));
+        let paths: Vec<&str> = cache.paths().iter().map(|p| p.to_str().unwrap()).collect();
+        assert_eq!(
+            paths,
+            [
+                "/data/collection.anki2",
+                "/data/collection.anki2-wal",
+                "/data/statistics.sqlite3",
+                "/data/statistics.sqlite3-wal",
+                "/data/database.sqlite",
+                "/data/database.sqlite-wal",
+                "/data/phone.sqlite",
+                "/data/phone.sqlite-wal",
+                "/data/arc/metadata.json",
+                "/data/youversion.json",
+                "/data/journal",
+                "/data/fasts.csv",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_weekly_stats_require_arc_export() {
+        let cache = FaithStatsCache::new(config(
+            r#"
+            [sources]
+            anki = "missing"
+            koreader = "missing"
+            proseuche = "missing"
+            "#,
+        ));
+        let err = cache.weekly_stats().unwrap_err();
+        assert!(err.to_string().contains("Arc Timeline export"));
+    }
+}
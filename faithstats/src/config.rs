@@ -0,0 +1,448 @@
+use anyhow::{Context, Result, bail};
+use arcstats::config::{ChurchPlace, DEFAULT_CHURCH_PLACES};
+use chrono::Weekday;
+use serde::Deserialize;
+use statsutils::{PeriodConfig, StatsTimezone, set_default_config};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::fasting::FastingSource;
+use crate::goals::FaithGoal;
+use crate::journal::{JournalOptions, JournalSource};
+use crate::notify::NotifyConfig;
+use crate::score::ScoreWeights;
+use crate::source::{FaithDataSource, builtin_sources};
+use crate::youversion::{DEFAULT_MINUTES_PER_PLAN_DAY, YouVersionSource};
+
+/// Default name of the configuration file, looked for in the working directory
+pub const CONFIG_FILE: &str = "lifestats.toml";
+
+/// Where each faith source's data lives, plus the day boundaries and place names to use
+///
+/// Read from a TOML file such as:
+///
+/// ```toml
+/// timezone = "America/Chicago"
+/// rollover_hour = 4
+/// week_start = "sunday"
+/// reading_plan_minutes = 15
+///
+/// [sources]
+/// anki = "/path/to/collection.anki2"
+/// koreader = "/path/to/statistics.sqlite3"
+/// arc = "/path/to/arc/export"
+/// proseuche = "/path/to/database.sqlite"
+/// youversion = "/path/to/youversion-export.json"
+/// journal = "/path/to/vault/Daily"
+/// fasting = "/path/to/fasts.csv"
+///
+/// [journal]
+/// pattern = "%Y-%m-%d.md"
+///
+/// [places]
+/// churches = ["Martin Luther Church"]
+/// home = ["Home"]
+///
+/// [places.church_categories]
+/// small_group = ["Smith House"]
+/// volunteering = ["City Food Pantry"]
+///
+/// [score.prayer]
+/// weight = 2
+/// half_minutes = 10
+///
+/// [[goals]]
+/// metric = "prayer_minutes"
+/// target = 20
+/// period = "day"
+///
+/// [notify]
+/// service = "ntfy"
+/// url = "https://ntfy.sh/my-topic"
+/// ```
+///
+/// Everything but the Anki, KOReader, and Proseuche paths is optional. The day boundaries
+/// and place names are process-wide settings, installed by `apply`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FaithConfig {
+    pub sources: SourcePaths,
+    /// IANA timezone name, e.g. `Europe/London`
+    #[serde(default)]
+    pub timezone: Option<String>,
+    /// Hour after midnight days start at, 0-23
+    #[serde(default)]
+    pub rollover_hour: Option<i64>,
+    /// Day weeks start on, e.g. `sunday` or `mon`, or `iso` for ISO 8601 week numbers
+    #[serde(default)]
+    pub week_start: Option<String>,
+    /// Minutes of reading credited per completed YouVersion plan day, since the app
+    /// doesn't record time; defaults to `youversion::DEFAULT_MINUTES_PER_PLAN_DAY`
+    #[serde(default)]
+    pub reading_plan_minutes: Option<f64>,
+    #[serde(default)]
+    pub places: PlaceNames,
+    /// How entries in `sources.journal` are found and timed
+    #[serde(default)]
+    pub journal: JournalOptions,
+    /// Weights for the daily consistency score, keyed by source name
+    #[serde(default)]
+    pub score: ScoreWeights,
+    /// Rule-of-life targets, measured by `goals::evaluate_faith_goals`
+    #[serde(default)]
+    pub goals: Vec<FaithGoal>,
+    /// Where the `notify` command posts events; none are sent without it
+    #[serde(default)]
+    pub notify: Option<NotifyConfig>,
+}
+
+/// Paths to each source's database or export
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SourcePaths {
+    /// Anki collection.anki2 file
+    pub anki: String,
+    /// KOReader statistics.sqlite3 file, or a Kobo KoboReader.sqlite database
+    pub koreader: String,
+    /// Arc Timeline export directory; only needed for weekly stats
+    #[serde(default)]
+    pub arc: Option<String>,
+    /// Proseuche database.sqlite file, or a CSV prayer journal
+    pub proseuche: String,
+    /// YouVersion (Bible App) JSON export, for reading-plan progress
+    #[serde(default)]
+    pub youversion: Option<String>,
+    /// Folder of Markdown journal entries, such as Obsidian daily notes
+    #[serde(default)]
+    pub journal: Option<String>,
+    /// Fasting log, as CSV or TOML
+    #[serde(default)]
+    pub fasting: Option<String>,
+}
+
+/// Names of places in the Arc Timeline export that need special treatment
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PlaceNames {
+    /// Places always counted as church services, on any day; other churches only count on
+    /// Sunday mornings. Defaults to `arcstats::config::DEFAULT_CHURCH_PLACES`.
+    #[serde(default)]
+    pub churches: Option<Vec<String>>,
+    /// Other places counted as church any time, keyed by the kind of attendance (e.g.
+    /// `small_group` or `volunteering`) so each gets its own weekly series
+    #[serde(default)]
+    pub church_categories: BTreeMap<String, Vec<String>>,
+    /// Places counted as home in Sunday stats. Defaults to
+    /// `arcstats::config::DEFAULT_HOME_PLACES`.
+    #[serde(default)]
+    pub home: Option<Vec<String>>,
+}
+
+impl PlaceNames {
+    /// Every place counted as church, with its category; `churches` are services
+    pub fn church_places(&self) -> Vec<ChurchPlace> {
+        let services: Vec<ChurchPlace> = match &self.churches {
+            Some(churches) => churches
+                .iter()
+                .map(|name| ChurchPlace::service(name))
+                .collect(),
+            None => DEFAULT_CHURCH_PLACES
+                .iter()
+                .map(|name| ChurchPlace::service(name))
+                .collect(),
+        };
+        let others = self.church_categories.iter().flat_map(|(category, names)| {
+            names.iter().map(|name| ChurchPlace {
+                name: name.clone(),
+                category: category.clone(),
+            })
+        });
+        services.into_iter().chain(others).collect()
+    }
+}
+
+impl FaithConfig {
+    /// Reads the config from a TOML file
+    ///
+    /// Relative source paths are resolved against the file's directory.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)
+            .context(format!("Failed to read config file '{}'", path.display()))?;
+        let mut config =
+            Self::parse(&content).context(format!("Invalid config file '{}'", path.display()))?;
+
+        if let Some(dir) = path.parent() {
+            config.sources.resolve_relative_to(dir);
+        }
+        Ok(config)
+    }
+
+    /// Parses the config from TOML text
+    pub fn parse(content: &str) -> Result<Self> {
+        let config: FaithConfig = toml::from_str(content)?;
+        config.period_config()?;
+        Ok(config)
+    }
+
+    /// Builds the config from the `ANKI_DATABASE_PATH`, `KOREADER_DATABASE_PATH`,
+    /// `ARCSTATS_EXPORT_PATH`, `PROSEUCHE_DATABASE_PATH`, `YOUVERSION_EXPORT_PATH`,
+    /// `JOURNAL_PATH`, and `FASTING_LOG_PATH` environment variables
+    ///
+    /// Day boundaries are left unset; `statsutils::configure_from_env` reads those.
+    pub fn from_env() -> Result<Self> {
+        let required = |name: &str| {
+            std::env::var(name).context(format!("{} environment variable is required", name))
+        };
+
+        Ok(FaithConfig {
+            sources: SourcePaths {
+                anki: required("ANKI_DATABASE_PATH")?,
+                koreader: required("KOREADER_DATABASE_PATH")?,
+                arc: std::env::var("ARCSTATS_EXPORT_PATH").ok(),
+                proseuche: required("PROSEUCHE_DATABASE_PATH")?,
+                youversion: std::env::var("YOUVERSION_EXPORT_PATH").ok(),
+                journal: std::env::var("JOURNAL_PATH").ok(),
+                fasting: std::env::var("FASTING_LOG_PATH").ok(),
+            },
+            timezone: None,
+            rollover_hour: None,
+            week_start: None,
+            reading_plan_minutes: None,
+            places: PlaceNames::default(),
+            journal: JournalOptions::default(),
+            score: ScoreWeights::default(),
+            goals: Vec::new(),
+            notify: None,
+        })
+    }
+
+    /// Path to the Arc Timeline export, which weekly stats require
+    pub fn arc_path(&self) -> Result<&str> {
+        self.sources.arc.as_deref().context(
+            "No Arc Timeline export path configured (set sources.arc or ARCSTATS_EXPORT_PATH)",
+        )
+    }
+
+    /// Every source this config has paths for: the built-in ones (church attendance only
+    /// with an Arc Timeline export) followed by `extra_sources`
+    pub fn data_sources(&self) -> Vec<Box<dyn FaithDataSource>> {
+        let mut sources = builtin_sources(
+            &self.sources.anki,
+            &self.sources.koreader,
+            self.sources.arc.as_deref(),
+            &self.sources.proseuche,
+        );
+        sources.extend(self.extra_sources());
+        sources
+    }
+
+    /// The optional sources this config has paths for, reported in `other_minutes`
+    pub fn extra_sources(&self) -> Vec<Box<dyn FaithDataSource>> {
+        let mut sources: Vec<Box<dyn FaithDataSource>> = Vec::new();
+        if let Some(export_path) = &self.sources.youversion {
+            sources.push(Box::new(YouVersionSource {
+                export_path: export_path.clone(),
+                minutes_per_day: self
+                    .reading_plan_minutes
+                    .unwrap_or(DEFAULT_MINUTES_PER_PLAN_DAY),
+            }));
+        }
+        if let Some(dir) = &self.sources.journal {
+            sources.push(Box::new(JournalSource {
+                dir: dir.clone(),
+                pattern: self.journal.pattern().to_string(),
+                words_per_minute: self.journal.words_per_minute(),
+            }));
+        }
+        if let Some(log_path) = &self.sources.fasting {
+            sources.push(Box::new(FastingSource {
+                log_path: log_path.clone(),
+            }));
+        }
+        sources
+    }
+
+    /// Day boundaries with this config's overrides applied on top of the current ones, or
+    /// `None` if it doesn't set any
+    fn period_config(&self) -> Result<Option<PeriodConfig>> {
+        if self.timezone.is_none() && self.rollover_hour.is_none() && self.week_start.is_none() {
+            return Ok(None);
+        }
+
+        let mut config = PeriodConfig::default();
+        if let Some(timezone) = &self.timezone {
+            let tz = timezone
+                .parse()
+                .map_err(|_| anyhow::anyhow!("Invalid timezone '{}'", timezone))?;
+            config.timezone = StatsTimezone::Named(tz);
+        }
+        if let Some(hour) = self.rollover_hour {
+            if !(0..24).contains(&hour) {
+                bail!("Invalid rollover_hour {} (expected 0-23)", hour);
+            }
+            config.rollover_hour = hour;
+        }
+        if let Some(day) = &self.week_start {
+            config = if day.eq_ignore_ascii_case("iso") {
+                config.with_iso_weeks()
+            } else {
+                match day.parse::<Weekday>() {
+                    Ok(weekday) => config.with_week_start(weekday),
+                    Err(_) => bail!(
+                        "Invalid week_start '{}' (expected a day name or 'iso')",
+                        day
+                    ),
+                }
+            };
+        }
+
+        Ok(Some(config))
+    }
+
+    /// Installs this config's day boundaries and church and home places for every stats
+    /// crate
+    ///
+    /// Settings the config leaves out keep their current values. Call this once at
+    /// startup, before any stats are queried.
+    pub fn apply(&self) -> Result<()> {
+        if let Some(period_config) = self.period_config()? {
+            set_default_config(period_config);
+        }
+        if self.places.churches.is_some() || !self.places.church_categories.is_empty() {
+            arcstats::config::set_church_place_categories(self.places.church_places());
+        }
+        if let Some(home) = &self.places.home {
+            arcstats::config::set_home_places(home.clone());
+        }
+        Ok(())
+    }
+}
+
+impl SourcePaths {
+    fn resolve_relative_to(&mut self, dir: &Path) {
+        let resolve = |path: &str| {
+            if Path::new(path).is_relative() {
+                dir.join(path).to_string_lossy().into_owned()
+            } else {
+                path.to_string()
+            }
+        };
+
+        self.anki = resolve(&self.anki);
+        self.koreader = resolve(&self.koreader);
+        self.arc = self.arc.as_deref().map(resolve);
+        self.youversion = self.youversion.as_deref().map(resolve);
+        self.journal = self.journal.as_deref().map(resolve);
+        self.fasting = self.fasting.as_deref().map(resolve);
+        // Proseuche accepts a comma-separated list of backups to merge
+        self.proseuche = self
+            .proseuche
+            .split(',')
+            .map(|path| resolve(path.trim()))
+            .collect::<Vec<_>>()
+            .join(",");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCES: &str = r#"
+        [sources]
+        anki = "collection.anki2"
+        koreader = "/data/statistics.sqlite3"
+        proseuche = "/data/database.sqlite,phone.sqlite"
+    "#;
+
+    #[test]
+    fn test_parse_minimal_config() {
+        let config = FaithConfig::parse(SOURCES).unwrap();
+        assert_eq!(config.sources.anki, "collection.anki2");
+        assert_eq!(config.sources.arc, None);
+        assert_eq!(config.places.churches, None);
+        assert!(config.arc_path().is_err());
+        assert!(config.extra_sources().is_empty());
+        assert_eq!(config.period_config().unwrap(), None);
+    }
+
+    #[test]
+    fn test_parse_full_config() {
+        let content = format!(
+            "timezone = \"Europe/London\"\nrollover_hour = 3\nweek_start = \"iso\"\n{}\
+             arc = \"/data/arc\"\nyouversion = \"/data/youversion.json\"\n\
+             journal = \"/data/journal\"\nfasting = \"fasts.csv\"\n[journal]\nwords_per_minute = 30\n[places]\nchurches = [\"St. Paul's\"]\n\
+             [places.church_categories]\nsmall_group = [\"Smith House\", \"Jones House\"]\n",
+            SOURCES
+        );
+        let config = FaithConfig::parse(&content).unwrap();
+        assert_eq!(config.arc_path().unwrap(), "/data/arc");
+        assert_eq!(config.journal.pattern(), "%Y-%m-%d.md");
+        assert_eq!(config.journal.words_per_minute(), 30.0);
+        let names: Vec<String> = config
+            .data_sources()
+            .iter()
+            .map(|s| s.name().to_string())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "anki",
+                "reading",
+                "prayer",
+                "church",
+                "reading_plans",
+                "journaling",
+                "fasting"
+            ]
+        );
+        assert_eq!(config.places.churches, Some(vec!["St. Paul's".to_string()]));
+        assert_eq!(
+            config.places.church_places(),
+            vec![
+                ChurchPlace::service("St. Paul's"),
+                ChurchPlace {
+                    name: "Smith House".to_string(),
+                    category: "small_group".to_string(),
+                },
+                ChurchPlace {
+                    name: "Jones House".to_string(),
+                    category: "small_group".to_string(),
+                },
+            ]
+        );
+
+        let period = config.period_config().unwrap().unwrap();
+        assert_eq!(
+            period.timezone,
+            StatsTimezone::Named("Europe/London".parse().unwrap())
+        );
+        assert_eq!(period.rollover_hour, 3);
+        assert_eq!(period.week_start, Weekday::Mon);
+        assert!(period.iso_weeks);
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_config() {
+        assert!(FaithConfig::parse("[sources]\nanki = \"a\"").is_err());
+        assert!(FaithConfig::parse(&format!("timezone = \"Mars/Olympus\"\n{}", SOURCES)).is_err());
+        assert!(FaithConfig::parse(&format!("rollover_hour = 24\n{}", SOURCES)).is_err());
+        assert!(FaithConfig::parse(&format!("week_start = \"someday\"\n{}", SOURCES)).is_err());
+        assert!(FaithConfig::parse(&format!("{}\nreading = \"x\"", SOURCES)).is_err());
+    }
+
+    #[test]
+    fn test_relative_paths_resolve_against_config_dir() {
+        let mut config = FaithConfig::parse(SOURCES).unwrap();
+        config
+            .sources
+            .resolve_relative_to(Path::new("/home/me/stats"));
+        assert_eq!(config.sources.anki, "/home/me/stats/collection.anki2");
+        assert_eq!(config.sources.koreader, "/data/statistics.sqlite3");
+        assert_eq!(
+            config.sources.proseuche,
+            "/data/database.sqlite,/home/me/stats/phone.sqlite"
+        );
+    }
+}
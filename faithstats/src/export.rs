@@ -0,0 +1,199 @@
+use anyhow::Result;
+use serde::Serialize;
+use statsutils::{Bucket, Granularity, PeriodConfig};
+use std::collections::BTreeMap;
+use std::io::Write;
+use utoipa::ToSchema;
+
+use crate::models::{FaithDailyStats, FaithWeeklyStats};
+
+/// Sources that always get a column, in this order; additional sources follow by name
+const BUILTIN_SOURCES: [&str; 4] = ["anki", "reading", "church", "prayer"];
+
+/// Writes the daily series as CSV, one row per day
+///
+/// Columns are `date`, `<source>_minutes` for each source, and `total_minutes`. Other
+/// (non-Bible) reading isn't faith time, so it's left out like in the totals.
+pub fn write_daily_csv<W: Write>(writer: W, stats: &FaithDailyStats) -> Result<()> {
+    write_series(writer, "date", &daily_rows(stats))
+}
+
+/// Writes the weekly series as CSV, one row per week, with the same columns as
+/// `write_daily_csv` keyed by `week_start`
+pub fn write_weekly_csv<W: Write>(writer: W, stats: &FaithWeeklyStats) -> Result<()> {
+    write_series(writer, "week_start", &weekly_rows(stats))
+}
+
+/// One metric's values over time, in the Grafana JSON datasource format
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct TimeSeries {
+    /// Metric name, the same as the CSV column, e.g. `prayer_minutes`
+    pub target: String,
+    /// `[value, timestamp]` pairs, oldest first, with the timestamp in Unix milliseconds
+    /// at the start of the day or week
+    pub datapoints: Vec<[f64; 2]>,
+}
+
+/// The daily series as one `TimeSeries` per CSV column of `write_daily_csv`
+pub fn daily_time_series(stats: &FaithDailyStats) -> Result<Vec<TimeSeries>> {
+    time_series(&daily_rows(stats), Granularity::Day)
+}
+
+/// The weekly series as one `TimeSeries` per CSV column of `write_weekly_csv`
+pub fn weekly_time_series(stats: &FaithWeeklyStats) -> Result<Vec<TimeSeries>> {
+    time_series(&weekly_rows(stats), Granularity::Week)
+}
+
+fn daily_rows(stats: &FaithDailyStats) -> Vec<(String, BTreeMap<String, f64>)> {
+    stats
+        .days
+        .iter()
+        .map(|day| (day.date.clone(), day.minutes_by_source()))
+        .collect()
+}
+
+fn weekly_rows(stats: &FaithWeeklyStats) -> Vec<(String, BTreeMap<String, f64>)> {
+    stats
+        .weeks
+        .iter()
+        .map(|week| (week.week_start.clone(), week.minutes_by_source()))
+        .collect()
+}
+
+/// Every source that appears in any row: the built-in ones, then the rest by name
+fn series_sources(rows: &[(String, BTreeMap<String, f64>)]) -> Vec<&str> {
+    let mut sources: Vec<&str> = BUILTIN_SOURCES.to_vec();
+    for (_, minutes) in rows {
+        for source in minutes.keys() {
+            if !sources.contains(&source.as_str()) {
+                sources.push(source);
+            }
+        }
+    }
+    sources[BUILTIN_SOURCES.len()..].sort();
+    sources
+}
+
+/// Turns rows keyed by period into a series per source plus a total, timestamped at the
+/// start of each period's first day
+fn time_series(
+    rows: &[(String, BTreeMap<String, f64>)],
+    granularity: Granularity,
+) -> Result<Vec<TimeSeries>> {
+    let config = PeriodConfig::default();
+    let sources = series_sources(rows);
+    let mut series: Vec<TimeSeries> = sources
+        .iter()
+        .map(|source| format!("{}_minutes", source))
+        .chain(std::iter::once("total_minutes".to_string()))
+        .map(|target| TimeSeries {
+            target,
+            datapoints: Vec::with_capacity(rows.len()),
+        })
+        .collect();
+
+    for (key, minutes) in rows {
+        let start = Bucket::from_key(key, granularity)?.start;
+        let timestamp = config.day_start_ms(start)? as f64;
+        let values: Vec<f64> = sources
+            .iter()
+            .map(|source| minutes.get(*source).copied().unwrap_or(0.0))
+            .collect();
+        let total: f64 = values.iter().sum();
+        for (metric, value) in series.iter_mut().zip(values.into_iter().chain([total])) {
+            metric.datapoints.push([value, timestamp]);
+        }
+    }
+
+    Ok(series)
+}
+
+/// Writes rows of minutes per source, with a column for every source that appears in any
+/// row (0 where a row lacks it) and a total
+fn write_series<W: Write>(
+    writer: W,
+    key_column: &str,
+    rows: &[(String, BTreeMap<String, f64>)],
+) -> Result<()> {
+    let sources = series_sources(rows);
+
+    let mut csv_writer = csv::Writer::from_writer(writer);
+    let mut header = vec![key_column.to_string()];
+    header.extend(sources.iter().map(|source| format!("{}_minutes", source)));
+    header.push("total_minutes".to_string());
+    csv_writer.write_record(&header)?;
+
+    for (key, minutes) in rows {
+        let values: Vec<f64> = sources
+            .iter()
+            .map(|source| minutes.get(*source).copied().unwrap_or(0.0))
+            .collect();
+        let total: f64 = values.iter().sum();
+
+        let mut record = vec![key.clone()];
+        record.extend(values.iter().map(|v| format!("{:.2}", v)));
+        record.push(format!("{:.2}", total));
+        csv_writer.write_record(&record)?;
+    }
+
+    csv_writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rows() -> Vec<(String, BTreeMap<String, f64>)> {
+        vec![
+            (
+                "2025-06-01".to_string(),
+                BTreeMap::from([("prayer".to_string(), 15.0), ("sermons".to_string(), 40.0)]),
+            ),
+            (
+                "2025-06-02".to_string(),
+                BTreeMap::from([("anki".to_string(), 7.5)]),
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_write_series() {
+        let mut out = Vec::new();
+        write_series(&mut out, "date", &rows()).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "date,anki_minutes,reading_minutes,church_minutes,prayer_minutes,sermons_minutes,total_minutes\n\
+             2025-06-01,0.00,0.00,0.00,15.00,40.00,55.00\n\
+             2025-06-02,7.50,0.00,0.00,0.00,0.00,7.50\n"
+        );
+    }
+
+    #[test]
+    fn test_time_series() {
+        let series = time_series(&rows(), Granularity::Day).unwrap();
+        let targets: Vec<&str> = series.iter().map(|s| s.target.as_str()).collect();
+        assert_eq!(
+            targets,
+            [
+                "anki_minutes",
+                "reading_minutes",
+                "church_minutes",
+                "prayer_minutes",
+                "sermons_minutes",
+                "total_minutes"
+            ]
+        );
+
+        let config = PeriodConfig::default();
+        let day_start = |d| {
+            config
+                .day_start_ms(chrono::NaiveDate::from_ymd_opt(2025, 6, d).unwrap())
+                .unwrap() as f64
+        };
+        assert_eq!(
+            series[5].datapoints,
+            vec![[55.0, day_start(1)], [7.5, day_start(2)]]
+        );
+    }
+}
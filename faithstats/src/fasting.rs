@@ -0,0 +1,203 @@
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use crate::source::FaithDataSource;
+
+/// Type recorded for fasts that don't name one
+pub const DEFAULT_FAST_TYPE: &str = "fast";
+
+/// One entry of a fasting log
+///
+/// As CSV:
+///
+/// ```text
+/// date,type
+/// 2025-03-05,Ash Wednesday
+/// 2025-03-07,
+/// ```
+///
+/// Or as TOML (any file ending in `.toml`):
+///
+/// ```toml
+/// [[fasts]]
+/// date = "2025-03-05"
+/// type = "Ash Wednesday"
+/// ```
+///
+/// `type` is optional; other columns (such as notes) are ignored.
+#[derive(Debug, Deserialize)]
+struct LogEntry {
+    date: String,
+    #[serde(default, rename = "type")]
+    kind: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TomlLog {
+    #[serde(default)]
+    fasts: Vec<LogEntry>,
+}
+
+/// A day of fasting
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fast {
+    pub date: NaiveDate,
+    /// Kind of fast, e.g. "Ash Wednesday" or "water"; `DEFAULT_FAST_TYPE` when unnamed
+    pub kind: String,
+}
+
+/// Loads a fasting log, oldest first
+///
+/// # Arguments
+/// * `path` - Path to the log; read as TOML if it ends in `.toml`, otherwise as CSV
+pub fn load_fasts(path: &str) -> Result<Vec<Fast>> {
+    let file =
+        std::fs::File::open(path).context(format!("Failed to open fasting log '{}'", path))?;
+    let entries = if is_toml(path) {
+        parse_toml(file)
+    } else {
+        parse_csv(file)
+    }
+    .context(format!("Failed to read fasting log '{}'", path))?;
+
+    let mut fasts = entries
+        .into_iter()
+        .map(|entry| {
+            let date = NaiveDate::parse_from_str(entry.date.trim(), "%Y-%m-%d")
+                .context(format!("Invalid fast date '{}'", entry.date))?;
+            let kind = entry
+                .kind
+                .map(|kind| kind.trim().to_string())
+                .filter(|kind| !kind.is_empty())
+                .unwrap_or_else(|| DEFAULT_FAST_TYPE.to_string());
+            Ok(Fast { date, kind })
+        })
+        .collect::<Result<Vec<_>>>()
+        .context(format!("Invalid fasting log '{}'", path))?;
+    fasts.sort_by_key(|fast| fast.date);
+    Ok(fasts)
+}
+
+/// Counts the fasts of each type
+pub fn fasts_by_type(fasts: &[Fast]) -> BTreeMap<String, usize> {
+    let mut counts = BTreeMap::new();
+    for fast in fasts {
+        *counts.entry(fast.kind.clone()).or_default() += 1;
+    }
+    counts
+}
+
+fn is_toml(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("toml"))
+}
+
+fn parse_toml(mut reader: impl Read) -> Result<Vec<LogEntry>> {
+    let mut content = String::new();
+    reader.read_to_string(&mut content)?;
+    Ok(toml::from_str::<TomlLog>(&content)?.fasts)
+}
+
+fn parse_csv(reader: impl Read) -> Result<Vec<LogEntry>> {
+    let mut csv = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(reader);
+    csv.deserialize::<LogEntry>()
+        .enumerate()
+        // Header is line 1
+        .map(|(index, row)| row.context(format!("Invalid row on line {}", index + 2)))
+        .collect()
+}
+
+/// Fasting days, from a CSV or TOML log
+///
+/// Fasting isn't timed, so the source reports no minutes; it's listed among the data
+/// sources so `get_source_status` can check the log.
+pub struct FastingSource {
+    pub log_path: String,
+}
+
+impl FaithDataSource for FastingSource {
+    fn name(&self) -> &str {
+        "fasting"
+    }
+
+    fn paths(&self) -> Vec<PathBuf> {
+        vec![PathBuf::from(&self.log_path)]
+    }
+
+    fn schema(&self) -> Result<Option<String>> {
+        let format = if is_toml(&self.log_path) {
+            "TOML"
+        } else {
+            "CSV"
+        };
+        let fasts = load_fasts(&self.log_path)?;
+        Ok(Some(format!(
+            "{} fasting log ({} fasts)",
+            format,
+            fasts.len()
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 3, d).unwrap()
+    }
+
+    #[test]
+    fn test_load_csv_and_toml_logs() {
+        let dir = tempfile::tempdir().unwrap();
+        let csv_path = dir.path().join("fasts.csv");
+        std::fs::write(
+            &csv_path,
+            "date,type,notes\n2025-03-07,,\n2025-03-05, Ash Wednesday ,skipped lunch\n",
+        )
+        .unwrap();
+        let toml_path = dir.path().join("fasts.toml");
+        std::fs::write(
+            &toml_path,
+            "[[fasts]]\ndate = \"2025-03-05\"\ntype = \"Ash Wednesday\"\n\n\
+             [[fasts]]\ndate = \"2025-03-07\"\n",
+        )
+        .unwrap();
+
+        let expected = vec![
+            Fast {
+                date: date(5),
+                kind: "Ash Wednesday".to_string(),
+            },
+            Fast {
+                date: date(7),
+                kind: DEFAULT_FAST_TYPE.to_string(),
+            },
+        ];
+        assert_eq!(load_fasts(csv_path.to_str().unwrap()).unwrap(), expected);
+        assert_eq!(load_fasts(toml_path.to_str().unwrap()).unwrap(), expected);
+        assert_eq!(
+            fasts_by_type(&expected),
+            BTreeMap::from([
+                ("Ash Wednesday".to_string(), 1),
+                (DEFAULT_FAST_TYPE.to_string(), 1)
+            ])
+        );
+    }
+
+    #[test]
+    fn test_load_fasts_rejects_bad_dates() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("fasts.csv");
+        std::fs::write(&path, "date,type\nLent,\n").unwrap();
+        let err = load_fasts(path.to_str().unwrap()).unwrap_err();
+        assert!(format!("{:#}", err).contains("Invalid fast date 'Lent'"));
+    }
+}
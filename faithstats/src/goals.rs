@@ -0,0 +1,342 @@
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use statsutils::goals::{self, Goal, GoalPeriod};
+use statsutils::{DatePeriod, Granularity, PeriodConfig, PeriodSeries};
+use std::fmt;
+use utoipa::ToSchema;
+
+use crate::config::FaithConfig;
+use crate::source::{
+    AnkiSource, ChurchSource, FaithDataSource, PrayerSource, ReadingSource, collect_minutes,
+};
+
+/// What a faith goal measures
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FaithMetric {
+    AnkiMinutes,
+    /// Anki passages that reached maturity
+    AnkiMaturedPassages,
+    ReadingMinutes,
+    /// Distinct Bible pages read
+    ReadingPages,
+    ChurchMinutes,
+    /// Days with any church attendance
+    ChurchServices,
+    PrayerMinutes,
+    /// Minutes across every configured source
+    TotalMinutes,
+}
+
+impl FaithMetric {
+    /// Singular and plural name of the metric's unit
+    fn unit(&self) -> (&'static str, &'static str) {
+        match self {
+            FaithMetric::AnkiMinutes => ("Anki minute", "Anki minutes"),
+            FaithMetric::AnkiMaturedPassages => ("passage matured", "passages matured"),
+            FaithMetric::ReadingMinutes => ("Bible reading minute", "Bible reading minutes"),
+            FaithMetric::ReadingPages => ("Bible page", "Bible pages"),
+            FaithMetric::ChurchMinutes => ("church minute", "church minutes"),
+            FaithMetric::ChurchServices => ("church service", "church services"),
+            FaithMetric::PrayerMinutes => ("prayer minute", "prayer minutes"),
+            FaithMetric::TotalMinutes => ("faith minute", "faith minutes"),
+        }
+    }
+}
+
+/// The window a faith goal's target applies to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum FaithGoalPeriod {
+    Day,
+    Week,
+    Month,
+    Year,
+}
+
+impl fmt::Display for FaithGoalPeriod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            FaithGoalPeriod::Day => "day",
+            FaithGoalPeriod::Week => "week",
+            FaithGoalPeriod::Month => "month",
+            FaithGoalPeriod::Year => "year",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl From<FaithGoalPeriod> for GoalPeriod {
+    fn from(period: FaithGoalPeriod) -> Self {
+        match period {
+            FaithGoalPeriod::Day => GoalPeriod::Day,
+            FaithGoalPeriod::Week => GoalPeriod::Week,
+            FaithGoalPeriod::Month => GoalPeriod::Month,
+            FaithGoalPeriod::Year => GoalPeriod::Year,
+        }
+    }
+}
+
+/// A rule-of-life target, as written in the `[[goals]]` tables of `lifestats.toml`
+///
+/// ```toml
+/// [[goals]]
+/// metric = "prayer_minutes"
+/// target = 20
+/// period = "day"
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct FaithGoal {
+    pub metric: FaithMetric,
+    pub target: f64,
+    pub period: FaithGoalPeriod,
+}
+
+impl FaithGoal {
+    /// Human-readable summary, e.g. "20 prayer minutes per day"
+    pub fn description(&self) -> String {
+        let (singular, plural) = self.metric.unit();
+        let unit = if self.target == 1.0 { singular } else { plural };
+        format!("{} {} per {}", self.target, unit, self.period)
+    }
+}
+
+/// How a faith goal is going in its current window
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct FaithGoalProgress {
+    pub goal: FaithGoal,
+    /// Human-readable summary of the goal
+    pub description: String,
+    /// Progress so far this window, in the goal's unit
+    pub current: f64,
+    pub target: f64,
+    /// Amount still needed to reach the target (0 once achieved)
+    pub remaining: f64,
+    /// Progress as a percentage of the target, capped at 100
+    pub percent: f64,
+    pub achieved: bool,
+    /// Whether the goal is keeping pace with the share of the window elapsed, or already met
+    pub on_track: bool,
+    /// Days left in the window, including today
+    pub days_left: i64,
+    /// Amount needed per remaining day to reach the target
+    pub pace_needed: f64,
+}
+
+/// Measures a goal against daily values of its metric (YYYY-MM-DD keys) covering the
+/// current window up to `today`
+pub fn evaluate_goal(
+    goal: &FaithGoal,
+    daily_values: &PeriodSeries<f64>,
+    today: NaiveDate,
+    config: &PeriodConfig,
+) -> Result<FaithGoalProgress> {
+    let shared_goal = Goal {
+        metric: goal.metric.unit().1.to_string(),
+        target: goal.target,
+        period: goal.period.into(),
+    };
+    let progress = goals::evaluate_as_of(daily_values, &shared_goal, today, config)?;
+
+    Ok(FaithGoalProgress {
+        goal: goal.clone(),
+        description: goal.description(),
+        current: progress.current,
+        target: progress.target,
+        remaining: progress.remaining,
+        percent: progress.percent,
+        achieved: progress.achieved,
+        on_track: progress.on_track,
+        days_left: progress.days_left,
+        pace_needed: progress.pace_needed,
+    })
+}
+
+/// Measures each of the config's goals, reading its current window from the sources
+///
+/// # Errors
+/// Returns an error if a source a goal needs is unavailable, including church goals
+/// without an Arc Timeline export
+pub fn evaluate_faith_goals(config: &FaithConfig) -> Result<Vec<FaithGoalProgress>> {
+    evaluate_faith_goals_as_of(config, PeriodConfig::default().today()?)
+}
+
+/// Like `evaluate_faith_goals`, measuring each goal's window as it stood at the end of
+/// `today`
+pub fn evaluate_faith_goals_as_of(
+    config: &FaithConfig,
+    today: NaiveDate,
+) -> Result<Vec<FaithGoalProgress>> {
+    let period_config = PeriodConfig::default();
+
+    config
+        .goals
+        .iter()
+        .map(|goal| {
+            let (start, _) = GoalPeriod::from(goal.period).bounds(today, today, &period_config)?;
+            let period = DatePeriod::for_range(start, today, Granularity::Day)?;
+            let daily_values = metric_values(config, goal.metric, &period)?;
+            evaluate_goal(goal, &daily_values, today, &period_config)
+        })
+        .collect()
+}
+
+/// Daily values of `metric` for each day of `period`
+fn metric_values(
+    config: &FaithConfig,
+    metric: FaithMetric,
+    period: &DatePeriod,
+) -> Result<PeriodSeries<f64>> {
+    let church = || -> Result<ChurchSource> {
+        Ok(ChurchSource {
+            export_path: config.arc_path()?.to_string(),
+        })
+    };
+
+    Ok(match metric {
+        FaithMetric::AnkiMinutes => period_minutes(
+            &AnkiSource {
+                db_path: config.sources.anki.clone(),
+            },
+            period,
+        )?,
+        FaithMetric::AnkiMaturedPassages => PeriodSeries::new(
+            ankistats::get_stats_for_period_with_config(
+                &config.sources.anki,
+                period,
+                &PeriodConfig::default(),
+            )?
+            .into_iter()
+            .map(|p| (p.period_start, p.matured_passages as f64))
+            .collect(),
+        ),
+        FaithMetric::ReadingMinutes => period_minutes(
+            &ReadingSource {
+                db_path: config.sources.koreader.clone(),
+            },
+            period,
+        )?,
+        FaithMetric::ReadingPages => PeriodSeries::new(
+            readingstats::source::source_for(&config.sources.koreader)?
+                .get_stats_for_period(period)?
+                .into_iter()
+                .map(|p| (p.period_start, p.pages as f64))
+                .collect(),
+        ),
+        FaithMetric::ChurchMinutes => period_minutes(&church()?, period)?,
+        FaithMetric::ChurchServices => {
+            let days = period_minutes(&church()?, period)?;
+            PeriodSeries::new(
+                days.map(|date, minutes| (date, if minutes > 0.0 { 1.0 } else { 0.0 })),
+            )
+        }
+        FaithMetric::PrayerMinutes => period_minutes(
+            &PrayerSource {
+                db_path: config.sources.proseuche.clone(),
+            },
+            period,
+        )?,
+        FaithMetric::TotalMinutes => {
+            let sources = config.data_sources();
+            let sources: Vec<&dyn FaithDataSource> = sources.iter().map(|s| s.as_ref()).collect();
+            let by_source =
+                collect_minutes(period.dates.clone(), &sources, |s| s.period_minutes(period))?;
+            PeriodSeries::new(by_source.map(|date, minutes| (date, minutes.values().sum())))
+        }
+    })
+}
+
+fn period_minutes(source: &dyn FaithDataSource, period: &DatePeriod) -> Result<PeriodSeries<f64>> {
+    source.period_minutes(period)?.context(format!(
+        "The {} source can't report arbitrary periods",
+        source.name()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Weekday;
+    use statsutils::StatsTimezone;
+
+    fn config() -> PeriodConfig {
+        PeriodConfig::new(StatsTimezone::Named("UTC".parse().unwrap()), 4)
+            .with_week_start(Weekday::Sun)
+    }
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    fn series(values: &[(&str, f64)]) -> PeriodSeries<f64> {
+        PeriodSeries::new(values.iter().map(|&(d, v)| (d.to_string(), v)).collect())
+    }
+
+    #[test]
+    fn test_parse_goals_from_config() {
+        let config = FaithConfig::parse(
+            r#"
+            [sources]
+            anki = "collection.anki2"
+            koreader = "statistics.sqlite3"
+            proseuche = "database.sqlite"
+
+            [[goals]]
+            metric = "prayer_minutes"
+            target = 20
+            period = "day"
+
+            [[goals]]
+            metric = "church_services"
+            target = 1
+            period = "week"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.goals.len(), 2);
+        assert_eq!(config.goals[0].description(), "20 prayer minutes per day");
+        assert_eq!(config.goals[1].metric, FaithMetric::ChurchServices);
+        assert_eq!(config.goals[1].description(), "1 church service per week");
+    }
+
+    #[test]
+    fn test_evaluate_weekly_goal() {
+        // Wednesday of the week starting Sunday 2025-06-01
+        let goal = FaithGoal {
+            metric: FaithMetric::PrayerMinutes,
+            target: 140.0,
+            period: FaithGoalPeriod::Week,
+        };
+        let values = series(&[
+            ("2025-06-01", 20.0),
+            ("2025-06-02", 30.0),
+            ("2025-06-03", 0.0),
+            ("2025-06-04", 30.0),
+        ]);
+
+        let progress = evaluate_goal(&goal, &values, date("2025-06-04"), &config()).unwrap();
+        assert_eq!(progress.current, 80.0);
+        assert_eq!(progress.remaining, 60.0);
+        assert_eq!(progress.days_left, 4);
+        assert!(progress.on_track);
+        assert!(!progress.achieved);
+    }
+
+    #[test]
+    fn test_evaluate_daily_goal_behind() {
+        let goal = FaithGoal {
+            metric: FaithMetric::ReadingMinutes,
+            target: 20.0,
+            period: FaithGoalPeriod::Day,
+        };
+        let values = series(&[("2025-06-04", 5.0)]);
+
+        let progress = evaluate_goal(&goal, &values, date("2025-06-04"), &config()).unwrap();
+        assert_eq!(progress.percent, 25.0);
+        assert!(!progress.on_track);
+        assert_eq!(progress.pace_needed, 15.0);
+    }
+}
@@ -0,0 +1,182 @@
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use serde::Deserialize;
+use statsutils::{DatePeriod, PeriodSeries};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::source::{DailyMinutes, FaithDataSource};
+
+/// File name of a daily note, as a chrono format, e.g. `2025-06-10.md`
+pub const DEFAULT_PATTERN: &str = "%Y-%m-%d.md";
+
+/// Writing speed used to turn word counts into journaling time
+pub const DEFAULT_WORDS_PER_MINUTE: f64 = 20.0;
+
+/// How journal entries are found and timed, from the config's `[journal]` table
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct JournalOptions {
+    /// chrono format matching entries' file names; defaults to `DEFAULT_PATTERN`
+    #[serde(default)]
+    pub pattern: Option<String>,
+    /// Words written per minute; defaults to `DEFAULT_WORDS_PER_MINUTE`
+    #[serde(default)]
+    pub words_per_minute: Option<f64>,
+}
+
+impl JournalOptions {
+    pub fn pattern(&self) -> &str {
+        self.pattern.as_deref().unwrap_or(DEFAULT_PATTERN)
+    }
+
+    pub fn words_per_minute(&self) -> f64 {
+        self.words_per_minute.unwrap_or(DEFAULT_WORDS_PER_MINUTE)
+    }
+}
+
+/// Counts the words written on each date in a folder of Markdown notes, such as Obsidian
+/// daily notes
+///
+/// Subfolders are searched too. Files whose names don't match `pattern` are skipped, and
+/// several entries on one date are added together.
+///
+/// # Arguments
+/// * `dir` - The journal folder
+/// * `pattern` - chrono format of entries' file names, e.g. `%Y-%m-%d.md`
+pub fn load_journal_words(dir: &str, pattern: &str) -> Result<BTreeMap<NaiveDate, i64>> {
+    let mut words = BTreeMap::new();
+    scan_dir(Path::new(dir), pattern, &mut words)
+        .context(format!("Failed to read journal folder '{}'", dir))?;
+    Ok(words)
+}
+
+fn scan_dir(dir: &Path, pattern: &str, words: &mut BTreeMap<NaiveDate, i64>) -> Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            scan_dir(&path, pattern, words)?;
+            continue;
+        }
+
+        let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        let Ok(date) = NaiveDate::parse_from_str(name, pattern) else {
+            continue;
+        };
+        let text = std::fs::read_to_string(&path)
+            .context(format!("Failed to read journal entry '{}'", path.display()))?;
+        *words.entry(date).or_default() += count_words(&text);
+    }
+    Ok(())
+}
+
+/// Counts the words in a Markdown note, leaving out YAML front matter and tokens without
+/// letters or digits (list markers, headings, rules)
+fn count_words(text: &str) -> i64 {
+    let body = text
+        .strip_prefix("---\n")
+        .and_then(|rest| rest.split_once("\n---").map(|(_, body)| body))
+        .unwrap_or(text);
+    body.split_whitespace()
+        .filter(|word| word.chars().any(char::is_alphanumeric))
+        .count() as i64
+}
+
+/// Journaling, from a folder of Markdown notes
+///
+/// Journaling time is estimated from each day's word count at `words_per_minute`.
+pub struct JournalSource {
+    pub dir: String,
+    pub pattern: String,
+    pub words_per_minute: f64,
+}
+
+impl JournalSource {
+    fn daily(&self) -> Result<DailyMinutes> {
+        let words = load_journal_words(&self.dir, &self.pattern)?;
+        Ok(DailyMinutes(
+            words
+                .into_iter()
+                .map(|(date, count)| (date, count as f64 / self.words_per_minute))
+                .collect(),
+        ))
+    }
+}
+
+impl FaithDataSource for JournalSource {
+    fn name(&self) -> &str {
+        "journaling"
+    }
+
+    fn today_minutes(&self) -> Result<Option<f64>> {
+        Ok(Some(self.daily()?.today()?))
+    }
+
+    fn daily_minutes(&self) -> Result<Option<PeriodSeries<f64>>> {
+        Ok(Some(self.daily()?.for_period(&DatePeriod::last_30_days()?)))
+    }
+
+    fn weekly_minutes(&self) -> Result<Option<PeriodSeries<f64>>> {
+        Ok(Some(
+            self.daily()?.for_period(&DatePeriod::last_12_weeks()?),
+        ))
+    }
+
+    fn period_minutes(&self, period: &DatePeriod) -> Result<Option<PeriodSeries<f64>>> {
+        Ok(Some(self.daily()?.for_period(period)))
+    }
+
+    fn active_dates(&self) -> Result<Option<Vec<NaiveDate>>> {
+        Ok(Some(self.daily()?.active_dates()))
+    }
+
+    /// The folder itself, whose modification time changes as entries are added or removed
+    /// at its top level
+    ///
+    /// Editing an existing entry, or adding one in a subfolder, leaves the folder's time
+    /// alone, so `FaithStatsCache` only picks those up when its TTL expires.
+    fn paths(&self) -> Vec<PathBuf> {
+        vec![PathBuf::from(&self.dir)]
+    }
+
+    fn schema(&self) -> Result<Option<String>> {
+        let words = load_journal_words(&self.dir, &self.pattern)?;
+        Ok(Some(format!(
+            "Markdown journal ({} days, {})",
+            words.len(),
+            self.pattern
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_count_words() {
+        assert_eq!(count_words("# Morning\n\n- Read Psalm 23 today.\n"), 5);
+        assert_eq!(
+            count_words("---\ntags: [journal, prayer]\n---\nGrateful for rain.\n---\n"),
+            3
+        );
+    }
+
+    #[test]
+    fn test_load_journal_words() {
+        let dir = tempfile::tempdir().unwrap();
+        let nested = dir.path().join("2025/06");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::write(nested.join("2025-06-10.md"), "One two three").unwrap();
+        std::fs::write(dir.path().join("2025-06-11.md"), "Four five").unwrap();
+        std::fs::write(dir.path().join("Ideas.md"), "Not a daily note").unwrap();
+
+        let words = load_journal_words(dir.path().to_str().unwrap(), DEFAULT_PATTERN).unwrap();
+        let date = |d| NaiveDate::from_ymd_opt(2025, 6, d).unwrap();
+        assert_eq!(words, BTreeMap::from([(date(10), 3), (date(11), 2)]));
+
+        assert!(load_journal_words("/nonexistent/journal", DEFAULT_PATTERN).is_err());
+    }
+}
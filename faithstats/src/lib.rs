@@ -1,168 +1,790 @@
+pub mod cache;
+pub mod config;
+pub mod export;
+pub mod fasting;
+pub mod goals;
+pub mod journal;
 pub mod models;
+#[cfg(feature = "tokio")]
+pub mod nonblocking;
+pub mod notify;
+pub mod report;
+pub mod score;
+pub mod source;
+pub mod youversion;
 
-use anyhow::Result;
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, SecondsFormat, Utc, Weekday};
+use statsutils::{DatePeriod, Granularity, PeriodConfig, PeriodSeries, parse_local_date, streaks};
+use std::collections::BTreeMap;
 
+use crate::config::FaithConfig;
 use crate::models::{
-    FaithDailyStats, FaithDayStats, FaithTodayStats, FaithWeekStats, FaithWeeklyStats,
+    BibleBookRead, FaithAnnualReport, FaithDailyStats, FaithDayStats, FaithPeriodStats,
+    FaithRangeStats, FaithStreaks, FaithTodayStats, FaithWeekStats, FaithWeeklyStats, SourceStatus,
+    SundayComparison, SundayStats,
 };
+use crate::score::ScoreWeights;
+use crate::source::{FaithDataSource, buckets_of, builtin_sources, collect_minutes};
 
 /// Gets unified faith statistics for the last 30 days, combining Anki Bible memorization,
-/// KOReader Bible reading, and prayer time data.
+/// KOReader Bible reading, Arc church attendance, prayer time, and the optional sources in
+/// `config`
 ///
-/// # Arguments
-/// * `anki_db_path` - Path to the Anki collection.anki2 database file
-/// * `koreader_db_path` - Path to the KOReader statistics.sqlite3 database file
-/// * `proseuche_db_path` - Path to the Proseuche database.sqlite file
-///
-/// # Returns
-/// FaithDailyStats containing daily breakdown and summary statistics
+/// Church time is 0 without an Arc Timeline export. Days are scored with the config's
+/// weights, and the summary reports progress on its goals.
 ///
 /// # Errors
 /// Returns an error if any database is unavailable or cannot be queried
 ///
 /// # Example
 /// ```ignore
-/// use faithstats::get_faith_daily_stats;
+/// use faithstats::{config::FaithConfig, get_faith_daily_stats};
 ///
-/// let stats = get_faith_daily_stats(
-///     "/path/to/collection.anki2",
-///     "/path/to/statistics.sqlite3",
-///     "/path/to/database.sqlite"
-/// )?;
+/// let config = FaithConfig::from_file("lifestats.toml")?;
+/// let stats = get_faith_daily_stats(&config)?;
 /// println!("Total faith time: {:.2} hours", stats.summary.total_hours);
 /// ```
-pub fn get_faith_daily_stats(
-    anki_db_path: &str,
+pub fn get_faith_daily_stats(config: &FaithConfig) -> Result<FaithDailyStats> {
+    let sources = config.data_sources();
+    let sources: Vec<&dyn FaithDataSource> = sources.iter().map(|s| s.as_ref()).collect();
+    let mut stats = faith_daily_stats(&sources, &config.sources.koreader, &config.score)?;
+
+    if let Some(export_path) = &config.sources.youversion {
+        let plan_days = youversion::plan_days_by_date(&youversion::load_plan_days(export_path)?);
+        for day in &mut stats.days {
+            let date = parse_local_date(&day.date)?;
+            day.reading_plan_days = plan_days.get(&date).copied().unwrap_or(0);
+        }
+        stats.summary.reading_plan_days_completed =
+            stats.days.iter().map(|d| d.reading_plan_days).sum();
+    }
+    if let Some(dir) = &config.sources.journal {
+        let words = journal::load_journal_words(dir, config.journal.pattern())?;
+        for day in &mut stats.days {
+            let date = parse_local_date(&day.date)?;
+            day.journal_words = words.get(&date).copied().unwrap_or(0);
+        }
+        stats.summary.journal_days = stats.days.iter().filter(|d| d.journal_words > 0).count();
+        stats.summary.journal_total_words = stats.days.iter().map(|d| d.journal_words).sum();
+    }
+    stats.summary.goals = goals::evaluate_faith_goals(config)?;
+    Ok(stats)
+}
+
+/// The last 30 days from each of `sources`, scored with `weights`, with reading streaks
+/// and session lengths from the reading database at `koreader_db_path`
+fn faith_daily_stats(
+    sources: &[&dyn FaithDataSource],
     koreader_db_path: &str,
-    proseuche_db_path: &str,
+    weights: &ScoreWeights,
 ) -> Result<FaithDailyStats> {
     // Query all databases - will return error if any is unavailable
-    let anki_stats = ankistats::get_last_30_days_stats(anki_db_path)?;
-    let reading_stats = readingstats::get_last_30_days_stats(koreader_db_path)?;
-    let prayer_stats = prayerstats::get_last_30_days_stats(proseuche_db_path)?;
-
-    // All functions return the same 30 dates in the same order (guaranteed by DatePeriod),
-    // so we can simply zip them together
-    let merged_days: Vec<FaithDayStats> = anki_stats
-        .into_iter()
-        .zip(reading_stats)
-        .zip(prayer_stats)
-        .map(|((anki_day, reading_day), prayer_day)| FaithDayStats {
-            date: anki_day.date,
-            anki_minutes: anki_day.minutes,
-            anki_matured_passages: anki_day.matured_passages,
-            anki_lost_passages: anki_day.lost_passages,
-            anki_cumulative_passages: anki_day.cumulative_passages,
-            reading_minutes: reading_day.minutes,
-            prayer_minutes: prayer_day.minutes,
-        })
+    let period = DatePeriod::last_30_days()?;
+    let mut days: Vec<FaithDayStats> = period
+        .dates
+        .iter()
+        .cloned()
+        .map(FaithDayStats::new)
         .collect();
+    for source in sources {
+        source.merge_daily(&mut days)?;
+    }
 
-    Ok(FaithDailyStats::new(merged_days))
+    let reading = readingstats::source::source_for(koreader_db_path)?;
+    let reading_streaks =
+        reading.get_reading_streaks(readingstats::streaks::DEFAULT_STREAK_MINUTES)?;
+    let reading_sessions = reading.get_bible_session_stats(&period)?;
+    let faith_streaks = faith_streaks(sources)?;
+
+    let mut stats = FaithDailyStats::new(days, &reading_streaks, &reading_sessions, &faith_streaks);
+    weights.apply(&mut stats);
+    Ok(stats)
 }
 
-/// Gets unified faith statistics for today, combining Anki Bible memorization,
-/// KOReader Bible reading, and prayer time data.
-///
-/// # Arguments
-/// * `anki_db_path` - Path to the Anki collection.anki2 database file
-/// * `koreader_db_path` - Path to the KOReader statistics.sqlite3 database file
-/// * `proseuche_db_path` - Path to the Proseuche database.sqlite file
+/// Gets unified faith statistics for today, combining Anki Bible memorization, KOReader
+/// Bible reading, Arc church attendance, prayer time, and the optional sources in `config`
+/// (reported in `other_minutes`)
 ///
-/// # Returns
-/// FaithTodayStats containing today's combined statistics
+/// Church time is 0 without an Arc Timeline export.
 ///
 /// # Errors
 /// Returns an error if any database is unavailable or cannot be queried
 ///
 /// # Example
 /// ```ignore
-/// use faithstats::get_faith_today_stats;
+/// use faithstats::{config::FaithConfig, get_faith_today_stats};
 ///
-/// let stats = get_faith_today_stats(
-///     "/path/to/collection.anki2",
-///     "/path/to/statistics.sqlite3",
-///     "/path/to/database.sqlite"
-/// )?;
+/// let config = FaithConfig::from_file("lifestats.toml")?;
+/// let stats = get_faith_today_stats(&config)?;
 /// println!("Total faith time today: {:.2} hours", stats.total_hours);
 /// ```
-pub fn get_faith_today_stats(
-    anki_db_path: &str,
-    koreader_db_path: &str,
-    proseuche_db_path: &str,
-) -> Result<FaithTodayStats> {
-    // Query all databases - will return error if any is unavailable
-    let anki_minutes = ankistats::get_today_study_time(anki_db_path)?;
-    let reading_minutes = readingstats::get_today_reading_time(koreader_db_path)?;
-    let prayer_minutes = prayerstats::get_today_prayer_time(proseuche_db_path)?;
+pub fn get_faith_today_stats(config: &FaithConfig) -> Result<FaithTodayStats> {
+    let sources = config.data_sources();
+    let sources: Vec<&dyn FaithDataSource> = sources.iter().map(|s| s.as_ref()).collect();
+    faith_today_stats(&sources)
+}
 
-    Ok(FaithTodayStats::new(
-        anki_minutes,
-        reading_minutes,
-        prayer_minutes,
-    ))
+/// Today's minutes from each of `sources`
+fn faith_today_stats(sources: &[&dyn FaithDataSource]) -> Result<FaithTodayStats> {
+    // Query all databases - will return error if any is unavailable
+    let mut today = FaithTodayStats::new(0.0, 0.0, 0.0, 0.0, BTreeMap::new());
+    for source in sources {
+        source.merge_today(&mut today)?;
+    }
+    today.update_totals();
+    Ok(today)
 }
 
 /// Gets unified faith statistics for the last 12 weeks, combining Anki Bible memorization,
-/// KOReader Bible reading, Arc church attendance, and prayer time data.
-///
-/// # Arguments
-/// * `anki_db_path` - Path to the Anki collection.anki2 database file
-/// * `koreader_db_path` - Path to the KOReader statistics.sqlite3 database file
-/// * `arcstats_export_path` - Path to the Arc Timeline export directory
-/// * `proseuche_db_path` - Path to the Proseuche database.sqlite file
-///
-/// # Returns
-/// FaithWeeklyStats containing weekly breakdown and summary statistics
+/// KOReader Bible reading, Arc church attendance, prayer time, and the optional sources in
+/// `config`, with journaling and fasting days and progress on the config's goals
 ///
 /// # Errors
-/// Returns an error if any database/export is unavailable or cannot be queried
+/// Returns an error if `config` has no Arc Timeline export path, or if any database/export
+/// is unavailable or cannot be queried
 ///
 /// # Example
 /// ```ignore
-/// use faithstats::get_faith_weekly_stats;
-///
-/// let stats = get_faith_weekly_stats(
-///     "/path/to/collection.anki2",
-///     "/path/to/statistics.sqlite3",
-///     "/path/to/arc/export",
-///     "/path/to/database.sqlite"
-/// )?;
+/// use faithstats::{config::FaithConfig, get_faith_weekly_stats};
+///
+/// let config = FaithConfig::from_file("lifestats.toml")?;
+/// let stats = get_faith_weekly_stats(&config)?;
 /// println!("Total faith time: {:.2} hours", stats.summary.total_hours);
 /// ```
-pub fn get_faith_weekly_stats(
-    anki_db_path: &str,
-    koreader_db_path: &str,
-    arcstats_export_path: &str,
-    proseuche_db_path: &str,
-) -> Result<FaithWeeklyStats> {
+pub fn get_faith_weekly_stats(config: &FaithConfig) -> Result<FaithWeeklyStats> {
+    config.arc_path()?;
+    let sources = config.data_sources();
+    let sources: Vec<&dyn FaithDataSource> = sources.iter().map(|s| s.as_ref()).collect();
+    let mut stats = faith_weekly_stats(&sources)?;
+
+    if let Some(dir) = &config.sources.journal {
+        let period_config = PeriodConfig::default();
+        let mut weekly: BTreeMap<String, (i64, i64)> = BTreeMap::new();
+        for (date, words) in journal::load_journal_words(dir, config.journal.pattern())? {
+            let week = weekly
+                .entry(Granularity::Week.key(date, &period_config))
+                .or_default();
+            week.0 += i64::from(words > 0);
+            week.1 += words;
+        }
+        for week in &mut stats.weeks {
+            let (days, words) = weekly.get(&week.week_start).copied().unwrap_or_default();
+            week.journal_days = days;
+            week.journal_words = words;
+        }
+    }
+    if let Some(log_path) = &config.sources.fasting {
+        let period_config = PeriodConfig::default();
+        let mut dates: Vec<NaiveDate> = fasting::load_fasts(log_path)?
+            .into_iter()
+            .map(|fast| fast.date)
+            .collect();
+        dates.dedup();
+        for week in &mut stats.weeks {
+            week.fasting_days = dates
+                .iter()
+                .filter(|date| Granularity::Week.key(**date, &period_config) == week.week_start)
+                .count() as i64;
+        }
+    }
+    stats.summary.goals = goals::evaluate_faith_goals(config)?;
+    Ok(stats)
+}
+
+/// The last 12 weeks from each of `sources`
+fn faith_weekly_stats(sources: &[&dyn FaithDataSource]) -> Result<FaithWeeklyStats> {
     // Query all databases - will return error if any is unavailable
-    let anki_stats = ankistats::get_last_12_weeks_stats(anki_db_path)?;
-    let reading_stats = readingstats::get_last_12_weeks_stats(koreader_db_path)?;
-    let church_stats = arcstats::get_last_12_weeks_stats(arcstats_export_path)?;
-    let prayer_stats = prayerstats::get_last_12_weeks_stats(proseuche_db_path)?;
-
-    // All functions return the same 12 weeks in the same order (guaranteed by DatePeriod),
-    // so we can simply zip them together
-    let merged_weeks: Vec<FaithWeekStats> = anki_stats
+    let mut weeks: Vec<FaithWeekStats> = DatePeriod::last_12_weeks()?
+        .dates
         .into_iter()
-        .zip(reading_stats)
-        .zip(church_stats)
-        .zip(prayer_stats)
-        .map(
-            |(((anki_week, reading_week), church_week), prayer_week)| FaithWeekStats {
-                week_start: anki_week.week_start,
-                anki_minutes: anki_week.minutes,
-                anki_matured_passages: anki_week.matured_passages,
-                anki_lost_passages: anki_week.lost_passages,
-                anki_cumulative_passages: anki_week.cumulative_passages,
-                reading_minutes: reading_week.minutes,
-                at_church_minutes: church_week.minutes,
-                at_church_daily_minutes: church_week.daily_minutes,
-                prayer_minutes: prayer_week.minutes,
-            },
-        )
+        .map(FaithWeekStats::new)
         .collect();
+    for source in sources {
+        source.merge_weekly(&mut weeks)?;
+    }
+
+    Ok(FaithWeeklyStats::new(weeks))
+}
+
+/// Gets faith time for an arbitrary date range from every source in `config`, with one
+/// entry per day, week, or month depending on `granularity`
+///
+/// `start_date` and `end_date` are inclusive YYYY-MM-DD dates. Sources that can't report
+/// arbitrary periods are left out; every other source must cover each bucket.
+///
+/// # Example
+/// ```ignore
+/// use faithstats::{config::FaithConfig, get_faith_stats_for_range};
+/// use statsutils::Granularity;
+///
+/// let config = FaithConfig::from_file("lifestats.toml")?;
+/// let stats = get_faith_stats_for_range(&config, "2025-01-01", "2025-12-31", Granularity::Month)?;
+/// ```
+pub fn get_faith_stats_for_range(
+    config: &FaithConfig,
+    start_date: &str,
+    end_date: &str,
+    granularity: Granularity,
+) -> Result<FaithRangeStats> {
+    let sources = config.data_sources();
+    let sources: Vec<&dyn FaithDataSource> = sources.iter().map(|s| s.as_ref()).collect();
+    faith_stats_for_range(&sources, start_date, end_date, granularity)
+}
+
+fn faith_stats_for_range(
+    sources: &[&dyn FaithDataSource],
+    start_date: &str,
+    end_date: &str,
+    granularity: Granularity,
+) -> Result<FaithRangeStats> {
+    let start = parse_local_date(start_date).context("Invalid start date")?;
+    let end = parse_local_date(end_date).context("Invalid end date")?;
+    faith_stats_for_period(sources, &DatePeriod::for_range(start, end, granularity)?)
+}
+
+/// Gets faith time from every source in `config` for each of the last 12 months,
+/// including the current one
+///
+/// Sources that can't report arbitrary periods are left out, as with
+/// `get_faith_stats_for_range`.
+pub fn get_faith_monthly_stats(config: &FaithConfig) -> Result<FaithRangeStats> {
+    let sources = config.data_sources();
+    let sources: Vec<&dyn FaithDataSource> = sources.iter().map(|s| s.as_ref()).collect();
+    faith_stats_for_period(&sources, &DatePeriod::last_12_months()?)
+}
+
+pub(crate) fn faith_stats_for_period(
+    sources: &[&dyn FaithDataSource],
+    period: &DatePeriod,
+) -> Result<FaithRangeStats> {
+    let minutes = collect_minutes(period.dates.clone(), sources, |s| s.period_minutes(period))?;
+    let periods = period
+        .buckets()?
+        .into_iter()
+        .zip(minutes.into_values())
+        .map(|(bucket, minutes)| FaithPeriodStats::new(bucket.key, bucket.label, minutes))
+        .collect();
+
+    Ok(FaithRangeStats::new(period.granularity, periods))
+}
+
+/// Gets all-time streaks of consecutive days with faith activity, across every source in
+/// `config` and for each source on its own
+///
+/// Sources that can't list their active days are left out.
+///
+/// # Example
+/// ```ignore
+/// use faithstats::{config::FaithConfig, get_faith_streaks};
+///
+/// let config = FaithConfig::from_file("lifestats.toml")?;
+/// let streaks = get_faith_streaks(&config)?;
+/// println!("Current streak: {} days", streaks.any_activity.current_streak);
+/// ```
+pub fn get_faith_streaks(config: &FaithConfig) -> Result<FaithStreaks> {
+    let sources = config.data_sources();
+    let sources: Vec<&dyn FaithDataSource> = sources.iter().map(|s| s.as_ref()).collect();
+    faith_streaks(&sources)
+}
+
+fn faith_streaks(sources: &[&dyn FaithDataSource]) -> Result<FaithStreaks> {
+    faith_streaks_as_of(sources, PeriodConfig::default().today()?)
+}
+
+/// Like `faith_streaks`, ignoring any activity after `today`
+pub(crate) fn faith_streaks_as_of(
+    sources: &[&dyn FaithDataSource],
+    today: NaiveDate,
+) -> Result<FaithStreaks> {
+    let mut active_dates = Vec::new();
+    for &source in sources {
+        if let Some(mut dates) = source.active_dates()? {
+            dates.retain(|date| *date <= today);
+            active_dates.push((source.name().to_string(), dates));
+        }
+    }
+    Ok(streaks_from_dates(&active_dates, today))
+}
+
+/// Streaks from each source's active days, as (source name, dates) pairs
+fn streaks_from_dates(active_dates: &[(String, Vec<NaiveDate>)], today: NaiveDate) -> FaithStreaks {
+    let all_dates: Vec<NaiveDate> = active_dates
+        .iter()
+        .flat_map(|(_, dates)| dates.iter().copied())
+        .collect();
+
+    FaithStreaks {
+        any_activity: streaks::compute_as_of(&all_dates, today).into(),
+        by_source: active_dates
+            .iter()
+            .map(|(name, dates)| (name.clone(), streaks::compute_as_of(dates, today).into()))
+            .collect(),
+    }
+}
+
+/// Checks every source in `config` for missing files, stale data, and unreadable
+/// databases
+///
+/// Reports whether each source's files exist, when they were last modified, the data
+/// format detected, and the last day with any activity, so a device that hasn't synced in
+/// days shows up as stale rather than as days with no activity. A source that can't be
+/// read gets an `error` instead of failing the whole check.
+///
+/// # Example
+/// ```ignore
+/// use faithstats::{config::FaithConfig, get_source_status};
+///
+/// let config = FaithConfig::from_file("lifestats.toml")?;
+/// for status in get_source_status(&config)? {
+///     println!("{}: {:?} days since last data", status.name, status.days_since_last_data);
+/// }
+/// ```
+pub fn get_source_status(config: &FaithConfig) -> Result<Vec<SourceStatus>> {
+    let today = PeriodConfig::default().today()?;
+    Ok(config
+        .data_sources()
+        .iter()
+        .map(|source| source_status(source.as_ref(), today))
+        .collect())
+}
+
+fn source_status(source: &dyn FaithDataSource, today: NaiveDate) -> SourceStatus {
+    let paths = source.paths();
+    let exists = paths.iter().all(|path| path.exists());
+    let last_modified = statsutils::cache::latest_mtime(&paths)
+        .map(|mtime| DateTime::<Utc>::from(mtime).to_rfc3339_opts(SecondsFormat::Secs, true));
+
+    let detected = source.schema().and_then(|schema| {
+        let last_date = source
+            .active_dates()?
+            .and_then(|dates| dates.into_iter().filter(|date| *date <= today).max());
+        Ok((schema, last_date))
+    });
+    let (schema, last_date, error) = match detected {
+        Ok((schema, last_date)) => (schema, last_date, None),
+        Err(e) => (None, None, Some(format!("{:#}", e))),
+    };
+
+    SourceStatus {
+        name: source.name().to_string(),
+        paths: paths
+            .iter()
+            .map(|path| path.to_string_lossy().into_owned())
+            .collect(),
+        exists,
+        last_modified,
+        schema,
+        last_data_date: last_date.map(|date| date.format("%Y-%m-%d").to_string()),
+        days_since_last_data: last_date.map(|date| (today - date).num_days()),
+        error,
+    }
+}
+
+/// Summarizes what Sundays have looked like over the last `weeks` weeks (ending today):
+/// average minutes from each source in `config` and combined on Sundays, against the
+/// average of the other six days, and, with an Arc Timeline export, time at home as well
+///
+/// Sources that can't report arbitrary periods are left out, as with
+/// `get_faith_stats_for_range`.
+pub fn get_sunday_stats(config: &FaithConfig, weeks: u32) -> Result<SundayStats> {
+    let sources = config.data_sources();
+    let sources: Vec<&dyn FaithDataSource> = sources.iter().map(|s| s.as_ref()).collect();
+    let period = last_n_weeks_of_days(weeks)?;
+    let home = match &config.sources.arc {
+        Some(export_path) => Some(
+            arcstats::get_home_stats_for_period(export_path, &period)?
+                .into_iter()
+                .map(|p| (p.period_start, p.minutes))
+                .collect(),
+        ),
+        None => None,
+    };
+    sunday_stats(&sources, &period, home)
+}
+
+/// Each day of the last `weeks` weeks, ending today
+fn last_n_weeks_of_days(weeks: u32) -> Result<DatePeriod> {
+    if weeks == 0 {
+        bail!("Sunday stats need at least one week");
+    }
+    let today = PeriodConfig::default().today()?;
+    let start = today - Duration::days(7 * i64::from(weeks) - 1);
+    DatePeriod::days(start, today)
+}
+
+fn sunday_stats(
+    sources: &[&dyn FaithDataSource],
+    period: &DatePeriod,
+    home_minutes: Option<Vec<(String, f64)>>,
+) -> Result<SundayStats> {
+    let minutes = collect_minutes(period.dates.clone(), sources, |s| s.period_minutes(period))?;
+
+    let mut by_source: BTreeMap<String, Vec<(NaiveDate, f64)>> = BTreeMap::new();
+    let mut totals = Vec::new();
+    for (date, day) in minutes.iter() {
+        let date = parse_local_date(date)?;
+        for (source, minutes) in day {
+            by_source
+                .entry(source.clone())
+                .or_default()
+                .push((date, *minutes));
+        }
+        totals.push((date, day.values().sum()));
+    }
+
+    let home = match home_minutes {
+        Some(days) => {
+            // Keyed by the period's days like the sources, so a different range is an error
+            let days = buckets_of(&period.dates)
+                .zip_source("home", PeriodSeries::new(days))?
+                .map(|date, ((), minutes)| Ok((parse_local_date(&date)?, minutes)))
+                .into_iter()
+                .collect::<Result<Vec<_>>>()?;
+            Some(SundayComparison::from_days(&days))
+        }
+        None => None,
+    };
+
+    Ok(SundayStats {
+        start_date: period.dates.first().cloned().unwrap_or_default(),
+        end_date: period.dates.last().cloned().unwrap_or_default(),
+        sundays: totals
+            .iter()
+            .filter(|(date, _)| date.weekday() == Weekday::Sun)
+            .count(),
+        by_source: by_source
+            .into_iter()
+            .map(|(source, days)| (source, SundayComparison::from_days(&days)))
+            .collect(),
+        total: SundayComparison::from_days(&totals),
+        home,
+    })
+}
+
+/// Gets a calendar year of faith activity from the built-in sources: hours per source,
+/// monthly trends, Anki passages matured, Bible books read, church services attended, days
+/// fasted, and the year's best streaks
+///
+/// Church attendance is only counted with an Arc Timeline export, and fasting with a
+/// fasting log. For the current year, months still to come are 0.
+///
+/// # Example
+/// ```ignore
+/// use faithstats::{config::FaithConfig, get_faith_annual_report};
+///
+/// let config = FaithConfig::from_file("lifestats.toml")?;
+/// let report = get_faith_annual_report(&config, 2025)?;
+/// println!("{:.0} hours in 2025", report.total_hours);
+/// ```
+pub fn get_faith_annual_report(config: &FaithConfig, year: i32) -> Result<FaithAnnualReport> {
+    let sources = &config.sources;
+    let builtin = builtin_sources(
+        &sources.anki,
+        &sources.koreader,
+        sources.arc.as_deref(),
+        &sources.proseuche,
+    );
+    let builtin: Vec<&dyn FaithDataSource> = builtin.iter().map(|s| s.as_ref()).collect();
+    let period = DatePeriod::calendar_year(year, Granularity::Month)?;
+    let months = faith_stats_for_period(&builtin, &period)?.periods;
+
+    let anki_months = ankistats::get_stats_for_period_with_config(
+        &sources.anki,
+        &period,
+        &PeriodConfig::default(),
+    )?;
+    let passages = (
+        anki_months.iter().map(|m| m.matured_passages).sum(),
+        anki_months.iter().map(|m| m.lost_passages).sum(),
+    );
+    let bible_books_read = readingstats::source::source_for(&sources.koreader)?
+        .get_bible_title_minutes(&period)?
+        .into_iter()
+        .map(|(title, minutes)| BibleBookRead { title, minutes })
+        .collect();
+
+    let first_day = NaiveDate::from_ymd_opt(year, 1, 1).context("Invalid year")?;
+    let last_day = NaiveDate::from_ymd_opt(year, 12, 31).context("Invalid year")?;
+    let mut active_dates = Vec::new();
+    for source in &builtin {
+        if let Some(mut dates) = source.active_dates()? {
+            dates.retain(|date| (first_day..=last_day).contains(date));
+            active_dates.push((source.name().to_string(), dates));
+        }
+    }
+    let church_services = active_dates
+        .iter()
+        .find(|(name, _)| name == "church")
+        .map_or(0, |(_, dates)| dates.len());
+    let today = PeriodConfig::default().today()?;
+    let streaks = streaks_from_dates(&active_dates, last_day.min(today));
+
+    let mut report = FaithAnnualReport::new(
+        year,
+        &months,
+        passages,
+        bible_books_read,
+        church_services,
+        streaks,
+    );
+    if let Some(log_path) = &sources.fasting {
+        let mut fasts = fasting::load_fasts(log_path)?;
+        fasts.retain(|fast| (first_day..=last_day).contains(&fast.date));
+        report.fasts_by_type = fasting::fasts_by_type(&fasts);
+        fasts.dedup_by_key(|fast| fast.date);
+        report.fasting_days = fasts.len();
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::source::AnkiSource;
+    use std::path::PathBuf;
+
+    /// Reports a fixed number of minutes for every bucket of any period
+    struct Steady {
+        name: &'static str,
+        minutes: f64,
+    }
+
+    impl FaithDataSource for Steady {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn period_minutes(&self, period: &DatePeriod) -> Result<Option<PeriodSeries<f64>>> {
+            Ok(Some(PeriodSeries::new(
+                period
+                    .iter()
+                    .map(|key| (key.clone(), self.minutes))
+                    .collect(),
+            )))
+        }
+    }
+
+    /// Active on a fixed set of days
+    struct Days {
+        name: &'static str,
+        dates: &'static [&'static str],
+    }
+
+    impl FaithDataSource for Days {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn active_dates(&self) -> Result<Option<Vec<NaiveDate>>> {
+            Ok(Some(
+                self.dates
+                    .iter()
+                    .map(|d| parse_local_date(d).unwrap())
+                    .collect(),
+            ))
+        }
+    }
+
+    /// Only reports the fixed windows, so it's left out of custom ranges and streaks
+    struct TodayOnly;
+
+    impl FaithDataSource for TodayOnly {
+        fn name(&self) -> &str {
+            "today"
+        }
+
+        fn today_minutes(&self) -> Result<Option<f64>> {
+            Ok(Some(10.0))
+        }
+    }
+
+    #[test]
+    fn test_get_faith_stats_for_range() {
+        let prayer = Steady {
+            name: "prayer",
+            minutes: 15.0,
+        };
+        let church = Steady {
+            name: "church",
+            minutes: 60.0,
+        };
+        let stats = faith_stats_for_range(
+            &[&prayer, &church, &TodayOnly],
+            "2025-01-15",
+            "2025-03-02",
+            Granularity::Month,
+        )
+        .unwrap();
+
+        assert_eq!(stats.granularity, Granularity::Month);
+        let starts: Vec<&str> = stats
+            .periods
+            .iter()
+            .map(|p| p.period_start.as_str())
+            .collect();
+        assert_eq!(starts, ["2025-01", "2025-02", "2025-03"]);
+        assert_eq!(stats.periods[1].label, "Feb 2025");
+        assert_eq!(stats.periods[0].total_minutes, 75.0);
+        assert!(!stats.periods[0].minutes.contains_key("today"));
+
+        assert_eq!(stats.summary.total_periods, 3);
+        assert_eq!(stats.summary.total_minutes, 225.0);
+        assert_eq!(stats.summary.source_total_minutes["church"], 180.0);
+        assert_eq!(stats.summary.average_minutes_per_period, 75.0);
+    }
+
+    #[test]
+    fn test_sunday_stats() {
+        // 2025-06-01 and 2025-06-08 are Sundays
+        let period = DatePeriod::days(
+            parse_local_date("2025-06-01").unwrap(),
+            parse_local_date("2025-06-14").unwrap(),
+        )
+        .unwrap();
+        let prayer = Steady {
+            name: "prayer",
+            minutes: 15.0,
+        };
+        let home = period
+            .iter()
+            .map(|date| {
+                let minutes = if date == "2025-06-08" { 0.0 } else { 600.0 };
+                (date.clone(), minutes)
+            })
+            .collect();
+
+        let stats = sunday_stats(&[&prayer, &TodayOnly], &period, Some(home)).unwrap();
+        assert_eq!(stats.start_date, "2025-06-01");
+        assert_eq!(stats.end_date, "2025-06-14");
+        assert_eq!(stats.sundays, 2);
+        assert_eq!(stats.by_source.keys().collect::<Vec<_>>(), ["prayer"]);
+        assert_eq!(stats.total.sunday_average_minutes, 15.0);
+        assert_eq!(stats.total.weekday_average_minutes, 15.0);
+        assert_eq!(stats.total.sundays_active, 2);
+        assert_eq!(
+            stats.home,
+            Some(SundayComparison {
+                sunday_average_minutes: 300.0,
+                weekday_average_minutes: 600.0,
+                sundays_active: 1,
+            })
+        );
+
+        // Home time must cover the same days
+        let err = sunday_stats(&[&prayer], &period, Some(Vec::new())).unwrap_err();
+        assert!(err.to_string().contains("home"));
+    }
+
+    #[test]
+    fn test_faith_streaks_combine_sources() {
+        let prayer = Days {
+            name: "prayer",
+            dates: &["2025-06-01", "2025-06-03", "2025-06-04"],
+        };
+        let reading = Days {
+            name: "reading",
+            dates: &["2025-06-02", "2025-06-03"],
+        };
+        let today = parse_local_date("2025-06-05").unwrap();
+        let streaks = faith_streaks_as_of(&[&prayer, &reading, &TodayOnly], today).unwrap();
+
+        assert_eq!(streaks.any_activity.current_streak, 4);
+        assert_eq!(streaks.any_activity.active_days, 4);
+        assert_eq!(
+            streaks.any_activity.last_active_date.as_deref(),
+            Some("2025-06-04")
+        );
+        assert_eq!(streaks.by_source["prayer"].current_streak, 2);
+        assert_eq!(streaks.by_source["reading"].current_streak, 0);
+        assert_eq!(streaks.by_source["reading"].longest_streak, 2);
+        assert!(!streaks.by_source.contains_key("today"));
+    }
+
+    #[test]
+    fn test_annual_report_totals_and_trends() {
+        let month = |key: &str, minutes: &[(&str, f64)]| {
+            let minutes = minutes.iter().map(|&(s, m)| (s.to_string(), m)).collect();
+            FaithPeriodStats::new(key.to_string(), String::new(), minutes)
+        };
+        let months = [
+            month("2025-01", &[("prayer", 60.0), ("reading", 30.0)]),
+            month("2025-02", &[("prayer", 120.0), ("reading", 0.0)]),
+        ];
+
+        let report = FaithAnnualReport::new(
+            2025,
+            &months,
+            (5, 1),
+            Vec::new(),
+            3,
+            FaithStreaks::default(),
+        );
+        assert_eq!(report.monthly_minutes["prayer"], vec![60.0, 120.0]);
+        assert_eq!(report.monthly_total_minutes, vec![90.0, 120.0]);
+        assert_eq!(report.source_total_hours["prayer"], 3.0);
+        assert_eq!(report.total_hours, 3.5);
+        assert_eq!(report.passages_matured, 5);
+    }
+
+    #[test]
+    fn test_source_status() {
+        let dir = tempfile::tempdir().unwrap();
+        let journal = dir.path().join("journal.csv");
+        std::fs::write(&journal, "").unwrap();
+
+        /// Reads one file that exists and one that doesn't
+        struct Synced(PathBuf);
+
+        impl FaithDataSource for Synced {
+            fn name(&self) -> &str {
+                "journal"
+            }
+
+            fn paths(&self) -> Vec<PathBuf> {
+                vec![self.0.clone(), self.0.with_extension("bak")]
+            }
+
+            fn schema(&self) -> Result<Option<String>> {
+                Ok(Some("CSV journal".to_string()))
+            }
+
+            fn active_dates(&self) -> Result<Option<Vec<NaiveDate>>> {
+                Ok(Some(vec![
+                    parse_local_date("2025-06-01").unwrap(),
+                    parse_local_date("2025-06-09").unwrap(),
+                ]))
+            }
+        }
+
+        let today = parse_local_date("2025-06-04").unwrap();
+        let status = source_status(&Synced(journal), today);
+        assert_eq!(status.name, "journal");
+        assert!(!status.exists);
+        assert!(status.last_modified.is_some());
+        assert_eq!(status.schema.as_deref(), Some("CSV journal"));
+        // Activity after today is ignored
+        assert_eq!(status.last_data_date.as_deref(), Some("2025-06-01"));
+        assert_eq!(status.days_since_last_data, Some(3));
+        assert_eq!(status.error, None);
+
+        let missing = AnkiSource {
+            db_path: dir
+                .path()
+                .join("missing.anki2")
+                .to_string_lossy()
+                .into_owned(),
+        };
+        let status = source_status(&missing, today);
+        assert!(!status.exists);
+        assert_eq!(status.last_modified, None);
+        assert_eq!(status.last_data_date, None);
+        assert!(status.error.is_some());
+    }
 
-    Ok(FaithWeeklyStats::new(merged_weeks))
+    #[test]
+    fn test_get_faith_stats_for_range_rejects_bad_dates() {
+        assert!(faith_stats_for_range(&[], "2025-13-01", "2025-12-31", Granularity::Day).is_err());
+        assert!(faith_stats_for_range(&[], "2025-02-01", "2025-01-01", Granularity::Day).is_err());
+    }
 }
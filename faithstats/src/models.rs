@@ -1,4 +1,12 @@
+use crate::goals::FaithGoalProgress;
+use chrono::{Datelike, NaiveDate, Weekday};
+use prayerstats::CategoryStats;
+use readingstats::models::{ReadingStreaks, SessionLengthStats, pages_per_hour};
 use serde::Serialize;
+use statsutils::fmt::duration_hours_minutes;
+use statsutils::streaks::Streaks;
+use statsutils::{Bucket, Granularity};
+use std::collections::BTreeMap;
 use tabled::Tabled;
 use utoipa::ToSchema;
 
@@ -21,16 +29,87 @@ pub struct FaithDayStats {
     // KOReader Bible reading stats
     /// Bible reading time in minutes
     pub reading_minutes: f64,
+    /// Number of distinct Bible pages read
+    pub reading_pages: i64,
+    /// Bible reading speed in pages per hour
+    pub reading_pages_per_hour: f64,
+    /// Time spent reading books other than the Bible, in minutes; tracked alongside but
+    /// not counted as faith time
+    pub other_reading_minutes: f64,
+    /// Number of YouVersion reading-plan days completed; their time is in `other_minutes`
+    /// under "reading_plans"
+    pub reading_plan_days: i64,
+
+    // Journaling stats
+    /// Words written in journal entries dated this day; their estimated time is in
+    /// `other_minutes` under "journaling"
+    pub journal_words: i64,
+
+    // Arc church attendance stats
+    /// Time spent at church in minutes
+    pub at_church_minutes: f64,
 
     // Prayer stats (future)
     /// Prayer time in minutes
     pub prayer_minutes: f64,
+    /// Prayer time broken down by prayer list, most prayed first; omitted on days without
+    /// prayer
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub prayer_categories: Vec<CategoryStats>,
+    /// Minutes from additional sources (see `FaithDataSource`), keyed by source name;
+    /// omitted when there are none
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub other_minutes: BTreeMap<String, f64>,
+
+    /// Consistency score from 0 to 100, weighting each source's minutes (see
+    /// `score::ScoreWeights`)
+    pub score: f64,
+    /// Average `score` over this day and the ones before it, up to `score::ROLLING_DAYS`
+    pub rolling_score: f64,
 }
 
 impl FaithDayStats {
+    /// A day with no activity yet, for each source to fill in (see
+    /// `FaithDataSource::merge_daily`)
+    pub fn new(date: String) -> Self {
+        Self {
+            date,
+            anki_minutes: 0.0,
+            anki_matured_passages: 0,
+            anki_lost_passages: 0,
+            anki_cumulative_passages: 0,
+            reading_minutes: 0.0,
+            reading_pages: 0,
+            reading_pages_per_hour: 0.0,
+            other_reading_minutes: 0.0,
+            reading_plan_days: 0,
+            journal_words: 0,
+            at_church_minutes: 0.0,
+            prayer_minutes: 0.0,
+            prayer_categories: Vec::new(),
+            other_minutes: BTreeMap::new(),
+            score: 0.0,
+            rolling_score: 0.0,
+        }
+    }
+
     /// Total minutes across all faith activities for this day
     pub fn total_minutes(&self) -> f64 {
-        self.anki_minutes + self.reading_minutes + self.prayer_minutes
+        self.anki_minutes
+            + self.reading_minutes
+            + self.at_church_minutes
+            + self.prayer_minutes
+            + self.other_minutes.values().sum::<f64>()
+    }
+
+    /// This day's minutes keyed by source name, as `FaithDataSource::name` reports them
+    pub fn minutes_by_source(&self) -> BTreeMap<String, f64> {
+        let mut minutes = self.other_minutes.clone();
+        minutes.insert("anki".to_string(), self.anki_minutes);
+        minutes.insert("reading".to_string(), self.reading_minutes);
+        minutes.insert("church".to_string(), self.at_church_minutes);
+        minutes.insert("prayer".to_string(), self.prayer_minutes);
+        minutes
     }
 }
 
@@ -46,11 +125,20 @@ pub struct FaithDayStatsDisplay {
     #[tabled(rename = "Reading (min)")]
     pub reading_minutes: String,
 
+    #[tabled(rename = "Other Reading (min)")]
+    pub other_reading_minutes: String,
+
+    #[tabled(rename = "Church (min)")]
+    pub church_minutes: String,
+
     #[tabled(rename = "Prayer (min)")]
     pub prayer_minutes: String,
 
-    #[tabled(rename = "Total (min)")]
+    #[tabled(rename = "Total")]
     pub total_minutes: String,
+
+    #[tabled(rename = "Score")]
+    pub score: String,
 }
 
 impl From<&FaithDayStats> for FaithDayStatsDisplay {
@@ -59,8 +147,11 @@ impl From<&FaithDayStats> for FaithDayStatsDisplay {
             date: stats.date.clone(),
             anki_minutes: format!("{:.1}", stats.anki_minutes),
             reading_minutes: format!("{:.1}", stats.reading_minutes),
+            other_reading_minutes: format!("{:.1}", stats.other_reading_minutes),
+            church_minutes: format!("{:.1}", stats.at_church_minutes),
             prayer_minutes: format!("{:.1}", stats.prayer_minutes),
-            total_minutes: format!("{:.1}", stats.total_minutes()),
+            total_minutes: duration_hours_minutes(stats.total_minutes()),
+            score: format!("{:.0}", stats.score),
         }
     }
 }
@@ -81,7 +172,30 @@ pub struct FaithDailySummary {
     pub reading_total_minutes: f64,
     pub reading_total_hours: f64,
     pub reading_average_minutes_per_day: f64,
+    pub reading_total_pages: i64,
+    pub reading_pages_per_hour: f64,
     pub reading_days_studied: usize,
+    /// Consecutive days ending today with at least `reading_streak_min_minutes` read
+    pub reading_current_streak: i64,
+    pub reading_longest_streak: i64,
+    pub reading_streak_min_minutes: f64,
+    pub reading_longest_session_minutes: f64,
+    pub reading_median_session_minutes: f64,
+    pub reading_plan_days_completed: i64,
+
+    // Journaling stats
+    pub journal_days: usize,
+    pub journal_total_words: i64,
+
+    // Other (non-Bible) reading stats, excluded from the combined totals
+    pub other_reading_total_minutes: f64,
+    pub other_reading_average_minutes_per_day: f64,
+
+    // Church attendance stats
+    pub church_total_minutes: f64,
+    pub church_total_hours: f64,
+    pub church_average_minutes_per_day: f64,
+    pub church_days_attended: usize,
 
     // Prayer stats
     pub prayer_total_minutes: f64,
@@ -95,17 +209,39 @@ pub struct FaithDailySummary {
     pub average_minutes_per_day: f64,
     pub total_days: usize,
     pub days_with_any_activity: usize,
+    /// Average daily consistency score
+    pub average_score: f64,
+
+    /// All-time streaks of days with faith activity, overall and per source
+    pub streaks: FaithStreaks,
+
+    /// Progress on the config's goals; omitted when none are set
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub goals: Vec<FaithGoalProgress>,
 }
 
 impl FaithDailySummary {
-    pub fn from_faith_daily_stats(days: &[FaithDayStats]) -> Self {
+    /// Summarizes the days, with Bible reading streaks (all-time) and session lengths
+    /// (over the same days) that can't be derived from daily totals
+    pub fn from_faith_daily_stats(
+        days: &[FaithDayStats],
+        reading_streaks: &ReadingStreaks,
+        reading_sessions: &SessionLengthStats,
+        faith_streaks: &FaithStreaks,
+    ) -> Self {
         let anki_total: f64 = days.iter().map(|d| d.anki_minutes).sum();
         let reading_total: f64 = days.iter().map(|d| d.reading_minutes).sum();
+        let reading_pages: i64 = days.iter().map(|d| d.reading_pages).sum();
+        let reading_plan_days: i64 = days.iter().map(|d| d.reading_plan_days).sum();
+        let journal_words: i64 = days.iter().map(|d| d.journal_words).sum();
+        let other_reading_total: f64 = days.iter().map(|d| d.other_reading_minutes).sum();
+        let church_total: f64 = days.iter().map(|d| d.at_church_minutes).sum();
         let prayer_total: f64 = days.iter().map(|d| d.prayer_minutes).sum();
-        let combined_total = anki_total + reading_total + prayer_total;
+        let combined_total: f64 = days.iter().map(|d| d.total_minutes()).sum();
 
         let anki_days = days.iter().filter(|d| d.anki_minutes > 0.0).count();
         let reading_days = days.iter().filter(|d| d.reading_minutes > 0.0).count();
+        let church_days = days.iter().filter(|d| d.at_church_minutes > 0.0).count();
         let prayer_days = days.iter().filter(|d| d.prayer_minutes > 0.0).count();
         let any_activity_days = days.iter().filter(|d| d.total_minutes() > 0.0).count();
 
@@ -130,7 +266,26 @@ impl FaithDailySummary {
             reading_total_minutes: reading_total,
             reading_total_hours: reading_total / 60.0,
             reading_average_minutes_per_day: reading_avg,
+            reading_total_pages: reading_pages,
+            reading_pages_per_hour: pages_per_hour(reading_pages, reading_total),
             reading_days_studied: reading_days,
+            reading_current_streak: reading_streaks.current_streak,
+            reading_longest_streak: reading_streaks.longest_streak,
+            reading_streak_min_minutes: reading_streaks.min_minutes,
+            reading_longest_session_minutes: reading_sessions.longest_minutes,
+            reading_median_session_minutes: reading_sessions.median_minutes,
+            reading_plan_days_completed: reading_plan_days,
+
+            journal_days: days.iter().filter(|d| d.journal_words > 0).count(),
+            journal_total_words: journal_words,
+
+            other_reading_total_minutes: other_reading_total,
+            other_reading_average_minutes_per_day: other_reading_total / total_days as f64,
+
+            church_total_minutes: church_total,
+            church_total_hours: church_total / 60.0,
+            church_average_minutes_per_day: church_total / total_days as f64,
+            church_days_attended: church_days,
 
             prayer_total_minutes: prayer_total,
             prayer_total_hours: prayer_total / 60.0,
@@ -142,6 +297,9 @@ impl FaithDailySummary {
             average_minutes_per_day: combined_avg,
             total_days,
             days_with_any_activity: any_activity_days,
+            average_score: 0.0,
+            streaks: faith_streaks.clone(),
+            goals: Vec::new(),
         }
     }
 }
@@ -154,12 +312,80 @@ pub struct FaithDailyStats {
 }
 
 impl FaithDailyStats {
-    pub fn new(days: Vec<FaithDayStats>) -> Self {
-        let summary = FaithDailySummary::from_faith_daily_stats(&days);
+    pub fn new(
+        days: Vec<FaithDayStats>,
+        reading_streaks: &ReadingStreaks,
+        reading_sessions: &SessionLengthStats,
+        faith_streaks: &FaithStreaks,
+    ) -> Self {
+        let summary = FaithDailySummary::from_faith_daily_stats(
+            &days,
+            reading_streaks,
+            reading_sessions,
+            faith_streaks,
+        );
         Self { days, summary }
     }
 }
 
+/// Runs of consecutive days with any activity
+#[derive(Debug, Clone, Default, PartialEq, Serialize, ToSchema)]
+pub struct ActivityStreaks {
+    /// Consecutive active days ending today (or yesterday if nothing yet today)
+    pub current_streak: i64,
+    pub longest_streak: i64,
+    /// All-time number of active days
+    pub active_days: i64,
+    /// Most recent active day in YYYY-MM-DD format
+    pub last_active_date: Option<String>,
+}
+
+impl From<Streaks> for ActivityStreaks {
+    fn from(streaks: Streaks) -> Self {
+        Self {
+            current_streak: streaks.current_streak,
+            longest_streak: streaks.longest_streak,
+            active_days: streaks.active_days,
+            last_active_date: streaks
+                .last_active_date
+                .map(|d| d.format("%Y-%m-%d").to_string()),
+        }
+    }
+}
+
+/// All-time faith activity streaks
+///
+/// Any time counts here, so a day with a few minutes of Bible reading extends the reading
+/// streak even if it falls short of the summary's `reading_streak_min_minutes`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, ToSchema)]
+pub struct FaithStreaks {
+    /// Days with activity from any source
+    pub any_activity: ActivityStreaks,
+    /// Each source's streaks on its own, keyed by source name (e.g. "prayer")
+    pub by_source: BTreeMap<String, ActivityStreaks>,
+}
+
+/// Whether a source's data is present and how fresh it is, from `get_source_status`
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct SourceStatus {
+    /// Source name, e.g. "anki"
+    pub name: String,
+    /// Files the source reads
+    pub paths: Vec<String>,
+    /// Whether every file exists
+    pub exists: bool,
+    /// Latest modification time among the files, in RFC 3339 format (UTC)
+    pub last_modified: Option<String>,
+    /// Format and version detected in the data, e.g. "Anki schema 18"
+    pub schema: Option<String>,
+    /// Most recent day with any activity in YYYY-MM-DD format
+    pub last_data_date: Option<String>,
+    /// Days from `last_data_date` to today; a large value usually means a stale sync
+    pub days_since_last_data: Option<i64>,
+    /// Why the source couldn't be read, if it couldn't
+    pub error: Option<String>,
+}
+
 /// Combined faith statistics for today
 #[derive(Debug, Clone, Serialize, ToSchema)]
 pub struct FaithTodayStats {
@@ -167,8 +393,14 @@ pub struct FaithTodayStats {
     pub anki_minutes: f64,
     /// Bible reading time in minutes
     pub reading_minutes: f64,
+    /// Time spent at church in minutes
+    pub at_church_minutes: f64,
     /// Prayer time in minutes
     pub prayer_minutes: f64,
+    /// Minutes from additional sources (see `FaithDataSource`), keyed by source name;
+    /// omitted when there are none
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub other_minutes: BTreeMap<String, f64>,
     /// Total minutes across all activities
     pub total_minutes: f64,
     /// Total hours across all activities
@@ -176,15 +408,34 @@ pub struct FaithTodayStats {
 }
 
 impl FaithTodayStats {
-    pub fn new(anki_minutes: f64, reading_minutes: f64, prayer_minutes: f64) -> Self {
-        let total_minutes = anki_minutes + reading_minutes + prayer_minutes;
-        Self {
+    pub fn new(
+        anki_minutes: f64,
+        reading_minutes: f64,
+        at_church_minutes: f64,
+        prayer_minutes: f64,
+        other_minutes: BTreeMap<String, f64>,
+    ) -> Self {
+        let mut stats = Self {
             anki_minutes,
             reading_minutes,
+            at_church_minutes,
             prayer_minutes,
-            total_minutes,
-            total_hours: total_minutes / 60.0,
-        }
+            other_minutes,
+            total_minutes: 0.0,
+            total_hours: 0.0,
+        };
+        stats.update_totals();
+        stats
+    }
+
+    /// Recomputes `total_minutes` and `total_hours` from each source's minutes
+    pub fn update_totals(&mut self) {
+        self.total_minutes = self.anki_minutes
+            + self.reading_minutes
+            + self.at_church_minutes
+            + self.prayer_minutes
+            + self.other_minutes.values().sum::<f64>();
+        self.total_hours = self.total_minutes / 60.0;
     }
 }
 
@@ -193,6 +444,8 @@ impl FaithTodayStats {
 pub struct FaithWeekStats {
     /// Week start date in YYYY-MM-DD format
     pub week_start: String,
+    /// Days the week covers, e.g. "Oct 19–25"
+    pub week_label: String,
 
     // Anki Bible memorization stats
     /// Anki study time in minutes
@@ -207,22 +460,88 @@ pub struct FaithWeekStats {
     // KOReader Bible reading stats
     /// Bible reading time in minutes
     pub reading_minutes: f64,
+    /// Number of distinct Bible pages read
+    pub reading_pages: i64,
+    /// Bible reading speed in pages per hour
+    pub reading_pages_per_hour: f64,
+    /// Time spent reading books other than the Bible, in minutes; tracked alongside but
+    /// not counted as faith time
+    pub other_reading_minutes: f64,
 
     // Arc church attendance stats
     /// Time spent at church in minutes
     pub at_church_minutes: f64,
-    /// Daily church attendance breakdown: [Sun, Mon, Tue, Wed, Thu, Fri, Sat]
+    /// Daily church attendance breakdown, one entry per day from the configured week start
     pub at_church_daily_minutes: Vec<f64>,
+    /// Church time by kind of attendance (e.g. "service", "small_group", "volunteering"),
+    /// with every configured category present
+    pub at_church_category_minutes: BTreeMap<String, f64>,
+
+    // Journaling stats
+    /// Days with a journal entry this week
+    pub journal_days: i64,
+    /// Words written in journal entries this week
+    pub journal_words: i64,
+
+    // Fasting stats
+    /// Days with a fast in the fasting log this week
+    pub fasting_days: i64,
 
     // Prayer stats (future)
     /// Prayer time in minutes
     pub prayer_minutes: f64,
+
+    /// Minutes from additional sources (see `FaithDataSource`), keyed by source name;
+    /// omitted when there are none
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub other_minutes: BTreeMap<String, f64>,
 }
 
 impl FaithWeekStats {
+    /// A week with no activity yet, for each source to fill in (see
+    /// `FaithDataSource::merge_weekly`)
+    pub fn new(week_start: String) -> Self {
+        Self {
+            week_label: Bucket::from_key(&week_start, Granularity::Week)
+                .map(|bucket| bucket.label)
+                .unwrap_or_else(|_| week_start.clone()),
+            week_start,
+            anki_minutes: 0.0,
+            anki_matured_passages: 0,
+            anki_lost_passages: 0,
+            anki_cumulative_passages: 0,
+            reading_minutes: 0.0,
+            reading_pages: 0,
+            reading_pages_per_hour: 0.0,
+            other_reading_minutes: 0.0,
+            at_church_minutes: 0.0,
+            at_church_daily_minutes: Vec::new(),
+            at_church_category_minutes: BTreeMap::new(),
+            journal_days: 0,
+            journal_words: 0,
+            fasting_days: 0,
+            prayer_minutes: 0.0,
+            other_minutes: BTreeMap::new(),
+        }
+    }
+
     /// Total minutes across all faith activities for this week
     pub fn total_minutes(&self) -> f64 {
-        self.anki_minutes + self.reading_minutes + self.at_church_minutes + self.prayer_minutes
+        self.anki_minutes
+            + self.reading_minutes
+            + self.at_church_minutes
+            + self.prayer_minutes
+            + self.other_minutes.values().sum::<f64>()
+    }
+
+    /// This week's minutes keyed by source name, as `FaithDataSource::name` reports them
+    pub fn minutes_by_source(&self) -> BTreeMap<String, f64> {
+        let mut minutes = self.other_minutes.clone();
+        minutes.insert("anki".to_string(), self.anki_minutes);
+        minutes.insert("reading".to_string(), self.reading_minutes);
+        minutes.insert("church".to_string(), self.at_church_minutes);
+        minutes.insert("prayer".to_string(), self.prayer_minutes);
+        minutes
     }
 }
 
@@ -238,25 +557,29 @@ pub struct FaithWeekStatsDisplay {
     #[tabled(rename = "Reading (min)")]
     pub reading_minutes: String,
 
+    #[tabled(rename = "Other Reading (min)")]
+    pub other_reading_minutes: String,
+
     #[tabled(rename = "Church (min)")]
     pub church_minutes: String,
 
     #[tabled(rename = "Prayer (min)")]
     pub prayer_minutes: String,
 
-    #[tabled(rename = "Total (min)")]
+    #[tabled(rename = "Total")]
     pub total_minutes: String,
 }
 
 impl From<&FaithWeekStats> for FaithWeekStatsDisplay {
     fn from(stats: &FaithWeekStats) -> Self {
         Self {
-            week_start: stats.week_start.clone(),
+            week_start: stats.week_label.clone(),
             anki_minutes: format!("{:.1}", stats.anki_minutes),
             reading_minutes: format!("{:.1}", stats.reading_minutes),
+            other_reading_minutes: format!("{:.1}", stats.other_reading_minutes),
             church_minutes: format!("{:.1}", stats.at_church_minutes),
             prayer_minutes: format!("{:.1}", stats.prayer_minutes),
-            total_minutes: format!("{:.1}", stats.total_minutes()),
+            total_minutes: duration_hours_minutes(stats.total_minutes()),
         }
     }
 }
@@ -277,13 +600,21 @@ pub struct FaithWeeklySummary {
     pub reading_total_minutes: f64,
     pub reading_total_hours: f64,
     pub reading_average_minutes_per_week: f64,
+    pub reading_total_pages: i64,
+    pub reading_pages_per_hour: f64,
     pub reading_weeks_studied: usize,
 
+    // Other (non-Bible) reading stats, excluded from the combined totals
+    pub other_reading_total_minutes: f64,
+    pub other_reading_average_minutes_per_week: f64,
+
     // Church stats
     pub church_total_minutes: f64,
     pub church_total_hours: f64,
     pub church_average_minutes_per_week: f64,
     pub church_weeks_attended: usize,
+    /// Church minutes for each kind of attendance, keyed by category
+    pub church_category_total_minutes: BTreeMap<String, f64>,
 
     // Prayer stats
     pub prayer_total_minutes: f64,
@@ -297,15 +628,29 @@ pub struct FaithWeeklySummary {
     pub average_minutes_per_week: f64,
     pub total_weeks: usize,
     pub weeks_with_any_activity: usize,
+
+    /// Progress on the config's goals; omitted when none are set
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub goals: Vec<FaithGoalProgress>,
 }
 
 impl FaithWeeklySummary {
     pub fn from_faith_weekly_stats(weeks: &[FaithWeekStats]) -> Self {
         let anki_total: f64 = weeks.iter().map(|w| w.anki_minutes).sum();
         let reading_total: f64 = weeks.iter().map(|w| w.reading_minutes).sum();
+        let reading_pages: i64 = weeks.iter().map(|w| w.reading_pages).sum();
+        let other_reading_total: f64 = weeks.iter().map(|w| w.other_reading_minutes).sum();
         let church_total: f64 = weeks.iter().map(|w| w.at_church_minutes).sum();
+        let mut church_category_totals: BTreeMap<String, f64> = BTreeMap::new();
+        for week in weeks {
+            for (category, minutes) in &week.at_church_category_minutes {
+                *church_category_totals
+                    .entry(category.clone())
+                    .or_insert(0.0) += minutes;
+            }
+        }
         let prayer_total: f64 = weeks.iter().map(|w| w.prayer_minutes).sum();
-        let combined_total = anki_total + reading_total + church_total + prayer_total;
+        let combined_total: f64 = weeks.iter().map(|w| w.total_minutes()).sum();
 
         let anki_weeks = weeks.iter().filter(|w| w.anki_minutes > 0.0).count();
         let reading_weeks = weeks.iter().filter(|w| w.reading_minutes > 0.0).count();
@@ -335,12 +680,18 @@ impl FaithWeeklySummary {
             reading_total_minutes: reading_total,
             reading_total_hours: reading_total / 60.0,
             reading_average_minutes_per_week: reading_avg,
+            reading_total_pages: reading_pages,
+            reading_pages_per_hour: pages_per_hour(reading_pages, reading_total),
             reading_weeks_studied: reading_weeks,
 
+            other_reading_total_minutes: other_reading_total,
+            other_reading_average_minutes_per_week: other_reading_total / total_weeks as f64,
+
             church_total_minutes: church_total,
             church_total_hours: church_total / 60.0,
             church_average_minutes_per_week: church_avg,
             church_weeks_attended: church_weeks,
+            church_category_total_minutes: church_category_totals,
 
             prayer_total_minutes: prayer_total,
             prayer_total_hours: prayer_total / 60.0,
@@ -352,6 +703,7 @@ impl FaithWeeklySummary {
             average_minutes_per_week: combined_avg,
             total_weeks,
             weeks_with_any_activity: any_activity_weeks,
+            goals: Vec::new(),
         }
     }
 }
@@ -369,3 +721,260 @@ impl FaithWeeklyStats {
         Self { weeks, summary }
     }
 }
+
+/// Faith time for one bucket (day, week, or month) of a custom date range
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct FaithPeriodStats {
+    /// First day of the bucket: YYYY-MM-DD for days and weeks, YYYY-MM for months
+    pub period_start: String,
+    /// Human-readable name of the bucket, e.g. "Oct 19–25"
+    pub label: String,
+    /// Minutes for each source, keyed by source name (e.g. "anki", "church")
+    pub minutes: BTreeMap<String, f64>,
+    /// Total minutes across all sources
+    pub total_minutes: f64,
+}
+
+impl FaithPeriodStats {
+    pub fn new(period_start: String, label: String, minutes: BTreeMap<String, f64>) -> Self {
+        let total_minutes = minutes.values().sum();
+        Self {
+            period_start,
+            label,
+            minutes,
+            total_minutes,
+        }
+    }
+}
+
+/// Time spent in one Bible book, by its e-reader title
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct BibleBookRead {
+    pub title: String,
+    pub minutes: f64,
+}
+
+/// A calendar year of faith activity, for a year-in-review page
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct FaithAnnualReport {
+    pub year: i32,
+    /// Hours for each source over the year, keyed by source name
+    pub source_total_hours: BTreeMap<String, f64>,
+    pub total_hours: f64,
+    /// Minutes for each source in each month, January first, keyed by source name
+    pub monthly_minutes: BTreeMap<String, Vec<f64>>,
+    /// Minutes across all sources in each month, January first
+    pub monthly_total_minutes: Vec<f64>,
+    /// Anki passages that reached maturity during the year
+    pub passages_matured: i64,
+    /// Anki passages that fell out of maturity during the year
+    pub passages_lost: i64,
+    /// Bible books read during the year, most read first
+    pub bible_books_read: Vec<BibleBookRead>,
+    /// Days with any time at church; 0 without an Arc Timeline export
+    pub church_services_attended: usize,
+    /// Days fasted during the year; 0 without a fasting log
+    pub fasting_days: usize,
+    /// Fasts during the year by type, e.g. "Ash Wednesday"
+    pub fasts_by_type: BTreeMap<String, usize>,
+    /// Streaks counting only days within the year; each `longest_streak` is the year's best
+    pub streaks: FaithStreaks,
+}
+
+impl FaithAnnualReport {
+    /// Builds the report from the year's months (as from a monthly `DatePeriod`) and the
+    /// figures that can't be derived from minutes
+    pub fn new(
+        year: i32,
+        months: &[FaithPeriodStats],
+        passages: (i64, i64),
+        bible_books_read: Vec<BibleBookRead>,
+        church_services_attended: usize,
+        streaks: FaithStreaks,
+    ) -> Self {
+        let mut monthly_minutes: BTreeMap<String, Vec<f64>> = BTreeMap::new();
+        for (i, month) in months.iter().enumerate() {
+            for (source, minutes) in &month.minutes {
+                monthly_minutes
+                    .entry(source.clone())
+                    .or_insert_with(|| vec![0.0; months.len()])[i] = *minutes;
+            }
+        }
+        let source_total_hours = monthly_minutes
+            .iter()
+            .map(|(source, minutes)| (source.clone(), minutes.iter().sum::<f64>() / 60.0))
+            .collect::<BTreeMap<_, _>>();
+        let (passages_matured, passages_lost) = passages;
+
+        Self {
+            year,
+            total_hours: source_total_hours.values().sum(),
+            source_total_hours,
+            monthly_minutes,
+            monthly_total_minutes: months.iter().map(|m| m.total_minutes).collect(),
+            passages_matured,
+            passages_lost,
+            bible_books_read,
+            church_services_attended,
+            fasting_days: 0,
+            fasts_by_type: BTreeMap::new(),
+            streaks,
+        }
+    }
+}
+
+/// Display wrapper for FaithPeriodStats for CLI table output
+///
+/// The built-in sources get their own columns; any others are summed under "Other".
+#[derive(Debug, Clone, Tabled)]
+pub struct FaithPeriodStatsDisplay {
+    #[tabled(rename = "Period")]
+    pub label: String,
+
+    #[tabled(rename = "Anki (min)")]
+    pub anki_minutes: String,
+
+    #[tabled(rename = "Reading (min)")]
+    pub reading_minutes: String,
+
+    #[tabled(rename = "Church (min)")]
+    pub church_minutes: String,
+
+    #[tabled(rename = "Prayer (min)")]
+    pub prayer_minutes: String,
+
+    #[tabled(rename = "Other (min)")]
+    pub other_minutes: String,
+
+    #[tabled(rename = "Total")]
+    pub total_minutes: String,
+}
+
+impl From<&FaithPeriodStats> for FaithPeriodStatsDisplay {
+    fn from(stats: &FaithPeriodStats) -> Self {
+        const BUILTIN: [&str; 4] = ["anki", "reading", "church", "prayer"];
+        let minutes = |source: &str| stats.minutes.get(source).copied().unwrap_or(0.0);
+        let other = stats
+            .minutes
+            .iter()
+            .filter(|(source, _)| !BUILTIN.contains(&source.as_str()))
+            .fold(0.0, |total, (_, minutes)| total + minutes);
+
+        Self {
+            label: stats.label.clone(),
+            anki_minutes: format!("{:.1}", minutes("anki")),
+            reading_minutes: format!("{:.1}", minutes("reading")),
+            church_minutes: format!("{:.1}", minutes("church")),
+            prayer_minutes: format!("{:.1}", minutes("prayer")),
+            other_minutes: format!("{:.1}", other),
+            total_minutes: duration_hours_minutes(stats.total_minutes),
+        }
+    }
+}
+
+/// Summary statistics for faith activities over a custom date range
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct FaithRangeSummary {
+    /// Total minutes for each source over the whole range, keyed by source name
+    pub source_total_minutes: BTreeMap<String, f64>,
+    pub total_minutes: f64,
+    pub total_hours: f64,
+    pub average_minutes_per_period: f64,
+    pub total_periods: usize,
+    pub periods_with_any_activity: usize,
+}
+
+impl FaithRangeSummary {
+    pub fn from_periods(periods: &[FaithPeriodStats]) -> Self {
+        let mut source_total_minutes: BTreeMap<String, f64> = BTreeMap::new();
+        for period in periods {
+            for (source, minutes) in &period.minutes {
+                *source_total_minutes.entry(source.clone()).or_insert(0.0) += minutes;
+            }
+        }
+
+        let total_minutes: f64 = periods.iter().map(|p| p.total_minutes).sum();
+        let total_periods = periods.len();
+
+        Self {
+            source_total_minutes,
+            total_minutes,
+            total_hours: total_minutes / 60.0,
+            average_minutes_per_period: if total_periods > 0 {
+                total_minutes / total_periods as f64
+            } else {
+                0.0
+            },
+            total_periods,
+            periods_with_any_activity: periods.iter().filter(|p| p.total_minutes > 0.0).count(),
+        }
+    }
+}
+
+/// Faith statistics for a custom date range, bucketed by day, week, or month
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct FaithRangeStats {
+    pub granularity: Granularity,
+    pub periods: Vec<FaithPeriodStats>,
+    pub summary: FaithRangeSummary,
+}
+
+impl FaithRangeStats {
+    pub fn new(granularity: Granularity, periods: Vec<FaithPeriodStats>) -> Self {
+        let summary = FaithRangeSummary::from_periods(&periods);
+        Self {
+            granularity,
+            periods,
+            summary,
+        }
+    }
+}
+
+/// Average minutes on Sundays against the other six days of the week
+#[derive(Debug, Clone, Default, PartialEq, Serialize, ToSchema)]
+pub struct SundayComparison {
+    pub sunday_average_minutes: f64,
+    pub weekday_average_minutes: f64,
+    /// Sundays with any time
+    pub sundays_active: usize,
+}
+
+impl SundayComparison {
+    /// Compares the Sundays among `days` (date, minutes) with the rest
+    pub fn from_days(days: &[(NaiveDate, f64)]) -> Self {
+        let (sundays, weekdays): (Vec<_>, Vec<_>) = days
+            .iter()
+            .partition(|(date, _)| date.weekday() == Weekday::Sun);
+        let average = |days: &[&(NaiveDate, f64)]| {
+            if days.is_empty() {
+                0.0
+            } else {
+                days.iter().map(|(_, minutes)| minutes).sum::<f64>() / days.len() as f64
+            }
+        };
+
+        Self {
+            sunday_average_minutes: average(&sundays),
+            weekday_average_minutes: average(&weekdays),
+            sundays_active: sundays.iter().filter(|(_, minutes)| *minutes > 0.0).count(),
+        }
+    }
+}
+
+/// What Sundays look like over recent weeks, compared to the rest of the week
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SundayStats {
+    /// First day covered, YYYY-MM-DD
+    pub start_date: String,
+    /// Last day covered (today), YYYY-MM-DD
+    pub end_date: String,
+    /// Number of Sundays covered
+    pub sundays: usize,
+    /// Each source's Sundays against its weekdays, keyed by source name
+    pub by_source: BTreeMap<String, SundayComparison>,
+    /// Combined time across sources
+    pub total: SundayComparison,
+    /// Time at home, from the Arc Timeline export; omitted without one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub home: Option<SundayComparison>,
+}
@@ -0,0 +1,75 @@
+//! Async versions of the entry points, run on tokio's blocking thread pool
+//!
+//! Each function takes the same arguments as its namesake in the crate root; the config is
+//! cloned so the work can outlive the caller's borrow.
+
+use anyhow::Result;
+use statsutils::Granularity;
+use statsutils::nonblocking::run;
+
+use crate::config::FaithConfig;
+use crate::models::{
+    FaithAnnualReport, FaithDailyStats, FaithRangeStats, FaithStreaks, FaithTodayStats,
+    FaithWeeklyStats, SourceStatus, SundayStats,
+};
+
+/// Async `crate::get_faith_today_stats`
+pub async fn get_faith_today_stats(config: &FaithConfig) -> Result<FaithTodayStats> {
+    let config = config.clone();
+    run(move || crate::get_faith_today_stats(&config)).await
+}
+
+/// Async `crate::get_faith_daily_stats`
+pub async fn get_faith_daily_stats(config: &FaithConfig) -> Result<FaithDailyStats> {
+    let config = config.clone();
+    run(move || crate::get_faith_daily_stats(&config)).await
+}
+
+/// Async `crate::get_faith_weekly_stats`
+pub async fn get_faith_weekly_stats(config: &FaithConfig) -> Result<FaithWeeklyStats> {
+    let config = config.clone();
+    run(move || crate::get_faith_weekly_stats(&config)).await
+}
+
+/// Async `crate::get_faith_monthly_stats`
+pub async fn get_faith_monthly_stats(config: &FaithConfig) -> Result<FaithRangeStats> {
+    let config = config.clone();
+    run(move || crate::get_faith_monthly_stats(&config)).await
+}
+
+/// Async `crate::get_faith_stats_for_range`
+pub async fn get_faith_stats_for_range(
+    config: &FaithConfig,
+    start_date: &str,
+    end_date: &str,
+    granularity: Granularity,
+) -> Result<FaithRangeStats> {
+    let (config, start_date, end_date) =
+        (config.clone(), start_date.to_string(), end_date.to_string());
+    run(move || crate::get_faith_stats_for_range(&config, &start_date, &end_date, granularity))
+        .await
+}
+
+/// Async `crate::get_faith_streaks`
+pub async fn get_faith_streaks(config: &FaithConfig) -> Result<FaithStreaks> {
+    let config = config.clone();
+    run(move || crate::get_faith_streaks(&config)).await
+}
+
+/// Async `crate::get_source_status`
+pub async fn get_source_status(config: &FaithConfig) -> Result<Vec<SourceStatus>> {
+    let config = config.clone();
+    run(move || crate::get_source_status(&config)).await
+}
+
+/// Async `crate::get_sunday_stats`
+pub async fn get_sunday_stats(config: &FaithConfig, weeks: u32) -> Result<SundayStats> {
+    let config = config.clone();
+    run(move || crate::get_sunday_stats(&config, weeks)).await
+}
+
+/// Async `crate::get_faith_annual_report`
+pub async fn get_faith_annual_report(config: &FaithConfig, year: i32) -> Result<FaithAnnualReport> {
+    let config = config.clone();
+    run(move || crate::get_faith_annual_report(&config, year)).await
+}
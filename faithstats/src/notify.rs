@@ -0,0 +1,458 @@
+use anyhow::{Context, Result, bail};
+use chrono::{Duration, NaiveDate, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use statsutils::PeriodConfig;
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use crate::models::FaithDailyStats;
+
+/// Streak lengths, in days, worth a notification
+pub const DEFAULT_STREAK_MILESTONES: [i64; 6] = [7, 30, 50, 100, 200, 365];
+
+/// Local hour after which a day without prayer triggers a reminder
+pub const DEFAULT_PRAYER_REMINDER_HOUR: u32 = 20;
+
+/// Where notifications are posted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NotifyService {
+    /// POSTs each event as JSON to `url`
+    Webhook,
+    /// Publishes to an ntfy topic URL, e.g. `https://ntfy.sh/my-topic`
+    Ntfy,
+    /// Sends a message through a Telegram bot (`token`) to `chat_id`
+    Telegram,
+}
+
+/// The config's `[notify]` table
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NotifyConfig {
+    pub service: NotifyService,
+    /// Endpoint for webhook and ntfy
+    #[serde(default)]
+    pub url: Option<String>,
+    /// Telegram bot token
+    #[serde(default)]
+    pub token: Option<String>,
+    /// Telegram chat to message
+    #[serde(default)]
+    pub chat_id: Option<String>,
+    /// Defaults to `DEFAULT_STREAK_MILESTONES`
+    #[serde(default)]
+    pub streak_milestones: Option<Vec<i64>>,
+    /// Defaults to `DEFAULT_PRAYER_REMINDER_HOUR`
+    #[serde(default)]
+    pub prayer_reminder_hour: Option<u32>,
+}
+
+/// What happened
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    GoalMet,
+    StreakMilestone,
+    PassageMatured,
+    MissedPrayer,
+}
+
+/// Something worth telling the user about
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NotifyEvent {
+    pub kind: EventKind,
+    /// Identifies the event across runs, so it's only sent once
+    pub key: String,
+    pub title: String,
+    pub message: String,
+}
+
+/// Finds the events in fresh daily stats (with goals evaluated) on logical date `today`,
+/// with `hour` the local wall-clock hour
+///
+/// `mature_passages` is the all-time number of mature passages (from
+/// `ankistats::get_cumulative_history`), mentioned when passages mature; the daily stats
+/// only count maturing within their window.
+///
+/// - a goal achieved in its current window
+/// - an overall or per-source streak reaching a milestone today
+/// - Anki passages maturing today
+/// - no prayer yet today after the reminder hour
+///
+/// Streaks and goals come from the summary, so run this on stats from
+/// `get_faith_daily_stats`.
+pub fn detect_events(
+    stats: &FaithDailyStats,
+    config: &NotifyConfig,
+    mature_passages: Option<i64>,
+    today: NaiveDate,
+    hour: u32,
+) -> Vec<NotifyEvent> {
+    let milestones = config
+        .streak_milestones
+        .as_deref()
+        .unwrap_or(&DEFAULT_STREAK_MILESTONES);
+    let reminder_hour = config
+        .prayer_reminder_hour
+        .unwrap_or(DEFAULT_PRAYER_REMINDER_HOUR);
+    let window_end = |days_left: i64| today + Duration::days(days_left.max(1) - 1);
+    let today = today.format("%Y-%m-%d").to_string();
+    let mut events = Vec::new();
+
+    for progress in &stats.summary.goals {
+        if progress.achieved {
+            events.push(NotifyEvent {
+                kind: EventKind::GoalMet,
+                // One per goal window, however many days of it remain
+                key: format!(
+                    "goal:{}:{}",
+                    progress.description,
+                    window_end(progress.days_left)
+                ),
+                title: "Goal met".to_string(),
+                message: format!(
+                    "{}: {:.0} of {:.0}",
+                    progress.description, progress.current, progress.target
+                ),
+            });
+        }
+    }
+
+    let streaks = std::iter::once(("faith activity", &stats.summary.streaks.any_activity)).chain(
+        stats
+            .summary
+            .streaks
+            .by_source
+            .iter()
+            .map(|(source, streaks)| (source.as_str(), streaks)),
+    );
+    for (name, streak) in streaks {
+        let active_today = streak.last_active_date.as_deref() == Some(today.as_str());
+        if active_today && milestones.contains(&streak.current_streak) {
+            events.push(NotifyEvent {
+                kind: EventKind::StreakMilestone,
+                key: format!("streak:{}:{}:{}", name, streak.current_streak, today),
+                title: format!("{}-day streak", streak.current_streak),
+                message: format!("{} days in a row of {}", streak.current_streak, name),
+            });
+        }
+    }
+
+    if let Some(day) = stats.days.iter().find(|day| day.date == today) {
+        if day.anki_matured_passages > 0 {
+            let mut message = format!(
+                "{} passage{} matured today",
+                day.anki_matured_passages,
+                if day.anki_matured_passages == 1 {
+                    ""
+                } else {
+                    "s"
+                }
+            );
+            if let Some(mature_passages) = mature_passages {
+                message.push_str(&format!(" ({} mature in all)", mature_passages));
+            }
+            events.push(NotifyEvent {
+                kind: EventKind::PassageMatured,
+                key: format!("matured:{}", today),
+                title: "Passages matured".to_string(),
+                message,
+            });
+        }
+        if day.prayer_minutes <= 0.0 && hour >= reminder_hour {
+            events.push(NotifyEvent {
+                kind: EventKind::MissedPrayer,
+                key: format!("missed-prayer:{}", today),
+                title: "No prayer yet today".to_string(),
+                message: "There's still time to pray before the day ends".to_string(),
+            });
+        }
+    }
+
+    events
+}
+
+/// `detect_events` as of now, using the current day boundaries
+pub fn detect_current_events(
+    stats: &FaithDailyStats,
+    config: &NotifyConfig,
+    mature_passages: Option<i64>,
+) -> Result<Vec<NotifyEvent>> {
+    let period_config = PeriodConfig::default();
+    let now = period_config
+        .timezone
+        .local_datetime(Utc::now().timestamp_millis())?;
+    Ok(detect_events(
+        stats,
+        config,
+        mature_passages,
+        period_config.today()?,
+        now.hour(),
+    ))
+}
+
+/// Posts an event to the configured service
+pub fn send(config: &NotifyConfig, event: &NotifyEvent) -> Result<()> {
+    let agent: ureq::Agent = ureq::Agent::config_builder()
+        .timeout_global(Some(std::time::Duration::from_secs(30)))
+        .build()
+        .into();
+    let required = |value: &Option<String>, name: &str| {
+        value
+            .clone()
+            .context(format!("notify.{} is required for this service", name))
+    };
+
+    let result = match config.service {
+        NotifyService::Webhook => agent
+            .post(&required(&config.url, "url")?)
+            .send_json(event)
+            .map(|_| ()),
+        NotifyService::Ntfy => agent
+            .post(&required(&config.url, "url")?)
+            .header("Title", &event.title)
+            .header("Tags", ntfy_tag(event.kind))
+            .send(&event.message)
+            .map(|_| ()),
+        NotifyService::Telegram => {
+            let url = format!(
+                "https://api.telegram.org/bot{}/sendMessage",
+                required(&config.token, "token")?
+            );
+            agent
+                .post(&url)
+                .send_json(json!({
+                    "chat_id": required(&config.chat_id, "chat_id")?,
+                    "text": format!("{}\n{}", event.title, event.message),
+                }))
+                .map(|_| ())
+        }
+    };
+    result.context(format!("Failed to send '{}' notification", event.title))
+}
+
+/// Emoji shortcode ntfy shows next to the title
+fn ntfy_tag(kind: EventKind) -> &'static str {
+    match kind {
+        EventKind::GoalMet => "white_check_mark",
+        EventKind::StreakMilestone => "fire",
+        EventKind::PassageMatured => "book",
+        EventKind::MissedPrayer => "pray",
+    }
+}
+
+/// Keys of events already sent, kept in a JSON file between runs
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SentEvents {
+    keys: BTreeSet<String>,
+}
+
+impl SentEvents {
+    /// Reads the sent keys, or starts empty if the file doesn't exist yet
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(path)
+            .context(format!("Failed to read '{}'", path.display()))?;
+        serde_json::from_str(&content).context(format!("Invalid notify state '{}'", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)
+            .context(format!("Failed to write '{}'", path.display()))
+    }
+
+    pub fn contains(&self, event: &NotifyEvent) -> bool {
+        self.keys.contains(&event.key)
+    }
+
+    pub fn insert(&mut self, event: &NotifyEvent) {
+        self.keys.insert(event.key.clone());
+    }
+}
+
+impl NotifyConfig {
+    /// Checks the service has the settings it needs
+    pub fn validate(&self) -> Result<()> {
+        let missing = match self.service {
+            NotifyService::Webhook | NotifyService::Ntfy if self.url.is_none() => "url",
+            NotifyService::Telegram if self.token.is_none() => "token",
+            NotifyService::Telegram if self.chat_id.is_none() => "chat_id",
+            _ => return Ok(()),
+        };
+        bail!("notify.{} is required for this service", missing)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ActivityStreaks, FaithDailySummary, FaithDayStats, FaithStreaks};
+    use readingstats::models::{ReadingStreaks, SessionLengthStats};
+    use statsutils::testing::AnkiFixture;
+    use std::collections::BTreeMap;
+
+    fn config() -> NotifyConfig {
+        toml::from_str("service = \"ntfy\"\nurl = \"https://ntfy.sh/test\"").unwrap()
+    }
+
+    fn day(date: &str, prayer_minutes: f64, matured: i64) -> FaithDayStats {
+        FaithDayStats {
+            date: date.to_string(),
+            anki_minutes: 5.0,
+            anki_matured_passages: matured,
+            anki_lost_passages: 0,
+            anki_cumulative_passages: matured,
+            reading_minutes: 0.0,
+            reading_pages: 0,
+            reading_pages_per_hour: 0.0,
+            other_reading_minutes: 0.0,
+            reading_plan_days: 0,
+            journal_words: 0,
+            at_church_minutes: 0.0,
+            prayer_minutes,
+            prayer_categories: Vec::new(),
+            other_minutes: BTreeMap::new(),
+            score: 0.0,
+            rolling_score: 0.0,
+        }
+    }
+
+    fn stats(days: Vec<FaithDayStats>, streak: i64) -> FaithDailyStats {
+        let streaks = FaithStreaks {
+            any_activity: ActivityStreaks {
+                current_streak: streak,
+                longest_streak: streak,
+                active_days: streak,
+                last_active_date: days.last().map(|d| d.date.clone()),
+            },
+            by_source: BTreeMap::new(),
+        };
+        let summary = FaithDailySummary::from_faith_daily_stats(
+            &days,
+            &ReadingStreaks {
+                min_minutes: 0.0,
+                current_streak: 0,
+                longest_streak: 0,
+                qualifying_days: 0,
+                last_qualifying_date: None,
+            },
+            &SessionLengthStats::default(),
+            &streaks,
+        );
+        FaithDailyStats { days, summary }
+    }
+
+    fn date(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 6, d).unwrap()
+    }
+
+    #[test]
+    fn test_detect_events() {
+        let stats = stats(vec![day("2025-06-10", 0.0, 2)], 30);
+
+        let kinds = |hour| {
+            detect_events(&stats, &config(), None, date(10), hour)
+                .into_iter()
+                .map(|e| e.kind)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            kinds(9),
+            [EventKind::StreakMilestone, EventKind::PassageMatured]
+        );
+        assert_eq!(
+            kinds(21),
+            [
+                EventKind::StreakMilestone,
+                EventKind::PassageMatured,
+                EventKind::MissedPrayer
+            ]
+        );
+
+        let events = detect_events(&stats, &config(), None, date(10), 9);
+        assert_eq!(events[0].key, "streak:faith activity:30:2025-06-10");
+        assert_eq!(events[1].message, "2 passages matured today");
+    }
+
+    /// Two passages matured months ago and one today: the message counts all three, though
+    /// the 30-day window's cumulative counter only sees today's
+    #[test]
+    fn test_matured_message_counts_all_mature_passages() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("collection.anki2");
+        let now_ms = Utc::now().timestamp_millis();
+
+        let mut anki = AnkiFixture::create(&path, now_ms / 1000 - 400 * 86_400).unwrap();
+        let deck = anki.deck("Bible::Verses").unwrap();
+        let model = anki.notetype("Bible Verse").unwrap();
+        for (reference, days_ago) in [("John 3:16", 100), ("Psalm 23:1", 90), ("Romans 8:28", 0)] {
+            let note = anki.note(model, reference, "").unwrap();
+            for ord in 0..2 {
+                let card = anki.review_card(note, deck, ord, 30, 410).unwrap();
+                anki.review(card, now_ms - days_ago * 86_400_000, 60_000, 3, 30)
+                    .unwrap();
+            }
+        }
+
+        let db_path = path.to_str().unwrap();
+        let anki_today = ankistats::get_last_30_days_stats(db_path)
+            .unwrap()
+            .pop()
+            .unwrap();
+        assert_eq!(anki_today.cumulative_passages, 1);
+        let mature_passages = ankistats::get_cumulative_history(db_path)
+            .unwrap()
+            .last()
+            .map(|point| point.mature_passages);
+        assert_eq!(mature_passages, Some(3));
+
+        let today = NaiveDate::parse_from_str(&anki_today.date, "%Y-%m-%d").unwrap();
+        let stats = stats(
+            vec![day(&anki_today.date, 10.0, anki_today.matured_passages)],
+            1,
+        );
+        let events = detect_events(&stats, &config(), mature_passages, today, 9);
+        assert_eq!(events.len(), 1);
+        assert_eq!(
+            events[0].message,
+            "1 passage matured today (3 mature in all)"
+        );
+    }
+
+    #[test]
+    fn test_sent_events_skip_repeats() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notify-state.json");
+        let event = detect_events(
+            &stats(vec![day("2025-06-10", 10.0, 1)], 3),
+            &config(),
+            None,
+            date(10),
+            9,
+        )
+        .remove(0);
+
+        let mut sent = SentEvents::load(&path).unwrap();
+        assert!(!sent.contains(&event));
+        sent.insert(&event);
+        sent.save(&path).unwrap();
+        assert!(SentEvents::load(&path).unwrap().contains(&event));
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(config().validate().is_ok());
+        let telegram: NotifyConfig =
+            toml::from_str("service = \"telegram\"\ntoken = \"t\"").unwrap();
+        assert!(
+            telegram
+                .validate()
+                .unwrap_err()
+                .to_string()
+                .contains("chat_id")
+        );
+    }
+}
@@ -0,0 +1,286 @@
+use ankistats::models::Milestone;
+use anyhow::{Result, bail};
+use chrono::NaiveDate;
+use statsutils::fmt::duration_hours_minutes;
+use statsutils::{Bucket, DatePeriod, Granularity, PeriodConfig};
+
+use crate::config::FaithConfig;
+use crate::goals::{self, FaithGoalProgress};
+use crate::models::{FaithPeriodStats, FaithStreaks};
+use crate::source::FaithDataSource;
+use crate::{faith_stats_for_period, faith_streaks_as_of};
+
+/// Everything a weekly review covers
+#[derive(Debug, Clone)]
+pub struct WeeklyReport {
+    /// The whole week, even if it isn't over yet
+    pub week: Bucket,
+    /// Minutes from each source on each day of the week, through today
+    pub days: Vec<FaithPeriodStats>,
+    /// Streaks as of the last day in `days`
+    pub streaks: FaithStreaks,
+    /// Anki milestones reached during the week
+    pub milestones: Vec<Milestone>,
+    /// Goal progress as of the last day in `days`
+    pub goals: Vec<FaithGoalProgress>,
+}
+
+/// Gathers the weekly review for the week containing `date`, from every source in `config`
+///
+/// # Errors
+/// Returns an error if any source is unavailable, or if the week hasn't started yet
+pub fn weekly_report(config: &FaithConfig, date: NaiveDate) -> Result<WeeklyReport> {
+    let period_config = PeriodConfig::default();
+    let today = period_config.today()?;
+    let start = period_config.week_start_date(date);
+    if start > today {
+        bail!("The week of {} hasn't started yet", date);
+    }
+    let week = Bucket::from_key(&start.format("%Y-%m-%d").to_string(), Granularity::Week)?;
+    let end = week.end.min(today);
+
+    let sources = config.data_sources();
+    let sources: Vec<&dyn FaithDataSource> = sources.iter().map(|s| s.as_ref()).collect();
+    let days = faith_stats_for_period(
+        &sources,
+        &DatePeriod::for_range(start, end, Granularity::Day)?,
+    )?
+    .periods;
+
+    let (first, last) = (start.to_string(), week.end.to_string());
+    let milestones = ankistats::get_milestones(&config.sources.anki)?
+        .into_iter()
+        .filter(|m| m.date >= first && m.date <= last)
+        .collect();
+
+    Ok(WeeklyReport {
+        week,
+        days,
+        streaks: faith_streaks_as_of(&sources, end)?,
+        milestones,
+        goals: goals::evaluate_faith_goals_as_of(config, end)?,
+    })
+}
+
+/// Renders the weekly review for the week containing `date` as Markdown, ready to paste
+/// into a weekly note
+pub fn weekly_markdown(config: &FaithConfig, date: NaiveDate) -> Result<String> {
+    Ok(to_markdown(&weekly_report(config, date)?))
+}
+
+/// Default file name for a week's report, e.g. "faith-review-2025-06-01.md"
+pub fn default_file_name(week_start: NaiveDate) -> String {
+    format!("faith-review-{}.md", week_start.format("%Y-%m-%d"))
+}
+
+/// Renders a weekly review as Markdown: totals per source, a per-day table, streaks,
+/// milestones, and goals as a checklist
+///
+/// ```text
+/// # Faith Review: Week of June 1, 2025
+///
+/// ## Totals
+///
+/// | Source | Time | Days |
+/// | --- | ---: | ---: |
+/// | Prayer | 0h 45m | 3 |
+/// | **Total** | **0h 45m** | **3** |
+/// ```
+pub fn to_markdown(report: &WeeklyReport) -> String {
+    let mut out = format!(
+        "# Faith Review: Week of {}\n",
+        report.week.start.format("%B %-d, %Y")
+    );
+
+    // Every day has the same sources, in name order
+    let sources: Vec<&String> = report
+        .days
+        .first()
+        .map(|day| day.minutes.keys().collect())
+        .unwrap_or_default();
+    let source_minutes =
+        |day: &FaithPeriodStats, source: &str| day.minutes.get(source).copied().unwrap_or(0.0);
+
+    out.push_str("\n## Totals\n\n| Source | Time | Days |\n| --- | ---: | ---: |\n");
+    for source in &sources {
+        let minutes: f64 = report.days.iter().map(|d| source_minutes(d, source)).sum();
+        let days = report
+            .days
+            .iter()
+            .filter(|d| source_minutes(d, source) > 0.0)
+            .count();
+        out.push_str(&format!(
+            "| {} | {} | {} |\n",
+            display_name(source),
+            duration_hours_minutes(minutes),
+            days
+        ));
+    }
+    let total: f64 = report.days.iter().map(|d| d.total_minutes).sum();
+    let active_days = report.days.iter().filter(|d| d.total_minutes > 0.0).count();
+    out.push_str(&format!(
+        "| **Total** | **{}** | **{}** |\n",
+        duration_hours_minutes(total),
+        active_days
+    ));
+
+    out.push_str("\n## Days\n\n| Day |");
+    for source in &sources {
+        out.push_str(&format!(" {} |", display_name(source)));
+    }
+    out.push_str(" Total |\n| --- |");
+    out.push_str(&" ---: |".repeat(sources.len() + 1));
+    out.push('\n');
+    for day in &report.days {
+        let name = NaiveDate::parse_from_str(&day.period_start, "%Y-%m-%d")
+            .map(|date| date.format("%a %b %-d").to_string())
+            .unwrap_or_else(|_| day.label.clone());
+        out.push_str(&format!("| {} |", name));
+        for source in &sources {
+            out.push_str(&format!(" {:.0} |", source_minutes(day, source)));
+        }
+        out.push_str(&format!(
+            " {} |\n",
+            duration_hours_minutes(day.total_minutes)
+        ));
+    }
+
+    out.push_str("\n## Streaks\n\n");
+    out.push_str(&format!(
+        "- Any faith activity: {} days (longest {})\n",
+        report.streaks.any_activity.current_streak, report.streaks.any_activity.longest_streak
+    ));
+    for (source, streak) in &report.streaks.by_source {
+        out.push_str(&format!(
+            "- {}: {} days (longest {})\n",
+            display_name(source),
+            streak.current_streak,
+            streak.longest_streak
+        ));
+    }
+
+    out.push_str("\n## Milestones\n\n");
+    if report.milestones.is_empty() {
+        out.push_str("None this week.\n");
+    }
+    for milestone in &report.milestones {
+        out.push_str(&format!(
+            "- {}: {}\n",
+            milestone.date, milestone.description
+        ));
+    }
+
+    out.push_str("\n## Goals\n\n");
+    if report.goals.is_empty() {
+        out.push_str("No goals set.\n");
+    }
+    for progress in &report.goals {
+        let (check, status) = if progress.achieved {
+            ("x", "achieved")
+        } else if progress.on_track {
+            (" ", "on track")
+        } else {
+            (" ", "behind")
+        };
+        out.push_str(&format!(
+            "- [{}] {}: {:.1} / {} ({:.0}%, {})\n",
+            check,
+            progress.description,
+            progress.current,
+            progress.target,
+            progress.percent,
+            status
+        ));
+    }
+
+    out
+}
+
+/// Source name for headings, e.g. "Prayer" for "prayer"
+fn display_name(source: &str) -> String {
+    let mut chars = source.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::goals::{FaithGoal, FaithGoalPeriod, FaithMetric};
+    use crate::models::ActivityStreaks;
+    use ankistats::models::MilestoneKind;
+    use std::collections::BTreeMap;
+
+    fn day(date: &str, prayer: f64, reading: f64) -> FaithPeriodStats {
+        let minutes = BTreeMap::from([
+            ("prayer".to_string(), prayer),
+            ("reading".to_string(), reading),
+        ]);
+        FaithPeriodStats::new(date.to_string(), String::new(), minutes)
+    }
+
+    fn streak(current: i64, longest: i64) -> ActivityStreaks {
+        ActivityStreaks {
+            current_streak: current,
+            longest_streak: longest,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_to_markdown() {
+        let goal = FaithGoal {
+            metric: FaithMetric::PrayerMinutes,
+            target: 20.0,
+            period: FaithGoalPeriod::Day,
+        };
+        let report = WeeklyReport {
+            week: Bucket::from_key("2025-06-01", Granularity::Week).unwrap(),
+            days: vec![day("2025-06-01", 15.0, 30.0), day("2025-06-02", 0.0, 0.0)],
+            streaks: FaithStreaks {
+                any_activity: streak(0, 4),
+                by_source: BTreeMap::from([("prayer".to_string(), streak(0, 2))]),
+            },
+            milestones: vec![Milestone {
+                date: "2025-06-01".to_string(),
+                kind: MilestoneKind::BookStarted,
+                description: "Started James".to_string(),
+            }],
+            goals: vec![FaithGoalProgress {
+                description: goal.description(),
+                goal,
+                current: 0.0,
+                target: 20.0,
+                remaining: 20.0,
+                percent: 0.0,
+                achieved: false,
+                on_track: false,
+                days_left: 1,
+                pace_needed: 20.0,
+            }],
+        };
+
+        assert_eq!(
+            to_markdown(&report),
+            "# Faith Review: Week of June 1, 2025\n\
+             \n## Totals\n\n\
+             | Source | Time | Days |\n| --- | ---: | ---: |\n\
+             | Prayer | 0h 15m | 1 |\n\
+             | Reading | 0h 30m | 1 |\n\
+             | **Total** | **0h 45m** | **1** |\n\
+             \n## Days\n\n\
+             | Day | Prayer | Reading | Total |\n| --- | ---: | ---: | ---: |\n\
+             | Sun Jun 1 | 15 | 30 | 0h 45m |\n\
+             | Mon Jun 2 | 0 | 0 | 0h 00m |\n\
+             \n## Streaks\n\n\
+             - Any faith activity: 0 days (longest 4)\n\
+             - Prayer: 0 days (longest 2)\n\
+             \n## Milestones\n\n\
+             - 2025-06-01: Started James\n\
+             \n## Goals\n\n\
+             - [ ] 20 prayer minutes per day: 0.0 / 20 (0%, behind)\n"
+        );
+    }
+}
@@ -0,0 +1,148 @@
+use serde::Deserialize;
+use statsutils::{PeriodSeries, math::mean, trend::rolling_mean};
+use std::collections::BTreeMap;
+
+use crate::models::FaithDailyStats;
+
+/// Number of days in the rolling score average, including the day itself
+pub const ROLLING_DAYS: usize = 7;
+
+/// How much one source counts toward the daily score
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SourceWeight {
+    /// Share of the score relative to the other sources' weights
+    pub weight: f64,
+    /// Minutes that earn half of the source's share; each further stretch of this many
+    /// minutes earns half of what's left, so long sessions have diminishing returns
+    pub half_minutes: f64,
+}
+
+/// Weights for the daily consistency score, keyed by source name (`anki`, `reading`,
+/// `church`, `prayer`, or an additional source's name)
+///
+/// Sources without a weight don't affect the score. Setting any weight in the config
+/// replaces the defaults: Anki, reading, and prayer weighted equally, with half credit at
+/// 10, 15, and 10 minutes. Church is left out by default since most days have none.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(transparent)]
+pub struct ScoreWeights(pub BTreeMap<String, SourceWeight>);
+
+impl Default for ScoreWeights {
+    fn default() -> Self {
+        let weight = |half_minutes| SourceWeight {
+            weight: 1.0,
+            half_minutes,
+        };
+        Self(BTreeMap::from([
+            ("anki".to_string(), weight(10.0)),
+            ("reading".to_string(), weight(15.0)),
+            ("prayer".to_string(), weight(10.0)),
+        ]))
+    }
+}
+
+impl ScoreWeights {
+    /// Scores a day's minutes per source from 0 (nothing) toward 100 (plenty of every
+    /// weighted source)
+    pub fn score(&self, minutes: &BTreeMap<String, f64>) -> f64 {
+        let total_weight: f64 = self.0.values().map(|w| w.weight.max(0.0)).sum();
+        if total_weight <= 0.0 {
+            return 0.0;
+        }
+
+        let earned: f64 = self
+            .0
+            .iter()
+            .map(|(source, w)| {
+                let minutes = minutes.get(source).copied().unwrap_or(0.0).max(0.0);
+                let credit = if w.half_minutes > 0.0 {
+                    1.0 - 0.5_f64.powf(minutes / w.half_minutes)
+                } else if minutes > 0.0 {
+                    1.0
+                } else {
+                    0.0
+                };
+                w.weight.max(0.0) * credit
+            })
+            .sum();
+        100.0 * earned / total_weight
+    }
+
+    /// Sets each day's score and `ROLLING_DAYS`-day average, and the summary's average
+    ///
+    /// The first days' averages only cover the days available.
+    pub fn apply(&self, stats: &mut FaithDailyStats) {
+        let scores = PeriodSeries::new(
+            stats
+                .days
+                .iter()
+                .map(|day| (day.date.clone(), self.score(&day.minutes_by_source())))
+                .collect(),
+        );
+        let rolling = rolling_mean(&scores, ROLLING_DAYS);
+
+        for ((day, score), rolling_score) in stats
+            .days
+            .iter_mut()
+            .zip(scores.values())
+            .zip(rolling.values())
+        {
+            day.score = *score;
+            day.rolling_score = *rolling_score;
+        }
+        stats.summary.average_score = mean(&scores.into_values());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn minutes(entries: &[(&str, f64)]) -> BTreeMap<String, f64> {
+        entries
+            .iter()
+            .map(|(source, minutes)| (source.to_string(), *minutes))
+            .collect()
+    }
+
+    #[test]
+    fn test_score_has_diminishing_returns() {
+        let weights = ScoreWeights::default();
+        assert_eq!(weights.score(&minutes(&[])), 0.0);
+
+        // Half credit for prayer alone, at its half-credit minutes
+        let half = weights.score(&minutes(&[("prayer", 10.0)]));
+        assert!((half - 100.0 / 6.0).abs() < 1e-9);
+        // Doubling the time adds only half as much again
+        let double = weights.score(&minutes(&[("prayer", 20.0)]));
+        assert!((double - 25.0).abs() < 1e-9);
+
+        // Unweighted sources don't count
+        assert_eq!(weights.score(&minutes(&[("church", 90.0)])), 0.0);
+
+        let all = weights.score(&minutes(&[
+            ("anki", 1000.0),
+            ("reading", 1000.0),
+            ("prayer", 1000.0),
+        ]));
+        assert!(all > 99.9 && all <= 100.0);
+    }
+
+    #[test]
+    fn test_parse_weights() {
+        let weights: ScoreWeights = toml::from_str(
+            "[prayer]\nweight = 2\nhalf_minutes = 5\n[sermons]\nweight = 1\nhalf_minutes = 30",
+        )
+        .unwrap();
+        assert_eq!(weights.0.len(), 2);
+        assert_eq!(
+            weights.0["prayer"],
+            SourceWeight {
+                weight: 2.0,
+                half_minutes: 5.0
+            }
+        );
+        assert!((weights.score(&minutes(&[("prayer", 5.0)])) - 100.0 / 3.0).abs() < 1e-9);
+    }
+}
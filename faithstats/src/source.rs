@@ -0,0 +1,684 @@
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use statsutils::{DatePeriod, PeriodConfig, PeriodSeries};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+use crate::models::{FaithDayStats, FaithTodayStats, FaithWeekStats};
+
+/// A kind of faith activity whose time can be combined with the others
+///
+/// Each method covers the same window as the matching `get_faith_*` function: today, the
+/// last 30 days, the last 12 weeks, or an arbitrary period. Sources that can't report a
+/// window return `None` and are left out of it.
+///
+/// Today's, daily, and weekly stats start out empty and let each source fill them in
+/// with `merge_today`, `merge_daily`, and `merge_weekly`; the built-in sources fill their
+/// own fields, and any other source adds its time to `other_minutes`.
+pub trait FaithDataSource {
+    /// Short lowercase name used as the source's key, e.g. "sermons"
+    fn name(&self) -> &str;
+
+    /// Minutes spent today
+    fn today_minutes(&self) -> Result<Option<f64>> {
+        Ok(None)
+    }
+
+    /// Fills in this source's activity today, adding its `today_minutes` to
+    /// `other_minutes` by default
+    fn merge_today(&self, today: &mut FaithTodayStats) -> Result<()> {
+        if let Some(minutes) = self.today_minutes()? {
+            today.other_minutes.insert(self.name().to_string(), minutes);
+        }
+        Ok(())
+    }
+
+    /// Minutes for each of the last 30 days, keyed by date (YYYY-MM-DD)
+    fn daily_minutes(&self) -> Result<Option<PeriodSeries<f64>>> {
+        Ok(None)
+    }
+
+    /// Minutes for each of the last 12 weeks, keyed by week start
+    fn weekly_minutes(&self) -> Result<Option<PeriodSeries<f64>>> {
+        Ok(None)
+    }
+
+    /// Fills in this source's activity on each of the last 30 days, adding its
+    /// `daily_minutes` to `other_minutes` by default
+    ///
+    /// # Errors
+    /// Returns an error if the source covers different days than `days`
+    fn merge_daily(&self, days: &mut [FaithDayStats]) -> Result<()> {
+        let Some(minutes) = self.daily_minutes()? else {
+            return Ok(());
+        };
+        let minutes = aligned(self.name(), days.iter().map(|d| &d.date), minutes)?;
+        for (day, minutes) in days.iter_mut().zip(minutes) {
+            day.other_minutes.insert(self.name().to_string(), minutes);
+        }
+        Ok(())
+    }
+
+    /// Fills in this source's activity on each of the last 12 weeks, adding its
+    /// `weekly_minutes` to `other_minutes` by default
+    ///
+    /// # Errors
+    /// Returns an error if the source covers different weeks than `weeks`
+    fn merge_weekly(&self, weeks: &mut [FaithWeekStats]) -> Result<()> {
+        let Some(minutes) = self.weekly_minutes()? else {
+            return Ok(());
+        };
+        let minutes = aligned(self.name(), weeks.iter().map(|w| &w.week_start), minutes)?;
+        for (week, minutes) in weeks.iter_mut().zip(minutes) {
+            week.other_minutes.insert(self.name().to_string(), minutes);
+        }
+        Ok(())
+    }
+
+    /// Minutes for each bucket of `period`, keyed like the period's dates
+    fn period_minutes(&self, _period: &DatePeriod) -> Result<Option<PeriodSeries<f64>>> {
+        Ok(None)
+    }
+
+    /// Every day with any activity, all-time, used for streaks
+    fn active_dates(&self) -> Result<Option<Vec<NaiveDate>>> {
+        Ok(None)
+    }
+
+    /// Files the source reads, checked for existence and freshness by `get_source_status`
+    fn paths(&self) -> Vec<PathBuf> {
+        Vec::new()
+    }
+
+    /// Format and version detected in the source's data, e.g. "Anki schema 18"
+    fn schema(&self) -> Result<Option<String>> {
+        Ok(None)
+    }
+}
+
+/// The built-in sources for the given paths; church attendance only with an Arc Timeline
+/// export
+pub fn builtin_sources(
+    anki_db_path: &str,
+    koreader_db_path: &str,
+    arcstats_export_path: Option<&str>,
+    proseuche_db_path: &str,
+) -> Vec<Box<dyn FaithDataSource>> {
+    let mut sources: Vec<Box<dyn FaithDataSource>> = vec![
+        Box::new(AnkiSource {
+            db_path: anki_db_path.to_string(),
+        }),
+        Box::new(ReadingSource {
+            db_path: koreader_db_path.to_string(),
+        }),
+        Box::new(PrayerSource {
+            db_path: proseuche_db_path.to_string(),
+        }),
+    ];
+    if let Some(export_path) = arcstats_export_path {
+        sources.push(Box::new(ChurchSource {
+            export_path: export_path.to_string(),
+        }));
+    }
+    sources
+}
+
+/// Anki Bible memorization, from a collection.anki2 file
+///
+/// Days are split at the workspace's boundaries (`PeriodConfig::default()`) rather than the
+/// collection's own timezone and rollover hour, so they line up with the other sources.
+pub struct AnkiSource {
+    pub db_path: String,
+}
+
+impl FaithDataSource for AnkiSource {
+    fn name(&self) -> &str {
+        "anki"
+    }
+
+    fn today_minutes(&self) -> Result<Option<f64>> {
+        Ok(Some(ankistats::get_today_study_time_with_config(
+            &self.db_path,
+            &PeriodConfig::default(),
+        )?))
+    }
+
+    fn merge_today(&self, today: &mut FaithTodayStats) -> Result<()> {
+        today.anki_minutes = self.today_minutes()?.unwrap_or(0.0);
+        Ok(())
+    }
+
+    fn merge_daily(&self, days: &mut [FaithDayStats]) -> Result<()> {
+        let stats =
+            ankistats::get_last_30_days_stats_with_config(&self.db_path, &PeriodConfig::default())?;
+        let stats = PeriodSeries::from_items(stats, |d| &d.date);
+        let stats = aligned(self.name(), days.iter().map(|d| &d.date), stats)?;
+        for (day, anki) in days.iter_mut().zip(stats) {
+            day.anki_minutes = anki.minutes;
+            day.anki_matured_passages = anki.matured_passages;
+            day.anki_lost_passages = anki.lost_passages;
+            day.anki_cumulative_passages = anki.cumulative_passages;
+        }
+        Ok(())
+    }
+
+    fn merge_weekly(&self, weeks: &mut [FaithWeekStats]) -> Result<()> {
+        let stats = ankistats::get_last_12_weeks_stats_with_config(
+            &self.db_path,
+            &PeriodConfig::default(),
+        )?;
+        let stats = PeriodSeries::from_items(stats, |w| &w.week_start);
+        let stats = aligned(self.name(), weeks.iter().map(|w| &w.week_start), stats)?;
+        for (week, anki) in weeks.iter_mut().zip(stats) {
+            week.anki_minutes = anki.minutes;
+            week.anki_matured_passages = anki.matured_passages;
+            week.anki_lost_passages = anki.lost_passages;
+            week.anki_cumulative_passages = anki.cumulative_passages;
+        }
+        Ok(())
+    }
+
+    fn period_minutes(&self, period: &DatePeriod) -> Result<Option<PeriodSeries<f64>>> {
+        let periods = ankistats::get_stats_for_period_with_config(
+            &self.db_path,
+            period,
+            &PeriodConfig::default(),
+        )?;
+        Ok(Some(PeriodSeries::new(
+            periods
+                .into_iter()
+                .map(|p| (p.period_start, p.minutes))
+                .collect(),
+        )))
+    }
+
+    fn active_dates(&self) -> Result<Option<Vec<NaiveDate>>> {
+        Ok(Some(ankistats::get_study_dates_with_config(
+            &self.db_path,
+            &PeriodConfig::default(),
+        )?))
+    }
+
+    fn paths(&self) -> Vec<PathBuf> {
+        vec![PathBuf::from(&self.db_path)]
+    }
+
+    fn schema(&self) -> Result<Option<String>> {
+        let version = ankistats::get_schema_version(&self.db_path)?;
+        Ok(Some(format!("Anki schema {}", version)))
+    }
+}
+
+/// Bible reading, from a KOReader statistics database or Kobo database
+pub struct ReadingSource {
+    pub db_path: String,
+}
+
+impl FaithDataSource for ReadingSource {
+    fn name(&self) -> &str {
+        "reading"
+    }
+
+    fn today_minutes(&self) -> Result<Option<f64>> {
+        let source = readingstats::source::source_for(&self.db_path)?;
+        Ok(Some(source.get_today_reading_time()?))
+    }
+
+    fn merge_today(&self, today: &mut FaithTodayStats) -> Result<()> {
+        today.reading_minutes = self.today_minutes()?.unwrap_or(0.0);
+        Ok(())
+    }
+
+    fn merge_daily(&self, days: &mut [FaithDayStats]) -> Result<()> {
+        let stats = readingstats::source::source_for(&self.db_path)?.get_last_30_days_stats()?;
+        let stats = PeriodSeries::from_items(stats, |d| &d.date);
+        let stats = aligned(self.name(), days.iter().map(|d| &d.date), stats)?;
+        for (day, reading) in days.iter_mut().zip(stats) {
+            day.reading_minutes = reading.minutes;
+            day.reading_pages = reading.pages;
+            day.reading_pages_per_hour = reading.pages_per_hour;
+            day.other_reading_minutes = reading.all_books_minutes - reading.minutes;
+        }
+        Ok(())
+    }
+
+    fn merge_weekly(&self, weeks: &mut [FaithWeekStats]) -> Result<()> {
+        let stats = readingstats::source::source_for(&self.db_path)?.get_last_12_weeks_stats()?;
+        let stats = PeriodSeries::from_items(stats, |w| &w.week_start);
+        let stats = aligned(self.name(), weeks.iter().map(|w| &w.week_start), stats)?;
+        for (week, reading) in weeks.iter_mut().zip(stats) {
+            week.reading_minutes = reading.minutes;
+            week.reading_pages = reading.pages;
+            week.reading_pages_per_hour = reading.pages_per_hour;
+            week.other_reading_minutes = reading.all_books_minutes - reading.minutes;
+        }
+        Ok(())
+    }
+
+    fn period_minutes(&self, period: &DatePeriod) -> Result<Option<PeriodSeries<f64>>> {
+        let periods =
+            readingstats::source::source_for(&self.db_path)?.get_stats_for_period(period)?;
+        Ok(Some(PeriodSeries::new(
+            periods
+                .into_iter()
+                .map(|p| (p.period_start, p.minutes))
+                .collect(),
+        )))
+    }
+
+    fn active_dates(&self) -> Result<Option<Vec<NaiveDate>>> {
+        let days = readingstats::source::source_for(&self.db_path)?.get_daily_bible_minutes()?;
+        Ok(Some(
+            days.into_iter()
+                .filter(|(_, minutes)| *minutes > 0.0)
+                .map(|(date, _)| date)
+                .collect(),
+        ))
+    }
+
+    fn paths(&self) -> Vec<PathBuf> {
+        vec![PathBuf::from(&self.db_path)]
+    }
+
+    fn schema(&self) -> Result<Option<String>> {
+        Ok(Some(
+            readingstats::source::source_for(&self.db_path)?.format(),
+        ))
+    }
+}
+
+/// Prayer time, from a Proseuche database or CSV prayer journal
+pub struct PrayerSource {
+    pub db_path: String,
+}
+
+impl FaithDataSource for PrayerSource {
+    fn name(&self) -> &str {
+        "prayer"
+    }
+
+    fn today_minutes(&self) -> Result<Option<f64>> {
+        let source = prayerstats::source::source_for(&self.db_path);
+        Ok(Some(source.get_today_prayer_time()?))
+    }
+
+    fn merge_today(&self, today: &mut FaithTodayStats) -> Result<()> {
+        today.prayer_minutes = self.today_minutes()?.unwrap_or(0.0);
+        Ok(())
+    }
+
+    fn merge_daily(&self, days: &mut [FaithDayStats]) -> Result<()> {
+        let source = prayerstats::source::source_for(&self.db_path);
+        let stats = PeriodSeries::from_items(source.get_last_30_days_stats()?, |d| &d.date)
+            .zip_source(
+                "prayer categories",
+                PeriodSeries::new(source.get_last_30_days_stats_by_category()?),
+            )?;
+        let stats = aligned(self.name(), days.iter().map(|d| &d.date), stats)?;
+        for (day, (prayer, categories)) in days.iter_mut().zip(stats) {
+            day.prayer_minutes = prayer.minutes;
+            day.prayer_categories = categories;
+        }
+        Ok(())
+    }
+
+    fn merge_weekly(&self, weeks: &mut [FaithWeekStats]) -> Result<()> {
+        let stats = prayerstats::source::source_for(&self.db_path).get_last_12_weeks_stats()?;
+        let stats = PeriodSeries::from_items(stats, |w| &w.week_start);
+        let stats = aligned(self.name(), weeks.iter().map(|w| &w.week_start), stats)?;
+        for (week, prayer) in weeks.iter_mut().zip(stats) {
+            week.prayer_minutes = prayer.minutes;
+        }
+        Ok(())
+    }
+
+    fn period_minutes(&self, period: &DatePeriod) -> Result<Option<PeriodSeries<f64>>> {
+        let periods =
+            prayerstats::source::source_for(&self.db_path).get_stats_for_period(period)?;
+        Ok(Some(PeriodSeries::new(
+            periods
+                .into_iter()
+                .map(|p| (p.period_start, p.minutes))
+                .collect(),
+        )))
+    }
+
+    fn active_dates(&self) -> Result<Option<Vec<NaiveDate>>> {
+        let source = prayerstats::source::source_for(&self.db_path);
+        Ok(Some(source.get_prayer_dates()?))
+    }
+
+    fn paths(&self) -> Vec<PathBuf> {
+        // Several backups can be merged, separated by commas
+        self.db_path
+            .split(',')
+            .map(str::trim)
+            .filter(|path| !path.is_empty())
+            .map(PathBuf::from)
+            .collect()
+    }
+
+    fn schema(&self) -> Result<Option<String>> {
+        Ok(Some(
+            prayerstats::source::source_for(&self.db_path).format(),
+        ))
+    }
+}
+
+/// Church attendance, from an Arc Timeline export
+pub struct ChurchSource {
+    pub export_path: String,
+}
+
+impl FaithDataSource for ChurchSource {
+    fn name(&self) -> &str {
+        "church"
+    }
+
+    fn today_minutes(&self) -> Result<Option<f64>> {
+        Ok(Some(arcstats::get_today_church_time(&self.export_path)?))
+    }
+
+    fn merge_today(&self, today: &mut FaithTodayStats) -> Result<()> {
+        today.at_church_minutes = self.today_minutes()?.unwrap_or(0.0);
+        Ok(())
+    }
+
+    fn merge_daily(&self, days: &mut [FaithDayStats]) -> Result<()> {
+        let stats = arcstats::get_last_30_days_stats(&self.export_path)?;
+        let stats = PeriodSeries::from_items(stats, |d| &d.date);
+        let stats = aligned(self.name(), days.iter().map(|d| &d.date), stats)?;
+        for (day, church) in days.iter_mut().zip(stats) {
+            day.at_church_minutes = church.minutes;
+        }
+        Ok(())
+    }
+
+    fn merge_weekly(&self, weeks: &mut [FaithWeekStats]) -> Result<()> {
+        let stats = arcstats::get_last_12_weeks_stats(&self.export_path)?;
+        let stats = PeriodSeries::from_items(stats, |w| &w.week_start);
+        let stats = aligned(self.name(), weeks.iter().map(|w| &w.week_start), stats)?;
+        for (week, church) in weeks.iter_mut().zip(stats) {
+            week.at_church_minutes = church.minutes;
+            week.at_church_daily_minutes = church.daily_minutes;
+            week.at_church_category_minutes = church.category_minutes;
+        }
+        Ok(())
+    }
+
+    fn period_minutes(&self, period: &DatePeriod) -> Result<Option<PeriodSeries<f64>>> {
+        let periods = arcstats::get_stats_for_period(&self.export_path, period)?;
+        Ok(Some(PeriodSeries::new(
+            periods
+                .into_iter()
+                .map(|p| (p.period_start, p.minutes))
+                .collect(),
+        )))
+    }
+
+    fn active_dates(&self) -> Result<Option<Vec<NaiveDate>>> {
+        Ok(Some(arcstats::get_church_dates(&self.export_path)?))
+    }
+
+    /// The export's metadata file, which Arc Timeline rewrites on every export
+    fn paths(&self) -> Vec<PathBuf> {
+        vec![Path::new(&self.export_path).join("metadata.json")]
+    }
+
+    fn schema(&self) -> Result<Option<String>> {
+        let metadata = arcstats::loader::load_metadata(&self.export_path)?;
+        Ok(Some(format!(
+            "Arc Timeline schema {}",
+            metadata.schema_version
+        )))
+    }
+}
+
+/// Minutes per logical day, for sources that load their whole history at once (such as
+/// app exports) and bucket it themselves to report each window
+pub(crate) struct DailyMinutes(pub BTreeMap<NaiveDate, f64>);
+
+impl DailyMinutes {
+    /// Minutes on today's logical date
+    pub fn today(&self) -> Result<f64> {
+        let today = PeriodConfig::default().today()?;
+        Ok(self.0.get(&today).copied().unwrap_or(0.0))
+    }
+
+    /// Minutes summed into each bucket of `period`; days outside it are ignored
+    pub fn for_period(&self, period: &DatePeriod) -> PeriodSeries<f64> {
+        let config = PeriodConfig::default();
+        let mut totals: HashMap<String, f64> = HashMap::new();
+        for (date, minutes) in &self.0 {
+            *totals
+                .entry(period.granularity.key(*date, &config))
+                .or_default() += minutes;
+        }
+        period.clone().build_series(totals)
+    }
+
+    /// Days with any minutes, oldest first
+    pub fn active_dates(&self) -> Vec<NaiveDate> {
+        self.0
+            .iter()
+            .filter(|(_, minutes)| **minutes > 0.0)
+            .map(|(date, _)| *date)
+            .collect()
+    }
+}
+
+/// An empty series over `keys`, to check other series' buckets against
+pub(crate) fn buckets_of<'a>(keys: impl IntoIterator<Item = &'a String>) -> PeriodSeries<()> {
+    PeriodSeries::new(keys.into_iter().map(|key| (key.clone(), ())).collect())
+}
+
+/// The values of `series` in the order of `keys`, failing if `source` covers different
+/// buckets
+///
+/// Every source buckets on the workspace's boundaries (`PeriodConfig::default()`), so the
+/// error names them.
+pub(crate) fn aligned<'a, T>(
+    source: &str,
+    keys: impl IntoIterator<Item = &'a String>,
+    series: PeriodSeries<T>,
+) -> Result<Vec<T>> {
+    let values = buckets_of(keys).zip(series).with_context(|| {
+        format!(
+            "{} results cover different buckets than the other sources ({})",
+            source,
+            PeriodConfig::default()
+        )
+    })?;
+    Ok(values.into_values().into_iter().map(|((), v)| v).collect())
+}
+
+/// Collects each source's minutes for `dates` into one map per bucket, keyed by source
+/// name
+///
+/// `fetch` picks the window (e.g. `FaithDataSource::daily_minutes`). Sources that return
+/// `None` are skipped; a source covering different buckets is an error.
+pub(crate) fn collect_minutes(
+    dates: Vec<String>,
+    sources: &[&dyn FaithDataSource],
+    fetch: impl Fn(&dyn FaithDataSource) -> Result<Option<PeriodSeries<f64>>>,
+) -> Result<PeriodSeries<BTreeMap<String, f64>>> {
+    let mut combined = PeriodSeries::new(dates.into_iter().map(|d| (d, BTreeMap::new())).collect());
+
+    for &source in sources {
+        let Some(series) = fetch(source)? else {
+            continue;
+        };
+        let entries =
+            combined
+                .zip_source(source.name(), series)?
+                .map(|date, (mut minutes, value)| {
+                    minutes.insert(source.name().to_string(), value);
+                    (date, minutes)
+                });
+        combined = PeriodSeries::new(entries);
+    }
+
+    Ok(combined)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use statsutils::testing::AnkiFixture;
+
+    struct Sermons;
+
+    impl FaithDataSource for Sermons {
+        fn name(&self) -> &str {
+            "sermons"
+        }
+
+        fn today_minutes(&self) -> Result<Option<f64>> {
+            Ok(Some(30.0))
+        }
+
+        fn daily_minutes(&self) -> Result<Option<PeriodSeries<f64>>> {
+            Ok(Some(PeriodSeries::new(vec![
+                ("2025-06-01".to_string(), 45.0),
+                ("2025-06-02".to_string(), 0.0),
+            ])))
+        }
+    }
+
+    struct WeeklyOnly;
+
+    impl FaithDataSource for WeeklyOnly {
+        fn name(&self) -> &str {
+            "weekly"
+        }
+    }
+
+    fn dates() -> Vec<String> {
+        vec!["2025-06-01".to_string(), "2025-06-02".to_string()]
+    }
+
+    /// A collection ten hours ahead of UTC with a midnight rollover still reports its days
+    /// on the workspace's boundaries
+    #[test]
+    fn test_anki_source_uses_workspace_day_boundaries() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("collection.anki2");
+        let config = PeriodConfig::default();
+        let yesterday = config.today().unwrap() - chrono::Duration::days(1);
+        // 11:30 PM yesterday in the workspace, already the next day in the collection's
+        // timezone
+        let at_ms = config.day_start_ms(yesterday).unwrap() + (19 * 60 + 30) * 60_000;
+
+        let mut anki = AnkiFixture::create(&path, at_ms / 1000 - 400 * 86_400).unwrap();
+        anki.set_day_boundaries(0, -600).unwrap();
+        let deck = anki.deck("Bible::Verses").unwrap();
+        let model = anki.notetype("Bible Verse").unwrap();
+        let note = anki.note(model, "John 3:16", "").unwrap();
+        let card = anki.review_card(note, deck, 0, 30, 410).unwrap();
+        anki.review(card, at_ms, 300_000, 3, 30).unwrap();
+
+        let db_path = path.to_str().unwrap();
+        let collection_config = ankistats::get_period_config(db_path).unwrap();
+        assert_eq!(
+            collection_config.logical_date(at_ms).unwrap(),
+            yesterday + chrono::Duration::days(1)
+        );
+
+        let source = AnkiSource {
+            db_path: db_path.to_string(),
+        };
+        let yesterday_key = yesterday.format("%Y-%m-%d").to_string();
+        let mut days: Vec<FaithDayStats> = DatePeriod::last_30_days()
+            .unwrap()
+            .dates
+            .into_iter()
+            .map(FaithDayStats::new)
+            .collect();
+        source.merge_daily(&mut days).unwrap();
+        let studied: Vec<(&str, f64)> = days
+            .iter()
+            .filter(|d| d.anki_minutes > 0.0)
+            .map(|d| (d.date.as_str(), d.anki_minutes))
+            .collect();
+        assert_eq!(studied, [(yesterday_key.as_str(), 5.0)]);
+
+        let period = DatePeriod::days(yesterday, config.today().unwrap()).unwrap();
+        let periods = source.period_minutes(&period).unwrap().unwrap();
+        assert_eq!(periods.into_values(), [5.0, 0.0]);
+        assert_eq!(source.active_dates().unwrap(), Some(vec![yesterday]));
+    }
+
+    #[test]
+    fn test_collect_minutes() {
+        let combined =
+            collect_minutes(dates(), &[&Sermons, &WeeklyOnly], |s| s.daily_minutes()).unwrap();
+        let days = combined.into_values();
+        assert_eq!(days[0], BTreeMap::from([("sermons".to_string(), 45.0)]));
+        assert_eq!(days[1], BTreeMap::from([("sermons".to_string(), 0.0)]));
+    }
+
+    #[test]
+    fn test_merge_today_adds_other_minutes() {
+        let mut today = FaithTodayStats::new(10.0, 0.0, 0.0, 0.0, BTreeMap::new());
+        Sermons.merge_today(&mut today).unwrap();
+        WeeklyOnly.merge_today(&mut today).unwrap();
+        today.update_totals();
+        assert_eq!(
+            today.other_minutes,
+            BTreeMap::from([("sermons".to_string(), 30.0)])
+        );
+        assert_eq!(today.total_minutes, 40.0);
+    }
+
+    #[test]
+    fn test_merge_daily_adds_other_minutes() {
+        let mut days: Vec<FaithDayStats> = dates().into_iter().map(FaithDayStats::new).collect();
+        Sermons.merge_daily(&mut days).unwrap();
+        WeeklyOnly.merge_daily(&mut days).unwrap();
+        assert_eq!(
+            days[0].other_minutes,
+            BTreeMap::from([("sermons".to_string(), 45.0)])
+        );
+        assert_eq!(days[1].other_minutes["sermons"], 0.0);
+        assert_eq!(days[0].total_minutes(), 45.0);
+
+        let mut days = vec![FaithDayStats::new("2025-06-03".to_string())];
+        let err = Sermons.merge_daily(&mut days).unwrap_err();
+        assert!(format!("{:#}", err).starts_with(&format!(
+            "sermons results cover different buckets than the other sources ({}): ",
+            PeriodConfig::default()
+        )));
+    }
+
+    #[test]
+    fn test_daily_minutes_for_period() {
+        let day = |d| NaiveDate::from_ymd_opt(2025, 6, d).unwrap();
+        let minutes = DailyMinutes(BTreeMap::from([
+            (day(1), 10.0),
+            (day(2), 0.0),
+            (day(3), 5.0),
+            (day(9), 20.0),
+        ]));
+        let period = DatePeriod {
+            dates: vec!["2025-06-01".to_string(), "2025-06-02".to_string()],
+            start_ms: 0,
+            end_ms: 0,
+            granularity: statsutils::Granularity::Day,
+        };
+        assert_eq!(minutes.for_period(&period).into_values(), vec![10.0, 0.0]);
+        assert_eq!(minutes.active_dates(), vec![day(1), day(3), day(9)]);
+    }
+
+    #[test]
+    fn test_collect_minutes_rejects_mismatched_dates() {
+        let err = collect_minutes(vec!["2025-06-03".to_string()], &[&Sermons], |s| {
+            s.daily_minutes()
+        })
+        .unwrap_err();
+        assert_eq!(
+            format!("{:#}", err),
+            "sermons results cover different buckets than the other sources: \
+             Can't join period results: missing 2025-06-03; unexpected 2025-06-01, 2025-06-02"
+        );
+    }
+}
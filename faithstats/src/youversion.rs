@@ -0,0 +1,241 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use serde::Deserialize;
+use statsutils::{DatePeriod, PeriodConfig, PeriodSeries};
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::path::PathBuf;
+
+use crate::source::{DailyMinutes, FaithDataSource};
+
+/// Minutes credited for each completed plan day, since the app doesn't record reading time
+pub const DEFAULT_MINUTES_PER_PLAN_DAY: f64 = 10.0;
+
+/// A YouVersion (Bible App) data export, as JSON
+///
+/// ```json
+/// {
+///   "reading_plans": [
+///     {
+///       "title": "Bible in One Year",
+///       "completed_days": [
+///         { "day": 1, "completed_at": "2025-06-10T07:12:00-05:00" },
+///         { "day": 2, "completed_at": "2025-06-11 06:50" },
+///         { "day": 3, "completed_at": "2025-06-12" }
+///       ]
+///     }
+///   ]
+/// }
+/// ```
+///
+/// Times are local unless they carry a UTC offset (RFC 3339); a bare date counts on that
+/// day. Other fields in the export are ignored.
+#[derive(Debug, Deserialize)]
+struct Export {
+    #[serde(default)]
+    reading_plans: Vec<Plan>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Plan {
+    title: String,
+    #[serde(default)]
+    completed_days: Vec<CompletedDay>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CompletedDay {
+    day: u32,
+    completed_at: String,
+}
+
+/// A reading-plan day marked complete in the app
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlanDay {
+    /// Title of the reading plan
+    pub plan: String,
+    /// Day number within the plan
+    pub day: u32,
+    /// Logical date it was completed on
+    pub date: NaiveDate,
+}
+
+/// Loads every completed reading-plan day from a YouVersion export
+///
+/// # Arguments
+/// * `path` - Path to the export's JSON file
+pub fn load_plan_days(path: &str) -> Result<Vec<PlanDay>> {
+    let file = std::fs::File::open(path)
+        .context(format!("Failed to open YouVersion export '{}'", path))?;
+    parse_export(file, &PeriodConfig::default())
+        .context(format!("Failed to read YouVersion export '{}'", path))
+}
+
+/// Counts the completed plan days on each logical date
+pub fn plan_days_by_date(days: &[PlanDay]) -> BTreeMap<NaiveDate, i64> {
+    let mut counts = BTreeMap::new();
+    for day in days {
+        *counts.entry(day.date).or_default() += 1;
+    }
+    counts
+}
+
+fn parse_export(reader: impl Read, config: &PeriodConfig) -> Result<Vec<PlanDay>> {
+    let export: Export = serde_json::from_reader(reader)?;
+
+    let mut days = Vec::new();
+    for plan in export.reading_plans {
+        for completed in plan.completed_days {
+            let date = parse_completion_date(&completed.completed_at, config).context(format!(
+                "Invalid completed_at for day {} of '{}'",
+                completed.day, plan.title
+            ))?;
+            days.push(PlanDay {
+                plan: plan.title.clone(),
+                day: completed.day,
+                date,
+            });
+        }
+    }
+    Ok(days)
+}
+
+/// Logical date of a completion time, so plans finished before the rollover hour count
+/// toward the previous day
+fn parse_completion_date(value: &str, config: &PeriodConfig) -> Result<NaiveDate> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return config.logical_date(time.timestamp_millis());
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Ok(date);
+    }
+
+    let local = [
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%d %H:%M",
+        "%Y-%m-%dT%H:%M:%S",
+        "%Y-%m-%dT%H:%M",
+    ]
+    .iter()
+    .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+    .context(format!(
+        "'{}' isn't a time like 2025-06-10 07:00, 2025-06-10T07:00:00-05:00, or 2025-06-10",
+        value
+    ))?;
+    config.logical_date(config.timezone.timestamp_ms(local)?)
+}
+
+/// Bible reading plans, from a YouVersion export
+///
+/// Each completed plan day counts as `minutes_per_day` of reading, supplementing (or, for
+/// those who read on their phone, standing in for) KOReader reading time.
+pub struct YouVersionSource {
+    pub export_path: String,
+    pub minutes_per_day: f64,
+}
+
+impl YouVersionSource {
+    fn daily(&self) -> Result<DailyMinutes> {
+        let days = load_plan_days(&self.export_path)?;
+        Ok(DailyMinutes(
+            plan_days_by_date(&days)
+                .into_iter()
+                .map(|(date, count)| (date, count as f64 * self.minutes_per_day))
+                .collect(),
+        ))
+    }
+}
+
+impl FaithDataSource for YouVersionSource {
+    fn name(&self) -> &str {
+        "reading_plans"
+    }
+
+    fn today_minutes(&self) -> Result<Option<f64>> {
+        Ok(Some(self.daily()?.today()?))
+    }
+
+    fn daily_minutes(&self) -> Result<Option<PeriodSeries<f64>>> {
+        Ok(Some(self.daily()?.for_period(&DatePeriod::last_30_days()?)))
+    }
+
+    fn weekly_minutes(&self) -> Result<Option<PeriodSeries<f64>>> {
+        Ok(Some(
+            self.daily()?.for_period(&DatePeriod::last_12_weeks()?),
+        ))
+    }
+
+    fn period_minutes(&self, period: &DatePeriod) -> Result<Option<PeriodSeries<f64>>> {
+        Ok(Some(self.daily()?.for_period(period)))
+    }
+
+    fn active_dates(&self) -> Result<Option<Vec<NaiveDate>>> {
+        Ok(Some(self.daily()?.active_dates()))
+    }
+
+    fn paths(&self) -> Vec<PathBuf> {
+        vec![PathBuf::from(&self.export_path)]
+    }
+
+    fn schema(&self) -> Result<Option<String>> {
+        let days = load_plan_days(&self.export_path)?;
+        let mut plans: Vec<&str> = days.iter().map(|d| d.plan.as_str()).collect();
+        plans.sort();
+        plans.dedup();
+        Ok(Some(format!("YouVersion export ({} plans)", plans.len())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::FixedOffset;
+    use statsutils::StatsTimezone;
+
+    fn config() -> PeriodConfig {
+        PeriodConfig::new(
+            StatsTimezone::Fixed(FixedOffset::west_opt(5 * 3600).unwrap()),
+            4,
+        )
+    }
+
+    fn date(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 6, d).unwrap()
+    }
+
+    #[test]
+    fn test_parse_export() {
+        let export = r#"{
+            "user": {"name": "ignored"},
+            "reading_plans": [
+                {
+                    "title": "Bible in One Year",
+                    "completed_days": [
+                        {"day": 1, "completed_at": "2025-06-10T07:12:00-05:00"},
+                        {"day": 2, "completed_at": "2025-06-11 02:30"},
+                        {"day": 3, "completed_at": "2025-06-12"}
+                    ]
+                },
+                {"title": "Psalms", "completed_days": [{"day": 1, "completed_at": "2025-06-12 21:00"}]},
+                {"title": "Not started"}
+            ]
+        }"#;
+        let days = parse_export(export.as_bytes(), &config()).unwrap();
+        assert_eq!(days.len(), 4);
+        assert_eq!(days[0].plan, "Bible in One Year");
+        // Before the 4 AM rollover, so it counts toward the previous day
+        assert_eq!(days[1].date, date(10));
+        assert_eq!(
+            plan_days_by_date(&days),
+            BTreeMap::from([(date(10), 2), (date(12), 2)])
+        );
+    }
+
+    #[test]
+    fn test_parse_export_rejects_bad_times() {
+        let export = r#"{"reading_plans": [{"title": "Psalms",
+            "completed_days": [{"day": 4, "completed_at": "yesterday"}]}]}"#;
+        let err = parse_export(export.as_bytes(), &config()).unwrap_err();
+        assert!(err.to_string().contains("day 4 of 'Psalms'"));
+    }
+}
@@ -0,0 +1,91 @@
+use chrono::Duration;
+use faithstats::config::FaithConfig;
+use faithstats::score::{ScoreWeights, SourceWeight};
+use faithstats::{get_faith_daily_stats, get_faith_weekly_stats};
+use statsutils::testing::{AnkiFixture, KoreaderFixture, ProseucheFixture};
+use statsutils::{DatePeriod, PeriodConfig};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Builds every built-in source in `dir`, with yesterday's activity at 11:30 PM
+/// workspace time, and an Anki collection ten hours ahead of UTC with a midnight rollover
+/// (so for Anki the review falls on today)
+///
+/// Returns a config reading them.
+fn build_sources(dir: &Path) -> FaithConfig {
+    let config = PeriodConfig::default();
+    let yesterday = config.today().unwrap() - Duration::days(1);
+    let at_ms = config.day_start_ms(yesterday).unwrap() + (19 * 60 + 30) * 60_000;
+
+    let anki_path = dir.join("collection.anki2");
+    let mut anki = AnkiFixture::create(&anki_path, at_ms / 1000 - 400 * 86_400).unwrap();
+    anki.set_day_boundaries(0, -600).unwrap();
+    let deck = anki.deck("Bible::Verses").unwrap();
+    let model = anki.notetype("Bible Verse").unwrap();
+    let note = anki.note(model, "John 3:16", "").unwrap();
+    let card = anki.review_card(note, deck, 0, 30, 410).unwrap();
+    anki.review(card, at_ms, 300_000, 3, 30).unwrap();
+
+    let koreader_path = dir.join("statistics.sqlite3");
+    let mut koreader = KoreaderFixture::create(&koreader_path).unwrap();
+    let bible = koreader.book("ESV Study Bible", "Crossway", 2752).unwrap();
+    koreader.page_read(bible, 100, at_ms, 600).unwrap();
+
+    let proseuche_path = dir.join("database.sqlite");
+    let mut proseuche = ProseucheFixture::create(&proseuche_path).unwrap();
+    proseuche.session(None, at_ms, 15.0, &[]).unwrap();
+
+    // An export with no visits
+    let arc_path = dir.join("arc");
+    std::fs::create_dir_all(arc_path.join("items")).unwrap();
+    std::fs::create_dir_all(arc_path.join("places")).unwrap();
+
+    FaithConfig::parse(&format!(
+        "[sources]\nanki = {:?}\nkoreader = {:?}\nproseuche = {:?}\narc = {:?}\n",
+        anki_path, koreader_path, proseuche_path, arc_path
+    ))
+    .unwrap()
+}
+
+/// The sources line up even though the Anki collection has its own day boundaries
+#[test]
+fn test_faith_stats_with_anki_boundaries_differing_from_workspace() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = build_sources(dir.path());
+
+    let stats = get_faith_daily_stats(&config).unwrap();
+    let dates: Vec<String> = stats.days.iter().map(|d| d.date.clone()).collect();
+    assert_eq!(dates, DatePeriod::last_30_days().unwrap().dates);
+    let yesterday = &stats.days[28];
+    assert_eq!(yesterday.anki_minutes, 5.0);
+    assert_eq!(yesterday.reading_minutes, 10.0);
+    // Proseuche durations come from julianday arithmetic
+    assert!((yesterday.prayer_minutes - 15.0).abs() < 0.01);
+    assert_eq!(stats.days[29].anki_minutes, 0.0);
+
+    let stats = get_faith_weekly_stats(&config).unwrap();
+    let weeks: Vec<String> = stats.weeks.iter().map(|w| w.week_start.clone()).collect();
+    assert_eq!(weeks, DatePeriod::last_12_weeks().unwrap().dates);
+    let anki_minutes: f64 = stats.weeks.iter().map(|w| w.anki_minutes).sum();
+    assert_eq!(anki_minutes, 5.0);
+}
+
+/// Days are scored with the config's weights rather than the defaults
+#[test]
+fn test_faith_daily_stats_use_configured_score_weights() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut config = build_sources(dir.path());
+    config.score = ScoreWeights(BTreeMap::from([(
+        "prayer".to_string(),
+        SourceWeight {
+            weight: 1.0,
+            half_minutes: 15.0,
+        },
+    )]));
+
+    let stats = get_faith_daily_stats(&config).unwrap();
+    // Half credit for 15 minutes of prayer, and nothing for Anki or reading
+    assert!((stats.days[28].score - 50.0).abs() < 0.01);
+    assert_eq!(stats.days[29].score, 0.0);
+    assert!((stats.summary.average_score - 50.0 / 30.0).abs() < 0.01);
+}
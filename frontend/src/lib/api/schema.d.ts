@@ -296,7 +296,7 @@ export interface components {
              * @description Anki study time in minutes
              */
             anki_minutes: number;
-            /** @description Daily church attendance breakdown: [Sun, Mon, Tue, Wed, Thu, Fri, Sat] */
+            /** @description Daily church attendance breakdown, one entry per day from the configured week start */
             at_church_daily_minutes: number[];
             /**
              * Format: double
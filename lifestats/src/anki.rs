@@ -0,0 +1,57 @@
+use crate::faith::OutputFormat;
+use crate::output::{print_series, print_today, unwrap_or_exit};
+use clap::Subcommand;
+use faithstats::config::FaithConfig;
+
+#[derive(Subcommand)]
+pub enum Commands {
+    /// Show study time for today
+    Today,
+    /// Show study time for each of the last 30 days
+    Daily {
+        /// Draw the days as a bar chart
+        #[arg(long)]
+        chart: bool,
+    },
+    /// Show study time for each of the last 12 weeks
+    Weekly {
+        /// Draw the weeks as a bar chart
+        #[arg(long)]
+        chart: bool,
+    },
+    /// Show study time for each of the last 12 months
+    Monthly,
+}
+
+pub fn run(command: Commands, config: &FaithConfig, format: OutputFormat) {
+    let db_path = config.sources.anki.as_str();
+    match command {
+        Commands::Today => {
+            let minutes = unwrap_or_exit(ankistats::get_today_study_time(db_path));
+            print_today("Anki", minutes, format);
+        }
+        Commands::Daily { chart } => {
+            let stats = unwrap_or_exit(ankistats::get_last_30_days_stats(db_path));
+            print_series("ANKI - LAST 30 DAYS", &stats, "day", format, chart, |d| {
+                (&d.date, d.minutes)
+            });
+        }
+        Commands::Weekly { chart } => {
+            let stats = unwrap_or_exit(ankistats::get_last_12_weeks_stats(db_path));
+            print_series("ANKI - LAST 12 WEEKS", &stats, "week", format, chart, |w| {
+                (&w.week_start, w.minutes)
+            });
+        }
+        Commands::Monthly => {
+            let stats = unwrap_or_exit(ankistats::get_last_12_months_stats(db_path));
+            print_series(
+                "ANKI - LAST 12 MONTHS",
+                &stats,
+                "month",
+                format,
+                false,
+                |m| (&m.month, m.minutes),
+            );
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use crate::faith::{OutputFormat, print_json};
+use crate::output::{print_series, print_today, unwrap_or_exit};
+use clap::Subcommand;
+use faithstats::config::FaithConfig;
+
+#[derive(Subcommand)]
+pub enum Commands {
+    /// Show the places you spent the most time at over the last 6 months
+    TopPlaces {
+        /// Number of places to show
+        #[arg(long, default_value_t = 10)]
+        limit: usize,
+    },
+    /// Show time at church today
+    Today,
+    /// Show time at church for each of the last 30 days
+    Daily {
+        /// Draw the days as a bar chart
+        #[arg(long)]
+        chart: bool,
+    },
+    /// Show time at church for each of the last 12 weeks
+    Weekly {
+        /// Draw the weeks as a bar chart
+        #[arg(long)]
+        chart: bool,
+    },
+}
+
+pub fn run(command: Commands, config: &FaithConfig, format: OutputFormat) {
+    let export_path = unwrap_or_exit(config.arc_path());
+    match command {
+        Commands::TopPlaces { limit } => {
+            let places = unwrap_or_exit(arcstats::stats::get_top_places_last_6_months(
+                export_path,
+                limit,
+            ));
+            if format == OutputFormat::Json {
+                unwrap_or_exit(print_json(&places));
+                return;
+            }
+
+            println!("\n=== TOP PLACES - LAST 6 MONTHS ===\n");
+            for (rank, place) in places.iter().enumerate() {
+                println!(
+                    "{:>2}. {}: {:.1} hours",
+                    rank + 1,
+                    place.place_name,
+                    place.hours
+                );
+            }
+            println!();
+        }
+        Commands::Today => {
+            let minutes = unwrap_or_exit(arcstats::stats::get_today_church_time(export_path));
+            print_today("Church", minutes, format);
+        }
+        Commands::Daily { chart } => {
+            let stats = unwrap_or_exit(arcstats::stats::get_last_30_days_stats(export_path));
+            print_series("CHURCH - LAST 30 DAYS", &stats, "day", format, chart, |d| {
+                (&d.date, d.minutes)
+            });
+        }
+        Commands::Weekly { chart } => {
+            let stats = unwrap_or_exit(arcstats::stats::get_last_12_weeks_stats(export_path));
+            print_series(
+                "CHURCH - LAST 12 WEEKS",
+                &stats,
+                "week",
+                format,
+                chart,
+                |w| (&w.week_start, w.minutes),
+            );
+        }
+    }
+}
@@ -0,0 +1,777 @@
+//! `lifestats faith` and `lifestats report`: combined faith stats from every source
+//!
+//! Also compiled into faithstats' `faith-stats` binary, so it only uses this crate's
+//! dependencies that faithstats shares.
+
+use anyhow::{Context, Result, bail};
+use clap::{Subcommand, ValueEnum};
+use faithstats::config::{CONFIG_FILE, FaithConfig};
+use faithstats::goals::{FaithGoalProgress, evaluate_faith_goals};
+use faithstats::models::{
+    FaithDayStatsDisplay, FaithPeriodStatsDisplay, FaithWeekStatsDisplay, SundayComparison,
+};
+use faithstats::notify::{self, SentEvents};
+use faithstats::{
+    export, get_faith_daily_stats, get_faith_monthly_stats, get_faith_today_stats,
+    get_faith_weekly_stats, get_source_status, get_sunday_stats, report,
+};
+use readingstats::config::{self, BibleBookMatcher};
+use serde::Serialize;
+use statsutils::chart;
+use statsutils::fmt::duration_minutes;
+use statsutils::{PeriodConfig, parse_local_date};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tabled::{Table, settings::Style};
+
+/// How command results are printed
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    Text,
+    Json,
+}
+
+/// The combined faith subcommands, run as `lifestats faith` or `faith-stats`
+#[derive(Subcommand)]
+pub enum Commands {
+    /// Show today's faith time from each source
+    Today,
+    /// Show faith statistics for each of the last 30 days
+    Daily {
+        /// Add a sparkline of each source's minutes under the table
+        #[arg(long)]
+        chart: bool,
+    },
+    /// Show faith statistics for each of the last 12 weeks
+    Weekly {
+        /// Add a sparkline of each source's minutes under the table
+        #[arg(long)]
+        chart: bool,
+    },
+    /// Show faith time for each of the last 12 months
+    Monthly,
+    /// Compare Sundays with the rest of the week
+    Sunday {
+        /// Number of weeks to look back over, ending today
+        #[arg(long, default_value_t = 12)]
+        weeks: u32,
+    },
+    /// Show progress on the goals in the config file
+    Goals,
+    /// Check each source's files, format, and last day with data
+    Status,
+    /// Write a Markdown weekly review (totals, days, streaks, milestones, goals)
+    Report {
+        /// Any day of the week to review (YYYY-MM-DD); defaults to this week
+        #[arg(long)]
+        week: Option<String>,
+        /// File to write the report to; defaults to faith-review-<week start>.md
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+    /// Write the daily or weekly series as CSV for charting in other tools, or with
+    /// --format json as Grafana JSON datasource time series
+    Export {
+        /// Which series to export
+        #[arg(long, value_enum, default_value = "daily")]
+        series: ExportSeries,
+        /// File to write the CSV or JSON to; defaults to stdout
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+    /// Post goal, streak, memorization, and prayer events to the config's [notify]
+    /// endpoint; meant to run from cron, sending each event once
+    Notify {
+        /// File remembering which events were already sent
+        #[arg(long, default_value = "faith-notify-state.json")]
+        state: PathBuf,
+        /// Print the events instead of sending them
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportSeries {
+    /// One row per day for the last 30 days
+    Daily,
+    /// One row per week for the last 12 weeks
+    Weekly,
+}
+
+/// Applies the day boundary and Bible book overrides from environment variables (and a
+/// .env file)
+pub fn configure_from_env() -> Result<()> {
+    // Load environment variables from .env file if present
+    let _ = dotenvy::dotenv();
+
+    // Optionally override the timezone, rollover hour, and week start used for every source
+    statsutils::configure_from_env()?;
+
+    // Optionally override which KOReader books count as Bible reading
+    let bible_titles = std::env::var("KOREADER_BIBLE_TITLES").ok();
+    let bible_md5s = std::env::var("KOREADER_BIBLE_MD5S").ok();
+    if bible_titles.is_some() || bible_md5s.is_some() {
+        config::set_bible_books(BibleBookMatcher::from_comma_lists(
+            bible_titles.as_deref(),
+            bible_md5s.as_deref(),
+        ));
+    }
+    Ok(())
+}
+
+/// Runs a subcommand, printing its results
+pub fn run(command: Commands, config: &FaithConfig, format: OutputFormat) -> Result<()> {
+    match command {
+        Commands::Today => run_today_command(config, format),
+        Commands::Daily { chart } => run_daily_command(config, chart, format),
+        Commands::Weekly { chart } => run_weekly_command(config, chart, format),
+        Commands::Monthly => run_monthly_command(config, format),
+        Commands::Sunday { weeks } => run_sunday_command(config, weeks, format),
+        Commands::Goals => run_goals_command(config, format),
+        Commands::Status => run_status_command(config, format),
+        Commands::Report { week, output } => run_report_command(config, week.as_deref(), output),
+        Commands::Export { series, output } => {
+            run_export_command(config, series, output.as_deref(), format)
+        }
+        Commands::Notify { state, dry_run } => run_notify_command(config, &state, dry_run, format),
+    }
+}
+
+/// Prints a value as pretty JSON
+pub fn print_json<T: Serialize>(value: &T) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+/// Reads the source paths from the config file, or from environment variables when there
+/// isn't one, and applies the file's day boundaries and place names
+pub fn load_config(path: Option<&Path>) -> Result<FaithConfig> {
+    let path = path.or_else(|| Some(Path::new(CONFIG_FILE)).filter(|p| p.exists()));
+
+    match path {
+        Some(path) => {
+            let config = FaithConfig::from_file(path)?;
+            config.apply()?;
+            Ok(config)
+        }
+        None => FaithConfig::from_env().inspect_err(|_| {
+            eprintln!(
+                "Set the paths in {}, a .env file, or your shell environment",
+                CONFIG_FILE
+            );
+        }),
+    }
+}
+
+fn run_today_command(config: &FaithConfig, format: OutputFormat) -> Result<()> {
+    let stats = get_faith_today_stats(config)?;
+    if format == OutputFormat::Json {
+        return print_json(&stats);
+    }
+
+    println!("\n=== TODAY'S FAITH TIME ===\n");
+    println!("Anki:    {}", duration_minutes(stats.anki_minutes));
+    println!("Reading: {}", duration_minutes(stats.reading_minutes));
+    println!("Church:  {}", duration_minutes(stats.at_church_minutes));
+    println!("Prayer:  {}", duration_minutes(stats.prayer_minutes));
+    for (source, minutes) in &stats.other_minutes {
+        println!("{}: {}", source, duration_minutes(*minutes));
+    }
+    println!(
+        "\nTotal: {} ({:.2} min)",
+        duration_minutes(stats.total_minutes),
+        stats.total_minutes
+    );
+    Ok(())
+}
+
+fn run_daily_command(config: &FaithConfig, chart: bool, format: OutputFormat) -> Result<()> {
+    let stats = get_faith_daily_stats(config)?;
+    if format == OutputFormat::Json {
+        return print_json(&stats);
+    }
+
+    println!("\n=== FAITH STATS - LAST 30 DAYS ===\n");
+
+    // Convert to display format and create table
+    let display_stats: Vec<FaithDayStatsDisplay> = stats.days.iter().map(|s| s.into()).collect();
+    let table = Table::new(display_stats).with(Style::rounded()).to_string();
+    println!("{}", table);
+
+    if chart {
+        print_trends(stats.days.iter().map(|d| {
+            [
+                d.anki_minutes,
+                d.reading_minutes,
+                d.at_church_minutes,
+                d.prayer_minutes,
+                d.total_minutes(),
+            ]
+        }));
+    }
+
+    // Print summary statistics
+    println!("\n=== SUMMARY ===\n");
+
+    println!("ANKI MEMORIZATION:");
+    println!(
+        "  Total: {} ({:.2} min)",
+        duration_minutes(stats.summary.anki_total_minutes),
+        stats.summary.anki_total_minutes
+    );
+    println!(
+        "  Average: {:.2} min/day",
+        stats.summary.anki_average_minutes_per_day
+    );
+    println!(
+        "  Days studied: {} / {}",
+        stats.summary.anki_days_studied, stats.summary.total_days
+    );
+    println!(
+        "  Passages: +{} matured, -{} lost (net: {:+})",
+        stats.summary.anki_total_matured_passages,
+        stats.summary.anki_total_lost_passages,
+        stats.summary.anki_net_progress
+    );
+
+    println!("\nBIBLE READING:");
+    println!(
+        "  Total: {} ({:.2} min)",
+        duration_minutes(stats.summary.reading_total_minutes),
+        stats.summary.reading_total_minutes
+    );
+    println!(
+        "  Average: {:.2} min/day",
+        stats.summary.reading_average_minutes_per_day
+    );
+    println!(
+        "  Days read: {} / {}",
+        stats.summary.reading_days_studied, stats.summary.total_days
+    );
+    println!(
+        "  Pages: {} ({:.0} pages/hr)",
+        stats.summary.reading_total_pages, stats.summary.reading_pages_per_hour
+    );
+    println!(
+        "  Streak ({} min+): {} days (longest {})",
+        stats.summary.reading_streak_min_minutes,
+        stats.summary.reading_current_streak,
+        stats.summary.reading_longest_streak
+    );
+    println!(
+        "  Sessions: longest {:.1} min, median {:.1} min",
+        stats.summary.reading_longest_session_minutes, stats.summary.reading_median_session_minutes
+    );
+    if stats.summary.reading_plan_days_completed > 0 {
+        println!(
+            "  Reading plan days: {}",
+            stats.summary.reading_plan_days_completed
+        );
+    }
+
+    println!("\nOTHER READING (not counted below):");
+    println!(
+        "  Total: {:.2} min ({:.2} min/day)",
+        stats.summary.other_reading_total_minutes,
+        stats.summary.other_reading_average_minutes_per_day
+    );
+
+    if stats.summary.journal_days > 0 {
+        println!("\nJOURNALING:");
+        println!(
+            "  Days journaled: {} / {}",
+            stats.summary.journal_days, stats.summary.total_days
+        );
+        println!("  Words: {}", stats.summary.journal_total_words);
+    }
+
+    if stats.summary.church_total_minutes > 0.0 {
+        println!("\nCHURCH ATTENDANCE:");
+        println!(
+            "  Total: {} ({:.2} min)",
+            duration_minutes(stats.summary.church_total_minutes),
+            stats.summary.church_total_minutes
+        );
+        println!(
+            "  Days attended: {} / {}",
+            stats.summary.church_days_attended, stats.summary.total_days
+        );
+    }
+
+    if stats.summary.prayer_total_minutes > 0.0 {
+        println!("\nPRAYER:");
+        println!(
+            "  Total: {} ({:.2} min)",
+            duration_minutes(stats.summary.prayer_total_minutes),
+            stats.summary.prayer_total_minutes
+        );
+        println!(
+            "  Average: {:.2} min/day",
+            stats.summary.prayer_average_minutes_per_day
+        );
+        println!(
+            "  Days prayed: {} / {}",
+            stats.summary.prayer_days_studied, stats.summary.total_days
+        );
+    }
+
+    println!("\nCOMBINED TOTAL:");
+    println!(
+        "  Total: {} ({:.2} min)",
+        duration_minutes(stats.summary.total_minutes),
+        stats.summary.total_minutes
+    );
+    println!(
+        "  Average: {:.2} min/day",
+        stats.summary.average_minutes_per_day
+    );
+    println!(
+        "  Days with any activity: {} / {}",
+        stats.summary.days_with_any_activity, stats.summary.total_days
+    );
+    if let Some(today) = stats.days.last() {
+        println!(
+            "  Score: {:.0} average, {:.0} over the last {} days",
+            stats.summary.average_score,
+            today.rolling_score,
+            faithstats::score::ROLLING_DAYS
+        );
+    }
+
+    let streaks = &stats.summary.streaks;
+    println!("\nSTREAKS:");
+    println!(
+        "  Any activity: {} days (longest {})",
+        streaks.any_activity.current_streak, streaks.any_activity.longest_streak
+    );
+    for (source, streak) in &streaks.by_source {
+        println!(
+            "  {}: {} days (longest {})",
+            source, streak.current_streak, streak.longest_streak
+        );
+    }
+
+    print_goals(&stats.summary.goals);
+
+    println!();
+    Ok(())
+}
+
+fn run_weekly_command(config: &FaithConfig, chart: bool, format: OutputFormat) -> Result<()> {
+    let stats = get_faith_weekly_stats(config)?;
+    if format == OutputFormat::Json {
+        return print_json(&stats);
+    }
+
+    println!("\n=== FAITH STATS - LAST 12 WEEKS ===\n");
+
+    // Convert to display format and create table
+    let display_stats: Vec<FaithWeekStatsDisplay> = stats.weeks.iter().map(|s| s.into()).collect();
+    let table = Table::new(display_stats).with(Style::rounded()).to_string();
+    println!("{}", table);
+
+    if chart {
+        print_trends(stats.weeks.iter().map(|w| {
+            [
+                w.anki_minutes,
+                w.reading_minutes,
+                w.at_church_minutes,
+                w.prayer_minutes,
+                w.total_minutes(),
+            ]
+        }));
+    }
+
+    // Print summary statistics
+    println!("\n=== SUMMARY ===\n");
+
+    println!("ANKI MEMORIZATION:");
+    println!(
+        "  Total: {} ({:.2} min)",
+        duration_minutes(stats.summary.anki_total_minutes),
+        stats.summary.anki_total_minutes
+    );
+    println!(
+        "  Average: {:.2} min/week",
+        stats.summary.anki_average_minutes_per_week
+    );
+    println!(
+        "  Weeks studied: {} / {}",
+        stats.summary.anki_weeks_studied, stats.summary.total_weeks
+    );
+    println!(
+        "  Passages: +{} matured, -{} lost (net: {:+})",
+        stats.summary.anki_total_matured_passages,
+        stats.summary.anki_total_lost_passages,
+        stats.summary.anki_net_progress
+    );
+
+    println!("\nBIBLE READING:");
+    println!(
+        "  Total: {} ({:.2} min)",
+        duration_minutes(stats.summary.reading_total_minutes),
+        stats.summary.reading_total_minutes
+    );
+    println!(
+        "  Average: {:.2} min/week",
+        stats.summary.reading_average_minutes_per_week
+    );
+    println!(
+        "  Weeks read: {} / {}",
+        stats.summary.reading_weeks_studied, stats.summary.total_weeks
+    );
+    println!(
+        "  Pages: {} ({:.0} pages/hr)",
+        stats.summary.reading_total_pages, stats.summary.reading_pages_per_hour
+    );
+
+    println!("\nOTHER READING (not counted below):");
+    println!(
+        "  Total: {:.2} min ({:.2} min/week)",
+        stats.summary.other_reading_total_minutes,
+        stats.summary.other_reading_average_minutes_per_week
+    );
+
+    println!("\nCHURCH ATTENDANCE:");
+    println!(
+        "  Total: {} ({:.2} min)",
+        duration_minutes(stats.summary.church_total_minutes),
+        stats.summary.church_total_minutes
+    );
+    println!(
+        "  Average: {:.2} min/week",
+        stats.summary.church_average_minutes_per_week
+    );
+    println!(
+        "  Weeks attended: {} / {}",
+        stats.summary.church_weeks_attended, stats.summary.total_weeks
+    );
+    // Only worth breaking down when more than one kind of attendance is configured
+    if stats.summary.church_category_total_minutes.len() > 1 {
+        for (category, minutes) in &stats.summary.church_category_total_minutes {
+            println!("  {}: {}", category, duration_minutes(*minutes));
+        }
+    }
+
+    if stats.summary.prayer_total_minutes > 0.0 {
+        println!("\nPRAYER:");
+        println!(
+            "  Total: {} ({:.2} min)",
+            duration_minutes(stats.summary.prayer_total_minutes),
+            stats.summary.prayer_total_minutes
+        );
+        println!(
+            "  Average: {:.2} min/week",
+            stats.summary.prayer_average_minutes_per_week
+        );
+        println!(
+            "  Weeks prayed: {} / {}",
+            stats.summary.prayer_weeks_studied, stats.summary.total_weeks
+        );
+    }
+
+    let fasting_days: i64 = stats.weeks.iter().map(|w| w.fasting_days).sum();
+    if fasting_days > 0 {
+        println!("\nFASTING:");
+        println!("  Days fasted: {}", fasting_days);
+        println!(
+            "  Weeks with a fast: {} / {}",
+            stats.weeks.iter().filter(|w| w.fasting_days > 0).count(),
+            stats.summary.total_weeks
+        );
+    }
+
+    println!("\nCOMBINED TOTAL:");
+    println!(
+        "  Total: {} ({:.2} min)",
+        duration_minutes(stats.summary.total_minutes),
+        stats.summary.total_minutes
+    );
+    println!(
+        "  Average: {:.2} min/week",
+        stats.summary.average_minutes_per_week
+    );
+    println!(
+        "  Weeks with any activity: {} / {}",
+        stats.summary.weeks_with_any_activity, stats.summary.total_weeks
+    );
+
+    print_goals(&stats.summary.goals);
+
+    println!();
+    Ok(())
+}
+
+/// Prints one sparkline per source, from rows of [anki, reading, church, prayer, total]
+/// minutes, each scaled to its own busiest day or week
+fn print_trends(rows: impl Iterator<Item = [f64; 5]>) {
+    let rows: Vec<[f64; 5]> = rows.collect();
+    println!("\n=== TRENDS ===\n");
+    for (i, label) in ["Anki", "Reading", "Church", "Prayer", "Total"]
+        .iter()
+        .enumerate()
+    {
+        let minutes: Vec<f64> = rows.iter().map(|row| row[i]).collect();
+        let peak = minutes.iter().copied().fold(0.0, f64::max);
+        println!(
+            "{:<8} │{}│ peak {}",
+            label,
+            chart::sparkline(&minutes),
+            duration_minutes(peak)
+        );
+    }
+}
+
+fn run_sunday_command(config: &FaithConfig, weeks: u32, format: OutputFormat) -> Result<()> {
+    let stats = get_sunday_stats(config, weeks)?;
+    if format == OutputFormat::Json {
+        return print_json(&stats);
+    }
+
+    println!(
+        "\n=== SUNDAYS - {} TO {} ({} SUNDAYS) ===\n",
+        stats.start_date, stats.end_date, stats.sundays
+    );
+    println!(
+        "{:<16} {:>10} {:>10} {:>8}",
+        "", "Sunday", "Weekday", "Active"
+    );
+    let mut rows: Vec<(&str, &SundayComparison)> = stats
+        .by_source
+        .iter()
+        .map(|(source, comparison)| (source.as_str(), comparison))
+        .collect();
+    rows.push(("Total", &stats.total));
+    if let Some(home) = &stats.home {
+        rows.push(("Home", home));
+    }
+    for (name, comparison) in rows {
+        println!(
+            "{:<16} {:>10} {:>10} {:>4} / {}",
+            name,
+            duration_minutes(comparison.sunday_average_minutes),
+            duration_minutes(comparison.weekday_average_minutes),
+            comparison.sundays_active,
+            stats.sundays
+        );
+    }
+    println!();
+    Ok(())
+}
+
+fn run_monthly_command(config: &FaithConfig, format: OutputFormat) -> Result<()> {
+    let stats = get_faith_monthly_stats(config)?;
+    if format == OutputFormat::Json {
+        return print_json(&stats);
+    }
+
+    println!("\n=== FAITH STATS - LAST 12 MONTHS ===\n");
+
+    let display_stats: Vec<FaithPeriodStatsDisplay> =
+        stats.periods.iter().map(|s| s.into()).collect();
+    let table = Table::new(display_stats).with(Style::rounded()).to_string();
+    println!("{}", table);
+
+    println!("\n=== SUMMARY ===\n");
+    for (source, minutes) in &stats.summary.source_total_minutes {
+        println!("  {}: {}", source, duration_minutes(*minutes));
+    }
+    println!(
+        "  Total: {} ({:.2} min)",
+        duration_minutes(stats.summary.total_minutes),
+        stats.summary.total_minutes
+    );
+    println!(
+        "  Average: {:.2} min/month",
+        stats.summary.average_minutes_per_period
+    );
+    println!(
+        "  Months with any activity: {} / {}",
+        stats.summary.periods_with_any_activity, stats.summary.total_periods
+    );
+
+    println!();
+    Ok(())
+}
+
+fn run_goals_command(config: &FaithConfig, format: OutputFormat) -> Result<()> {
+    let goals = evaluate_faith_goals(config)?;
+    if format == OutputFormat::Json {
+        return print_json(&goals);
+    }
+
+    if goals.is_empty() {
+        println!(
+            "No goals set. Add [[goals]] tables to {} to track them.",
+            CONFIG_FILE
+        );
+        return Ok(());
+    }
+    print_goals(&goals);
+    println!();
+    Ok(())
+}
+
+fn run_status_command(config: &FaithConfig, format: OutputFormat) -> Result<()> {
+    let statuses = get_source_status(config)?;
+    if format == OutputFormat::Json {
+        return print_json(&statuses);
+    }
+
+    println!("\n=== SOURCE STATUS ===\n");
+    for status in &statuses {
+        println!("{}:", status.name.to_uppercase());
+        println!("  Files: {}", status.paths.join(", "));
+        if !status.exists {
+            println!("  Missing one or more files");
+        }
+        if let Some(modified) = &status.last_modified {
+            println!("  Last modified: {}", modified);
+        }
+        if let Some(schema) = &status.schema {
+            println!("  Format: {}", schema);
+        }
+        match (&status.last_data_date, status.days_since_last_data) {
+            (Some(date), Some(days)) => {
+                println!("  Last data: {} ({} days ago)", date, days)
+            }
+            _ => println!("  Last data: none"),
+        }
+        if let Some(error) = &status.error {
+            println!("  Error: {}", error);
+        }
+    }
+    println!();
+    Ok(())
+}
+
+fn run_report_command(
+    config: &FaithConfig,
+    week: Option<&str>,
+    output: Option<PathBuf>,
+) -> Result<()> {
+    let date = match week {
+        Some(date) => parse_local_date(date).context("Invalid --week date")?,
+        None => PeriodConfig::default().today()?,
+    };
+    let weekly = report::weekly_report(config, date)?;
+
+    let path =
+        output.unwrap_or_else(|| PathBuf::from(report::default_file_name(weekly.week.start)));
+    std::fs::write(&path, report::to_markdown(&weekly))
+        .with_context(|| format!("Failed to write report to '{}'", path.display()))?;
+    println!(
+        "Wrote weekly review for {} to {}",
+        weekly.week.label,
+        path.display()
+    );
+    Ok(())
+}
+
+fn run_export_command(
+    config: &FaithConfig,
+    series: ExportSeries,
+    output: Option<&Path>,
+    format: OutputFormat,
+) -> Result<()> {
+    let mut out = Vec::new();
+    match (series, format) {
+        (ExportSeries::Daily, OutputFormat::Text) => get_faith_daily_stats(config)
+            .and_then(|stats| export::write_daily_csv(&mut out, &stats)),
+        (ExportSeries::Weekly, OutputFormat::Text) => get_faith_weekly_stats(config)
+            .and_then(|stats| export::write_weekly_csv(&mut out, &stats)),
+        (ExportSeries::Daily, OutputFormat::Json) => get_faith_daily_stats(config)
+            .and_then(|stats| export::daily_time_series(&stats))
+            .and_then(|series| Ok(serde_json::to_writer_pretty(&mut out, &series)?)),
+        (ExportSeries::Weekly, OutputFormat::Json) => get_faith_weekly_stats(config)
+            .and_then(|stats| export::weekly_time_series(&stats))
+            .and_then(|series| Ok(serde_json::to_writer_pretty(&mut out, &series)?)),
+    }?;
+
+    match output {
+        Some(path) => {
+            std::fs::write(path, &out)
+                .with_context(|| format!("Failed to write export to '{}'", path.display()))?;
+            let kind = match format {
+                OutputFormat::Text => "CSV",
+                OutputFormat::Json => "JSON",
+            };
+            eprintln!("Wrote {} to {}", kind, path.display());
+        }
+        None => std::io::stdout().write_all(&out)?,
+    }
+    Ok(())
+}
+
+fn run_notify_command(
+    config: &FaithConfig,
+    state: &Path,
+    dry_run: bool,
+    format: OutputFormat,
+) -> Result<()> {
+    let Some(notify_config) = &config.notify else {
+        bail!("No [notify] section in the config file");
+    };
+    notify_config.validate()?;
+    let stats = get_faith_daily_stats(config)?;
+    let mature_passages = ankistats::get_cumulative_history(&config.sources.anki)?
+        .last()
+        .map(|point| point.mature_passages);
+    let events = notify::detect_current_events(&stats, notify_config, mature_passages)?;
+    let mut sent = SentEvents::load(state)?;
+
+    let new_events: Vec<_> = events.iter().filter(|e| !sent.contains(e)).collect();
+    if dry_run {
+        if format == OutputFormat::Json {
+            print_json(&new_events)?;
+        } else if new_events.is_empty() {
+            println!("No new events");
+        } else {
+            for event in &new_events {
+                println!("{}: {}", event.title, event.message);
+            }
+        }
+        return Ok(());
+    }
+
+    let mut failed = false;
+    for event in new_events {
+        match notify::send(notify_config, event) {
+            Ok(()) => {
+                println!("Sent: {}", event.title);
+                sent.insert(event);
+            }
+            Err(e) => {
+                eprintln!("Error: {:#}", e);
+                failed = true;
+            }
+        }
+    }
+    sent.save(state)?;
+    if failed {
+        bail!("Failed to send one or more events");
+    }
+    Ok(())
+}
+
+fn print_goals(goals: &[FaithGoalProgress]) {
+    if goals.is_empty() {
+        return;
+    }
+
+    println!("\nGOALS:");
+    for progress in goals {
+        let status = if progress.achieved {
+            "achieved".to_string()
+        } else if progress.on_track {
+            "on track".to_string()
+        } else {
+            format!("behind, {:.1}/day needed", progress.pace_needed)
+        };
+        println!(
+            "  {}: {:.1} / {} ({:.0}%, {})",
+            progress.description, progress.current, progress.target, progress.percent, status
+        );
+    }
+}
@@ -0,0 +1,118 @@
+mod anki;
+mod arc;
+mod faith;
+mod output;
+mod prayer;
+mod reading;
+mod tui;
+
+use clap::{Parser, Subcommand};
+use faith::OutputFormat;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::Duration;
+
+#[derive(Parser)]
+#[command(name = "lifestats")]
+#[command(about = "Life and faith statistics from every source, with paths from lifestats.toml", long_about = None)]
+#[command(version)]
+struct Cli {
+    /// Config file with the source paths (default: lifestats.toml if present, otherwise
+    /// environment variables)
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+
+    /// Output format: human-readable text or JSON for scripts and dashboards
+    #[arg(long, global = true, value_enum, default_value = "text")]
+    format: OutputFormat,
+
+    #[command(subcommand)]
+    command: Domain,
+}
+
+#[derive(Subcommand)]
+enum Domain {
+    /// Bible memorization time from Anki
+    Anki {
+        #[command(subcommand)]
+        command: anki::Commands,
+    },
+    /// Bible reading time from KOReader
+    Reading {
+        #[command(subcommand)]
+        command: reading::Commands,
+    },
+    /// Prayer time from Proseuche
+    Prayer {
+        #[command(subcommand)]
+        command: prayer::Commands,
+    },
+    /// Places and church attendance from Arc Timeline
+    Arc {
+        #[command(subcommand)]
+        command: arc::Commands,
+    },
+    /// Combined faith stats from every source
+    Faith {
+        #[command(subcommand)]
+        command: faith::Commands,
+    },
+    /// Markdown reviews of your faith time
+    Report {
+        #[command(subcommand)]
+        command: ReportCommands,
+    },
+    /// Live terminal dashboard of today's totals, 30-day trends, streaks, and goals
+    Tui {
+        /// Seconds between refreshes
+        #[arg(long, default_value_t = 30)]
+        refresh: u64,
+    },
+}
+
+#[derive(Subcommand)]
+enum ReportCommands {
+    /// Write a Markdown weekly review (totals, days, streaks, milestones, goals)
+    Weekly {
+        /// Any day of the week to review (YYYY-MM-DD); defaults to this week
+        #[arg(long)]
+        week: Option<String>,
+        /// File to write the report to; defaults to faith-review-<week start>.md
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+}
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {:#}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run() -> anyhow::Result<()> {
+    faith::configure_from_env()?;
+
+    let cli = Cli::parse();
+    let config = faith::load_config(cli.config.as_deref())?;
+
+    match cli.command {
+        Domain::Anki { command } => anki::run(command, &config, cli.format),
+        Domain::Reading { command } => reading::run(command, &config, cli.format),
+        Domain::Prayer { command } => prayer::run(command, &config, cli.format),
+        Domain::Arc { command } => arc::run(command, &config, cli.format),
+        Domain::Faith { command } => faith::run(command, &config, cli.format)?,
+        Domain::Report {
+            command: ReportCommands::Weekly { week, output },
+        } => faith::run(
+            faith::Commands::Report { week, output },
+            &config,
+            cli.format,
+        )?,
+        Domain::Tui { refresh } => tui::run(&config, Duration::from_secs(refresh.max(1)))?,
+    }
+    Ok(())
+}
@@ -0,0 +1,86 @@
+use crate::faith::{OutputFormat, print_json};
+use serde::Serialize;
+use statsutils::chart;
+use statsutils::fmt::duration_minutes;
+use std::process;
+
+/// Cells in the longest bar of a `--chart`
+const CHART_WIDTH: usize = 40;
+
+/// Returns the value, or prints the error and exits
+pub fn unwrap_or_exit<T>(result: anyhow::Result<T>) -> T {
+    result.unwrap_or_else(|e| {
+        eprintln!("Error: {:#}", e);
+        process::exit(1);
+    })
+}
+
+/// Prints a single day's minutes for one source
+pub fn print_today(label: &str, minutes: f64, format: OutputFormat) {
+    if format == OutputFormat::Json {
+        unwrap_or_exit(print_json(&TodayMinutes { minutes }));
+        return;
+    }
+
+    println!(
+        "{} today: {} ({:.2} min)",
+        label,
+        duration_minutes(minutes),
+        minutes
+    );
+}
+
+#[derive(Serialize)]
+struct TodayMinutes {
+    minutes: f64,
+}
+
+/// Prints a series as JSON, or as one line per day, week, or month followed by the total
+///
+/// `minutes` picks each row's label and minutes for the text view; `unit` names a row in
+/// the summary ("day", "week", "month"). With `chart`, the rows are drawn as bars.
+pub fn print_series<T: Serialize>(
+    title: &str,
+    rows: &[T],
+    unit: &str,
+    format: OutputFormat,
+    chart: bool,
+    minutes: impl Fn(&T) -> (&str, f64),
+) {
+    if format == OutputFormat::Json {
+        unwrap_or_exit(print_json(&rows));
+        return;
+    }
+
+    println!("\n=== {} ===\n", title);
+
+    let rows: Vec<(&str, f64)> = rows.iter().map(minutes).collect();
+    if chart {
+        print!("{}", chart::minutes_bar_chart(&rows, CHART_WIDTH));
+    } else {
+        for &(label, row_minutes) in &rows {
+            if row_minutes > 0.0 {
+                println!(
+                    "{}: {:.2} min ({})",
+                    label,
+                    row_minutes,
+                    duration_minutes(row_minutes)
+                );
+            } else {
+                println!("{}: ---", label);
+            }
+        }
+    }
+
+    let total_minutes: f64 = rows.iter().map(|&(_, minutes)| minutes).sum();
+    let active = rows.iter().filter(|&&(_, minutes)| minutes > 0.0).count();
+
+    println!("\n--- SUMMARY ---");
+    println!(
+        "Total: {} ({:.2} minutes)",
+        duration_minutes(total_minutes),
+        total_minutes
+    );
+    println!("Active {}s: {} out of {}", unit, active, rows.len());
+    println!();
+}
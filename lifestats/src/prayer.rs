@@ -0,0 +1,79 @@
+use crate::faith::OutputFormat;
+use crate::output::{print_series, print_today, unwrap_or_exit};
+use clap::Subcommand;
+use faithstats::config::FaithConfig;
+use statsutils::DatePeriod;
+
+#[derive(Subcommand)]
+pub enum Commands {
+    /// Show prayer time for today
+    Today,
+    /// Show prayer time for each of the last 30 days
+    Daily {
+        /// Draw the days as a bar chart
+        #[arg(long)]
+        chart: bool,
+    },
+    /// Show prayer time for each of the last 12 weeks
+    Weekly {
+        /// Draw the weeks as a bar chart
+        #[arg(long)]
+        chart: bool,
+    },
+    /// Show prayer time for each of the last 12 months
+    Monthly,
+    /// Show prayer time per prayer list over the last 30 days
+    Categories,
+}
+
+pub fn run(command: Commands, config: &FaithConfig, format: OutputFormat) {
+    let db_path = config.sources.proseuche.as_str();
+    match command {
+        Commands::Today => {
+            let minutes = unwrap_or_exit(prayerstats::get_today_prayer_time(db_path));
+            print_today("Prayer", minutes, format);
+        }
+        Commands::Daily { chart } => {
+            let stats = unwrap_or_exit(prayerstats::get_last_30_days_stats(db_path));
+            print_series("PRAYER - LAST 30 DAYS", &stats, "day", format, chart, |d| {
+                (&d.date, d.minutes)
+            });
+        }
+        Commands::Weekly { chart } => {
+            let stats = unwrap_or_exit(prayerstats::get_last_12_weeks_stats(db_path));
+            print_series(
+                "PRAYER - LAST 12 WEEKS",
+                &stats,
+                "week",
+                format,
+                chart,
+                |w| (&w.week_start, w.minutes),
+            );
+        }
+        Commands::Monthly => {
+            let stats = unwrap_or_exit(prayerstats::get_last_12_months_stats(db_path));
+            print_series(
+                "PRAYER - LAST 12 MONTHS",
+                &stats,
+                "month",
+                format,
+                false,
+                |m| (&m.month, m.minutes),
+            );
+        }
+        Commands::Categories => {
+            let stats = unwrap_or_exit(
+                DatePeriod::last_30_days()
+                    .and_then(|period| prayerstats::get_stats_by_category(db_path, &period)),
+            );
+            print_series(
+                "PRAYER LISTS - LAST 30 DAYS",
+                &stats,
+                "list",
+                format,
+                false,
+                |c| (&c.category, c.minutes),
+            );
+        }
+    }
+}
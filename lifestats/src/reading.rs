@@ -0,0 +1,79 @@
+use crate::faith::OutputFormat;
+use crate::output::{print_series, print_today, unwrap_or_exit};
+use clap::Subcommand;
+use faithstats::config::FaithConfig;
+use statsutils::DatePeriod;
+
+#[derive(Subcommand)]
+pub enum Commands {
+    /// Show Bible reading time for today
+    Today,
+    /// Show Bible reading time for each of the last 30 days
+    Daily {
+        /// Draw the days as a bar chart
+        #[arg(long)]
+        chart: bool,
+    },
+    /// Show Bible reading time for each of the last 12 weeks
+    Weekly {
+        /// Draw the weeks as a bar chart
+        #[arg(long)]
+        chart: bool,
+    },
+    /// Show Bible reading time for each of the last 12 months
+    Monthly,
+    /// Show reading time for every book, not just the Bible, over the last 30 days
+    Books,
+}
+
+pub fn run(command: Commands, config: &FaithConfig, format: OutputFormat) {
+    let db_path = config.sources.koreader.as_str();
+    match command {
+        Commands::Today => {
+            let minutes = unwrap_or_exit(readingstats::get_today_reading_time(db_path));
+            print_today("Bible reading", minutes, format);
+        }
+        Commands::Daily { chart } => {
+            let stats = unwrap_or_exit(readingstats::get_last_30_days_stats(db_path));
+            print_series(
+                "BIBLE READING - LAST 30 DAYS",
+                &stats,
+                "day",
+                format,
+                chart,
+                |d| (&d.date, d.minutes),
+            );
+        }
+        Commands::Weekly { chart } => {
+            let stats = unwrap_or_exit(readingstats::get_last_12_weeks_stats(db_path));
+            print_series(
+                "BIBLE READING - LAST 12 WEEKS",
+                &stats,
+                "week",
+                format,
+                chart,
+                |w| (&w.week_start, w.minutes),
+            );
+        }
+        Commands::Monthly => {
+            let stats = unwrap_or_exit(readingstats::get_last_12_months_stats(db_path));
+            print_series(
+                "BIBLE READING - LAST 12 MONTHS",
+                &stats,
+                "month",
+                format,
+                false,
+                |m| (&m.month, m.minutes),
+            );
+        }
+        Commands::Books => {
+            let stats = unwrap_or_exit(
+                DatePeriod::last_30_days()
+                    .and_then(|period| readingstats::get_per_book_stats(db_path, &period)),
+            );
+            print_series("BOOKS - LAST 30 DAYS", &stats, "book", format, false, |b| {
+                (&b.title, b.minutes)
+            });
+        }
+    }
+}
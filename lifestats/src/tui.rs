@@ -0,0 +1,297 @@
+use anyhow::Result;
+use faithstats::cache::FaithStatsCache;
+use faithstats::config::FaithConfig;
+use faithstats::goals::FaithGoalProgress;
+use faithstats::models::{ActivityStreaks, FaithDailyStats, FaithDayStats, FaithTodayStats};
+use ratatui::DefaultTerminal;
+use ratatui::Frame;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, LineGauge, Paragraph, Sparkline};
+use statsutils::fmt::duration_minutes;
+use std::time::{Duration, Instant};
+
+/// Picks one source's minutes out of a day
+type DayMinutes = fn(&FaithDayStats) -> f64;
+
+/// Everything on screen, from the last successful refresh
+struct Dashboard {
+    today: FaithTodayStats,
+    daily: FaithDailyStats,
+}
+
+/// Dashboard state between refreshes
+struct App {
+    cache: FaithStatsCache,
+    dashboard: Option<Dashboard>,
+    /// Why the last refresh failed; the previous dashboard stays on screen
+    error: Option<String>,
+    updated: Option<chrono::DateTime<chrono::Local>>,
+}
+
+impl App {
+    fn refresh(&mut self) {
+        match load_dashboard(&self.cache) {
+            Ok(dashboard) => {
+                self.dashboard = Some(dashboard);
+                self.error = None;
+                self.updated = Some(chrono::Local::now());
+            }
+            Err(e) => self.error = Some(format!("{:#}", e)),
+        }
+    }
+}
+
+/// Reads today's totals and the daily series, whose summary has the goal progress, through
+/// the cache, so a refresh with no changed sources is cheap
+fn load_dashboard(cache: &FaithStatsCache) -> Result<Dashboard> {
+    Ok(Dashboard {
+        today: cache.today_stats()?,
+        daily: cache.daily_stats()?,
+    })
+}
+
+/// Runs the dashboard until q or Esc, refreshing every `refresh` and on r
+pub fn run(config: &FaithConfig, refresh: Duration) -> Result<()> {
+    let mut app = App {
+        cache: FaithStatsCache::new(config.clone()),
+        dashboard: None,
+        error: None,
+        updated: None,
+    };
+
+    let mut terminal = ratatui::init();
+    let result = event_loop(&mut terminal, &mut app, refresh);
+    ratatui::restore();
+    result
+}
+
+fn event_loop(terminal: &mut DefaultTerminal, app: &mut App, refresh: Duration) -> Result<()> {
+    // Show the loading screen while the first stats are read
+    terminal.draw(|frame| draw(frame, app))?;
+    app.refresh();
+
+    let mut last_refresh = Instant::now();
+    loop {
+        terminal.draw(|frame| draw(frame, app))?;
+
+        let timeout = refresh.saturating_sub(last_refresh.elapsed());
+        if event::poll(timeout)? {
+            if let Event::Key(key) = event::read()? {
+                if key.kind != KeyEventKind::Press {
+                    continue;
+                }
+                match key.code {
+                    KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                    KeyCode::Char('r') => {
+                        app.cache.clear();
+                        app.refresh();
+                        last_refresh = Instant::now();
+                    }
+                    _ => {}
+                }
+            }
+        } else {
+            app.refresh();
+            last_refresh = Instant::now();
+        }
+    }
+}
+
+fn draw(frame: &mut Frame, app: &App) {
+    let [today_area, trends_area, bottom_area, footer_area] = Layout::vertical([
+        Constraint::Length(3),
+        Constraint::Length(10),
+        Constraint::Min(6),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+
+    if let Some(dashboard) = &app.dashboard {
+        draw_today(frame, today_area, &dashboard.today);
+        draw_trends(frame, trends_area, &dashboard.daily);
+
+        let [streaks_area, goals_area] =
+            Layout::horizontal([Constraint::Percentage(40), Constraint::Percentage(60)])
+                .areas(bottom_area);
+        draw_streaks(frame, streaks_area, &dashboard.daily);
+        draw_goals(frame, goals_area, &dashboard.daily.summary.goals);
+    } else {
+        frame.render_widget(
+            Paragraph::new("Loading stats...").block(Block::bordered().title(" Faith stats ")),
+            today_area,
+        );
+    }
+
+    draw_footer(frame, footer_area, app);
+}
+
+fn draw_today(frame: &mut Frame, area: Rect, today: &FaithTodayStats) {
+    let mut spans = vec![];
+    let mut sources = vec![
+        ("Anki", today.anki_minutes),
+        ("Reading", today.reading_minutes),
+        ("Church", today.at_church_minutes),
+        ("Prayer", today.prayer_minutes),
+    ];
+    sources.extend(
+        today
+            .other_minutes
+            .iter()
+            .map(|(source, minutes)| (source.as_str(), *minutes)),
+    );
+    for (label, minutes) in sources {
+        spans.push(Span::raw(format!("{}: ", label)));
+        spans.push(Span::raw(duration_minutes(minutes)).bold());
+        spans.push(Span::raw("   "));
+    }
+    spans.push(Span::raw("Total: "));
+    spans.push(
+        Span::raw(duration_minutes(today.total_minutes))
+            .bold()
+            .fg(Color::Green),
+    );
+
+    frame.render_widget(
+        Paragraph::new(Line::from(spans)).block(Block::bordered().title(" Today ")),
+        area,
+    );
+}
+
+/// One sparkline per source over the last 30 days, each scaled to its own busiest day
+fn draw_trends(frame: &mut Frame, area: Rect, daily: &FaithDailyStats) {
+    let block = Block::bordered().title(" Last 30 days ");
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let series: [(&str, Color, DayMinutes); 4] = [
+        ("Anki", Color::Cyan, |d| d.anki_minutes),
+        ("Reading", Color::Yellow, |d| d.reading_minutes),
+        ("Church", Color::Magenta, |d| d.at_church_minutes),
+        ("Prayer", Color::Blue, |d| d.prayer_minutes),
+    ];
+    let rows = Layout::vertical([Constraint::Ratio(1, 4); 4]).split(inner);
+    for ((label, color, minutes), row) in series.into_iter().zip(rows.iter()) {
+        let values: Vec<f64> = daily.days.iter().map(minutes).collect();
+        let total: f64 = values.iter().sum();
+
+        let [label_area, spark_area] =
+            Layout::horizontal([Constraint::Length(18), Constraint::Min(10)]).areas(*row);
+        frame.render_widget(
+            Paragraph::new(vec![
+                Line::from(label).bold(),
+                Line::from(duration_minutes(total)),
+            ]),
+            label_area,
+        );
+        let data: Vec<u64> = values.iter().map(|m| m.round() as u64).collect();
+        frame.render_widget(
+            Sparkline::default()
+                .data(&data)
+                .style(Style::default().fg(color)),
+            spark_area,
+        );
+    }
+}
+
+fn draw_streaks(frame: &mut Frame, area: Rect, daily: &FaithDailyStats) {
+    let streaks = &daily.summary.streaks;
+    let line = |label: &str, streaks: &ActivityStreaks| {
+        Line::from(vec![
+            Span::raw(format!("{:<12}", label)),
+            Span::raw(format!("{:>4}", streaks.current_streak)).bold(),
+            Span::raw(format!(" days  (longest {})", streaks.longest_streak)),
+        ])
+    };
+
+    let mut lines = vec![line("Any", &streaks.any_activity)];
+    lines.extend(
+        streaks
+            .by_source
+            .iter()
+            .map(|(source, streaks)| line(source, streaks)),
+    );
+
+    frame.render_widget(
+        Paragraph::new(lines).block(Block::bordered().title(" Current streaks ")),
+        area,
+    );
+}
+
+fn draw_goals(frame: &mut Frame, area: Rect, goals: &[FaithGoalProgress]) {
+    let block = Block::bordered().title(" Goals ");
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    if goals.is_empty() {
+        frame.render_widget(
+            Paragraph::new("No goals set. Add [[goals]] tables to the config file."),
+            inner,
+        );
+        return;
+    }
+
+    let rows = Layout::vertical(vec![Constraint::Length(1); goals.len()]).split(inner);
+    for (goal, row) in goals.iter().zip(rows.iter()) {
+        let color = if goal.achieved {
+            Color::Green
+        } else if goal.on_track {
+            Color::Yellow
+        } else {
+            Color::Red
+        };
+        frame.render_widget(
+            LineGauge::default()
+                .ratio((goal.percent / 100.0).clamp(0.0, 1.0))
+                .label(format!("{} ({:.0}%)", goal.description, goal.percent))
+                .filled_style(Style::default().fg(color))
+                .unfilled_style(Style::default().add_modifier(Modifier::DIM)),
+            *row,
+        );
+    }
+}
+
+fn draw_footer(frame: &mut Frame, area: Rect, app: &App) {
+    let line = match (&app.error, app.updated) {
+        (Some(error), _) => Line::from(format!("Error: {}", error)).fg(Color::Red),
+        (None, Some(updated)) => Line::from(format!(
+            "Updated {}   r refresh   q quit",
+            updated.format("%H:%M:%S")
+        ))
+        .add_modifier(Modifier::DIM),
+        (None, None) => Line::from("r refresh   q quit"),
+    };
+    frame.render_widget(Paragraph::new(line), area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::Terminal;
+    use ratatui::backend::TestBackend;
+
+    #[test]
+    fn test_draw_keeps_error_in_footer() {
+        let app = App {
+            cache: FaithStatsCache::new(
+                FaithConfig::parse(
+                    "[sources]\nanki = \"anki.db\"\nkoreader = \"koreader.db\"\nproseuche = \"proseuche.db\"\n",
+                )
+                .unwrap(),
+            ),
+            dashboard: None,
+            error: Some("Failed to open Anki database".to_string()),
+            updated: None,
+        };
+
+        let mut terminal = Terminal::new(TestBackend::new(60, 24)).unwrap();
+        terminal.draw(|frame| draw(frame, &app)).unwrap();
+
+        let buffer = terminal.backend().buffer();
+        let text: String = buffer.content().iter().map(|cell| cell.symbol()).collect();
+        assert!(text.contains("Loading stats..."));
+        assert!(text.contains("Error: Failed to open Anki database"));
+    }
+}
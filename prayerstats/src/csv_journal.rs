@@ -0,0 +1,161 @@
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use rusqlite::Connection;
+use serde::Deserialize;
+use statsutils::PeriodConfig;
+use std::io::Read;
+
+use crate::memory::SessionStore;
+
+/// One row of a CSV prayer journal
+///
+/// ```text
+/// started_at,ended_at,category,items
+/// 2025-06-10 07:00,2025-06-10 07:15,Intercession,Family; Church
+/// 2025-06-10 21:00,,Thanksgiving,
+/// ```
+///
+/// Times are local unless they carry a UTC offset (RFC 3339). Either `ended_at` or
+/// `minutes` gives the session's length; `category` and `items` (separated by `;`) are
+/// optional.
+#[derive(Debug, Deserialize)]
+struct JournalRow {
+    started_at: String,
+    #[serde(default)]
+    ended_at: Option<String>,
+    #[serde(default)]
+    minutes: Option<f64>,
+    #[serde(default)]
+    category: Option<String>,
+    #[serde(default)]
+    items: Option<String>,
+}
+
+/// Loads a CSV prayer journal into an in-memory database with the same tables as
+/// Proseuche, so the queries in `db` work on it unchanged
+///
+/// # Arguments
+/// * `path` - Path to the CSV journal
+pub fn open_journal(path: &str) -> Result<Connection> {
+    let file =
+        std::fs::File::open(path).context(format!("Failed to open prayer journal '{}'", path))?;
+    load_journal(file).context(format!("Failed to read prayer journal '{}'", path))
+}
+
+fn load_journal(reader: impl Read) -> Result<Connection> {
+    let config = PeriodConfig::default();
+    let mut store = SessionStore::new()?;
+
+    let mut csv = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(reader);
+    for (index, row) in csv.deserialize::<JournalRow>().enumerate() {
+        // Header is line 1
+        let line = index + 2;
+        let row = row.context(format!("Invalid row on line {}", line))?;
+
+        let started_at = parse_time(&row.started_at, &config)
+            .context(format!("Invalid started_at on line {}", line))?;
+        let ended_at = match (non_empty(&row.ended_at), row.minutes) {
+            (Some(ended_at), _) => parse_time(ended_at, &config)
+                .context(format!("Invalid ended_at on line {}", line))?,
+            (None, Some(minutes)) => {
+                started_at + chrono::Duration::seconds((minutes * 60.0) as i64)
+            }
+            (None, None) => bail!("Line {} needs either ended_at or minutes", line),
+        };
+
+        let items: Vec<String> = row
+            .items
+            .as_deref()
+            .unwrap_or("")
+            .split(';')
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .map(String::from)
+            .collect();
+        store.add_session(
+            &format_utc(started_at),
+            &format_utc(ended_at),
+            non_empty(&row.category),
+            &items,
+        )?;
+    }
+
+    Ok(store.into_connection())
+}
+
+fn non_empty(value: &Option<String>) -> Option<&str> {
+    value.as_deref().filter(|v| !v.is_empty())
+}
+
+/// Parses an RFC 3339 time, or a local time without an offset
+fn parse_time(value: &str, config: &PeriodConfig) -> Result<DateTime<Utc>> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Ok(time.with_timezone(&Utc));
+    }
+
+    let local = [
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%d %H:%M",
+        "%Y-%m-%dT%H:%M:%S",
+        "%Y-%m-%dT%H:%M",
+    ]
+    .iter()
+    .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+    .context(format!(
+        "'{}' isn't a time like 2025-06-10 07:00 or 2025-06-10T07:00:00-05:00",
+        value
+    ))?;
+    let ms = config.timezone.timestamp_ms(local)?;
+    DateTime::<Utc>::from_timestamp_millis(ms).context("Time out of range")
+}
+
+/// Formats a time the way Proseuche stores it, which is what the queries in `db` expect
+fn format_utc(time: DateTime<Utc>) -> String {
+    time.format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db;
+    use chrono::NaiveDate;
+    use statsutils::{DatePeriod, Granularity};
+
+    const JOURNAL: &str = "\
+started_at,ended_at,minutes,category,items
+2025-06-10T07:00:00Z,2025-06-10T07:15:00Z,,Intercession,Family; Church
+2025-06-11T07:00:00Z,,10,Thanksgiving,
+2025-06-12T07:00:00Z,,5,,
+";
+
+    fn june() -> DatePeriod {
+        DatePeriod::for_range(
+            NaiveDate::from_ymd_opt(2025, 6, 1).unwrap(),
+            NaiveDate::from_ymd_opt(2025, 6, 30).unwrap(),
+            Granularity::Day,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_journal_sessions() {
+        let conn = load_journal(JOURNAL.as_bytes()).unwrap();
+        let sessions = db::get_prayer_sessions(&conn, &june()).unwrap();
+
+        assert_eq!(sessions.len(), 3);
+        assert!((sessions[0].minutes - 15.0).abs() < 0.01);
+        assert_eq!(sessions[0].category, "Intercession");
+        assert_eq!(sessions[0].items, vec!["Family", "Church"]);
+        assert!((sessions[1].minutes - 10.0).abs() < 0.01);
+        assert_eq!(sessions[2].category, db::UNCATEGORIZED);
+    }
+
+    #[test]
+    fn test_journal_needs_length() {
+        let err = load_journal("started_at,category\n2025-06-10 07:00,Intercession\n".as_bytes())
+            .unwrap_err();
+        assert!(format!("{:#}", err).contains("Line 2 needs either ended_at or minutes"));
+    }
+}
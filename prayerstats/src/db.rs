@@ -1,9 +1,15 @@
 use anyhow::{Context, Result};
-use rusqlite::{Connection, OpenFlags};
-use statsutils::{DatePeriod, get_today_start_ms, register_date_functions};
+use chrono::NaiveDate;
+use rusqlite::Connection;
+use statsutils::series::densify;
+use statsutils::sqlite::{OpenOptions, open_readonly_with_retry};
+use statsutils::{DatePeriod, Granularity, PeriodConfig, get_today_start_ms};
 use std::collections::HashMap;
 
-use crate::models::{DayStats, WeekStats};
+use crate::models::{
+    CategoryStats, DayStats, MonthStats, PeriodStats, PrayerSession, PrayerStreaks, WeekStats,
+};
+use crate::schema;
 
 /// Opens a connection to a Proseuche database in read-only mode
 ///
@@ -18,6 +24,13 @@ use crate::models::{DayStats, WeekStats};
 /// - started_at: datetime when prayer session started
 /// - ended_at: datetime when prayer session ended
 /// - duration_minutes: computed column with session duration in minutes
+/// - prayer_list_id: the prayer list (category) prayed through, in newer versions
+///
+/// Older versions without `duration_minutes` are adapted by `schema::adapt_schema`.
+///
+/// Prayer lists are stored in a prayer_lists table with `id` and `name`, and the prayer
+/// items prayed for during a session in prayer_session_items (`prayer_session_id`,
+/// `prayer_item_id`, `position`) joined to prayer_items (`id`, `title`).
 ///
 /// # Example
 /// ```ignore
@@ -26,14 +39,11 @@ use crate::models::{DayStats, WeekStats};
 /// let conn = open_database("/path/to/database.sqlite")?;
 /// ```
 pub fn open_database(path: &str) -> Result<Connection> {
-    let conn = Connection::open_with_flags(
-        path,
-        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
-    )
-    .context("Failed to open Proseuche database in read-only mode")?;
+    // Retries while Proseuche holds a lock, and registers date functions from statsutils
+    let conn = open_readonly_with_retry(path, &OpenOptions::new("Proseuche database"))?;
 
-    // Register date functions from statsutils
-    register_date_functions(&conn)?;
+    // Older versions of Proseuche lack some of the columns queried below
+    schema::adapt_schema(&conn).context(format!("Can't read '{}'", path))?;
 
     Ok(conn)
 }
@@ -56,80 +66,476 @@ pub fn get_today_prayer_minutes(conn: &Connection) -> Result<f64> {
     Ok(total_minutes)
 }
 
-/// Gets prayer time for each of the last 30 days
-///
-/// # Arguments
-/// * `conn` - Database connection to Proseuche database
+/// Gets every distinct day (with rollover applied) with a prayer session, oldest first
+pub fn get_prayer_dates(conn: &Connection) -> Result<Vec<NaiveDate>> {
+    let query = r#"
+        SELECT DISTINCT date_str_from_sec(CAST(strftime('%s', started_at) AS INTEGER)) as date
+        FROM prayer_sessions
+        WHERE started_at IS NOT NULL
+            AND ended_at IS NOT NULL
+        ORDER BY date
+    "#;
+
+    let mut stmt = conn.prepare(query)?;
+    let dates = stmt
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<String>, _>>()?;
+
+    dates
+        .into_iter()
+        .map(|date| {
+            NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+                .context(format!("Invalid prayer date '{}'", date))
+        })
+        .collect()
+}
+
+/// Gets the current and longest prayer streaks and the total number of days prayed
+pub fn get_prayer_streaks(conn: &Connection) -> Result<PrayerStreaks> {
+    let dates = get_prayer_dates(conn)?;
+    let streaks = statsutils::streaks::compute(&dates, &PeriodConfig::default())?;
+
+    Ok(PrayerStreaks {
+        current_streak: streaks.current_streak,
+        longest_streak: streaks.longest_streak,
+        days_prayed: streaks.active_days,
+        last_prayer_date: streaks
+            .last_active_date
+            .map(|d| d.format("%Y-%m-%d").to_string()),
+    })
+}
+
+/// Queries prayer minutes for every bucket of a period
 ///
-/// # Returns
-/// Vector of DayStats with date and minutes for each of the last 30 days
-pub fn get_last_30_days_stats(conn: &Connection) -> Result<Vec<DayStats>> {
-    // Get the period data for the last 30 days
-    let period = DatePeriod::last_30_days()?;
+/// Sessions are grouped by start time with the SQLite bucket function for `granularity`,
+/// which must produce keys matching the period's dates; any that don't are an error.
+fn get_period_minutes(
+    conn: &Connection,
+    period: DatePeriod,
+    granularity: Granularity,
+) -> Result<Vec<(String, f64)>> {
+    let bucket_fn = granularity.sec_bucket_function();
 
     // Convert milliseconds to seconds for SQL query (strftime works with seconds)
     let start_sec = period.start_ms / 1000;
     let end_sec = period.end_ms / 1000;
 
-    // Query prayer time grouped by date
-    let query = r#"
-        SELECT date_str_from_sec(CAST(strftime('%s', started_at) AS INTEGER)) as date,
+    let query = format!(
+        r#"
+        SELECT {bucket_fn}(CAST(strftime('%s', started_at) AS INTEGER)) as bucket,
                SUM(duration_minutes) as total_minutes
         FROM prayer_sessions
         WHERE started_at IS NOT NULL
             AND ended_at IS NOT NULL
             AND CAST(strftime('%s', started_at) AS INTEGER) >= ?1
             AND CAST(strftime('%s', started_at) AS INTEGER) < ?2
-        GROUP BY date_str_from_sec(CAST(strftime('%s', started_at) AS INTEGER))
-    "#;
+        GROUP BY {bucket_fn}(CAST(strftime('%s', started_at) AS INTEGER))
+        "#
+    );
 
-    let mut stmt = conn.prepare(query)?;
+    let mut stmt = conn.prepare(&query)?;
     let prayer_results = stmt
         .query_map([start_sec, end_sec], |row| {
             Ok((row.get::<_, String>(0)?, row.get::<_, f64>(1)?))
         })?
         .collect::<Result<HashMap<String, f64>, _>>()?;
 
-    let results = period.build_results(prayer_results, |date, total_minutes| DayStats {
-        date,
-        minutes: total_minutes,
-    });
+    Ok(densify(prayer_results, &period)?.map(|key, total_minutes| (key, total_minutes)))
+}
 
-    Ok(results)
+/// Gets prayer time for each of the last 30 days
+///
+/// # Arguments
+/// * `conn` - Database connection to Proseuche database
+///
+/// # Returns
+/// Vector of DayStats with date and minutes for each of the last 30 days
+pub fn get_last_30_days_stats(conn: &Connection) -> Result<Vec<DayStats>> {
+    let results = get_period_minutes(conn, DatePeriod::last_30_days()?, Granularity::Day)?;
+
+    Ok(results
+        .into_iter()
+        .map(|(date, minutes)| DayStats { date, minutes })
+        .collect())
 }
 
 /// Gets prayer time for each of the last 12 weeks
 pub fn get_last_12_weeks_stats(conn: &Connection) -> Result<Vec<WeekStats>> {
-    // Get the period data for the last 12 weeks
-    let period = DatePeriod::last_12_weeks()?;
+    let results = get_period_minutes(conn, DatePeriod::last_12_weeks()?, Granularity::Week)?;
+
+    Ok(results
+        .into_iter()
+        .map(|(week_start, minutes)| WeekStats {
+            week_start,
+            minutes,
+        })
+        .collect())
+}
+
+/// Gets prayer time for each of the last 12 months
+pub fn get_last_12_months_stats(conn: &Connection) -> Result<Vec<MonthStats>> {
+    let results = get_period_minutes(conn, DatePeriod::last_12_months()?, Granularity::Month)?;
+
+    Ok(results
+        .into_iter()
+        .map(|(month, minutes)| MonthStats { month, minutes })
+        .collect())
+}
+
+/// Gets prayer time for each bucket of an arbitrary period
+pub fn get_stats_for_period(
+    conn: &Connection,
+    period: DatePeriod,
+    granularity: Granularity,
+) -> Result<Vec<PeriodStats>> {
+    let results = get_period_minutes(conn, period, granularity)?;
+
+    Ok(results
+        .into_iter()
+        .map(|(period_start, minutes)| PeriodStats {
+            period_start,
+            minutes,
+        })
+        .collect())
+}
+
+/// Name given to sessions that aren't tied to a prayer list
+pub const UNCATEGORIZED: &str = "Uncategorized";
+
+/// SQL expression naming each session's prayer list, falling back to `UNCATEGORIZED` for
+/// sessions without one or databases from before prayer lists were recorded
+pub(crate) fn category_expression(conn: &Connection) -> Result<String> {
+    let has_lists: bool = conn.query_row(
+        r#"
+        SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'prayer_lists')
+            AND EXISTS(SELECT 1 FROM pragma_table_info('prayer_sessions')
+                WHERE name = 'prayer_list_id')
+        "#,
+        [],
+        |row| row.get(0),
+    )?;
+
+    Ok(if has_lists {
+        format!(
+            "COALESCE((SELECT pl.name FROM prayer_lists pl WHERE pl.id = ps.prayer_list_id), '{}')",
+            UNCATEGORIZED
+        )
+    } else {
+        format!("'{}'", UNCATEGORIZED)
+    })
+}
+
+/// Gets prayer time and session count for each prayer list during a period, most prayed
+/// first
+pub fn get_stats_by_category(conn: &Connection, period: &DatePeriod) -> Result<Vec<CategoryStats>> {
+    let category = category_expression(conn)?;
+
+    let query = format!(
+        r#"
+        SELECT {category} as category,
+               SUM(ps.duration_minutes) as total_minutes,
+               COUNT(*) as sessions
+        FROM prayer_sessions ps
+        WHERE ps.started_at IS NOT NULL
+            AND ps.ended_at IS NOT NULL
+            AND CAST(strftime('%s', ps.started_at) AS INTEGER) >= ?1
+            AND CAST(strftime('%s', ps.started_at) AS INTEGER) < ?2
+        GROUP BY category
+        ORDER BY total_minutes DESC, category
+        "#
+    );
+
+    let mut stmt = conn.prepare(&query)?;
+    let results = stmt
+        .query_map([period.start_ms / 1000, period.end_ms / 1000], |row| {
+            Ok(CategoryStats {
+                category: row.get(0)?,
+                minutes: row.get(1)?,
+                sessions: row.get(2)?,
+            })
+        })?
+        .collect::<Result<Vec<CategoryStats>, _>>()?;
+
+    Ok(results)
+}
+
+/// Gets prayer time per prayer list for every bucket of a period
+///
+/// # Returns
+/// One entry per bucket in the period's order, each listing the prayer lists prayed
+/// through during it, most prayed first (empty when there was no prayer)
+pub fn get_period_stats_by_category(
+    conn: &Connection,
+    period: DatePeriod,
+    granularity: Granularity,
+) -> Result<Vec<(String, Vec<CategoryStats>)>> {
+    let category = category_expression(conn)?;
+    let bucket_fn = granularity.sec_bucket_function();
+
+    let query = format!(
+        r#"
+        SELECT {bucket_fn}(CAST(strftime('%s', ps.started_at) AS INTEGER)) as bucket,
+               {category} as category,
+               SUM(ps.duration_minutes) as total_minutes,
+               COUNT(*) as sessions
+        FROM prayer_sessions ps
+        WHERE ps.started_at IS NOT NULL
+            AND ps.ended_at IS NOT NULL
+            AND CAST(strftime('%s', ps.started_at) AS INTEGER) >= ?1
+            AND CAST(strftime('%s', ps.started_at) AS INTEGER) < ?2
+        GROUP BY bucket, category
+        ORDER BY bucket, total_minutes DESC, category
+        "#
+    );
 
-    // Convert milliseconds to seconds for SQL query
+    let mut stmt = conn.prepare(&query)?;
+    let mut by_bucket: HashMap<String, Vec<CategoryStats>> = HashMap::new();
+    let rows = stmt.query_map([period.start_ms / 1000, period.end_ms / 1000], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            CategoryStats {
+                category: row.get(1)?,
+                minutes: row.get(2)?,
+                sessions: row.get(3)?,
+            },
+        ))
+    })?;
+    for row in rows {
+        let (bucket, stats) = row?;
+        by_bucket.entry(bucket).or_default().push(stats);
+    }
+
+    Ok(period.build_results(by_bucket, |key, categories| (key, categories)))
+}
+
+/// Gets every prayer session that started during a period, oldest first, with the prayer
+/// items prayed for
+pub fn get_prayer_sessions(conn: &Connection, period: &DatePeriod) -> Result<Vec<PrayerSession>> {
+    let category = category_expression(conn)?;
+    let config = PeriodConfig::default();
     let start_sec = period.start_ms / 1000;
     let end_sec = period.end_ms / 1000;
 
-    // Query prayer time grouped by week
+    let query = format!(
+        r#"
+        SELECT ps.id,
+               CAST(strftime('%s', ps.started_at) AS INTEGER) as start_time,
+               CAST(strftime('%s', ps.ended_at) AS INTEGER) as end_time,
+               ps.duration_minutes,
+               {category} as category
+        FROM prayer_sessions ps
+        WHERE ps.started_at IS NOT NULL
+            AND ps.ended_at IS NOT NULL
+            AND CAST(strftime('%s', ps.started_at) AS INTEGER) >= ?1
+            AND CAST(strftime('%s', ps.started_at) AS INTEGER) < ?2
+        ORDER BY start_time
+        "#
+    );
+
+    let mut stmt = conn.prepare(&query)?;
+    let rows = stmt
+        .query_map([start_sec, end_sec], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, i64>(2)?,
+                row.get::<_, f64>(3)?,
+                row.get::<_, String>(4)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut items = get_session_items(conn, start_sec, end_sec)?;
+    let local_time = |sec: i64| -> Result<String> {
+        Ok(config
+            .timezone
+            .local_datetime(sec * 1000)?
+            .format("%Y-%m-%d %H:%M:%S")
+            .to_string())
+    };
+
+    rows.into_iter()
+        .map(|(id, start_time, end_time, minutes, category)| {
+            Ok(PrayerSession {
+                id,
+                date: config
+                    .logical_date(start_time * 1000)?
+                    .format("%Y-%m-%d")
+                    .to_string(),
+                start_time: local_time(start_time)?,
+                end_time: local_time(end_time)?,
+                minutes,
+                category,
+                items: items.remove(&id).unwrap_or_default(),
+            })
+        })
+        .collect()
+}
+
+/// Gets the titles of the prayer items prayed for in each session started between two
+/// Unix times, keyed by session id; empty for databases that don't record items
+pub(crate) fn get_session_items(
+    conn: &Connection,
+    start_sec: i64,
+    end_sec: i64,
+) -> Result<HashMap<i64, Vec<String>>> {
+    let has_items: bool = conn.query_row(
+        r#"
+        SELECT COUNT(*) = 2 FROM sqlite_master
+        WHERE type = 'table' AND name IN ('prayer_items', 'prayer_session_items')
+        "#,
+        [],
+        |row| row.get(0),
+    )?;
+    if !has_items {
+        return Ok(HashMap::new());
+    }
+
     let query = r#"
-        SELECT week_str_from_sec(CAST(strftime('%s', started_at) AS INTEGER)) as week,
-               SUM(duration_minutes) as total_minutes
-        FROM prayer_sessions
-        WHERE started_at IS NOT NULL
-            AND ended_at IS NOT NULL
-            AND CAST(strftime('%s', started_at) AS INTEGER) >= ?1
-            AND CAST(strftime('%s', started_at) AS INTEGER) < ?2
-        GROUP BY week_str_from_sec(CAST(strftime('%s', started_at) AS INTEGER))
+        SELECT psi.prayer_session_id, pi.title
+        FROM prayer_session_items psi
+        JOIN prayer_items pi ON pi.id = psi.prayer_item_id
+        JOIN prayer_sessions ps ON ps.id = psi.prayer_session_id
+        WHERE CAST(strftime('%s', ps.started_at) AS INTEGER) >= ?1
+            AND CAST(strftime('%s', ps.started_at) AS INTEGER) < ?2
+        ORDER BY psi.prayer_session_id, psi.position
     "#;
 
     let mut stmt = conn.prepare(query)?;
-    let prayer_results = stmt
-        .query_map([start_sec, end_sec], |row| {
-            Ok((row.get::<_, String>(0)?, row.get::<_, f64>(1)?))
-        })?
-        .collect::<Result<HashMap<String, f64>, _>>()?;
+    let rows = stmt.query_map([start_sec, end_sec], |row| {
+        Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+    })?;
 
-    let results = period.build_results(prayer_results, |week_start, total_minutes| WeekStats {
-        week_start,
-        minutes: total_minutes,
-    });
+    let mut items: HashMap<i64, Vec<String>> = HashMap::new();
+    for row in rows {
+        let (session_id, title) = row?;
+        items.entry(session_id).or_default().push(title);
+    }
+    Ok(items)
+}
 
-    Ok(results)
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use statsutils::register_date_functions;
+
+    fn test_db(with_lists: bool) -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        register_date_functions(&conn).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE prayer_sessions (id INTEGER PRIMARY KEY, started_at TEXT, ended_at TEXT, duration_minutes REAL);",
+        )
+        .unwrap();
+        if with_lists {
+            conn.execute_batch(
+                "ALTER TABLE prayer_sessions ADD COLUMN prayer_list_id INTEGER;
+                 CREATE TABLE prayer_lists (id INTEGER PRIMARY KEY, name TEXT);
+                 INSERT INTO prayer_lists VALUES (1, 'Intercession'), (2, 'Thanksgiving');",
+            )
+            .unwrap();
+        }
+        conn
+    }
+
+    fn insert(conn: &Connection, started_at: &str, minutes: f64, list: Option<i64>) {
+        if let Some(list) = list {
+            conn.execute(
+                "INSERT INTO prayer_sessions (started_at, ended_at, duration_minutes, prayer_list_id) VALUES (?1, ?1, ?2, ?3)",
+                rusqlite::params![started_at, minutes, list],
+            )
+            .unwrap();
+        } else {
+            conn.execute(
+                "INSERT INTO prayer_sessions (started_at, ended_at, duration_minutes) VALUES (?1, ?1, ?2)",
+                rusqlite::params![started_at, minutes],
+            )
+            .unwrap();
+        }
+    }
+
+    fn june() -> DatePeriod {
+        DatePeriod::for_range(
+            NaiveDate::from_ymd_opt(2025, 6, 1).unwrap(),
+            NaiveDate::from_ymd_opt(2025, 6, 30).unwrap(),
+            Granularity::Day,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_prayer_dates_apply_rollover() {
+        let conn = test_db(false);
+        insert(&conn, "2025-06-01 14:00:00", 10.0, None);
+        // 3 AM in Chicago still counts as June 1st
+        insert(&conn, "2025-06-02 08:00:00", 5.0, None);
+        insert(&conn, "2025-06-03 14:00:00", 5.0, None);
+
+        let dates = get_prayer_dates(&conn).unwrap();
+        assert_eq!(
+            dates,
+            vec![
+                NaiveDate::from_ymd_opt(2025, 6, 1).unwrap(),
+                NaiveDate::from_ymd_opt(2025, 6, 3).unwrap()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_stats_by_category() {
+        let conn = test_db(true);
+        insert(&conn, "2025-06-10 12:00:00", 10.0, Some(1));
+        insert(&conn, "2025-06-11 12:00:00", 15.0, Some(1));
+        insert(&conn, "2025-06-11 13:00:00", 5.0, Some(2));
+        insert(&conn, "2025-06-12 12:00:00", 3.0, None);
+        // Outside the period
+        insert(&conn, "2025-07-02 12:00:00", 60.0, Some(2));
+
+        let stats = get_stats_by_category(&conn, &june()).unwrap();
+        let summary: Vec<(&str, f64, i64)> = stats
+            .iter()
+            .map(|s| (s.category.as_str(), s.minutes, s.sessions))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("Intercession", 25.0, 2),
+                ("Thanksgiving", 5.0, 1),
+                (UNCATEGORIZED, 3.0, 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_stats_without_prayer_lists() {
+        let conn = test_db(false);
+        insert(&conn, "2025-06-10 12:00:00", 10.0, None);
+
+        let days = get_period_stats_by_category(&conn, june(), Granularity::Day).unwrap();
+        assert_eq!(days.len(), 30);
+        let (_, categories) = days.iter().find(|(_, c)| !c.is_empty()).unwrap();
+        assert_eq!(categories.len(), 1);
+        assert_eq!(categories[0].category, UNCATEGORIZED);
+    }
+
+    #[test]
+    fn test_prayer_sessions_with_items() {
+        let conn = test_db(true);
+        conn.execute_batch(
+            "CREATE TABLE prayer_items (id INTEGER PRIMARY KEY, title TEXT);
+             CREATE TABLE prayer_session_items (prayer_session_id INTEGER, prayer_item_id INTEGER, position INTEGER);
+             INSERT INTO prayer_items VALUES (1, 'Family'), (2, 'Church'), (3, 'Missionaries');",
+        )
+        .unwrap();
+        insert(&conn, "2025-06-10 12:00:00", 10.0, Some(1));
+        insert(&conn, "2025-06-11 12:00:00", 5.0, None);
+        conn.execute_batch("INSERT INTO prayer_session_items VALUES (1, 3, 2), (1, 1, 1);")
+            .unwrap();
+
+        let sessions = get_prayer_sessions(&conn, &june()).unwrap();
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].category, "Intercession");
+        assert_eq!(sessions[0].items, vec!["Family", "Missionaries"]);
+        assert_eq!(sessions[1].category, UNCATEGORIZED);
+        assert!(sessions[1].items.is_empty());
+    }
 }
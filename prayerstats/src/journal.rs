@@ -0,0 +1,102 @@
+use crate::models::PrayerSession;
+
+/// Renders prayer sessions as a Markdown journal, one section per day
+///
+/// Each session becomes a bullet with its time, length, and prayer list, followed by the
+/// prayer items prayed for as nested bullets:
+///
+/// ```text
+/// # Prayer Journal
+///
+/// ## 2025-06-10
+///
+/// - **07:00–07:15** · 15 min · Intercession
+///   - Family
+///   - Missionaries
+/// ```
+pub fn to_markdown(title: &str, sessions: &[PrayerSession]) -> String {
+    let mut out = format!("# {}\n", title);
+
+    if sessions.is_empty() {
+        out.push_str("\nNo prayer sessions recorded.\n");
+        return out;
+    }
+
+    let mut current_date: Option<&str> = None;
+    for session in sessions {
+        if current_date != Some(session.date.as_str()) {
+            out.push_str(&format!("\n## {}\n\n", session.date));
+            current_date = Some(&session.date);
+        }
+
+        out.push_str(&format!(
+            "- **{}–{}** · {:.0} min · {}\n",
+            clock(&session.start_time),
+            clock(&session.end_time),
+            session.minutes,
+            session.category
+        ));
+        for item in &session.items {
+            out.push_str(&format!("  - {}\n", item));
+        }
+    }
+
+    let total_minutes: f64 = sessions.iter().map(|s| s.minutes).sum();
+    out.push_str(&format!(
+        "\n---\n\n{} sessions, {:.0} minutes in prayer\n",
+        sessions.len(),
+        total_minutes
+    ));
+    out
+}
+
+/// Time of day without the date, e.g. "07:15"
+fn clock(time: &str) -> &str {
+    time.get(11..16).unwrap_or(time)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(date: &str, start: &str, end: &str, minutes: f64, items: &[&str]) -> PrayerSession {
+        PrayerSession {
+            id: 0,
+            date: date.to_string(),
+            start_time: format!("{} {}:00", date, start),
+            end_time: format!("{} {}:00", date, end),
+            minutes,
+            category: "Intercession".to_string(),
+            items: items.iter().map(|i| i.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_to_markdown() {
+        let sessions = vec![
+            session("2025-06-10", "07:00", "07:15", 15.0, &["Family", "Church"]),
+            session("2025-06-10", "21:00", "21:05", 5.0, &[]),
+            session("2025-06-11", "07:00", "07:10", 10.0, &[]),
+        ];
+
+        let markdown = to_markdown("Prayer Journal", &sessions);
+        assert_eq!(
+            markdown,
+            "# Prayer Journal\n\
+             \n## 2025-06-10\n\n\
+             - **07:00–07:15** · 15 min · Intercession\n  - Family\n  - Church\n\
+             - **21:00–21:05** · 5 min · Intercession\n\
+             \n## 2025-06-11\n\n\
+             - **07:00–07:10** · 10 min · Intercession\n\
+             \n---\n\n3 sessions, 30 minutes in prayer\n"
+        );
+    }
+
+    #[test]
+    fn test_to_markdown_empty() {
+        assert_eq!(
+            to_markdown("Prayer Journal", &[]),
+            "# Prayer Journal\n\nNo prayer sessions recorded.\n"
+        );
+    }
+}
@@ -1,9 +1,21 @@
+pub mod csv_journal;
 pub mod db;
+pub mod journal;
+pub mod memory;
+pub mod merge;
 pub mod models;
+#[cfg(feature = "tokio")]
+pub mod nonblocking;
+pub mod schema;
+pub mod source;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use statsutils::{DatePeriod, Granularity, parse_local_date};
 
-pub use models::{DayStats, WeekStats};
+pub use models::{
+    CategoryStats, DayStats, MonthStats, PeriodStats, PrayerSession, PrayerStreaks, WeekStats,
+};
 
 /// Gets the total prayer time for today in minutes
 ///
@@ -77,3 +89,148 @@ pub fn get_last_12_weeks_stats(db_path: &str) -> Result<Vec<WeekStats>> {
     let conn = db::open_database(db_path)?;
     db::get_last_12_weeks_stats(&conn)
 }
+
+/// Gets prayer time for each of the last 12 months
+///
+/// # Arguments
+/// * `db_path` - Path to the Proseuche SQLite database file
+///
+/// # Returns
+/// Vector of MonthStats with month (YYYY-MM) and minutes for each of the last 12 months
+///
+/// # Errors
+/// Returns an error if the database cannot be opened or queried
+pub fn get_last_12_months_stats(db_path: &str) -> Result<Vec<MonthStats>> {
+    let conn = db::open_database(db_path)?;
+    db::get_last_12_months_stats(&conn)
+}
+
+/// Gets prayer time for an arbitrary date range
+///
+/// `start_date` and `end_date` are inclusive YYYY-MM-DD dates. The results contain one
+/// entry per day, week, or month depending on `granularity`, with zeros for empty buckets.
+///
+/// # Example
+/// ```ignore
+/// use prayerstats::get_stats_for_range;
+/// use statsutils::Granularity;
+///
+/// let stats = get_stats_for_range(
+///     "/path/to/database.sqlite",
+///     "2025-01-01",
+///     "2025-03-31",
+///     Granularity::Week,
+/// )?;
+/// ```
+pub fn get_stats_for_range(
+    db_path: &str,
+    start_date: &str,
+    end_date: &str,
+    granularity: Granularity,
+) -> Result<Vec<PeriodStats>> {
+    let start = parse_local_date(start_date).context("Invalid start date")?;
+    let end = parse_local_date(end_date).context("Invalid end date")?;
+
+    let conn = db::open_database(db_path)?;
+    let period = DatePeriod::for_range(start, end, granularity)?;
+    db::get_stats_for_period(&conn, period, granularity)
+}
+
+/// Gets prayer time for each bucket of a period, at the period's granularity, with zeros
+/// for empty buckets
+pub fn get_stats_for_period(db_path: &str, period: &DatePeriod) -> Result<Vec<PeriodStats>> {
+    let conn = db::open_database(db_path)?;
+    db::get_stats_for_period(&conn, period.clone(), period.granularity)
+}
+
+/// Gets prayer time and session count for each prayer list (intercession, thanksgiving,
+/// etc.) during a period
+///
+/// # Arguments
+/// * `db_path` - Path to the Proseuche SQLite database file
+/// * `period` - The period to report on
+///
+/// # Returns
+/// Vector of CategoryStats, most prayed first
+///
+/// # Errors
+/// Returns an error if the database cannot be opened or queried
+///
+/// # Example
+/// ```ignore
+/// use prayerstats::get_stats_by_category;
+/// use statsutils::DatePeriod;
+///
+/// let stats = get_stats_by_category("/path/to/database.sqlite", &DatePeriod::last_30_days()?)?;
+/// for category in stats {
+///     println!("{}: {:.1} minutes", category.category, category.minutes);
+/// }
+/// ```
+pub fn get_stats_by_category(db_path: &str, period: &DatePeriod) -> Result<Vec<CategoryStats>> {
+    let conn = db::open_database(db_path)?;
+    db::get_stats_by_category(&conn, period)
+}
+
+/// Gets prayer time per prayer list for each of the last 30 days
+///
+/// # Arguments
+/// * `db_path` - Path to the Proseuche SQLite database file
+///
+/// # Returns
+/// The categories prayed through on each of the last 30 days, keyed by date (YYYY-MM-DD),
+/// oldest first
+///
+/// # Errors
+/// Returns an error if the database cannot be opened or queried
+pub fn get_last_30_days_stats_by_category(
+    db_path: &str,
+) -> Result<Vec<(String, Vec<CategoryStats>)>> {
+    let conn = db::open_database(db_path)?;
+    db::get_period_stats_by_category(&conn, DatePeriod::last_30_days()?, Granularity::Day)
+}
+
+/// Gets every prayer session during a period, oldest first
+///
+/// # Arguments
+/// * `db_path` - Path to the Proseuche SQLite database file
+/// * `period` - The period to list sessions for
+///
+/// # Returns
+/// Vector of PrayerSession with start and end time, duration, prayer list, and the prayer
+/// items prayed for
+///
+/// # Errors
+/// Returns an error if the database cannot be opened or queried
+///
+/// # Example
+/// ```ignore
+/// use prayerstats::get_prayer_sessions;
+/// use statsutils::DatePeriod;
+///
+/// let sessions = get_prayer_sessions("/path/to/database.sqlite", &DatePeriod::last_30_days()?)?;
+/// for session in sessions {
+///     println!("{}: {:.1} minutes", session.start_time, session.minutes);
+/// }
+/// ```
+pub fn get_prayer_sessions(db_path: &str, period: &DatePeriod) -> Result<Vec<PrayerSession>> {
+    let conn = db::open_database(db_path)?;
+    db::get_prayer_sessions(&conn, period)
+}
+
+/// Gets every day with a prayer session, oldest first
+pub fn get_prayer_dates(db_path: &str) -> Result<Vec<NaiveDate>> {
+    let conn = db::open_database(db_path)?;
+    db::get_prayer_dates(&conn)
+}
+
+/// Gets the current and longest prayer streaks and the total number of days prayed
+///
+/// # Arguments
+/// * `db_path` - Path to the Proseuche SQLite database file
+///
+/// # Errors
+/// Returns an error if the database cannot be opened or queried
+pub fn get_prayer_streaks(db_path: &str) -> Result<PrayerStreaks> {
+    let conn = db::open_database(db_path)?;
+    db::get_prayer_streaks(&conn)
+}
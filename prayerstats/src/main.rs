@@ -1,21 +1,248 @@
-use clap::Parser;
+use clap::{Parser, Subcommand};
+use prayerstats::source::source_for;
+use prayerstats::{get_last_12_months_stats, get_stats_for_range, journal, models::PeriodStats};
+use statsutils::fmt::duration_minutes;
+use statsutils::{DatePeriod, Granularity, get_today_date};
+use std::process;
 
 #[derive(Parser)]
 #[command(name = "prayerstats")]
 #[command(about = "Analyze prayer statistics from prayer app databases", long_about = None)]
 #[command(version)]
 struct Cli {
-    // Future subcommands will be added here
-    // Examples:
+    #[command(subcommand)]
+    command: Commands,
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    /// List individual prayer sessions with their prayer items
+    Sessions {
+        /// Path to the Proseuche database file or CSV journal; separate several with commas
+        /// to merge them
+        #[arg(value_name = "DATABASE_PATH")]
+        db_path: String,
+        /// Number of days to list, ending today
+        #[arg(long, default_value_t = 7)]
+        days: i64,
+    },
+    /// Export prayer sessions as a Markdown journal
+    Journal {
+        /// Path to the Proseuche database file or CSV journal; separate several with commas
+        /// to merge them
+        #[arg(value_name = "DATABASE_PATH")]
+        db_path: String,
+        /// Number of days to export, ending today
+        #[arg(long, default_value_t = 30)]
+        days: i64,
+        /// File to write the journal to instead of printing it
+        #[arg(long, short)]
+        output: Option<String>,
+    },
+    /// Show prayer time for each of the last 12 months
+    Monthly {
+        /// Path to the Proseuche database file
+        #[arg(value_name = "DATABASE_PATH")]
+        db_path: String,
+    },
+    /// Show prayer time for a custom date range
+    Range {
+        /// First day of the range (YYYY-MM-DD)
+        start_date: String,
+        /// Last day of the range, inclusive (YYYY-MM-DD)
+        end_date: String,
+        /// Path to the Proseuche database file
+        #[arg(value_name = "DATABASE_PATH")]
+        db_path: String,
+        /// Bucket size: day, week, or month
+        #[arg(long, default_value = "day")]
+        granularity: Granularity,
+    },
+    // Future subcommands:
     // - Today: Show today's prayer time
     // - Daily: Show prayer time for last 30 days
     // - Weekly: Show prayer time for last 12 weeks
 }
 
 fn main() {
-    let _cli = Cli::parse();
+    let cli = Cli::parse();
+
+    // Timezone, rollover hour, and week start shared with the rest of the workspace
+    if let Err(e) = statsutils::configure_from_env() {
+        eprintln!("Error: {:#}", e);
+        process::exit(1);
+    }
+
+    match cli.command {
+        Commands::Sessions { db_path, days } => {
+            run_sessions_command(&db_path, days);
+        }
+        Commands::Journal {
+            db_path,
+            days,
+            output,
+        } => {
+            run_journal_command(&db_path, days, output.as_deref());
+        }
+        Commands::Monthly { db_path } => {
+            run_monthly_command(&db_path);
+        }
+        Commands::Range {
+            start_date,
+            end_date,
+            db_path,
+            granularity,
+        } => {
+            run_range_command(&start_date, &end_date, &db_path, granularity);
+        }
+    }
+}
+
+fn run_sessions_command(db_path: &str, days: i64) {
+    let result =
+        last_days_period(days).and_then(|period| source_for(db_path).get_prayer_sessions(&period));
+
+    match result {
+        Ok(sessions) => {
+            println!("\n=== PRAYER SESSIONS - LAST {} DAYS ===", days.max(1));
+
+            if sessions.is_empty() {
+                println!("\nNo prayer in this period");
+                return;
+            }
+
+            let mut current_date: Option<&str> = None;
+            for session in &sessions {
+                if current_date != Some(session.date.as_str()) {
+                    println!("\n{}", session.date);
+                    current_date = Some(&session.date);
+                }
+
+                // Times without the date, e.g. "07:15"
+                let clock = |time: &str| time.get(11..16).unwrap_or(time).to_string();
+                println!(
+                    "  {}-{}  {:>5.1} min  {}",
+                    clock(&session.start_time),
+                    clock(&session.end_time),
+                    session.minutes,
+                    session.category
+                );
+                if !session.items.is_empty() {
+                    println!("      {}", session.items.join(", "));
+                }
+            }
+
+            let total_minutes: f64 = sessions.iter().map(|s| s.minutes).sum();
+            println!("\n--- SUMMARY ---");
+            println!(
+                "Sessions: {} ({:.2} minutes total)",
+                sessions.len(),
+                total_minutes
+            );
+            println!();
+        }
+        Err(e) => {
+            eprintln!("Error: {:#}", e);
+            process::exit(1);
+        }
+    }
+}
+
+fn run_journal_command(db_path: &str, days: i64, output: Option<&str>) {
+    let result =
+        last_days_period(days).and_then(|period| source_for(db_path).get_prayer_sessions(&period));
+
+    match result {
+        Ok(sessions) => {
+            let title = format!("Prayer Journal - Last {} Days", days.max(1));
+            let markdown = journal::to_markdown(&title, &sessions);
+
+            match output {
+                Some(path) => {
+                    if let Err(e) = std::fs::write(path, markdown) {
+                        eprintln!("Error: Failed to write journal to '{}': {}", path, e);
+                        process::exit(1);
+                    }
+                    println!("Wrote {} prayer sessions to {}", sessions.len(), path);
+                }
+                None => print!("{}", markdown),
+            }
+        }
+        Err(e) => {
+            eprintln!("Error: {:#}", e);
+            process::exit(1);
+        }
+    }
+}
+
+fn run_monthly_command(db_path: &str) {
+    match get_last_12_months_stats(db_path) {
+        Ok(monthly_stats) => {
+            println!("\n=== MONTHLY PRAYER STATS - LAST 12 MONTHS ===\n");
+            let buckets: Vec<PeriodStats> = monthly_stats
+                .into_iter()
+                .map(|m| PeriodStats {
+                    period_start: m.month,
+                    minutes: m.minutes,
+                })
+                .collect();
+            print_buckets(&buckets, "month");
+        }
+        Err(e) => {
+            eprintln!("Error: {:#}", e);
+            process::exit(1);
+        }
+    }
+}
+
+fn run_range_command(start_date: &str, end_date: &str, db_path: &str, granularity: Granularity) {
+    match get_stats_for_range(db_path, start_date, end_date, granularity) {
+        Ok(range_stats) => {
+            println!(
+                "\n=== {} PRAYER STATS - {} TO {} ===\n",
+                granularity.to_string().to_uppercase(),
+                start_date,
+                end_date
+            );
+            print_buckets(&range_stats, &granularity.to_string());
+        }
+        Err(e) => {
+            eprintln!("Error: {:#}", e);
+            process::exit(1);
+        }
+    }
+}
+
+/// Prints prayer time per bucket followed by a summary
+fn print_buckets(buckets: &[PeriodStats], unit: &str) {
+    for bucket in buckets {
+        if bucket.minutes > 0.0 {
+            println!(
+                "{}: {:.2} min ({})",
+                bucket.period_start,
+                bucket.minutes,
+                duration_minutes(bucket.minutes)
+            );
+        } else {
+            println!("{}: --- (no prayer)", bucket.period_start);
+        }
+    }
+
+    let total_minutes: f64 = buckets.iter().map(|b| b.minutes).sum();
+    let active = buckets.iter().filter(|b| b.minutes > 0.0).count();
+    println!("\n--- SUMMARY ---");
+    println!(
+        "Prayer Time: {} ({:.2} minutes)",
+        duration_minutes(total_minutes),
+        total_minutes
+    );
+    println!("Active {}s: {} out of {}", unit, active, buckets.len());
+    println!();
+}
 
-    println!("prayerstats CLI - Coming soon!");
-    println!("\nThis CLI tool will provide commands for analyzing prayer statistics.");
-    println!("Subcommands are not yet implemented.");
+/// Daily period covering the last `days` days, ending today
+fn last_days_period(days: i64) -> anyhow::Result<DatePeriod> {
+    let today = get_today_date()?;
+    let start = today - chrono::Duration::days(days.max(1) - 1);
+    DatePeriod::for_range(start, today, Granularity::Day)
 }
@@ -0,0 +1,104 @@
+use anyhow::Result;
+use rusqlite::{Connection, params};
+use statsutils::register_date_functions;
+use std::collections::HashMap;
+
+/// Builds an in-memory database with the same tables as the current Proseuche schema, so
+/// sessions from other sources can be queried with the functions in `db`
+pub struct SessionStore {
+    conn: Connection,
+    lists: HashMap<String, i64>,
+    items: HashMap<String, i64>,
+}
+
+impl SessionStore {
+    pub fn new() -> Result<Self> {
+        let conn = Connection::open_in_memory()?;
+        register_date_functions(&conn)?;
+        conn.execute_batch(
+            r#"
+            CREATE TABLE prayer_lists (id INTEGER PRIMARY KEY, name TEXT UNIQUE);
+            CREATE TABLE prayer_items (id INTEGER PRIMARY KEY, title TEXT UNIQUE);
+            CREATE TABLE prayer_sessions (
+                id INTEGER PRIMARY KEY,
+                started_at DATETIME,
+                ended_at DATETIME,
+                prayer_list_id INTEGER,
+                duration_minutes REAL GENERATED ALWAYS AS
+                    ((julianday(ended_at) - julianday(started_at)) * 1440) VIRTUAL
+            );
+            CREATE TABLE prayer_session_items (
+                prayer_session_id INTEGER,
+                prayer_item_id INTEGER,
+                position INTEGER
+            );
+            "#,
+        )?;
+
+        Ok(Self {
+            conn,
+            lists: HashMap::new(),
+            items: HashMap::new(),
+        })
+    }
+
+    /// Adds a session given UTC start and end times formatted as Proseuche stores them
+    /// (YYYY-MM-DD HH:MM:SS)
+    pub fn add_session(
+        &mut self,
+        started_at: &str,
+        ended_at: &str,
+        category: Option<&str>,
+        items: &[String],
+    ) -> Result<()> {
+        let list_id = match category {
+            Some(name) => Some(intern(
+                &self.conn,
+                &mut self.lists,
+                "prayer_lists",
+                "name",
+                name,
+            )?),
+            None => None,
+        };
+
+        self.conn.execute(
+            "INSERT INTO prayer_sessions (started_at, ended_at, prayer_list_id) VALUES (?1, ?2, ?3)",
+            params![started_at, ended_at, list_id],
+        )?;
+        let session_id = self.conn.last_insert_rowid();
+
+        for (position, title) in items.iter().enumerate() {
+            let item_id = intern(&self.conn, &mut self.items, "prayer_items", "title", title)?;
+            self.conn.execute(
+                "INSERT INTO prayer_session_items VALUES (?1, ?2, ?3)",
+                params![session_id, item_id, position as i64],
+            )?;
+        }
+        Ok(())
+    }
+
+    pub fn into_connection(self) -> Connection {
+        self.conn
+    }
+}
+
+/// Id of the row named `name` in a lookup table, inserting it the first time it's seen
+fn intern(
+    conn: &Connection,
+    ids: &mut HashMap<String, i64>,
+    table: &str,
+    column: &str,
+    name: &str,
+) -> Result<i64> {
+    if let Some(id) = ids.get(name) {
+        return Ok(*id);
+    }
+    conn.execute(
+        &format!("INSERT INTO {} ({}) VALUES (?1)", table, column),
+        [name],
+    )?;
+    let id = conn.last_insert_rowid();
+    ids.insert(name.to_string(), id);
+    Ok(id)
+}
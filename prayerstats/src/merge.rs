@@ -0,0 +1,163 @@
+use anyhow::{Context, Result};
+use rusqlite::Connection;
+use std::collections::HashMap;
+
+use crate::db;
+use crate::memory::SessionStore;
+use crate::source::open_connection;
+
+/// A session read from one of the databases being merged
+struct MergedSession {
+    started_at: String,
+    ended_at: String,
+    category: Option<String>,
+    items: Vec<String>,
+}
+
+/// Opens several prayer databases as one, e.g. Proseuche backups from a phone and a tablet
+///
+/// Session ids are assigned per device, so the same session synced to two devices can
+/// have different ids while unrelated sessions share one. Sessions are therefore matched
+/// by start time (to the second): when several databases have a session starting at the
+/// same moment, the longest copy is kept.
+///
+/// # Arguments
+/// * `paths` - Proseuche databases or CSV journals to merge
+pub fn open_merged(paths: &[String]) -> Result<Connection> {
+    let mut sessions: Vec<MergedSession> = Vec::new();
+    let mut by_start: HashMap<String, usize> = HashMap::new();
+
+    for path in paths {
+        let conn = open_connection(path)?;
+        for session in read_sessions(&conn).context(format!("Failed to read '{}'", path))? {
+            match by_start.get(&session.started_at) {
+                Some(&index) => {
+                    if session.ended_at > sessions[index].ended_at {
+                        sessions[index] = session;
+                    }
+                }
+                None => {
+                    by_start.insert(session.started_at.clone(), sessions.len());
+                    sessions.push(session);
+                }
+            }
+        }
+    }
+
+    sessions.sort_by(|a, b| a.started_at.cmp(&b.started_at));
+
+    let mut store = SessionStore::new()?;
+    for session in &sessions {
+        store.add_session(
+            &session.started_at,
+            &session.ended_at,
+            session.category.as_deref(),
+            &session.items,
+        )?;
+    }
+    Ok(store.into_connection())
+}
+
+/// Reads every finished session in a database with its prayer list and items
+fn read_sessions(conn: &Connection) -> Result<Vec<MergedSession>> {
+    let category = db::category_expression(conn)?;
+    let query = format!(
+        r#"
+        SELECT ps.id,
+               strftime('%Y-%m-%d %H:%M:%S', ps.started_at),
+               strftime('%Y-%m-%d %H:%M:%S', ps.ended_at),
+               NULLIF({category}, '{uncategorized}')
+        FROM prayer_sessions ps
+        WHERE ps.started_at IS NOT NULL AND ps.ended_at IS NOT NULL
+        "#,
+        uncategorized = db::UNCATEGORIZED
+    );
+
+    let mut items = db::get_session_items(conn, i64::MIN, i64::MAX)?;
+    let mut stmt = conn.prepare(&query)?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, Option<String>>(3)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(id, started_at, ended_at, category)| MergedSession {
+            started_at,
+            ended_at,
+            category,
+            items: items.remove(&id).unwrap_or_default(),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use statsutils::{DatePeriod, Granularity};
+
+    fn write_db(path: &std::path::Path, sessions: &[(i64, &str, &str)]) {
+        let conn = Connection::open(path).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE prayer_sessions (id INTEGER PRIMARY KEY, started_at TEXT, ended_at TEXT,
+                duration_minutes REAL GENERATED ALWAYS AS ((julianday(ended_at) - julianday(started_at)) * 1440) VIRTUAL)",
+        )
+        .unwrap();
+        for (id, started_at, ended_at) in sessions {
+            conn.execute(
+                "INSERT INTO prayer_sessions (id, started_at, ended_at) VALUES (?1, ?2, ?3)",
+                rusqlite::params![id, started_at, ended_at],
+            )
+            .unwrap();
+        }
+    }
+
+    #[test]
+    fn test_merge_deduplicates_by_start_time() {
+        let dir = tempfile::tempdir().unwrap();
+        let phone = dir.path().join("phone.sqlite");
+        let tablet = dir.path().join("tablet.sqlite");
+        write_db(
+            &phone,
+            &[
+                (1, "2025-06-10 07:00:00", "2025-06-10 07:10:00"),
+                (2, "2025-06-11 07:00:00", "2025-06-11 07:05:00"),
+            ],
+        );
+        write_db(
+            &tablet,
+            &[
+                // Same session as the phone's first, synced with a different id and a
+                // later end
+                (7, "2025-06-10 07:00:00", "2025-06-10 07:12:00"),
+                // Different session that happens to reuse id 2
+                (2, "2025-06-12 21:00:00", "2025-06-12 21:20:00"),
+            ],
+        );
+
+        let paths = vec![
+            phone.to_str().unwrap().to_string(),
+            tablet.to_str().unwrap().to_string(),
+        ];
+        let conn = open_merged(&paths).unwrap();
+        let june = DatePeriod::for_range(
+            NaiveDate::from_ymd_opt(2025, 6, 1).unwrap(),
+            NaiveDate::from_ymd_opt(2025, 6, 30).unwrap(),
+            Granularity::Day,
+        )
+        .unwrap();
+        let minutes: Vec<f64> = db::get_prayer_sessions(&conn, &june)
+            .unwrap()
+            .iter()
+            .map(|s| s.minutes.round())
+            .collect();
+        assert_eq!(minutes, vec![12.0, 5.0, 20.0]);
+    }
+}
@@ -1,7 +1,9 @@
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
 /// Statistics for a single day
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[schema(as = PrayerDayStats)]
 pub struct DayStats {
     /// Date in YYYY-MM-DD format
     pub date: String,
@@ -10,10 +12,72 @@ pub struct DayStats {
 }
 
 /// Statistics for a single week
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[schema(as = PrayerWeekStats)]
 pub struct WeekStats {
-    /// Week start date (Sunday) in YYYY-MM-DD format
+    /// Week start date in YYYY-MM-DD format
     pub week_start: String,
     /// Total prayer time in minutes
     pub minutes: f64,
 }
+
+/// Statistics for a single month
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct MonthStats {
+    /// Month in YYYY-MM format
+    pub month: String,
+    /// Total prayer time in minutes
+    pub minutes: f64,
+}
+
+/// Statistics for one day, week, or month of an arbitrary range
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PeriodStats {
+    /// First day of the bucket: YYYY-MM-DD for days and weeks, YYYY-MM for months
+    pub period_start: String,
+    /// Total prayer time in minutes
+    pub minutes: f64,
+}
+
+/// Runs of consecutive days with any prayer
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PrayerStreaks {
+    /// Consecutive days prayed, ending today (or yesterday if not yet prayed today)
+    pub current_streak: i64,
+    pub longest_streak: i64,
+    /// All-time number of days with prayer
+    pub days_prayed: i64,
+    /// Most recent day with prayer in YYYY-MM-DD format
+    pub last_prayer_date: Option<String>,
+}
+
+/// Prayer time for one prayer list (category) over a period
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CategoryStats {
+    /// Name of the prayer list, e.g. "Intercession" or "Thanksgiving"; sessions not tied
+    /// to a list are reported as "Uncategorized"
+    pub category: String,
+    /// Total prayer time in minutes
+    pub minutes: f64,
+    /// Number of prayer sessions
+    pub sessions: i64,
+}
+
+/// A single prayer session
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PrayerSession {
+    /// Proseuche's id for the session
+    pub id: i64,
+    /// Date the session counts toward in YYYY-MM-DD format (days start at 4 AM)
+    pub date: String,
+    /// Local start time, YYYY-MM-DD HH:MM:SS
+    pub start_time: String,
+    /// Local end time, YYYY-MM-DD HH:MM:SS
+    pub end_time: String,
+    /// Prayer time in minutes
+    pub minutes: f64,
+    /// Prayer list prayed through, or "Uncategorized"
+    pub category: String,
+    /// Titles of the prayer items prayed for, in the order they were prayed
+    pub items: Vec<String>,
+}
@@ -0,0 +1,63 @@
+//! Async versions of the main entry points, run on tokio's blocking thread pool
+//!
+//! Each function takes the same arguments as its namesake in the crate root.
+
+use anyhow::Result;
+use statsutils::nonblocking::run;
+use statsutils::{DatePeriod, Granularity};
+
+use crate::models::{CategoryStats, DayStats, MonthStats, PeriodStats, PrayerStreaks, WeekStats};
+
+/// Async `crate::get_today_prayer_time`
+pub async fn get_today_prayer_time(db_path: &str) -> Result<f64> {
+    let db_path = db_path.to_string();
+    run(move || crate::get_today_prayer_time(&db_path)).await
+}
+
+/// Async `crate::get_last_30_days_stats`
+pub async fn get_last_30_days_stats(db_path: &str) -> Result<Vec<DayStats>> {
+    let db_path = db_path.to_string();
+    run(move || crate::get_last_30_days_stats(&db_path)).await
+}
+
+/// Async `crate::get_last_12_weeks_stats`
+pub async fn get_last_12_weeks_stats(db_path: &str) -> Result<Vec<WeekStats>> {
+    let db_path = db_path.to_string();
+    run(move || crate::get_last_12_weeks_stats(&db_path)).await
+}
+
+/// Async `crate::get_last_12_months_stats`
+pub async fn get_last_12_months_stats(db_path: &str) -> Result<Vec<MonthStats>> {
+    let db_path = db_path.to_string();
+    run(move || crate::get_last_12_months_stats(&db_path)).await
+}
+
+/// Async `crate::get_stats_for_range`
+pub async fn get_stats_for_range(
+    db_path: &str,
+    start_date: &str,
+    end_date: &str,
+    granularity: Granularity,
+) -> Result<Vec<PeriodStats>> {
+    let (db_path, start_date, end_date) = (
+        db_path.to_string(),
+        start_date.to_string(),
+        end_date.to_string(),
+    );
+    run(move || crate::get_stats_for_range(&db_path, &start_date, &end_date, granularity)).await
+}
+
+/// Async `crate::get_stats_by_category`
+pub async fn get_stats_by_category(
+    db_path: &str,
+    period: &DatePeriod,
+) -> Result<Vec<CategoryStats>> {
+    let (db_path, period) = (db_path.to_string(), period.clone());
+    run(move || crate::get_stats_by_category(&db_path, &period)).await
+}
+
+/// Async `crate::get_prayer_streaks`
+pub async fn get_prayer_streaks(db_path: &str) -> Result<PrayerStreaks> {
+    let db_path = db_path.to_string();
+    run(move || crate::get_prayer_streaks(&db_path)).await
+}
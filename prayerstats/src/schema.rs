@@ -0,0 +1,161 @@
+use anyhow::{Result, bail};
+use rusqlite::Connection;
+use std::fmt;
+
+/// Proseuche database layouts this crate can read, oldest first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SchemaVersion {
+    /// `prayer_sessions` with only start and end times
+    Sessions = 1,
+    /// Adds the computed `duration_minutes` column
+    ComputedDuration = 2,
+    /// Adds prayer lists (`prayer_list_id`) and the items prayed for in each session
+    PrayerLists = 3,
+}
+
+impl fmt::Display for SchemaVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let description = match self {
+            SchemaVersion::Sessions => "prayer sessions",
+            SchemaVersion::ComputedDuration => "computed session durations",
+            SchemaVersion::PrayerLists => "prayer lists and items",
+        };
+        write!(f, "{} ({})", *self as i32, description)
+    }
+}
+
+const SUPPORTED: [SchemaVersion; 3] = [
+    SchemaVersion::Sessions,
+    SchemaVersion::ComputedDuration,
+    SchemaVersion::PrayerLists,
+];
+
+/// Works out which version of the Proseuche schema a database uses from the shape of its
+/// `prayer_sessions` table
+///
+/// # Errors
+/// Returns an error listing the supported versions when the table is missing or lacks
+/// the start and end times every version has
+pub fn detect_schema(conn: &Connection) -> Result<SchemaVersion> {
+    let columns = session_columns(conn)?;
+    let has = |name: &str| columns.iter().any(|c| c == name);
+
+    if !(has("started_at") && has("ended_at")) {
+        let supported: Vec<String> = SUPPORTED.iter().map(|v| v.to_string()).collect();
+        let found = if columns.is_empty() {
+            "no prayer_sessions table".to_string()
+        } else {
+            format!("prayer_sessions columns: {}", columns.join(", "))
+        };
+        bail!(
+            "Unsupported Proseuche database schema ({}). Supported schema versions: {}",
+            found,
+            supported.join("; ")
+        );
+    }
+
+    Ok(if has("prayer_list_id") {
+        SchemaVersion::PrayerLists
+    } else if has("duration_minutes") {
+        SchemaVersion::ComputedDuration
+    } else {
+        SchemaVersion::Sessions
+    })
+}
+
+/// Adapts an older Proseuche database so the current queries can run against it
+///
+/// Databases without the `duration_minutes` column get a temporary `prayer_sessions` view
+/// computing it (and `id` from the rowid if needed); nothing is written to the database.
+pub fn adapt_schema(conn: &Connection) -> Result<SchemaVersion> {
+    let version = detect_schema(conn)?;
+    let columns = session_columns(conn)?;
+
+    if !columns.iter().any(|c| c == "duration_minutes") {
+        let id = if columns.iter().any(|c| c == "id") {
+            "id"
+        } else {
+            "rowid AS id"
+        };
+        let list = if version == SchemaVersion::PrayerLists {
+            ", prayer_list_id"
+        } else {
+            ""
+        };
+        conn.execute_batch(&format!(
+            r#"
+            CREATE TEMP VIEW prayer_sessions AS
+            SELECT
+                {id},
+                started_at,
+                ended_at{list},
+                (julianday(ended_at) - julianday(started_at)) * 1440 AS duration_minutes
+            FROM main.prayer_sessions
+            "#
+        ))?;
+    }
+
+    Ok(version)
+}
+
+fn session_columns(conn: &Connection) -> Result<Vec<String>> {
+    let mut stmt = conn.prepare("SELECT name FROM pragma_table_info('prayer_sessions', 'main')")?;
+    let names = stmt
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<String>, _>>()?;
+    Ok(names)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_versions() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("CREATE TABLE prayer_sessions (started_at TEXT, ended_at TEXT)")
+            .unwrap();
+        assert_eq!(detect_schema(&conn).unwrap(), SchemaVersion::Sessions);
+
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE prayer_sessions (id INTEGER PRIMARY KEY, started_at TEXT, ended_at TEXT, prayer_list_id INTEGER,
+                duration_minutes REAL GENERATED ALWAYS AS ((julianday(ended_at) - julianday(started_at)) * 1440) VIRTUAL)",
+        )
+        .unwrap();
+        assert_eq!(detect_schema(&conn).unwrap(), SchemaVersion::PrayerLists);
+    }
+
+    #[test]
+    fn test_adapt_sessions_without_duration() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE prayer_sessions (started_at TEXT, ended_at TEXT);
+             INSERT INTO prayer_sessions VALUES ('2025-06-10 07:00:00', '2025-06-10 07:15:00');",
+        )
+        .unwrap();
+
+        assert_eq!(adapt_schema(&conn).unwrap(), SchemaVersion::Sessions);
+        let (id, minutes): (i64, f64) = conn
+            .query_row(
+                "SELECT id, duration_minutes FROM prayer_sessions",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(id, 1);
+        assert!((minutes - 15.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_unknown_schema_lists_supported_versions() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("CREATE TABLE prayers (text TEXT)")
+            .unwrap();
+
+        let err = detect_schema(&conn).unwrap_err().to_string();
+        assert!(err.contains("no prayer_sessions table"));
+        assert!(err.contains("1 (prayer sessions)"));
+        assert!(err.contains("3 (prayer lists and items)"));
+    }
+}
@@ -0,0 +1,221 @@
+use anyhow::Result;
+use chrono::NaiveDate;
+use rusqlite::Connection;
+use statsutils::{DatePeriod, Granularity};
+
+use crate::models::{
+    CategoryStats, DayStats, PeriodStats, PrayerSession, PrayerStreaks, WeekStats,
+};
+use crate::{csv_journal, db, merge};
+
+/// A source of prayer time
+///
+/// Proseuche's database is the primary source; a CSV journal lets users of other prayer
+/// apps, or anyone keeping a log by hand, feed the same stats.
+pub trait PrayerSource {
+    /// Name of the data format, e.g. "Proseuche" or "CSV journal"
+    fn format(&self) -> String;
+
+    /// Gets the total prayer time for today in minutes
+    fn get_today_prayer_time(&self) -> Result<f64>;
+
+    /// Gets prayer time for each of the last 30 days
+    fn get_last_30_days_stats(&self) -> Result<Vec<DayStats>>;
+
+    /// Gets prayer time for each of the last 12 weeks
+    fn get_last_12_weeks_stats(&self) -> Result<Vec<WeekStats>>;
+
+    /// Gets prayer time for each bucket of a period, at the period's granularity
+    fn get_stats_for_period(&self, period: &DatePeriod) -> Result<Vec<PeriodStats>>;
+
+    /// Gets prayer time per prayer list for each of the last 30 days, keyed by date
+    fn get_last_30_days_stats_by_category(&self) -> Result<Vec<(String, Vec<CategoryStats>)>>;
+
+    /// Gets every prayer session during a period, oldest first
+    fn get_prayer_sessions(&self, period: &DatePeriod) -> Result<Vec<PrayerSession>>;
+
+    /// Gets the current and longest prayer streaks
+    fn get_prayer_streaks(&self) -> Result<PrayerStreaks>;
+
+    /// Gets every day with a prayer session, oldest first
+    fn get_prayer_dates(&self) -> Result<Vec<NaiveDate>>;
+}
+
+/// Reads a Proseuche database.sqlite
+pub struct ProseucheSource {
+    db_path: String,
+}
+
+impl ProseucheSource {
+    pub fn new(db_path: &str) -> Self {
+        Self {
+            db_path: db_path.to_string(),
+        }
+    }
+}
+
+impl PrayerSource for ProseucheSource {
+    fn format(&self) -> String {
+        "Proseuche".to_string()
+    }
+
+    fn get_today_prayer_time(&self) -> Result<f64> {
+        crate::get_today_prayer_time(&self.db_path)
+    }
+
+    fn get_last_30_days_stats(&self) -> Result<Vec<DayStats>> {
+        crate::get_last_30_days_stats(&self.db_path)
+    }
+
+    fn get_last_12_weeks_stats(&self) -> Result<Vec<WeekStats>> {
+        crate::get_last_12_weeks_stats(&self.db_path)
+    }
+
+    fn get_stats_for_period(&self, period: &DatePeriod) -> Result<Vec<PeriodStats>> {
+        crate::get_stats_for_period(&self.db_path, period)
+    }
+
+    fn get_last_30_days_stats_by_category(&self) -> Result<Vec<(String, Vec<CategoryStats>)>> {
+        crate::get_last_30_days_stats_by_category(&self.db_path)
+    }
+
+    fn get_prayer_sessions(&self, period: &DatePeriod) -> Result<Vec<PrayerSession>> {
+        crate::get_prayer_sessions(&self.db_path, period)
+    }
+
+    fn get_prayer_streaks(&self) -> Result<PrayerStreaks> {
+        crate::get_prayer_streaks(&self.db_path)
+    }
+
+    fn get_prayer_dates(&self) -> Result<Vec<NaiveDate>> {
+        crate::get_prayer_dates(&self.db_path)
+    }
+}
+
+/// Reads a CSV prayer journal (see `csv_journal::open_journal` for the format)
+pub struct CsvJournalSource {
+    path: String,
+}
+
+impl CsvJournalSource {
+    pub fn new(path: &str) -> Self {
+        Self {
+            path: path.to_string(),
+        }
+    }
+}
+
+impl ConnectionSource for CsvJournalSource {
+    fn format(&self) -> String {
+        "CSV journal".to_string()
+    }
+
+    fn open(&self) -> Result<Connection> {
+        csv_journal::open_journal(&self.path)
+    }
+}
+
+/// Reads several databases or journals as one, e.g. backups from more than one device
+/// (see `merge::open_merged`)
+pub struct MergedSource {
+    paths: Vec<String>,
+}
+
+impl MergedSource {
+    pub fn new(paths: Vec<String>) -> Self {
+        Self { paths }
+    }
+}
+
+impl ConnectionSource for MergedSource {
+    fn format(&self) -> String {
+        format!("{} merged files", self.paths.len())
+    }
+
+    fn open(&self) -> Result<Connection> {
+        merge::open_merged(&self.paths)
+    }
+}
+
+/// A source loaded into a Proseuche-shaped connection, queried with the functions in `db`
+trait ConnectionSource {
+    fn format(&self) -> String;
+
+    fn open(&self) -> Result<Connection>;
+}
+
+impl<T: ConnectionSource> PrayerSource for T {
+    fn format(&self) -> String {
+        ConnectionSource::format(self)
+    }
+
+    fn get_today_prayer_time(&self) -> Result<f64> {
+        db::get_today_prayer_minutes(&self.open()?)
+    }
+
+    fn get_last_30_days_stats(&self) -> Result<Vec<DayStats>> {
+        db::get_last_30_days_stats(&self.open()?)
+    }
+
+    fn get_last_12_weeks_stats(&self) -> Result<Vec<WeekStats>> {
+        db::get_last_12_weeks_stats(&self.open()?)
+    }
+
+    fn get_stats_for_period(&self, period: &DatePeriod) -> Result<Vec<PeriodStats>> {
+        db::get_stats_for_period(&self.open()?, period.clone(), period.granularity)
+    }
+
+    fn get_last_30_days_stats_by_category(&self) -> Result<Vec<(String, Vec<CategoryStats>)>> {
+        db::get_period_stats_by_category(
+            &self.open()?,
+            DatePeriod::last_30_days()?,
+            Granularity::Day,
+        )
+    }
+
+    fn get_prayer_sessions(&self, period: &DatePeriod) -> Result<Vec<PrayerSession>> {
+        db::get_prayer_sessions(&self.open()?, period)
+    }
+
+    fn get_prayer_streaks(&self) -> Result<PrayerStreaks> {
+        db::get_prayer_streaks(&self.open()?)
+    }
+
+    fn get_prayer_dates(&self) -> Result<Vec<NaiveDate>> {
+        db::get_prayer_dates(&self.open()?)
+    }
+}
+
+/// Opens a single Proseuche database or CSV journal as a Proseuche-shaped connection
+pub(crate) fn open_connection(path: &str) -> Result<Connection> {
+    if is_csv(path) {
+        csv_journal::open_journal(path)
+    } else {
+        db::open_database(path)
+    }
+}
+
+fn is_csv(path: &str) -> bool {
+    std::path::Path::new(path)
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("csv"))
+}
+
+/// Picks a source for a path: `.csv` files are read as journals, anything else as a
+/// Proseuche database. Several paths separated by commas are merged into one source.
+pub fn source_for(path: &str) -> Box<dyn PrayerSource> {
+    let paths: Vec<String> = path
+        .split(',')
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .map(String::from)
+        .collect();
+
+    if paths.len() > 1 {
+        Box::new(MergedSource::new(paths))
+    } else if is_csv(path) {
+        Box::new(CsvJournalSource::new(path))
+    } else {
+        Box::new(ProseucheSource::new(path))
+    }
+}
@@ -0,0 +1,51 @@
+use prayerstats::{
+    get_last_30_days_stats, get_prayer_sessions, get_stats_for_period, get_today_prayer_time,
+};
+use statsutils::testing::ProseucheFixture;
+use statsutils::{DatePeriod, Granularity, get_today_date, get_today_start_ms};
+
+/// Builds a Proseuche database with two sessions today and checks totals and session
+/// details end to end
+#[test]
+fn test_stats_from_built_database() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("database.sqlite");
+    // Early in the logical day, so both sessions fall on today whenever the test runs
+    let start_ms = get_today_start_ms().unwrap();
+
+    let mut proseuche = ProseucheFixture::create(&path).unwrap();
+    let intercession = proseuche.list("Intercession").unwrap();
+    let family = proseuche.item("Family").unwrap();
+    let church = proseuche.item("Church").unwrap();
+    proseuche
+        .session(
+            Some(intercession),
+            start_ms + 60_000,
+            5.0,
+            &[family, church],
+        )
+        .unwrap();
+    proseuche
+        .session(None, start_ms + 600_000, 2.0, &[])
+        .unwrap();
+
+    let db_path = path.to_str().unwrap();
+    assert!((get_today_prayer_time(db_path).unwrap() - 7.0).abs() < 0.01);
+
+    let days = get_last_30_days_stats(db_path).unwrap();
+    assert_eq!(days.len(), 30);
+    assert!((days.last().unwrap().minutes - 7.0).abs() < 0.01);
+
+    let today = get_today_date().unwrap();
+    let period = DatePeriod::for_range(today, today, Granularity::Day).unwrap();
+    let sessions = get_prayer_sessions(db_path, &period).unwrap();
+    assert_eq!(sessions.len(), 2);
+    assert_eq!(sessions[0].category, "Intercession");
+    assert_eq!(sessions[0].items, ["Family", "Church"]);
+
+    let month = DatePeriod::for_range(today, today, Granularity::Month).unwrap();
+    let months = get_stats_for_period(db_path, &month).unwrap();
+    assert_eq!(months.len(), 1);
+    assert_eq!(months[0].period_start, today.format("%Y-%m").to_string());
+    assert!((months[0].minutes - 7.0).abs() < 0.01);
+}
@@ -0,0 +1,147 @@
+use std::sync::RwLock;
+
+/// Title patterns matching the books counted as Bible reading by default
+pub const DEFAULT_BIBLE_TITLE_PATTERNS: [&str; 2] = ["*Bible*", "Treasury of Daily Prayer*"];
+
+/// Identifies which KOReader books count as "Bible reading"
+///
+/// A book matches when its title matches any of `title_patterns` or its MD5 is one of
+/// `md5s`. Patterns are case-insensitive globs where `*` matches any run of characters
+/// and `?` matches a single character.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BibleBookMatcher {
+    pub title_patterns: Vec<String>,
+    /// Partial MD5 checksums KOReader records for each book, for files whose titles
+    /// don't identify them
+    pub md5s: Vec<String>,
+}
+
+impl Default for BibleBookMatcher {
+    fn default() -> Self {
+        Self {
+            title_patterns: DEFAULT_BIBLE_TITLE_PATTERNS
+                .iter()
+                .map(|p| p.to_string())
+                .collect(),
+            md5s: Vec::new(),
+        }
+    }
+}
+
+impl BibleBookMatcher {
+    /// Builds a matcher from comma-separated lists, as given in environment variables
+    ///
+    /// The default title patterns are kept when `titles` is `None`.
+    pub fn from_comma_lists(titles: Option<&str>, md5s: Option<&str>) -> Self {
+        let split = |list: &str| {
+            list.split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(str::to_string)
+                .collect()
+        };
+        Self {
+            title_patterns: titles.map_or_else(|| Self::default().title_patterns, split),
+            md5s: md5s.map(split).unwrap_or_default(),
+        }
+    }
+
+    /// Whether a book with this title and MD5 counts as Bible reading
+    pub fn matches(&self, title: &str, md5: Option<&str>) -> bool {
+        self.title_patterns
+            .iter()
+            .any(|pattern| glob_matches(pattern, title))
+            || md5.is_some_and(|md5| self.md5s.iter().any(|m| m.eq_ignore_ascii_case(md5)))
+    }
+}
+
+/// Case-insensitive glob match of the whole of `text` against `pattern`
+fn glob_matches(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+
+    // Backtrack to just after the most recent `*` on a mismatch
+    let (mut p, mut t) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match star {
+                Some((star_p, star_t)) => {
+                    p = star_p + 1;
+                    t = star_t + 1;
+                    star = Some((star_p, star_t + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+static BIBLE_BOOKS: RwLock<Option<BibleBookMatcher>> = RwLock::new(None);
+
+/// Sets which books count as Bible reading for databases opened afterwards
+pub fn set_bible_books(matcher: BibleBookMatcher) {
+    *BIBLE_BOOKS.write().unwrap_or_else(|e| e.into_inner()) = Some(matcher);
+}
+
+/// Returns the matcher currently in use
+pub fn bible_books() -> BibleBookMatcher {
+    BIBLE_BOOKS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glob_matches() {
+        assert!(glob_matches("*Bible*", "The ESV Study bible"));
+        assert!(glob_matches(
+            "Treasury of Daily Prayer*",
+            "Treasury of Daily Prayer"
+        ));
+        assert!(!glob_matches(
+            "Treasury of Daily Prayer*",
+            "A Treasury of Daily Prayer"
+        ));
+        assert!(glob_matches(
+            "Biblia ?ebraica*",
+            "Biblia Hebraica Stuttgartensia"
+        ));
+        assert!(glob_matches("*a*b", "aXbYb"));
+        assert!(!glob_matches("*a*b", "aXbYc"));
+        assert!(glob_matches("*", ""));
+    }
+
+    #[test]
+    fn test_matches_title_or_md5() {
+        let matcher = BibleBookMatcher {
+            title_patterns: vec!["NKJV*".to_string()],
+            md5s: vec!["0123ABCD".to_string()],
+        };
+        assert!(matcher.matches("NKJV Study Bible", None));
+        assert!(matcher.matches("scan_0042", Some("0123abcd")));
+        assert!(!matcher.matches("ESV Bible", Some("ffff")));
+
+        let from_env = BibleBookMatcher::from_comma_lists(None, Some(" 0123abcd, ,"));
+        assert_eq!(from_env.title_patterns, DEFAULT_BIBLE_TITLE_PATTERNS);
+        assert_eq!(from_env.md5s, ["0123abcd"]);
+
+        let default = BibleBookMatcher::default();
+        assert!(default.matches("ESV Bible", None));
+        assert!(!default.matches("The Hobbit", None));
+    }
+}
@@ -1,9 +1,24 @@
 use anyhow::{Context, Result};
-use rusqlite::{Connection, OpenFlags};
-use statsutils::{DatePeriod, get_today_start_ms, register_date_functions};
-use std::collections::HashMap;
+use chrono::NaiveDate;
+use rusqlite::Connection;
+use rusqlite::functions::FunctionFlags;
+use statsutils::series::densify;
+use statsutils::sqlite::{OpenOptions, open_readonly_with_retry};
+use statsutils::{DatePeriod, PeriodConfig, get_today_start_ms};
+use std::collections::{BTreeSet, HashMap, HashSet};
 
-use crate::models::{DayStats, WeekStats};
+use crate::models::{
+    BookInProgress, BookReadingStats, BooksFinished, DayStats, FinishedBook, MonthBooksFinished,
+    MonthStats, PeriodStats, ReadingSession, WeekStats, pages_per_hour,
+};
+use crate::{config, schema};
+
+/// Share of a book's pages that must have been read for it to count as finished,
+/// allowing for skipped front matter, indexes, and end notes
+pub const FINISHED_PERCENT: f64 = 95.0;
+
+/// Longest pause between page turns, in seconds, that still counts as the same session
+pub const SESSION_GAP_SECONDS: i64 = 30 * 60;
 
 /// Opens a connection to a KOReader statistics database in read-only mode
 ///
@@ -19,58 +34,185 @@ use crate::models::{DayStats, WeekStats};
 /// - `page_stat_data`: Raw reading session data (id_book, page, start_time, duration, total_pages)
 /// - `page_stat`: Normalized view of reading statistics
 /// - `numbers`: Helper table for views
+///
+/// Databases from older KOReader releases are adapted with temporary views (see
+/// `schema::adapt_schema`) so the same queries work against them.
 pub fn open_database(path: &str) -> Result<Connection> {
-    let conn = Connection::open_with_flags(
-        path,
-        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
-    )
-    .context("Failed to open KOReader statistics database in read-only mode")?;
+    // Retries while KOReader holds a lock, and registers date functions from statsutils
+    let conn = open_readonly_with_retry(path, &OpenOptions::new("KOReader statistics database"))?;
 
-    // Register date functions from statsutils
-    register_date_functions(&conn)?;
+    schema::adapt_schema(&conn).context(format!("Can't read '{}'", path))?;
+
+    register_bible_function(&conn)?;
 
     Ok(conn)
 }
 
-/// Gets reading time for each of the last 30 days for Bible and Treasury of Daily Prayer books
+/// Registers the `is_bible_book(title, md5)` SQLite function, which applies the
+/// configured `BibleBookMatcher`
+pub(crate) fn register_bible_function(conn: &Connection) -> Result<()> {
+    let bible_books = config::bible_books();
+    conn.create_scalar_function(
+        "is_bible_book",
+        2, // number of arguments: title, md5
+        FunctionFlags::SQLITE_UTF8 | FunctionFlags::SQLITE_DETERMINISTIC,
+        move |ctx| {
+            let title = ctx.get::<Option<String>>(0)?.unwrap_or_default();
+            let md5 = ctx.get::<Option<String>>(1)?;
+            Ok(bible_books.matches(&title, md5.as_deref()))
+        },
+    )
+    .context("Failed to register is_bible_book SQLite function")?;
+    Ok(())
+}
+
+/// Bible reading time and pages for one bucket of a period, plus time spent on every book
+struct BucketReading {
+    key: String,
+    minutes: f64,
+    all_books_minutes: f64,
+    pages: i64,
+}
+
+/// A bucket's Bible minutes, minutes across all books, and distinct Bible pages read (book
+/// and page)
+type BucketTotals = (f64, f64, HashSet<(i64, i64)>);
+
+/// Queries Bible reading time and distinct pages read for every bucket of a period, along
+/// with reading time across all books
+///
+/// A page read that runs past a bucket boundary (such as the rollover hour) has its time
+/// split across the buckets it overlaps; the page itself counts where the read started.
+fn get_period_reading(conn: &Connection, period: DatePeriod) -> Result<Vec<BucketReading>> {
+    let config = PeriodConfig::default();
+
+    // Convert milliseconds to seconds for KOReader database (uses Unix seconds)
+    let start_sec = period.start_ms / 1000;
+    let end_sec = period.end_ms / 1000;
+
+    // Includes reads that started before the period but ran into it
+    let query = r#"
+        SELECT psd.id_book, psd.page, psd.start_time, psd.duration,
+            is_bible_book(b.title, b.md5)
+        FROM page_stat_data psd
+        JOIN book b ON b.id = psd.id_book
+        WHERE psd.start_time + psd.duration > ?1
+            AND psd.start_time < ?2
+    "#;
+
+    let mut stmt = conn.prepare(query)?;
+    let mut rows = stmt.query([start_sec, end_sec])?;
+
+    let mut buckets: HashMap<String, BucketTotals> = HashMap::new();
+    while let Some(row) = rows.next()? {
+        let book_id: i64 = row.get(0)?;
+        let page: i64 = row.get(1)?;
+        let start_time: i64 = row.get(2)?;
+        let duration: i64 = row.get(3)?;
+        let is_bible: bool = row.get(4)?;
+
+        let end_time = start_time + duration;
+        for (key, minutes) in period.split_interval(start_time * 1000, end_time * 1000, &config)? {
+            let bucket = buckets.entry(key).or_default();
+            bucket.1 += minutes;
+            if is_bible {
+                bucket.0 += minutes;
+            }
+        }
+        if is_bible && start_time >= start_sec {
+            let date = config.logical_date(start_time * 1000)?;
+            let key = period.granularity.key(date, &config);
+            buckets.entry(key).or_default().2.insert((book_id, page));
+        }
+    }
+
+    Ok(
+        densify(buckets, &period)?.map(|key, (minutes, all_books_minutes, pages)| BucketReading {
+            key,
+            minutes,
+            all_books_minutes,
+            pages: pages.len() as i64,
+        }),
+    )
+}
+
+/// Gets reading time for each of the last 30 days for books counted as Bible reading
+/// (see `config::set_bible_books`)
 ///
 /// # Arguments
 /// * `conn` - Database connection to KOReader statistics database
 ///
 /// # Returns
-/// Vector of DayStats with date and minutes for each of the last 30 days
+/// Vector of DayStats with date, minutes, and pages read for each of the last 30 days
 pub fn get_last_30_days_stats(conn: &Connection) -> Result<Vec<DayStats>> {
-    // Get the period data for the last 30 days
-    let period = DatePeriod::last_30_days()?;
+    let results = get_period_reading(conn, DatePeriod::last_30_days()?)?;
 
-    // Convert milliseconds to seconds for KOReader database (uses Unix seconds)
-    let start_sec = period.start_ms / 1000;
-    let end_sec = period.end_ms / 1000;
+    Ok(results
+        .into_iter()
+        .map(|bucket| DayStats {
+            date: bucket.key,
+            minutes: bucket.minutes,
+            all_books_minutes: bucket.all_books_minutes,
+            pages: bucket.pages,
+            pages_per_hour: pages_per_hour(bucket.pages, bucket.minutes),
+        })
+        .collect())
+}
 
-    // Query reading time grouped by date
+/// Gets the minutes of Bible reading on every day with any, all-time
+pub fn get_daily_bible_minutes(conn: &Connection) -> Result<Vec<(NaiveDate, f64)>> {
     let query = r#"
         SELECT date_str_from_sec(psd.start_time) as date, SUM(psd.duration) as total_seconds
         FROM page_stat_data psd
         JOIN book b ON b.id = psd.id_book
-        WHERE (b.title LIKE '%Bible%' OR b.title LIKE 'Treasury of Daily Prayer%')
-            AND psd.start_time >= ?1
-            AND psd.start_time < ?2
+        WHERE is_bible_book(b.title, b.md5)
         GROUP BY date_str_from_sec(psd.start_time)
+        ORDER BY date
     "#;
 
     let mut stmt = conn.prepare(query)?;
-    let reading_results = stmt
-        .query_map([start_sec, end_sec], |row| {
+    let rows = stmt
+        .query_map([], |row| {
             Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
         })?
-        .collect::<Result<HashMap<String, i64>, _>>()?;
+        .collect::<Result<Vec<(String, i64)>, _>>()?;
+
+    rows.into_iter()
+        .map(|(date, total_seconds)| {
+            let date = NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+                .context(format!("Invalid reading date '{}'", date))?;
+            Ok((date, total_seconds as f64 / 60.0))
+        })
+        .collect()
+}
+
+/// Gets the minutes spent in each Bible book (by title) during a period, most read first
+pub fn get_bible_title_minutes(
+    conn: &Connection,
+    period: &DatePeriod,
+) -> Result<Vec<(String, f64)>> {
+    let query = r#"
+        SELECT b.title, SUM(psd.duration) as total_seconds
+        FROM page_stat_data psd
+        JOIN book b ON b.id = psd.id_book
+        WHERE is_bible_book(b.title, b.md5)
+            AND psd.start_time >= ?1
+            AND psd.start_time < ?2
+        GROUP BY b.id
+        ORDER BY total_seconds DESC, b.title
+    "#;
 
-    let results = period.build_results(reading_results, |date, total_seconds| DayStats {
-        date,
-        minutes: total_seconds as f64 / 60.0,
-    });
+    let mut stmt = conn.prepare(query)?;
+    let titles = stmt
+        .query_map([period.start_ms / 1000, period.end_ms / 1000], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, i64>(1)? as f64 / 60.0,
+            ))
+        })?
+        .collect::<Result<Vec<(String, f64)>, _>>()?;
 
-    Ok(results)
+    Ok(titles)
 }
 
 /// Gets the total reading time for today in minutes
@@ -82,7 +224,7 @@ pub fn get_today_reading_minutes(conn: &Connection) -> Result<f64> {
         SELECT COALESCE(SUM(psd.duration), 0) as total_seconds
         FROM page_stat_data psd
         JOIN book b ON b.id = psd.id_book
-        WHERE (b.title LIKE '%Bible%' OR b.title LIKE 'Treasury of Daily Prayer%')
+        WHERE is_bible_book(b.title, b.md5)
             AND psd.start_time >= ?1
     "#;
 
@@ -94,35 +236,350 @@ pub fn get_today_reading_minutes(conn: &Connection) -> Result<f64> {
 
 /// Gets reading time for each of the last 12 weeks
 pub fn get_last_12_weeks_stats(conn: &Connection) -> Result<Vec<WeekStats>> {
-    // Get the period data for the last 12 weeks
-    let period = DatePeriod::last_12_weeks()?;
+    let results = get_period_reading(conn, DatePeriod::last_12_weeks()?)?;
+
+    Ok(results
+        .into_iter()
+        .map(|bucket| WeekStats {
+            week_start: bucket.key,
+            minutes: bucket.minutes,
+            all_books_minutes: bucket.all_books_minutes,
+            pages: bucket.pages,
+            pages_per_hour: pages_per_hour(bucket.pages, bucket.minutes),
+        })
+        .collect())
+}
 
+/// Gets Bible reading time for each bucket of a period, at the period's granularity
+pub fn get_stats_for_period(conn: &Connection, period: DatePeriod) -> Result<Vec<PeriodStats>> {
+    let results = get_period_reading(conn, period)?;
+
+    Ok(results
+        .into_iter()
+        .map(|bucket| PeriodStats {
+            period_start: bucket.key,
+            minutes: bucket.minutes,
+            pages: bucket.pages,
+            pages_per_hour: pages_per_hour(bucket.pages, bucket.minutes),
+        })
+        .collect())
+}
+
+/// Gets reading time for each month of a period
+fn get_monthly_stats(conn: &Connection, period: DatePeriod) -> Result<Vec<MonthStats>> {
+    let results = get_period_reading(conn, period)?;
+
+    Ok(results
+        .into_iter()
+        .map(|bucket| MonthStats {
+            month: bucket.key,
+            minutes: bucket.minutes,
+            pages: bucket.pages,
+            pages_per_hour: pages_per_hour(bucket.pages, bucket.minutes),
+        })
+        .collect())
+}
+
+/// Gets reading time for each of the last 12 months
+pub fn get_last_12_months_stats(conn: &Connection) -> Result<Vec<MonthStats>> {
+    get_monthly_stats(conn, DatePeriod::last_12_months()?)
+}
+
+/// Gets reading time for each month of a calendar year
+pub fn get_year_stats(conn: &Connection, year: i32) -> Result<Vec<MonthStats>> {
+    get_monthly_stats(conn, DatePeriod::months_of_year(year)?)
+}
+
+/// A run of page reads in one book without a pause longer than `SESSION_GAP_SECONDS`
+struct RawSession {
+    book_id: i64,
+    title: String,
+    authors: String,
+    is_bible: bool,
+    start_time: i64,
+    end_time: i64,
+    seconds: i64,
+    pages: BTreeSet<i64>,
+}
+
+/// Reconstructs reading sessions from the page reads that started during a period
+fn query_sessions(conn: &Connection, period: &DatePeriod) -> Result<Vec<RawSession>> {
     // Convert milliseconds to seconds for KOReader database (uses Unix seconds)
     let start_sec = period.start_ms / 1000;
     let end_sec = period.end_ms / 1000;
 
-    // Query reading time grouped by week
     let query = r#"
-        SELECT week_str_from_sec(psd.start_time) as week, SUM(psd.duration) as total_seconds
+        SELECT
+            b.id, b.title, COALESCE(b.authors, ''), is_bible_book(b.title, b.md5),
+            psd.page, psd.start_time, psd.duration
         FROM page_stat_data psd
         JOIN book b ON b.id = psd.id_book
-        WHERE (b.title LIKE '%Bible%' OR b.title LIKE 'Treasury of Daily Prayer%')
-            AND psd.start_time >= ?1
-            AND psd.start_time < ?2
-        GROUP BY week_str_from_sec(psd.start_time)
+        WHERE psd.start_time >= ?1 AND psd.start_time < ?2
+        ORDER BY b.id, psd.start_time
     "#;
 
     let mut stmt = conn.prepare(query)?;
-    let reading_results = stmt
-        .query_map([start_sec, end_sec], |row| {
-            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
-        })?
-        .collect::<Result<HashMap<String, i64>, _>>()?;
+    let mut rows = stmt.query([start_sec, end_sec])?;
+
+    let mut sessions: Vec<RawSession> = Vec::new();
+    while let Some(row) = rows.next()? {
+        let book_id: i64 = row.get(0)?;
+        let page: i64 = row.get(4)?;
+        let start_time: i64 = row.get(5)?;
+        let duration: i64 = row.get(6)?;
+
+        let continues_session = sessions.last().is_some_and(|session| {
+            session.book_id == book_id && start_time - session.end_time <= SESSION_GAP_SECONDS
+        });
+        if !continues_session {
+            sessions.push(RawSession {
+                book_id,
+                title: row.get(1)?,
+                authors: row.get(2)?,
+                is_bible: row.get(3)?,
+                start_time,
+                end_time: start_time,
+                seconds: 0,
+                pages: BTreeSet::new(),
+            });
+        }
+
+        let session = sessions.last_mut().expect("a session was just pushed");
+        session.end_time = session.end_time.max(start_time + duration);
+        session.seconds += duration;
+        session.pages.insert(page);
+    }
+
+    Ok(sessions)
+}
+
+/// Gets reading time, pages read, and sessions for every book read during a period
+///
+/// # Arguments
+/// * `conn` - Database connection to KOReader statistics database
+/// * `period` - The period to report on
+///
+/// # Returns
+/// Vector of BookReadingStats for each book with any reading in the period, most read first
+pub fn get_per_book_stats(conn: &Connection, period: &DatePeriod) -> Result<Vec<BookReadingStats>> {
+    let mut books: Vec<BookReadingStats> = Vec::new();
+    let mut pages: HashSet<i64> = HashSet::new();
+
+    // Sessions come grouped by book
+    for session in query_sessions(conn, period)? {
+        if books.last().is_none_or(|book| book.id != session.book_id) {
+            if let Some(book) = books.last_mut() {
+                book.pages_read = pages.len() as i64;
+            }
+            books.push(BookReadingStats {
+                id: session.book_id,
+                title: session.title,
+                authors: session.authors,
+                minutes: 0.0,
+                pages_read: 0,
+                sessions: 0,
+            });
+            pages.clear();
+        }
+
+        let book = books.last_mut().expect("a book was just pushed");
+        book.minutes += session.seconds as f64 / 60.0;
+        book.sessions += 1;
+        pages.extend(session.pages);
+    }
+
+    if let Some(book) = books.last_mut() {
+        book.pages_read = pages.len() as i64;
+    }
+
+    books.sort_by(|a, b| b.minutes.total_cmp(&a.minutes));
+    Ok(books)
+}
+
+/// Gets every reading session that started during a period, in any book
+///
+/// # Arguments
+/// * `conn` - Database connection to KOReader statistics database
+/// * `period` - The period to report on
+///
+/// # Returns
+/// Vector of ReadingSession in the order they started
+pub fn get_reading_sessions(conn: &Connection, period: &DatePeriod) -> Result<Vec<ReadingSession>> {
+    let config = PeriodConfig::default();
+    let format_time = |timestamp: i64| -> Result<String> {
+        Ok(config
+            .timezone
+            .local_datetime(timestamp * 1000)?
+            .format("%Y-%m-%d %H:%M:%S")
+            .to_string())
+    };
+
+    let mut sessions = query_sessions(conn, period)?;
+    sessions.sort_by_key(|session| session.start_time);
+
+    sessions
+        .into_iter()
+        .map(|session| {
+            Ok(ReadingSession {
+                date: config
+                    .logical_date(session.start_time * 1000)?
+                    .format("%Y-%m-%d")
+                    .to_string(),
+                start_time: format_time(session.start_time)?,
+                end_time: format_time(session.end_time)?,
+                minutes: session.seconds as f64 / 60.0,
+                book_id: session.book_id,
+                title: session.title,
+                authors: session.authors,
+                is_bible: session.is_bible,
+                pages: session.pages.len() as i64,
+                first_page: session.pages.first().copied().unwrap_or_default(),
+                last_page: session.pages.last().copied().unwrap_or_default(),
+            })
+        })
+        .collect()
+}
+
+/// Everything read of one book, for working out when it was finished
+struct BookHistory {
+    id: i64,
+    title: String,
+    authors: String,
+    pages: i64,
+    pages_read: HashSet<i64>,
+    seconds: i64,
+    finished_at: Option<i64>,
+    last_read: i64,
+}
+
+/// Gets the books finished during a calendar year, with the books still in progress
+///
+/// A book is finished on the day the distinct pages read first reach `FINISHED_PERCENT` of
+/// its page count. Pages read with a different layout (font size, margins) are scaled to
+/// the book's current page count.
+///
+/// # Arguments
+/// * `conn` - Database connection to KOReader statistics database
+/// * `year` - Calendar year to report on
+///
+/// # Returns
+/// BooksFinished with the finished books, monthly counts, and books in progress
+pub fn get_books_finished(conn: &Connection, year: i32) -> Result<BooksFinished> {
+    let config = PeriodConfig::default();
+    let format_date = |timestamp: i64| -> Result<String> {
+        Ok(config
+            .logical_date(timestamp * 1000)?
+            .format("%Y-%m-%d")
+            .to_string())
+    };
+
+    let query = r#"
+        SELECT
+            b.id, b.title, COALESCE(b.authors, ''), COALESCE(b.pages, 0),
+            CASE
+                WHEN psd.total_pages > 0 AND b.pages > 0 AND psd.total_pages != b.pages
+                    THEN CAST(ROUND(psd.page * 1.0 * b.pages / psd.total_pages) AS INTEGER)
+                ELSE psd.page
+            END as page,
+            psd.start_time, psd.duration
+        FROM page_stat_data psd
+        JOIN book b ON b.id = psd.id_book
+        ORDER BY b.id, psd.start_time
+    "#;
+
+    let mut stmt = conn.prepare(query)?;
+    let mut rows = stmt.query([])?;
+
+    let mut histories: Vec<BookHistory> = Vec::new();
+    while let Some(row) = rows.next()? {
+        let id: i64 = row.get(0)?;
+        let page: i64 = row.get(4)?;
+        let start_time: i64 = row.get(5)?;
+        let duration: i64 = row.get(6)?;
+
+        if histories.last().is_none_or(|book| book.id != id) {
+            histories.push(BookHistory {
+                id,
+                title: row.get(1)?,
+                authors: row.get(2)?,
+                pages: row.get(3)?,
+                pages_read: HashSet::new(),
+                seconds: 0,
+                finished_at: None,
+                last_read: start_time,
+            });
+        }
+
+        let book = histories.last_mut().expect("a book was just pushed");
+        book.pages_read.insert(page);
+        book.seconds += duration;
+        book.last_read = start_time;
+        if book.finished_at.is_none()
+            && book.pages > 0
+            && book.pages_read.len() as f64 >= book.pages as f64 * FINISHED_PERCENT / 100.0
+        {
+            book.finished_at = Some(start_time);
+        }
+    }
+
+    let year_prefix = format!("{}-", year);
+    let mut finished = Vec::new();
+    let mut in_progress = Vec::new();
+    for book in histories {
+        match book.finished_at {
+            Some(finished_at) => {
+                let finished_date = format_date(finished_at)?;
+                if finished_date.starts_with(&year_prefix) {
+                    finished.push(FinishedBook {
+                        id: book.id,
+                        title: book.title,
+                        authors: book.authors,
+                        finished_date,
+                        minutes: book.seconds as f64 / 60.0,
+                        pages: book.pages,
+                    });
+                }
+            }
+            None => {
+                let last_read_date = format_date(book.last_read)?;
+                if last_read_date.starts_with(&year_prefix) {
+                    let pages_read = book.pages_read.len() as i64;
+                    in_progress.push(BookInProgress {
+                        id: book.id,
+                        title: book.title,
+                        authors: book.authors,
+                        pages_read,
+                        pages: book.pages,
+                        percent_complete: if book.pages > 0 {
+                            (pages_read as f64 / book.pages as f64 * 100.0).min(100.0)
+                        } else {
+                            0.0
+                        },
+                        last_read_date,
+                    });
+                }
+            }
+        }
+    }
+
+    finished.sort_by(|a, b| a.finished_date.cmp(&b.finished_date));
+    in_progress.sort_by(|a, b| b.percent_complete.total_cmp(&a.percent_complete));
 
-    let results = period.build_results(reading_results, |week_start, total_seconds| WeekStats {
-        week_start,
-        minutes: total_seconds as f64 / 60.0,
-    });
+    let by_month = (1..=12)
+        .map(|month| {
+            let month = format!("{}-{:02}", year, month);
+            let books = finished
+                .iter()
+                .filter(|book| book.finished_date.starts_with(&month))
+                .count() as i64;
+            MonthBooksFinished { month, books }
+        })
+        .collect();
 
-    Ok(results)
+    Ok(BooksFinished {
+        year,
+        finished,
+        by_month,
+        in_progress,
+    })
 }
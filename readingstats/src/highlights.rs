@@ -0,0 +1,589 @@
+use anyhow::{Context, Result, bail};
+use chrono::{Duration, NaiveDate, NaiveDateTime};
+use statsutils::{DatePeriod, PeriodConfig};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::models::{BookHighlights, DayHighlights, Highlight};
+
+/// A value in a KOReader metadata file, which is a serialized Lua table
+#[derive(Debug, Clone, PartialEq)]
+enum LuaValue {
+    Nil,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    /// Keys are stored as strings; integer keys like `[1]` become "1"
+    Table(BTreeMap<String, LuaValue>),
+}
+
+impl LuaValue {
+    fn get(&self, key: &str) -> Option<&LuaValue> {
+        match self {
+            LuaValue::Table(table) => table.get(key),
+            _ => None,
+        }
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        match self {
+            LuaValue::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    fn as_i64(&self) -> Option<i64> {
+        match self {
+            LuaValue::Number(n) => Some(*n as i64),
+            _ => None,
+        }
+    }
+
+    /// Entries of an array-like table in index order
+    fn array(&self) -> Vec<&LuaValue> {
+        let LuaValue::Table(table) = self else {
+            return Vec::new();
+        };
+        let mut entries: Vec<(i64, &LuaValue)> = table
+            .iter()
+            .filter_map(|(key, value)| key.parse::<i64>().ok().map(|i| (i, value)))
+            .collect();
+        entries.sort_by_key(|(i, _)| *i);
+        entries.into_iter().map(|(_, value)| value).collect()
+    }
+}
+
+/// Parser for the subset of Lua that KOReader writes: `return` followed by a table
+/// constructor of strings, numbers, booleans, and nested tables
+struct LuaParser<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+}
+
+impl<'a> LuaParser<'a> {
+    fn parse(source: &'a str) -> Result<LuaValue> {
+        let mut parser = Self {
+            chars: source.chars().peekable(),
+        };
+        parser.skip_whitespace();
+        if parser.peek_word() == "return" {
+            parser.read_word();
+        }
+        parser.value()
+    }
+
+    fn skip_whitespace(&mut self) {
+        loop {
+            match self.chars.peek() {
+                Some(c) if c.is_whitespace() => {
+                    self.chars.next();
+                }
+                Some('-') => {
+                    // Comments run to the end of the line
+                    let mut lookahead = self.chars.clone();
+                    lookahead.next();
+                    if lookahead.peek() != Some(&'-') {
+                        return;
+                    }
+                    for c in self.chars.by_ref() {
+                        if c == '\n' {
+                            break;
+                        }
+                    }
+                }
+                _ => return,
+            }
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<()> {
+        self.skip_whitespace();
+        match self.chars.next() {
+            Some(c) if c == expected => Ok(()),
+            other => bail!("Expected '{}' but found {:?}", expected, other),
+        }
+    }
+
+    fn peek_word(&self) -> String {
+        self.chars
+            .clone()
+            .take_while(|c| c.is_alphanumeric() || *c == '_')
+            .collect()
+    }
+
+    fn read_word(&mut self) -> String {
+        let mut word = String::new();
+        while let Some(&c) = self.chars.peek() {
+            if !(c.is_alphanumeric() || c == '_') {
+                break;
+            }
+            word.push(c);
+            self.chars.next();
+        }
+        word
+    }
+
+    fn value(&mut self) -> Result<LuaValue> {
+        self.skip_whitespace();
+        match self.chars.peek() {
+            Some('{') => self.table(),
+            Some('"') | Some('\'') => Ok(LuaValue::String(self.string()?)),
+            Some(c) if c.is_ascii_digit() || *c == '-' || *c == '.' => self.number(),
+            Some(_) => match self.read_word().as_str() {
+                "true" => Ok(LuaValue::Bool(true)),
+                "false" => Ok(LuaValue::Bool(false)),
+                "nil" => Ok(LuaValue::Nil),
+                word => bail!("Unexpected '{}' in metadata", word),
+            },
+            None => bail!("Unexpected end of metadata"),
+        }
+    }
+
+    fn number(&mut self) -> Result<LuaValue> {
+        let mut text = String::new();
+        while let Some(&c) = self.chars.peek() {
+            if !(c.is_ascii_alphanumeric() || c == '-' || c == '+' || c == '.') {
+                break;
+            }
+            text.push(c);
+            self.chars.next();
+        }
+        text.parse()
+            .map(LuaValue::Number)
+            .context(format!("Invalid number '{}' in metadata", text))
+    }
+
+    fn string(&mut self) -> Result<String> {
+        let quote = self.chars.next().context("Expected a string")?;
+        let mut value = String::new();
+        loop {
+            match self.chars.next() {
+                Some(c) if c == quote => return Ok(value),
+                Some('\\') => match self.chars.next() {
+                    Some('n') => value.push('\n'),
+                    Some('t') => value.push('\t'),
+                    Some('r') => value.push('\r'),
+                    // A backslash before a newline continues the string onto the next line
+                    Some('\n') => value.push('\n'),
+                    Some(d) if d.is_ascii_digit() => {
+                        let mut code = d.to_digit(10).unwrap_or_default();
+                        for _ in 0..2 {
+                            match self.chars.peek().and_then(|c| c.to_digit(10)) {
+                                Some(digit) => {
+                                    code = code * 10 + digit;
+                                    self.chars.next();
+                                }
+                                None => break,
+                            }
+                        }
+                        value.push(char::from_u32(code).unwrap_or('\u{FFFD}'));
+                    }
+                    Some(c) => value.push(c),
+                    None => bail!("Unterminated string in metadata"),
+                },
+                Some(c) => value.push(c),
+                None => bail!("Unterminated string in metadata"),
+            }
+        }
+    }
+
+    fn table(&mut self) -> Result<LuaValue> {
+        self.expect('{')?;
+        let mut table = BTreeMap::new();
+        let mut next_index = 1;
+        loop {
+            self.skip_whitespace();
+            match self.chars.peek() {
+                Some('}') => {
+                    self.chars.next();
+                    return Ok(LuaValue::Table(table));
+                }
+                Some(',') | Some(';') => {
+                    self.chars.next();
+                }
+                Some('[') => {
+                    self.chars.next();
+                    let key = match self.value()? {
+                        LuaValue::String(s) => s,
+                        LuaValue::Number(n) => (n as i64).to_string(),
+                        other => bail!("Unsupported table key {:?} in metadata", other),
+                    };
+                    self.expect(']')?;
+                    self.expect('=')?;
+                    table.insert(key, self.value()?);
+                }
+                Some(c) if c.is_alphabetic() || *c == '_' => {
+                    // Either `name = value` or a bare value like `true`
+                    let word = self.peek_word();
+                    let is_field = self
+                        .chars
+                        .clone()
+                        .skip(word.chars().count())
+                        .find(|c| !c.is_whitespace())
+                        .is_some_and(|c| c == '=');
+                    if is_field {
+                        self.read_word();
+                        self.expect('=')?;
+                        table.insert(word, self.value()?);
+                    } else {
+                        table.insert(next_index.to_string(), self.value()?);
+                        next_index += 1;
+                    }
+                }
+                Some(_) => {
+                    table.insert(next_index.to_string(), self.value()?);
+                    next_index += 1;
+                }
+                None => bail!("Unterminated table in metadata"),
+            }
+        }
+    }
+}
+
+/// Finds the KOReader metadata files (`*.sdr/metadata.*.lua`) anywhere under `dir`
+fn find_metadata_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    let entries =
+        fs::read_dir(dir).context(format!("Failed to read directory '{}'", dir.display()))?;
+    for entry in entries {
+        let path = entry?.path();
+        if !path.is_dir() {
+            continue;
+        }
+        if path.extension().is_some_and(|ext| ext == "sdr") {
+            for file in fs::read_dir(&path)? {
+                let file = file?.path();
+                let name = file.file_name().and_then(|n| n.to_str()).unwrap_or("");
+                // Skip backups like metadata.epub.lua.old
+                if name.starts_with("metadata.") && name.ends_with(".lua") {
+                    files.push(file);
+                }
+            }
+        } else {
+            find_metadata_files(&path, files)?;
+        }
+    }
+    Ok(())
+}
+
+/// Reads every highlight and note in one metadata file
+///
+/// Newer KOReader versions keep them in `annotations`; older ones keep highlights in
+/// `highlight` (keyed by page) with notes attached to the matching `bookmarks` entry.
+fn read_metadata_file(path: &Path, include_text: bool) -> Result<Vec<Highlight>> {
+    let source =
+        fs::read_to_string(path).context(format!("Failed to read '{}'", path.display()))?;
+    let metadata =
+        LuaParser::parse(&source).context(format!("Failed to parse '{}'", path.display()))?;
+
+    let props = metadata.get("doc_props");
+    let stats = metadata.get("stats");
+    let field = |name: &str| {
+        props
+            .and_then(|p| p.get(name))
+            .or_else(|| stats.and_then(|s| s.get(name)))
+            .and_then(LuaValue::as_str)
+            .unwrap_or_default()
+            .to_string()
+    };
+    let title = match field("title") {
+        title if title.is_empty() => path
+            .parent()
+            .and_then(|p| p.file_stem())
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        title => title,
+    };
+    let authors = field("authors");
+    let md5 = metadata
+        .get("partial_md5_checksum")
+        .and_then(LuaValue::as_str)
+        .map(str::to_string);
+
+    let make_highlight = |entry: &LuaValue, note: Option<&str>| -> Option<Highlight> {
+        let datetime = entry.get("datetime").and_then(LuaValue::as_str)?;
+        let text = entry.get("text").and_then(LuaValue::as_str);
+        let note = note.or_else(|| entry.get("note").and_then(LuaValue::as_str));
+        Some(Highlight {
+            title: title.clone(),
+            authors: authors.clone(),
+            md5: md5.clone(),
+            datetime: datetime.to_string(),
+            date: logical_date(datetime)?.format("%Y-%m-%d").to_string(),
+            chapter: entry
+                .get("chapter")
+                .and_then(LuaValue::as_str)
+                .map(str::to_string),
+            page: entry.get("pageno").and_then(LuaValue::as_i64),
+            has_note: note.is_some_and(|n| !n.is_empty()),
+            text: text.filter(|_| include_text).map(str::to_string),
+            note: note.filter(|_| include_text).map(str::to_string),
+        })
+    };
+
+    // Page bookmarks have no highlighted text, so they aren't counted as highlights
+    if let Some(annotations) = metadata.get("annotations") {
+        return Ok(annotations
+            .array()
+            .into_iter()
+            .filter(|entry| entry.get("pos0").is_some())
+            .filter_map(|entry| make_highlight(entry, None))
+            .collect());
+    }
+
+    let notes: HashMap<&str, &str> = metadata
+        .get("bookmarks")
+        .map(LuaValue::array)
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|bookmark| {
+            let datetime = bookmark.get("datetime")?.as_str()?;
+            let text = bookmark.get("text")?.as_str()?;
+            // Old versions store the highlighted text as the note when there is none
+            let highlighted = bookmark.get("notes").and_then(LuaValue::as_str);
+            (highlighted != Some(text)).then_some((datetime, text))
+        })
+        .collect();
+
+    let mut highlights = Vec::new();
+    if let Some(LuaValue::Table(pages)) = metadata.get("highlight") {
+        for (page, entries) in pages {
+            for entry in entries.array() {
+                let note = entry
+                    .get("datetime")
+                    .and_then(LuaValue::as_str)
+                    .and_then(|dt| notes.get(dt).copied());
+                if let Some(mut highlight) = make_highlight(entry, note) {
+                    highlight.page = highlight.page.or_else(|| page.parse().ok());
+                    highlights.push(highlight);
+                }
+            }
+        }
+    }
+    Ok(highlights)
+}
+
+/// Date a highlight made at local time `datetime` counts toward (days start at 4 AM)
+fn logical_date(datetime: &str) -> Option<NaiveDate> {
+    let datetime = NaiveDateTime::parse_from_str(datetime, "%Y-%m-%d %H:%M:%S").ok()?;
+    let rollover = Duration::hours(PeriodConfig::default().rollover_hour);
+    Some((datetime - rollover).date())
+}
+
+/// Reads every highlight from the KOReader metadata files under `dir`, oldest first
+///
+/// KOReader keeps highlights in a `.sdr` folder next to each book (or in its
+/// `docsettings` folder), not in the statistics database. The highlighted text and notes
+/// are only kept when `include_text` is set.
+pub fn get_highlights(dir: &str, include_text: bool) -> Result<Vec<Highlight>> {
+    let mut files = Vec::new();
+    find_metadata_files(Path::new(dir), &mut files)?;
+    files.sort();
+
+    let mut highlights = Vec::new();
+    for file in files {
+        highlights.extend(read_metadata_file(&file, include_text)?);
+    }
+    highlights.sort_by(|a, b| a.datetime.cmp(&b.datetime));
+    Ok(highlights)
+}
+
+/// Counts highlights and notes per book, most highlighted first
+pub fn count_by_book(highlights: &[Highlight]) -> Vec<BookHighlights> {
+    let mut books: Vec<BookHighlights> = Vec::new();
+    for highlight in highlights {
+        let position = books
+            .iter()
+            .position(|b| b.title == highlight.title && b.md5 == highlight.md5);
+        let book = match position {
+            Some(i) => &mut books[i],
+            None => {
+                books.push(BookHighlights {
+                    title: highlight.title.clone(),
+                    authors: highlight.authors.clone(),
+                    md5: highlight.md5.clone(),
+                    highlights: 0,
+                    notes: 0,
+                    last_highlight_date: highlight.date.clone(),
+                });
+                books.last_mut().expect("a book was just pushed")
+            }
+        };
+        book.highlights += 1;
+        book.notes += highlight.has_note as i64;
+        book.last_highlight_date = book.last_highlight_date.clone().max(highlight.date.clone());
+    }
+    books.sort_by_key(|b| std::cmp::Reverse(b.highlights));
+    books
+}
+
+/// Counts highlights and notes on each day of a daily period, with zeros for days
+/// without any
+pub fn count_by_day(highlights: &[Highlight], period: DatePeriod) -> Vec<DayHighlights> {
+    let mut counts: HashMap<String, (i64, i64)> = HashMap::new();
+    for highlight in highlights {
+        let entry = counts.entry(highlight.date.clone()).or_default();
+        entry.0 += 1;
+        entry.1 += highlight.has_note as i64;
+    }
+    period.build_results(counts, |date, (highlights, notes)| DayHighlights {
+        date,
+        highlights,
+        notes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ANNOTATIONS: &str = r#"-- we can read Lua syntax here!
+return {
+    ["annotations"] = {
+        [1] = {
+            ["chapter"] = "Psalm 23",
+            ["datetime"] = "2025-10-19 07:30:00",
+            ["pageno"] = 512,
+            ["pos0"] = "/body/DocFragment[40]/body/p[3]/text().0",
+            ["pos1"] = "/body/DocFragment[40]/body/p[3]/text().58",
+            ["text"] = "The \"LORD\" is my shepherd;\
+I shall not want.",
+        },
+        [2] = {
+            ["datetime"] = "2025-10-20 02:15:00",
+            ["note"] = "Compare John 10",
+            ["pageno"] = 513,
+            ["pos0"] = "/body/DocFragment[40]/body/p[5]/text().0",
+            ["text"] = "He restores my soul",
+        },
+        [3] = {
+            ["datetime"] = "2025-10-20 21:00:00",
+            ["page"] = "/body/DocFragment[41]",
+            ["text"] = "in Page 530",
+        },
+    },
+    ["doc_props"] = {
+        ["authors"] = "Crossway",
+        ["title"] = "ESV Bible",
+    },
+    ["partial_md5_checksum"] = "0123abcd",
+    ["percent_finished"] = 0.25,
+    ["summary"] = {
+        ["status"] = "reading",
+    },
+}
+"#;
+
+    const LEGACY: &str = r#"return {
+    ["bookmarks"] = {
+        [1] = {
+            ["datetime"] = "2021-03-01 20:00:00",
+            ["notes"] = "Call me Ishmael.",
+            ["text"] = "A famous opening",
+        },
+        [2] = {
+            ["datetime"] = "2021-03-02 20:00:00",
+            ["notes"] = "Whale",
+            ["text"] = "Whale",
+        },
+    },
+    ["highlight"] = {
+        [3] = {
+            [1] = {
+                ["datetime"] = "2021-03-01 20:00:00",
+                ["text"] = "Call me Ishmael.",
+            },
+        },
+        [40] = {
+            [1] = {
+                ["datetime"] = "2021-03-02 20:00:00",
+                ["text"] = "Whale",
+            },
+        },
+    },
+    ["stats"] = {
+        ["authors"] = "Herman Melville",
+        ["title"] = "Moby-Dick",
+    },
+}
+"#;
+
+    fn write_sidecar(dir: &Path, book: &str, contents: &str) {
+        let sdr = dir.join(format!("{}.sdr", book));
+        fs::create_dir_all(&sdr).unwrap();
+        fs::write(sdr.join("metadata.epub.lua"), contents).unwrap();
+        fs::write(sdr.join("metadata.epub.lua.old"), "not lua").unwrap();
+    }
+
+    #[test]
+    fn test_parse_lua_table() {
+        let value =
+            LuaParser::parse(r#"return { ["a"] = { 1, 2.5, "x\65\n" }, b=true, [3] = nil }"#)
+                .unwrap();
+        let a = value.get("a").unwrap().array();
+        assert_eq!(a[0], &LuaValue::Number(1.0));
+        assert_eq!(a[1], &LuaValue::Number(2.5));
+        assert_eq!(a[2], &LuaValue::String("xA\n".to_string()));
+        assert_eq!(value.get("b"), Some(&LuaValue::Bool(true)));
+        assert_eq!(value.get("3"), Some(&LuaValue::Nil));
+
+        assert!(LuaParser::parse("return { [\"a\"] = ").is_err());
+    }
+
+    #[test]
+    fn test_read_annotations() {
+        let dir = tempfile::tempdir().unwrap();
+        write_sidecar(&dir.path().join("Bibles"), "esv", ANNOTATIONS);
+
+        let highlights = get_highlights(dir.path().to_str().unwrap(), true).unwrap();
+        // The page bookmark isn't a highlight
+        assert_eq!(highlights.len(), 2);
+        assert_eq!(highlights[0].title, "ESV Bible");
+        assert_eq!(highlights[0].md5.as_deref(), Some("0123abcd"));
+        assert_eq!(highlights[0].chapter.as_deref(), Some("Psalm 23"));
+        assert_eq!(
+            highlights[0].text.as_deref(),
+            Some("The \"LORD\" is my shepherd;\nI shall not want.")
+        );
+        assert!(!highlights[0].has_note);
+        // Made before the 4 AM rollover, so it counts toward the previous day
+        assert_eq!(highlights[1].date, "2025-10-19");
+        assert!(highlights[1].has_note);
+
+        let without_text = get_highlights(dir.path().to_str().unwrap(), false).unwrap();
+        assert_eq!(without_text[1].text, None);
+        assert_eq!(without_text[1].note, None);
+        assert!(without_text[1].has_note);
+    }
+
+    #[test]
+    fn test_read_legacy_highlights_and_count() {
+        let dir = tempfile::tempdir().unwrap();
+        write_sidecar(dir.path(), "esv", ANNOTATIONS);
+        write_sidecar(dir.path(), "moby", LEGACY);
+
+        let highlights = get_highlights(dir.path().to_str().unwrap(), true).unwrap();
+        let moby: Vec<&Highlight> = highlights
+            .iter()
+            .filter(|h| h.title == "Moby-Dick")
+            .collect();
+        assert_eq!(moby.len(), 2);
+        assert_eq!(moby[0].page, Some(3));
+        assert_eq!(moby[0].note.as_deref(), Some("A famous opening"));
+        assert!(!moby[1].has_note);
+
+        let books = count_by_book(&highlights);
+        assert_eq!(books.len(), 2);
+        assert_eq!(books[0].highlights, 2);
+        assert_eq!(books.iter().map(|b| b.notes).sum::<i64>(), 2);
+
+        let period = DatePeriod::for_range(
+            NaiveDate::from_ymd_opt(2025, 10, 18).unwrap(),
+            NaiveDate::from_ymd_opt(2025, 10, 20).unwrap(),
+            statsutils::Granularity::Day,
+        )
+        .unwrap();
+        let days = count_by_day(&highlights, period);
+        let counts: Vec<(i64, i64)> = days.iter().map(|d| (d.highlights, d.notes)).collect();
+        assert_eq!(counts, [(0, 0), (2, 1), (0, 0)]);
+    }
+}
@@ -0,0 +1,236 @@
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use rusqlite::Connection;
+use statsutils::series::densify;
+use statsutils::sqlite::{OpenOptions, open_readonly_with_retry};
+use statsutils::{DatePeriod, Granularity, get_today_start_ms};
+use std::collections::HashMap;
+
+use crate::db::register_bible_function;
+use crate::models::{DayStats, PeriodStats, SessionLengthStats, WeekStats, pages_per_hour};
+
+/// Opens a Kobo e-reader database (`.kobo/KoboReader.sqlite`) in read-only mode
+///
+/// # Database Schema
+/// Kobo records each time a book is closed as a `LeaveContent` row in `AnalyticsEvents`:
+/// - `Timestamp`: ISO 8601 time the book was closed
+/// - `Attributes`: JSON object including the book's `title`
+/// - `Metrics`: JSON object including `SecondsRead` and `PagesTurned`
+///
+/// Events are removed once the device syncs them to Kobo, so only reading since the last
+/// sync is available.
+pub fn open_database(path: &str) -> Result<Connection> {
+    let conn = open_readonly_with_retry(path, &OpenOptions::new("Kobo database"))?;
+    register_bible_function(&conn)?;
+
+    Ok(conn)
+}
+
+/// Bible reading time and pages turned for one bucket of a period, plus time spent on
+/// every book
+#[derive(Default)]
+struct BucketReading {
+    bible_seconds: i64,
+    total_seconds: i64,
+    pages: i64,
+}
+
+/// Queries reading for every bucket of a period
+fn get_period_reading(
+    conn: &Connection,
+    period: &DatePeriod,
+    granularity: Granularity,
+) -> Result<HashMap<String, BucketReading>> {
+    let bucket_fn = granularity.sec_bucket_function();
+
+    let query = format!(
+        r#"
+        SELECT
+            {bucket_fn}(end_time) as bucket,
+            SUM(CASE WHEN is_bible THEN seconds_read ELSE 0 END) as bible_seconds,
+            SUM(seconds_read) as total_seconds,
+            SUM(CASE WHEN is_bible THEN pages_turned ELSE 0 END) as pages
+        FROM (
+            SELECT
+                CAST(strftime('%s', Timestamp) AS INTEGER) as end_time,
+                is_bible_book(json_extract(Attributes, '$.title'), NULL) as is_bible,
+                COALESCE(json_extract(Metrics, '$.SecondsRead'), 0) as seconds_read,
+                COALESCE(json_extract(Metrics, '$.PagesTurned'), 0) as pages_turned
+            FROM AnalyticsEvents
+            WHERE Type = 'LeaveContent'
+        )
+        WHERE end_time >= ?1 AND end_time < ?2
+        GROUP BY {bucket_fn}(end_time)
+        "#
+    );
+
+    let mut stmt = conn.prepare(&query)?;
+    let results = stmt
+        .query_map([period.start_ms / 1000, period.end_ms / 1000], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                BucketReading {
+                    bible_seconds: row.get(1)?,
+                    total_seconds: row.get(2)?,
+                    pages: row.get(3)?,
+                },
+            ))
+        })?
+        .collect::<Result<HashMap<String, BucketReading>, _>>()?;
+
+    Ok(results)
+}
+
+/// Gets Bible reading time and pages turned for each of the last 30 days
+pub fn get_last_30_days_stats(conn: &Connection) -> Result<Vec<DayStats>> {
+    let period = DatePeriod::last_30_days()?;
+    let results = get_period_reading(conn, &period, Granularity::Day)?;
+
+    Ok(densify(results, &period)?.map(|date, bucket| {
+        let minutes = bucket.bible_seconds as f64 / 60.0;
+        DayStats {
+            date,
+            minutes,
+            all_books_minutes: bucket.total_seconds as f64 / 60.0,
+            pages: bucket.pages,
+            pages_per_hour: pages_per_hour(bucket.pages, minutes),
+        }
+    }))
+}
+
+/// Gets Bible reading time and pages turned for each of the last 12 weeks
+pub fn get_last_12_weeks_stats(conn: &Connection) -> Result<Vec<WeekStats>> {
+    let period = DatePeriod::last_12_weeks()?;
+    let results = get_period_reading(conn, &period, Granularity::Week)?;
+
+    Ok(densify(results, &period)?.map(|week_start, bucket| {
+        let minutes = bucket.bible_seconds as f64 / 60.0;
+        WeekStats {
+            week_start,
+            minutes,
+            all_books_minutes: bucket.total_seconds as f64 / 60.0,
+            pages: bucket.pages,
+            pages_per_hour: pages_per_hour(bucket.pages, minutes),
+        }
+    }))
+}
+
+/// Gets Bible reading time and pages turned for each bucket of a period, at the period's
+/// granularity
+pub fn get_stats_for_period(conn: &Connection, period: DatePeriod) -> Result<Vec<PeriodStats>> {
+    let results = get_period_reading(conn, &period, period.granularity)?;
+
+    Ok(densify(results, &period)?.map(|period_start, bucket| {
+        let minutes = bucket.bible_seconds as f64 / 60.0;
+        PeriodStats {
+            period_start,
+            minutes,
+            pages: bucket.pages,
+            pages_per_hour: pages_per_hour(bucket.pages, minutes),
+        }
+    }))
+}
+
+/// Gets the total Bible reading time for today in minutes
+pub fn get_today_reading_minutes(conn: &Connection) -> Result<f64> {
+    let today_start_sec = get_today_start_ms()? / 1000;
+
+    let query = r#"
+        SELECT COALESCE(SUM(json_extract(Metrics, '$.SecondsRead')), 0)
+        FROM AnalyticsEvents
+        WHERE Type = 'LeaveContent'
+            AND is_bible_book(json_extract(Attributes, '$.title'), NULL)
+            AND CAST(strftime('%s', Timestamp) AS INTEGER) >= ?1
+    "#;
+
+    let total_seconds: i64 = conn.query_row(query, [today_start_sec], |row| row.get(0))?;
+    Ok(total_seconds as f64 / 60.0)
+}
+
+/// Gets total Bible reading minutes for every day with reading, oldest first
+pub fn get_daily_bible_minutes(conn: &Connection) -> Result<Vec<(NaiveDate, f64)>> {
+    let query = r#"
+        SELECT
+            date_str_from_sec(CAST(strftime('%s', Timestamp) AS INTEGER)) as date,
+            SUM(COALESCE(json_extract(Metrics, '$.SecondsRead'), 0)) as total_seconds
+        FROM AnalyticsEvents
+        WHERE Type = 'LeaveContent'
+            AND is_bible_book(json_extract(Attributes, '$.title'), NULL)
+        GROUP BY date
+        ORDER BY date
+    "#;
+
+    let mut stmt = conn.prepare(query)?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+        })?
+        .collect::<Result<Vec<(String, i64)>, _>>()?;
+
+    rows.into_iter()
+        .map(|(date, total_seconds)| {
+            let date = NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+                .context(format!("Invalid reading date '{}'", date))?;
+            Ok((date, total_seconds as f64 / 60.0))
+        })
+        .collect()
+}
+
+/// Gets the minutes spent in each Bible book (by title) during a period, most read first
+pub fn get_bible_title_minutes(
+    conn: &Connection,
+    period: &DatePeriod,
+) -> Result<Vec<(String, f64)>> {
+    let query = r#"
+        SELECT
+            json_extract(Attributes, '$.title') as title,
+            SUM(COALESCE(json_extract(Metrics, '$.SecondsRead'), 0)) as total_seconds
+        FROM AnalyticsEvents
+        WHERE Type = 'LeaveContent'
+            AND is_bible_book(title, NULL)
+            AND CAST(strftime('%s', Timestamp) AS INTEGER) >= ?1
+            AND CAST(strftime('%s', Timestamp) AS INTEGER) < ?2
+        GROUP BY title
+        ORDER BY total_seconds DESC, title
+    "#;
+
+    let mut stmt = conn.prepare(query)?;
+    let titles = stmt
+        .query_map([period.start_ms / 1000, period.end_ms / 1000], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, i64>(1)? as f64 / 60.0,
+            ))
+        })?
+        .collect::<Result<Vec<(String, f64)>, _>>()?;
+
+    Ok(titles)
+}
+
+/// Gets Bible reading session lengths during a period
+///
+/// Each `LeaveContent` event already covers one sitting with a book, so events are used
+/// as sessions directly rather than grouped by page turns as for KOReader.
+pub fn get_bible_session_stats(
+    conn: &Connection,
+    period: &DatePeriod,
+) -> Result<SessionLengthStats> {
+    let query = r#"
+        SELECT json_extract(Metrics, '$.SecondsRead') as seconds_read
+        FROM AnalyticsEvents
+        WHERE Type = 'LeaveContent'
+            AND is_bible_book(json_extract(Attributes, '$.title'), NULL)
+            AND CAST(strftime('%s', Timestamp) AS INTEGER) >= ?1
+            AND CAST(strftime('%s', Timestamp) AS INTEGER) < ?2
+            AND seconds_read > 0
+    "#;
+
+    let mut stmt = conn.prepare(query)?;
+    let minutes = stmt
+        .query_map([period.start_ms / 1000, period.end_ms / 1000], |row| {
+            Ok(row.get::<_, i64>(0)? as f64 / 60.0)
+        })?
+        .collect::<Result<Vec<f64>, _>>()?;
+
+    Ok(SessionLengthStats::from_minutes(minutes))
+}
@@ -1,16 +1,30 @@
+pub mod config;
 pub mod db;
+pub mod highlights;
+pub mod kobo;
 pub mod models;
+#[cfg(feature = "tokio")]
+pub mod nonblocking;
+pub mod schema;
+pub mod source;
+pub mod streaks;
 
-use crate::models::{DayStats, WeekStats};
+use crate::models::{
+    BookHighlights, BookReadingStats, BooksFinished, DayHighlights, DayStats, MonthStats,
+    PeriodStats, ReadingSession, ReadingStreaks, SessionLengthStats, WeekStats,
+};
 use anyhow::Result;
+use chrono::NaiveDate;
+use statsutils::DatePeriod;
 
-/// Gets reading time for each of the last 30 days for Bible and Treasury of Daily Prayer books
+/// Gets reading time for each of the last 30 days for books counted as Bible reading
+/// (see `config::set_bible_books`)
 ///
 /// # Arguments
 /// * `db_path` - Path to the KOReader statistics.sqlite3 database file
 ///
 /// # Returns
-/// Vector of DayStats with date and minutes for each of the last 30 days
+/// Vector of DayStats with date, minutes, and pages read for each of the last 30 days
 ///
 /// # Example
 /// ```ignore
@@ -46,13 +60,13 @@ pub fn get_today_reading_time(db_path: &str) -> Result<f64> {
     db::get_today_reading_minutes(&conn)
 }
 
-/// Gets reading time for each of the last 12 weeks for Bible and Treasury of Daily Prayer books
+/// Gets reading time for each of the last 12 weeks for books counted as Bible reading
 ///
 /// # Arguments
 /// * `db_path` - Path to the KOReader statistics.sqlite3 database file
 ///
 /// # Returns
-/// Vector of WeekStats with week_start and minutes for each of the last 12 weeks
+/// Vector of WeekStats with week_start, minutes, and pages read for each of the last 12 weeks
 ///
 /// # Example
 /// ```ignore
@@ -67,3 +81,232 @@ pub fn get_last_12_weeks_stats(db_path: &str) -> Result<Vec<WeekStats>> {
     let conn = db::open_database(db_path)?;
     db::get_last_12_weeks_stats(&conn)
 }
+
+/// Gets reading time for each of the last 12 months for books counted as Bible reading
+///
+/// # Arguments
+/// * `db_path` - Path to the KOReader statistics.sqlite3 database file
+///
+/// # Returns
+/// Vector of MonthStats with month, minutes, and pages read for each of the last 12 months
+///
+/// # Example
+/// ```ignore
+/// use readingstats::get_last_12_months_stats;
+///
+/// let monthly_stats = get_last_12_months_stats("/path/to/statistics.sqlite3")?;
+/// for month in monthly_stats {
+///     println!("{}: {:.2} minutes", month.month, month.minutes);
+/// }
+/// ```
+pub fn get_last_12_months_stats(db_path: &str) -> Result<Vec<MonthStats>> {
+    let conn = db::open_database(db_path)?;
+    db::get_last_12_months_stats(&conn)
+}
+
+/// Gets reading time for each month of a calendar year, for an annual view
+///
+/// # Arguments
+/// * `db_path` - Path to the KOReader statistics.sqlite3 database file
+/// * `year` - Calendar year to report on
+///
+/// # Returns
+/// Vector of 12 MonthStats, January first, with zeros for months without reading
+///
+/// # Example
+/// ```ignore
+/// use readingstats::get_year_stats;
+///
+/// let months = get_year_stats("/path/to/statistics.sqlite3", 2025)?;
+/// let total: f64 = months.iter().map(|m| m.minutes).sum();
+/// println!("Read for {:.1} hours in 2025", total / 60.0);
+/// ```
+pub fn get_year_stats(db_path: &str, year: i32) -> Result<Vec<MonthStats>> {
+    let conn = db::open_database(db_path)?;
+    db::get_year_stats(&conn, year)
+}
+
+/// Gets reading time, pages read, and sessions for every book read during a period,
+/// not just the Bible
+///
+/// # Arguments
+/// * `db_path` - Path to the KOReader statistics.sqlite3 database file
+/// * `period` - The period to report on
+///
+/// # Returns
+/// Vector of BookReadingStats for each book with any reading in the period, most read first
+///
+/// # Example
+/// ```ignore
+/// use readingstats::get_per_book_stats;
+/// use statsutils::DatePeriod;
+///
+/// let books = get_per_book_stats("/path/to/statistics.sqlite3", &DatePeriod::last_30_days()?)?;
+/// for book in books {
+///     println!("{}: {:.2} minutes, {} pages", book.title, book.minutes, book.pages_read);
+/// }
+/// ```
+pub fn get_per_book_stats(db_path: &str, period: &DatePeriod) -> Result<Vec<BookReadingStats>> {
+    let conn = db::open_database(db_path)?;
+    db::get_per_book_stats(&conn, period)
+}
+
+/// Gets every reading session that started during a period, for a journal-style view
+///
+/// Sessions are reconstructed from KOReader's page reads: reading one book with no pause
+/// longer than 30 minutes is a single session.
+///
+/// # Arguments
+/// * `db_path` - Path to the KOReader statistics.sqlite3 database file
+/// * `period` - The period to report on
+///
+/// # Returns
+/// Vector of ReadingSession in the order they started
+///
+/// # Example
+/// ```ignore
+/// use readingstats::get_reading_sessions;
+/// use statsutils::DatePeriod;
+///
+/// let sessions = get_reading_sessions("/path/to/statistics.sqlite3", &DatePeriod::last_30_days()?)?;
+/// for session in sessions {
+///     println!("{} {}: {:.0} min", session.start_time, session.title, session.minutes);
+/// }
+/// ```
+pub fn get_reading_sessions(db_path: &str, period: &DatePeriod) -> Result<Vec<ReadingSession>> {
+    let conn = db::open_database(db_path)?;
+    db::get_reading_sessions(&conn, period)
+}
+
+/// Gets the books finished during a calendar year, counted per month, along with the
+/// completion percentage of books read that year but not yet finished
+///
+/// # Arguments
+/// * `db_path` - Path to the KOReader statistics.sqlite3 database file
+/// * `year` - Calendar year to report on
+///
+/// # Returns
+/// BooksFinished with the finished books, monthly counts, and books in progress
+///
+/// # Example
+/// ```ignore
+/// use readingstats::get_books_finished;
+///
+/// let books = get_books_finished("/path/to/statistics.sqlite3", 2025)?;
+/// println!("Finished {} books in {}", books.finished.len(), books.year);
+/// ```
+pub fn get_books_finished(db_path: &str, year: i32) -> Result<BooksFinished> {
+    let conn = db::open_database(db_path)?;
+    db::get_books_finished(&conn, year)
+}
+
+/// Gets the current and longest Bible reading streaks, counting only days with at least
+/// `min_minutes` read
+///
+/// # Arguments
+/// * `db_path` - Path to the KOReader statistics.sqlite3 database file
+/// * `min_minutes` - Reading time a day needs to extend a streak
+///   (`streaks::DEFAULT_STREAK_MINUTES` is a reasonable default)
+///
+/// # Returns
+/// ReadingStreaks with the current and longest streaks
+///
+/// # Example
+/// ```ignore
+/// use readingstats::{get_reading_streaks, streaks::DEFAULT_STREAK_MINUTES};
+///
+/// let streaks = get_reading_streaks("/path/to/statistics.sqlite3", DEFAULT_STREAK_MINUTES)?;
+/// println!("Current streak: {} days", streaks.current_streak);
+/// ```
+pub fn get_reading_streaks(db_path: &str, min_minutes: f64) -> Result<ReadingStreaks> {
+    let conn = db::open_database(db_path)?;
+    let daily_minutes = db::get_daily_bible_minutes(&conn)?;
+    Ok(streaks::compute_streaks(
+        &daily_minutes,
+        min_minutes,
+        statsutils::get_today_date()?,
+    ))
+}
+
+/// Gets the minutes of Bible reading on every day with any, oldest first
+pub fn get_daily_bible_minutes(db_path: &str) -> Result<Vec<(NaiveDate, f64)>> {
+    let conn = db::open_database(db_path)?;
+    db::get_daily_bible_minutes(&conn)
+}
+
+/// Gets the minutes spent in each Bible book (by title) during a period, most read first
+pub fn get_bible_title_minutes(db_path: &str, period: &DatePeriod) -> Result<Vec<(String, f64)>> {
+    let conn = db::open_database(db_path)?;
+    db::get_bible_title_minutes(&conn, period)
+}
+
+/// Gets Bible reading time for each bucket of a period
+///
+/// # Arguments
+/// * `db_path` - Path to the KOReader statistics.sqlite3 database file
+/// * `period` - The period to report on; its granularity sets the bucket size
+///
+/// # Returns
+/// One PeriodStats per bucket of the period, with zeros for buckets without reading
+pub fn get_stats_for_period(db_path: &str, period: &DatePeriod) -> Result<Vec<PeriodStats>> {
+    let conn = db::open_database(db_path)?;
+    db::get_stats_for_period(&conn, period.clone())
+}
+
+/// Gets the longest, median, and average length of Bible reading sessions during a period
+///
+/// # Arguments
+/// * `db_path` - Path to the KOReader statistics.sqlite3 database file
+/// * `period` - The period to report on
+///
+/// # Returns
+/// SessionLengthStats for the Bible reading sessions that started in the period
+pub fn get_bible_session_stats(db_path: &str, period: &DatePeriod) -> Result<SessionLengthStats> {
+    let sessions: Vec<ReadingSession> = get_reading_sessions(db_path, period)?
+        .into_iter()
+        .filter(|session| session.is_bible)
+        .collect();
+    Ok(SessionLengthStats::from_sessions(&sessions))
+}
+
+/// Gets highlight and note counts per book from the KOReader metadata files under a
+/// directory, most highlighted first
+///
+/// KOReader keeps highlights in each book's `.sdr` metadata folder rather than in the
+/// statistics database, so this reads the folder holding the books (or KOReader's
+/// `docsettings` folder) instead of `statistics.sqlite3`.
+///
+/// # Arguments
+/// * `books_dir` - Directory to search for `*.sdr/metadata.*.lua` files
+///
+/// # Returns
+/// Vector of BookHighlights for each book with any highlights
+///
+/// # Example
+/// ```ignore
+/// use readingstats::get_highlights_per_book;
+///
+/// for book in get_highlights_per_book("/path/to/books")? {
+///     println!("{}: {} highlights, {} notes", book.title, book.highlights, book.notes);
+/// }
+/// ```
+pub fn get_highlights_per_book(books_dir: &str) -> Result<Vec<BookHighlights>> {
+    let highlights = highlights::get_highlights(books_dir, false)?;
+    Ok(highlights::count_by_book(&highlights))
+}
+
+/// Gets highlight and note counts for each of the last 30 days from the KOReader metadata
+/// files under a directory
+///
+/// # Arguments
+/// * `books_dir` - Directory to search for `*.sdr/metadata.*.lua` files
+///
+/// # Returns
+/// Vector of DayHighlights for each of the last 30 days
+pub fn get_last_30_days_highlights(books_dir: &str) -> Result<Vec<DayHighlights>> {
+    let highlights = highlights::get_highlights(books_dir, false)?;
+    Ok(highlights::count_by_day(
+        &highlights,
+        DatePeriod::last_30_days()?,
+    ))
+}
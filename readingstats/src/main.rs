@@ -1,5 +1,16 @@
+use chrono::Datelike;
 use clap::{Parser, Subcommand};
-use readingstats::get_last_30_days_stats;
+use readingstats::config::{self, BibleBookMatcher};
+use readingstats::highlights;
+use readingstats::models::pages_per_hour;
+use readingstats::source::source_for;
+use readingstats::streaks::DEFAULT_STREAK_MINUTES;
+use readingstats::{
+    get_books_finished, get_highlights_per_book, get_last_12_months_stats,
+    get_last_30_days_highlights, get_per_book_stats, get_reading_sessions, get_year_stats,
+};
+use statsutils::fmt::duration_minutes;
+use statsutils::{DatePeriod, Granularity, get_today_date};
 use std::process;
 
 #[derive(Parser)]
@@ -9,31 +20,131 @@ use std::process;
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+
+    /// Title pattern identifying a Bible (case-insensitive, `*` and `?` wildcards); may be
+    /// repeated, and replaces the default "*Bible*" and "Treasury of Daily Prayer*" patterns
+    #[arg(long = "bible-title", value_name = "PATTERN", global = true)]
+    bible_titles: Vec<String>,
+
+    /// KOReader MD5 of a book to count as Bible reading whatever its title; may be repeated
+    #[arg(long = "bible-md5", value_name = "MD5", global = true)]
+    bible_md5s: Vec<String>,
 }
 
 #[derive(Subcommand)]
 enum Commands {
     /// Show reading time for each of the last 30 days
     Daily {
+        /// Path to the KOReader statistics database or Kobo KoboReader.sqlite file
+        #[arg(value_name = "DATABASE_PATH")]
+        db_path: String,
+        /// Minutes of reading a day needs to count toward a streak
+        #[arg(long, default_value_t = DEFAULT_STREAK_MINUTES)]
+        streak_minutes: f64,
+    },
+    /// Show reading time for each of the last 12 months, or each month of a year
+    Monthly {
+        /// Path to the KOReader statistics database file
+        #[arg(value_name = "DATABASE_PATH")]
+        db_path: String,
+        /// Calendar year to show instead of the last 12 months
+        #[arg(long)]
+        year: Option<i32>,
+    },
+    /// Show reading time, pages, and sessions for every book read recently
+    Books {
+        /// Path to the KOReader statistics database file
+        #[arg(value_name = "DATABASE_PATH")]
+        db_path: String,
+        /// Number of days to include, ending today
+        #[arg(long, default_value_t = 30)]
+        days: i64,
+    },
+    /// Show books finished in a year and books still in progress
+    Finished {
+        /// Path to the KOReader statistics database file
+        #[arg(value_name = "DATABASE_PATH")]
+        db_path: String,
+        /// Calendar year (defaults to the current year)
+        #[arg(long)]
+        year: Option<i32>,
+    },
+    /// Show highlight and note counts from KOReader's per-book metadata files
+    Highlights {
+        /// Directory holding the books' `.sdr` metadata folders (or KOReader's docsettings)
+        #[arg(value_name = "BOOKS_DIR")]
+        books_dir: String,
+        /// List each highlight's text and note from the last 30 days
+        #[arg(long)]
+        text: bool,
+    },
+    /// List individual reading sessions, journal-style
+    Sessions {
         /// Path to the KOReader statistics database file
         #[arg(value_name = "DATABASE_PATH")]
         db_path: String,
+        /// Number of days to include, ending today
+        #[arg(long, default_value_t = 7)]
+        days: i64,
     },
 }
 
 fn main() {
     let cli = Cli::parse();
 
+    // Timezone, rollover hour, and week start shared with the rest of the workspace
+    if let Err(e) = statsutils::configure_from_env() {
+        eprintln!("Error: {:#}", e);
+        process::exit(1);
+    }
+
+    if !cli.bible_titles.is_empty() || !cli.bible_md5s.is_empty() {
+        let default = BibleBookMatcher::default();
+        config::set_bible_books(BibleBookMatcher {
+            title_patterns: if cli.bible_titles.is_empty() {
+                default.title_patterns
+            } else {
+                cli.bible_titles
+            },
+            md5s: cli.bible_md5s,
+        });
+    }
+
     match cli.command {
-        Commands::Daily { db_path } => {
-            run_daily_command(&db_path);
+        Commands::Daily {
+            db_path,
+            streak_minutes,
+        } => {
+            run_daily_command(&db_path, streak_minutes);
+        }
+        Commands::Monthly { db_path, year } => {
+            run_monthly_command(&db_path, year);
+        }
+        Commands::Books { db_path, days } => {
+            run_books_command(&db_path, days);
+        }
+        Commands::Finished { db_path, year } => {
+            run_finished_command(&db_path, year);
+        }
+        Commands::Highlights { books_dir, text } => {
+            run_highlights_command(&books_dir, text);
+        }
+        Commands::Sessions { db_path, days } => {
+            run_sessions_command(&db_path, days);
         }
     }
 }
 
-fn run_daily_command(db_path: &str) {
-    match get_last_30_days_stats(db_path) {
-        Ok(daily_stats) => {
+fn run_daily_command(db_path: &str, streak_minutes: f64) {
+    let result = source_for(db_path).and_then(|source| {
+        let daily_stats = source.get_last_30_days_stats()?;
+        let streaks = source.get_reading_streaks(streak_minutes)?;
+        let sessions = source.get_bible_session_stats(&DatePeriod::last_30_days()?)?;
+        Ok((daily_stats, streaks, sessions))
+    });
+
+    match result {
+        Ok((daily_stats, streaks, sessions)) => {
             println!("\n=== DAILY READING STATS - LAST 30 DAYS ===\n");
 
             let total_minutes: f64 = daily_stats.iter().map(|d| d.minutes).sum();
@@ -41,10 +152,15 @@ fn run_daily_command(db_path: &str) {
 
             // Print each day
             for day in &daily_stats {
-                let hours = day.minutes / 60.0;
-
                 if day.minutes > 0.0 {
-                    println!("{}: {:.2} min ({:.1} hrs)", day.date, day.minutes, hours);
+                    println!(
+                        "{}: {:.2} min ({}) | {} pages, {:.0} pages/hr",
+                        day.date,
+                        day.minutes,
+                        duration_minutes(day.minutes),
+                        day.pages,
+                        day.pages_per_hour
+                    );
                 } else {
                     println!("{}: --- (no reading)", day.date);
                 }
@@ -52,18 +168,307 @@ fn run_daily_command(db_path: &str) {
 
             println!("\n--- SUMMARY ---");
             println!(
-                "Total Reading Time: {:.2} minutes ({:.1} hours)",
-                total_minutes,
-                total_minutes / 60.0
+                "Total Reading Time: {} ({:.2} minutes)",
+                duration_minutes(total_minutes),
+                total_minutes
             );
             println!(
-                "Average per day: {:.2} minutes ({:.1} hours)",
-                avg_minutes,
-                avg_minutes / 60.0
+                "Average per day: {} ({:.2} minutes)",
+                duration_minutes(avg_minutes),
+                avg_minutes
             );
 
             let days_read = daily_stats.iter().filter(|d| d.minutes > 0.0).count();
             println!("Days with reading: {} out of 30", days_read);
+
+            let total_pages: i64 = daily_stats.iter().map(|d| d.pages).sum();
+            println!(
+                "Pages read: {} ({:.0} pages/hr)",
+                total_pages,
+                pages_per_hour(total_pages, total_minutes)
+            );
+
+            let all_books_minutes: f64 = daily_stats.iter().map(|d| d.all_books_minutes).sum();
+            println!(
+                "Other reading (not Bible): {:.2} minutes",
+                all_books_minutes - total_minutes
+            );
+
+            println!(
+                "\nStreak (days with {} min or more): {} current, {} longest",
+                streaks.min_minutes, streaks.current_streak, streaks.longest_streak
+            );
+            println!(
+                "Sessions: {} (longest {:.1} min, median {:.1} min)",
+                sessions.sessions, sessions.longest_minutes, sessions.median_minutes
+            );
+        }
+        Err(e) => {
+            eprintln!("Error: {:#}", e);
+            process::exit(1);
+        }
+    }
+}
+
+fn run_monthly_command(db_path: &str, year: Option<i32>) {
+    let result = match year {
+        Some(year) => get_year_stats(db_path, year),
+        None => get_last_12_months_stats(db_path),
+    };
+
+    match result {
+        Ok(monthly_stats) => {
+            match year {
+                Some(year) => println!("\n=== MONTHLY READING STATS - {} ===\n", year),
+                None => println!("\n=== MONTHLY READING STATS - LAST 12 MONTHS ===\n"),
+            }
+
+            for month in &monthly_stats {
+                if month.minutes > 0.0 {
+                    println!(
+                        "{}: {:.2} min ({}) | {} pages, {:.0} pages/hr",
+                        month.month,
+                        month.minutes,
+                        duration_minutes(month.minutes),
+                        month.pages,
+                        month.pages_per_hour
+                    );
+                } else {
+                    println!("{}: --- (no reading)", month.month);
+                }
+            }
+
+            let total_minutes: f64 = monthly_stats.iter().map(|m| m.minutes).sum();
+            let total_pages: i64 = monthly_stats.iter().map(|m| m.pages).sum();
+            let months_read = monthly_stats.iter().filter(|m| m.minutes > 0.0).count();
+            println!("\n--- SUMMARY ---");
+            println!(
+                "Total Reading Time: {} ({:.2} minutes)",
+                duration_minutes(total_minutes),
+                total_minutes
+            );
+            println!(
+                "Average per month: {:.2} minutes",
+                total_minutes / monthly_stats.len() as f64
+            );
+            println!(
+                "Months with reading: {} out of {}",
+                months_read,
+                monthly_stats.len()
+            );
+            println!("Pages read: {}", total_pages);
+        }
+        Err(e) => {
+            eprintln!("Error: {:#}", e);
+            process::exit(1);
+        }
+    }
+}
+
+/// Daily period covering the last `days` days, ending today
+fn last_days_period(days: i64) -> anyhow::Result<DatePeriod> {
+    let today = get_today_date()?;
+    let start = today - chrono::Duration::days(days.max(1) - 1);
+    DatePeriod::for_range(start, today, Granularity::Day)
+}
+
+fn run_books_command(db_path: &str, days: i64) {
+    let result = last_days_period(days).and_then(|period| get_per_book_stats(db_path, &period));
+
+    match result {
+        Ok(books) => {
+            println!("\n=== BOOKS READ - LAST {} DAYS ===\n", days.max(1));
+
+            if books.is_empty() {
+                println!("No reading in this period");
+                return;
+            }
+
+            for book in &books {
+                let authors = if book.authors.is_empty() {
+                    String::new()
+                } else {
+                    format!(" ({})", book.authors)
+                };
+                println!(
+                    "{}{}: {:.2} min, {} pages, {} sessions",
+                    book.title, authors, book.minutes, book.pages_read, book.sessions
+                );
+            }
+
+            let total_minutes: f64 = books.iter().map(|b| b.minutes).sum();
+            println!("\n--- SUMMARY ---");
+            println!(
+                "Total Reading Time: {} ({:.2} minutes)",
+                duration_minutes(total_minutes),
+                total_minutes
+            );
+            println!("Books read: {}", books.len());
+        }
+        Err(e) => {
+            eprintln!("Error: {:#}", e);
+            process::exit(1);
+        }
+    }
+}
+
+fn run_sessions_command(db_path: &str, days: i64) {
+    let result = last_days_period(days).and_then(|period| get_reading_sessions(db_path, &period));
+
+    match result {
+        Ok(sessions) => {
+            println!("\n=== READING SESSIONS - LAST {} DAYS ===", days.max(1));
+
+            if sessions.is_empty() {
+                println!("\nNo reading in this period");
+                return;
+            }
+
+            let mut current_date: Option<&str> = None;
+            for session in &sessions {
+                if current_date != Some(session.date.as_str()) {
+                    println!("\n{}", session.date);
+                    current_date = Some(&session.date);
+                }
+
+                // Times without the date, e.g. "07:15"
+                let clock = |time: &str| time.get(11..16).unwrap_or(time).to_string();
+                let pages = if session.first_page == session.last_page {
+                    format!("p. {}", session.first_page)
+                } else {
+                    format!("pp. {}-{}", session.first_page, session.last_page)
+                };
+                println!(
+                    "  {}-{}  {:>5.1} min  {}{} ({}, {} pages)",
+                    clock(&session.start_time),
+                    clock(&session.end_time),
+                    session.minutes,
+                    session.title,
+                    if session.is_bible { " [Bible]" } else { "" },
+                    pages,
+                    session.pages
+                );
+            }
+
+            let total_minutes: f64 = sessions.iter().map(|s| s.minutes).sum();
+            println!("\n--- SUMMARY ---");
+            println!(
+                "{} sessions, {:.2} minutes ({})",
+                sessions.len(),
+                total_minutes,
+                duration_minutes(total_minutes)
+            );
+        }
+        Err(e) => {
+            eprintln!("Error: {:#}", e);
+            process::exit(1);
+        }
+    }
+}
+
+fn run_finished_command(db_path: &str, year: Option<i32>) {
+    let result = year
+        .map_or_else(|| get_today_date().map(|today| today.year()), Ok)
+        .and_then(|year| get_books_finished(db_path, year));
+
+    match result {
+        Ok(books) => {
+            println!("\n=== BOOKS FINISHED IN {} ===\n", books.year);
+
+            if books.finished.is_empty() {
+                println!("No books finished yet");
+            }
+            for book in &books.finished {
+                println!(
+                    "{}: {} ({}, {} pages)",
+                    book.finished_date,
+                    book.title,
+                    duration_minutes(book.minutes),
+                    book.pages
+                );
+            }
+
+            println!("\nBy month:");
+            for month in &books.by_month {
+                println!("  {}: {}", month.month, month.books);
+            }
+
+            if !books.in_progress.is_empty() {
+                println!("\nIn progress:");
+                for book in &books.in_progress {
+                    println!(
+                        "  {}: {:.0}% ({} of {} pages, last read {})",
+                        book.title,
+                        book.percent_complete,
+                        book.pages_read,
+                        book.pages,
+                        book.last_read_date
+                    );
+                }
+            }
+        }
+        Err(e) => {
+            eprintln!("Error: {:#}", e);
+            process::exit(1);
+        }
+    }
+}
+
+fn run_highlights_command(books_dir: &str, text: bool) {
+    let result = get_highlights_per_book(books_dir).and_then(|books| {
+        let days = get_last_30_days_highlights(books_dir)?;
+        let highlights = if text {
+            highlights::get_highlights(books_dir, true)?
+        } else {
+            Vec::new()
+        };
+        Ok((books, days, highlights))
+    });
+
+    match result {
+        Ok((books, days, highlights)) => {
+            println!("\n=== HIGHLIGHTS BY BOOK ===\n");
+            if books.is_empty() {
+                println!("No highlights found");
+            }
+            for book in &books {
+                println!(
+                    "{}: {} highlights, {} notes (last {})",
+                    book.title, book.highlights, book.notes, book.last_highlight_date
+                );
+            }
+
+            println!("\n=== HIGHLIGHTS - LAST 30 DAYS ===\n");
+            for day in days.iter().filter(|d| d.highlights > 0) {
+                println!(
+                    "{}: {} highlights, {} notes",
+                    day.date, day.highlights, day.notes
+                );
+            }
+            let total: i64 = days.iter().map(|d| d.highlights).sum();
+            let days_with = days.iter().filter(|d| d.highlights > 0).count();
+            println!("\nTotal: {} highlights on {} of 30 days", total, days_with);
+
+            if let Some(first_day) = days.first() {
+                for highlight in highlights.iter().filter(|h| h.date >= first_day.date) {
+                    println!(
+                        "\n{} - {}{}",
+                        highlight.datetime,
+                        highlight.title,
+                        highlight
+                            .chapter
+                            .as_ref()
+                            .map(|c| format!(", {}", c))
+                            .unwrap_or_default()
+                    );
+                    if let Some(text) = &highlight.text {
+                        println!("  \"{}\"", text.trim());
+                    }
+                    if let Some(note) = highlight.note.as_ref().filter(|n| !n.is_empty()) {
+                        println!("  Note: {}", note.trim());
+                    }
+                }
+            }
         }
         Err(e) => {
             eprintln!("Error: {:#}", e);
@@ -1,20 +1,250 @@
 use serde::Serialize;
+use statsutils::math;
 use utoipa::ToSchema;
 
+/// Reading speed in pages per hour, or 0 when no time was spent reading
+pub fn pages_per_hour(pages: i64, minutes: f64) -> f64 {
+    if minutes > 0.0 {
+        pages as f64 / (minutes / 60.0)
+    } else {
+        0.0
+    }
+}
+
 /// Reading time statistics for a single day
 #[derive(Debug, Clone, Serialize, ToSchema)]
+#[schema(as = ReadingDayStats)]
 pub struct DayStats {
     /// Date in YYYY-MM-DD format
     pub date: String,
-    /// Reading time in minutes
+    /// Bible reading time in minutes
     pub minutes: f64,
+    /// Reading time in minutes across every book, Bible or not
+    pub all_books_minutes: f64,
+    /// Number of distinct pages turned to
+    pub pages: i64,
+    /// Reading speed in pages per hour
+    pub pages_per_hour: f64,
 }
 
 /// Reading time statistics for a single week
 #[derive(Debug, Clone, Serialize, ToSchema)]
+#[schema(as = ReadingWeekStats)]
 pub struct WeekStats {
     /// Week start date in YYYY-MM-DD format
     pub week_start: String,
+    /// Bible reading time in minutes
+    pub minutes: f64,
+    /// Reading time in minutes across every book, Bible or not
+    pub all_books_minutes: f64,
+    /// Number of distinct pages turned to
+    pub pages: i64,
+    /// Reading speed in pages per hour
+    pub pages_per_hour: f64,
+}
+
+/// Reading time statistics for one bucket (day, week, or month) of an arbitrary period
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct PeriodStats {
+    /// First day of the bucket: YYYY-MM-DD for days and weeks, YYYY-MM for months
+    pub period_start: String,
+    /// Bible reading time in minutes
+    pub minutes: f64,
+    /// Number of distinct pages turned to
+    pub pages: i64,
+    /// Reading speed in pages per hour
+    pub pages_per_hour: f64,
+}
+
+/// Reading time statistics for a single month
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct MonthStats {
+    /// Month in YYYY-MM format
+    pub month: String,
+    /// Reading time in minutes
+    pub minutes: f64,
+    /// Number of distinct pages turned to
+    pub pages: i64,
+    /// Reading speed in pages per hour
+    pub pages_per_hour: f64,
+}
+
+/// Reading totals for a single book over a period
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct BookReadingStats {
+    /// KOReader's id for the book
+    pub id: i64,
+    pub title: String,
+    pub authors: String,
     /// Reading time in minutes
     pub minutes: f64,
+    /// Number of distinct pages read
+    pub pages_read: i64,
+    /// Number of reading sessions, where a pause of more than 30 minutes starts a new one
+    pub sessions: i64,
+}
+
+/// One continuous stretch of reading in a single book
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ReadingSession {
+    /// Date the session counts toward in YYYY-MM-DD format (days start at 4 AM)
+    pub date: String,
+    /// Local time of the first page turn, YYYY-MM-DD HH:MM:SS
+    pub start_time: String,
+    /// Local time the last page was left, YYYY-MM-DD HH:MM:SS
+    pub end_time: String,
+    /// Time spent on pages in minutes, excluding short pauses between them
+    pub minutes: f64,
+    /// KOReader's id for the book
+    pub book_id: i64,
+    pub title: String,
+    pub authors: String,
+    /// Whether the book counts as Bible reading
+    pub is_bible: bool,
+    /// Number of distinct pages read
+    pub pages: i64,
+    pub first_page: i64,
+    pub last_page: i64,
+}
+
+/// A book whose pages were read through to the end
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct FinishedBook {
+    /// KOReader's id for the book
+    pub id: i64,
+    pub title: String,
+    pub authors: String,
+    /// Date the book was finished in YYYY-MM-DD format
+    pub finished_date: String,
+    /// Total reading time in minutes, across all sessions
+    pub minutes: f64,
+    /// Page count of the book
+    pub pages: i64,
+}
+
+/// A book started but not yet finished
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct BookInProgress {
+    /// KOReader's id for the book
+    pub id: i64,
+    pub title: String,
+    pub authors: String,
+    /// Number of distinct pages read
+    pub pages_read: i64,
+    /// Page count of the book
+    pub pages: i64,
+    /// Percent of the book's pages read (0-100)
+    pub percent_complete: f64,
+    /// Date the book was last read in YYYY-MM-DD format
+    pub last_read_date: String,
+}
+
+/// Number of books finished in one month
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct MonthBooksFinished {
+    /// Month in YYYY-MM format
+    pub month: String,
+    pub books: i64,
+}
+
+/// Books finished during a year, and the books still being read
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct BooksFinished {
+    pub year: i32,
+    /// Books finished during the year, in the order they were finished
+    pub finished: Vec<FinishedBook>,
+    /// Books finished in each month of the year, January first
+    pub by_month: Vec<MonthBooksFinished>,
+    /// Books read during the year but not finished, most complete first
+    pub in_progress: Vec<BookInProgress>,
+}
+
+/// Runs of consecutive days with enough Bible reading
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ReadingStreaks {
+    /// Minutes a day needs for it to count toward a streak
+    pub min_minutes: f64,
+    /// Consecutive qualifying days ending today (or yesterday, if today doesn't qualify yet)
+    pub current_streak: i64,
+    pub longest_streak: i64,
+    /// All-time number of qualifying days
+    pub qualifying_days: i64,
+    /// Most recent qualifying date in YYYY-MM-DD format
+    pub last_qualifying_date: Option<String>,
+}
+
+/// How long reading sessions last
+#[derive(Debug, Clone, Default, Serialize, ToSchema)]
+pub struct SessionLengthStats {
+    pub sessions: i64,
+    /// Length of the longest session in minutes
+    pub longest_minutes: f64,
+    /// Median session length in minutes
+    pub median_minutes: f64,
+    /// Mean session length in minutes
+    pub average_minutes: f64,
+}
+
+impl SessionLengthStats {
+    pub fn from_sessions(sessions: &[ReadingSession]) -> Self {
+        Self::from_minutes(sessions.iter().map(|s| s.minutes).collect())
+    }
+
+    /// Summarizes session lengths given in minutes
+    pub fn from_minutes(minutes: Vec<f64>) -> Self {
+        if minutes.is_empty() {
+            return Self::default();
+        }
+
+        Self {
+            sessions: minutes.len() as i64,
+            longest_minutes: minutes.iter().copied().fold(f64::MIN, f64::max),
+            median_minutes: math::median(&minutes),
+            average_minutes: math::mean(&minutes),
+        }
+    }
+}
+
+/// A passage highlighted in KOReader, from the book's metadata file
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct Highlight {
+    pub title: String,
+    pub authors: String,
+    /// KOReader's partial MD5 of the book file, matching `book.md5` in the statistics database
+    pub md5: Option<String>,
+    /// Local time the highlight was made, YYYY-MM-DD HH:MM:SS
+    pub datetime: String,
+    /// Date the highlight counts toward in YYYY-MM-DD format (days start at 4 AM)
+    pub date: String,
+    pub chapter: Option<String>,
+    pub page: Option<i64>,
+    /// Whether a note was written on the highlight
+    pub has_note: bool,
+    /// Highlighted text, when requested
+    pub text: Option<String>,
+    /// Note text, when requested
+    pub note: Option<String>,
+}
+
+/// Highlight and note counts for a single book
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct BookHighlights {
+    pub title: String,
+    pub authors: String,
+    pub md5: Option<String>,
+    pub highlights: i64,
+    /// Number of highlights with a note
+    pub notes: i64,
+    /// Date of the most recent highlight in YYYY-MM-DD format
+    pub last_highlight_date: String,
+}
+
+/// Highlight and note counts for a single day
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct DayHighlights {
+    /// Date in YYYY-MM-DD format
+    pub date: String,
+    pub highlights: i64,
+    /// Number of highlights with a note
+    pub notes: i64,
 }
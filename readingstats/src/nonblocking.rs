@@ -0,0 +1,62 @@
+//! Async versions of the main entry points, run on tokio's blocking thread pool
+//!
+//! Each function takes the same arguments as its namesake in the crate root.
+
+use anyhow::Result;
+use statsutils::DatePeriod;
+use statsutils::nonblocking::run;
+
+use crate::models::{
+    BookReadingStats, BooksFinished, DayStats, MonthStats, PeriodStats, ReadingStreaks, WeekStats,
+};
+
+/// Async `crate::get_today_reading_time`
+pub async fn get_today_reading_time(db_path: &str) -> Result<f64> {
+    let db_path = db_path.to_string();
+    run(move || crate::get_today_reading_time(&db_path)).await
+}
+
+/// Async `crate::get_last_30_days_stats`
+pub async fn get_last_30_days_stats(db_path: &str) -> Result<Vec<DayStats>> {
+    let db_path = db_path.to_string();
+    run(move || crate::get_last_30_days_stats(&db_path)).await
+}
+
+/// Async `crate::get_last_12_weeks_stats`
+pub async fn get_last_12_weeks_stats(db_path: &str) -> Result<Vec<WeekStats>> {
+    let db_path = db_path.to_string();
+    run(move || crate::get_last_12_weeks_stats(&db_path)).await
+}
+
+/// Async `crate::get_last_12_months_stats`
+pub async fn get_last_12_months_stats(db_path: &str) -> Result<Vec<MonthStats>> {
+    let db_path = db_path.to_string();
+    run(move || crate::get_last_12_months_stats(&db_path)).await
+}
+
+/// Async `crate::get_per_book_stats`
+pub async fn get_per_book_stats(
+    db_path: &str,
+    period: &DatePeriod,
+) -> Result<Vec<BookReadingStats>> {
+    let (db_path, period) = (db_path.to_string(), period.clone());
+    run(move || crate::get_per_book_stats(&db_path, &period)).await
+}
+
+/// Async `crate::get_books_finished`
+pub async fn get_books_finished(db_path: &str, year: i32) -> Result<BooksFinished> {
+    let db_path = db_path.to_string();
+    run(move || crate::get_books_finished(&db_path, year)).await
+}
+
+/// Async `crate::get_reading_streaks`
+pub async fn get_reading_streaks(db_path: &str, min_minutes: f64) -> Result<ReadingStreaks> {
+    let db_path = db_path.to_string();
+    run(move || crate::get_reading_streaks(&db_path, min_minutes)).await
+}
+
+/// Async `crate::get_stats_for_period`
+pub async fn get_stats_for_period(db_path: &str, period: &DatePeriod) -> Result<Vec<PeriodStats>> {
+    let (db_path, period) = (db_path.to_string(), period.clone());
+    run(move || crate::get_stats_for_period(&db_path, &period)).await
+}
@@ -0,0 +1,174 @@
+use anyhow::{Context, Result, bail};
+use rusqlite::Connection;
+
+/// Layout of a KOReader statistics database
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchemaVersion {
+    /// Before KOReader 2020.10: reading time lives in a `page_stat` table with a `period`
+    /// column and no per-row page count
+    Legacy,
+    /// KOReader 2020.10 (schema version 20201010) and later: reading time lives in
+    /// `page_stat_data`
+    PageStatData,
+}
+
+/// Works out which layout a KOReader statistics database uses
+///
+/// The tables themselves are checked rather than trusting `PRAGMA user_version`, since
+/// databases copied between devices or repaired by hand don't always keep it in step.
+pub fn detect_schema(conn: &Connection) -> Result<SchemaVersion> {
+    if !has_object(conn, "table", "book")? {
+        let user_version: i64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        bail!(
+            "Not a KOReader statistics database: no 'book' table (schema version {})",
+            user_version
+        );
+    }
+
+    if has_object(conn, "table", "page_stat_data")? {
+        Ok(SchemaVersion::PageStatData)
+    } else if has_object(conn, "table", "page_stat")? {
+        Ok(SchemaVersion::Legacy)
+    } else {
+        bail!("Unsupported KOReader statistics database: no 'page_stat_data' or 'page_stat' table")
+    }
+}
+
+/// Adapts an older KOReader statistics database so the current queries can run against it
+///
+/// Temporary views are created over the original tables, so nothing is written to the
+/// database itself:
+/// - `page_stat_data` over a legacy `page_stat` table, taking each row's page count from
+///   the book
+/// - `book` filling in `md5`, `authors`, and `pages` as NULL where releases before they
+///   were added didn't record them
+pub fn adapt_schema(conn: &Connection) -> Result<SchemaVersion> {
+    let version = detect_schema(conn)?;
+
+    let book_columns = column_names(conn, "book")?;
+    let missing: Vec<&str> = ["authors", "pages", "md5"]
+        .into_iter()
+        .filter(|column| !book_columns.iter().any(|c| c == column))
+        .collect();
+    if !missing.is_empty() {
+        let columns: Vec<String> = ["id", "title", "authors", "pages", "md5"]
+            .iter()
+            .map(|column| {
+                if missing.contains(column) {
+                    format!("NULL AS {}", column)
+                } else {
+                    column.to_string()
+                }
+            })
+            .collect();
+        conn.execute_batch(&format!(
+            "CREATE TEMP VIEW book AS SELECT {} FROM main.book",
+            columns.join(", ")
+        ))
+        .context("Failed to adapt KOReader book table")?;
+    }
+
+    if version == SchemaVersion::Legacy {
+        conn.execute_batch(
+            r#"
+            CREATE TEMP VIEW page_stat_data AS
+            SELECT
+                ps.id_book,
+                ps.page,
+                ps.start_time,
+                ps.period AS duration,
+                COALESCE(b.pages, 0) AS total_pages
+            FROM main.page_stat ps
+            LEFT JOIN book b ON b.id = ps.id_book
+            "#,
+        )
+        .context("Failed to adapt legacy KOReader page_stat table")?;
+    }
+
+    Ok(version)
+}
+
+fn has_object(conn: &Connection, kind: &str, name: &str) -> Result<bool> {
+    Ok(conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM main.sqlite_master WHERE type = ?1 AND name = ?2)",
+        [kind, name],
+        |row| row.get(0),
+    )?)
+}
+
+fn column_names(conn: &Connection, table: &str) -> Result<Vec<String>> {
+    let mut stmt = conn.prepare(&format!("PRAGMA main.table_info({})", table))?;
+    let names = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .collect::<Result<Vec<String>, _>>()?;
+    Ok(names)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rusqlite::OpenFlags;
+
+    fn read_only(path: &std::path::Path) -> Connection {
+        Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY).unwrap()
+    }
+
+    #[test]
+    fn test_current_schema_is_untouched() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("statistics.sqlite3");
+        Connection::open(&path)
+            .unwrap()
+            .execute_batch(
+                "CREATE TABLE book (id integer PRIMARY KEY, title text, authors text, pages integer, md5 text);
+                 CREATE TABLE page_stat_data (id_book integer, page integer, start_time integer, duration integer, total_pages integer);
+                 PRAGMA user_version = 20221111;",
+            )
+            .unwrap();
+
+        let conn = read_only(&path);
+        assert_eq!(adapt_schema(&conn).unwrap(), SchemaVersion::PageStatData);
+        let temp_views: i64 = conn
+            .query_row("SELECT COUNT(*) FROM temp.sqlite_master", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(temp_views, 0);
+    }
+
+    #[test]
+    fn test_legacy_schema_is_adapted() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("statistics.sqlite3");
+        Connection::open(&path)
+            .unwrap()
+            .execute_batch(
+                "CREATE TABLE book (id integer PRIMARY KEY, title text, authors text, pages integer);
+                 CREATE TABLE page_stat (id_book integer, page integer, start_time integer, period integer);
+                 INSERT INTO book VALUES (1, 'ESV Bible', 'Crossway', 1200);
+                 INSERT INTO page_stat VALUES (1, 5, 1700000000, 90);",
+            )
+            .unwrap();
+
+        let conn = read_only(&path);
+        assert_eq!(adapt_schema(&conn).unwrap(), SchemaVersion::Legacy);
+        let row: (String, Option<String>, i64, i64) = conn
+            .query_row(
+                "SELECT b.title, b.md5, psd.duration, psd.total_pages
+                 FROM page_stat_data psd JOIN book b ON b.id = psd.id_book",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+            )
+            .unwrap();
+        assert_eq!(row, ("ESV Bible".to_string(), None, 90, 1200));
+    }
+
+    #[test]
+    fn test_other_database_is_rejected() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("CREATE TABLE notes (id integer)")
+            .unwrap();
+        let err = detect_schema(&conn).unwrap_err();
+        assert!(err.to_string().contains("no 'book' table"));
+    }
+}
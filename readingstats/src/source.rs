@@ -0,0 +1,176 @@
+use anyhow::{Context, Result, bail};
+use chrono::NaiveDate;
+use statsutils::sqlite::{OpenOptions, open_readonly_with_retry};
+use statsutils::{DatePeriod, get_today_date};
+
+use crate::models::{DayStats, PeriodStats, ReadingStreaks, SessionLengthStats, WeekStats};
+use crate::{kobo, streaks};
+
+/// A source of Bible reading time
+///
+/// KOReader's statistics database is the primary source; a Kobo device's own database is
+/// supported for readers using the stock Kobo software.
+pub trait ReadingSource {
+    /// Name of the database format, e.g. "KOReader" or "Kobo"
+    fn format(&self) -> String;
+
+    /// Gets reading time for each of the last 30 days
+    fn get_last_30_days_stats(&self) -> Result<Vec<DayStats>>;
+
+    /// Gets reading time for each of the last 12 weeks
+    fn get_last_12_weeks_stats(&self) -> Result<Vec<WeekStats>>;
+
+    /// Gets reading time for each bucket of a period, at the period's granularity
+    fn get_stats_for_period(&self, period: &DatePeriod) -> Result<Vec<PeriodStats>>;
+
+    /// Gets the total reading time for today in minutes
+    fn get_today_reading_time(&self) -> Result<f64>;
+
+    /// Gets the current and longest run of days with at least `min_minutes` of reading
+    fn get_reading_streaks(&self, min_minutes: f64) -> Result<ReadingStreaks>;
+
+    /// Gets the minutes of Bible reading on every day with any, oldest first
+    fn get_daily_bible_minutes(&self) -> Result<Vec<(NaiveDate, f64)>>;
+
+    /// Gets the minutes spent in each Bible book (by title) during a period, most read first
+    fn get_bible_title_minutes(&self, period: &DatePeriod) -> Result<Vec<(String, f64)>>;
+
+    /// Gets the longest, median, and average reading session length during a period
+    fn get_bible_session_stats(&self, period: &DatePeriod) -> Result<SessionLengthStats>;
+}
+
+/// Reads a KOReader statistics.sqlite3 database
+pub struct KoreaderSource {
+    db_path: String,
+}
+
+impl KoreaderSource {
+    pub fn new(db_path: &str) -> Self {
+        Self {
+            db_path: db_path.to_string(),
+        }
+    }
+}
+
+impl ReadingSource for KoreaderSource {
+    fn format(&self) -> String {
+        "KOReader".to_string()
+    }
+
+    fn get_last_30_days_stats(&self) -> Result<Vec<DayStats>> {
+        crate::get_last_30_days_stats(&self.db_path)
+    }
+
+    fn get_last_12_weeks_stats(&self) -> Result<Vec<WeekStats>> {
+        crate::get_last_12_weeks_stats(&self.db_path)
+    }
+
+    fn get_stats_for_period(&self, period: &DatePeriod) -> Result<Vec<PeriodStats>> {
+        crate::get_stats_for_period(&self.db_path, period)
+    }
+
+    fn get_today_reading_time(&self) -> Result<f64> {
+        crate::get_today_reading_time(&self.db_path)
+    }
+
+    fn get_reading_streaks(&self, min_minutes: f64) -> Result<ReadingStreaks> {
+        crate::get_reading_streaks(&self.db_path, min_minutes)
+    }
+
+    fn get_daily_bible_minutes(&self) -> Result<Vec<(NaiveDate, f64)>> {
+        crate::get_daily_bible_minutes(&self.db_path)
+    }
+
+    fn get_bible_title_minutes(&self, period: &DatePeriod) -> Result<Vec<(String, f64)>> {
+        crate::get_bible_title_minutes(&self.db_path, period)
+    }
+
+    fn get_bible_session_stats(&self, period: &DatePeriod) -> Result<SessionLengthStats> {
+        crate::get_bible_session_stats(&self.db_path, period)
+    }
+}
+
+/// Reads a Kobo e-reader's KoboReader.sqlite database
+pub struct KoboSource {
+    db_path: String,
+}
+
+impl KoboSource {
+    pub fn new(db_path: &str) -> Self {
+        Self {
+            db_path: db_path.to_string(),
+        }
+    }
+}
+
+impl ReadingSource for KoboSource {
+    fn format(&self) -> String {
+        "Kobo".to_string()
+    }
+
+    fn get_last_30_days_stats(&self) -> Result<Vec<DayStats>> {
+        kobo::get_last_30_days_stats(&kobo::open_database(&self.db_path)?)
+    }
+
+    fn get_last_12_weeks_stats(&self) -> Result<Vec<WeekStats>> {
+        kobo::get_last_12_weeks_stats(&kobo::open_database(&self.db_path)?)
+    }
+
+    fn get_stats_for_period(&self, period: &DatePeriod) -> Result<Vec<PeriodStats>> {
+        kobo::get_stats_for_period(&kobo::open_database(&self.db_path)?, period.clone())
+    }
+
+    fn get_today_reading_time(&self) -> Result<f64> {
+        kobo::get_today_reading_minutes(&kobo::open_database(&self.db_path)?)
+    }
+
+    fn get_reading_streaks(&self, min_minutes: f64) -> Result<ReadingStreaks> {
+        let daily_minutes = kobo::get_daily_bible_minutes(&kobo::open_database(&self.db_path)?)?;
+        Ok(streaks::compute_streaks(
+            &daily_minutes,
+            min_minutes,
+            get_today_date()?,
+        ))
+    }
+
+    fn get_daily_bible_minutes(&self) -> Result<Vec<(NaiveDate, f64)>> {
+        kobo::get_daily_bible_minutes(&kobo::open_database(&self.db_path)?)
+    }
+
+    fn get_bible_title_minutes(&self, period: &DatePeriod) -> Result<Vec<(String, f64)>> {
+        kobo::get_bible_title_minutes(&kobo::open_database(&self.db_path)?, period)
+    }
+
+    fn get_bible_session_stats(&self, period: &DatePeriod) -> Result<SessionLengthStats> {
+        kobo::get_bible_session_stats(&kobo::open_database(&self.db_path)?, period)
+    }
+}
+
+/// Picks a source for a database by looking at which tables it has
+pub fn source_for(db_path: &str) -> Result<Box<dyn ReadingSource>> {
+    let options = OpenOptions {
+        date_functions: None,
+        ..OpenOptions::new("reading database")
+    };
+    let conn = open_readonly_with_retry(db_path, &options)
+        .context(format!("Failed to open reading database '{}'", db_path))?;
+    let has_table = |name: &str| -> Result<bool> {
+        Ok(conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?1)",
+            [name],
+            |row| row.get(0),
+        )?)
+    };
+
+    // Older KOReader releases have `page_stat` rather than `page_stat_data`
+    if has_table("page_stat_data")? || has_table("page_stat")? {
+        Ok(Box::new(KoreaderSource::new(db_path)))
+    } else if has_table("AnalyticsEvents")? {
+        Ok(Box::new(KoboSource::new(db_path)))
+    } else {
+        bail!(
+            "'{}' is neither a KOReader statistics database nor a Kobo database",
+            db_path
+        )
+    }
+}
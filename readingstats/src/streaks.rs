@@ -0,0 +1,74 @@
+use chrono::NaiveDate;
+
+use crate::models::ReadingStreaks;
+
+/// Minutes of Bible reading a day needs for it to extend a streak by default
+pub const DEFAULT_STREAK_MINUTES: f64 = 5.0;
+
+/// Computes reading streaks: runs of consecutive days with at least `min_minutes` read
+///
+/// The current streak counts qualifying days ending today. If today doesn't qualify yet,
+/// the streak ending yesterday is still considered current, since the day isn't over.
+pub fn compute_streaks(
+    daily_minutes: &[(NaiveDate, f64)],
+    min_minutes: f64,
+    today: NaiveDate,
+) -> ReadingStreaks {
+    let dates: Vec<NaiveDate> = daily_minutes
+        .iter()
+        .filter(|(_, minutes)| *minutes >= min_minutes)
+        .map(|(date, _)| *date)
+        .collect();
+    let streaks = statsutils::streaks::compute_as_of(&dates, today);
+
+    ReadingStreaks {
+        min_minutes,
+        current_streak: streaks.current_streak,
+        longest_streak: streaks.longest_streak,
+        qualifying_days: streaks.active_days,
+        last_qualifying_date: streaks
+            .last_active_date
+            .map(|d| d.format("%Y-%m-%d").to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_short_days_break_streaks() {
+        let days = [
+            (date("2025-10-14"), 12.0),
+            (date("2025-10-15"), 6.0),
+            (date("2025-10-16"), 2.0),
+            (date("2025-10-17"), 20.0),
+            (date("2025-10-18"), 5.0),
+            (date("2025-10-19"), 7.5),
+        ];
+
+        let streaks = compute_streaks(&days, 5.0, date("2025-10-20"));
+        assert_eq!(streaks.current_streak, 3);
+        assert_eq!(streaks.longest_streak, 3);
+        assert_eq!(streaks.qualifying_days, 5);
+        assert_eq!(streaks.last_qualifying_date.as_deref(), Some("2025-10-19"));
+
+        let streaks = compute_streaks(&days, 1.0, date("2025-10-20"));
+        assert_eq!(streaks.longest_streak, 6);
+
+        let streaks = compute_streaks(&days, 5.0, date("2025-10-22"));
+        assert_eq!(streaks.current_streak, 0);
+    }
+
+    #[test]
+    fn test_no_reading() {
+        let streaks = compute_streaks(&[], DEFAULT_STREAK_MINUTES, date("2025-10-20"));
+        assert_eq!(streaks.current_streak, 0);
+        assert_eq!(streaks.longest_streak, 0);
+        assert_eq!(streaks.last_qualifying_date, None);
+    }
+}
@@ -0,0 +1,277 @@
+use chrono::{NaiveDate, Utc};
+use readingstats::db::SESSION_GAP_SECONDS;
+use readingstats::kobo;
+use readingstats::{
+    get_bible_title_minutes, get_books_finished, get_last_30_days_stats, get_reading_sessions,
+    get_today_reading_time,
+};
+use statsutils::testing::{KoboFixture, KoreaderFixture};
+use statsutils::{DatePeriod, Granularity, PeriodConfig};
+
+/// Milliseconds for `hours` past the start of a logical day (the rollover hour)
+fn local_ms(date: &str, hours: i64) -> i64 {
+    let date = NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap();
+    PeriodConfig::default().day_start_ms(date).unwrap() + hours * 3_600_000
+}
+
+/// Builds a statistics database with a Bible and a novel, and checks that only Bible
+/// reading is counted
+#[test]
+fn test_stats_from_built_database() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("statistics.sqlite3");
+    let now_ms = Utc::now().timestamp_millis();
+
+    let mut koreader = KoreaderFixture::create(&path).unwrap();
+    let bible = koreader.book("ESV Study Bible", "Crossway", 2752).unwrap();
+    let novel = koreader.book("Gilead", "Marilynne Robinson", 247).unwrap();
+    koreader.page_read(bible, 100, now_ms, 300).unwrap();
+    koreader.page_read(bible, 101, now_ms + 1_000, 180).unwrap();
+    koreader.page_read(novel, 12, now_ms, 900).unwrap();
+
+    let db_path = path.to_str().unwrap();
+    assert_eq!(get_today_reading_time(db_path).unwrap(), 8.0);
+
+    let days = get_last_30_days_stats(db_path).unwrap();
+    assert_eq!(days.len(), 30);
+    assert_eq!(days.last().unwrap().minutes, 8.0);
+
+    let titles = get_bible_title_minutes(db_path, &DatePeriod::last_30_days().unwrap()).unwrap();
+    assert_eq!(titles, vec![("ESV Study Bible".to_string(), 8.0)]);
+}
+
+/// A page read that runs past the rollover hour has its time split between the two days,
+/// and its page counted on the day it started
+#[test]
+fn test_read_across_rollover_splits_minutes() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("statistics.sqlite3");
+    let today = PeriodConfig::default().today().unwrap();
+    let today_start_ms = PeriodConfig::default().day_start_ms(today).unwrap();
+
+    let mut koreader = KoreaderFixture::create(&path).unwrap();
+    let bible = koreader.book("ESV Study Bible", "Crossway", 2752).unwrap();
+    koreader
+        .page_read(bible, 100, today_start_ms - 10 * 60_000, 30 * 60)
+        .unwrap();
+
+    let days = get_last_30_days_stats(path.to_str().unwrap()).unwrap();
+    let (yesterday, today) = (&days[28], &days[29]);
+    assert_eq!((yesterday.minutes, yesterday.pages), (10.0, 1));
+    assert_eq!((today.minutes, today.pages), (20.0, 0));
+    assert_eq!(today.all_books_minutes, 20.0);
+}
+
+/// Reads a minute per page, one page after another, starting `hours` into the day
+fn read_pages(
+    koreader: &mut KoreaderFixture,
+    book: i64,
+    pages: std::ops::RangeInclusive<i64>,
+    date: &str,
+    hours: i64,
+) {
+    let start_ms = local_ms(date, hours);
+    for (i, page) in pages.enumerate() {
+        koreader
+            .page_read(book, page, start_ms + i as i64 * 60_000, 60)
+            .unwrap();
+    }
+}
+
+/// A book is finished when its distinct pages first reach 95% of its page count; rereading
+/// pages adds time but neither new pages nor a later finish date
+#[test]
+fn test_books_finished_with_rereads() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("statistics.sqlite3");
+
+    let mut koreader = KoreaderFixture::create(&path).unwrap();
+    let book = koreader.book("Confessions", "Augustine", 20).unwrap();
+    read_pages(&mut koreader, book, 1..=10, "2024-03-01", 2);
+    read_pages(&mut koreader, book, 1..=10, "2024-03-05", 2);
+    read_pages(&mut koreader, book, 11..=19, "2024-03-09", 2);
+    read_pages(&mut koreader, book, 1..=20, "2024-04-02", 2);
+
+    let books = get_books_finished(path.to_str().unwrap(), 2024).unwrap();
+    assert_eq!(books.finished.len(), 1);
+    let finished = &books.finished[0];
+    assert_eq!(finished.title, "Confessions");
+    // 19 of 20 pages is 95%, reached on the third day; the rereads don't move it
+    assert_eq!(finished.finished_date, "2024-03-09");
+    assert_eq!(finished.minutes, 49.0);
+    assert_eq!(finished.pages, 20);
+    assert!(books.in_progress.is_empty());
+
+    let by_month: Vec<i64> = books.by_month.iter().map(|month| month.books).collect();
+    assert_eq!(by_month, vec![0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+
+    // Counted in the year it was finished, not the years it was reread
+    let next_year = get_books_finished(path.to_str().unwrap(), 2025).unwrap();
+    assert!(next_year.finished.is_empty());
+}
+
+/// Pages read in a different layout are scaled to the book's page count, and a book
+/// without a page count is never finished
+#[test]
+fn test_books_finished_with_partial_and_zero_page_counts() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("statistics.sqlite3");
+
+    let mut koreader = KoreaderFixture::create(&path).unwrap();
+    let scaled = koreader
+        .book("The Pilgrim's Progress", "John Bunyan", 100)
+        .unwrap();
+    let start_ms = local_ms("2024-06-10", 2);
+    for page in 1..=100 {
+        koreader
+            .page_read_in_layout(scaled, page, 200, start_ms + page * 60_000, 60)
+            .unwrap();
+    }
+    let unknown = koreader.book("Untitled", "", 0).unwrap();
+    read_pages(&mut koreader, unknown, 1..=30, "2024-06-11", 2);
+
+    let books = get_books_finished(path.to_str().unwrap(), 2024).unwrap();
+    assert!(books.finished.is_empty());
+    assert_eq!(books.in_progress.len(), 2);
+
+    // 100 of 200 small pages is half the book
+    let pilgrim = books
+        .in_progress
+        .iter()
+        .find(|book| book.id == scaled)
+        .unwrap();
+    assert_eq!(pilgrim.pages_read, 50);
+    assert_eq!(pilgrim.percent_complete, 50.0);
+    assert_eq!(pilgrim.last_read_date, "2024-06-10");
+
+    let untitled = books
+        .in_progress
+        .iter()
+        .find(|book| book.id == unknown)
+        .unwrap();
+    assert_eq!(untitled.pages_read, 30);
+    assert_eq!(untitled.pages, 0);
+    assert_eq!(untitled.percent_complete, 0.0);
+}
+
+/// Page reads join one session while each starts within `SESSION_GAP_SECONDS` of the end
+/// of the reads before it, whether they touch, overlap, or leave a short pause
+#[test]
+fn test_reading_sessions_merge_adjacent_and_overlapping_reads() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("statistics.sqlite3");
+
+    let mut koreader = KoreaderFixture::create(&path).unwrap();
+    let bible = koreader.book("ESV Study Bible", "Crossway", 2752).unwrap();
+    let novel = koreader.book("Gilead", "Marilynne Robinson", 247).unwrap();
+    let start_ms = local_ms("2024-05-12", 4);
+    let at = |secs: i64| start_ms + secs * 1000;
+
+    // Adjacent: page 11 starts as page 10 ends
+    koreader.page_read(bible, 10, at(0), 120).unwrap();
+    koreader.page_read(bible, 11, at(120), 120).unwrap();
+    // Overlapping: page 12 starts before page 11 ends, and is reread
+    koreader.page_read(bible, 12, at(200), 100).unwrap();
+    koreader.page_read(bible, 12, at(300), 80).unwrap();
+    // A pause of exactly the gap still continues the session
+    koreader
+        .page_read(bible, 13, at(380 + SESSION_GAP_SECONDS), 60)
+        .unwrap();
+    // One second more starts a new one
+    koreader
+        .page_read(bible, 14, at(440 + 2 * SESSION_GAP_SECONDS + 1), 90)
+        .unwrap();
+    // Another book read in between is its own session
+    koreader.page_read(novel, 1, at(600), 300).unwrap();
+
+    let date = NaiveDate::from_ymd_opt(2024, 5, 12).unwrap();
+    let period = DatePeriod::days(date, date).unwrap();
+    let sessions = get_reading_sessions(path.to_str().unwrap(), &period).unwrap();
+
+    let summary: Vec<(&str, f64, i64, i64, i64)> = sessions
+        .iter()
+        .map(|session| {
+            (
+                session.title.as_str(),
+                session.minutes,
+                session.pages,
+                session.first_page,
+                session.last_page,
+            )
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("ESV Study Bible", 8.0, 4, 10, 13),
+            ("Gilead", 5.0, 1, 1, 1),
+            ("ESV Study Bible", 1.5, 1, 14, 14),
+        ]
+    );
+
+    // The first session runs from the first page turn to the end of the last read
+    let config = PeriodConfig::default();
+    let local_time = |ms: i64| {
+        config
+            .timezone
+            .local_datetime(ms)
+            .unwrap()
+            .format("%Y-%m-%d %H:%M:%S")
+            .to_string()
+    };
+    assert_eq!(sessions[0].date, "2024-05-12");
+    assert_eq!(sessions[0].start_time, local_time(at(0)));
+    assert_eq!(
+        sessions[0].end_time,
+        local_time(at(440 + SESSION_GAP_SECONDS))
+    );
+    assert!(sessions[0].is_bible);
+    assert!(!sessions[1].is_bible);
+}
+
+/// Kobo's close-book events count toward the logical day they end on, for Bible titles only
+#[test]
+fn test_kobo_stats_per_day() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("KoboReader.sqlite");
+
+    let mut kobo_db = KoboFixture::create(&path).unwrap();
+    kobo_db
+        .leave_content("ESV Study Bible", local_ms("2024-05-12", 3), 600, 12)
+        .unwrap();
+    kobo_db
+        .leave_content("ESV Study Bible", local_ms("2024-05-12", 15), 900, 8)
+        .unwrap();
+    kobo_db
+        .leave_content("Gilead", local_ms("2024-05-12", 16), 1_200, 30)
+        .unwrap();
+    // Just before the rollover hour still counts toward the 13th
+    kobo_db
+        .leave_content(
+            "ESV Study Bible",
+            local_ms("2024-05-14", 0) - 60_000,
+            300,
+            4,
+        )
+        .unwrap();
+
+    let conn = kobo::open_database(path.to_str().unwrap()).unwrap();
+    let start = NaiveDate::from_ymd_opt(2024, 5, 12).unwrap();
+    let end = NaiveDate::from_ymd_opt(2024, 5, 14).unwrap();
+    let period = DatePeriod::for_range(start, end, Granularity::Day).unwrap();
+    let days = kobo::get_stats_for_period(&conn, period).unwrap();
+
+    let summary: Vec<(&str, f64, i64)> = days
+        .iter()
+        .map(|day| (day.period_start.as_str(), day.minutes, day.pages))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("2024-05-12", 25.0, 20),
+            ("2024-05-13", 5.0, 4),
+            ("2024-05-14", 0.0, 0),
+        ]
+    );
+    assert_eq!(days[0].pages_per_hour, 48.0);
+}
@@ -0,0 +1,152 @@
+use anyhow::{Context, Result};
+use chrono::{Datelike, Duration, Months, NaiveDate, Weekday};
+
+use crate::date_periods::Granularity;
+
+/// Which day a bucket's label names
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LabelStyle {
+    /// First and last day, e.g. "Oct 19–25"
+    #[default]
+    Range,
+    /// First day only, e.g. "Oct 19"
+    Start,
+    /// Last day only, for "week ending" displays, e.g. "Oct 25"
+    End,
+}
+
+/// One bucket of a `DatePeriod`, with the days it covers
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bucket {
+    /// Key the bucket's results are stored under (YYYY-MM-DD, YYYY-Www, or YYYY-MM)
+    pub key: String,
+    /// First day of the bucket
+    pub start: NaiveDate,
+    /// Last day of the bucket, inclusive
+    pub end: NaiveDate,
+    /// Human-readable name in `LabelStyle::Range` style: "Oct 19", "Oct 19–25", "Oct 2025"
+    pub label: String,
+}
+
+impl Bucket {
+    /// Describes the bucket identified by `key`, as produced by a `DatePeriod` of the same
+    /// granularity
+    pub fn from_key(key: &str, granularity: Granularity) -> Result<Self> {
+        let invalid = || format!("Invalid {} bucket key '{}'", granularity, key);
+
+        let (start, end) = match granularity {
+            Granularity::Day => {
+                let date = NaiveDate::parse_from_str(key, "%Y-%m-%d").with_context(invalid)?;
+                (date, date)
+            }
+            Granularity::Week => {
+                let start = match key.split_once("-W") {
+                    Some((year, week)) => NaiveDate::from_isoywd_opt(
+                        year.parse().with_context(invalid)?,
+                        week.parse().with_context(invalid)?,
+                        Weekday::Mon,
+                    )
+                    .with_context(invalid)?,
+                    None => NaiveDate::parse_from_str(key, "%Y-%m-%d").with_context(invalid)?,
+                };
+                (start, start + Duration::days(6))
+            }
+            Granularity::Month => {
+                let start = NaiveDate::parse_from_str(&format!("{}-01", key), "%Y-%m-%d")
+                    .with_context(invalid)?;
+                let next = start
+                    .checked_add_months(Months::new(1))
+                    .with_context(invalid)?;
+                (start, next - Duration::days(1))
+            }
+        };
+
+        let mut bucket = Bucket {
+            key: key.to_string(),
+            start,
+            end,
+            label: String::new(),
+        };
+        bucket.label = bucket.format_label(LabelStyle::Range);
+        Ok(bucket)
+    }
+
+    /// Formats the bucket's name in the given style
+    ///
+    /// Months are always named by month and year ("Oct 2025"), and days by their date.
+    pub fn format_label(&self, style: LabelStyle) -> String {
+        let day = |date: NaiveDate| date.format("%b %-d").to_string();
+
+        if self.start.day() == 1 && self.end == last_day_of_month(self.start) {
+            return self.start.format("%b %Y").to_string();
+        }
+        if self.start == self.end {
+            return day(self.start);
+        }
+
+        match style {
+            LabelStyle::Start => day(self.start),
+            LabelStyle::End => day(self.end),
+            LabelStyle::Range if self.start.year() != self.end.year() => format!(
+                "{}–{}",
+                self.start.format("%b %-d, %Y"),
+                self.end.format("%b %-d, %Y")
+            ),
+            LabelStyle::Range if self.start.month() != self.end.month() => {
+                format!("{}–{}", day(self.start), day(self.end))
+            }
+            LabelStyle::Range => format!("{}–{}", day(self.start), self.end.day()),
+        }
+    }
+}
+
+fn last_day_of_month(date: NaiveDate) -> NaiveDate {
+    date.with_day(1)
+        .and_then(|first| first.checked_add_months(Months::new(1)))
+        .map_or(date, |next| next - Duration::days(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_week_buckets() {
+        let week = Bucket::from_key("2025-10-19", Granularity::Week).unwrap();
+        assert_eq!(week.start, date("2025-10-19"));
+        assert_eq!(week.end, date("2025-10-25"));
+        assert_eq!(week.label, "Oct 19–25");
+        assert_eq!(week.format_label(LabelStyle::End), "Oct 25");
+        assert_eq!(week.format_label(LabelStyle::Start), "Oct 19");
+
+        let across_months = Bucket::from_key("2025-09-28", Granularity::Week).unwrap();
+        assert_eq!(across_months.label, "Sep 28–Oct 4");
+
+        let across_years = Bucket::from_key("2025-12-28", Granularity::Week).unwrap();
+        assert_eq!(across_years.label, "Dec 28, 2025–Jan 3, 2026");
+
+        let iso = Bucket::from_key("2025-W01", Granularity::Week).unwrap();
+        assert_eq!(iso.start, date("2024-12-30"));
+        assert_eq!(iso.end, date("2025-01-05"));
+    }
+
+    #[test]
+    fn test_day_and_month_buckets() {
+        let day = Bucket::from_key("2025-10-19", Granularity::Day).unwrap();
+        assert_eq!(day.end, day.start);
+        assert_eq!(day.label, "Oct 19");
+
+        let month = Bucket::from_key("2024-02", Granularity::Month).unwrap();
+        assert_eq!(month.start, date("2024-02-01"));
+        assert_eq!(month.end, date("2024-02-29"));
+        assert_eq!(month.label, "Feb 2024");
+        assert_eq!(month.format_label(LabelStyle::End), "Feb 2024");
+
+        assert!(Bucket::from_key("2025-W99", Granularity::Week).is_err());
+        assert!(Bucket::from_key("October", Granularity::Month).is_err());
+    }
+}
@@ -0,0 +1,200 @@
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+
+/// How long the stats caches reuse a result when none of its files have changed
+///
+/// Results depend on the current day as well as the files, so they can't be kept forever
+/// even when the files are untouched.
+pub const DEFAULT_TTL: Duration = Duration::from_secs(60);
+
+/// Modification time of a file, or `None` if it doesn't exist or can't be read
+pub fn file_mtime(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Latest modification time among `paths`, ignoring any that don't exist
+pub fn latest_mtime<P: AsRef<Path>>(paths: &[P]) -> Option<SystemTime> {
+    paths
+        .iter()
+        .filter_map(|path| file_mtime(path.as_ref()))
+        .max()
+}
+
+struct Entry<T> {
+    /// Modification time of each source file when the value was computed
+    mtimes: Vec<Option<SystemTime>>,
+    stored_at: Instant,
+    value: T,
+}
+
+/// A computed value that is reused until one of its source files changes
+///
+/// The value is recomputed when any source file's modification time differs from when it
+/// was stored (including the file appearing or disappearing), when the optional TTL runs
+/// out, or after `invalidate`. Errors are not cached. Wrap in an `Arc` to share between
+/// threads.
+pub struct FileMemo<T> {
+    paths: Vec<PathBuf>,
+    ttl: Option<Duration>,
+    entry: Mutex<Option<Entry<T>>>,
+}
+
+impl<T: Clone> FileMemo<T> {
+    /// Memoizes a value derived from `paths`, with no TTL
+    pub fn new<P: Into<PathBuf>>(paths: impl IntoIterator<Item = P>) -> Self {
+        FileMemo {
+            paths: paths.into_iter().map(Into::into).collect(),
+            ttl: None,
+            entry: Mutex::new(None),
+        }
+    }
+
+    /// Also recomputes once a value is older than `ttl`, for values that depend on the
+    /// current time as well as the files
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    pub fn paths(&self) -> &[PathBuf] {
+        &self.paths
+    }
+
+    /// Returns the stored value if it's still fresh, or computes and stores a new one
+    ///
+    /// The lock isn't held while computing, so concurrent misses may both compute.
+    pub fn get_or_compute(&self, compute: impl FnOnce() -> Result<T>) -> Result<T> {
+        let mtimes = self.current_mtimes();
+
+        {
+            let entry = self.entry.lock().unwrap_or_else(|e| e.into_inner());
+            if let Some(entry) = entry.as_ref().filter(|e| self.is_current(e, &mtimes)) {
+                return Ok(entry.value.clone());
+            }
+        }
+
+        let value = compute()?;
+        *self.entry.lock().unwrap_or_else(|e| e.into_inner()) = Some(Entry {
+            mtimes,
+            stored_at: Instant::now(),
+            value: value.clone(),
+        });
+        Ok(value)
+    }
+
+    /// Whether a value is stored and would be returned without recomputing
+    pub fn is_fresh(&self) -> bool {
+        let mtimes = self.current_mtimes();
+        let entry = self.entry.lock().unwrap_or_else(|e| e.into_inner());
+        entry
+            .as_ref()
+            .is_some_and(|entry| self.is_current(entry, &mtimes))
+    }
+
+    /// Drops the stored value so the next call recomputes it
+    pub fn invalidate(&self) {
+        *self.entry.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    }
+
+    fn is_current(&self, entry: &Entry<T>, mtimes: &[Option<SystemTime>]) -> bool {
+        let expired = self.ttl.is_some_and(|ttl| entry.stored_at.elapsed() >= ttl);
+        entry.mtimes == mtimes && !expired
+    }
+
+    fn current_mtimes(&self) -> Vec<Option<SystemTime>> {
+        self.paths.iter().map(|path| file_mtime(path)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use std::fs::File;
+
+    fn touch(path: &Path, secs: u64) {
+        let file = File::options().write(true).open(path).unwrap();
+        file.set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(secs))
+            .unwrap();
+    }
+
+    #[test]
+    fn test_recomputes_when_a_source_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = dir.path().join("a.sqlite");
+        let b = dir.path().join("b.sqlite");
+        File::create(&a).unwrap();
+        touch(&a, 1_000);
+
+        let memo = FileMemo::new([&a, &b]);
+        let calls = Cell::new(0);
+        let compute = || {
+            calls.set(calls.get() + 1);
+            Ok(calls.get())
+        };
+
+        assert_eq!(memo.get_or_compute(compute).unwrap(), 1);
+        assert_eq!(memo.get_or_compute(compute).unwrap(), 1);
+
+        touch(&a, 2_000);
+        assert_eq!(memo.get_or_compute(compute).unwrap(), 2);
+
+        // A source appearing counts as a change
+        File::create(&b).unwrap();
+        assert!(!memo.is_fresh());
+        assert_eq!(memo.get_or_compute(compute).unwrap(), 3);
+        assert_eq!(memo.get_or_compute(compute).unwrap(), 3);
+        assert!(memo.is_fresh());
+
+        memo.invalidate();
+        assert!(!memo.is_fresh());
+        assert_eq!(memo.get_or_compute(compute).unwrap(), 4);
+    }
+
+    #[test]
+    fn test_ttl_and_errors() {
+        let memo = FileMemo::new(Vec::<PathBuf>::new()).with_ttl(Duration::ZERO);
+        assert_eq!(memo.get_or_compute(|| Ok(1)).unwrap(), 1);
+        assert!(!memo.is_fresh());
+        assert_eq!(memo.get_or_compute(|| Ok(2)).unwrap(), 2);
+
+        let memo = FileMemo::new(Vec::<PathBuf>::new());
+        assert!(memo
+            .get_or_compute(|| Err(anyhow::anyhow!("locked")))
+            .is_err());
+        assert_eq!(memo.get_or_compute(|| Ok(3)).unwrap(), 3);
+        assert_eq!(memo.get_or_compute(|| Ok(4)).unwrap(), 3);
+    }
+
+    #[test]
+    fn test_survives_poisoned_lock() {
+        let memo = FileMemo::new(Vec::<PathBuf>::new());
+        let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            let _guard = memo.entry.lock().unwrap();
+            panic!("poison the lock");
+        }));
+
+        assert_eq!(memo.get_or_compute(|| Ok(1)).unwrap(), 1);
+        memo.invalidate();
+        assert_eq!(memo.get_or_compute(|| Ok(2)).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_latest_mtime() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = dir.path().join("db.sqlite");
+        let wal = dir.path().join("db.sqlite-wal");
+        File::create(&db).unwrap();
+        File::create(&wal).unwrap();
+        touch(&db, 1_000);
+        touch(&wal, 5_000);
+
+        assert_eq!(
+            latest_mtime(&[&db, &wal, &dir.path().join("missing")]),
+            Some(SystemTime::UNIX_EPOCH + Duration::from_secs(5_000))
+        );
+        assert_eq!(latest_mtime::<PathBuf>(&[]), None);
+    }
+}
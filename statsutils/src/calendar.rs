@@ -0,0 +1,184 @@
+use anyhow::{bail, Context, Result};
+use chrono::{Duration, NaiveDate};
+use std::collections::HashMap;
+
+use crate::config::PeriodConfig;
+
+/// Number of intensity levels (excluding "no activity") used for rendering, like GitHub's
+/// contribution grid
+pub const INTENSITY_LEVELS: u8 = 4;
+
+/// One day of a calendar heatmap
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalendarCell {
+    pub date: NaiveDate,
+    pub value: f64,
+    /// Color intensity from 0 (no activity) to `INTENSITY_LEVELS` (close to the busiest day)
+    pub level: u8,
+}
+
+/// One column of a calendar heatmap
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalendarWeek {
+    /// First day of the week, per `PeriodConfig::week_start`
+    pub week_start: NaiveDate,
+    /// Seven slots from the first day of the week; `None` for days outside the range
+    pub days: Vec<Option<CalendarCell>>,
+}
+
+/// Daily values laid out as a week × weekday grid
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalendarMatrix {
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub total: f64,
+    pub max: f64,
+    /// Number of days with a value above zero
+    pub active_days: usize,
+    pub weeks: Vec<CalendarWeek>,
+}
+
+impl CalendarMatrix {
+    /// Every day in the range, oldest first
+    pub fn cells(&self) -> impl Iterator<Item = &CalendarCell> {
+        self.weeks
+            .iter()
+            .flat_map(|week| week.days.iter().flatten())
+    }
+}
+
+/// Lays out a calendar year of daily values, with weeks starting on `config.week_start`
+///
+/// Days missing from `values` count as zero.
+pub fn build_matrix(
+    values: &HashMap<NaiveDate, f64>,
+    year: i32,
+    config: &PeriodConfig,
+) -> Result<CalendarMatrix> {
+    let start = NaiveDate::from_ymd_opt(year, 1, 1).context("Invalid year")?;
+    let end = NaiveDate::from_ymd_opt(year, 12, 31).context("Invalid year")?;
+    build_matrix_for_range(values, start, end, config)
+}
+
+/// Lays out daily values from `start` to `end` (inclusive), with weeks starting on
+/// `config.week_start`
+///
+/// Slots before `start` and after `end` are `None`, so every week has exactly seven
+/// entries. Days missing from `values` count as zero, and values outside the range are
+/// ignored.
+pub fn build_matrix_for_range(
+    values: &HashMap<NaiveDate, f64>,
+    start: NaiveDate,
+    end: NaiveDate,
+    config: &PeriodConfig,
+) -> Result<CalendarMatrix> {
+    if end < start {
+        bail!("Calendar ends ({}) before it starts ({})", end, start);
+    }
+
+    let days: Vec<(NaiveDate, f64)> = start
+        .iter_days()
+        .take_while(|date| *date <= end)
+        .map(|date| (date, values.get(&date).copied().unwrap_or(0.0)))
+        .collect();
+    let max = days.iter().map(|&(_, value)| value).fold(0.0, f64::max);
+
+    let mut weeks: Vec<CalendarWeek> = Vec::new();
+    for &(date, value) in &days {
+        let weekday = config.day_of_week_index(date);
+        if weekday == 0 || weeks.is_empty() {
+            weeks.push(CalendarWeek {
+                week_start: date - Duration::days(weekday as i64),
+                days: vec![None; 7],
+            });
+        }
+        if let Some(week) = weeks.last_mut() {
+            week.days[weekday] = Some(CalendarCell {
+                date,
+                value,
+                level: intensity_level(value, max),
+            });
+        }
+    }
+
+    Ok(CalendarMatrix {
+        start_date: start,
+        end_date: end,
+        total: days.iter().map(|&(_, value)| value).sum(),
+        max,
+        active_days: days.iter().filter(|&&(_, value)| value > 0.0).count(),
+        weeks,
+    })
+}
+
+/// Buckets a value into 0 (no activity) through `INTENSITY_LEVELS` relative to the busiest
+/// day
+pub fn intensity_level(value: f64, max: f64) -> u8 {
+    if value <= 0.0 || max <= 0.0 {
+        return 0;
+    }
+    let level = (value / max * INTENSITY_LEVELS as f64).ceil() as u8;
+    level.clamp(1, INTENSITY_LEVELS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::StatsTimezone;
+    use chrono::{FixedOffset, Weekday};
+
+    fn config(week_start: Weekday) -> PeriodConfig {
+        PeriodConfig::new(StatsTimezone::Fixed(FixedOffset::east_opt(0).unwrap()), 4)
+            .with_week_start(week_start)
+    }
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_year_matrix() {
+        let values = HashMap::from([(date("2025-01-01"), 10.0), (date("2025-06-15"), 40.0)]);
+        let matrix = build_matrix(&values, 2025, &config(Weekday::Sun)).unwrap();
+
+        // 2025-01-01 is a Wednesday
+        assert_eq!(matrix.weeks[0].week_start, date("2024-12-29"));
+        assert!(matrix.weeks[0].days[..3].iter().all(Option::is_none));
+        assert_eq!(matrix.weeks[0].days[3].as_ref().unwrap().level, 1);
+        assert_eq!(matrix.weeks.len(), 53);
+        assert_eq!(matrix.cells().count(), 365);
+        assert_eq!(matrix.total, 50.0);
+        assert_eq!(matrix.max, 40.0);
+        assert_eq!(matrix.active_days, 2);
+    }
+
+    #[test]
+    fn test_monday_weeks() {
+        let matrix = build_matrix_for_range(
+            &HashMap::new(),
+            date("2025-06-01"),
+            date("2025-06-09"),
+            &config(Weekday::Mon),
+        )
+        .unwrap();
+
+        // Sunday June 1 is the last day of the week starting Monday May 26
+        assert_eq!(matrix.weeks.len(), 3);
+        assert_eq!(matrix.weeks[0].week_start, date("2025-05-26"));
+        assert_eq!(
+            matrix.weeks[0].days[6].as_ref().map(|d| d.date),
+            Some(date("2025-06-01"))
+        );
+        assert_eq!(matrix.weeks[2].week_start, date("2025-06-09"));
+        assert!(matrix.cells().all(|cell| cell.level == 0));
+    }
+
+    #[test]
+    fn test_intensity_levels() {
+        assert_eq!(intensity_level(0.0, 60.0), 0);
+        assert_eq!(intensity_level(1.0, 60.0), 1);
+        assert_eq!(intensity_level(30.0, 60.0), 2);
+        assert_eq!(intensity_level(60.0, 60.0), 4);
+        assert_eq!(intensity_level(5.0, 0.0), 0);
+    }
+}
@@ -0,0 +1,100 @@
+//! Text charts for eyeballing trends in the terminal
+
+use crate::fmt::duration_minutes;
+
+/// Sparkline levels, lowest to highest
+const SPARK_LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Partial bar blocks for one to seven eighths of a cell
+const BAR_EIGHTHS: [char; 7] = ['▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+
+/// Renders values as a one-line sparkline scaled to the largest value
+///
+/// Zero (and negative) values are left blank so days with no activity show as gaps,
+/// while any activity at all gets at least the lowest block.
+pub fn sparkline(values: &[f64]) -> String {
+    let max = values.iter().copied().fold(0.0, f64::max);
+    values
+        .iter()
+        .map(|&value| {
+            if value <= 0.0 {
+                return ' ';
+            }
+            let level = (value / max * SPARK_LEVELS.len() as f64).ceil() as usize;
+            SPARK_LEVELS[level.clamp(1, SPARK_LEVELS.len()) - 1]
+        })
+        .collect()
+}
+
+/// Renders a horizontal bar `width` cells long at `max`, to the nearest eighth of a cell
+pub fn bar(value: f64, max: f64, width: usize) -> String {
+    if value <= 0.0 || max <= 0.0 {
+        return String::new();
+    }
+
+    let eighths = ((value / max).min(1.0) * width as f64 * 8.0).round() as usize;
+    let mut bar = "█".repeat(eighths / 8);
+    if !eighths.is_multiple_of(8) {
+        bar.push(BAR_EIGHTHS[eighths % 8 - 1]);
+    }
+    bar
+}
+
+/// Renders one labelled bar per row, scaled to the largest value, with each row's minutes
+/// after its bar:
+///
+/// ```text
+/// 2025-03-02 │████████████████████ 1h 5m
+/// 2025-03-09 │█████████▌ 31m
+/// ```
+pub fn minutes_bar_chart(rows: &[(&str, f64)], width: usize) -> String {
+    let label_width = rows.iter().map(|(label, _)| label.chars().count()).max();
+    let max = rows.iter().map(|&(_, minutes)| minutes).fold(0.0, f64::max);
+
+    rows.iter()
+        .map(|&(label, minutes)| {
+            let bar = bar(minutes, max, width);
+            let value = if minutes > 0.0 {
+                duration_minutes(minutes)
+            } else {
+                "-".to_string()
+            };
+            let separator = if bar.is_empty() { "" } else { " " };
+            format!(
+                "{:<label_width$} │{}{}{}\n",
+                label,
+                bar,
+                separator,
+                value,
+                label_width = label_width.unwrap_or(0)
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sparkline() {
+        assert_eq!(sparkline(&[]), "");
+        assert_eq!(sparkline(&[0.0, 0.0]), "  ");
+        assert_eq!(sparkline(&[0.0, 1.0, 4.0, 8.0]), " ▁▄█");
+        assert_eq!(sparkline(&[0.1, 100.0]), "▁█");
+    }
+
+    #[test]
+    fn test_bar() {
+        assert_eq!(bar(0.0, 10.0, 10), "");
+        assert_eq!(bar(10.0, 10.0, 4), "████");
+        assert_eq!(bar(5.0, 10.0, 3), "█▌");
+        assert_eq!(bar(20.0, 10.0, 2), "██");
+    }
+
+    #[test]
+    fn test_minutes_bar_chart() {
+        let chart = minutes_bar_chart(&[("Mon", 60.0), ("Tuesday", 30.0), ("Wed", 0.0)], 4);
+        assert_eq!(chart, "Mon     │████ 1h\nTuesday │██ 30m\nWed     │-\n");
+    }
+}
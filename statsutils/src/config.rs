@@ -1,6 +1,360 @@
+use anyhow::{bail, Context, Result};
+use chrono::{
+    DateTime, Datelike, Duration, FixedOffset, NaiveDate, NaiveDateTime, TimeZone, Utc, Weekday,
+};
+use chrono_tz::Tz;
+use std::fmt;
+use std::sync::RwLock;
+
 /// Timezone used for calculating day boundaries across all stats crates
 pub const TIMEZONE: &str = "America/Chicago";
 
 /// Rollover hour for determining day boundaries (4 AM)
 /// Days start at 4 AM instead of midnight to better reflect human activity patterns
 pub const ROLLOVER_HOUR: i64 = 4;
+
+/// Day weeks start on
+pub const WEEK_START: Weekday = Weekday::Sun;
+
+/// Workspace-wide day boundaries set with `set_default_config`, if any
+static DEFAULT_CONFIG: RwLock<Option<PeriodConfig>> = RwLock::new(None);
+
+/// Sets the timezone, rollover hour, and week start returned by `PeriodConfig::default()`,
+/// which every stats crate uses unless a database records its own (as Anki does)
+///
+/// Call this once at startup, before any stats are queried.
+pub fn set_default_config(config: PeriodConfig) {
+    *DEFAULT_CONFIG.write().unwrap_or_else(|e| e.into_inner()) = Some(config);
+}
+
+/// Sets the workspace-wide day boundaries from `STATS_TIMEZONE`, `STATS_ROLLOVER_HOUR`, and
+/// `STATS_WEEK_START` (see `PeriodConfig::from_env`)
+pub fn configure_from_env() -> Result<()> {
+    set_default_config(PeriodConfig::from_env()?);
+    Ok(())
+}
+
+/// Timezone used to find day boundaries
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StatsTimezone {
+    /// IANA timezone with daylight saving rules (e.g. America/Chicago)
+    Named(Tz),
+    /// Fixed offset from UTC, like the one Anki records for the user's machine
+    Fixed(FixedOffset),
+}
+
+impl StatsTimezone {
+    /// Converts a timestamp to local wall-clock time
+    pub fn local_datetime(&self, timestamp_ms: i64) -> Result<NaiveDateTime> {
+        let utc = DateTime::<Utc>::from_timestamp_millis(timestamp_ms)
+            .context("Failed to convert timestamp to datetime")?;
+
+        Ok(match self {
+            StatsTimezone::Named(tz) => utc.with_timezone(tz).naive_local(),
+            StatsTimezone::Fixed(offset) => utc.with_timezone(offset).naive_local(),
+        })
+    }
+
+    /// Converts local wall-clock time to a timestamp in milliseconds
+    ///
+    /// Ambiguous times (when clocks fall back) use the earlier instant. Times skipped when
+    /// clocks spring forward, such as midnight in America/Santiago, use the first valid
+    /// instant after the gap.
+    pub fn timestamp_ms(&self, local: NaiveDateTime) -> Result<i64> {
+        let timestamp_ms = match self {
+            StatsTimezone::Named(tz) => first_valid_ms(tz, local),
+            StatsTimezone::Fixed(offset) => first_valid_ms(offset, local),
+        };
+
+        timestamp_ms.context(format!("Local time {} does not exist in timezone", local))
+    }
+}
+
+/// The earliest instant at or after `local` wall-clock time, checking each minute after it
+/// for up to a day when `local` falls in a gap
+fn first_valid_ms<T: TimeZone>(tz: &T, local: NaiveDateTime) -> Option<i64> {
+    (0..=24 * 60).find_map(|minutes| {
+        tz.from_local_datetime(&(local + Duration::minutes(minutes)))
+            .earliest()
+            .map(|dt| dt.timestamp_millis())
+    })
+}
+
+impl fmt::Display for StatsTimezone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StatsTimezone::Named(tz) => write!(f, "{}", tz),
+            StatsTimezone::Fixed(offset) => write!(f, "UTC{}", offset),
+        }
+    }
+}
+
+/// Determines where days and weeks start: the timezone, the rollover hour after midnight,
+/// and the first day of the week
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PeriodConfig {
+    pub timezone: StatsTimezone,
+    pub rollover_hour: i64,
+    pub week_start: Weekday,
+    /// Label weeks with their ISO 8601 week number (e.g. `2025-W10`) instead of their first
+    /// day; see `with_iso_weeks`
+    pub iso_weeks: bool,
+}
+
+impl PeriodConfig {
+    /// Creates a config with the workspace's week start
+    pub fn new(timezone: StatsTimezone, rollover_hour: i64) -> Self {
+        Self {
+            timezone,
+            rollover_hour,
+            ..Self::default()
+        }
+    }
+
+    /// Returns the config with weeks starting on `week_start`, labelled by their first day
+    pub fn with_week_start(self, week_start: Weekday) -> Self {
+        Self {
+            week_start,
+            iso_weeks: false,
+            ..self
+        }
+    }
+
+    /// Returns the config with ISO 8601 weeks: starting on Monday and labelled by week
+    /// number, e.g. `2025-W10`
+    pub fn with_iso_weeks(self) -> Self {
+        Self {
+            week_start: Weekday::Mon,
+            iso_weeks: true,
+            ..self
+        }
+    }
+
+    /// Reads day boundaries from the environment, falling back to the defaults for
+    /// anything unset:
+    /// - `STATS_TIMEZONE`: IANA timezone name, e.g. `Europe/London`
+    /// - `STATS_ROLLOVER_HOUR`: hour after midnight days start at, 0-23
+    /// - `STATS_WEEK_START`: day weeks start on, e.g. `sunday` or `mon`, or `iso` for ISO
+    ///   8601 week numbers
+    pub fn from_env() -> Result<Self> {
+        let mut config = Self::builtin();
+
+        if let Ok(timezone) = std::env::var("STATS_TIMEZONE") {
+            let tz: Tz = timezone
+                .parse()
+                .map_err(|_| anyhow::anyhow!("Invalid STATS_TIMEZONE '{}'", timezone))?;
+            config.timezone = StatsTimezone::Named(tz);
+        }
+        if let Ok(hour) = std::env::var("STATS_ROLLOVER_HOUR") {
+            config.rollover_hour =
+                hour.parse()
+                    .ok()
+                    .filter(|h| (0..24).contains(h))
+                    .context(format!(
+                        "Invalid STATS_ROLLOVER_HOUR '{}' (expected 0-23)",
+                        hour
+                    ))?;
+        }
+        if let Ok(day) = std::env::var("STATS_WEEK_START") {
+            config = if day.eq_ignore_ascii_case("iso") {
+                config.with_iso_weeks()
+            } else {
+                match day.parse::<Weekday>() {
+                    Ok(weekday) => config.with_week_start(weekday),
+                    Err(_) => bail!(
+                        "Invalid STATS_WEEK_START '{}' (expected a day name or 'iso')",
+                        day
+                    ),
+                }
+            };
+        }
+
+        Ok(config)
+    }
+
+    /// Chicago time with a 4 AM rollover and weeks starting on Sunday
+    fn builtin() -> Self {
+        Self {
+            timezone: StatsTimezone::Named(
+                TIMEZONE
+                    .parse()
+                    .expect("TIMEZONE must be a valid IANA timezone"),
+            ),
+            rollover_hour: ROLLOVER_HOUR,
+            week_start: WEEK_START,
+            iso_weeks: false,
+        }
+    }
+
+    /// Returns the logical date of a timestamp, so times before the rollover hour count
+    /// toward the previous day
+    pub fn logical_date(&self, timestamp_ms: i64) -> Result<NaiveDate> {
+        let rollover_ms = Duration::hours(self.rollover_hour).num_milliseconds();
+        Ok(self
+            .timezone
+            .local_datetime(timestamp_ms - rollover_ms)?
+            .date())
+    }
+
+    /// Returns the start of a logical day in milliseconds (midnight plus the rollover hour)
+    pub fn day_start_ms(&self, date: NaiveDate) -> Result<i64> {
+        let midnight = self
+            .timezone
+            .timestamp_ms(date.and_time(Default::default()))?;
+        Ok(midnight + Duration::hours(self.rollover_hour).num_milliseconds())
+    }
+
+    /// Returns today's logical date in the configured timezone, counting times before the
+    /// rollover hour toward the previous day
+    pub fn today(&self) -> Result<NaiveDate> {
+        self.logical_date(Utc::now().timestamp_millis())
+    }
+
+    /// Returns the first day of the week containing `date`
+    pub fn week_start_date(&self, date: NaiveDate) -> NaiveDate {
+        date - Duration::days(self.day_of_week_index(date) as i64)
+    }
+
+    /// Returns the bucket key for the week containing `date`: its first day (YYYY-MM-DD), or
+    /// its ISO week number (YYYY-Www) with `iso_weeks`
+    pub fn week_key(&self, date: NaiveDate) -> String {
+        if self.iso_weeks {
+            let week = date.iso_week();
+            format!("{}-W{:02}", week.year(), week.week())
+        } else {
+            self.week_start_date(date).format("%Y-%m-%d").to_string()
+        }
+    }
+
+    /// Returns how many days `date` falls after the start of its week (0-6)
+    pub fn day_of_week_index(&self, date: NaiveDate) -> usize {
+        let days = date.weekday().num_days_from_monday() as i64
+            - self.week_start.num_days_from_monday() as i64;
+        days.rem_euclid(7) as usize
+    }
+}
+
+impl fmt::Display for PeriodConfig {
+    /// Describes the boundaries for error messages, e.g. "America/Chicago, 4:00 rollover,
+    /// weeks from Sun"
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}, {}:00 rollover, ", self.timezone, self.rollover_hour)?;
+        if self.iso_weeks {
+            write!(f, "ISO weeks")
+        } else {
+            write!(f, "weeks from {}", self.week_start)
+        }
+    }
+}
+
+impl Default for PeriodConfig {
+    /// The config passed to `set_default_config`, or Chicago time with a 4 AM rollover and
+    /// weeks starting on Sunday
+    fn default() -> Self {
+        DEFAULT_CONFIG
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .unwrap_or_else(Self::builtin)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_describes_boundaries() {
+        let config = PeriodConfig::new(StatsTimezone::Named(chrono_tz::America::Chicago), 4)
+            .with_week_start(Weekday::Sun);
+        assert_eq!(
+            config.to_string(),
+            "America/Chicago, 4:00 rollover, weeks from Sun"
+        );
+
+        let config = PeriodConfig::new(
+            StatsTimezone::Fixed(FixedOffset::east_opt(10 * 3600).unwrap()),
+            0,
+        )
+        .with_iso_weeks();
+        assert_eq!(config.to_string(), "UTC+10:00, 0:00 rollover, ISO weeks");
+    }
+
+    #[test]
+    fn test_timestamp_ms_across_dst() {
+        let santiago = StatsTimezone::Named(chrono_tz::America::Santiago);
+        let utc_ms = |s: &str| {
+            NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M")
+                .unwrap()
+                .and_utc()
+                .timestamp_millis()
+        };
+
+        // Clocks sprang forward from midnight to 1:00 on 2024-09-08, so the day starts at
+        // the end of the gap
+        let midnight = NaiveDate::from_ymd_opt(2024, 9, 8)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap();
+        assert_eq!(
+            santiago.timestamp_ms(midnight).unwrap(),
+            utc_ms("2024-09-08 04:00")
+        );
+
+        // 23:30 happened twice on 2024-04-06 when clocks fell back; the first one counts
+        let ambiguous = NaiveDate::from_ymd_opt(2024, 4, 6)
+            .unwrap()
+            .and_hms_opt(23, 30, 0)
+            .unwrap();
+        assert_eq!(
+            santiago.timestamp_ms(ambiguous).unwrap(),
+            utc_ms("2024-04-07 02:30")
+        );
+    }
+
+    #[test]
+    fn test_week_start_date() {
+        let config = PeriodConfig::new(StatsTimezone::Fixed(FixedOffset::east_opt(0).unwrap()), 4)
+            .with_week_start(Weekday::Sun);
+        // 2025-03-05 is a Wednesday
+        let wednesday = NaiveDate::from_ymd_opt(2025, 3, 5).unwrap();
+
+        assert_eq!(
+            config.week_start_date(wednesday),
+            NaiveDate::from_ymd_opt(2025, 3, 2).unwrap()
+        );
+        assert_eq!(config.day_of_week_index(wednesday), 3);
+
+        let config = config.with_week_start(Weekday::Mon);
+        assert_eq!(
+            config.week_start_date(wednesday),
+            NaiveDate::from_ymd_opt(2025, 3, 3).unwrap()
+        );
+        assert_eq!(config.day_of_week_index(wednesday), 2);
+
+        // A Sunday is the last day of a Monday week
+        let sunday = NaiveDate::from_ymd_opt(2025, 3, 9).unwrap();
+        assert_eq!(config.day_of_week_index(sunday), 6);
+    }
+
+    #[test]
+    fn test_iso_week_keys() {
+        let config = PeriodConfig::new(StatsTimezone::Fixed(FixedOffset::east_opt(0).unwrap()), 4);
+        let sunday = NaiveDate::from_ymd_opt(2025, 3, 9).unwrap();
+        assert_eq!(
+            config.with_week_start(Weekday::Sun).week_key(sunday),
+            "2025-03-09"
+        );
+
+        let iso = config.with_iso_weeks();
+        assert_eq!(iso.week_key(sunday), "2025-W10");
+        assert_eq!(
+            iso.week_start_date(sunday),
+            NaiveDate::from_ymd_opt(2025, 3, 3).unwrap()
+        );
+        // The ISO year can differ from the calendar year around New Year
+        assert_eq!(
+            iso.week_key(NaiveDate::from_ymd_opt(2024, 12, 30).unwrap()),
+            "2025-W01"
+        );
+    }
+}
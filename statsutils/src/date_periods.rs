@@ -1,12 +1,91 @@
+use anyhow::bail;
 use anyhow::{Context, Result};
-use chrono::{Datelike, Duration, Local, TimeZone};
-use chrono_tz::Tz;
+use chrono::{Datelike, Duration, Months, NaiveDate};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use utoipa::ToSchema;
 
-use crate::config;
+use crate::bucket::Bucket;
+use crate::config::PeriodConfig;
+use crate::series::PeriodSeries;
+
+/// Size of the buckets a period is split into
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Granularity {
+    /// One bucket per day, keyed by YYYY-MM-DD
+    #[serde(alias = "daily")]
+    #[default]
+    Day,
+    /// One bucket per week, keyed by the week's first day (YYYY-MM-DD; Sunday unless
+    /// `PeriodConfig::week_start` says otherwise), or by ISO week number (YYYY-Www) when
+    /// `PeriodConfig::iso_weeks` is set
+    #[serde(alias = "weekly")]
+    Week,
+    /// One bucket per calendar month, keyed by YYYY-MM
+    #[serde(alias = "monthly")]
+    Month,
+}
+
+impl Granularity {
+    /// Returns the key of the bucket containing `date`
+    pub fn key(&self, date: NaiveDate, config: &PeriodConfig) -> String {
+        match self {
+            Granularity::Day => date.format("%Y-%m-%d").to_string(),
+            Granularity::Week => config.week_key(date),
+            Granularity::Month => date.format("%Y-%m").to_string(),
+        }
+    }
+
+    /// Name of the registered SQLite function that buckets millisecond timestamps
+    pub fn ms_bucket_function(&self) -> &'static str {
+        match self {
+            Granularity::Day => "date_str_from_ms",
+            Granularity::Week => "week_str_from_ms",
+            Granularity::Month => "month_str_from_ms",
+        }
+    }
+
+    /// Name of the registered SQLite function that buckets second timestamps
+    pub fn sec_bucket_function(&self) -> &'static str {
+        match self {
+            Granularity::Day => "date_str_from_sec",
+            Granularity::Week => "week_str_from_sec",
+            Granularity::Month => "month_str_from_sec",
+        }
+    }
+}
+
+impl FromStr for Granularity {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "day" | "daily" => Ok(Granularity::Day),
+            "week" | "weekly" => Ok(Granularity::Week),
+            "month" | "monthly" => Ok(Granularity::Month),
+            _ => bail!("Unknown granularity '{}' (expected day, week, or month)", s),
+        }
+    }
+}
+
+impl fmt::Display for Granularity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Granularity::Day => "day",
+            Granularity::Week => "week",
+            Granularity::Month => "month",
+        };
+        write!(f, "{}", name)
+    }
+}
 
 /// Time period with date strings and millisecond boundaries
-#[derive(Debug, Clone)]
+///
+/// Iterating a period yields its bucket keys in order.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
 pub struct DatePeriod {
     /// Date strings (YYYY-MM-DD) for each day/week in the period
     pub dates: Vec<String>,
@@ -14,45 +93,263 @@ pub struct DatePeriod {
     pub start_ms: i64,
     /// End timestamp in milliseconds
     pub end_ms: i64,
+    /// Size of the buckets `dates` identifies
+    #[serde(default)]
+    pub granularity: Granularity,
 }
 
 impl DatePeriod {
+    /// Bucket keys (days, week keys, or months) in order
+    pub fn iter(&self) -> std::slice::Iter<'_, String> {
+        self.dates.iter()
+    }
+
     /// Returns DatePeriod for the last 30 days
     pub fn last_30_days() -> Result<Self> {
-        let (start_ms, _, _) = get_day_boundaries(29)?;
-        let (_, end_ms, _) = get_day_boundaries(0)?;
+        Self::last_30_days_with_config(&PeriodConfig::default())
+    }
 
-        let mut dates = Vec::new();
-        for day_offset in (0..30).rev() {
-            let (_, _, date_str) = get_day_boundaries(day_offset)?;
-            dates.push(date_str);
+    /// Returns DatePeriod for the last 30 days using the given day boundaries
+    pub fn last_30_days_with_config(config: &PeriodConfig) -> Result<Self> {
+        let today = config.today()?;
+        Self::for_range_with_config(today - Duration::days(29), today, Granularity::Day, config)
+    }
+
+    /// Returns DatePeriod for the last 12 weeks, including the current one
+    pub fn last_12_weeks() -> Result<Self> {
+        Self::weeks(12)
+    }
+
+    /// Returns DatePeriod for the last 12 weeks using the given day boundaries
+    pub fn last_12_weeks_with_config(config: &PeriodConfig) -> Result<Self> {
+        Self::weeks_with_config(12, config)
+    }
+
+    /// Returns DatePeriod for the last 12 months (calendar months, including the current one)
+    pub fn last_12_months() -> Result<Self> {
+        Self::months(12)
+    }
+
+    /// Returns DatePeriod for the last 12 months using the given day boundaries
+    pub fn last_12_months_with_config(config: &PeriodConfig) -> Result<Self> {
+        Self::months_with_config(12, config)
+    }
+
+    /// Returns a daily DatePeriod from `start` to `end`, both inclusive
+    pub fn days(start: NaiveDate, end: NaiveDate) -> Result<Self> {
+        Self::days_with_config(start, end, &PeriodConfig::default())
+    }
+
+    /// Returns a daily DatePeriod from `start` to `end` using the given day boundaries
+    pub fn days_with_config(
+        start: NaiveDate,
+        end: NaiveDate,
+        config: &PeriodConfig,
+    ) -> Result<Self> {
+        Self::for_range_with_config(start, end, Granularity::Day, config)
+    }
+
+    /// Returns DatePeriod for the last `n` weeks, including the current one
+    pub fn weeks(n: u32) -> Result<Self> {
+        Self::weeks_with_config(n, &PeriodConfig::default())
+    }
+
+    /// Returns DatePeriod for the last `n` weeks using the given day boundaries
+    pub fn weeks_with_config(n: u32, config: &PeriodConfig) -> Result<Self> {
+        if n == 0 {
+            bail!("A period needs at least one week");
         }
 
-        Ok(DatePeriod {
-            dates,
-            start_ms,
-            end_ms,
-        })
+        let today = config.today()?;
+        Self::for_range_with_config(
+            today - Duration::weeks(n as i64 - 1),
+            today,
+            Granularity::Week,
+            config,
+        )
     }
 
-    /// Returns DatePeriod for the last 12 weeks (Sunday to Sunday)
-    pub fn last_12_weeks() -> Result<Self> {
-        let (start_ms, _, _) = get_week_boundaries(11)?;
-        let (_, end_ms, _) = get_week_boundaries(0)?;
+    /// Returns DatePeriod for the last `n` calendar months, including the current one
+    pub fn months(n: u32) -> Result<Self> {
+        Self::months_with_config(n, &PeriodConfig::default())
+    }
 
-        let mut dates = Vec::new();
-        for week_offset in (0..12).rev() {
-            let (_, _, week_start_str) = get_week_boundaries(week_offset)?;
-            dates.push(week_start_str);
+    /// Returns DatePeriod for the last `n` calendar months using the given day boundaries
+    pub fn months_with_config(n: u32, config: &PeriodConfig) -> Result<Self> {
+        if n == 0 {
+            bail!("A period needs at least one month");
         }
 
+        let today = config.today()?;
+        let first_month = month_start(today)?
+            .checked_sub_months(Months::new(n - 1))
+            .context("Failed to compute first month of period")?;
+
+        Self::for_range_with_config(first_month, today, Granularity::Month, config)
+    }
+
+    /// Returns DatePeriod for the 12 calendar months of the given year
+    pub fn months_of_year(year: i32) -> Result<Self> {
+        Self::months_of_year_with_config(year, &PeriodConfig::default())
+    }
+
+    /// Returns DatePeriod for the 12 calendar months of the given year using the given
+    /// day boundaries
+    pub fn months_of_year_with_config(year: i32, config: &PeriodConfig) -> Result<Self> {
+        Self::calendar_year_with_config(year, Granularity::Month, config)
+    }
+
+    /// Returns DatePeriod from January 1st of the current year through today
+    ///
+    /// With `Granularity::Week` the first bucket is the week containing January 1st, so it
+    /// can start in the previous year.
+    pub fn year_to_date(granularity: Granularity) -> Result<Self> {
+        Self::year_to_date_with_config(granularity, &PeriodConfig::default())
+    }
+
+    /// Returns DatePeriod from January 1st of the current year through today using the
+    /// given day boundaries
+    pub fn year_to_date_with_config(
+        granularity: Granularity,
+        config: &PeriodConfig,
+    ) -> Result<Self> {
+        let today = config.today()?;
+        let first_day = NaiveDate::from_ymd_opt(today.year(), 1, 1).context("Invalid year")?;
+        Self::for_range_with_config(first_day, today, granularity, config)
+    }
+
+    /// Returns DatePeriod for every day, week, or month of the given year
+    ///
+    /// With `Granularity::Week` the first and last buckets are the weeks containing January
+    /// 1st and December 31st, so they can spill into the neighbouring years.
+    pub fn calendar_year(year: i32, granularity: Granularity) -> Result<Self> {
+        Self::calendar_year_with_config(year, granularity, &PeriodConfig::default())
+    }
+
+    /// Returns DatePeriod for every day, week, or month of the given year using the given
+    /// day boundaries
+    pub fn calendar_year_with_config(
+        year: i32,
+        granularity: Granularity,
+        config: &PeriodConfig,
+    ) -> Result<Self> {
+        let first_day = NaiveDate::from_ymd_opt(year, 1, 1).context("Invalid year")?;
+        let last_day = NaiveDate::from_ymd_opt(year, 12, 31).context("Invalid year")?;
+        Self::for_range_with_config(first_day, last_day, granularity, config)
+    }
+
+    /// Returns DatePeriod covering an arbitrary inclusive date range
+    ///
+    /// The range is widened to whole buckets: with `Granularity::Week` it starts on the Sunday
+    /// of `start_date`'s week, and with `Granularity::Month` on the 1st of `start_date`'s month.
+    pub fn for_range(
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+        granularity: Granularity,
+    ) -> Result<Self> {
+        Self::for_range_with_config(start_date, end_date, granularity, &PeriodConfig::default())
+    }
+
+    /// Returns DatePeriod covering an arbitrary inclusive date range using the given
+    /// day boundaries
+    pub fn for_range_with_config(
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+        granularity: Granularity,
+        config: &PeriodConfig,
+    ) -> Result<Self> {
+        if end_date < start_date {
+            bail!(
+                "End date {} is before start date {}",
+                end_date.format("%Y-%m-%d"),
+                start_date.format("%Y-%m-%d")
+            );
+        }
+
+        let mut dates = Vec::new();
+
+        let (first_day, next_day) = match granularity {
+            Granularity::Day => {
+                let mut date = start_date;
+                while date <= end_date {
+                    dates.push(date.format("%Y-%m-%d").to_string());
+                    date += Duration::days(1);
+                }
+
+                (start_date, end_date + Duration::days(1))
+            }
+            Granularity::Week => {
+                let first_week = config.week_start_date(start_date);
+                let last_week = config.week_start_date(end_date);
+
+                let mut week = first_week;
+                while week <= last_week {
+                    dates.push(config.week_key(week));
+                    week += Duration::weeks(1);
+                }
+
+                (first_week, last_week + Duration::weeks(1))
+            }
+            Granularity::Month => {
+                let first_month = start_date.year() * 12 + start_date.month0() as i32;
+                let last_month = end_date.year() * 12 + end_date.month0() as i32;
+
+                for total_months in first_month..=last_month {
+                    let year = total_months.div_euclid(12);
+                    let month = total_months.rem_euclid(12) + 1;
+                    dates.push(format!("{:04}-{:02}", year, month));
+                }
+
+                (
+                    month_start(start_date)?,
+                    month_start(end_date)?
+                        .checked_add_months(Months::new(1))
+                        .context("Failed to compute next month")?,
+                )
+            }
+        };
+
         Ok(DatePeriod {
             dates,
-            start_ms,
-            end_ms,
+            start_ms: config.day_start_ms(first_day)?,
+            end_ms: config.day_start_ms(next_day)?,
+            granularity,
         })
     }
 
+    /// Describes each bucket in order: its key, first and last day, and a display label
+    pub fn buckets(&self) -> Result<Vec<Bucket>> {
+        self.dates
+            .iter()
+            .map(|key| Bucket::from_key(key, self.granularity))
+            .collect()
+    }
+
+    /// Splits the minutes of `[start_ms, end_ms)` across the buckets of this period that the
+    /// interval overlaps, in proportion to the time spent in each
+    ///
+    /// Time outside the period is dropped. `config` must match the one the period was
+    /// built with.
+    pub fn split_interval(
+        &self,
+        start_ms: i64,
+        end_ms: i64,
+        config: &PeriodConfig,
+    ) -> Result<Vec<(String, f64)>> {
+        let start_ms = start_ms.max(self.start_ms);
+        let end_ms = end_ms.min(self.end_ms);
+
+        let mut buckets: Vec<(String, f64)> = Vec::new();
+        for (date, minutes) in split_minutes_by_day(start_ms, end_ms, config)? {
+            let key = self.granularity.key(date, config);
+            match buckets.last_mut() {
+                Some((last, total)) if *last == key => *total += minutes,
+                _ => buckets.push((key, minutes)),
+            }
+        }
+        Ok(buckets)
+    }
+
     /// Builds results for all dates, using defaults for missing entries
     pub fn build_results<T, R>(
         self,
@@ -71,6 +368,42 @@ impl DatePeriod {
             .collect()
     }
 
+    /// Like `build_results`, but passes each bucket's description instead of just its key
+    pub fn build_bucket_results<T, R>(
+        self,
+        mut results: HashMap<String, T>,
+        mut mapper: impl FnMut(Bucket, T) -> R,
+    ) -> Result<Vec<R>>
+    where
+        T: Default,
+    {
+        Ok(self
+            .buckets()?
+            .into_iter()
+            .map(|bucket| {
+                let value = results.remove(&bucket.key).unwrap_or_default();
+                mapper(bucket, value)
+            })
+            .collect())
+    }
+
+    /// Like `build_results`, but keeps each value keyed by its bucket so it can be joined
+    /// with other sources using `PeriodSeries::zip`
+    pub fn build_series<T>(self, mut results: HashMap<String, T>) -> PeriodSeries<T>
+    where
+        T: Default,
+    {
+        PeriodSeries::new(
+            self.dates
+                .into_iter()
+                .map(|date| {
+                    let value = results.remove(&date).unwrap_or_default();
+                    (date, value)
+                })
+                .collect(),
+        )
+    }
+
     /// Builds results for all dates from two sources, using defaults for missing entries
     pub fn build_results_2<T1, T2, R>(
         self,
@@ -93,94 +426,425 @@ impl DatePeriod {
     }
 }
 
+impl IntoIterator for DatePeriod {
+    type Item = String;
+    type IntoIter = std::vec::IntoIter<String>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.dates.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a DatePeriod {
+    type Item = &'a String;
+    type IntoIter = std::slice::Iter<'a, String>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.dates.iter()
+    }
+}
+
+/// Splits `[start_ms, end_ms)` into the minutes spent on each logical day it overlaps,
+/// oldest first
+///
+/// Days run from one rollover to the next, so an evening visit that ends after midnight
+/// but before the rollover stays on one day. Returns nothing for an empty interval.
+pub fn split_minutes_by_day(
+    start_ms: i64,
+    end_ms: i64,
+    config: &PeriodConfig,
+) -> Result<Vec<(NaiveDate, f64)>> {
+    if end_ms <= start_ms {
+        return Ok(Vec::new());
+    }
+
+    let mut days = Vec::new();
+    let mut date = config.logical_date(start_ms)?;
+    let last = config.logical_date(end_ms - 1)?;
+    while date <= last {
+        let (day_start, day_end) = ms_range_for_local_date(date, config)?;
+        let overlap = end_ms.min(day_end) - start_ms.max(day_start);
+        if overlap > 0 {
+            days.push((date, overlap as f64 / 60_000.0));
+        }
+        date = date.succ_opt().context("Date out of range")?;
+    }
+    Ok(days)
+}
+
 /// Returns the start of today in milliseconds (applies 4 AM rollover)
 pub fn get_today_start_ms() -> Result<i64> {
-    let (today_start_ms, _, _) = get_day_boundaries(0)?;
-    Ok(today_start_ms)
-}
-
-/// Calculates day boundaries with 4 AM rollover
-/// Returns (start_ms, end_ms, date_str)
-fn get_day_boundaries(day_offset: i32) -> Result<(i64, i64, String)> {
-    let tz: Tz = config::TIMEZONE
-        .parse()
-        .context("Failed to parse timezone from config")?;
-
-    let now_in_tz = Local::now().with_timezone(&tz);
-
-    // Calculate the target date (today - day_offset)
-    let target_date = now_in_tz - Duration::days(day_offset as i64);
-
-    // Get start of that day at midnight
-    let day_midnight = tz
-        .with_ymd_and_hms(
-            target_date.year(),
-            target_date.month(),
-            target_date.day(),
-            0,
-            0,
-            0,
+    get_today_start_ms_with_config(&PeriodConfig::default())
+}
+
+/// Returns the start of today in milliseconds using the given day boundaries
+pub fn get_today_start_ms_with_config(config: &PeriodConfig) -> Result<i64> {
+    config.day_start_ms(config.today()?)
+}
+
+/// Returns today's logical date (applies 4 AM rollover)
+pub fn get_today_date() -> Result<NaiveDate> {
+    PeriodConfig::default().today()
+}
+
+/// Parses a local date in YYYY-MM-DD format, ignoring surrounding whitespace
+pub fn parse_local_date(s: &str) -> Result<NaiveDate> {
+    NaiveDate::parse_from_str(s.trim(), "%Y-%m-%d")
+        .context(format!("Invalid date '{}' (expected YYYY-MM-DD)", s))
+}
+
+/// Returns the millisecond range `[start, end)` covered by a logical day, from its rollover
+/// hour to the next day's
+///
+/// The range is 23 or 25 hours long on daylight saving transitions.
+fn ms_range_for_local_date(date: NaiveDate, config: &PeriodConfig) -> Result<(i64, i64)> {
+    let next_day = date.succ_opt().context("Date out of range")?;
+    Ok((config.day_start_ms(date)?, config.day_start_ms(next_day)?))
+}
+
+/// Returns the 1st of the month that contains `date`
+fn month_start(date: NaiveDate) -> Result<NaiveDate> {
+    date.with_day(1).context("Failed to compute start of month")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::StatsTimezone;
+    use chrono::{FixedOffset, Weekday};
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_for_range_days() {
+        let period =
+            DatePeriod::for_range(date("2025-02-27"), date("2025-03-02"), Granularity::Day)
+                .unwrap();
+        assert_eq!(
+            period.dates,
+            vec!["2025-02-27", "2025-02-28", "2025-03-01", "2025-03-02"]
+        );
+        // Four days, with the 4 AM rollover on both ends
+        assert_eq!(period.end_ms - period.start_ms, 4 * 24 * 60 * 60 * 1000);
+    }
+
+    #[test]
+    fn test_for_range_weeks_align_to_sunday() {
+        // 2025-10-22 is a Wednesday, 2025-11-03 is a Monday
+        let period =
+            DatePeriod::for_range(date("2025-10-22"), date("2025-11-03"), Granularity::Week)
+                .unwrap();
+        assert_eq!(period.dates, vec!["2025-10-19", "2025-10-26", "2025-11-02"]);
+    }
+
+    #[test]
+    fn test_for_range_months_cross_year() {
+        let period =
+            DatePeriod::for_range(date("2024-11-15"), date("2025-02-01"), Granularity::Month)
+                .unwrap();
+        assert_eq!(
+            period.dates,
+            vec!["2024-11", "2024-12", "2025-01", "2025-02"]
+        );
+    }
+
+    #[test]
+    fn test_for_range_rejects_reversed_dates() {
+        assert!(
+            DatePeriod::for_range(date("2025-03-02"), date("2025-03-01"), Granularity::Day)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_days() {
+        let period = DatePeriod::days(date("2024-12-30"), date("2025-01-02")).unwrap();
+        assert_eq!(
+            period.dates,
+            vec!["2024-12-30", "2024-12-31", "2025-01-01", "2025-01-02"]
+        );
+    }
+
+    #[test]
+    fn test_weeks_end_this_week() {
+        let period = DatePeriod::weeks(4).unwrap();
+        let this_week = PeriodConfig::default().week_start_date(get_today_date().unwrap());
+
+        assert_eq!(period.dates.len(), 4);
+        assert_eq!(
+            period.dates.last().unwrap(),
+            &this_week.format("%Y-%m-%d").to_string()
+        );
+        assert_eq!(
+            period.dates[0],
+            (this_week - Duration::weeks(3))
+                .format("%Y-%m-%d")
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn test_months_end_this_month() {
+        let period = DatePeriod::months(14).unwrap();
+        let today = get_today_date().unwrap();
+
+        assert_eq!(period.dates.len(), 14);
+        assert_eq!(
+            period.dates.last().unwrap(),
+            &today.format("%Y-%m").to_string()
+        );
+        assert_eq!(
+            DatePeriod::last_12_months().unwrap().dates,
+            period.dates[2..]
+        );
+    }
+
+    #[test]
+    fn test_empty_periods_are_rejected() {
+        assert!(DatePeriod::weeks(0).is_err());
+        assert!(DatePeriod::months(0).is_err());
+    }
+
+    #[test]
+    fn test_granularity_from_str() {
+        assert_eq!("day".parse::<Granularity>().unwrap(), Granularity::Day);
+        assert_eq!("Weekly".parse::<Granularity>().unwrap(), Granularity::Week);
+        assert_eq!("month".parse::<Granularity>().unwrap(), Granularity::Month);
+        assert!("year".parse::<Granularity>().is_err());
+    }
+
+    #[test]
+    fn test_for_range_with_fixed_offset() {
+        // UTC-5 with a 2 AM rollover, as Anki records it
+        let config = PeriodConfig::new(
+            StatsTimezone::Fixed(FixedOffset::west_opt(5 * 3600).unwrap()),
+            2,
+        );
+        let period = DatePeriod::for_range_with_config(
+            date("2025-03-01"),
+            date("2025-03-01"),
+            Granularity::Day,
+            &config,
         )
-        .single()
-        .context("Failed to create target day's midnight")?;
-
-    // Add rollover hours for day start
-    let day_start = day_midnight + Duration::hours(config::ROLLOVER_HOUR);
-
-    // Next day start
-    let next_day_start = day_start + Duration::days(1);
-
-    // Format date for output
-    let date_str = target_date.format("%Y-%m-%d").to_string();
-
-    Ok((
-        day_start.timestamp_millis(),
-        next_day_start.timestamp_millis(),
-        date_str,
-    ))
-}
-
-/// Calculates week boundaries (Sunday start, 4 AM rollover)
-/// Returns (start_ms, end_ms, week_start_str)
-fn get_week_boundaries(week_offset: i32) -> Result<(i64, i64, String)> {
-    let tz: Tz = config::TIMEZONE
-        .parse()
-        .context("Failed to parse timezone from config")?;
-
-    let now_in_tz = Local::now().with_timezone(&tz);
-
-    // Calculate days since last Sunday (0 if today is Sunday)
-    let days_since_sunday = now_in_tz.weekday().num_days_from_sunday();
-
-    // Calculate the target Sunday (go back to most recent Sunday, then subtract week_offset weeks)
-    let target_date =
-        now_in_tz - Duration::days(days_since_sunday as i64) - Duration::weeks(week_offset as i64);
-
-    // Get start of that Sunday at midnight
-    let week_midnight = tz
-        .with_ymd_and_hms(
-            target_date.year(),
-            target_date.month(),
-            target_date.day(),
-            0,
-            0,
-            0,
+        .unwrap();
+
+        // 2025-03-01 02:00 at UTC-5 is 07:00 UTC
+        assert_eq!(period.start_ms, 1740812400000);
+        assert_eq!(period.end_ms - period.start_ms, 24 * 60 * 60 * 1000);
+    }
+
+    #[test]
+    fn test_logical_date_applies_rollover() {
+        let config = PeriodConfig::new(StatsTimezone::Fixed(FixedOffset::east_opt(0).unwrap()), 4);
+
+        // 2025-03-02 03:59 UTC still belongs to March 1st
+        assert_eq!(
+            config.logical_date(1740887940000).unwrap(),
+            date("2025-03-01")
+        );
+        // 2025-03-02 04:00 UTC starts March 2nd
+        assert_eq!(
+            config.logical_date(1740888000000).unwrap(),
+            date("2025-03-02")
+        );
+    }
+
+    #[test]
+    fn test_build_series_fills_missing_buckets() {
+        let period = DatePeriod::days(date("2025-03-01"), date("2025-03-03")).unwrap();
+        let mut results = HashMap::new();
+        results.insert("2025-03-02".to_string(), 7);
+
+        let series = period.build_series(results);
+        assert_eq!(
+            series.dates().collect::<Vec<_>>(),
+            vec!["2025-03-01", "2025-03-02", "2025-03-03"]
+        );
+        assert_eq!(series.into_values(), vec![0, 7, 0]);
+    }
+
+    #[test]
+    fn test_calendar_year() {
+        let config = PeriodConfig::new(StatsTimezone::Fixed(FixedOffset::east_opt(0).unwrap()), 4)
+            .with_week_start(Weekday::Sun);
+
+        let days = DatePeriod::calendar_year_with_config(2024, Granularity::Day, &config).unwrap();
+        assert_eq!(days.dates.len(), 366);
+        assert_eq!(days.dates.first().unwrap(), "2024-01-01");
+        assert_eq!(days.dates.last().unwrap(), "2024-12-31");
+
+        // 2025-01-01 is a Wednesday, so the first week starts the Sunday before
+        let weeks =
+            DatePeriod::calendar_year_with_config(2025, Granularity::Week, &config).unwrap();
+        assert_eq!(weeks.dates.first().unwrap(), "2024-12-29");
+        assert_eq!(weeks.dates.last().unwrap(), "2025-12-28");
+        assert_eq!(weeks.dates.len(), 53);
+    }
+
+    #[test]
+    fn test_year_to_date() {
+        let today = get_today_date().unwrap();
+        let period = DatePeriod::year_to_date(Granularity::Day).unwrap();
+        assert_eq!(period.dates.len(), today.ordinal() as usize);
+        assert_eq!(
+            period.dates.first().unwrap(),
+            &format!("{}-01-01", today.year())
+        );
+        assert_eq!(
+            period.dates.last().unwrap(),
+            &today.format("%Y-%m-%d").to_string()
+        );
+    }
+
+    #[test]
+    fn test_iterates_bucket_keys() {
+        let period =
+            DatePeriod::for_range(date("2025-06-01"), date("2025-06-03"), Granularity::Day)
+                .unwrap();
+        let keys: Vec<&String> = period.iter().collect();
+        assert_eq!(keys, ["2025-06-01", "2025-06-02", "2025-06-03"]);
+        assert_eq!((&period).into_iter().count(), 3);
+        assert_eq!(period.into_iter().last().unwrap(), "2025-06-03");
+    }
+
+    #[test]
+    fn test_serde_round_trip() {
+        let period = DatePeriod {
+            dates: vec!["2025-06".to_string()],
+            start_ms: 1,
+            end_ms: 2,
+            granularity: Granularity::Month,
+        };
+        let json = serde_json::to_string(&period).unwrap();
+        assert_eq!(
+            json,
+            r#"{"dates":["2025-06"],"start_ms":1,"end_ms":2,"granularity":"month"}"#
+        );
+        assert_eq!(serde_json::from_str::<DatePeriod>(&json).unwrap(), period);
+
+        assert_eq!(
+            serde_json::to_string(&Granularity::Week).unwrap(),
+            r#""week""#
+        );
+        assert_eq!(
+            serde_json::from_str::<Granularity>(r#""month""#).unwrap(),
+            Granularity::Month
+        );
+    }
+
+    #[test]
+    fn test_parse_local_date() {
+        assert_eq!(
+            parse_local_date(" 2025-06-01\n").unwrap(),
+            date("2025-06-01")
+        );
+        let err = parse_local_date("2025-13-01").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid date '2025-13-01' (expected YYYY-MM-DD)"
+        );
+    }
+
+    #[test]
+    fn test_ms_range_for_local_date() {
+        let utc = PeriodConfig::new(StatsTimezone::Fixed(FixedOffset::east_opt(0).unwrap()), 4);
+        let (start, end) = ms_range_for_local_date(date("2025-06-01"), &utc).unwrap();
+        assert_eq!(utc.logical_date(start).unwrap(), date("2025-06-01"));
+        assert_eq!(utc.logical_date(end - 1).unwrap(), date("2025-06-01"));
+        assert_eq!(utc.logical_date(end).unwrap(), date("2025-06-02"));
+
+        // Clocks spring forward in Chicago on 2025-03-09
+        let chicago = PeriodConfig::new(StatsTimezone::Named(chrono_tz::America::Chicago), 4);
+        let (start, end) = ms_range_for_local_date(date("2025-03-09"), &chicago).unwrap();
+        assert_eq!(end - start, 23 * 60 * 60 * 1000);
+    }
+
+    #[test]
+    fn test_split_minutes_by_day() {
+        let config = PeriodConfig::new(StatsTimezone::Fixed(FixedOffset::east_opt(0).unwrap()), 4);
+        let at = |s: &str| {
+            let date = NaiveDate::parse_from_str(&s[..10], "%Y-%m-%d").unwrap();
+            let time = chrono::NaiveTime::parse_from_str(&s[11..], "%H:%M").unwrap();
+            date.and_time(time).and_utc().timestamp_millis()
+        };
+
+        // 22:00 to 02:00 stays on one day with a 4 AM rollover
+        let days =
+            split_minutes_by_day(at("2025-06-01 22:00"), at("2025-06-02 02:00"), &config).unwrap();
+        assert_eq!(days, vec![(date("2025-06-01"), 240.0)]);
+
+        // 03:00 to 05:00 is an hour on each side of the rollover
+        let days =
+            split_minutes_by_day(at("2025-06-02 03:00"), at("2025-06-02 05:00"), &config).unwrap();
+        assert_eq!(
+            days,
+            vec![(date("2025-06-01"), 60.0), (date("2025-06-02"), 60.0)]
+        );
+
+        assert!(
+            split_minutes_by_day(at("2025-06-02 05:00"), at("2025-06-02 05:00"), &config)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_split_interval_across_weeks() {
+        let config = PeriodConfig::new(StatsTimezone::Fixed(FixedOffset::east_opt(0).unwrap()), 4)
+            .with_week_start(Weekday::Sun);
+        let period = DatePeriod::for_range_with_config(
+            date("2025-06-01"),
+            date("2025-06-14"),
+            Granularity::Week,
+            &config,
         )
-        .single()
-        .context("Failed to create week's midnight")?;
+        .unwrap();
 
-    // Add rollover hours for week start
-    let week_start = week_midnight + Duration::hours(config::ROLLOVER_HOUR);
+        // Saturday 2025-06-07 from 12:00 to Sunday 06:00: 16 hours in the first week, 2 in
+        // the second
+        let start = config.day_start_ms(date("2025-06-07")).unwrap() + 8 * 3_600_000;
+        let end = start + 18 * 3_600_000;
+        let buckets = period.split_interval(start, end, &config).unwrap();
+        assert_eq!(
+            buckets,
+            vec![
+                ("2025-06-01".to_string(), 16.0 * 60.0),
+                ("2025-06-08".to_string(), 2.0 * 60.0)
+            ]
+        );
 
-    // Next week start (7 days later)
-    let next_week_start = week_start + Duration::weeks(1);
+        // Time before the period is dropped
+        let before = period.start_ms - 3_600_000;
+        let buckets = period
+            .split_interval(before, period.start_ms + 3_600_000, &config)
+            .unwrap();
+        assert_eq!(buckets, vec![("2025-06-01".to_string(), 60.0)]);
+    }
 
-    // Format week start date for output
-    let week_start_str = target_date.format("%Y-%m-%d").to_string();
+    #[test]
+    fn test_build_bucket_results() {
+        let config = PeriodConfig::new(StatsTimezone::Fixed(FixedOffset::east_opt(0).unwrap()), 4)
+            .with_week_start(Weekday::Sun);
+        let period = DatePeriod::for_range_with_config(
+            date("2025-10-19"),
+            date("2025-11-01"),
+            Granularity::Week,
+            &config,
+        )
+        .unwrap();
+        let results = HashMap::from([("2025-10-26".to_string(), 30.0)]);
 
-    Ok((
-        week_start.timestamp_millis(),
-        next_week_start.timestamp_millis(),
-        week_start_str,
-    ))
+        let rows = period
+            .build_bucket_results(results, |bucket, minutes: f64| (bucket.label, minutes))
+            .unwrap();
+        assert_eq!(
+            rows,
+            vec![
+                ("Oct 19–25".to_string(), 0.0),
+                ("Oct 26–Nov 1".to_string(), 30.0)
+            ]
+        );
+    }
 }
@@ -0,0 +1,56 @@
+/// Formats a number of minutes compactly, leaving out zero parts: "45m", "2h", "1h 24m"
+///
+/// Rounds to the nearest minute; anything above zero but under half a minute shows as
+/// "<1m" so brief activity doesn't read as none. Hours are never rolled up into days.
+pub fn duration_minutes(minutes: f64) -> String {
+    if minutes > 0.0 && minutes < 0.5 {
+        return "<1m".to_string();
+    }
+
+    let (sign, hours, mins) = split(minutes);
+    match (hours, mins) {
+        (0, mins) => format!("{}{}m", sign, mins),
+        (hours, 0) => format!("{}{}h", sign, hours),
+        (hours, mins) => format!("{}{}h {}m", sign, hours, mins),
+    }
+}
+
+/// Formats a number of minutes with both parts always present, for aligned columns:
+/// "0h 45m", "2h 00m", "1h 24m"
+pub fn duration_hours_minutes(minutes: f64) -> String {
+    let (sign, hours, mins) = split(minutes);
+    format!("{}{}h {:02}m", sign, hours, mins)
+}
+
+/// Splits minutes into a sign and whole hours and minutes, rounded to the nearest minute
+fn split(minutes: f64) -> (&'static str, u64, u64) {
+    let total = minutes.abs().round() as u64;
+    let sign = if minutes < 0.0 && total > 0 { "-" } else { "" };
+    (sign, total / 60, total % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_duration_minutes() {
+        assert_eq!(duration_minutes(0.0), "0m");
+        assert_eq!(duration_minutes(0.2), "<1m");
+        assert_eq!(duration_minutes(44.6), "45m");
+        assert_eq!(duration_minutes(60.0), "1h");
+        assert_eq!(duration_minutes(84.0), "1h 24m");
+        assert_eq!(duration_minutes(1505.0), "25h 5m");
+        assert_eq!(duration_minutes(-90.0), "-1h 30m");
+    }
+
+    #[test]
+    fn test_duration_hours_minutes() {
+        assert_eq!(duration_hours_minutes(0.0), "0h 00m");
+        assert_eq!(duration_hours_minutes(45.0), "0h 45m");
+        assert_eq!(duration_hours_minutes(120.0), "2h 00m");
+        assert_eq!(duration_hours_minutes(84.4), "1h 24m");
+        assert_eq!(duration_hours_minutes(59.7), "1h 00m");
+        assert_eq!(duration_hours_minutes(-0.2), "0h 00m");
+    }
+}
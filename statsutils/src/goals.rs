@@ -0,0 +1,271 @@
+use anyhow::{bail, Context, Result};
+use chrono::{Datelike, Duration, NaiveDate};
+
+use crate::config::PeriodConfig;
+use crate::series::PeriodSeries;
+
+/// The window a goal's target applies to
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GoalPeriod {
+    /// Each logical day
+    Day,
+    /// Each week, starting on `PeriodConfig::week_start`
+    Week,
+    /// Each calendar month
+    Month,
+    /// Each calendar year
+    Year,
+    /// Once, counting everything from the start of the series up to this date
+    Until(NaiveDate),
+}
+
+impl GoalPeriod {
+    /// First and last day (inclusive) of the window containing `today`
+    ///
+    /// `Until` windows start at `since`, usually the first day of the series being evaluated.
+    pub fn bounds(
+        &self,
+        today: NaiveDate,
+        since: NaiveDate,
+        config: &PeriodConfig,
+    ) -> Result<(NaiveDate, NaiveDate)> {
+        Ok(match *self {
+            GoalPeriod::Day => (today, today),
+            GoalPeriod::Week => {
+                let start = config.week_start_date(today);
+                (start, start + Duration::days(6))
+            }
+            GoalPeriod::Month => {
+                let start = today.with_day(1).context("Invalid date")?;
+                let next = start
+                    .checked_add_months(chrono::Months::new(1))
+                    .context("Date out of range")?;
+                (start, next - Duration::days(1))
+            }
+            GoalPeriod::Year => (
+                NaiveDate::from_ymd_opt(today.year(), 1, 1).context("Invalid date")?,
+                NaiveDate::from_ymd_opt(today.year(), 12, 31).context("Invalid date")?,
+            ),
+            GoalPeriod::Until(deadline) => (since.min(deadline), deadline),
+        })
+    }
+}
+
+/// A target for some metric over a period, e.g. 600 minutes of reading per month
+#[derive(Debug, Clone, PartialEq)]
+pub struct Goal {
+    /// Name of what's being measured, e.g. "minutes" or "verses"
+    pub metric: String,
+    pub target: f64,
+    pub period: GoalPeriod,
+}
+
+/// How a goal is going
+#[derive(Debug, Clone, PartialEq)]
+pub struct GoalProgress {
+    /// Progress so far in the current window, in the goal's unit
+    pub current: f64,
+    pub target: f64,
+    /// Amount still needed to reach the target (0 once achieved)
+    pub remaining: f64,
+    /// Progress as a percentage of the target, capped at 100
+    pub percent: f64,
+    pub achieved: bool,
+    /// Whether the goal is on pace to be met, or already met
+    pub on_track: bool,
+    /// Days left in the window, including today
+    pub days_left: i64,
+    /// Amount needed per remaining day to reach the target
+    pub pace_needed: f64,
+}
+
+impl GoalProgress {
+    /// Builds progress from a current value and whether it's on pace, for goals whose pace
+    /// isn't a simple share of the window (e.g. judged by a recent average)
+    pub fn measure(current: f64, target: f64, on_pace: bool, days_left: i64) -> Self {
+        let achieved = current >= target;
+        let remaining = (target - current).max(0.0);
+        let days_left = days_left.max(0);
+        GoalProgress {
+            current,
+            target,
+            remaining,
+            percent: if target > 0.0 {
+                (current / target * 100.0).min(100.0)
+            } else {
+                100.0
+            },
+            achieved,
+            on_track: achieved || on_pace,
+            days_left,
+            pace_needed: if days_left > 0 {
+                remaining / days_left as f64
+            } else {
+                remaining
+            },
+        }
+    }
+}
+
+/// Evaluates a goal against a daily series (YYYY-MM-DD keys), with "today" taken from
+/// `config`
+///
+/// Values within the current window up to today are summed. The goal is on track when that
+/// sum is at least the target's share of the window elapsed so far.
+pub fn evaluate(
+    series: &PeriodSeries<f64>,
+    goal: &Goal,
+    config: &PeriodConfig,
+) -> Result<GoalProgress> {
+    evaluate_as_of(series, goal, config.today()?, config)
+}
+
+/// Like `evaluate`, for an explicit `today`
+pub fn evaluate_as_of(
+    series: &PeriodSeries<f64>,
+    goal: &Goal,
+    today: NaiveDate,
+    config: &PeriodConfig,
+) -> Result<GoalProgress> {
+    let days = series
+        .iter()
+        .map(|(date, value)| {
+            NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                .map(|date| (date, *value))
+                .context(format!("Goal series has a non-daily date '{}'", date))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let since = days.iter().map(|&(date, _)| date).min().unwrap_or(today);
+    let (start, end) = goal.period.bounds(today, since, config)?;
+    if today < start {
+        bail!("Goal window starts ({}) after today ({})", start, today);
+    }
+
+    let current: f64 = days
+        .iter()
+        .filter(|&&(date, _)| date >= start && date <= today.min(end))
+        .map(|&(_, value)| value)
+        .sum();
+
+    let window_days = (end - start).num_days() + 1;
+    let elapsed_days = ((today - start).num_days() + 1).min(window_days);
+    let expected = goal.target * elapsed_days as f64 / window_days as f64;
+
+    Ok(GoalProgress::measure(
+        current,
+        goal.target,
+        current >= expected,
+        (end - today).num_days() + 1,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::StatsTimezone;
+    use chrono::{FixedOffset, Weekday};
+
+    fn config() -> PeriodConfig {
+        PeriodConfig::new(StatsTimezone::Fixed(FixedOffset::east_opt(0).unwrap()), 4)
+            .with_week_start(Weekday::Sun)
+    }
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    fn series(days: &[(&str, f64)]) -> PeriodSeries<f64> {
+        PeriodSeries::new(days.iter().map(|&(d, v)| (d.to_string(), v)).collect())
+    }
+
+    fn goal(target: f64, period: GoalPeriod) -> Goal {
+        Goal {
+            metric: "minutes".to_string(),
+            target,
+            period,
+        }
+    }
+
+    #[test]
+    fn test_monthly_goal_pace() {
+        let series = series(&[
+            ("2025-05-31", 500.0),
+            ("2025-06-01", 100.0),
+            ("2025-06-10", 150.0),
+        ]);
+        let goal = goal(600.0, GoalPeriod::Month);
+
+        // 10 of 30 days gone: 250 is ahead of the 200 expected so far
+        let progress = evaluate_as_of(&series, &goal, date("2025-06-10"), &config()).unwrap();
+        assert_eq!(progress.current, 250.0);
+        assert_eq!(progress.remaining, 350.0);
+        assert_eq!(progress.days_left, 21);
+        assert!((progress.pace_needed - 350.0 / 21.0).abs() < 1e-9);
+        assert!(progress.on_track);
+        assert!(!progress.achieved);
+
+        // 20 of 30 days gone with no more progress: behind the 400 expected
+        let progress = evaluate_as_of(&series, &goal, date("2025-06-20"), &config()).unwrap();
+        assert!(!progress.on_track);
+    }
+
+    #[test]
+    fn test_weekly_and_daily_goals() {
+        let series = series(&[("2025-06-08", 30.0), ("2025-06-09", 45.0)]);
+
+        // Sunday June 8 starts the week
+        let weekly = evaluate_as_of(
+            &series,
+            &goal(70.0, GoalPeriod::Week),
+            date("2025-06-09"),
+            &config(),
+        )
+        .unwrap();
+        assert_eq!(weekly.current, 75.0);
+        assert!(weekly.achieved);
+        assert_eq!(weekly.percent, 100.0);
+        assert_eq!(weekly.pace_needed, 0.0);
+
+        let daily = evaluate_as_of(
+            &series,
+            &goal(60.0, GoalPeriod::Day),
+            date("2025-06-09"),
+            &config(),
+        )
+        .unwrap();
+        assert_eq!(daily.current, 45.0);
+        assert_eq!(daily.percent, 75.0);
+        assert_eq!(daily.days_left, 1);
+        assert_eq!(daily.pace_needed, 15.0);
+    }
+
+    #[test]
+    fn test_goal_by_date() {
+        let series = series(&[("2025-01-01", 10.0), ("2025-01-05", 10.0)]);
+        let goal = goal(100.0, GoalPeriod::Until(date("2025-01-10")));
+
+        // Half the window gone with a fifth of the target
+        let progress = evaluate_as_of(&series, &goal, date("2025-01-05"), &config()).unwrap();
+        assert_eq!(progress.current, 20.0);
+        assert_eq!(progress.days_left, 6);
+        assert!(!progress.on_track);
+
+        // Past the deadline, everything left is still needed
+        let progress = evaluate_as_of(&series, &goal, date("2025-01-12"), &config()).unwrap();
+        assert_eq!(progress.days_left, 0);
+        assert_eq!(progress.pace_needed, 80.0);
+    }
+
+    #[test]
+    fn test_rejects_non_daily_series() {
+        let series = series(&[("2025-W23", 10.0)]);
+        let result = evaluate_as_of(
+            &series,
+            &goal(10.0, GoalPeriod::Week),
+            date("2025-06-09"),
+            &config(),
+        );
+        assert!(result.is_err());
+    }
+}
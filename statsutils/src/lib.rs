@@ -1,6 +1,24 @@
+mod bucket;
+pub mod cache;
+pub mod calendar;
+pub mod chart;
 mod config;
 mod date_periods;
+pub mod fmt;
+pub mod goals;
+pub mod math;
+#[cfg(feature = "tokio")]
+pub mod nonblocking;
+pub mod series;
+pub mod sqlite;
 mod sqlite_functions;
+pub mod streaks;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod trend;
 
+pub use bucket::{Bucket, LabelStyle};
+pub use config::{configure_from_env, set_default_config, PeriodConfig, StatsTimezone};
 pub use date_periods::*;
+pub use series::PeriodSeries;
 pub use sqlite_functions::*;
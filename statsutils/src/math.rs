@@ -0,0 +1,98 @@
+/// Arithmetic mean of `values`, or 0 when there are none
+pub fn mean(values: &[f64]) -> f64 {
+    if values.is_empty() {
+        0.0
+    } else {
+        values.iter().sum::<f64>() / values.len() as f64
+    }
+}
+
+/// Median of `values`, or 0 when there are none
+pub fn median(values: &[f64]) -> f64 {
+    percentile(values, 50.0)
+}
+
+/// Value below which `p` percent of `values` fall (0-100), interpolating linearly between
+/// the closest ranks, or 0 when there are none
+///
+/// `p` is clamped to 0-100, so 0 gives the minimum and 100 the maximum.
+pub fn percentile(values: &[f64], p: f64) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+
+    let rank = p.clamp(0.0, 100.0) / 100.0 * (sorted.len() - 1) as f64;
+    let lower = rank.floor() as usize;
+    let upper = rank.ceil() as usize;
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (rank - lower as f64)
+}
+
+/// Population standard deviation of `values`, or 0 when there are fewer than two
+pub fn stddev(values: &[f64]) -> f64 {
+    if values.len() < 2 {
+        return 0.0;
+    }
+    let mean = mean(values);
+    let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / values.len() as f64;
+    variance.sqrt()
+}
+
+/// Counts values into buckets starting at each of `lower_bounds` (ascending); the last
+/// bucket is open-ended
+///
+/// Values below the first bound are counted in the first bucket. Returns one count per
+/// bound.
+pub fn bucket_counts(values: &[f64], lower_bounds: &[f64]) -> Vec<usize> {
+    let mut counts = vec![0; lower_bounds.len()];
+    if counts.is_empty() {
+        return counts;
+    }
+    for &value in values {
+        let index = lower_bounds
+            .iter()
+            .rposition(|&min| value >= min)
+            .unwrap_or(0);
+        counts[index] += 1;
+    }
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mean_and_median() {
+        assert_eq!(mean(&[]), 0.0);
+        assert_eq!(mean(&[1.0, 2.0, 6.0]), 3.0);
+        assert_eq!(median(&[]), 0.0);
+        assert_eq!(median(&[5.0, 1.0, 3.0]), 3.0);
+        assert_eq!(median(&[4.0, 1.0, 3.0, 2.0]), 2.5);
+    }
+
+    #[test]
+    fn test_percentile() {
+        let values = [15.0, 20.0, 35.0, 40.0, 50.0];
+        assert_eq!(percentile(&values, 0.0), 15.0);
+        assert_eq!(percentile(&values, 25.0), 20.0);
+        assert_eq!(percentile(&values, 90.0), 46.0);
+        assert_eq!(percentile(&values, 100.0), 50.0);
+        assert_eq!(percentile(&values, 150.0), 50.0);
+        assert_eq!(percentile(&[7.0], 90.0), 7.0);
+    }
+
+    #[test]
+    fn test_stddev() {
+        assert_eq!(stddev(&[3.0]), 0.0);
+        assert_eq!(stddev(&[2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0]), 2.0);
+    }
+
+    #[test]
+    fn test_bucket_counts() {
+        let counts = bucket_counts(&[0.0, 1.0, 3.0, 4.0, 100.0], &[1.0, 2.0, 4.0]);
+        assert_eq!(counts, vec![2, 1, 2]);
+        assert!(bucket_counts(&[1.0], &[]).is_empty());
+    }
+}
@@ -0,0 +1,35 @@
+//! Support for calling the stats crates from async code
+//!
+//! Every stats entry point reads SQLite databases or export files synchronously, which
+//! would stall an async runtime's worker thread. The `nonblocking` modules of the other
+//! crates (behind their `tokio` features) wrap their entry points with `run`.
+
+use anyhow::{Context, Result};
+
+/// Runs blocking stats work on tokio's blocking thread pool and waits for its result
+///
+/// A panic in `work` is returned as an error.
+pub async fn run<T, F>(work: F) -> Result<T>
+where
+    F: FnOnce() -> Result<T> + Send + 'static,
+    T: Send + 'static,
+{
+    tokio::task::spawn_blocking(work)
+        .await
+        .context("Stats task failed")?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_run() {
+        assert_eq!(run(|| Ok(2 + 2)).await.unwrap(), 4);
+        assert!(run(|| -> Result<()> { anyhow::bail!("no database") })
+            .await
+            .is_err());
+        let panicked = run(|| -> Result<()> { panic!("bad row") }).await;
+        assert!(format!("{:#}", panicked.unwrap_err()).contains("Stats task failed"));
+    }
+}
@@ -0,0 +1,269 @@
+use anyhow::{bail, Context, Result};
+use std::collections::HashMap;
+
+use crate::date_periods::DatePeriod;
+
+/// Values keyed by period bucket (YYYY-MM-DD), in chronological order
+///
+/// Lets results from different sources be combined by date instead of by position:
+/// `zip` matches buckets by key and checks both series cover the same ones, so a source
+/// that returns a different range is an error rather than silently misaligned data.
+///
+/// # Example
+/// ```
+/// use statsutils::PeriodSeries;
+///
+/// let minutes = PeriodSeries::new(vec![("2025-01-01".to_string(), 5.0)]);
+/// let pages = PeriodSeries::new(vec![("2025-01-01".to_string(), 12)]);
+/// let combined = minutes.zip(pages)?.map(|date, (minutes, pages)| (date, minutes, pages));
+/// assert_eq!(combined, vec![("2025-01-01".to_string(), 5.0, 12)]);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct PeriodSeries<T> {
+    entries: Vec<(String, T)>,
+}
+
+impl<T> PeriodSeries<T> {
+    pub fn new(entries: Vec<(String, T)>) -> Self {
+        Self { entries }
+    }
+
+    /// Builds a series from values that carry their own bucket key, such as a `date` or
+    /// `week_start` field
+    pub fn from_items(items: Vec<T>, key: impl Fn(&T) -> &str) -> Self {
+        let entries = items
+            .into_iter()
+            .map(|item| (key(&item).to_string(), item))
+            .collect();
+        Self { entries }
+    }
+
+    /// Bucket keys, oldest first
+    pub fn dates(&self) -> impl Iterator<Item = &str> {
+        self.entries.iter().map(|(date, _)| date.as_str())
+    }
+
+    /// Bucket keys and values, oldest first
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &T)> {
+        self.entries
+            .iter()
+            .map(|(date, value)| (date.as_str(), value))
+    }
+
+    /// Values, oldest first
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.entries.iter().map(|(_, value)| value)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Pairs up the values of two series by bucket key, in this series' order
+    ///
+    /// # Errors
+    /// Returns an error listing the missing and unexpected buckets if the series don't
+    /// cover exactly the same buckets, or if `other` repeats a bucket
+    pub fn zip<U>(self, other: PeriodSeries<U>) -> Result<PeriodSeries<(T, U)>> {
+        let other_len = other.len();
+        let mut by_date: HashMap<String, U> = other.entries.into_iter().collect();
+        if by_date.len() != other_len {
+            bail!("Can't join period results: the other series repeats a bucket");
+        }
+
+        let mut entries = Vec::with_capacity(self.len());
+        let mut missing = Vec::new();
+        for (date, value) in self.entries {
+            match by_date.remove(&date) {
+                Some(other_value) => entries.push((date, (value, other_value))),
+                None => missing.push(date),
+            }
+        }
+
+        if !missing.is_empty() || !by_date.is_empty() {
+            let mut unexpected: Vec<String> = by_date.into_keys().collect();
+            unexpected.sort();
+            let mut problems = Vec::new();
+            if !missing.is_empty() {
+                problems.push(format!("missing {}", missing.join(", ")));
+            }
+            if !unexpected.is_empty() {
+                problems.push(format!("unexpected {}", unexpected.join(", ")));
+            }
+            bail!("Can't join period results: {}", problems.join("; "));
+        }
+
+        Ok(PeriodSeries { entries })
+    }
+
+    /// Like `zip`, naming the source of `other` in the error so a misaligned source is
+    /// easy to find
+    pub fn zip_source<U>(
+        self,
+        source: &str,
+        other: PeriodSeries<U>,
+    ) -> Result<PeriodSeries<(T, U)>> {
+        self.zip(other).with_context(|| {
+            format!(
+                "{} results cover different buckets than the other sources",
+                source
+            )
+        })
+    }
+
+    /// Maps each bucket's key and value to a result, keeping chronological order
+    pub fn map<R>(self, mut mapper: impl FnMut(String, T) -> R) -> Vec<R> {
+        self.entries
+            .into_iter()
+            .map(|(date, value)| mapper(date, value))
+            .collect()
+    }
+
+    /// Values without their keys, oldest first
+    pub fn into_values(self) -> Vec<T> {
+        self.entries.into_iter().map(|(_, value)| value).collect()
+    }
+}
+
+/// Lays out sparse results (e.g. from a SQL `GROUP BY` on a bucket function) over every
+/// bucket of `period`, filling gaps with defaults
+///
+/// Unlike `DatePeriod::build_series`, keys that aren't buckets of the period are an
+/// error rather than being dropped: they mean the query bucketed with a different
+/// timezone, rollover hour, or week start than the period was built with.
+pub fn densify<T: Default>(
+    mut results: HashMap<String, T>,
+    period: &DatePeriod,
+) -> Result<PeriodSeries<T>> {
+    let entries: Vec<(String, T)> = period
+        .iter()
+        .map(|key| {
+            let value = results.remove(key).unwrap_or_default();
+            (key.clone(), value)
+        })
+        .collect();
+
+    if !results.is_empty() {
+        let mut unexpected: Vec<String> = results.into_keys().collect();
+        unexpected.sort();
+        bail!(
+            "Results contain buckets outside the period ({} to {}): {}",
+            period.dates.first().map_or("", String::as_str),
+            period.dates.last().map_or("", String::as_str),
+            unexpected.join(", ")
+        );
+    }
+
+    Ok(PeriodSeries::new(entries))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn series<T>(entries: &[(&str, T)]) -> PeriodSeries<T>
+    where
+        T: Clone,
+    {
+        PeriodSeries::new(
+            entries
+                .iter()
+                .map(|(date, value)| (date.to_string(), value.clone()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_zip_joins_many_series_by_date() {
+        let anki = series(&[("2025-01-01", 1), ("2025-01-02", 2)]);
+        let reading = series(&[("2025-01-01", 10), ("2025-01-02", 20)]);
+        let church = series(&[("2025-01-01", 100), ("2025-01-02", 200)]);
+        let prayer = series(&[("2025-01-01", 1000), ("2025-01-02", 2000)]);
+
+        let totals = anki
+            .zip(reading)
+            .and_then(|s| s.zip(church))
+            .and_then(|s| s.zip(prayer))
+            .unwrap()
+            .map(|date, (((a, b), c), d)| (date, a + b + c + d));
+
+        assert_eq!(
+            totals,
+            vec![
+                ("2025-01-01".to_string(), 1111),
+                ("2025-01-02".to_string(), 2222)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_zip_rejects_mismatched_dates() {
+        let days = series(&[("2025-01-01", 1), ("2025-01-02", 2)]);
+        let shifted = series(&[("2025-01-02", 2), ("2025-01-03", 3)]);
+        let err = days.zip(shifted).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Can't join period results: missing 2025-01-01; unexpected 2025-01-03"
+        );
+
+        let days = series(&[("2025-01-01", 1), ("2025-01-02", 2)]);
+        let shorter = series(&[("2025-01-01", 1)]);
+        let err = days.zip_source("prayer", shorter).unwrap_err();
+        assert_eq!(
+            format!("{:#}", err),
+            "prayer results cover different buckets than the other sources: \
+             Can't join period results: missing 2025-01-02"
+        );
+
+        let days = series(&[("2025-01-01", 1)]);
+        let repeated = series(&[("2025-01-01", 1), ("2025-01-01", 2)]);
+        assert!(days.zip(repeated).is_err());
+    }
+
+    #[test]
+    fn test_zip_matches_by_date_not_position() {
+        let days = series(&[("2025-01-01", 1), ("2025-01-02", 2)]);
+        let reversed = series(&[("2025-01-02", 20), ("2025-01-01", 10)]);
+        assert_eq!(
+            days.zip(reversed).unwrap(),
+            series(&[("2025-01-01", (1, 10)), ("2025-01-02", (2, 20))])
+        );
+    }
+
+    #[test]
+    fn test_from_items() {
+        let items = vec![("2025-01-05".to_string(), 3.0)];
+        let series = PeriodSeries::from_items(items, |(date, _)| date);
+        assert_eq!(series.dates().collect::<Vec<_>>(), vec!["2025-01-05"]);
+        assert_eq!(series.into_values(), vec![("2025-01-05".to_string(), 3.0)]);
+    }
+
+    #[test]
+    fn test_densify() {
+        let period = DatePeriod {
+            dates: vec!["2025-06-01".to_string(), "2025-06-02".to_string()],
+            start_ms: 0,
+            end_ms: 0,
+            granularity: crate::Granularity::Day,
+        };
+
+        let filled = densify(HashMap::from([("2025-06-02".to_string(), 5)]), &period).unwrap();
+        assert_eq!(filled, series(&[("2025-06-01", 0), ("2025-06-02", 5)]));
+
+        // A key from a mismatched rollover or timezone
+        let err = densify(
+            HashMap::from([("2025-05-31".to_string(), 1), ("2025-06-01".to_string(), 2)]),
+            &period,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Results contain buckets outside the period (2025-06-01 to 2025-06-02): 2025-05-31"
+        );
+    }
+}
@@ -0,0 +1,228 @@
+use anyhow::{Context, Result};
+use rusqlite::backup::Progress;
+use rusqlite::{Connection, ErrorCode, OpenFlags, MAIN_DB};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+use crate::config::PeriodConfig;
+use crate::sqlite_functions::register_date_functions_with_config;
+
+/// How `open_readonly_with_retry` opens a database
+#[derive(Debug, Clone)]
+pub struct OpenOptions {
+    /// What the database is, for error messages (e.g. "Anki database")
+    pub label: String,
+    /// Number of attempts to open a busy database before giving up or snapshotting it
+    pub attempts: u32,
+    /// Delay before the first retry; doubled after each failed attempt
+    pub retry_delay: Duration,
+    /// How long queries wait on a lock once the database has been opened
+    pub busy_timeout: Duration,
+    /// Read a copy of the database and its write-ahead log when it stays locked
+    pub snapshot_when_locked: bool,
+    /// Day boundaries to register the statsutils date functions with, or `None` to leave
+    /// registration to the caller (e.g. when the database records its own boundaries)
+    pub date_functions: Option<PeriodConfig>,
+}
+
+impl OpenOptions {
+    /// Default options with the given label
+    pub fn new(label: &str) -> Self {
+        Self {
+            label: label.to_string(),
+            ..Self::default()
+        }
+    }
+}
+
+impl Default for OpenOptions {
+    fn default() -> Self {
+        Self {
+            label: "database".to_string(),
+            attempts: 4,
+            retry_delay: Duration::from_millis(250),
+            busy_timeout: Duration::from_secs(5),
+            snapshot_when_locked: true,
+            date_functions: Some(PeriodConfig::default()),
+        }
+    }
+}
+
+/// Opens a SQLite database read-only, coping with the app that owns it holding a lock
+///
+/// Opening is retried with backoff while the database is busy. If it stays locked and
+/// `snapshot_when_locked` is set, the file and its write-ahead log are copied to a
+/// temporary directory and loaded into memory instead. The statsutils date functions are
+/// registered on the returned connection unless `date_functions` is `None`.
+pub fn open_readonly_with_retry(path: &str, options: &OpenOptions) -> Result<Connection> {
+    let conn = open_with_retry(path, options)?;
+
+    if let Some(config) = &options.date_functions {
+        register_date_functions_with_config(&conn, config)?;
+    }
+
+    Ok(conn)
+}
+
+fn open_with_retry(path: &str, options: &OpenOptions) -> Result<Connection> {
+    let mut delay = options.retry_delay;
+    let attempts = options.attempts.max(1);
+
+    for attempt in 1..=attempts {
+        match try_open_read_only(path, options.busy_timeout) {
+            Ok(conn) => return Ok(conn),
+            Err(e) if is_lock_error(&e) => {
+                if attempt == attempts && !options.snapshot_when_locked {
+                    return Err(e).context(format!("{} is locked", options.label));
+                }
+                if attempt < attempts {
+                    thread::sleep(delay);
+                    delay *= 2;
+                }
+            }
+            Err(e) => {
+                return Err(e).context(format!(
+                    "Failed to open {} in read-only mode",
+                    options.label
+                ))
+            }
+        }
+    }
+
+    open_snapshot(Path::new(path)).context(format!(
+        "{} is locked and no snapshot could be read",
+        options.label
+    ))
+}
+
+/// Opens the database read-only and reads the schema to surface any locking errors up front
+fn try_open_read_only(path: &str, busy_timeout: Duration) -> rusqlite::Result<Connection> {
+    let conn = Connection::open_with_flags(
+        path,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )?;
+    // Fail fast on locks; retries and backoff are handled by the caller
+    conn.busy_timeout(Duration::ZERO)?;
+    conn.query_row("SELECT COUNT(*) FROM sqlite_master", [], |row| {
+        row.get::<_, i64>(0)
+    })?;
+    // Later queries can wait out brief locks as usual
+    conn.busy_timeout(busy_timeout)?;
+    Ok(conn)
+}
+
+fn is_lock_error(error: &rusqlite::Error) -> bool {
+    matches!(
+        error.sqlite_error_code(),
+        Some(ErrorCode::DatabaseBusy | ErrorCode::DatabaseLocked)
+    )
+}
+
+/// Copies the database and its write-ahead log to a temporary directory and loads the copy
+///
+/// Opening the copy replays any committed transactions still in the WAL, so the snapshot
+/// includes changes not yet checkpointed into the main file.
+pub fn open_snapshot(path: &Path) -> Result<Connection> {
+    let snapshot_dir = tempfile::tempdir().context("Failed to create snapshot directory")?;
+    let file_name = path
+        .file_name()
+        .context(format!("Not a database file: {:?}", path))?;
+    let snapshot_path = snapshot_dir.path().join(file_name);
+
+    fs::copy(path, &snapshot_path).context(format!("Failed to copy database {:?}", path))?;
+
+    let mut wal_path = path.as_os_str().to_owned();
+    wal_path.push("-wal");
+    let wal_path = PathBuf::from(wal_path);
+    if wal_path.exists() {
+        let mut snapshot_wal = snapshot_path.as_os_str().to_owned();
+        snapshot_wal.push("-wal");
+        fs::copy(&wal_path, snapshot_wal)
+            .context(format!("Failed to copy write-ahead log {:?}", wal_path))?;
+    }
+
+    load_into_memory(&snapshot_path)
+}
+
+/// Loads a database file into an in-memory connection so the file can be discarded
+pub fn load_into_memory(path: &Path) -> Result<Connection> {
+    let mut conn = Connection::open_in_memory().context("Failed to open in-memory database")?;
+    conn.restore(MAIN_DB, path, None::<fn(Progress)>)?;
+    Ok(conn)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_database(path: &Path) -> Connection {
+        let conn = Connection::open(path).unwrap();
+        conn.execute_batch("CREATE TABLE t (value INTEGER); INSERT INTO t VALUES (1);")
+            .unwrap();
+        conn
+    }
+
+    fn value(conn: &Connection) -> i64 {
+        conn.query_row("SELECT value FROM t", [], |row| row.get(0))
+            .unwrap()
+    }
+
+    fn quick(snapshot_when_locked: bool) -> OpenOptions {
+        OpenOptions {
+            attempts: 2,
+            retry_delay: Duration::from_millis(1),
+            snapshot_when_locked,
+            ..OpenOptions::new("Test database")
+        }
+    }
+
+    #[test]
+    fn test_registers_date_functions() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("test.sqlite");
+        drop(create_database(&path));
+
+        let conn = open_readonly_with_retry(path.to_str().unwrap(), &quick(true)).unwrap();
+        assert_eq!(value(&conn), 1);
+        let date: String = conn
+            .query_row("SELECT date_str_from_sec(1700000000)", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(date.len(), 10);
+    }
+
+    #[test]
+    fn test_locked_database_falls_back_to_snapshot() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("test.sqlite");
+        let writer = create_database(&path);
+        writer
+            .execute_batch(
+                "PRAGMA locking_mode = EXCLUSIVE; BEGIN EXCLUSIVE; UPDATE t SET value = 1;",
+            )
+            .unwrap();
+
+        let conn = open_readonly_with_retry(path.to_str().unwrap(), &quick(true)).unwrap();
+        assert_eq!(value(&conn), 1);
+
+        let err = open_readonly_with_retry(path.to_str().unwrap(), &quick(false)).unwrap_err();
+        assert!(err.to_string().contains("Test database is locked"));
+    }
+
+    #[test]
+    fn test_snapshot_includes_wal() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("test.sqlite");
+
+        let writer = create_database(&path);
+        writer
+            .execute_batch(
+                "PRAGMA journal_mode = WAL; PRAGMA wal_autocheckpoint = 0; UPDATE t SET value = 2;",
+            )
+            .unwrap();
+
+        let conn = open_snapshot(&path).unwrap();
+        assert_eq!(value(&conn), 2);
+    }
+}
@@ -1,65 +1,52 @@
 use anyhow::{Context, Result};
-use chrono::{Datelike, Duration, TimeZone};
-use chrono_tz::Tz;
 use rusqlite::Connection;
 
-use crate::config;
+use crate::config::PeriodConfig;
 
 /// Converts timestamp to date string with timezone and rollover applied
-fn timestamp_ms_to_date_string(timestamp_ms: i64) -> Result<String> {
-    let tz: Tz = config::TIMEZONE
-        .parse()
-        .context("Failed to parse timezone from config")?;
-
-    // Convert timestamp to datetime in Chicago timezone
-    let dt = tz
-        .timestamp_millis_opt(timestamp_ms)
-        .single()
-        .context("Failed to convert timestamp to datetime")?;
-
-    // Subtract rollover hours to get the logical date
-    let adjusted_dt = dt - Duration::hours(config::ROLLOVER_HOUR);
+fn timestamp_ms_to_date_string(config: &PeriodConfig, timestamp_ms: i64) -> Result<String> {
+    let date = config.logical_date(timestamp_ms)?;
 
     // Format as YYYY-MM-DD
-    Ok(adjusted_dt.format("%Y-%m-%d").to_string())
+    Ok(date.format("%Y-%m-%d").to_string())
 }
 
-/// Converts timestamp to week string (Sunday of that week)
-fn timestamp_ms_to_week_string(timestamp_ms: i64) -> Result<String> {
-    let tz: Tz = config::TIMEZONE
-        .parse()
-        .context("Failed to parse timezone from config")?;
-
-    // Convert timestamp to datetime in Chicago timezone
-    let dt = tz
-        .timestamp_millis_opt(timestamp_ms)
-        .single()
-        .context("Failed to convert timestamp to datetime")?;
+/// Converts timestamp to week string (first day of that week, or its ISO week number)
+fn timestamp_ms_to_week_string(config: &PeriodConfig, timestamp_ms: i64) -> Result<String> {
+    let date = config.logical_date(timestamp_ms)?;
 
-    // Subtract rollover hours to get the logical datetime
-    let adjusted_dt = dt - Duration::hours(config::ROLLOVER_HOUR);
+    Ok(config.week_key(date))
+}
 
-    // Find the Sunday of this week
-    let days_since_sunday = adjusted_dt.weekday().num_days_from_sunday();
-    let sunday = adjusted_dt - Duration::days(days_since_sunday as i64);
+/// Converts timestamp to month string (YYYY-MM)
+fn timestamp_ms_to_month_string(config: &PeriodConfig, timestamp_ms: i64) -> Result<String> {
+    let date = config.logical_date(timestamp_ms)?;
 
-    // Format as YYYY-MM-DD
-    Ok(sunday.format("%Y-%m-%d").to_string())
+    // Format as YYYY-MM
+    Ok(date.format("%Y-%m").to_string())
 }
 
-/// Registers custom SQLite functions: date_str_from_ms/sec, week_str_from_ms/sec
+/// Registers custom SQLite functions: date_str_from_ms/sec, week_str_from_ms/sec,
+/// month_str_from_ms/sec
 ///
 /// All functions apply timezone and 4 AM rollover
 pub fn register_date_functions(conn: &Connection) -> Result<()> {
+    register_date_functions_with_config(conn, &PeriodConfig::default())
+}
+
+/// Registers the same SQLite date functions using the given timezone and rollover hour
+pub fn register_date_functions_with_config(conn: &Connection, config: &PeriodConfig) -> Result<()> {
+    let config = *config;
+
     // date_str_from_ms: milliseconds -> YYYY-MM-DD
     conn.create_scalar_function(
         "date_str_from_ms",
         1,
         rusqlite::functions::FunctionFlags::SQLITE_UTF8
             | rusqlite::functions::FunctionFlags::SQLITE_DETERMINISTIC,
-        |ctx| {
+        move |ctx| {
             let timestamp_ms = ctx.get::<i64>(0)?;
-            timestamp_ms_to_date_string(timestamp_ms)
+            timestamp_ms_to_date_string(&config, timestamp_ms)
                 .map_err(|e| rusqlite::Error::UserFunctionError(e.into()))
         },
     )
@@ -71,10 +58,10 @@ pub fn register_date_functions(conn: &Connection) -> Result<()> {
         1,
         rusqlite::functions::FunctionFlags::SQLITE_UTF8
             | rusqlite::functions::FunctionFlags::SQLITE_DETERMINISTIC,
-        |ctx| {
+        move |ctx| {
             let timestamp_sec = ctx.get::<i64>(0)?;
             let timestamp_ms = timestamp_sec * 1000;
-            timestamp_ms_to_date_string(timestamp_ms)
+            timestamp_ms_to_date_string(&config, timestamp_ms)
                 .map_err(|e| rusqlite::Error::UserFunctionError(e.into()))
         },
     )
@@ -86,9 +73,9 @@ pub fn register_date_functions(conn: &Connection) -> Result<()> {
         1,
         rusqlite::functions::FunctionFlags::SQLITE_UTF8
             | rusqlite::functions::FunctionFlags::SQLITE_DETERMINISTIC,
-        |ctx| {
+        move |ctx| {
             let timestamp_ms = ctx.get::<i64>(0)?;
-            timestamp_ms_to_week_string(timestamp_ms)
+            timestamp_ms_to_week_string(&config, timestamp_ms)
                 .map_err(|e| rusqlite::Error::UserFunctionError(e.into()))
         },
     )
@@ -100,14 +87,128 @@ pub fn register_date_functions(conn: &Connection) -> Result<()> {
         1,
         rusqlite::functions::FunctionFlags::SQLITE_UTF8
             | rusqlite::functions::FunctionFlags::SQLITE_DETERMINISTIC,
-        |ctx| {
+        move |ctx| {
             let timestamp_sec = ctx.get::<i64>(0)?;
             let timestamp_ms = timestamp_sec * 1000;
-            timestamp_ms_to_week_string(timestamp_ms)
+            timestamp_ms_to_week_string(&config, timestamp_ms)
                 .map_err(|e| rusqlite::Error::UserFunctionError(e.into()))
         },
     )
     .context("Failed to register week_str_from_sec function")?;
 
+    // month_str_from_ms: milliseconds -> YYYY-MM
+    conn.create_scalar_function(
+        "month_str_from_ms",
+        1,
+        rusqlite::functions::FunctionFlags::SQLITE_UTF8
+            | rusqlite::functions::FunctionFlags::SQLITE_DETERMINISTIC,
+        move |ctx| {
+            let timestamp_ms = ctx.get::<i64>(0)?;
+            timestamp_ms_to_month_string(&config, timestamp_ms)
+                .map_err(|e| rusqlite::Error::UserFunctionError(e.into()))
+        },
+    )
+    .context("Failed to register month_str_from_ms function")?;
+
+    // month_str_from_sec: seconds -> YYYY-MM
+    conn.create_scalar_function(
+        "month_str_from_sec",
+        1,
+        rusqlite::functions::FunctionFlags::SQLITE_UTF8
+            | rusqlite::functions::FunctionFlags::SQLITE_DETERMINISTIC,
+        move |ctx| {
+            let timestamp_sec = ctx.get::<i64>(0)?;
+            let timestamp_ms = timestamp_sec * 1000;
+            timestamp_ms_to_month_string(&config, timestamp_ms)
+                .map_err(|e| rusqlite::Error::UserFunctionError(e.into()))
+        },
+    )
+    .context("Failed to register month_str_from_sec function")?;
+
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::StatsTimezone;
+    use crate::date_periods::{DatePeriod, Granularity};
+    use chrono::{FixedOffset, NaiveDate};
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_month_buckets_match_period() {
+        let config = PeriodConfig::new(StatsTimezone::Fixed(FixedOffset::east_opt(0).unwrap()), 4);
+        let conn = Connection::open_in_memory().unwrap();
+        register_date_functions_with_config(&conn, &config).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE events (ms INTEGER);
+             -- 2025-03-01 03:00 UTC, before the rollover, so still February
+             INSERT INTO events VALUES (1740798000000);
+             -- 2025-03-01 05:00 UTC
+             INSERT INTO events VALUES (1740805200000);
+             -- 2025-04-15 12:00 UTC
+             INSERT INTO events VALUES (1744718400000);",
+        )
+        .unwrap();
+
+        let period = DatePeriod::for_range_with_config(
+            NaiveDate::from_ymd_opt(2025, 2, 1).unwrap(),
+            NaiveDate::from_ymd_opt(2025, 4, 30).unwrap(),
+            Granularity::Month,
+            &config,
+        )
+        .unwrap();
+
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT {0}(ms), COUNT(*) FROM events WHERE ms >= ?1 AND ms < ?2 GROUP BY {0}(ms)",
+                Granularity::Month.ms_bucket_function()
+            ))
+            .unwrap();
+        let counts = stmt
+            .query_map([period.start_ms, period.end_ms], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+            })
+            .unwrap()
+            .collect::<Result<HashMap<String, i64>, _>>()
+            .unwrap();
+
+        let results = period.build_results(counts, |month, count| (month, count));
+        assert_eq!(
+            results,
+            vec![
+                ("2025-02".to_string(), 1),
+                ("2025-03".to_string(), 1),
+                ("2025-04".to_string(), 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_iso_week_buckets_match_period() {
+        let config = PeriodConfig::new(StatsTimezone::Fixed(FixedOffset::east_opt(0).unwrap()), 4)
+            .with_iso_weeks();
+        let conn = Connection::open_in_memory().unwrap();
+        register_date_functions_with_config(&conn, &config).unwrap();
+
+        // 2025-03-10 03:00 UTC is a Monday before the rollover, so still Sunday in week 10
+        let week: String = conn
+            .query_row("SELECT week_str_from_ms(1741575600000)", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(week, "2025-W10");
+
+        let period = DatePeriod::for_range_with_config(
+            NaiveDate::from_ymd_opt(2025, 3, 2).unwrap(),
+            NaiveDate::from_ymd_opt(2025, 3, 10).unwrap(),
+            Granularity::Week,
+            &config,
+        )
+        .unwrap();
+        assert_eq!(period.dates, vec!["2025-W09", "2025-W10", "2025-W11"]);
+        // Weeks still start on Monday: 2025-02-24 04:00 UTC
+        assert_eq!(period.start_ms, 1740369600000);
+    }
+}
@@ -0,0 +1,162 @@
+use anyhow::Result;
+use chrono::{Duration, NaiveDate};
+
+use crate::config::PeriodConfig;
+
+/// Runs of consecutive active days, and the gaps between them
+#[derive(Debug, Clone, PartialEq)]
+pub struct Streaks {
+    /// Consecutive active days ending today (or yesterday if today has no activity yet)
+    pub current_streak: i64,
+    /// Longest run of consecutive active days
+    pub longest_streak: i64,
+    /// Number of distinct active days
+    pub active_days: i64,
+    /// Most recent active day
+    pub last_active_date: Option<NaiveDate>,
+    /// Inactive stretches between active days, oldest first
+    pub gaps: Vec<Gap>,
+}
+
+/// A stretch of consecutive days without activity, between two active days
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Gap {
+    /// First inactive day
+    pub start: NaiveDate,
+    /// Last inactive day
+    pub end: NaiveDate,
+    pub days: i64,
+}
+
+/// Computes streaks from the logical dates (see `PeriodConfig::logical_date`) with activity,
+/// with "today" taken from `config`
+///
+/// The current streak counts active days ending today. If today has no activity yet, the
+/// streak ending yesterday is still considered current, since the day isn't over.
+pub fn compute(dates_with_activity: &[NaiveDate], config: &PeriodConfig) -> Result<Streaks> {
+    Ok(compute_as_of(dates_with_activity, config.today()?))
+}
+
+/// Like `compute`, for an explicit `today`
+pub fn compute_as_of(dates_with_activity: &[NaiveDate], today: NaiveDate) -> Streaks {
+    let mut dates = dates_with_activity.to_vec();
+    dates.sort();
+    dates.dedup();
+
+    let mut longest_streak = 0;
+    let mut run = 0;
+    let mut gaps = Vec::new();
+    let mut previous: Option<NaiveDate> = None;
+
+    for &date in &dates {
+        run = match previous {
+            Some(prev) if date - prev == Duration::days(1) => run + 1,
+            Some(prev) => {
+                gaps.push(Gap {
+                    start: prev + Duration::days(1),
+                    end: date - Duration::days(1),
+                    days: (date - prev).num_days() - 1,
+                });
+                1
+            }
+            None => 1,
+        };
+        longest_streak = longest_streak.max(run);
+        previous = Some(date);
+    }
+
+    // The last run is the current streak if it ends today or yesterday
+    let current_streak = match dates.last() {
+        Some(&last) if last == today || last == today - Duration::days(1) => run,
+        _ => 0,
+    };
+
+    Streaks {
+        current_streak,
+        longest_streak,
+        active_days: dates.len() as i64,
+        last_active_date: dates.last().copied(),
+        gaps,
+    }
+}
+
+/// Converts activity timestamps to the logical dates they fall on, applying the rollover
+pub fn dates_from_timestamps(
+    timestamps_ms: impl IntoIterator<Item = i64>,
+    config: &PeriodConfig,
+) -> Result<Vec<NaiveDate>> {
+    timestamps_ms
+        .into_iter()
+        .map(|timestamp_ms| config.logical_date(timestamp_ms))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::StatsTimezone;
+    use chrono::FixedOffset;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_streaks_and_gaps() {
+        let dates = [
+            date("2025-10-01"),
+            date("2025-10-02"),
+            date("2025-10-05"),
+            date("2025-10-06"),
+            date("2025-10-07"),
+            date("2025-10-08"),
+            date("2025-10-19"),
+        ];
+
+        let streaks = compute_as_of(&dates, date("2025-10-20"));
+        assert_eq!(streaks.current_streak, 1);
+        assert_eq!(streaks.longest_streak, 4);
+        assert_eq!(streaks.active_days, 7);
+        assert_eq!(streaks.last_active_date, Some(date("2025-10-19")));
+        assert_eq!(
+            streaks.gaps,
+            vec![
+                Gap {
+                    start: date("2025-10-03"),
+                    end: date("2025-10-04"),
+                    days: 2
+                },
+                Gap {
+                    start: date("2025-10-09"),
+                    end: date("2025-10-18"),
+                    days: 10
+                },
+            ]
+        );
+
+        let streaks = compute_as_of(&dates, date("2025-10-21"));
+        assert_eq!(streaks.current_streak, 0);
+    }
+
+    #[test]
+    fn test_no_activity() {
+        let streaks = compute_as_of(&[], date("2025-10-20"));
+        assert_eq!(streaks.current_streak, 0);
+        assert_eq!(streaks.longest_streak, 0);
+        assert_eq!(streaks.last_active_date, None);
+        assert!(streaks.gaps.is_empty());
+    }
+
+    #[test]
+    fn test_dates_from_timestamps_apply_rollover() {
+        let config = PeriodConfig::new(StatsTimezone::Fixed(FixedOffset::east_opt(0).unwrap()), 4);
+
+        // 2025-03-01 23:00 and 2025-03-02 03:00 UTC are the same logical day
+        let dates = dates_from_timestamps([1740870000000, 1740884400000], &config).unwrap();
+        assert_eq!(dates, vec![date("2025-03-01"), date("2025-03-01")]);
+
+        let streaks = compute_as_of(&dates, date("2025-03-02"));
+        assert_eq!(streaks.active_days, 1);
+        assert_eq!(streaks.current_streak, 1);
+    }
+}
@@ -0,0 +1,373 @@
+//! Builders for minimal Anki, KOReader, Kobo, and Proseuche databases, for tests that exercise the
+//! stats crates end to end without a real database
+//!
+//! Each builder creates a fresh SQLite file with just the tables and columns the stats
+//! crates read. Timestamps are milliseconds since the epoch, like `DatePeriod` boundaries.
+
+use anyhow::{Context, Result};
+use chrono::DateTime;
+use rusqlite::{params, Connection};
+use std::path::Path;
+
+/// Builds an Anki collection (`collection.anki2`)
+pub struct AnkiFixture {
+    conn: Connection,
+    next_id: i64,
+}
+
+impl AnkiFixture {
+    /// Creates an empty collection at `path` using UTC and Anki's default 4 AM rollover
+    ///
+    /// `crt_secs` is the collection creation time, which Anki counts due days from.
+    pub fn create(path: &Path, crt_secs: i64) -> Result<Self> {
+        let conn = create_database(path)?;
+        conn.execute_batch(
+            "CREATE TABLE col (id integer primary key, crt integer, ver integer, conf text);
+             CREATE TABLE config (KEY text primary key, usn integer, mtime_secs integer, val blob);
+             CREATE TABLE decks (id integer primary key, name text);
+             CREATE TABLE notetypes (id integer primary key, name text);
+             CREATE TABLE notes (id integer primary key, mid integer, mod integer, tags text,
+                 flds text, sfld text);
+             CREATE TABLE cards (id integer primary key, nid integer, did integer, ord integer,
+                 mod integer, type integer, queue integer, due integer, ivl integer,
+                 factor integer, reps integer, lapses integer, odue integer, odid integer);
+             CREATE TABLE revlog (id integer primary key, cid integer, usn integer,
+                 ease integer, ivl integer, lastIvl integer, factor integer, time integer,
+                 type integer);
+             INSERT INTO config VALUES ('rollover', 0, 0, CAST('4' AS BLOB));
+             INSERT INTO config VALUES ('localOffset', 0, 0, CAST('0' AS BLOB));",
+        )
+        .context("Failed to create Anki tables")?;
+        conn.execute("INSERT INTO col VALUES (1, ?1, 18, '{}')", [crt_secs])?;
+
+        Ok(AnkiFixture { conn, next_id: 1 })
+    }
+
+    /// Sets the collection's rollover hour and UTC offset (`localOffset`, in minutes west
+    /// of UTC, so UTC+10 is -600)
+    pub fn set_day_boundaries(
+        &mut self,
+        rollover_hour: i64,
+        local_offset_minutes: i64,
+    ) -> Result<()> {
+        self.conn.execute(
+            "UPDATE config SET val = CAST(?2 AS BLOB) WHERE KEY = ?1",
+            params!["rollover", rollover_hour.to_string()],
+        )?;
+        self.conn.execute(
+            "UPDATE config SET val = CAST(?2 AS BLOB) WHERE KEY = ?1",
+            params!["localOffset", local_offset_minutes.to_string()],
+        )?;
+        Ok(())
+    }
+
+    fn next_id(&mut self) -> i64 {
+        self.next_id += 1;
+        self.next_id
+    }
+
+    /// Adds a deck; "::" in `name` separates parent and child decks, as in Anki's UI
+    pub fn deck(&mut self, name: &str) -> Result<i64> {
+        let id = self.next_id();
+        self.conn.execute(
+            "INSERT INTO decks VALUES (?1, ?2)",
+            params![id, name.replace("::", "\x1f")],
+        )?;
+        Ok(id)
+    }
+
+    pub fn notetype(&mut self, name: &str) -> Result<i64> {
+        let id = self.next_id();
+        self.conn
+            .execute("INSERT INTO notetypes VALUES (?1, ?2)", params![id, name])?;
+        Ok(id)
+    }
+
+    /// Adds a note whose sort field (and first field) is `sort_field`, with space-separated
+    /// `tags`
+    pub fn note(&mut self, notetype_id: i64, sort_field: &str, tags: &str) -> Result<i64> {
+        let id = self.next_id();
+        self.conn.execute(
+            "INSERT INTO notes VALUES (?1, ?2, 0, ?3, ?4, ?5)",
+            params![
+                id,
+                notetype_id,
+                format!(" {} ", tags.trim()),
+                format!("{}\x1f", sort_field),
+                sort_field
+            ],
+        )?;
+        Ok(id)
+    }
+
+    /// Adds a new, never-studied card for template `ord` of a note
+    pub fn new_card(&mut self, note_id: i64, deck_id: i64, ord: i64) -> Result<i64> {
+        self.card(note_id, deck_id, ord, 0, 0, 0)
+    }
+
+    /// Adds a card in review with the given interval (days) and due day (days since the
+    /// collection was created)
+    pub fn review_card(
+        &mut self,
+        note_id: i64,
+        deck_id: i64,
+        ord: i64,
+        interval_days: i64,
+        due_day: i64,
+    ) -> Result<i64> {
+        self.card(note_id, deck_id, ord, 2, interval_days, due_day)
+    }
+
+    fn card(
+        &mut self,
+        note_id: i64,
+        deck_id: i64,
+        ord: i64,
+        card_type: i64,
+        interval_days: i64,
+        due: i64,
+    ) -> Result<i64> {
+        let id = self.next_id();
+        self.conn.execute(
+            "INSERT INTO cards VALUES (?1, ?2, ?3, ?4, 0, ?5, ?5, ?6, ?7, 2500, 0, 0, 0, 0)",
+            params![id, note_id, deck_id, ord, card_type, due, interval_days],
+        )?;
+        Ok(id)
+    }
+
+    /// Marks a card suspended
+    pub fn suspend(&mut self, card_id: i64) -> Result<()> {
+        self.conn
+            .execute("UPDATE cards SET queue = -1 WHERE id = ?1", [card_id])?;
+        Ok(())
+    }
+
+    /// Logs a review of a card at `at_ms` taking `duration_ms`, answered with `ease`
+    /// (1 = again through 4 = easy) and leaving the card at `interval_days`
+    pub fn review(
+        &mut self,
+        card_id: i64,
+        at_ms: i64,
+        duration_ms: i64,
+        ease: i64,
+        interval_days: i64,
+    ) -> Result<()> {
+        // Review IDs are their timestamps, so keep them unique if two land on the same ms
+        let mut id = at_ms;
+        while self.conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM revlog WHERE id = ?1)",
+            [id],
+            |row| row.get::<_, bool>(0),
+        )? {
+            id += 1;
+        }
+        self.conn.execute(
+            "INSERT INTO revlog VALUES (?1, ?2, 0, ?3, ?4, 0, 2500, ?5, 1)",
+            params![id, card_id, ease, interval_days, duration_ms],
+        )?;
+        self.conn.execute(
+            "UPDATE cards SET reps = reps + 1, lapses = lapses + (?2 = 1) WHERE id = ?1",
+            params![card_id, ease],
+        )?;
+        Ok(())
+    }
+
+    pub fn connection(&self) -> &Connection {
+        &self.conn
+    }
+}
+
+/// Builds a KOReader statistics database (`statistics.sqlite3`)
+pub struct KoreaderFixture {
+    conn: Connection,
+}
+
+impl KoreaderFixture {
+    pub fn create(path: &Path) -> Result<Self> {
+        let conn = create_database(path)?;
+        conn.execute_batch(
+            "CREATE TABLE book (id integer PRIMARY KEY autoincrement, title text, authors text,
+                 notes integer, last_open integer, highlights integer, pages integer,
+                 series text, language text, md5 text, total_read_time integer,
+                 total_read_pages integer);
+             CREATE TABLE page_stat_data (id_book integer, page integer NOT NULL DEFAULT 0,
+                 start_time integer NOT NULL DEFAULT 0, duration integer NOT NULL DEFAULT 0,
+                 total_pages integer NOT NULL DEFAULT 0, UNIQUE (id_book, page, start_time));",
+        )
+        .context("Failed to create KOReader tables")?;
+        Ok(KoreaderFixture { conn })
+    }
+
+    pub fn book(&mut self, title: &str, authors: &str, pages: i64) -> Result<i64> {
+        self.conn.execute(
+            "INSERT INTO book (title, authors, pages, notes, highlights)
+             VALUES (?1, ?2, ?3, 0, 0)",
+            params![title, authors, pages],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    /// Logs `duration_secs` spent on `page` of a book, starting at `at_ms`
+    pub fn page_read(
+        &mut self,
+        book_id: i64,
+        page: i64,
+        at_ms: i64,
+        duration_secs: i64,
+    ) -> Result<()> {
+        let pages: i64 =
+            self.conn
+                .query_row("SELECT pages FROM book WHERE id = ?1", [book_id], |row| {
+                    row.get(0)
+                })?;
+        self.conn.execute(
+            "INSERT INTO page_stat_data VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![book_id, page, at_ms / 1000, duration_secs, pages],
+        )?;
+        Ok(())
+    }
+
+    /// Logs a page read in a layout with `total_pages` pages (a different font size or
+    /// margins than the book's own page count)
+    pub fn page_read_in_layout(
+        &mut self,
+        book_id: i64,
+        page: i64,
+        total_pages: i64,
+        at_ms: i64,
+        duration_secs: i64,
+    ) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO page_stat_data VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![book_id, page, at_ms / 1000, duration_secs, total_pages],
+        )?;
+        Ok(())
+    }
+
+    pub fn connection(&self) -> &Connection {
+        &self.conn
+    }
+}
+
+/// Builds a Kobo e-reader database (`.kobo/KoboReader.sqlite`) with just its analytics
+/// events
+pub struct KoboFixture {
+    conn: Connection,
+}
+
+impl KoboFixture {
+    pub fn create(path: &Path) -> Result<Self> {
+        let conn = create_database(path)?;
+        conn.execute_batch(
+            "CREATE TABLE AnalyticsEvents (Id TEXT PRIMARY KEY, Type TEXT, Timestamp TEXT,
+                 Attributes TEXT, Metrics TEXT);",
+        )
+        .context("Failed to create Kobo tables")?;
+        Ok(KoboFixture { conn })
+    }
+
+    /// Logs closing a book at `at_ms` after reading it for `seconds_read`, as Kobo's
+    /// `LeaveContent` event
+    pub fn leave_content(
+        &mut self,
+        title: &str,
+        at_ms: i64,
+        seconds_read: i64,
+        pages_turned: i64,
+    ) -> Result<()> {
+        let timestamp = DateTime::from_timestamp_millis(at_ms)
+            .context(format!("Timestamp out of range: {}", at_ms))?
+            .format("%Y-%m-%dT%H:%M:%SZ")
+            .to_string();
+        self.conn.execute(
+            "INSERT INTO AnalyticsEvents (Id, Type, Timestamp, Attributes, Metrics)
+             VALUES (lower(hex(randomblob(16))), 'LeaveContent', ?1, json_object('title', ?2),
+                 json_object('SecondsRead', ?3, 'PagesTurned', ?4))",
+            params![timestamp, title, seconds_read, pages_turned],
+        )?;
+        Ok(())
+    }
+
+    pub fn connection(&self) -> &Connection {
+        &self.conn
+    }
+}
+
+/// Builds a Proseuche prayer database
+pub struct ProseucheFixture {
+    conn: Connection,
+}
+
+impl ProseucheFixture {
+    pub fn create(path: &Path) -> Result<Self> {
+        let conn = create_database(path)?;
+        conn.execute_batch(
+            "CREATE TABLE prayer_lists (id INTEGER PRIMARY KEY, name TEXT);
+             CREATE TABLE prayer_items (id INTEGER PRIMARY KEY, title TEXT);
+             CREATE TABLE prayer_sessions (id INTEGER PRIMARY KEY, started_at DATETIME,
+                 ended_at DATETIME, prayer_list_id INTEGER,
+                 duration_minutes REAL GENERATED ALWAYS AS
+                     ((julianday(ended_at) - julianday(started_at)) * 1440) VIRTUAL);
+             CREATE TABLE prayer_session_items (prayer_session_id INTEGER,
+                 prayer_item_id INTEGER, position INTEGER);",
+        )
+        .context("Failed to create Proseuche tables")?;
+        Ok(ProseucheFixture { conn })
+    }
+
+    /// Adds a prayer list (category), e.g. "Intercession"
+    pub fn list(&mut self, name: &str) -> Result<i64> {
+        self.conn
+            .execute("INSERT INTO prayer_lists (name) VALUES (?1)", [name])?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    pub fn item(&mut self, title: &str) -> Result<i64> {
+        self.conn
+            .execute("INSERT INTO prayer_items (title) VALUES (?1)", [title])?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    /// Logs a session of `minutes` starting at `at_ms`, praying through `items` in order
+    pub fn session(
+        &mut self,
+        list_id: Option<i64>,
+        at_ms: i64,
+        minutes: f64,
+        items: &[i64],
+    ) -> Result<i64> {
+        let end_ms = at_ms + (minutes * 60_000.0).round() as i64;
+        self.conn.execute(
+            "INSERT INTO prayer_sessions (started_at, ended_at, prayer_list_id)
+             VALUES (?1, ?2, ?3)",
+            params![utc_datetime(at_ms)?, utc_datetime(end_ms)?, list_id],
+        )?;
+        let session_id = self.conn.last_insert_rowid();
+        for (position, item_id) in items.iter().enumerate() {
+            self.conn.execute(
+                "INSERT INTO prayer_session_items VALUES (?1, ?2, ?3)",
+                params![session_id, item_id, position as i64],
+            )?;
+        }
+        Ok(session_id)
+    }
+
+    pub fn connection(&self) -> &Connection {
+        &self.conn
+    }
+}
+
+fn create_database(path: &Path) -> Result<Connection> {
+    if path.exists() {
+        std::fs::remove_file(path).context(format!("Failed to replace '{}'", path.display()))?;
+    }
+    Connection::open(path).context(format!("Failed to create '{}'", path.display()))
+}
+
+/// Formats a timestamp the way Proseuche stores it: UTC, "YYYY-MM-DD HH:MM:SS"
+fn utc_datetime(ms: i64) -> Result<String> {
+    Ok(DateTime::from_timestamp_millis(ms)
+        .context(format!("Timestamp out of range: {}", ms))?
+        .format("%Y-%m-%d %H:%M:%S")
+        .to_string())
+}
@@ -0,0 +1,164 @@
+use crate::series::PeriodSeries;
+
+/// Which way a series is heading
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trend {
+    Up,
+    Down,
+    Flat,
+}
+
+impl Trend {
+    /// Classifies a slope, treating anything within `threshold` of zero as flat
+    pub fn from_slope(slope: f64, threshold: f64) -> Self {
+        if slope > threshold {
+            Trend::Up
+        } else if slope < -threshold {
+            Trend::Down
+        } else {
+            Trend::Flat
+        }
+    }
+}
+
+/// Totals for the latest stretch of buckets against the stretch before it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PeriodChange {
+    pub current: f64,
+    pub previous: f64,
+    /// `current - previous`
+    pub change: f64,
+    /// Change as a percentage of `previous`, or `None` when `previous` is zero
+    pub percent_change: Option<f64>,
+}
+
+/// Averages each bucket with the buckets before it, up to `window` buckets in all
+///
+/// The first buckets average over however many buckets are available, so the result has
+/// the same dates as `series`.
+pub fn rolling_mean(series: &PeriodSeries<f64>, window: usize) -> PeriodSeries<f64> {
+    let window = window.max(1);
+    let values: Vec<f64> = series.values().copied().collect();
+
+    PeriodSeries::new(
+        series
+            .dates()
+            .enumerate()
+            .map(|(i, date)| {
+                let start = (i + 1).saturating_sub(window);
+                let slice = &values[start..=i];
+                (
+                    date.to_string(),
+                    slice.iter().sum::<f64>() / slice.len() as f64,
+                )
+            })
+            .collect(),
+    )
+}
+
+/// Compares the total of the last `buckets` buckets with the `buckets` before them
+///
+/// Returns `None` if the series is shorter than two stretches.
+pub fn period_over_period(series: &PeriodSeries<f64>, buckets: usize) -> Option<PeriodChange> {
+    if buckets == 0 || series.len() < buckets * 2 {
+        return None;
+    }
+
+    let values: Vec<f64> = series.values().copied().collect();
+    let split = values.len() - buckets;
+    let current: f64 = values[split..].iter().sum();
+    let previous: f64 = values[split - buckets..split].iter().sum();
+
+    Some(PeriodChange {
+        current,
+        previous,
+        change: current - previous,
+        percent_change: (previous != 0.0).then(|| (current - previous) / previous * 100.0),
+    })
+}
+
+/// Compares the last 7 days of a daily series with the 7 days before
+pub fn week_over_week(daily: &PeriodSeries<f64>) -> Option<PeriodChange> {
+    period_over_period(daily, 7)
+}
+
+/// Least-squares slope of the series, in units per bucket
+///
+/// Returns `None` for fewer than two buckets.
+pub fn slope(series: &PeriodSeries<f64>) -> Option<f64> {
+    let n = series.len();
+    if n < 2 {
+        return None;
+    }
+
+    let mean_x = (n - 1) as f64 / 2.0;
+    let mean_y = series.values().sum::<f64>() / n as f64;
+
+    let (covariance, variance) =
+        series
+            .values()
+            .enumerate()
+            .fold((0.0, 0.0), |(covariance, variance), (i, &y)| {
+                let dx = i as f64 - mean_x;
+                (covariance + dx * (y - mean_y), variance + dx * dx)
+            });
+
+    Some(covariance / variance)
+}
+
+/// Whether the series is trending up or down, ignoring slopes within `threshold` per
+/// bucket of zero
+pub fn trend(series: &PeriodSeries<f64>, threshold: f64) -> Trend {
+    slope(series).map_or(Trend::Flat, |slope| Trend::from_slope(slope, threshold))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn daily(values: &[f64]) -> PeriodSeries<f64> {
+        PeriodSeries::new(
+            values
+                .iter()
+                .enumerate()
+                .map(|(i, &value)| (format!("2025-01-{:02}", i + 1), value))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_rolling_mean() {
+        let means = rolling_mean(&daily(&[3.0, 6.0, 9.0, 0.0]), 3);
+        assert_eq!(
+            means.dates().collect::<Vec<_>>(),
+            vec!["2025-01-01", "2025-01-02", "2025-01-03", "2025-01-04"]
+        );
+        assert_eq!(means.into_values(), vec![3.0, 4.5, 6.0, 5.0]);
+    }
+
+    #[test]
+    fn test_week_over_week() {
+        let mut values = vec![10.0; 7];
+        values.extend([15.0; 7]);
+        let change = week_over_week(&daily(&values)).unwrap();
+        assert_eq!(change.current, 105.0);
+        assert_eq!(change.previous, 70.0);
+        assert_eq!(change.change, 35.0);
+        assert_eq!(change.percent_change, Some(50.0));
+
+        assert_eq!(week_over_week(&daily(&[1.0; 13])), None);
+
+        let change = week_over_week(&daily(&[[0.0; 7], [5.0; 7]].concat())).unwrap();
+        assert_eq!(change.percent_change, None);
+    }
+
+    #[test]
+    fn test_slope_and_trend() {
+        assert_eq!(slope(&daily(&[1.0, 3.0, 5.0, 7.0])), Some(2.0));
+        assert_eq!(slope(&daily(&[4.0])), None);
+
+        assert_eq!(trend(&daily(&[1.0, 3.0, 5.0, 7.0]), 0.5), Trend::Up);
+        assert_eq!(trend(&daily(&[7.0, 5.0, 3.0, 1.0]), 0.5), Trend::Down);
+        assert_eq!(trend(&daily(&[5.0, 5.2, 4.9, 5.1]), 0.5), Trend::Flat);
+    }
+}